@@ -0,0 +1,265 @@
+// Licensed under the Apache-2.0 license
+
+//! Get Attestation Log (0x07) and Clear Attestation Log (0x08) commands
+//!
+//! Retrieves or clears the attestation measurement log of the RoT. Clearing the
+//! attestation log requires authorization.
+
+use crate::codec::{VdmCodec, VdmCodecError};
+use crate::message::debug_log::MAX_LOG_DATA_SIZE;
+use crate::protocol::{VdmCommand, VdmMsgHeader};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+/// Get Attestation Log Request.
+///
+/// Request Payload: Empty (only header)
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct GetAttestationLogRequest {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+}
+
+impl GetAttestationLogRequest {
+    /// Create a new Get Attestation Log request.
+    pub fn new() -> Self {
+        GetAttestationLogRequest {
+            hdr: VdmMsgHeader::new_request(VdmCommand::GetAttestationLog.into()),
+        }
+    }
+}
+
+impl Default for GetAttestationLogRequest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Get Attestation Log Response (fixed header part).
+///
+/// Response Payload:
+/// - Bytes 0:3 - completion_code (u32): Command completion status
+/// - Bytes 4:7 - data_size (u32): Size of the log data in bytes
+/// - Bytes 8:N - data (u8[data_size]): Attestation log contents
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct GetAttestationLogResponseHeader {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Command completion status.
+    pub completion_code: u32,
+    /// Size of the log data in bytes.
+    pub data_size: u32,
+}
+
+impl GetAttestationLogResponseHeader {
+    /// Create a new Get Attestation Log response header.
+    pub fn new(completion_code: u32, data_size: u32) -> Self {
+        GetAttestationLogResponseHeader {
+            hdr: VdmMsgHeader::new_response(VdmCommand::GetAttestationLog.into()),
+            completion_code,
+            data_size,
+        }
+    }
+}
+
+impl Default for GetAttestationLogResponseHeader {
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
+/// Get Attestation Log Response with variable-length data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GetAttestationLogResponse {
+    /// Response header.
+    pub header: GetAttestationLogResponseHeader,
+    /// Log data buffer.
+    pub data: [u8; MAX_LOG_DATA_SIZE],
+}
+
+impl GetAttestationLogResponse {
+    /// Create a new Get Attestation Log response.
+    pub fn new(completion_code: u32, data: &[u8]) -> Self {
+        let data_size = data.len().min(MAX_LOG_DATA_SIZE);
+        let mut response_data = [0u8; MAX_LOG_DATA_SIZE];
+        response_data[..data_size].copy_from_slice(&data[..data_size]);
+
+        GetAttestationLogResponse {
+            header: GetAttestationLogResponseHeader::new(completion_code, data_size as u32),
+            data: response_data,
+        }
+    }
+
+    /// Get the actual data size.
+    pub fn data_size(&self) -> usize {
+        self.header.data_size as usize
+    }
+
+    /// Get a slice of the actual data.
+    pub fn data(&self) -> &[u8] {
+        let size = self.data_size().min(MAX_LOG_DATA_SIZE);
+        &self.data[..size]
+    }
+}
+
+impl Default for GetAttestationLogResponse {
+    fn default() -> Self {
+        GetAttestationLogResponse {
+            header: GetAttestationLogResponseHeader::default(),
+            data: [0u8; MAX_LOG_DATA_SIZE],
+        }
+    }
+}
+
+impl VdmCodec for GetAttestationLogResponse {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, VdmCodecError> {
+        let header_size = core::mem::size_of::<GetAttestationLogResponseHeader>();
+        let data_size = self.data_size();
+        let total_size = header_size + data_size;
+
+        if buffer.len() < total_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        // Encode header
+        self.header.encode(buffer)?;
+
+        // Copy data
+        buffer[header_size..total_size].copy_from_slice(&self.data[..data_size]);
+
+        Ok(total_size)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, VdmCodecError> {
+        let header_size = core::mem::size_of::<GetAttestationLogResponseHeader>();
+
+        if buffer.len() < header_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        let header = GetAttestationLogResponseHeader::decode(buffer)?;
+        let data_size = (header.data_size as usize).min(MAX_LOG_DATA_SIZE);
+
+        if buffer.len() < header_size + data_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        let mut data = [0u8; MAX_LOG_DATA_SIZE];
+        data[..data_size].copy_from_slice(&buffer[header_size..header_size + data_size]);
+
+        Ok(GetAttestationLogResponse { header, data })
+    }
+}
+
+/// Clear Attestation Log Request.
+///
+/// Request Payload: Empty (only header)
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct ClearAttestationLogRequest {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+}
+
+impl ClearAttestationLogRequest {
+    /// Create a new Clear Attestation Log request.
+    pub fn new() -> Self {
+        ClearAttestationLogRequest {
+            hdr: VdmMsgHeader::new_request(VdmCommand::ClearAttestationLog.into()),
+        }
+    }
+}
+
+impl Default for ClearAttestationLogRequest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Clear Attestation Log Response.
+///
+/// Response Payload:
+/// - Bytes 0:3 - completion_code (u32): Command completion status
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct ClearAttestationLogResponse {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Command completion status.
+    pub completion_code: u32,
+}
+
+impl ClearAttestationLogResponse {
+    /// Create a new Clear Attestation Log response.
+    pub fn new(completion_code: u32) -> Self {
+        ClearAttestationLogResponse {
+            hdr: VdmMsgHeader::new_response(VdmCommand::ClearAttestationLog.into()),
+            completion_code,
+        }
+    }
+}
+
+impl Default for ClearAttestationLogResponse {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{VdmCompletionCode, VDM_MSG_HEADER_LEN};
+
+    #[test]
+    fn test_get_attestation_log_request() {
+        let req = GetAttestationLogRequest::new();
+        assert!(req.hdr.is_request());
+        let command_code = req.hdr.command_code;
+        assert_eq!(command_code, VdmCommand::GetAttestationLog as u8);
+
+        let mut buffer = [0u8; 64];
+        let size = req.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN);
+
+        let decoded = GetAttestationLogRequest::decode(&buffer).unwrap();
+        assert_eq!(req, decoded);
+    }
+
+    #[test]
+    fn test_get_attestation_log_response() {
+        let data = [0x54, 0x43, 0x47, 0x00, 0x11, 0x22, 0x33, 0x44];
+        let resp = GetAttestationLogResponse::new(VdmCompletionCode::Success as u32, &data);
+        assert!(resp.header.hdr.is_response());
+
+        let header_size = core::mem::size_of::<GetAttestationLogResponseHeader>();
+        let mut buffer = [0u8; MAX_LOG_DATA_SIZE + 64];
+        let size = resp.encode(&mut buffer).unwrap();
+        assert_eq!(size, header_size + data.len());
+
+        let decoded = GetAttestationLogResponse::decode(&buffer[..size]).unwrap();
+        assert_eq!(decoded.data_size(), data.len());
+        assert_eq!(decoded.data(), &data);
+
+        // Truncated data
+        assert_eq!(
+            GetAttestationLogResponse::decode(&buffer[..size - 1]),
+            Err(VdmCodecError::BufferTooShort)
+        );
+    }
+
+    #[test]
+    fn test_clear_attestation_log() {
+        let req = ClearAttestationLogRequest::new();
+        let mut buffer = [0u8; 64];
+        let size = req.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN);
+        assert_eq!(ClearAttestationLogRequest::decode(&buffer).unwrap(), req);
+
+        let resp = ClearAttestationLogResponse::new(VdmCompletionCode::Success as u32);
+        assert!(resp.hdr.is_response());
+        let size = resp.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN + 4);
+        assert_eq!(ClearAttestationLogResponse::decode(&buffer).unwrap(), resp);
+    }
+}
@@ -0,0 +1,267 @@
+// Licensed under the Apache-2.0 license
+
+//! Get Debug Log (0x05) and Clear Debug Log (0x06) commands
+//!
+//! Retrieves or clears the debug log of the RoT. The debug log contains RoT
+//! application information and machine state.
+
+use crate::codec::{VdmCodec, VdmCodecError};
+use crate::protocol::{VdmCommand, VdmMsgHeader};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+/// Maximum size of log data returned in a single response.
+pub const MAX_LOG_DATA_SIZE: usize = 1024;
+
+/// Get Debug Log Request.
+///
+/// Request Payload: Empty (only header)
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct GetDebugLogRequest {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+}
+
+impl GetDebugLogRequest {
+    /// Create a new Get Debug Log request.
+    pub fn new() -> Self {
+        GetDebugLogRequest {
+            hdr: VdmMsgHeader::new_request(VdmCommand::GetDebugLog.into()),
+        }
+    }
+}
+
+impl Default for GetDebugLogRequest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Get Debug Log Response (fixed header part).
+///
+/// Response Payload:
+/// - Bytes 0:3 - completion_code (u32): Command completion status
+/// - Bytes 4:7 - data_size (u32): Size of the log data in bytes
+/// - Bytes 8:N - data (u8[data_size]): Debug log contents
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct GetDebugLogResponseHeader {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Command completion status.
+    pub completion_code: u32,
+    /// Size of the log data in bytes.
+    pub data_size: u32,
+}
+
+impl GetDebugLogResponseHeader {
+    /// Create a new Get Debug Log response header.
+    pub fn new(completion_code: u32, data_size: u32) -> Self {
+        GetDebugLogResponseHeader {
+            hdr: VdmMsgHeader::new_response(VdmCommand::GetDebugLog.into()),
+            completion_code,
+            data_size,
+        }
+    }
+}
+
+impl Default for GetDebugLogResponseHeader {
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
+/// Get Debug Log Response with variable-length data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GetDebugLogResponse {
+    /// Response header.
+    pub header: GetDebugLogResponseHeader,
+    /// Log data buffer.
+    pub data: [u8; MAX_LOG_DATA_SIZE],
+}
+
+impl GetDebugLogResponse {
+    /// Create a new Get Debug Log response.
+    pub fn new(completion_code: u32, data: &[u8]) -> Self {
+        let data_size = data.len().min(MAX_LOG_DATA_SIZE);
+        let mut response_data = [0u8; MAX_LOG_DATA_SIZE];
+        response_data[..data_size].copy_from_slice(&data[..data_size]);
+
+        GetDebugLogResponse {
+            header: GetDebugLogResponseHeader::new(completion_code, data_size as u32),
+            data: response_data,
+        }
+    }
+
+    /// Get the actual data size.
+    pub fn data_size(&self) -> usize {
+        self.header.data_size as usize
+    }
+
+    /// Get a slice of the actual data.
+    pub fn data(&self) -> &[u8] {
+        let size = self.data_size().min(MAX_LOG_DATA_SIZE);
+        &self.data[..size]
+    }
+}
+
+impl Default for GetDebugLogResponse {
+    fn default() -> Self {
+        GetDebugLogResponse {
+            header: GetDebugLogResponseHeader::default(),
+            data: [0u8; MAX_LOG_DATA_SIZE],
+        }
+    }
+}
+
+impl VdmCodec for GetDebugLogResponse {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, VdmCodecError> {
+        let header_size = core::mem::size_of::<GetDebugLogResponseHeader>();
+        let data_size = self.data_size();
+        let total_size = header_size + data_size;
+
+        if buffer.len() < total_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        // Encode header
+        self.header.encode(buffer)?;
+
+        // Copy data
+        buffer[header_size..total_size].copy_from_slice(&self.data[..data_size]);
+
+        Ok(total_size)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, VdmCodecError> {
+        let header_size = core::mem::size_of::<GetDebugLogResponseHeader>();
+
+        if buffer.len() < header_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        let header = GetDebugLogResponseHeader::decode(buffer)?;
+        let data_size = (header.data_size as usize).min(MAX_LOG_DATA_SIZE);
+
+        if buffer.len() < header_size + data_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        let mut data = [0u8; MAX_LOG_DATA_SIZE];
+        data[..data_size].copy_from_slice(&buffer[header_size..header_size + data_size]);
+
+        Ok(GetDebugLogResponse { header, data })
+    }
+}
+
+/// Clear Debug Log Request.
+///
+/// Request Payload: Empty (only header)
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct ClearDebugLogRequest {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+}
+
+impl ClearDebugLogRequest {
+    /// Create a new Clear Debug Log request.
+    pub fn new() -> Self {
+        ClearDebugLogRequest {
+            hdr: VdmMsgHeader::new_request(VdmCommand::ClearDebugLog.into()),
+        }
+    }
+}
+
+impl Default for ClearDebugLogRequest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Clear Debug Log Response.
+///
+/// Response Payload:
+/// - Bytes 0:3 - completion_code (u32): Command completion status
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct ClearDebugLogResponse {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Command completion status.
+    pub completion_code: u32,
+}
+
+impl ClearDebugLogResponse {
+    /// Create a new Clear Debug Log response.
+    pub fn new(completion_code: u32) -> Self {
+        ClearDebugLogResponse {
+            hdr: VdmMsgHeader::new_response(VdmCommand::ClearDebugLog.into()),
+            completion_code,
+        }
+    }
+}
+
+impl Default for ClearDebugLogResponse {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{VdmCompletionCode, VDM_MSG_HEADER_LEN};
+
+    #[test]
+    fn test_get_debug_log_request() {
+        let req = GetDebugLogRequest::new();
+        assert!(req.hdr.is_request());
+        let command_code = req.hdr.command_code;
+        assert_eq!(command_code, VdmCommand::GetDebugLog as u8);
+
+        let mut buffer = [0u8; 64];
+        let size = req.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN);
+
+        let decoded = GetDebugLogRequest::decode(&buffer).unwrap();
+        assert_eq!(req, decoded);
+    }
+
+    #[test]
+    fn test_get_debug_log_response() {
+        let data = [0x01, 0x00, 0x02, 0x10, 0x20, 0xAA, 0xBB, 0xCC];
+        let resp = GetDebugLogResponse::new(VdmCompletionCode::Success as u32, &data);
+        assert!(resp.header.hdr.is_response());
+
+        let header_size = core::mem::size_of::<GetDebugLogResponseHeader>();
+        let mut buffer = [0u8; MAX_LOG_DATA_SIZE + 64];
+        let size = resp.encode(&mut buffer).unwrap();
+        assert_eq!(size, header_size + data.len());
+
+        let decoded = GetDebugLogResponse::decode(&buffer[..size]).unwrap();
+        assert_eq!(decoded.data_size(), data.len());
+        assert_eq!(decoded.data(), &data);
+
+        // Truncated data
+        assert_eq!(
+            GetDebugLogResponse::decode(&buffer[..size - 1]),
+            Err(VdmCodecError::BufferTooShort)
+        );
+    }
+
+    #[test]
+    fn test_clear_debug_log() {
+        let req = ClearDebugLogRequest::new();
+        let mut buffer = [0u8; 64];
+        let size = req.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN);
+        assert_eq!(ClearDebugLogRequest::decode(&buffer).unwrap(), req);
+
+        let resp = ClearDebugLogResponse::new(VdmCompletionCode::Success as u32);
+        assert!(resp.hdr.is_response());
+        let size = resp.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN + 4);
+        assert_eq!(ClearDebugLogResponse::decode(&buffer).unwrap(), resp);
+    }
+}
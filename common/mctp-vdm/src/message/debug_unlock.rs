@@ -0,0 +1,285 @@
+// Licensed under the Apache-2.0 license
+
+//! Request Debug Unlock (0x0A) and Authorize Debug Unlock Token (0x0B) commands
+//!
+//! Production debug unlock is a challenge/response exchange: the BMC requests a
+//! challenge for an unlock level, then returns it in a token signed by the
+//! debug unlock authority.
+
+use crate::protocol::{VdmCommand, VdmMsgHeader};
+use zerocopy::{FromBytes, FromZeros, Immutable, IntoBytes};
+
+/// Size of the unique device identifier.
+pub const DEBUG_UNLOCK_UDI_SIZE: usize = 32;
+/// Size of the random number challenge.
+pub const DEBUG_UNLOCK_CHALLENGE_SIZE: usize = 48;
+/// Size of the ECC P-384 public key in dwords.
+pub const DEBUG_UNLOCK_ECC_PUB_KEY_DWORDS: usize = 24;
+/// Size of the ML-DSA-87 public key in dwords.
+pub const DEBUG_UNLOCK_MLDSA_PUB_KEY_DWORDS: usize = 648;
+/// Size of the ECC P-384 signature in dwords.
+pub const DEBUG_UNLOCK_ECC_SIG_DWORDS: usize = 24;
+/// Size of the ML-DSA-87 signature in dwords (4627 bytes + 1 reserved byte).
+pub const DEBUG_UNLOCK_MLDSA_SIG_DWORDS: usize = 1157;
+
+/// Request Debug Unlock Request.
+///
+/// Request Payload:
+/// - Bytes 0:3 - length (u32): Length of the message in DWORDs
+/// - Byte 4 - unlock_level (u8): Debug unlock level (1-8)
+/// - Bytes 5:7 - reserved (u8[3]): Reserved
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct RequestDebugUnlockRequest {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Length of the message in DWORDs.
+    pub length: u32,
+    /// Debug unlock level (1-8).
+    pub unlock_level: u8,
+    /// Reserved.
+    pub reserved: [u8; 3],
+}
+
+impl RequestDebugUnlockRequest {
+    /// Create a new Request Debug Unlock request.
+    pub fn new(unlock_level: u8) -> Self {
+        RequestDebugUnlockRequest {
+            hdr: VdmMsgHeader::new_request(VdmCommand::RequestDebugUnlock.into()),
+            length: 2,
+            unlock_level,
+            reserved: [0u8; 3],
+        }
+    }
+}
+
+impl Default for RequestDebugUnlockRequest {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+/// Request Debug Unlock Response.
+///
+/// Response Payload:
+/// - Bytes 0:3 - completion_code (u32): Command completion status
+/// - Bytes 4:7 - length (u32): Length of the message in DWORDs
+/// - Bytes 8:39 - unique_device_identifier (u8[32]): Device identifier of the Caliptra device
+/// - Bytes 40:87 - challenge (u8[48]): Random number challenge
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct RequestDebugUnlockResponse {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Command completion status.
+    pub completion_code: u32,
+    /// Length of the message in DWORDs.
+    pub length: u32,
+    /// Device identifier of the Caliptra device.
+    pub unique_device_identifier: [u8; DEBUG_UNLOCK_UDI_SIZE],
+    /// Random number challenge.
+    pub challenge: [u8; DEBUG_UNLOCK_CHALLENGE_SIZE],
+}
+
+impl RequestDebugUnlockResponse {
+    /// Create a new Request Debug Unlock response.
+    pub fn new(
+        completion_code: u32,
+        unique_device_identifier: &[u8; DEBUG_UNLOCK_UDI_SIZE],
+        challenge: &[u8; DEBUG_UNLOCK_CHALLENGE_SIZE],
+    ) -> Self {
+        RequestDebugUnlockResponse {
+            hdr: VdmMsgHeader::new_response(VdmCommand::RequestDebugUnlock.into()),
+            completion_code,
+            length: ((DEBUG_UNLOCK_UDI_SIZE + DEBUG_UNLOCK_CHALLENGE_SIZE) / 4) as u32,
+            unique_device_identifier: *unique_device_identifier,
+            challenge: *challenge,
+        }
+    }
+}
+
+impl Default for RequestDebugUnlockResponse {
+    fn default() -> Self {
+        Self::new(
+            0,
+            &[0u8; DEBUG_UNLOCK_UDI_SIZE],
+            &[0u8; DEBUG_UNLOCK_CHALLENGE_SIZE],
+        )
+    }
+}
+
+/// Authorize Debug Unlock Token Request.
+///
+/// Request Payload:
+/// - Bytes 0:3 - length (u32): Length of the message in DWORDs
+/// - Bytes 4:35 - unique_device_identifier (u8[32]): Device identifier of the Caliptra device
+/// - Byte 36 - unlock_level (u8): Debug unlock level (1-8)
+/// - Bytes 37:39 - reserved (u8[3]): Reserved
+/// - Bytes 40:87 - challenge (u8[48]): Random number challenge
+/// - Bytes 88:183 - ecc_public_key (u32[24]): ECC public key in hardware format
+/// - Bytes 184:2775 - mldsa_public_key (u32[648]): ML-DSA public key in hardware format
+/// - Bytes 2776:2871 - ecc_signature (u32[24]): ECC P-384 signature (R and S coordinates)
+/// - Bytes 2872:7499 - mldsa_signature (u32[1157]): ML-DSA signature
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct AuthorizeDebugUnlockTokenRequest {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Length of the message in DWORDs.
+    pub length: u32,
+    /// Device identifier of the Caliptra device.
+    pub unique_device_identifier: [u8; DEBUG_UNLOCK_UDI_SIZE],
+    /// Debug unlock level (1-8).
+    pub unlock_level: u8,
+    /// Reserved.
+    pub reserved: [u8; 3],
+    /// Random number challenge.
+    pub challenge: [u8; DEBUG_UNLOCK_CHALLENGE_SIZE],
+    /// ECC public key in hardware format (little endian).
+    pub ecc_public_key: [u32; DEBUG_UNLOCK_ECC_PUB_KEY_DWORDS],
+    /// ML-DSA public key in hardware format (little endian).
+    pub mldsa_public_key: [u32; DEBUG_UNLOCK_MLDSA_PUB_KEY_DWORDS],
+    /// ECC P-384 signature of the message hashed using SHA2-384.
+    pub ecc_signature: [u32; DEBUG_UNLOCK_ECC_SIG_DWORDS],
+    /// ML-DSA signature of the message hashed using SHA2-512.
+    pub mldsa_signature: [u32; DEBUG_UNLOCK_MLDSA_SIG_DWORDS],
+}
+
+impl AuthorizeDebugUnlockTokenRequest {
+    /// Create a new Authorize Debug Unlock Token request with zeroed keys and signatures.
+    pub fn new(
+        unlock_level: u8,
+        unique_device_identifier: &[u8; DEBUG_UNLOCK_UDI_SIZE],
+        challenge: &[u8; DEBUG_UNLOCK_CHALLENGE_SIZE],
+    ) -> Self {
+        let mut req = Self::new_zeroed();
+        req.hdr = VdmMsgHeader::new_request(VdmCommand::AuthorizeDebugUnlockToken.into());
+        req.length =
+            ((core::mem::size_of::<Self>() - core::mem::size_of::<VdmMsgHeader>()) / 4) as u32;
+        req.unique_device_identifier = *unique_device_identifier;
+        req.unlock_level = unlock_level;
+        req.challenge = *challenge;
+        req
+    }
+
+    /// Get the token payload (everything after the VDM message header).
+    pub fn token(&self) -> &[u8] {
+        &self.as_bytes()[core::mem::size_of::<VdmMsgHeader>()..]
+    }
+}
+
+impl Default for AuthorizeDebugUnlockTokenRequest {
+    fn default() -> Self {
+        Self::new(
+            1,
+            &[0u8; DEBUG_UNLOCK_UDI_SIZE],
+            &[0u8; DEBUG_UNLOCK_CHALLENGE_SIZE],
+        )
+    }
+}
+
+/// Authorize Debug Unlock Token Response.
+///
+/// Response Payload:
+/// - Bytes 0:3 - completion_code (u32): Command completion status
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct AuthorizeDebugUnlockTokenResponse {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Command completion status.
+    pub completion_code: u32,
+}
+
+impl AuthorizeDebugUnlockTokenResponse {
+    /// Create a new Authorize Debug Unlock Token response.
+    pub fn new(completion_code: u32) -> Self {
+        AuthorizeDebugUnlockTokenResponse {
+            hdr: VdmMsgHeader::new_response(VdmCommand::AuthorizeDebugUnlockToken.into()),
+            completion_code,
+        }
+    }
+}
+
+impl Default for AuthorizeDebugUnlockTokenResponse {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::VdmCodec;
+    use crate::protocol::{VdmCompletionCode, VDM_MSG_HEADER_LEN};
+
+    #[test]
+    fn test_request_debug_unlock_request() {
+        let req = RequestDebugUnlockRequest::new(3);
+        assert!(req.hdr.is_request());
+        let command_code = req.hdr.command_code;
+        assert_eq!(command_code, VdmCommand::RequestDebugUnlock as u8);
+
+        let mut buffer = [0u8; 64];
+        let size = req.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN + 8);
+        assert_eq!(buffer[VDM_MSG_HEADER_LEN + 4], 3);
+
+        let decoded = RequestDebugUnlockRequest::decode(&buffer).unwrap();
+        assert_eq!(req, decoded);
+    }
+
+    #[test]
+    fn test_request_debug_unlock_response() {
+        let resp = RequestDebugUnlockResponse::new(
+            VdmCompletionCode::Success as u32,
+            &[0x11; DEBUG_UNLOCK_UDI_SIZE],
+            &[0x22; DEBUG_UNLOCK_CHALLENGE_SIZE],
+        );
+        assert!(resp.hdr.is_response());
+        let length = resp.length;
+        assert_eq!(length, 20);
+
+        let mut buffer = [0u8; 128];
+        let size = resp.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN + 88);
+
+        let decoded = RequestDebugUnlockResponse::decode(&buffer).unwrap();
+        assert_eq!(resp, decoded);
+    }
+
+    #[test]
+    fn test_authorize_debug_unlock_token_request() {
+        let mut req = AuthorizeDebugUnlockTokenRequest::new(
+            2,
+            &[0x33; DEBUG_UNLOCK_UDI_SIZE],
+            &[0x44; DEBUG_UNLOCK_CHALLENGE_SIZE],
+        );
+        req.mldsa_signature[DEBUG_UNLOCK_MLDSA_SIG_DWORDS - 1] = 0xDEAD_BEEF;
+        assert!(req.hdr.is_request());
+        assert_eq!(req.token().len(), 7500);
+        let length = req.length;
+        assert_eq!(length, 1875);
+
+        let mut buffer = [0u8; 8192];
+        let size = req.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN + 7500);
+        assert_eq!(buffer[VDM_MSG_HEADER_LEN + 36], 2);
+
+        let decoded = AuthorizeDebugUnlockTokenRequest::decode(&buffer[..size]).unwrap();
+        assert_eq!(req, decoded);
+        assert!(AuthorizeDebugUnlockTokenRequest::decode(&buffer[..size - 1]).is_err());
+    }
+
+    #[test]
+    fn test_authorize_debug_unlock_token_response() {
+        let resp = AuthorizeDebugUnlockTokenResponse::new(VdmCompletionCode::InvalidData as u32);
+        let mut buffer = [0u8; 64];
+        let size = resp.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN + 4);
+        assert_eq!(
+            AuthorizeDebugUnlockTokenResponse::decode(&buffer).unwrap(),
+            resp
+        );
+    }
+}
@@ -0,0 +1,342 @@
+// Licensed under the Apache-2.0 license
+
+//! Device Ownership Transfer command (0x11)
+//!
+//! Carries the Device Ownership Transfer (DOT) runtime commands. The subcommand payload
+//! is opaque to this protocol and is authenticated by the command handler.
+
+use crate::codec::{VdmCodec, VdmCodecError};
+use crate::error::VdmError;
+use crate::protocol::{VdmCommand, VdmMsgHeader};
+use core::convert::TryFrom;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+/// Maximum size of the subcommand request data.
+pub const MAX_DOT_REQ_DATA_SIZE: usize = 7680;
+/// Maximum size of the subcommand response data.
+pub const MAX_DOT_RESP_DATA_SIZE: usize = 256;
+
+/// DOT subcommand values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum DotSubcommand {
+    /// Install a volatile Code Authentication Key.
+    CakInstall = 0x01,
+    /// Lock ownership to the silicon.
+    Lock = 0x02,
+    /// Disable DOT in the locked state.
+    Disable = 0x03,
+    /// Request an unlock challenge.
+    UnlockChallenge = 0x04,
+    /// Unlock ownership from the silicon.
+    Unlock = 0x05,
+    /// Restore a corrupted DOT_BLOB.
+    Recovery = 0x06,
+    /// Force unlock with the vendor key.
+    Override = 0x07,
+}
+
+impl TryFrom<u32> for DotSubcommand {
+    type Error = VdmError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0x01 => Ok(DotSubcommand::CakInstall),
+            0x02 => Ok(DotSubcommand::Lock),
+            0x03 => Ok(DotSubcommand::Disable),
+            0x04 => Ok(DotSubcommand::UnlockChallenge),
+            0x05 => Ok(DotSubcommand::Unlock),
+            0x06 => Ok(DotSubcommand::Recovery),
+            0x07 => Ok(DotSubcommand::Override),
+            _ => Err(VdmError::InvalidData),
+        }
+    }
+}
+
+/// Device Ownership Transfer Request (fixed header part).
+///
+/// Request Payload:
+/// - Bytes 0:3 - subcommand (u32): DOT subcommand
+/// - Bytes 4:7 - data_size (u32): Size of the subcommand data in bytes
+/// - Bytes 8:N - data (u8[data_size]): Subcommand data
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct DeviceOwnershipTransferRequestHeader {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// DOT subcommand.
+    pub subcommand: u32,
+    /// Size of the subcommand data in bytes.
+    pub data_size: u32,
+}
+
+impl DeviceOwnershipTransferRequestHeader {
+    /// Create a new Device Ownership Transfer request header.
+    pub fn new(subcommand: u32, data_size: u32) -> Self {
+        DeviceOwnershipTransferRequestHeader {
+            hdr: VdmMsgHeader::new_request(VdmCommand::DeviceOwnershipTransfer.into()),
+            subcommand,
+            data_size,
+        }
+    }
+}
+
+impl Default for DeviceOwnershipTransferRequestHeader {
+    fn default() -> Self {
+        Self::new(DotSubcommand::UnlockChallenge as u32, 0)
+    }
+}
+
+/// Device Ownership Transfer Request with variable-length data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceOwnershipTransferRequest {
+    /// Request header.
+    pub header: DeviceOwnershipTransferRequestHeader,
+    /// Subcommand data buffer.
+    pub data: [u8; MAX_DOT_REQ_DATA_SIZE],
+}
+
+impl DeviceOwnershipTransferRequest {
+    /// Create a new Device Ownership Transfer request.
+    pub fn new(subcommand: u32, data: &[u8]) -> Self {
+        let data_size = data.len().min(MAX_DOT_REQ_DATA_SIZE);
+        let mut request_data = [0u8; MAX_DOT_REQ_DATA_SIZE];
+        request_data[..data_size].copy_from_slice(&data[..data_size]);
+
+        DeviceOwnershipTransferRequest {
+            header: DeviceOwnershipTransferRequestHeader::new(subcommand, data_size as u32),
+            data: request_data,
+        }
+    }
+
+    /// Get the actual data size.
+    pub fn data_size(&self) -> usize {
+        self.header.data_size as usize
+    }
+
+    /// Get a slice of the actual data.
+    pub fn data(&self) -> &[u8] {
+        let size = self.data_size().min(MAX_DOT_REQ_DATA_SIZE);
+        &self.data[..size]
+    }
+}
+
+impl Default for DeviceOwnershipTransferRequest {
+    fn default() -> Self {
+        DeviceOwnershipTransferRequest {
+            header: DeviceOwnershipTransferRequestHeader::default(),
+            data: [0u8; MAX_DOT_REQ_DATA_SIZE],
+        }
+    }
+}
+
+impl VdmCodec for DeviceOwnershipTransferRequest {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, VdmCodecError> {
+        let header_size = core::mem::size_of::<DeviceOwnershipTransferRequestHeader>();
+        let data_size = self.data_size();
+        let total_size = header_size + data_size;
+
+        if buffer.len() < total_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        // Encode header
+        self.header.encode(buffer)?;
+
+        // Copy data
+        buffer[header_size..total_size].copy_from_slice(&self.data[..data_size]);
+
+        Ok(total_size)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, VdmCodecError> {
+        let header_size = core::mem::size_of::<DeviceOwnershipTransferRequestHeader>();
+
+        if buffer.len() < header_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        let header = DeviceOwnershipTransferRequestHeader::decode(buffer)?;
+        let data_size = header.data_size as usize;
+
+        if data_size > MAX_DOT_REQ_DATA_SIZE {
+            return Err(VdmCodecError::Unsupported);
+        }
+        if buffer.len() < header_size + data_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        let mut data = [0u8; MAX_DOT_REQ_DATA_SIZE];
+        data[..data_size].copy_from_slice(&buffer[header_size..header_size + data_size]);
+
+        Ok(DeviceOwnershipTransferRequest { header, data })
+    }
+}
+
+/// Device Ownership Transfer Response (fixed header part).
+///
+/// Response Payload:
+/// - Bytes 0:3 - completion_code (u32): Command completion status
+/// - Bytes 4:7 - data_size (u32): Size of the subcommand response data in bytes
+/// - Bytes 8:N - data (u8[data_size]): Subcommand response data
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct DeviceOwnershipTransferResponseHeader {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Command completion status.
+    pub completion_code: u32,
+    /// Size of the subcommand response data in bytes.
+    pub data_size: u32,
+}
+
+impl DeviceOwnershipTransferResponseHeader {
+    /// Create a new Device Ownership Transfer response header.
+    pub fn new(completion_code: u32, data_size: u32) -> Self {
+        DeviceOwnershipTransferResponseHeader {
+            hdr: VdmMsgHeader::new_response(VdmCommand::DeviceOwnershipTransfer.into()),
+            completion_code,
+            data_size,
+        }
+    }
+}
+
+impl Default for DeviceOwnershipTransferResponseHeader {
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
+/// Device Ownership Transfer Response with variable-length data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceOwnershipTransferResponse {
+    /// Response header.
+    pub header: DeviceOwnershipTransferResponseHeader,
+    /// Data buffer.
+    pub data: [u8; MAX_DOT_RESP_DATA_SIZE],
+}
+
+impl DeviceOwnershipTransferResponse {
+    /// Create a new Device Ownership Transfer response.
+    pub fn new(completion_code: u32, data: &[u8]) -> Self {
+        let data_size = data.len().min(MAX_DOT_RESP_DATA_SIZE);
+        let mut response_data = [0u8; MAX_DOT_RESP_DATA_SIZE];
+        response_data[..data_size].copy_from_slice(&data[..data_size]);
+
+        DeviceOwnershipTransferResponse {
+            header: DeviceOwnershipTransferResponseHeader::new(completion_code, data_size as u32),
+            data: response_data,
+        }
+    }
+
+    /// Get the actual data size.
+    pub fn data_size(&self) -> usize {
+        self.header.data_size as usize
+    }
+
+    /// Get a slice of the actual data.
+    pub fn data(&self) -> &[u8] {
+        let size = self.data_size().min(MAX_DOT_RESP_DATA_SIZE);
+        &self.data[..size]
+    }
+}
+
+impl Default for DeviceOwnershipTransferResponse {
+    fn default() -> Self {
+        DeviceOwnershipTransferResponse {
+            header: DeviceOwnershipTransferResponseHeader::default(),
+            data: [0u8; MAX_DOT_RESP_DATA_SIZE],
+        }
+    }
+}
+
+impl VdmCodec for DeviceOwnershipTransferResponse {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, VdmCodecError> {
+        let header_size = core::mem::size_of::<DeviceOwnershipTransferResponseHeader>();
+        let data_size = self.data_size();
+        let total_size = header_size + data_size;
+
+        if buffer.len() < total_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        // Encode header
+        self.header.encode(buffer)?;
+
+        // Copy data
+        buffer[header_size..total_size].copy_from_slice(&self.data[..data_size]);
+
+        Ok(total_size)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, VdmCodecError> {
+        let header_size = core::mem::size_of::<DeviceOwnershipTransferResponseHeader>();
+
+        if buffer.len() < header_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        let header = DeviceOwnershipTransferResponseHeader::decode(buffer)?;
+        let data_size = (header.data_size as usize).min(MAX_DOT_RESP_DATA_SIZE);
+
+        if buffer.len() < header_size + data_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        let mut data = [0u8; MAX_DOT_RESP_DATA_SIZE];
+        data[..data_size].copy_from_slice(&buffer[header_size..header_size + data_size]);
+
+        Ok(DeviceOwnershipTransferResponse { header, data })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{VdmCompletionCode, VDM_MSG_HEADER_LEN};
+
+    #[test]
+    fn test_device_ownership_transfer_request() {
+        let data = [0x01, 0x02, 0x03, 0x04, 0x05];
+        let req = DeviceOwnershipTransferRequest::new(DotSubcommand::Lock as u32, &data);
+        assert!(req.header.hdr.is_request());
+        let command_code = req.header.hdr.command_code;
+        let subcommand = req.header.subcommand;
+        assert_eq!(command_code, VdmCommand::DeviceOwnershipTransfer as u8);
+        assert_eq!(subcommand, 0x02);
+
+        let mut buffer = [0u8; 64];
+        let size = req.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN + 8 + data.len());
+
+        let decoded = DeviceOwnershipTransferRequest::decode(&buffer[..size]).unwrap();
+        assert_eq!(decoded.data(), &data);
+        assert_eq!(
+            DeviceOwnershipTransferRequest::decode(&buffer[..size - 1]),
+            Err(VdmCodecError::BufferTooShort)
+        );
+    }
+
+    #[test]
+    fn test_device_ownership_transfer_response() {
+        let data = [0xC0; 48];
+        let resp = DeviceOwnershipTransferResponse::new(VdmCompletionCode::Success as u32, &data);
+        assert!(resp.header.hdr.is_response());
+
+        let header_size = core::mem::size_of::<DeviceOwnershipTransferResponseHeader>();
+        let mut buffer = [0u8; MAX_DOT_RESP_DATA_SIZE + 64];
+        let size = resp.encode(&mut buffer).unwrap();
+        assert_eq!(size, header_size + data.len());
+
+        let decoded = DeviceOwnershipTransferResponse::decode(&buffer[..size]).unwrap();
+        assert_eq!(decoded.data(), &data);
+    }
+
+    #[test]
+    fn test_dot_subcommand_try_from() {
+        assert_eq!(DotSubcommand::try_from(0x01), Ok(DotSubcommand::CakInstall));
+        assert_eq!(DotSubcommand::try_from(0x07), Ok(DotSubcommand::Override));
+        assert!(DotSubcommand::try_from(0x00).is_err());
+        assert!(DotSubcommand::try_from(0x08).is_err());
+    }
+}
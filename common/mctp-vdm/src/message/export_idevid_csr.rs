@@ -0,0 +1,230 @@
+// Licensed under the Apache-2.0 license
+
+//! Export IDevID CSR command (0x0C)
+//!
+//! Exports the IDevID Certificate Signing Request (CSR) so that a Certificate
+//! Authority can endorse it. Only available in the Manufacturing lifecycle state.
+
+use crate::codec::{VdmCodec, VdmCodecError};
+use crate::error::VdmError;
+use crate::protocol::{VdmCommand, VdmMsgHeader};
+use core::convert::TryFrom;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+/// Maximum size of the DER-encoded IDevID CSR (large enough for ML-DSA-87).
+pub const MAX_IDEVID_CSR_SIZE: usize = 7680;
+
+/// CSR index values for Export IDevID CSR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum IdevidCsrIndex {
+    /// IDevID ECC P-384 CSR.
+    Ecc384 = 0x00,
+    /// IDevID ML-DSA CSR.
+    MlDsa = 0x01,
+}
+
+impl TryFrom<u32> for IdevidCsrIndex {
+    type Error = VdmError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0x00 => Ok(IdevidCsrIndex::Ecc384),
+            0x01 => Ok(IdevidCsrIndex::MlDsa),
+            _ => Err(VdmError::InvalidData),
+        }
+    }
+}
+
+/// Export IDevID CSR Request.
+///
+/// Request Payload:
+/// - Bytes 0:3 - index (u32): CSR index
+///   - 0x00 = IDevID ECC P-384 CSR
+///   - 0x01 = IDevID ML-DSA CSR
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct ExportIdevidCsrRequest {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// CSR index.
+    pub index: u32,
+}
+
+impl ExportIdevidCsrRequest {
+    /// Create a new Export IDevID CSR request.
+    pub fn new(index: u32) -> Self {
+        ExportIdevidCsrRequest {
+            hdr: VdmMsgHeader::new_request(VdmCommand::ExportIdevidCsr.into()),
+            index,
+        }
+    }
+}
+
+impl Default for ExportIdevidCsrRequest {
+    fn default() -> Self {
+        Self::new(IdevidCsrIndex::Ecc384 as u32)
+    }
+}
+
+/// Export IDevID CSR Response (fixed header part).
+///
+/// Response Payload:
+/// - Bytes 0:3 - completion_code (u32): Command completion status
+/// - Bytes 4:7 - data_size (u32): Length in bytes of the valid data in the data field
+/// - Bytes 8:N - data (u8[data_size]): DER-encoded IDevID certificate signing request
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct ExportIdevidCsrResponseHeader {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Command completion status.
+    pub completion_code: u32,
+    /// Length in bytes of the valid data in the data field.
+    pub data_size: u32,
+}
+
+impl ExportIdevidCsrResponseHeader {
+    /// Create a new Export IDevID CSR response header.
+    pub fn new(completion_code: u32, data_size: u32) -> Self {
+        ExportIdevidCsrResponseHeader {
+            hdr: VdmMsgHeader::new_response(VdmCommand::ExportIdevidCsr.into()),
+            completion_code,
+            data_size,
+        }
+    }
+}
+
+impl Default for ExportIdevidCsrResponseHeader {
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
+/// Export IDevID CSR Response with variable-length data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportIdevidCsrResponse {
+    /// Response header.
+    pub header: ExportIdevidCsrResponseHeader,
+    /// Data buffer.
+    pub data: [u8; MAX_IDEVID_CSR_SIZE],
+}
+
+impl ExportIdevidCsrResponse {
+    /// Create a new Export IDevID CSR response.
+    pub fn new(completion_code: u32, data: &[u8]) -> Self {
+        let data_size = data.len().min(MAX_IDEVID_CSR_SIZE);
+        let mut response_data = [0u8; MAX_IDEVID_CSR_SIZE];
+        response_data[..data_size].copy_from_slice(&data[..data_size]);
+
+        ExportIdevidCsrResponse {
+            header: ExportIdevidCsrResponseHeader::new(completion_code, data_size as u32),
+            data: response_data,
+        }
+    }
+
+    /// Get the actual data size.
+    pub fn data_size(&self) -> usize {
+        self.header.data_size as usize
+    }
+
+    /// Get a slice of the actual data.
+    pub fn data(&self) -> &[u8] {
+        let size = self.data_size().min(MAX_IDEVID_CSR_SIZE);
+        &self.data[..size]
+    }
+}
+
+impl Default for ExportIdevidCsrResponse {
+    fn default() -> Self {
+        ExportIdevidCsrResponse {
+            header: ExportIdevidCsrResponseHeader::default(),
+            data: [0u8; MAX_IDEVID_CSR_SIZE],
+        }
+    }
+}
+
+impl VdmCodec for ExportIdevidCsrResponse {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, VdmCodecError> {
+        let header_size = core::mem::size_of::<ExportIdevidCsrResponseHeader>();
+        let data_size = self.data_size();
+        let total_size = header_size + data_size;
+
+        if buffer.len() < total_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        // Encode header
+        self.header.encode(buffer)?;
+
+        // Copy data
+        buffer[header_size..total_size].copy_from_slice(&self.data[..data_size]);
+
+        Ok(total_size)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, VdmCodecError> {
+        let header_size = core::mem::size_of::<ExportIdevidCsrResponseHeader>();
+
+        if buffer.len() < header_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        let header = ExportIdevidCsrResponseHeader::decode(buffer)?;
+        let data_size = (header.data_size as usize).min(MAX_IDEVID_CSR_SIZE);
+
+        if buffer.len() < header_size + data_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        let mut data = [0u8; MAX_IDEVID_CSR_SIZE];
+        data[..data_size].copy_from_slice(&buffer[header_size..header_size + data_size]);
+
+        Ok(ExportIdevidCsrResponse { header, data })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{VdmCompletionCode, VDM_MSG_HEADER_LEN};
+
+    #[test]
+    fn test_export_idevid_csr_request() {
+        let req = ExportIdevidCsrRequest::new(IdevidCsrIndex::MlDsa as u32);
+        assert!(req.hdr.is_request());
+        let command_code = req.hdr.command_code;
+        let index = req.index;
+        assert_eq!(command_code, VdmCommand::ExportIdevidCsr as u8);
+        assert_eq!(index, 0x01);
+
+        let mut buffer = [0u8; 64];
+        let size = req.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN + 4);
+
+        let decoded = ExportIdevidCsrRequest::decode(&buffer).unwrap();
+        assert_eq!(req, decoded);
+    }
+
+    #[test]
+    fn test_export_idevid_csr_response() {
+        let data = [0x30, 0x82, 0x01, 0x2C, 0x30, 0x81, 0xB3, 0x02];
+        let resp = ExportIdevidCsrResponse::new(VdmCompletionCode::Success as u32, &data);
+        assert!(resp.header.hdr.is_response());
+
+        let header_size = core::mem::size_of::<ExportIdevidCsrResponseHeader>();
+        let mut buffer = [0u8; MAX_IDEVID_CSR_SIZE + 64];
+        let size = resp.encode(&mut buffer).unwrap();
+        assert_eq!(size, header_size + data.len());
+
+        let decoded = ExportIdevidCsrResponse::decode(&buffer[..size]).unwrap();
+        assert_eq!(decoded.data(), &data);
+    }
+
+    #[test]
+    fn test_idevid_csr_index_try_from() {
+        assert_eq!(IdevidCsrIndex::try_from(0x00), Ok(IdevidCsrIndex::Ecc384));
+        assert_eq!(IdevidCsrIndex::try_from(0x01), Ok(IdevidCsrIndex::MlDsa));
+        assert!(IdevidCsrIndex::try_from(0x02).is_err());
+    }
+}
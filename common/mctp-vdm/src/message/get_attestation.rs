@@ -0,0 +1,246 @@
+// Licensed under the Apache-2.0 license
+
+//! Get Attestation command (0x09)
+//!
+//! Retrieves attestation evidence from the device, either as an OCP EAT claims
+//! token or as a signed PCR quote.
+
+use crate::codec::{VdmCodec, VdmCodecError};
+use crate::error::VdmError;
+use crate::protocol::{VdmCommand, VdmMsgHeader};
+use core::convert::TryFrom;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+/// Maximum size of the attestation evidence.
+pub const MAX_ATTESTATION_SIZE: usize = 2048;
+
+/// Size of the freshness nonce.
+pub const ATTESTATION_NONCE_SIZE: usize = 32;
+
+/// Evidence format values for Get Attestation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum AttestationFormat {
+    /// OCP EAT claims token.
+    OcpEat = 0x0000,
+    /// Signed PCR quote.
+    PcrQuote = 0x0001,
+}
+
+impl TryFrom<u32> for AttestationFormat {
+    type Error = VdmError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0x0000 => Ok(AttestationFormat::OcpEat),
+            0x0001 => Ok(AttestationFormat::PcrQuote),
+            _ => Err(VdmError::InvalidData),
+        }
+    }
+}
+
+/// Get Attestation Request.
+///
+/// Request Payload:
+/// - Bytes 0:3 - format (u32): Evidence format
+///   - 0x0000 = OCP EAT claims token
+///   - 0x0001 = Signed PCR quote
+/// - Bytes 4:35 - nonce (u8[32]): Freshness nonce included in the evidence
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct GetAttestationRequest {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Evidence format.
+    pub format: u32,
+    /// Freshness nonce.
+    pub nonce: [u8; ATTESTATION_NONCE_SIZE],
+}
+
+impl GetAttestationRequest {
+    /// Create a new Get Attestation request.
+    pub fn new(format: u32, nonce: [u8; ATTESTATION_NONCE_SIZE]) -> Self {
+        GetAttestationRequest {
+            hdr: VdmMsgHeader::new_request(VdmCommand::GetAttestation.into()),
+            format,
+            nonce,
+        }
+    }
+}
+
+impl Default for GetAttestationRequest {
+    fn default() -> Self {
+        Self::new(
+            AttestationFormat::OcpEat as u32,
+            [0u8; ATTESTATION_NONCE_SIZE],
+        )
+    }
+}
+
+/// Get Attestation Response (fixed header part).
+///
+/// Response Payload:
+/// - Bytes 0:3 - completion_code (u32): Command completion status
+/// - Bytes 4:7 - data_size (u32): Size of the attestation evidence in bytes
+/// - Bytes 8:N - data (u8[data_size]): Attestation evidence
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct GetAttestationResponseHeader {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Command completion status.
+    pub completion_code: u32,
+    /// Size of the attestation evidence in bytes.
+    pub data_size: u32,
+}
+
+impl GetAttestationResponseHeader {
+    /// Create a new Get Attestation response header.
+    pub fn new(completion_code: u32, data_size: u32) -> Self {
+        GetAttestationResponseHeader {
+            hdr: VdmMsgHeader::new_response(VdmCommand::GetAttestation.into()),
+            completion_code,
+            data_size,
+        }
+    }
+}
+
+impl Default for GetAttestationResponseHeader {
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
+/// Get Attestation Response with variable-length data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GetAttestationResponse {
+    /// Response header.
+    pub header: GetAttestationResponseHeader,
+    /// Data buffer.
+    pub data: [u8; MAX_ATTESTATION_SIZE],
+}
+
+impl GetAttestationResponse {
+    /// Create a new Get Attestation response.
+    pub fn new(completion_code: u32, data: &[u8]) -> Self {
+        let data_size = data.len().min(MAX_ATTESTATION_SIZE);
+        let mut response_data = [0u8; MAX_ATTESTATION_SIZE];
+        response_data[..data_size].copy_from_slice(&data[..data_size]);
+
+        GetAttestationResponse {
+            header: GetAttestationResponseHeader::new(completion_code, data_size as u32),
+            data: response_data,
+        }
+    }
+
+    /// Get the actual data size.
+    pub fn data_size(&self) -> usize {
+        self.header.data_size as usize
+    }
+
+    /// Get a slice of the actual data.
+    pub fn data(&self) -> &[u8] {
+        let size = self.data_size().min(MAX_ATTESTATION_SIZE);
+        &self.data[..size]
+    }
+}
+
+impl Default for GetAttestationResponse {
+    fn default() -> Self {
+        GetAttestationResponse {
+            header: GetAttestationResponseHeader::default(),
+            data: [0u8; MAX_ATTESTATION_SIZE],
+        }
+    }
+}
+
+impl VdmCodec for GetAttestationResponse {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, VdmCodecError> {
+        let header_size = core::mem::size_of::<GetAttestationResponseHeader>();
+        let data_size = self.data_size();
+        let total_size = header_size + data_size;
+
+        if buffer.len() < total_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        // Encode header
+        self.header.encode(buffer)?;
+
+        // Copy data
+        buffer[header_size..total_size].copy_from_slice(&self.data[..data_size]);
+
+        Ok(total_size)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, VdmCodecError> {
+        let header_size = core::mem::size_of::<GetAttestationResponseHeader>();
+
+        if buffer.len() < header_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        let header = GetAttestationResponseHeader::decode(buffer)?;
+        let data_size = (header.data_size as usize).min(MAX_ATTESTATION_SIZE);
+
+        if buffer.len() < header_size + data_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        let mut data = [0u8; MAX_ATTESTATION_SIZE];
+        data[..data_size].copy_from_slice(&buffer[header_size..header_size + data_size]);
+
+        Ok(GetAttestationResponse { header, data })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{VdmCompletionCode, VDM_MSG_HEADER_LEN};
+
+    #[test]
+    fn test_get_attestation_request() {
+        let req = GetAttestationRequest::new(AttestationFormat::PcrQuote as u32, [0x5A; 32]);
+        assert!(req.hdr.is_request());
+        let command_code = req.hdr.command_code;
+        let format = req.format;
+        assert_eq!(command_code, VdmCommand::GetAttestation as u8);
+        assert_eq!(format, 0x0001);
+
+        let mut buffer = [0u8; 64];
+        let size = req.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN + 4 + ATTESTATION_NONCE_SIZE);
+
+        let decoded = GetAttestationRequest::decode(&buffer).unwrap();
+        assert_eq!(req, decoded);
+    }
+
+    #[test]
+    fn test_get_attestation_response() {
+        let data = [0xD2, 0x84, 0x43, 0xA1, 0x01, 0x38, 0x22, 0xA0];
+        let resp = GetAttestationResponse::new(VdmCompletionCode::Success as u32, &data);
+        assert!(resp.header.hdr.is_response());
+
+        let header_size = core::mem::size_of::<GetAttestationResponseHeader>();
+        let mut buffer = [0u8; MAX_ATTESTATION_SIZE + 64];
+        let size = resp.encode(&mut buffer).unwrap();
+        assert_eq!(size, header_size + data.len());
+
+        let decoded = GetAttestationResponse::decode(&buffer[..size]).unwrap();
+        assert_eq!(decoded.data(), &data);
+    }
+
+    #[test]
+    fn test_attestation_format_try_from() {
+        assert_eq!(
+            AttestationFormat::try_from(0x0000),
+            Ok(AttestationFormat::OcpEat)
+        );
+        assert_eq!(
+            AttestationFormat::try_from(0x0001),
+            Ok(AttestationFormat::PcrQuote)
+        );
+        assert!(AttestationFormat::try_from(0x0002).is_err());
+    }
+}
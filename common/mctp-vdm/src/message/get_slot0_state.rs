@@ -0,0 +1,146 @@
+// Licensed under the Apache-2.0 license
+
+//! Get Slot 0 State command (0x0E)
+//!
+//! Determines the provisioning state of certificate slot 0 (Vendor PKI).
+
+use crate::error::VdmError;
+use crate::protocol::{VdmCommand, VdmMsgHeader};
+use core::convert::TryFrom;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+/// Slot state values, per the SPDM slot provisioning model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum Slot0State {
+    /// Slot is not supported.
+    NotExist = 0,
+    /// Slot is supported but not provisioned.
+    Empty = 1,
+    /// Slot has a key but no certificate.
+    KeyOnly = 2,
+    /// Slot has both a key and a certificate.
+    KeyAndCert = 3,
+}
+
+impl TryFrom<u32> for Slot0State {
+    type Error = VdmError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Slot0State::NotExist),
+            1 => Ok(Slot0State::Empty),
+            2 => Ok(Slot0State::KeyOnly),
+            3 => Ok(Slot0State::KeyAndCert),
+            _ => Err(VdmError::InvalidData),
+        }
+    }
+}
+
+/// Get Slot 0 State Request.
+///
+/// Request Payload: Empty (only header)
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct GetSlot0StateRequest {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+}
+
+impl GetSlot0StateRequest {
+    /// Create a new Get Slot 0 State request.
+    pub fn new() -> Self {
+        GetSlot0StateRequest {
+            hdr: VdmMsgHeader::new_request(VdmCommand::GetSlot0State.into()),
+        }
+    }
+}
+
+impl Default for GetSlot0StateRequest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Get Slot 0 State Response.
+///
+/// Response Payload:
+/// - Bytes 0:3 - completion_code (u32): Command completion status
+/// - Bytes 4:7 - state (u32): Slot state
+///   - 0 = Does not exist
+///   - 1 = Exists and empty
+///   - 2 = Exists with key
+///   - 3 = Exists with key and cert
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct GetSlot0StateResponse {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Command completion status.
+    pub completion_code: u32,
+    /// Slot state.
+    pub state: u32,
+}
+
+impl GetSlot0StateResponse {
+    /// Create a new Get Slot 0 State response.
+    pub fn new(completion_code: u32, state: u32) -> Self {
+        GetSlot0StateResponse {
+            hdr: VdmMsgHeader::new_response(VdmCommand::GetSlot0State.into()),
+            completion_code,
+            state,
+        }
+    }
+}
+
+impl Default for GetSlot0StateResponse {
+    fn default() -> Self {
+        Self::new(0, Slot0State::NotExist as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::VdmCodec;
+    use crate::protocol::{VdmCompletionCode, VDM_MSG_HEADER_LEN};
+
+    #[test]
+    fn test_get_slot0_state_request() {
+        let req = GetSlot0StateRequest::new();
+        assert!(req.hdr.is_request());
+        let command_code = req.hdr.command_code;
+        assert_eq!(command_code, VdmCommand::GetSlot0State as u8);
+
+        let mut buffer = [0u8; 64];
+        let size = req.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN);
+        assert_eq!(GetSlot0StateRequest::decode(&buffer).unwrap(), req);
+    }
+
+    #[test]
+    fn test_get_slot0_state_response() {
+        let resp = GetSlot0StateResponse::new(
+            VdmCompletionCode::Success as u32,
+            Slot0State::KeyAndCert as u32,
+        );
+        assert!(resp.hdr.is_response());
+
+        let mut buffer = [0u8; 64];
+        let size = resp.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN + 8);
+
+        let decoded = GetSlot0StateResponse::decode(&buffer).unwrap();
+        assert_eq!(resp, decoded);
+        let state = decoded.state;
+        assert_eq!(Slot0State::try_from(state), Ok(Slot0State::KeyAndCert));
+    }
+
+    #[test]
+    fn test_slot0_state_try_from() {
+        assert_eq!(Slot0State::try_from(0), Ok(Slot0State::NotExist));
+        assert_eq!(Slot0State::try_from(1), Ok(Slot0State::Empty));
+        assert_eq!(Slot0State::try_from(2), Ok(Slot0State::KeyOnly));
+        assert!(Slot0State::try_from(4).is_err());
+    }
+}
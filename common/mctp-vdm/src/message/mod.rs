@@ -1,13 +1,31 @@
 // Licensed under the Apache-2.0 license
 
+pub mod attestation_log;
+pub mod debug_log;
+pub mod debug_unlock;
 pub mod device_capabilities;
 pub mod device_id;
 pub mod device_info;
+pub mod device_ownership_transfer;
 pub mod export_attested_csr;
+pub mod export_idevid_csr;
 pub mod firmware_version;
+pub mod get_attestation;
+pub mod get_slot0_state;
+pub mod program_field_entropy;
+pub mod set_slot0_cert;
 
+pub use attestation_log::*;
+pub use debug_log::*;
+pub use debug_unlock::*;
 pub use device_capabilities::*;
 pub use device_id::*;
 pub use device_info::*;
+pub use device_ownership_transfer::*;
 pub use export_attested_csr::*;
+pub use export_idevid_csr::*;
 pub use firmware_version::*;
+pub use get_attestation::*;
+pub use get_slot0_state::*;
+pub use program_field_entropy::*;
+pub use set_slot0_cert::*;
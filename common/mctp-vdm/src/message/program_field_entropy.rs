@@ -0,0 +1,102 @@
+// Licensed under the Apache-2.0 license
+
+//! Program Field Entropy command (0x10)
+//!
+//! Programs field entropy into the device fuses. Requires authorization, which is
+//! enforced by the command handler.
+
+use crate::protocol::{VdmCommand, VdmMsgHeader};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+/// Size of the field entropy value.
+pub const FIELD_ENTROPY_SIZE: usize = 32;
+
+/// Program Field Entropy Request.
+///
+/// Request Payload:
+/// - Bytes 0:31 - entropy (u8[32]): Field entropy to program
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct ProgramFieldEntropyRequest {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Field entropy to program.
+    pub entropy: [u8; FIELD_ENTROPY_SIZE],
+}
+
+impl ProgramFieldEntropyRequest {
+    /// Create a new Program Field Entropy request.
+    pub fn new(entropy: &[u8; FIELD_ENTROPY_SIZE]) -> Self {
+        ProgramFieldEntropyRequest {
+            hdr: VdmMsgHeader::new_request(VdmCommand::ProgramFieldEntropy.into()),
+            entropy: *entropy,
+        }
+    }
+}
+
+impl Default for ProgramFieldEntropyRequest {
+    fn default() -> Self {
+        Self::new(&[0u8; FIELD_ENTROPY_SIZE])
+    }
+}
+
+/// Program Field Entropy Response.
+///
+/// Response Payload:
+/// - Bytes 0:3 - completion_code (u32): Command completion status
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct ProgramFieldEntropyResponse {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Command completion status.
+    pub completion_code: u32,
+}
+
+impl ProgramFieldEntropyResponse {
+    /// Create a new Program Field Entropy response.
+    pub fn new(completion_code: u32) -> Self {
+        ProgramFieldEntropyResponse {
+            hdr: VdmMsgHeader::new_response(VdmCommand::ProgramFieldEntropy.into()),
+            completion_code,
+        }
+    }
+}
+
+impl Default for ProgramFieldEntropyResponse {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::VdmCodec;
+    use crate::protocol::{VdmCompletionCode, VDM_MSG_HEADER_LEN};
+
+    #[test]
+    fn test_program_field_entropy_request() {
+        let req = ProgramFieldEntropyRequest::new(&[0xA5; FIELD_ENTROPY_SIZE]);
+        assert!(req.hdr.is_request());
+        let command_code = req.hdr.command_code;
+        assert_eq!(command_code, VdmCommand::ProgramFieldEntropy as u8);
+
+        let mut buffer = [0u8; 64];
+        let size = req.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN + FIELD_ENTROPY_SIZE);
+
+        let decoded = ProgramFieldEntropyRequest::decode(&buffer).unwrap();
+        assert_eq!(req, decoded);
+    }
+
+    #[test]
+    fn test_program_field_entropy_response() {
+        let resp = ProgramFieldEntropyResponse::new(VdmCompletionCode::Success as u32);
+        assert!(resp.hdr.is_response());
+        let mut buffer = [0u8; 64];
+        let size = resp.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN + 4);
+        assert_eq!(ProgramFieldEntropyResponse::decode(&buffer).unwrap(), resp);
+    }
+}
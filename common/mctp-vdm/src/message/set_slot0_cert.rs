@@ -0,0 +1,207 @@
+// Licensed under the Apache-2.0 license
+
+//! Set Slot 0 Cert command (0x0D)
+//!
+//! Sets the CA-signed IDevID certificate in certificate slot 0 (Vendor PKI). This is a
+//! one-time operation performed during manufacturing.
+
+use crate::codec::{VdmCodec, VdmCodecError};
+use crate::protocol::{VdmCommand, VdmMsgHeader};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+/// Maximum size of the DER-encoded IDevID certificate (large enough for ML-DSA-87).
+pub const MAX_SLOT0_CERT_SIZE: usize = 7680;
+
+/// Set Slot 0 Cert Request (fixed header part).
+///
+/// Request Payload:
+/// - Bytes 0:3 - cert_size (u32): Size of the DER-encoded IDevID certificate
+/// - Bytes 4:N - cert (u8[cert_size]): DER-encoded CA-signed IDevID certificate
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct SetSlot0CertRequestHeader {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Size of the certificate in bytes.
+    pub cert_size: u32,
+}
+
+impl SetSlot0CertRequestHeader {
+    /// Create a new Set Slot 0 Cert request header.
+    pub fn new(cert_size: u32) -> Self {
+        SetSlot0CertRequestHeader {
+            hdr: VdmMsgHeader::new_request(VdmCommand::SetSlot0Cert.into()),
+            cert_size,
+        }
+    }
+}
+
+impl Default for SetSlot0CertRequestHeader {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+/// Set Slot 0 Cert Request with variable-length certificate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetSlot0CertRequest {
+    /// Request header.
+    pub header: SetSlot0CertRequestHeader,
+    /// Certificate buffer.
+    pub cert: [u8; MAX_SLOT0_CERT_SIZE],
+}
+
+impl SetSlot0CertRequest {
+    /// Create a new Set Slot 0 Cert request.
+    pub fn new(cert: &[u8]) -> Self {
+        let cert_size = cert.len().min(MAX_SLOT0_CERT_SIZE);
+        let mut request_cert = [0u8; MAX_SLOT0_CERT_SIZE];
+        request_cert[..cert_size].copy_from_slice(&cert[..cert_size]);
+
+        SetSlot0CertRequest {
+            header: SetSlot0CertRequestHeader::new(cert_size as u32),
+            cert: request_cert,
+        }
+    }
+
+    /// Get the actual certificate size.
+    pub fn cert_size(&self) -> usize {
+        self.header.cert_size as usize
+    }
+
+    /// Get a slice of the actual certificate.
+    pub fn cert(&self) -> &[u8] {
+        let size = self.cert_size().min(MAX_SLOT0_CERT_SIZE);
+        &self.cert[..size]
+    }
+}
+
+impl Default for SetSlot0CertRequest {
+    fn default() -> Self {
+        SetSlot0CertRequest {
+            header: SetSlot0CertRequestHeader::default(),
+            cert: [0u8; MAX_SLOT0_CERT_SIZE],
+        }
+    }
+}
+
+impl VdmCodec for SetSlot0CertRequest {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, VdmCodecError> {
+        let header_size = core::mem::size_of::<SetSlot0CertRequestHeader>();
+        let cert_size = self.cert_size();
+        let total_size = header_size + cert_size;
+
+        if buffer.len() < total_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        // Encode header
+        self.header.encode(buffer)?;
+
+        // Copy certificate
+        buffer[header_size..total_size].copy_from_slice(&self.cert[..cert_size]);
+
+        Ok(total_size)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, VdmCodecError> {
+        let header_size = core::mem::size_of::<SetSlot0CertRequestHeader>();
+
+        if buffer.len() < header_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        let header = SetSlot0CertRequestHeader::decode(buffer)?;
+        let cert_size = header.cert_size as usize;
+
+        if cert_size > MAX_SLOT0_CERT_SIZE {
+            return Err(VdmCodecError::Unsupported);
+        }
+        if buffer.len() < header_size + cert_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        let mut cert = [0u8; MAX_SLOT0_CERT_SIZE];
+        cert[..cert_size].copy_from_slice(&buffer[header_size..header_size + cert_size]);
+
+        Ok(SetSlot0CertRequest { header, cert })
+    }
+}
+
+/// Set Slot 0 Cert Response.
+///
+/// Response Payload:
+/// - Bytes 0:3 - completion_code (u32): Command completion status
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct SetSlot0CertResponse {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Command completion status.
+    pub completion_code: u32,
+}
+
+impl SetSlot0CertResponse {
+    /// Create a new Set Slot 0 Cert response.
+    pub fn new(completion_code: u32) -> Self {
+        SetSlot0CertResponse {
+            hdr: VdmMsgHeader::new_response(VdmCommand::SetSlot0Cert.into()),
+            completion_code,
+        }
+    }
+}
+
+impl Default for SetSlot0CertResponse {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{VdmCompletionCode, VDM_MSG_HEADER_LEN};
+
+    #[test]
+    fn test_set_slot0_cert_request() {
+        let cert = [0x30, 0x82, 0x02, 0x10, 0xA0, 0x03, 0x02, 0x01, 0x02];
+        let req = SetSlot0CertRequest::new(&cert);
+        assert!(req.header.hdr.is_request());
+        let command_code = req.header.hdr.command_code;
+        assert_eq!(command_code, VdmCommand::SetSlot0Cert as u8);
+
+        let mut buffer = [0u8; 64];
+        let size = req.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN + 4 + cert.len());
+
+        let decoded = SetSlot0CertRequest::decode(&buffer[..size]).unwrap();
+        assert_eq!(decoded.cert(), &cert);
+
+        // Truncated certificate
+        assert_eq!(
+            SetSlot0CertRequest::decode(&buffer[..size - 1]),
+            Err(VdmCodecError::BufferTooShort)
+        );
+    }
+
+    #[test]
+    fn test_set_slot0_cert_request_too_large() {
+        let header = SetSlot0CertRequestHeader::new(MAX_SLOT0_CERT_SIZE as u32 + 1);
+        let mut buffer = [0u8; 64];
+        header.encode(&mut buffer).unwrap();
+        assert_eq!(
+            SetSlot0CertRequest::decode(&buffer),
+            Err(VdmCodecError::Unsupported)
+        );
+    }
+
+    #[test]
+    fn test_set_slot0_cert_response() {
+        let resp = SetSlot0CertResponse::new(VdmCompletionCode::Success as u32);
+        assert!(resp.hdr.is_response());
+        let mut buffer = [0u8; 64];
+        let size = resp.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN + 4);
+        assert_eq!(SetSlot0CertResponse::decode(&buffer).unwrap(), resp);
+    }
+}
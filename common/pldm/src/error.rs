@@ -27,6 +27,11 @@ pub enum PldmError {
     InvalidApplyResult,
     InvalidGetStatusReasonCode,
     InvalidAuxStateStatus,
+
+    InvalidPdrType,
+    InvalidSensorDataSize,
+    InvalidSensorState,
+    InvalidEventClass,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -0,0 +1,323 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{PldmCodec, PldmCodecError};
+use crate::error::PldmError;
+use crate::protocol::base::{
+    InstanceId, PldmMsgHeader, PldmMsgType, PldmSupportedType, TransferOperationFlag,
+    TransferRespFlag, PLDM_MSG_HEADER_LEN,
+};
+use crate::protocol::firmware_update::{
+    DownstreamDeviceParameterEntry, FirmwareDeviceCapability, FwUpdateCmd,
+    MAX_DOWNSTREAM_DEVICE_COUNT, PLDM_FWUP_MAX_DATA_PORTION_SIZE,
+};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetDownstreamFirmwareParametersRequest {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub data_transfer_handle: u32,
+    pub transfer_op_flag: u8,
+}
+
+impl GetDownstreamFirmwareParametersRequest {
+    pub fn new(
+        instance_id: InstanceId,
+        msg_type: PldmMsgType,
+        data_transfer_handle: u32,
+        transfer_op_flag: TransferOperationFlag,
+    ) -> Self {
+        GetDownstreamFirmwareParametersRequest {
+            hdr: PldmMsgHeader::new(
+                instance_id,
+                msg_type,
+                PldmSupportedType::FwUpdate,
+                FwUpdateCmd::GetDownstreamFirmwareParameters as u8,
+            ),
+            data_transfer_handle,
+            transfer_op_flag: transfer_op_flag as u8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetDownstreamFirmwareParametersResponseFixed {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub completion_code: u8,
+    pub next_data_transfer_handle: u32,
+    pub transfer_flag: u8,
+}
+
+/// GetDownstreamFirmwareParameters response. The portion runs to the end of the message, so
+/// `decode` must be given a buffer trimmed to the received message length. The portions of a
+/// multipart transfer concatenate to an encoded `DownstreamFirmwareParameters`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GetDownstreamFirmwareParametersResponse {
+    pub fixed: GetDownstreamFirmwareParametersResponseFixed,
+    pub portion_len: usize,
+    pub portion: [u8; PLDM_FWUP_MAX_DATA_PORTION_SIZE],
+}
+
+impl GetDownstreamFirmwareParametersResponse {
+    pub fn new(
+        instance_id: InstanceId,
+        completion_code: u8,
+        next_data_transfer_handle: u32,
+        transfer_flag: TransferRespFlag,
+        portion: &[u8],
+    ) -> Result<Self, PldmError> {
+        if portion.len() > PLDM_FWUP_MAX_DATA_PORTION_SIZE {
+            return Err(PldmError::InvalidLength);
+        }
+
+        let mut data = [0u8; PLDM_FWUP_MAX_DATA_PORTION_SIZE];
+        data[..portion.len()].copy_from_slice(portion);
+        Ok(GetDownstreamFirmwareParametersResponse {
+            fixed: GetDownstreamFirmwareParametersResponseFixed {
+                hdr: PldmMsgHeader::new(
+                    instance_id,
+                    PldmMsgType::Response,
+                    PldmSupportedType::FwUpdate,
+                    FwUpdateCmd::GetDownstreamFirmwareParameters as u8,
+                ),
+                completion_code,
+                next_data_transfer_handle,
+                transfer_flag: transfer_flag as u8,
+            },
+            portion_len: portion.len(),
+            portion: data,
+        })
+    }
+
+    pub fn portion(&self) -> &[u8] {
+        &self.portion[..self.portion_len]
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        core::mem::size_of::<GetDownstreamFirmwareParametersResponseFixed>() + self.portion_len
+    }
+}
+
+impl PldmCodec for GetDownstreamFirmwareParametersResponse {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, PldmCodecError> {
+        if self.portion_len > PLDM_FWUP_MAX_DATA_PORTION_SIZE {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+        if buffer.len() < self.codec_size_in_bytes() {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+
+        let mut offset = 0;
+        let bytes = core::mem::size_of::<GetDownstreamFirmwareParametersResponseFixed>();
+        self.fixed
+            .write_to(&mut buffer[offset..offset + bytes])
+            .unwrap();
+        offset += bytes;
+
+        buffer[offset..offset + self.portion_len].copy_from_slice(self.portion());
+        Ok(offset + self.portion_len)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, PldmCodecError> {
+        let bytes = core::mem::size_of::<GetDownstreamFirmwareParametersResponseFixed>();
+        let fixed = GetDownstreamFirmwareParametersResponseFixed::read_from_bytes(
+            buffer.get(..bytes).ok_or(PldmCodecError::BufferTooShort)?,
+        )
+        .unwrap();
+
+        let data = &buffer[bytes..];
+        if data.len() > PLDM_FWUP_MAX_DATA_PORTION_SIZE {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+        let mut portion = [0u8; PLDM_FWUP_MAX_DATA_PORTION_SIZE];
+        portion[..data.len()].copy_from_slice(data);
+
+        Ok(GetDownstreamFirmwareParametersResponse {
+            fixed,
+            portion_len: data.len(),
+            portion,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Immutable, PartialEq, Default)]
+#[repr(C, packed)]
+pub struct DownstreamFirmwareParamsFixed {
+    pub fdp_capabilities_during_update: FirmwareDeviceCapability,
+    pub downstream_device_count: u16,
+}
+
+// The downstream device parameter table carried by GetDownstreamFirmwareParameters
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DownstreamFirmwareParameters {
+    pub fixed: DownstreamFirmwareParamsFixed,
+    pub param_table: [DownstreamDeviceParameterEntry; MAX_DOWNSTREAM_DEVICE_COUNT],
+}
+
+impl DownstreamFirmwareParameters {
+    pub fn new(
+        fdp_capabilities_during_update: FirmwareDeviceCapability,
+        param_table: &[DownstreamDeviceParameterEntry],
+    ) -> Result<Self, PldmError> {
+        if param_table.len() > MAX_DOWNSTREAM_DEVICE_COUNT {
+            return Err(PldmError::InvalidLength);
+        }
+
+        Ok(DownstreamFirmwareParameters {
+            fixed: DownstreamFirmwareParamsFixed {
+                fdp_capabilities_during_update,
+                downstream_device_count: param_table.len() as u16,
+            },
+            param_table: core::array::from_fn(|i| param_table.get(i).cloned().unwrap_or_default()),
+        })
+    }
+
+    pub fn entries(&self) -> &[DownstreamDeviceParameterEntry] {
+        &self.param_table[..self.fixed.downstream_device_count as usize]
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        core::mem::size_of::<DownstreamFirmwareParamsFixed>()
+            + self
+                .entries()
+                .iter()
+                .map(|entry| entry.codec_size_in_bytes())
+                .sum::<usize>()
+    }
+}
+
+impl PldmCodec for DownstreamFirmwareParameters {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, PldmCodecError> {
+        if buffer.len() < self.codec_size_in_bytes() {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+        let mut offset = 0;
+
+        let bytes = core::mem::size_of::<DownstreamFirmwareParamsFixed>();
+        self.fixed
+            .write_to(&mut buffer[offset..offset + bytes])
+            .unwrap();
+        offset += bytes;
+
+        for entry in self.entries() {
+            offset += entry.encode(&mut buffer[offset..])?;
+        }
+        Ok(offset)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, PldmCodecError> {
+        let mut offset = 0;
+
+        let bytes = core::mem::size_of::<DownstreamFirmwareParamsFixed>();
+        let fixed = DownstreamFirmwareParamsFixed::read_from_bytes(
+            buffer
+                .get(offset..offset + bytes)
+                .ok_or(PldmCodecError::BufferTooShort)?,
+        )
+        .unwrap();
+        offset += bytes;
+        if fixed.downstream_device_count as usize > MAX_DOWNSTREAM_DEVICE_COUNT {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+
+        let mut param_table: [DownstreamDeviceParameterEntry; MAX_DOWNSTREAM_DEVICE_COUNT] =
+            Default::default();
+        for entry in param_table
+            .iter_mut()
+            .take(fixed.downstream_device_count as usize)
+        {
+            *entry =
+                DownstreamDeviceParameterEntry::decode(buffer.get(offset..).unwrap_or_default())?;
+            offset += entry.codec_size_in_bytes();
+        }
+
+        Ok(DownstreamFirmwareParameters { fixed, param_table })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::protocol::firmware_update::{
+        ComponentActivationMethods, PldmFirmwareString, PldmFirmwareVersion,
+    };
+
+    fn construct_downstream_firmware_params() -> DownstreamFirmwareParameters {
+        let active_firmware_string = PldmFirmwareString::new("ASCII", "soc-fw-1.0").unwrap();
+        let active_firmware_version =
+            PldmFirmwareVersion::new(0x00010000, &active_firmware_string, Some("20250210"));
+        let pending_firmware_string = PldmFirmwareString::new("ASCII", "soc-fw-1.1").unwrap();
+        let pending_firmware_version =
+            PldmFirmwareVersion::new(0x00010001, &pending_firmware_string, Some("20250213"));
+        let entries = [
+            DownstreamDeviceParameterEntry::new(
+                0,
+                &active_firmware_version,
+                &pending_firmware_version,
+                ComponentActivationMethods(0x0001),
+                FirmwareDeviceCapability(0x0010),
+            ),
+            DownstreamDeviceParameterEntry::new(
+                1,
+                &active_firmware_version,
+                &PldmFirmwareVersion::default(),
+                ComponentActivationMethods(0x0002),
+                FirmwareDeviceCapability(0x0010),
+            ),
+        ];
+        DownstreamFirmwareParameters::new(FirmwareDeviceCapability(0x0010), &entries).unwrap()
+    }
+
+    #[test]
+    fn test_get_downstream_firmware_parameters_request() {
+        let request = GetDownstreamFirmwareParametersRequest::new(
+            0x01,
+            PldmMsgType::Request,
+            0,
+            TransferOperationFlag::GetFirstPart,
+        );
+        let mut buffer = [0u8; 16];
+        let bytes = request.encode(&mut buffer).unwrap();
+        let decoded_request =
+            GetDownstreamFirmwareParametersRequest::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(request, decoded_request);
+    }
+
+    #[test]
+    fn test_downstream_firmware_parameters() {
+        let params = construct_downstream_firmware_params();
+        let mut buffer = [0u8; 512];
+        let bytes = params.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, params.codec_size_in_bytes());
+        let decoded_params = DownstreamFirmwareParameters::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(params, decoded_params);
+        assert_eq!(decoded_params.entries().len(), 2);
+        assert!(decoded_params.entries()[1].pending_comp_ver_str.is_none());
+    }
+
+    #[test]
+    fn test_get_downstream_firmware_parameters_response() {
+        let params = construct_downstream_firmware_params();
+        let mut data = [0u8; 512];
+        let data_len = params.encode(&mut data).unwrap();
+
+        let response = GetDownstreamFirmwareParametersResponse::new(
+            0x01,
+            0,
+            0,
+            TransferRespFlag::StartAndEnd,
+            &data[..data_len],
+        )
+        .unwrap();
+        let mut buffer = [0u8; 512];
+        let bytes = response.encode(&mut buffer).unwrap();
+        let decoded_response =
+            GetDownstreamFirmwareParametersResponse::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(response, decoded_response);
+        assert_eq!(
+            DownstreamFirmwareParameters::decode(decoded_response.portion()).unwrap(),
+            params
+        );
+    }
+}
@@ -0,0 +1,338 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{PldmCodec, PldmCodecError};
+use crate::error::PldmError;
+use crate::protocol::base::{
+    InstanceId, PldmMsgHeader, PldmMsgType, PldmSupportedType, TransferOperationFlag,
+    TransferRespFlag, PLDM_MSG_HEADER_LEN,
+};
+use crate::protocol::firmware_update::{FwUpdateCmd, PLDM_FWUP_MAX_DATA_PORTION_SIZE};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetDeviceMetaDataRequest {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub data_transfer_handle: u32,
+    pub transfer_op_flag: u8,
+}
+
+impl GetDeviceMetaDataRequest {
+    pub fn new(
+        instance_id: InstanceId,
+        msg_type: PldmMsgType,
+        data_transfer_handle: u32,
+        transfer_op_flag: TransferOperationFlag,
+    ) -> Self {
+        GetDeviceMetaDataRequest {
+            hdr: PldmMsgHeader::new(
+                instance_id,
+                msg_type,
+                PldmSupportedType::FwUpdate,
+                FwUpdateCmd::GetDeviceMetaData as u8,
+            ),
+            data_transfer_handle,
+            transfer_op_flag: transfer_op_flag as u8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetDeviceMetaDataResponseFixed {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub completion_code: u8,
+    pub next_data_transfer_handle: u32,
+    pub transfer_flag: u8,
+}
+
+/// GetDeviceMetaData response. The device metadata portion runs to the end of the message, so
+/// `decode` must be given a buffer trimmed to the received message length.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GetDeviceMetaDataResponse {
+    pub fixed: GetDeviceMetaDataResponseFixed,
+    pub portion_len: usize,
+    pub portion: [u8; PLDM_FWUP_MAX_DATA_PORTION_SIZE],
+}
+
+impl GetDeviceMetaDataResponse {
+    pub fn new(
+        instance_id: InstanceId,
+        completion_code: u8,
+        next_data_transfer_handle: u32,
+        transfer_flag: TransferRespFlag,
+        portion: &[u8],
+    ) -> Result<Self, PldmError> {
+        if portion.len() > PLDM_FWUP_MAX_DATA_PORTION_SIZE {
+            return Err(PldmError::InvalidLength);
+        }
+
+        let mut data = [0u8; PLDM_FWUP_MAX_DATA_PORTION_SIZE];
+        data[..portion.len()].copy_from_slice(portion);
+        Ok(GetDeviceMetaDataResponse {
+            fixed: GetDeviceMetaDataResponseFixed {
+                hdr: PldmMsgHeader::new(
+                    instance_id,
+                    PldmMsgType::Response,
+                    PldmSupportedType::FwUpdate,
+                    FwUpdateCmd::GetDeviceMetaData as u8,
+                ),
+                completion_code,
+                next_data_transfer_handle,
+                transfer_flag: transfer_flag as u8,
+            },
+            portion_len: portion.len(),
+            portion: data,
+        })
+    }
+
+    pub fn portion(&self) -> &[u8] {
+        &self.portion[..self.portion_len]
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        core::mem::size_of::<GetDeviceMetaDataResponseFixed>() + self.portion_len
+    }
+}
+
+impl PldmCodec for GetDeviceMetaDataResponse {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, PldmCodecError> {
+        if self.portion_len > PLDM_FWUP_MAX_DATA_PORTION_SIZE {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+        if buffer.len() < self.codec_size_in_bytes() {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+
+        let mut offset = 0;
+        let bytes = core::mem::size_of::<GetDeviceMetaDataResponseFixed>();
+        self.fixed
+            .write_to(&mut buffer[offset..offset + bytes])
+            .unwrap();
+        offset += bytes;
+
+        buffer[offset..offset + self.portion_len].copy_from_slice(self.portion());
+        Ok(offset + self.portion_len)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, PldmCodecError> {
+        let bytes = core::mem::size_of::<GetDeviceMetaDataResponseFixed>();
+        let fixed = GetDeviceMetaDataResponseFixed::read_from_bytes(
+            buffer.get(..bytes).ok_or(PldmCodecError::BufferTooShort)?,
+        )
+        .unwrap();
+
+        let data = &buffer[bytes..];
+        if data.len() > PLDM_FWUP_MAX_DATA_PORTION_SIZE {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+        let mut portion = [0u8; PLDM_FWUP_MAX_DATA_PORTION_SIZE];
+        portion[..data.len()].copy_from_slice(data);
+
+        Ok(GetDeviceMetaDataResponse {
+            fixed,
+            portion_len: data.len(),
+            portion,
+        })
+    }
+}
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetMetaDataRequest {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub data_transfer_handle: u32,
+    pub transfer_op_flag: u8,
+}
+
+impl GetMetaDataRequest {
+    pub fn new(
+        instance_id: InstanceId,
+        msg_type: PldmMsgType,
+        data_transfer_handle: u32,
+        transfer_op_flag: TransferOperationFlag,
+    ) -> Self {
+        GetMetaDataRequest {
+            hdr: PldmMsgHeader::new(
+                instance_id,
+                msg_type,
+                PldmSupportedType::FwUpdate,
+                FwUpdateCmd::GetMetaData as u8,
+            ),
+            data_transfer_handle,
+            transfer_op_flag: transfer_op_flag as u8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetMetaDataResponseFixed {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub completion_code: u8,
+    pub next_data_transfer_handle: u32,
+    pub transfer_flag: u8,
+}
+
+/// GetMetaData response. The metadata portion runs to the end of the message, so
+/// `decode` must be given a buffer trimmed to the received message length.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GetMetaDataResponse {
+    pub fixed: GetMetaDataResponseFixed,
+    pub portion_len: usize,
+    pub portion: [u8; PLDM_FWUP_MAX_DATA_PORTION_SIZE],
+}
+
+impl GetMetaDataResponse {
+    pub fn new(
+        instance_id: InstanceId,
+        completion_code: u8,
+        next_data_transfer_handle: u32,
+        transfer_flag: TransferRespFlag,
+        portion: &[u8],
+    ) -> Result<Self, PldmError> {
+        if portion.len() > PLDM_FWUP_MAX_DATA_PORTION_SIZE {
+            return Err(PldmError::InvalidLength);
+        }
+
+        let mut data = [0u8; PLDM_FWUP_MAX_DATA_PORTION_SIZE];
+        data[..portion.len()].copy_from_slice(portion);
+        Ok(GetMetaDataResponse {
+            fixed: GetMetaDataResponseFixed {
+                hdr: PldmMsgHeader::new(
+                    instance_id,
+                    PldmMsgType::Response,
+                    PldmSupportedType::FwUpdate,
+                    FwUpdateCmd::GetMetaData as u8,
+                ),
+                completion_code,
+                next_data_transfer_handle,
+                transfer_flag: transfer_flag as u8,
+            },
+            portion_len: portion.len(),
+            portion: data,
+        })
+    }
+
+    pub fn portion(&self) -> &[u8] {
+        &self.portion[..self.portion_len]
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        core::mem::size_of::<GetMetaDataResponseFixed>() + self.portion_len
+    }
+}
+
+impl PldmCodec for GetMetaDataResponse {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, PldmCodecError> {
+        if self.portion_len > PLDM_FWUP_MAX_DATA_PORTION_SIZE {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+        if buffer.len() < self.codec_size_in_bytes() {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+
+        let mut offset = 0;
+        let bytes = core::mem::size_of::<GetMetaDataResponseFixed>();
+        self.fixed
+            .write_to(&mut buffer[offset..offset + bytes])
+            .unwrap();
+        offset += bytes;
+
+        buffer[offset..offset + self.portion_len].copy_from_slice(self.portion());
+        Ok(offset + self.portion_len)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, PldmCodecError> {
+        let bytes = core::mem::size_of::<GetMetaDataResponseFixed>();
+        let fixed = GetMetaDataResponseFixed::read_from_bytes(
+            buffer.get(..bytes).ok_or(PldmCodecError::BufferTooShort)?,
+        )
+        .unwrap();
+
+        let data = &buffer[bytes..];
+        if data.len() > PLDM_FWUP_MAX_DATA_PORTION_SIZE {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+        let mut portion = [0u8; PLDM_FWUP_MAX_DATA_PORTION_SIZE];
+        portion[..data.len()].copy_from_slice(data);
+
+        Ok(GetMetaDataResponse {
+            fixed,
+            portion_len: data.len(),
+            portion,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_get_device_metadata_request() {
+        let request = GetDeviceMetaDataRequest::new(
+            0x01,
+            PldmMsgType::Request,
+            0x40,
+            TransferOperationFlag::GetNextPart,
+        );
+        let mut buffer = [0u8; 16];
+        let bytes = request.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, core::mem::size_of::<GetDeviceMetaDataRequest>());
+        let decoded_request = GetDeviceMetaDataRequest::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(request, decoded_request);
+    }
+
+    #[test]
+    fn test_get_device_metadata_response() {
+        let metadata = [0xa5u8; 24];
+        let response =
+            GetDeviceMetaDataResponse::new(0x01, 0, 0, TransferRespFlag::End, &metadata).unwrap();
+        let mut buffer = [0u8; 512];
+        let bytes = response.encode(&mut buffer).unwrap();
+        assert_eq!(
+            bytes,
+            core::mem::size_of::<GetDeviceMetaDataResponseFixed>() + metadata.len()
+        );
+        let decoded_response = GetDeviceMetaDataResponse::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(response, decoded_response);
+        assert_eq!(decoded_response.portion(), &metadata);
+    }
+
+    #[test]
+    fn test_get_metadata_request() {
+        let request = GetMetaDataRequest::new(
+            0x02,
+            PldmMsgType::Request,
+            0,
+            TransferOperationFlag::GetFirstPart,
+        );
+        let mut buffer = [0u8; 16];
+        let bytes = request.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, core::mem::size_of::<GetMetaDataRequest>());
+        let decoded_request = GetMetaDataRequest::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(request, decoded_request);
+    }
+
+    #[test]
+    fn test_get_metadata_response() {
+        let metadata = [0x3cu8; PLDM_FWUP_MAX_DATA_PORTION_SIZE];
+        let response = GetMetaDataResponse::new(
+            0x02,
+            0,
+            PLDM_FWUP_MAX_DATA_PORTION_SIZE as u32,
+            TransferRespFlag::Start,
+            &metadata,
+        )
+        .unwrap();
+        let mut buffer = [0u8; 512];
+        let bytes = response.encode(&mut buffer).unwrap();
+        assert_eq!(
+            bytes,
+            core::mem::size_of::<GetMetaDataResponseFixed>() + metadata.len()
+        );
+        let decoded_response = GetMetaDataResponse::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(response, decoded_response);
+    }
+}
@@ -2,11 +2,17 @@
 
 pub mod activate_fw;
 pub mod apply_complete;
+pub mod get_downstream_fw_params;
 pub mod get_fw_params;
+pub mod get_metadata;
+pub mod get_package_data;
 pub mod get_status;
 pub mod pass_component;
 pub mod query_devid;
+pub mod query_downstream_devices;
+pub mod query_downstream_identifiers;
 pub mod request_cancel;
+pub mod request_downstream_update;
 pub mod request_fw_data;
 pub mod request_update;
 pub mod transfer_complete;
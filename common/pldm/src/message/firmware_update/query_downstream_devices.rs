@@ -0,0 +1,105 @@
+// Licensed under the Apache-2.0 license
+
+use crate::protocol::base::{
+    InstanceId, PldmMsgHeader, PldmMsgType, PldmSupportedType, PLDM_MSG_HEADER_LEN,
+};
+use crate::protocol::firmware_update::FwUpdateCmd;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum DownstreamDeviceUpdateSupported {
+    NotSupported = 0x00,
+    Supported = 0x01,
+}
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct QueryDownstreamDevicesRequest {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+}
+
+impl QueryDownstreamDevicesRequest {
+    pub fn new(instance_id: InstanceId, message_type: PldmMsgType) -> Self {
+        QueryDownstreamDevicesRequest {
+            hdr: PldmMsgHeader::new(
+                instance_id,
+                message_type,
+                PldmSupportedType::FwUpdate,
+                FwUpdateCmd::QueryDownstreamDevices as u8,
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq, Default)]
+#[repr(C, packed)]
+pub struct QueryDownstreamDevicesResponse {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub completion_code: u8,
+    pub downstream_device_update_supported: u8,
+    pub number_of_downstream_devices: u16,
+    pub max_number_of_downstream_devices: u16,
+    pub capabilities: u32, // bitfield32
+}
+
+impl QueryDownstreamDevicesResponse {
+    pub fn new(
+        instance_id: InstanceId,
+        completion_code: u8,
+        downstream_device_update_supported: DownstreamDeviceUpdateSupported,
+        number_of_downstream_devices: u16,
+        max_number_of_downstream_devices: u16,
+        capabilities: u32,
+    ) -> Self {
+        QueryDownstreamDevicesResponse {
+            hdr: PldmMsgHeader::new(
+                instance_id,
+                PldmMsgType::Response,
+                PldmSupportedType::FwUpdate,
+                FwUpdateCmd::QueryDownstreamDevices as u8,
+            ),
+            completion_code,
+            downstream_device_update_supported: downstream_device_update_supported as u8,
+            number_of_downstream_devices,
+            max_number_of_downstream_devices,
+            capabilities,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::codec::PldmCodec;
+
+    #[test]
+    fn test_query_downstream_devices_request() {
+        let request = QueryDownstreamDevicesRequest::new(0x01, PldmMsgType::Request);
+        let mut buffer = [0u8; 16];
+        let bytes = request.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, core::mem::size_of::<QueryDownstreamDevicesRequest>());
+        let decoded_request = QueryDownstreamDevicesRequest::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(request, decoded_request);
+    }
+
+    #[test]
+    fn test_query_downstream_devices_response() {
+        let response = QueryDownstreamDevicesResponse::new(
+            0x01,
+            0,
+            DownstreamDeviceUpdateSupported::Supported,
+            2,
+            4,
+            0,
+        );
+        let mut buffer = [0u8; 32];
+        let bytes = response.encode(&mut buffer).unwrap();
+        assert_eq!(
+            bytes,
+            core::mem::size_of::<QueryDownstreamDevicesResponse>()
+        );
+        let decoded_response = QueryDownstreamDevicesResponse::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(response, decoded_response);
+    }
+}
@@ -0,0 +1,415 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{PldmCodec, PldmCodecError};
+use crate::error::PldmError;
+use crate::protocol::base::{
+    InstanceId, PldmMsgHeader, PldmMsgType, PldmSupportedType, TransferOperationFlag,
+    TransferRespFlag, PLDM_MSG_HEADER_LEN,
+};
+use crate::protocol::firmware_update::{
+    Descriptor, FwUpdateCmd, MAX_DESCRIPTORS_COUNT, MAX_DOWNSTREAM_DEVICE_COUNT,
+    PLDM_FWUP_MAX_DATA_PORTION_SIZE,
+};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct QueryDownstreamIdentifiersRequest {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub data_transfer_handle: u32,
+    pub transfer_op_flag: u8,
+}
+
+impl QueryDownstreamIdentifiersRequest {
+    pub fn new(
+        instance_id: InstanceId,
+        msg_type: PldmMsgType,
+        data_transfer_handle: u32,
+        transfer_op_flag: TransferOperationFlag,
+    ) -> Self {
+        QueryDownstreamIdentifiersRequest {
+            hdr: PldmMsgHeader::new(
+                instance_id,
+                msg_type,
+                PldmSupportedType::FwUpdate,
+                FwUpdateCmd::QueryDownstreamIdentifiers as u8,
+            ),
+            data_transfer_handle,
+            transfer_op_flag: transfer_op_flag as u8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct QueryDownstreamIdentifiersResponseFixed {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub completion_code: u8,
+    pub next_data_transfer_handle: u32,
+    pub transfer_flag: u8,
+}
+
+/// QueryDownstreamIdentifiers response. The portion runs to the end of the message, so `decode`
+/// must be given a buffer trimmed to the received message length. The portions of a multipart
+/// transfer concatenate to an encoded `DownstreamDeviceIdentifiers`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryDownstreamIdentifiersResponse {
+    pub fixed: QueryDownstreamIdentifiersResponseFixed,
+    pub portion_len: usize,
+    pub portion: [u8; PLDM_FWUP_MAX_DATA_PORTION_SIZE],
+}
+
+impl QueryDownstreamIdentifiersResponse {
+    pub fn new(
+        instance_id: InstanceId,
+        completion_code: u8,
+        next_data_transfer_handle: u32,
+        transfer_flag: TransferRespFlag,
+        portion: &[u8],
+    ) -> Result<Self, PldmError> {
+        if portion.len() > PLDM_FWUP_MAX_DATA_PORTION_SIZE {
+            return Err(PldmError::InvalidLength);
+        }
+
+        let mut data = [0u8; PLDM_FWUP_MAX_DATA_PORTION_SIZE];
+        data[..portion.len()].copy_from_slice(portion);
+        Ok(QueryDownstreamIdentifiersResponse {
+            fixed: QueryDownstreamIdentifiersResponseFixed {
+                hdr: PldmMsgHeader::new(
+                    instance_id,
+                    PldmMsgType::Response,
+                    PldmSupportedType::FwUpdate,
+                    FwUpdateCmd::QueryDownstreamIdentifiers as u8,
+                ),
+                completion_code,
+                next_data_transfer_handle,
+                transfer_flag: transfer_flag as u8,
+            },
+            portion_len: portion.len(),
+            portion: data,
+        })
+    }
+
+    pub fn portion(&self) -> &[u8] {
+        &self.portion[..self.portion_len]
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        core::mem::size_of::<QueryDownstreamIdentifiersResponseFixed>() + self.portion_len
+    }
+}
+
+impl PldmCodec for QueryDownstreamIdentifiersResponse {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, PldmCodecError> {
+        if self.portion_len > PLDM_FWUP_MAX_DATA_PORTION_SIZE {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+        if buffer.len() < self.codec_size_in_bytes() {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+
+        let mut offset = 0;
+        let bytes = core::mem::size_of::<QueryDownstreamIdentifiersResponseFixed>();
+        self.fixed
+            .write_to(&mut buffer[offset..offset + bytes])
+            .unwrap();
+        offset += bytes;
+
+        buffer[offset..offset + self.portion_len].copy_from_slice(self.portion());
+        Ok(offset + self.portion_len)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, PldmCodecError> {
+        let bytes = core::mem::size_of::<QueryDownstreamIdentifiersResponseFixed>();
+        let fixed = QueryDownstreamIdentifiersResponseFixed::read_from_bytes(
+            buffer.get(..bytes).ok_or(PldmCodecError::BufferTooShort)?,
+        )
+        .unwrap();
+
+        let data = &buffer[bytes..];
+        if data.len() > PLDM_FWUP_MAX_DATA_PORTION_SIZE {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+        let mut portion = [0u8; PLDM_FWUP_MAX_DATA_PORTION_SIZE];
+        portion[..data.len()].copy_from_slice(data);
+
+        Ok(QueryDownstreamIdentifiersResponse {
+            fixed,
+            portion_len: data.len(),
+            portion,
+        })
+    }
+}
+
+// A downstream device record: the device index and its descriptors
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DownstreamDevice {
+    pub downstream_device_index: u16,
+    pub downstream_descriptor_count: u8,
+    pub downstream_descriptors: [Descriptor; MAX_DESCRIPTORS_COUNT],
+}
+
+impl Default for DownstreamDevice {
+    fn default() -> Self {
+        DownstreamDevice {
+            downstream_device_index: 0,
+            downstream_descriptor_count: 0,
+            downstream_descriptors: [Descriptor::new_empty(); MAX_DESCRIPTORS_COUNT],
+        }
+    }
+}
+
+impl DownstreamDevice {
+    pub fn new(
+        downstream_device_index: u16,
+        descriptors: &[Descriptor],
+    ) -> Result<Self, PldmError> {
+        if descriptors.is_empty() || descriptors.len() > MAX_DESCRIPTORS_COUNT {
+            return Err(PldmError::InvalidDescriptorCount);
+        }
+
+        let mut downstream_descriptors = [Descriptor::new_empty(); MAX_DESCRIPTORS_COUNT];
+        downstream_descriptors[..descriptors.len()].copy_from_slice(descriptors);
+        Ok(DownstreamDevice {
+            downstream_device_index,
+            downstream_descriptor_count: descriptors.len() as u8,
+            downstream_descriptors,
+        })
+    }
+
+    pub fn descriptors(&self) -> &[Descriptor] {
+        &self.downstream_descriptors[..self.downstream_descriptor_count as usize]
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        let mut bytes = core::mem::size_of::<u16>() + core::mem::size_of::<u8>();
+        for descriptor in self.descriptors() {
+            bytes += descriptor.codec_size_in_bytes();
+        }
+        bytes
+    }
+}
+
+impl PldmCodec for DownstreamDevice {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, PldmCodecError> {
+        if buffer.len() < self.codec_size_in_bytes() {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+        let mut offset = 0;
+
+        self.downstream_device_index
+            .write_to(&mut buffer[offset..offset + core::mem::size_of::<u16>()])
+            .unwrap();
+        offset += core::mem::size_of::<u16>();
+
+        self.downstream_descriptor_count
+            .write_to(&mut buffer[offset..offset + core::mem::size_of::<u8>()])
+            .unwrap();
+        offset += core::mem::size_of::<u8>();
+
+        for descriptor in self.descriptors() {
+            offset += descriptor.encode(&mut buffer[offset..])?;
+        }
+        Ok(offset)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, PldmCodecError> {
+        let mut offset = 0;
+
+        let downstream_device_index = u16::read_from_bytes(
+            buffer
+                .get(offset..offset + core::mem::size_of::<u16>())
+                .ok_or(PldmCodecError::BufferTooShort)?,
+        )
+        .unwrap();
+        offset += core::mem::size_of::<u16>();
+
+        let downstream_descriptor_count =
+            *buffer.get(offset).ok_or(PldmCodecError::BufferTooShort)?;
+        offset += core::mem::size_of::<u8>();
+        if downstream_descriptor_count as usize > MAX_DESCRIPTORS_COUNT {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+
+        let mut downstream_descriptors = [Descriptor::new_empty(); MAX_DESCRIPTORS_COUNT];
+        for descriptor in downstream_descriptors
+            .iter_mut()
+            .take(downstream_descriptor_count as usize)
+        {
+            *descriptor = Descriptor::decode(buffer.get(offset..).unwrap_or_default())?;
+            offset += descriptor.codec_size_in_bytes();
+        }
+
+        Ok(DownstreamDevice {
+            downstream_device_index,
+            downstream_descriptor_count,
+            downstream_descriptors,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Immutable, PartialEq, Default)]
+#[repr(C, packed)]
+pub struct DownstreamDeviceIdentifiersFixed {
+    // Length of the downstream device records that follow the device count
+    pub downstream_devices_length: u32,
+    pub number_of_downstream_devices: u16,
+}
+
+// The downstream device identifiers carried by QueryDownstreamIdentifiers
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DownstreamDeviceIdentifiers {
+    pub fixed: DownstreamDeviceIdentifiersFixed,
+    pub downstream_devices: [DownstreamDevice; MAX_DOWNSTREAM_DEVICE_COUNT],
+}
+
+impl DownstreamDeviceIdentifiers {
+    pub fn new(downstream_devices: &[DownstreamDevice]) -> Result<Self, PldmError> {
+        if downstream_devices.len() > MAX_DOWNSTREAM_DEVICE_COUNT {
+            return Err(PldmError::InvalidLength);
+        }
+
+        let mut devices = [DownstreamDevice::default(); MAX_DOWNSTREAM_DEVICE_COUNT];
+        devices[..downstream_devices.len()].copy_from_slice(downstream_devices);
+        Ok(DownstreamDeviceIdentifiers {
+            fixed: DownstreamDeviceIdentifiersFixed {
+                downstream_devices_length: downstream_devices
+                    .iter()
+                    .map(|device| device.codec_size_in_bytes())
+                    .sum::<usize>() as u32,
+                number_of_downstream_devices: downstream_devices.len() as u16,
+            },
+            downstream_devices: devices,
+        })
+    }
+
+    pub fn devices(&self) -> &[DownstreamDevice] {
+        &self.downstream_devices[..self.fixed.number_of_downstream_devices as usize]
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        core::mem::size_of::<DownstreamDeviceIdentifiersFixed>()
+            + self.fixed.downstream_devices_length as usize
+    }
+}
+
+impl PldmCodec for DownstreamDeviceIdentifiers {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, PldmCodecError> {
+        if buffer.len() < self.codec_size_in_bytes() {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+        let mut offset = 0;
+
+        let bytes = core::mem::size_of::<DownstreamDeviceIdentifiersFixed>();
+        self.fixed
+            .write_to(&mut buffer[offset..offset + bytes])
+            .unwrap();
+        offset += bytes;
+
+        for device in self.devices() {
+            offset += device.encode(&mut buffer[offset..])?;
+        }
+        Ok(offset)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, PldmCodecError> {
+        let mut offset = 0;
+
+        let bytes = core::mem::size_of::<DownstreamDeviceIdentifiersFixed>();
+        let fixed = DownstreamDeviceIdentifiersFixed::read_from_bytes(
+            buffer
+                .get(offset..offset + bytes)
+                .ok_or(PldmCodecError::BufferTooShort)?,
+        )
+        .unwrap();
+        offset += bytes;
+        if fixed.number_of_downstream_devices as usize > MAX_DOWNSTREAM_DEVICE_COUNT {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+
+        let mut downstream_devices = [DownstreamDevice::default(); MAX_DOWNSTREAM_DEVICE_COUNT];
+        for device in downstream_devices
+            .iter_mut()
+            .take(fixed.number_of_downstream_devices as usize)
+        {
+            *device = DownstreamDevice::decode(buffer.get(offset..).unwrap_or_default())?;
+            offset += device.codec_size_in_bytes();
+        }
+
+        Ok(DownstreamDeviceIdentifiers {
+            fixed,
+            downstream_devices,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::protocol::firmware_update::DescriptorType;
+
+    fn construct_downstream_device_identifiers() -> DownstreamDeviceIdentifiers {
+        let uuid = Descriptor::new(DescriptorType::Uuid, &[0xA5; 16]).unwrap();
+        let pci_vendor_id = Descriptor::new(DescriptorType::PciVendorId, &[0x14, 0x1e]).unwrap();
+        let devices = [
+            DownstreamDevice::new(0, &[uuid]).unwrap(),
+            DownstreamDevice::new(1, &[uuid, pci_vendor_id]).unwrap(),
+        ];
+        DownstreamDeviceIdentifiers::new(&devices).unwrap()
+    }
+
+    #[test]
+    fn test_query_downstream_identifiers_request() {
+        let request = QueryDownstreamIdentifiersRequest::new(
+            0x01,
+            PldmMsgType::Request,
+            0,
+            TransferOperationFlag::GetFirstPart,
+        );
+        let mut buffer = [0u8; 16];
+        let bytes = request.encode(&mut buffer).unwrap();
+        assert_eq!(
+            bytes,
+            core::mem::size_of::<QueryDownstreamIdentifiersRequest>()
+        );
+        let decoded_request = QueryDownstreamIdentifiersRequest::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(request, decoded_request);
+    }
+
+    #[test]
+    fn test_downstream_device_identifiers() {
+        let identifiers = construct_downstream_device_identifiers();
+        assert_eq!(identifiers.devices().len(), 2);
+        let mut buffer = [0u8; 512];
+        let bytes = identifiers.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, identifiers.codec_size_in_bytes());
+        let decoded_identifiers = DownstreamDeviceIdentifiers::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(identifiers, decoded_identifiers);
+        assert_eq!(decoded_identifiers.devices()[1].descriptors().len(), 2);
+    }
+
+    #[test]
+    fn test_query_downstream_identifiers_response() {
+        let identifiers = construct_downstream_device_identifiers();
+        let mut data = [0u8; 512];
+        let data_len = identifiers.encode(&mut data).unwrap();
+
+        let response = QueryDownstreamIdentifiersResponse::new(
+            0x01,
+            0,
+            0,
+            TransferRespFlag::StartAndEnd,
+            &data[..data_len],
+        )
+        .unwrap();
+        let mut buffer = [0u8; 512];
+        let bytes = response.encode(&mut buffer).unwrap();
+        let decoded_response =
+            QueryDownstreamIdentifiersResponse::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(response, decoded_response);
+        assert_eq!(
+            DownstreamDeviceIdentifiers::decode(decoded_response.portion()).unwrap(),
+            identifiers
+        );
+    }
+}
@@ -0,0 +1,187 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{PldmCodec, PldmCodecError};
+use crate::message::firmware_update::request_update::FdWillSendPkgDataCmd;
+use crate::protocol::base::{
+    InstanceId, PldmMsgHeader, PldmMsgType, PldmSupportedType, PLDM_MSG_HEADER_LEN,
+};
+use crate::protocol::firmware_update::FwUpdateCmd;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+#[derive(Debug, Copy, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct RequestDownstreamDeviceUpdateRequest {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub max_downstream_device_transfer_size: u32,
+    pub max_outstanding_transfer_req: u8,
+    pub downstream_device_pkg_data_len: u16,
+}
+
+impl RequestDownstreamDeviceUpdateRequest {
+    pub fn new(
+        instance_id: InstanceId,
+        msg_type: PldmMsgType,
+        max_downstream_device_transfer_size: u32,
+        max_outstanding_transfer_req: u8,
+        downstream_device_pkg_data_len: u16,
+    ) -> Self {
+        RequestDownstreamDeviceUpdateRequest {
+            hdr: PldmMsgHeader::new(
+                instance_id,
+                msg_type,
+                PldmSupportedType::FwUpdate,
+                FwUpdateCmd::RequestDownstreamDeviceUpdate as u8,
+            ),
+            max_downstream_device_transfer_size,
+            max_outstanding_transfer_req,
+            downstream_device_pkg_data_len,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, FromBytes, IntoBytes, Immutable, PartialEq, Default)]
+#[repr(C, packed)]
+pub struct RequestDownstreamDeviceUpdateResponseFixed {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub completion_code: u8,
+    pub downstream_device_meta_data_len: u16,
+    pub downstream_device_will_send_pkg_data_cmd: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RequestDownstreamDeviceUpdateResponse {
+    pub fixed: RequestDownstreamDeviceUpdateResponseFixed,
+    // This field is only present if DownstreamDeviceWillSendGetPackageDataCommand is set to 0x02.
+    pub get_pkg_data_max_transfer_size: Option<u32>,
+}
+
+impl RequestDownstreamDeviceUpdateResponse {
+    pub fn new(
+        instance_id: InstanceId,
+        completion_code: u8,
+        downstream_device_meta_data_len: u16,
+        downstream_device_will_send_pkg_data_cmd: u8,
+        get_pkg_data_max_transfer_size: Option<u32>,
+    ) -> RequestDownstreamDeviceUpdateResponse {
+        RequestDownstreamDeviceUpdateResponse {
+            fixed: RequestDownstreamDeviceUpdateResponseFixed {
+                hdr: PldmMsgHeader::new(
+                    instance_id,
+                    PldmMsgType::Response,
+                    PldmSupportedType::FwUpdate,
+                    FwUpdateCmd::RequestDownstreamDeviceUpdate as u8,
+                ),
+                completion_code,
+                downstream_device_meta_data_len,
+                downstream_device_will_send_pkg_data_cmd,
+            },
+            get_pkg_data_max_transfer_size,
+        }
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        let mut bytes = core::mem::size_of::<RequestDownstreamDeviceUpdateResponseFixed>();
+        if self.fixed.downstream_device_will_send_pkg_data_cmd
+            == FdWillSendPkgDataCmd::WillSendWithMaxTransferSize as u8
+        {
+            bytes += core::mem::size_of::<u32>();
+        }
+        bytes
+    }
+}
+
+impl PldmCodec for RequestDownstreamDeviceUpdateResponse {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, PldmCodecError> {
+        if buffer.len() < self.codec_size_in_bytes() {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+
+        let mut offset = 0;
+        let bytes = core::mem::size_of::<RequestDownstreamDeviceUpdateResponseFixed>();
+        self.fixed
+            .write_to(&mut buffer[offset..offset + bytes])
+            .unwrap();
+        offset += bytes;
+
+        if let Some(size) = self.get_pkg_data_max_transfer_size {
+            size.write_to(&mut buffer[offset..offset + core::mem::size_of::<u32>()])
+                .unwrap();
+            offset += core::mem::size_of::<u32>();
+        }
+
+        Ok(offset)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, PldmCodecError> {
+        let mut offset = 0;
+        let bytes = core::mem::size_of::<RequestDownstreamDeviceUpdateResponseFixed>();
+        let fixed = RequestDownstreamDeviceUpdateResponseFixed::read_from_bytes(
+            buffer
+                .get(offset..offset + bytes)
+                .ok_or(PldmCodecError::BufferTooShort)?,
+        )
+        .unwrap();
+        offset += bytes;
+
+        let get_pkg_data_max_transfer_size = if fixed.downstream_device_will_send_pkg_data_cmd
+            == FdWillSendPkgDataCmd::WillSendWithMaxTransferSize as u8
+        {
+            Some(
+                u32::read_from_bytes(
+                    buffer
+                        .get(offset..offset + core::mem::size_of::<u32>())
+                        .ok_or(PldmCodecError::BufferTooShort)?,
+                )
+                .unwrap(),
+            )
+        } else {
+            None
+        };
+
+        Ok(RequestDownstreamDeviceUpdateResponse {
+            fixed,
+            get_pkg_data_max_transfer_size,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_downstream_device_update_request() {
+        let request =
+            RequestDownstreamDeviceUpdateRequest::new(0, PldmMsgType::Request, 512, 1, 64);
+
+        let mut buffer = [0u8; 64];
+        let encoded_size = request.encode(&mut buffer).unwrap();
+        assert_eq!(
+            encoded_size,
+            core::mem::size_of::<RequestDownstreamDeviceUpdateRequest>()
+        );
+
+        let decoded_request =
+            RequestDownstreamDeviceUpdateRequest::decode(&buffer[..encoded_size]).unwrap();
+        assert_eq!(request, decoded_request);
+    }
+
+    #[test]
+    fn test_request_downstream_device_update_response() {
+        let response = RequestDownstreamDeviceUpdateResponse::new(
+            1,
+            0,
+            0,
+            FdWillSendPkgDataCmd::WillSendWithMaxTransferSize as u8,
+            Some(256),
+        );
+
+        let mut buffer = [0u8; 64];
+        let encoded_size = response.encode(&mut buffer).unwrap();
+        assert_eq!(encoded_size, response.codec_size_in_bytes());
+
+        let decoded_response =
+            RequestDownstreamDeviceUpdateResponse::decode(&buffer[..encoded_size]).unwrap();
+        assert_eq!(response, decoded_response);
+    }
+}
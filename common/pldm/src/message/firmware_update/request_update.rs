@@ -131,6 +131,15 @@ impl PldmCodec for RequestUpdateRequest {
     }
 }
 
+/// Values of the FDWillSendGetPackageDataCommand field in the RequestUpdate response.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum FdWillSendPkgDataCmd {
+    NoPackageData = 0x00,
+    WillSend = 0x01,
+    WillSendWithMaxTransferSize = 0x02,
+}
+
 #[derive(Debug, Copy, Clone, FromBytes, IntoBytes, Immutable, PartialEq, Default)]
 #[repr(C, packed)]
 pub struct RequestUpdateResponseFixed {
@@ -173,7 +182,9 @@ impl RequestUpdateResponse {
 
     pub fn codec_size_in_bytes(&self) -> usize {
         let mut bytes = core::mem::size_of::<RequestUpdateResponseFixed>();
-        if self.fixed.fd_will_send_pkg_data_cmd == 0x02 {
+        if self.fixed.fd_will_send_pkg_data_cmd
+            == FdWillSendPkgDataCmd::WillSendWithMaxTransferSize as u8
+        {
             bytes += core::mem::size_of::<u32>();
         }
         bytes
@@ -213,7 +224,9 @@ impl PldmCodec for RequestUpdateResponse {
         .unwrap();
         offset += core::mem::size_of::<RequestUpdateResponseFixed>();
 
-        let get_pkg_data_max_transfer_size = if fixed.fd_will_send_pkg_data_cmd == 0x02 {
+        let get_pkg_data_max_transfer_size = if fixed.fd_will_send_pkg_data_cmd
+            == FdWillSendPkgDataCmd::WillSendWithMaxTransferSize as u8
+        {
             Some(
                 u32::read_from_bytes(
                     buffer
@@ -2,3 +2,4 @@
 
 pub mod control;
 pub mod firmware_update;
+pub mod platform;
@@ -0,0 +1,248 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{PldmCodec, PldmCodecError};
+use crate::error::PldmError;
+use crate::protocol::base::{
+    InstanceId, PldmMsgHeader, PldmMsgType, PldmSupportedType, TransferOperationFlag,
+    TransferRespFlag, PLDM_MSG_HEADER_LEN,
+};
+use crate::protocol::platform::PlatformCmd;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+pub const MAX_PDR_TRANSFER_SIZE: usize = 128; // Arbitrary limit for static storage
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetPdrRequest {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub record_handle: u32,
+    pub data_transfer_handle: u32,
+    pub transfer_op_flag: u8,
+    pub request_count: u16,
+    pub record_change_number: u16,
+}
+
+impl GetPdrRequest {
+    pub fn new(
+        instance_id: InstanceId,
+        msg_type: PldmMsgType,
+        record_handle: u32,
+        data_transfer_handle: u32,
+        transfer_op_flag: TransferOperationFlag,
+        request_count: u16,
+        record_change_number: u16,
+    ) -> Self {
+        GetPdrRequest {
+            hdr: PldmMsgHeader::new(
+                instance_id,
+                msg_type,
+                PldmSupportedType::Platform,
+                PlatformCmd::GetPdr as u8,
+            ),
+            record_handle,
+            data_transfer_handle,
+            transfer_op_flag: transfer_op_flag as u8,
+            request_count,
+            record_change_number,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetPdrResponseFixed {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub completion_code: u8,
+    pub next_record_handle: u32,
+    pub next_data_transfer_handle: u32,
+    pub transfer_flag: u8,
+    pub response_count: u16,
+}
+
+/// GetPDR response. `transfer_crc` is only present on the wire when `transfer_flag` is `End`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GetPdrResponse {
+    pub fixed: GetPdrResponseFixed,
+    pub record_data: [u8; MAX_PDR_TRANSFER_SIZE],
+    pub transfer_crc: u8,
+}
+
+impl GetPdrResponse {
+    pub fn new(
+        instance_id: InstanceId,
+        completion_code: u8,
+        next_record_handle: u32,
+        next_data_transfer_handle: u32,
+        transfer_flag: TransferRespFlag,
+        record_data: &[u8],
+        transfer_crc: u8,
+    ) -> Result<Self, PldmError> {
+        if record_data.len() > MAX_PDR_TRANSFER_SIZE {
+            return Err(PldmError::InvalidLength);
+        }
+
+        let mut data = [0u8; MAX_PDR_TRANSFER_SIZE];
+        data[..record_data.len()].copy_from_slice(record_data);
+        Ok(GetPdrResponse {
+            fixed: GetPdrResponseFixed {
+                hdr: PldmMsgHeader::new(
+                    instance_id,
+                    PldmMsgType::Response,
+                    PldmSupportedType::Platform,
+                    PlatformCmd::GetPdr as u8,
+                ),
+                completion_code,
+                next_record_handle,
+                next_data_transfer_handle,
+                transfer_flag: transfer_flag as u8,
+                response_count: record_data.len() as u16,
+            },
+            record_data: data,
+            transfer_crc,
+        })
+    }
+
+    pub fn record_data(&self) -> &[u8] {
+        &self.record_data[..self.fixed.response_count as usize]
+    }
+
+    fn has_transfer_crc(&self) -> bool {
+        self.fixed.transfer_flag == TransferRespFlag::End as u8
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        let mut bytes = core::mem::size_of::<GetPdrResponseFixed>();
+        bytes += self.fixed.response_count as usize;
+        if self.has_transfer_crc() {
+            bytes += core::mem::size_of::<u8>();
+        }
+        bytes
+    }
+}
+
+impl PldmCodec for GetPdrResponse {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, PldmCodecError> {
+        let data_len = self.fixed.response_count as usize;
+        if data_len > MAX_PDR_TRANSFER_SIZE {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+        if buffer.len() < self.codec_size_in_bytes() {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+
+        let mut offset = 0;
+        let bytes = core::mem::size_of::<GetPdrResponseFixed>();
+        self.fixed
+            .write_to(&mut buffer[offset..offset + bytes])
+            .unwrap();
+        offset += bytes;
+
+        buffer[offset..offset + data_len].copy_from_slice(&self.record_data[..data_len]);
+        offset += data_len;
+
+        if self.has_transfer_crc() {
+            buffer[offset] = self.transfer_crc;
+            offset += core::mem::size_of::<u8>();
+        }
+        Ok(offset)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, PldmCodecError> {
+        let mut offset = 0;
+        let bytes = core::mem::size_of::<GetPdrResponseFixed>();
+        let fixed = GetPdrResponseFixed::read_from_bytes(
+            buffer
+                .get(offset..offset + bytes)
+                .ok_or(PldmCodecError::BufferTooShort)?,
+        )
+        .unwrap();
+        offset += bytes;
+
+        let data_len = fixed.response_count as usize;
+        if data_len > MAX_PDR_TRANSFER_SIZE {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+        let mut record_data = [0u8; MAX_PDR_TRANSFER_SIZE];
+        record_data[..data_len].copy_from_slice(
+            buffer
+                .get(offset..offset + data_len)
+                .ok_or(PldmCodecError::BufferTooShort)?,
+        );
+        offset += data_len;
+
+        let transfer_crc = if fixed.transfer_flag == TransferRespFlag::End as u8 {
+            *buffer.get(offset).ok_or(PldmCodecError::BufferTooShort)?
+        } else {
+            0
+        };
+
+        Ok(GetPdrResponse {
+            fixed,
+            record_data,
+            transfer_crc,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_get_pdr_request() {
+        let request = GetPdrRequest::new(
+            0x01,
+            PldmMsgType::Request,
+            0x0000_0002,
+            0,
+            TransferOperationFlag::GetFirstPart,
+            MAX_PDR_TRANSFER_SIZE as u16,
+            0,
+        );
+        let mut buffer = [0u8; 32];
+        let bytes = request.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, core::mem::size_of::<GetPdrRequest>());
+        let decoded_request = GetPdrRequest::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(request, decoded_request);
+    }
+
+    #[test]
+    fn test_get_pdr_response() {
+        let record = [0xa5u8; 20];
+        let response = GetPdrResponse::new(
+            0x01,
+            0,
+            0x0000_0003,
+            0,
+            TransferRespFlag::StartAndEnd,
+            &record,
+            0,
+        )
+        .unwrap();
+        let mut buffer = [0u8; 256];
+        let bytes = response.encode(&mut buffer).unwrap();
+        assert_eq!(
+            bytes,
+            core::mem::size_of::<GetPdrResponseFixed>() + record.len()
+        );
+        let decoded_response = GetPdrResponse::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(response, decoded_response);
+        assert_eq!(decoded_response.record_data(), &record);
+    }
+
+    #[test]
+    fn test_get_pdr_response_end_carries_crc() {
+        let record = [0x11u8; 8];
+        let response =
+            GetPdrResponse::new(0x01, 0, 0, 0, TransferRespFlag::End, &record, 0x3c).unwrap();
+        let mut buffer = [0u8; 256];
+        let bytes = response.encode(&mut buffer).unwrap();
+        assert_eq!(
+            bytes,
+            core::mem::size_of::<GetPdrResponseFixed>() + record.len() + 1
+        );
+        assert_eq!(buffer[bytes - 1], 0x3c);
+        let decoded_response = GetPdrResponse::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(response, decoded_response);
+    }
+}
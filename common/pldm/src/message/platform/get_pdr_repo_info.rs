@@ -0,0 +1,108 @@
+// Licensed under the Apache-2.0 license
+
+use crate::protocol::base::{
+    InstanceId, PldmMsgHeader, PldmMsgType, PldmSupportedType, PLDM_MSG_HEADER_LEN,
+};
+use crate::protocol::platform::{PdrRepositoryState, PlatformCmd, Timestamp104};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetPdrRepositoryInfoRequest {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+}
+
+impl GetPdrRepositoryInfoRequest {
+    pub fn new(instance_id: InstanceId, msg_type: PldmMsgType) -> Self {
+        GetPdrRepositoryInfoRequest {
+            hdr: PldmMsgHeader::new(
+                instance_id,
+                msg_type,
+                PldmSupportedType::Platform,
+                PlatformCmd::GetPdrRepositoryInfo as u8,
+            ),
+        }
+    }
+}
+
+/// Summary of the PDR repository as reported by GetPDRRepositoryInfo.
+#[derive(Debug, Clone, Copy, Default, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct PdrRepositoryInfo {
+    pub repository_state: u8,
+    pub update_time: Timestamp104,
+    pub oem_update_time: Timestamp104,
+    pub record_count: u32,
+    pub repository_size: u32,
+    pub largest_record_size: u32,
+    pub data_transfer_handle_timeout: u8,
+}
+
+impl PdrRepositoryInfo {
+    pub fn new(
+        repository_state: PdrRepositoryState,
+        record_count: u32,
+        repository_size: u32,
+        largest_record_size: u32,
+    ) -> Self {
+        PdrRepositoryInfo {
+            repository_state: repository_state as u8,
+            record_count,
+            repository_size,
+            largest_record_size,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetPdrRepositoryInfoResponse {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub completion_code: u8,
+    pub info: PdrRepositoryInfo,
+}
+
+impl GetPdrRepositoryInfoResponse {
+    pub fn new(instance_id: InstanceId, completion_code: u8, info: &PdrRepositoryInfo) -> Self {
+        GetPdrRepositoryInfoResponse {
+            hdr: PldmMsgHeader::new(
+                instance_id,
+                PldmMsgType::Response,
+                PldmSupportedType::Platform,
+                PlatformCmd::GetPdrRepositoryInfo as u8,
+            ),
+            completion_code,
+            info: *info,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::codec::PldmCodec;
+
+    #[test]
+    fn test_get_pdr_repository_info_request() {
+        let request = GetPdrRepositoryInfoRequest::new(0x01, PldmMsgType::Request);
+        let mut buffer = [0u8; 16];
+        let bytes = request.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, core::mem::size_of::<GetPdrRepositoryInfoRequest>());
+        let decoded_request = GetPdrRepositoryInfoRequest::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(request, decoded_request);
+    }
+
+    #[test]
+    fn test_get_pdr_repository_info_response() {
+        let mut info = PdrRepositoryInfo::new(PdrRepositoryState::Available, 3, 256, 128);
+        info.update_time[0] = 0x5a;
+        let response = GetPdrRepositoryInfoResponse::new(0x01, 0, &info);
+        let mut buffer = [0u8; 64];
+        let bytes = response.encode(&mut buffer).unwrap();
+        // hdr(3) + completion_code(1) + state(1) + 2 * timestamp104(13) + 3 * u32 + timeout(1)
+        assert_eq!(bytes, 44);
+        let decoded_response = GetPdrRepositoryInfoResponse::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(response, decoded_response);
+    }
+}
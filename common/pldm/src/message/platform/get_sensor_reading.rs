@@ -0,0 +1,178 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{PldmCodec, PldmCodecError};
+use crate::protocol::base::{
+    InstanceId, PldmMsgHeader, PldmMsgType, PldmSupportedType, PLDM_MSG_HEADER_LEN,
+};
+use crate::protocol::platform::{
+    PlatformCmd, SensorDataSize, SensorEventMessageEnable, SensorOperationalState, SensorReading,
+    SensorState,
+};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetSensorReadingRequest {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub sensor_id: u16,
+    pub rearm_event_state: u8,
+}
+
+impl GetSensorReadingRequest {
+    pub fn new(
+        instance_id: InstanceId,
+        msg_type: PldmMsgType,
+        sensor_id: u16,
+        rearm_event_state: bool,
+    ) -> Self {
+        GetSensorReadingRequest {
+            hdr: PldmMsgHeader::new(
+                instance_id,
+                msg_type,
+                PldmSupportedType::Platform,
+                PlatformCmd::GetSensorReading as u8,
+            ),
+            sensor_id,
+            rearm_event_state: rearm_event_state as u8,
+        }
+    }
+}
+
+/// Present state of a numeric sensor as reported by GetSensorReading.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumericSensorReading {
+    pub sensor_op_state: SensorOperationalState,
+    pub event_message_enable: SensorEventMessageEnable,
+    pub present_state: SensorState,
+    pub previous_state: SensorState,
+    pub event_state: SensorState,
+    pub present_reading: SensorReading,
+}
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetSensorReadingResponseFixed {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub completion_code: u8,
+    pub sensor_data_size: u8,
+    pub sensor_op_state: u8,
+    pub sensor_event_message_enable: u8,
+    pub present_state: u8,
+    pub previous_state: u8,
+    pub event_state: u8,
+}
+
+/// GetSensorReading response. The width of `present_reading` on the wire follows
+/// `sensor_data_size`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GetSensorReadingResponse {
+    pub fixed: GetSensorReadingResponseFixed,
+    pub present_reading: SensorReading,
+}
+
+impl GetSensorReadingResponse {
+    pub fn new(
+        instance_id: InstanceId,
+        completion_code: u8,
+        reading: &NumericSensorReading,
+    ) -> Self {
+        GetSensorReadingResponse {
+            fixed: GetSensorReadingResponseFixed {
+                hdr: PldmMsgHeader::new(
+                    instance_id,
+                    PldmMsgType::Response,
+                    PldmSupportedType::Platform,
+                    PlatformCmd::GetSensorReading as u8,
+                ),
+                completion_code,
+                sensor_data_size: reading.present_reading.data_size() as u8,
+                sensor_op_state: reading.sensor_op_state as u8,
+                sensor_event_message_enable: reading.event_message_enable as u8,
+                present_state: reading.present_state as u8,
+                previous_state: reading.previous_state as u8,
+                event_state: reading.event_state as u8,
+            },
+            present_reading: reading.present_reading,
+        }
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        core::mem::size_of::<GetSensorReadingResponseFixed>()
+            + self.present_reading.data_size().size_in_bytes()
+    }
+}
+
+impl PldmCodec for GetSensorReadingResponse {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, PldmCodecError> {
+        if buffer.len() < self.codec_size_in_bytes() {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+
+        let mut offset = 0;
+        let bytes = core::mem::size_of::<GetSensorReadingResponseFixed>();
+        self.fixed
+            .write_to(&mut buffer[offset..offset + bytes])
+            .unwrap();
+        offset += bytes;
+
+        offset += self
+            .present_reading
+            .write_le(&mut buffer[offset..])
+            .map_err(|_| PldmCodecError::BufferTooShort)?;
+        Ok(offset)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, PldmCodecError> {
+        let bytes = core::mem::size_of::<GetSensorReadingResponseFixed>();
+        let fixed = GetSensorReadingResponseFixed::read_from_bytes(
+            buffer.get(..bytes).ok_or(PldmCodecError::BufferTooShort)?,
+        )
+        .unwrap();
+
+        let data_size = SensorDataSize::try_from(fixed.sensor_data_size)
+            .map_err(|_| PldmCodecError::Unsupported)?;
+        let present_reading = SensorReading::read_le(data_size, &buffer[bytes..])
+            .map_err(|_| PldmCodecError::BufferTooShort)?;
+
+        Ok(GetSensorReadingResponse {
+            fixed,
+            present_reading,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_get_sensor_reading_request() {
+        let request = GetSensorReadingRequest::new(0x01, PldmMsgType::Request, 0x0010, true);
+        let mut buffer = [0u8; 16];
+        let bytes = request.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, core::mem::size_of::<GetSensorReadingRequest>());
+        let decoded_request = GetSensorReadingRequest::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(request, decoded_request);
+    }
+
+    #[test]
+    fn test_get_sensor_reading_response() {
+        let reading = NumericSensorReading {
+            sensor_op_state: SensorOperationalState::Enabled,
+            event_message_enable: SensorEventMessageEnable::NoEventGeneration,
+            present_state: SensorState::Normal,
+            previous_state: SensorState::UpperWarning,
+            event_state: SensorState::Normal,
+            present_reading: SensorReading::Sint16(-40),
+        };
+        let response = GetSensorReadingResponse::new(0x01, 0, &reading);
+        let mut buffer = [0u8; 32];
+        let bytes = response.encode(&mut buffer).unwrap();
+        assert_eq!(
+            bytes,
+            core::mem::size_of::<GetSensorReadingResponseFixed>() + 2
+        );
+        let decoded_response = GetSensorReadingResponse::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(response, decoded_response);
+    }
+}
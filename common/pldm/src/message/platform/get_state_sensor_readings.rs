@@ -0,0 +1,179 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{PldmCodec, PldmCodecError};
+use crate::error::PldmError;
+use crate::protocol::base::{
+    InstanceId, PldmMsgHeader, PldmMsgType, PldmSupportedType, PLDM_MSG_HEADER_LEN,
+};
+use crate::protocol::platform::{PlatformCmd, StateField, MAX_COMPOSITE_SENSOR_COUNT};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetStateSensorReadingsRequest {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub sensor_id: u16,
+    pub sensor_rearm: u8,
+    pub reserved: u8,
+}
+
+impl GetStateSensorReadingsRequest {
+    pub fn new(
+        instance_id: InstanceId,
+        msg_type: PldmMsgType,
+        sensor_id: u16,
+        sensor_rearm: u8,
+    ) -> Self {
+        GetStateSensorReadingsRequest {
+            hdr: PldmMsgHeader::new(
+                instance_id,
+                msg_type,
+                PldmSupportedType::Platform,
+                PlatformCmd::GetStateSensorReadings as u8,
+            ),
+            sensor_id,
+            sensor_rearm,
+            reserved: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetStateSensorReadingsResponseFixed {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub completion_code: u8,
+    pub composite_sensor_count: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GetStateSensorReadingsResponse {
+    pub fixed: GetStateSensorReadingsResponseFixed,
+    pub state_fields: [StateField; MAX_COMPOSITE_SENSOR_COUNT],
+}
+
+impl GetStateSensorReadingsResponse {
+    pub fn new(
+        instance_id: InstanceId,
+        completion_code: u8,
+        state_fields: &[StateField],
+    ) -> Result<Self, PldmError> {
+        if state_fields.len() > MAX_COMPOSITE_SENSOR_COUNT {
+            return Err(PldmError::InvalidLength);
+        }
+
+        let mut fields = [StateField::default(); MAX_COMPOSITE_SENSOR_COUNT];
+        fields[..state_fields.len()].copy_from_slice(state_fields);
+        Ok(GetStateSensorReadingsResponse {
+            fixed: GetStateSensorReadingsResponseFixed {
+                hdr: PldmMsgHeader::new(
+                    instance_id,
+                    PldmMsgType::Response,
+                    PldmSupportedType::Platform,
+                    PlatformCmd::GetStateSensorReadings as u8,
+                ),
+                completion_code,
+                composite_sensor_count: state_fields.len() as u8,
+            },
+            state_fields: fields,
+        })
+    }
+
+    pub fn state_fields(&self) -> &[StateField] {
+        &self.state_fields[..self.fixed.composite_sensor_count as usize]
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        core::mem::size_of::<GetStateSensorReadingsResponseFixed>()
+            + self.fixed.composite_sensor_count as usize * core::mem::size_of::<StateField>()
+    }
+}
+
+impl PldmCodec for GetStateSensorReadingsResponse {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, PldmCodecError> {
+        if self.fixed.composite_sensor_count as usize > MAX_COMPOSITE_SENSOR_COUNT {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+        if buffer.len() < self.codec_size_in_bytes() {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+
+        let mut offset = 0;
+        let bytes = core::mem::size_of::<GetStateSensorReadingsResponseFixed>();
+        self.fixed
+            .write_to(&mut buffer[offset..offset + bytes])
+            .unwrap();
+        offset += bytes;
+
+        for field in self.state_fields() {
+            let bytes = field.encode(&mut buffer[offset..])?;
+            offset += bytes;
+        }
+        Ok(offset)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, PldmCodecError> {
+        let mut offset = 0;
+        let bytes = core::mem::size_of::<GetStateSensorReadingsResponseFixed>();
+        let fixed = GetStateSensorReadingsResponseFixed::read_from_bytes(
+            buffer
+                .get(offset..offset + bytes)
+                .ok_or(PldmCodecError::BufferTooShort)?,
+        )
+        .unwrap();
+        offset += bytes;
+
+        let count = fixed.composite_sensor_count as usize;
+        if count > MAX_COMPOSITE_SENSOR_COUNT {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+        let mut state_fields = [StateField::default(); MAX_COMPOSITE_SENSOR_COUNT];
+        for field in state_fields.iter_mut().take(count) {
+            *field =
+                StateField::decode(buffer.get(offset..).ok_or(PldmCodecError::BufferTooShort)?)?;
+            offset += core::mem::size_of::<StateField>();
+        }
+
+        Ok(GetStateSensorReadingsResponse {
+            fixed,
+            state_fields,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::protocol::platform::SensorOperationalState;
+
+    #[test]
+    fn test_get_state_sensor_readings_request() {
+        let request = GetStateSensorReadingsRequest::new(0x01, PldmMsgType::Request, 0x0020, 0);
+        let mut buffer = [0u8; 16];
+        let bytes = request.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, core::mem::size_of::<GetStateSensorReadingsRequest>());
+        let decoded_request = GetStateSensorReadingsRequest::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(request, decoded_request);
+    }
+
+    #[test]
+    fn test_get_state_sensor_readings_response() {
+        let fields = [
+            StateField::new(SensorOperationalState::Enabled, 1, 1, 1),
+            StateField::new(SensorOperationalState::Failed, 2, 1, 2),
+        ];
+        let response = GetStateSensorReadingsResponse::new(0x01, 0, &fields).unwrap();
+        let mut buffer = [0u8; 64];
+        let bytes = response.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, 5 + fields.len() * 4);
+        let decoded_response = GetStateSensorReadingsResponse::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(response, decoded_response);
+        assert_eq!(decoded_response.state_fields(), &fields);
+    }
+
+    #[test]
+    fn test_get_state_sensor_readings_response_too_many_fields() {
+        let fields = [StateField::default(); MAX_COMPOSITE_SENSOR_COUNT + 1];
+        assert!(GetStateSensorReadingsResponse::new(0x01, 0, &fields).is_err());
+    }
+}
@@ -0,0 +1,8 @@
+// Licensed under the Apache-2.0 license
+
+pub mod get_pdr;
+pub mod get_pdr_repo_info;
+pub mod get_sensor_reading;
+pub mod get_state_sensor_readings;
+pub mod platform_event_msg;
+pub mod poll_platform_event_msg;
@@ -0,0 +1,178 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{PldmCodec, PldmCodecError};
+use crate::error::PldmError;
+use crate::protocol::base::{
+    InstanceId, PldmMsgHeader, PldmMsgType, PldmSupportedType, PLDM_MSG_HEADER_LEN,
+};
+use crate::protocol::platform::{
+    PlatformCmd, PlatformEventClass, PlatformEventStatus, PLATFORM_EVENT_FORMAT_VERSION,
+};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+pub const MAX_EVENT_DATA_SIZE: usize = 128; // Arbitrary limit for static storage
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct PlatformEventMessageRequestFixed {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub format_version: u8,
+    pub tid: u8,
+    pub event_class: u8,
+}
+
+/// PlatformEventMessage request. The event data runs to the end of the message, so
+/// `decode` must be given a buffer trimmed to the received message length.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlatformEventMessageRequest {
+    pub fixed: PlatformEventMessageRequestFixed,
+    pub event_data_len: usize,
+    pub event_data: [u8; MAX_EVENT_DATA_SIZE],
+}
+
+impl PlatformEventMessageRequest {
+    pub fn new(
+        instance_id: InstanceId,
+        msg_type: PldmMsgType,
+        tid: u8,
+        event_class: PlatformEventClass,
+        event_data: &[u8],
+    ) -> Result<Self, PldmError> {
+        if event_data.len() > MAX_EVENT_DATA_SIZE {
+            return Err(PldmError::InvalidLength);
+        }
+
+        let mut data = [0u8; MAX_EVENT_DATA_SIZE];
+        data[..event_data.len()].copy_from_slice(event_data);
+        Ok(PlatformEventMessageRequest {
+            fixed: PlatformEventMessageRequestFixed {
+                hdr: PldmMsgHeader::new(
+                    instance_id,
+                    msg_type,
+                    PldmSupportedType::Platform,
+                    PlatformCmd::PlatformEventMessage as u8,
+                ),
+                format_version: PLATFORM_EVENT_FORMAT_VERSION,
+                tid,
+                event_class: event_class as u8,
+            },
+            event_data_len: event_data.len(),
+            event_data: data,
+        })
+    }
+
+    pub fn event_data(&self) -> &[u8] {
+        &self.event_data[..self.event_data_len]
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        core::mem::size_of::<PlatformEventMessageRequestFixed>() + self.event_data_len
+    }
+}
+
+impl PldmCodec for PlatformEventMessageRequest {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, PldmCodecError> {
+        if self.event_data_len > MAX_EVENT_DATA_SIZE {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+        if buffer.len() < self.codec_size_in_bytes() {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+
+        let mut offset = 0;
+        let bytes = core::mem::size_of::<PlatformEventMessageRequestFixed>();
+        self.fixed
+            .write_to(&mut buffer[offset..offset + bytes])
+            .unwrap();
+        offset += bytes;
+
+        buffer[offset..offset + self.event_data_len].copy_from_slice(self.event_data());
+        Ok(offset + self.event_data_len)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, PldmCodecError> {
+        let bytes = core::mem::size_of::<PlatformEventMessageRequestFixed>();
+        let fixed = PlatformEventMessageRequestFixed::read_from_bytes(
+            buffer.get(..bytes).ok_or(PldmCodecError::BufferTooShort)?,
+        )
+        .unwrap();
+
+        let data = &buffer[bytes..];
+        if data.len() > MAX_EVENT_DATA_SIZE {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+        let mut event_data = [0u8; MAX_EVENT_DATA_SIZE];
+        event_data[..data.len()].copy_from_slice(data);
+
+        Ok(PlatformEventMessageRequest {
+            fixed,
+            event_data_len: data.len(),
+            event_data,
+        })
+    }
+}
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct PlatformEventMessageResponse {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub completion_code: u8,
+    pub platform_event_status: u8,
+}
+
+impl PlatformEventMessageResponse {
+    pub fn new(
+        instance_id: InstanceId,
+        completion_code: u8,
+        platform_event_status: PlatformEventStatus,
+    ) -> Self {
+        PlatformEventMessageResponse {
+            hdr: PldmMsgHeader::new(
+                instance_id,
+                PldmMsgType::Response,
+                PldmSupportedType::Platform,
+                PlatformCmd::PlatformEventMessage as u8,
+            ),
+            completion_code,
+            platform_event_status: platform_event_status as u8,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_platform_event_message_request() {
+        // sensorID, sensorEventClass (stateSensorState), sensorOffset, eventState, previousEventState
+        let event_data = [0x20, 0x00, 0x01, 0x00, 0x02, 0x01];
+        let request = PlatformEventMessageRequest::new(
+            0x01,
+            PldmMsgType::Request,
+            0x02,
+            PlatformEventClass::SensorEvent,
+            &event_data,
+        )
+        .unwrap();
+        let mut buffer = [0u8; 64];
+        let bytes = request.encode(&mut buffer).unwrap();
+        assert_eq!(
+            bytes,
+            core::mem::size_of::<PlatformEventMessageRequestFixed>() + event_data.len()
+        );
+        let decoded_request = PlatformEventMessageRequest::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(request, decoded_request);
+        assert_eq!(decoded_request.event_data(), &event_data);
+    }
+
+    #[test]
+    fn test_platform_event_message_response() {
+        let response = PlatformEventMessageResponse::new(0x01, 0, PlatformEventStatus::NoLogging);
+        let mut buffer = [0u8; 16];
+        let bytes = response.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, core::mem::size_of::<PlatformEventMessageResponse>());
+        let decoded_response = PlatformEventMessageResponse::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(response, decoded_response);
+    }
+}
@@ -0,0 +1,306 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{PldmCodec, PldmCodecError};
+use crate::error::PldmError;
+use crate::message::platform::platform_event_msg::MAX_EVENT_DATA_SIZE;
+use crate::protocol::base::{
+    InstanceId, PldmMsgHeader, PldmMsgType, PldmSupportedType, TransferRespFlag,
+    PLDM_MSG_HEADER_LEN,
+};
+use crate::protocol::platform::{
+    PlatformCmd, PlatformEventClass, PollTransferOperationFlag, PLATFORM_EVENT_FORMAT_VERSION,
+    POLL_EVENT_ID_ACK, POLL_EVENT_ID_NONE,
+};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct PollForPlatformEventMessageRequest {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub format_version: u8,
+    pub transfer_op_flag: u8,
+    pub data_transfer_handle: u32,
+    pub event_id_to_ack: u16,
+}
+
+impl PollForPlatformEventMessageRequest {
+    pub fn new(
+        instance_id: InstanceId,
+        msg_type: PldmMsgType,
+        transfer_op_flag: PollTransferOperationFlag,
+        data_transfer_handle: u32,
+        event_id_to_ack: u16,
+    ) -> Self {
+        PollForPlatformEventMessageRequest {
+            hdr: PldmMsgHeader::new(
+                instance_id,
+                msg_type,
+                PldmSupportedType::Platform,
+                PlatformCmd::PollForPlatformEventMessage as u8,
+            ),
+            format_version: PLATFORM_EVENT_FORMAT_VERSION,
+            transfer_op_flag: transfer_op_flag as u8,
+            data_transfer_handle,
+            event_id_to_ack,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct PollForPlatformEventMessageResponseFixed {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub completion_code: u8,
+    pub tid: u8,
+    pub event_id: u16,
+}
+
+#[derive(Debug, Clone, Default, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct PolledEventDataHeader {
+    pub next_data_transfer_handle: u32,
+    pub transfer_flag: u8,
+    pub event_class: u8,
+    pub event_data_size: u32,
+}
+
+/// PollForPlatformEventMessage response.
+///
+/// The event data header and event data are only present on the wire when `event_id` refers
+/// to an event, i.e. it is neither `POLL_EVENT_ID_NONE` nor `POLL_EVENT_ID_ACK`. The
+/// `event_data_checksum` is only present when the transfer flag is `End`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PollForPlatformEventMessageResponse {
+    pub fixed: PollForPlatformEventMessageResponseFixed,
+    pub event_hdr: PolledEventDataHeader,
+    pub event_data: [u8; MAX_EVENT_DATA_SIZE],
+    pub event_data_checksum: u32,
+}
+
+impl PollForPlatformEventMessageResponse {
+    /// Creates a response that carries no event data.
+    pub fn new(instance_id: InstanceId, completion_code: u8, tid: u8, event_id: u16) -> Self {
+        PollForPlatformEventMessageResponse {
+            fixed: PollForPlatformEventMessageResponseFixed {
+                hdr: PldmMsgHeader::new(
+                    instance_id,
+                    PldmMsgType::Response,
+                    PldmSupportedType::Platform,
+                    PlatformCmd::PollForPlatformEventMessage as u8,
+                ),
+                completion_code,
+                tid,
+                event_id,
+            },
+            event_hdr: PolledEventDataHeader::default(),
+            event_data: [0u8; MAX_EVENT_DATA_SIZE],
+            event_data_checksum: 0,
+        }
+    }
+
+    /// Attaches a portion of the event data to the response.
+    pub fn with_event_data(
+        mut self,
+        next_data_transfer_handle: u32,
+        transfer_flag: TransferRespFlag,
+        event_class: PlatformEventClass,
+        event_data: &[u8],
+        event_data_checksum: u32,
+    ) -> Result<Self, PldmError> {
+        if event_data.len() > MAX_EVENT_DATA_SIZE {
+            return Err(PldmError::InvalidLength);
+        }
+
+        self.event_hdr = PolledEventDataHeader {
+            next_data_transfer_handle,
+            transfer_flag: transfer_flag as u8,
+            event_class: event_class as u8,
+            event_data_size: event_data.len() as u32,
+        };
+        self.event_data[..event_data.len()].copy_from_slice(event_data);
+        self.event_data_checksum = event_data_checksum;
+        Ok(self)
+    }
+
+    pub fn has_event_data(&self) -> bool {
+        let event_id = self.fixed.event_id;
+        event_id != POLL_EVENT_ID_NONE && event_id != POLL_EVENT_ID_ACK
+    }
+
+    pub fn event_data(&self) -> &[u8] {
+        &self.event_data[..self.event_hdr.event_data_size as usize]
+    }
+
+    fn has_checksum(&self) -> bool {
+        self.event_hdr.transfer_flag == TransferRespFlag::End as u8
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        let mut bytes = core::mem::size_of::<PollForPlatformEventMessageResponseFixed>();
+        if self.has_event_data() {
+            bytes += core::mem::size_of::<PolledEventDataHeader>();
+            bytes += self.event_hdr.event_data_size as usize;
+            if self.has_checksum() {
+                bytes += core::mem::size_of::<u32>();
+            }
+        }
+        bytes
+    }
+}
+
+impl PldmCodec for PollForPlatformEventMessageResponse {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, PldmCodecError> {
+        if self.event_hdr.event_data_size as usize > MAX_EVENT_DATA_SIZE {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+        if buffer.len() < self.codec_size_in_bytes() {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+
+        let mut offset = 0;
+        let bytes = core::mem::size_of::<PollForPlatformEventMessageResponseFixed>();
+        self.fixed
+            .write_to(&mut buffer[offset..offset + bytes])
+            .unwrap();
+        offset += bytes;
+
+        if !self.has_event_data() {
+            return Ok(offset);
+        }
+
+        let bytes = core::mem::size_of::<PolledEventDataHeader>();
+        self.event_hdr
+            .write_to(&mut buffer[offset..offset + bytes])
+            .unwrap();
+        offset += bytes;
+
+        let data_len = self.event_hdr.event_data_size as usize;
+        buffer[offset..offset + data_len].copy_from_slice(self.event_data());
+        offset += data_len;
+
+        if self.has_checksum() {
+            self.event_data_checksum
+                .write_to(&mut buffer[offset..offset + core::mem::size_of::<u32>()])
+                .unwrap();
+            offset += core::mem::size_of::<u32>();
+        }
+        Ok(offset)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, PldmCodecError> {
+        let mut offset = 0;
+        let bytes = core::mem::size_of::<PollForPlatformEventMessageResponseFixed>();
+        let fixed = PollForPlatformEventMessageResponseFixed::read_from_bytes(
+            buffer
+                .get(offset..offset + bytes)
+                .ok_or(PldmCodecError::BufferTooShort)?,
+        )
+        .unwrap();
+        offset += bytes;
+
+        let mut response = PollForPlatformEventMessageResponse {
+            fixed,
+            event_hdr: PolledEventDataHeader::default(),
+            event_data: [0u8; MAX_EVENT_DATA_SIZE],
+            event_data_checksum: 0,
+        };
+        if !response.has_event_data() {
+            return Ok(response);
+        }
+
+        let bytes = core::mem::size_of::<PolledEventDataHeader>();
+        response.event_hdr = PolledEventDataHeader::read_from_bytes(
+            buffer
+                .get(offset..offset + bytes)
+                .ok_or(PldmCodecError::BufferTooShort)?,
+        )
+        .unwrap();
+        offset += bytes;
+
+        let data_len = response.event_hdr.event_data_size as usize;
+        if data_len > MAX_EVENT_DATA_SIZE {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+        response.event_data[..data_len].copy_from_slice(
+            buffer
+                .get(offset..offset + data_len)
+                .ok_or(PldmCodecError::BufferTooShort)?,
+        );
+        offset += data_len;
+
+        if response.has_checksum() {
+            response.event_data_checksum = u32::read_from_bytes(
+                buffer
+                    .get(offset..offset + core::mem::size_of::<u32>())
+                    .ok_or(PldmCodecError::BufferTooShort)?,
+            )
+            .unwrap();
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_poll_for_platform_event_message_request() {
+        let request = PollForPlatformEventMessageRequest::new(
+            0x01,
+            PldmMsgType::Request,
+            PollTransferOperationFlag::AcknowledgementOnly,
+            0,
+            0x0007,
+        );
+        let mut buffer = [0u8; 16];
+        let bytes = request.encode(&mut buffer).unwrap();
+        assert_eq!(
+            bytes,
+            core::mem::size_of::<PollForPlatformEventMessageRequest>()
+        );
+        let decoded_request = PollForPlatformEventMessageRequest::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(request, decoded_request);
+    }
+
+    #[test]
+    fn test_poll_for_platform_event_message_response_no_event() {
+        let response = PollForPlatformEventMessageResponse::new(0x01, 0, 0x02, POLL_EVENT_ID_NONE);
+        let mut buffer = [0u8; 64];
+        let bytes = response.encode(&mut buffer).unwrap();
+        assert_eq!(
+            bytes,
+            core::mem::size_of::<PollForPlatformEventMessageResponseFixed>()
+        );
+        let decoded_response =
+            PollForPlatformEventMessageResponse::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(response, decoded_response);
+    }
+
+    #[test]
+    fn test_poll_for_platform_event_message_response_with_event() {
+        let event_data = [0x10, 0x00, 0x00, 0x03, 0x01];
+        let response = PollForPlatformEventMessageResponse::new(0x01, 0, 0x02, 0x0007)
+            .with_event_data(
+                0,
+                TransferRespFlag::End,
+                PlatformEventClass::SensorEvent,
+                &event_data,
+                0xdead_beef,
+            )
+            .unwrap();
+        let mut buffer = [0u8; 64];
+        let bytes = response.encode(&mut buffer).unwrap();
+        assert_eq!(
+            bytes,
+            core::mem::size_of::<PollForPlatformEventMessageResponseFixed>()
+                + core::mem::size_of::<PolledEventDataHeader>()
+                + event_data.len()
+                + core::mem::size_of::<u32>()
+        );
+        let decoded_response =
+            PollForPlatformEventMessageResponse::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(response, decoded_response);
+        assert_eq!(decoded_response.event_data(), &event_data);
+    }
+}
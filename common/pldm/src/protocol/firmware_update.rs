@@ -14,23 +14,32 @@ pub const PLDM_FWUP_IMAGE_SET_VER_STR_MAX_LEN: usize = 32;
 pub const DESCRIPTOR_DATA_MAX_LEN: usize = 64; // Arbitrary limit for static storage
 pub const MAX_COMPONENT_COUNT: usize = 8; // Arbitrary limit, change as needed
 pub const MAX_DESCRIPTORS_COUNT: usize = 4; // Arbitrary limit, change as needed
+pub const PLDM_FWUP_MAX_DATA_PORTION_SIZE: usize = 256; // Arbitrary limit for static storage
+pub const MAX_DOWNSTREAM_DEVICE_COUNT: usize = 4; // Arbitrary limit, change as needed
 pub type PldmFdTime = u64; // Monotonic timestamp in milliseconds
 
 #[repr(u8)]
 pub enum FwUpdateCmd {
     QueryDeviceIdentifiers = 0x01,
     GetFirmwareParameters = 0x02,
+    QueryDownstreamDevices = 0x03,
+    QueryDownstreamIdentifiers = 0x04,
+    GetDownstreamFirmwareParameters = 0x05,
     RequestUpdate = 0x10,
+    GetPackageData = 0x11,
+    GetDeviceMetaData = 0x12,
     PassComponentTable = 0x13,
     UpdateComponent = 0x14,
     RequestFirmwareData = 0x15,
     TransferComplete = 0x16,
     VerifyComplete = 0x17,
     ApplyComplete = 0x18,
+    GetMetaData = 0x19,
     ActivateFirmware = 0x1A,
     GetStatus = 0x1B,
     CancelUpdateComponent = 0x1C,
     CancelUpdate = 0x1D,
+    RequestDownstreamDeviceUpdate = 0x20,
 }
 
 impl TryFrom<u8> for FwUpdateCmd {
@@ -40,17 +49,24 @@ impl TryFrom<u8> for FwUpdateCmd {
         match value {
             0x01 => Ok(FwUpdateCmd::QueryDeviceIdentifiers),
             0x02 => Ok(FwUpdateCmd::GetFirmwareParameters),
+            0x03 => Ok(FwUpdateCmd::QueryDownstreamDevices),
+            0x04 => Ok(FwUpdateCmd::QueryDownstreamIdentifiers),
+            0x05 => Ok(FwUpdateCmd::GetDownstreamFirmwareParameters),
             0x10 => Ok(FwUpdateCmd::RequestUpdate),
+            0x11 => Ok(FwUpdateCmd::GetPackageData),
+            0x12 => Ok(FwUpdateCmd::GetDeviceMetaData),
             0x13 => Ok(FwUpdateCmd::PassComponentTable),
             0x14 => Ok(FwUpdateCmd::UpdateComponent),
             0x15 => Ok(FwUpdateCmd::RequestFirmwareData),
             0x16 => Ok(FwUpdateCmd::TransferComplete),
             0x17 => Ok(FwUpdateCmd::VerifyComplete),
             0x18 => Ok(FwUpdateCmd::ApplyComplete),
+            0x19 => Ok(FwUpdateCmd::GetMetaData),
             0x1A => Ok(FwUpdateCmd::ActivateFirmware),
             0x1B => Ok(FwUpdateCmd::GetStatus),
             0x1C => Ok(FwUpdateCmd::CancelUpdateComponent),
             0x1D => Ok(FwUpdateCmd::CancelUpdate),
+            0x20 => Ok(FwUpdateCmd::RequestDownstreamDeviceUpdate),
             _ => Err(PldmError::UnsupportedCmd),
         }
     }
@@ -721,6 +737,186 @@ impl PldmCodec for ComponentParameterEntry {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, FromBytes, IntoBytes, Immutable, Copy)]
+#[repr(C, packed)]
+pub struct DownstreamDeviceParameterEntryFixed {
+    pub downstream_device_index: u16,
+    pub active_comp_comparison_stamp: u32,
+    pub active_comp_ver_str_type: u8,
+    pub active_comp_ver_str_len: u8,
+    pub active_comp_release_date: [u8; PLDM_FWUP_COMPONENT_RELEASE_DATA_LEN],
+    pub pending_comp_comparison_stamp: u32,
+    pub pending_comp_ver_str_type: u8,
+    pub pending_comp_ver_str_len: u8,
+    pub pending_comp_release_date: [u8; PLDM_FWUP_COMPONENT_RELEASE_DATA_LEN],
+    pub comp_activation_methods: ComponentActivationMethods,
+    pub capabilities_during_update: FirmwareDeviceCapability,
+}
+
+// An entry of the DownstreamDeviceParameterTable returned by GetDownstreamFirmwareParameters
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[repr(C)]
+pub struct DownstreamDeviceParameterEntry {
+    pub entry_fixed: DownstreamDeviceParameterEntryFixed,
+    pub active_comp_ver_str: [u8; PLDM_FWUP_IMAGE_SET_VER_STR_MAX_LEN],
+    pub pending_comp_ver_str: Option<[u8; PLDM_FWUP_IMAGE_SET_VER_STR_MAX_LEN]>,
+}
+
+impl Default for DownstreamDeviceParameterEntry {
+    fn default() -> Self {
+        DownstreamDeviceParameterEntry {
+            entry_fixed: DownstreamDeviceParameterEntryFixed {
+                downstream_device_index: 0,
+                active_comp_comparison_stamp: 0,
+                active_comp_ver_str_type: 0,
+                active_comp_ver_str_len: 0,
+                active_comp_release_date: [0; PLDM_FWUP_COMPONENT_RELEASE_DATA_LEN],
+                pending_comp_comparison_stamp: 0,
+                pending_comp_ver_str_type: 0,
+                pending_comp_ver_str_len: 0,
+                pending_comp_release_date: [0; PLDM_FWUP_COMPONENT_RELEASE_DATA_LEN],
+                comp_activation_methods: ComponentActivationMethods(0),
+                capabilities_during_update: FirmwareDeviceCapability(0),
+            },
+            active_comp_ver_str: [0; PLDM_FWUP_IMAGE_SET_VER_STR_MAX_LEN],
+            pending_comp_ver_str: None,
+        }
+    }
+}
+
+impl DownstreamDeviceParameterEntry {
+    pub fn new(
+        downstream_device_index: u16,
+        active_firmware_version: &PldmFirmwareVersion,
+        pending_firmware_version: &PldmFirmwareVersion,
+        comp_activation_methods: ComponentActivationMethods,
+        capabilities_during_update: FirmwareDeviceCapability,
+    ) -> Self {
+        DownstreamDeviceParameterEntry {
+            entry_fixed: DownstreamDeviceParameterEntryFixed {
+                downstream_device_index,
+                active_comp_comparison_stamp: active_firmware_version.comparison_stamp,
+                active_comp_ver_str_type: active_firmware_version.str.str_type,
+                active_comp_ver_str_len: active_firmware_version.str.str_len,
+                active_comp_release_date: active_firmware_version.date,
+                pending_comp_comparison_stamp: pending_firmware_version.comparison_stamp,
+                pending_comp_ver_str_type: pending_firmware_version.str.str_type,
+                pending_comp_ver_str_len: pending_firmware_version.str.str_len,
+                pending_comp_release_date: pending_firmware_version.date,
+                comp_activation_methods,
+                capabilities_during_update,
+            },
+            active_comp_ver_str: {
+                let mut arr = [0u8; PLDM_FWUP_IMAGE_SET_VER_STR_MAX_LEN];
+                let len = active_firmware_version.str.str_len as usize;
+                arr[..len].copy_from_slice(&active_firmware_version.str.str_data[..len]);
+                arr
+            },
+            pending_comp_ver_str: {
+                if pending_firmware_version.str.str_len > 0 {
+                    let mut arr = [0u8; PLDM_FWUP_IMAGE_SET_VER_STR_MAX_LEN];
+                    let len = pending_firmware_version.str.str_len as usize;
+                    arr[..len].copy_from_slice(&pending_firmware_version.str.str_data[..len]);
+                    Some(arr)
+                } else {
+                    None
+                }
+            },
+        }
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        let mut bytes = core::mem::size_of::<DownstreamDeviceParameterEntryFixed>();
+        bytes += self.entry_fixed.active_comp_ver_str_len as usize;
+        if self.pending_comp_ver_str.is_some() {
+            bytes += self.entry_fixed.pending_comp_ver_str_len as usize;
+        }
+        bytes
+    }
+
+    pub fn get_active_fw_ver(&self) -> PldmFirmwareString {
+        PldmFirmwareString {
+            str_type: self.entry_fixed.active_comp_ver_str_type,
+            str_len: self.entry_fixed.active_comp_ver_str_len,
+            str_data: self.active_comp_ver_str,
+        }
+    }
+}
+
+impl PldmCodec for DownstreamDeviceParameterEntry {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, PldmCodecError> {
+        if buffer.len() < self.codec_size_in_bytes() {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+        let mut offset = 0;
+
+        let bytes = core::mem::size_of::<DownstreamDeviceParameterEntryFixed>();
+        self.entry_fixed
+            .write_to(&mut buffer[offset..offset + bytes])
+            .unwrap();
+        offset += bytes;
+
+        let len = self.entry_fixed.active_comp_ver_str_len as usize;
+        buffer[offset..offset + len].copy_from_slice(&self.active_comp_ver_str[..len]);
+        offset += len;
+
+        if let Some(pending_comp_ver_str) = &self.pending_comp_ver_str {
+            let len = self.entry_fixed.pending_comp_ver_str_len as usize;
+            buffer[offset..offset + len].copy_from_slice(&pending_comp_ver_str[..len]);
+            offset += len;
+        }
+
+        Ok(offset)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, PldmCodecError> {
+        let mut offset = 0;
+
+        let bytes = core::mem::size_of::<DownstreamDeviceParameterEntryFixed>();
+        let entry_fixed = DownstreamDeviceParameterEntryFixed::read_from_bytes(
+            buffer
+                .get(offset..offset + bytes)
+                .ok_or(PldmCodecError::BufferTooShort)?,
+        )
+        .unwrap();
+        offset += bytes;
+
+        let len = entry_fixed.active_comp_ver_str_len as usize;
+        if len > PLDM_FWUP_IMAGE_SET_VER_STR_MAX_LEN {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+        let mut active_comp_ver_str = [0u8; PLDM_FWUP_IMAGE_SET_VER_STR_MAX_LEN];
+        active_comp_ver_str[..len].copy_from_slice(
+            buffer
+                .get(offset..offset + len)
+                .ok_or(PldmCodecError::BufferTooShort)?,
+        );
+        offset += len;
+
+        let pending_comp_ver_str = if entry_fixed.pending_comp_ver_str_len > 0 {
+            let len = entry_fixed.pending_comp_ver_str_len as usize;
+            if len > PLDM_FWUP_IMAGE_SET_VER_STR_MAX_LEN {
+                return Err(PldmCodecError::BufferTooShort);
+            }
+            let mut arr = [0u8; PLDM_FWUP_IMAGE_SET_VER_STR_MAX_LEN];
+            arr[..len].copy_from_slice(
+                buffer
+                    .get(offset..offset + len)
+                    .ok_or(PldmCodecError::BufferTooShort)?,
+            );
+            Some(arr)
+        } else {
+            None
+        };
+
+        Ok(DownstreamDeviceParameterEntry {
+            entry_fixed,
+            active_comp_ver_str,
+            pending_comp_ver_str,
+        })
+    }
+}
+
 #[repr(u8)]
 #[derive(Debug, Clone, Copy)]
 pub enum ComponentResponse {
@@ -0,0 +1,499 @@
+// Licensed under the Apache-2.0 license
+
+use crate::error::PldmError;
+use core::convert::TryFrom;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+pub const PLDM_TIMESTAMP104_LEN: usize = 13;
+pub const PDR_HEADER_VERSION: u8 = 0x01;
+pub const PLATFORM_EVENT_FORMAT_VERSION: u8 = 0x01;
+pub const MAX_COMPOSITE_SENSOR_COUNT: usize = 8;
+
+/// PollForPlatformEventMessage eventID reported when no event is queued.
+pub const POLL_EVENT_ID_NONE: u16 = 0x0000;
+/// PollForPlatformEventMessage eventID reported in response to an acknowledgement-only request.
+pub const POLL_EVENT_ID_ACK: u16 = 0xFFFF;
+
+/// Timestamp104 as defined in DSP0240: UTC offset, microseconds, second, minute, hour,
+/// day, month, year and UTC resolution packed into 13 bytes.
+pub type Timestamp104 = [u8; PLDM_TIMESTAMP104_LEN];
+
+// GetPDR transferCRC uses CRC-8 with polynomial x^8 + x^2 + x + 1.
+const PDR_TRANSFER_CRC8: crc::Crc<u8> = crc::Crc::<u8>::new(&crc::CRC_8_SMBUS);
+// PollForPlatformEventMessage eventDataIntegrityChecksum uses CRC-32 (ISO 3309).
+const EVENT_DATA_CRC32: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
+
+/// Computes the transferCRC over a complete PDR record for a multipart GetPDR transfer.
+pub fn pdr_transfer_crc(record: &[u8]) -> u8 {
+    PDR_TRANSFER_CRC8.checksum(record)
+}
+
+/// Computes the eventDataIntegrityChecksum over the complete event data of a multipart
+/// PollForPlatformEventMessage transfer.
+pub fn event_data_checksum(event_data: &[u8]) -> u32 {
+    EVENT_DATA_CRC32.checksum(event_data)
+}
+
+#[repr(u8)]
+pub enum PlatformCmd {
+    PlatformEventMessage = 0x0A,
+    PollForPlatformEventMessage = 0x0B,
+    GetSensorReading = 0x11,
+    GetStateSensorReadings = 0x21,
+    GetPdrRepositoryInfo = 0x50,
+    GetPdr = 0x51,
+}
+
+impl TryFrom<u8> for PlatformCmd {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0x0A => Ok(PlatformCmd::PlatformEventMessage),
+            0x0B => Ok(PlatformCmd::PollForPlatformEventMessage),
+            0x11 => Ok(PlatformCmd::GetSensorReading),
+            0x21 => Ok(PlatformCmd::GetStateSensorReadings),
+            0x50 => Ok(PlatformCmd::GetPdrRepositoryInfo),
+            0x51 => Ok(PlatformCmd::GetPdr),
+            _ => Err(PldmError::UnsupportedCmd),
+        }
+    }
+}
+
+/// Command specific completion codes for GetPDR.
+#[repr(u8)]
+pub enum GetPdrCompletionCode {
+    InvalidDataTransferHandle = 0x80,
+    InvalidTransferOperationFlag = 0x81,
+    InvalidRecordHandle = 0x82,
+    InvalidRecordChangeNumber = 0x83,
+    TransferTimeout = 0x84,
+    RepositoryUpdateInProgress = 0x85,
+}
+
+/// Command specific completion codes for GetSensorReading and GetStateSensorReadings.
+#[repr(u8)]
+pub enum SensorCompletionCode {
+    InvalidSensorId = 0x80,
+    RearmUnavailableInPresentState = 0x81,
+}
+
+/// Command specific completion codes for PlatformEventMessage.
+#[repr(u8)]
+pub enum PlatformEventCompletionCode {
+    InvalidProtocolType = 0x80,
+    UnsupportedEventFormatVersion = 0x81,
+}
+
+/// Command specific completion codes for PollForPlatformEventMessage.
+#[repr(u8)]
+pub enum PollEventCompletionCode {
+    InvalidDataTransferHandle = 0x80,
+    InvalidTransferOperationFlag = 0x81,
+    EventIdNotValid = 0x82,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum PdrRepositoryState {
+    Available = 0,
+    UpdateInProgress = 1,
+    Failed = 2,
+}
+
+impl TryFrom<u8> for PdrRepositoryState {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0 => Ok(PdrRepositoryState::Available),
+            1 => Ok(PdrRepositoryState::UpdateInProgress),
+            2 => Ok(PdrRepositoryState::Failed),
+            _ => Err(PldmError::InvalidData),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum PdrType {
+    TerminusLocator = 1,
+    NumericSensor = 2,
+    NumericSensorInitialization = 3,
+    StateSensor = 4,
+    StateSensorInitialization = 5,
+    SensorAuxiliaryNames = 6,
+    OemUnitNames = 7,
+    OemStateSet = 8,
+    NumericEffecter = 9,
+    NumericEffecterInitialization = 10,
+    StateEffecter = 11,
+    StateEffecterInitialization = 12,
+    EffecterAuxiliaryNames = 13,
+    EffecterOemSemantic = 14,
+    EntityAssociation = 15,
+    EntityAuxiliaryNames = 16,
+    OemEntityIdAssociation = 17,
+    InterruptAssociation = 18,
+    EventLog = 19,
+    FruRecordSet = 20,
+    CompactNumericSensor = 21,
+    Oem = 127,
+}
+
+impl TryFrom<u8> for PdrType {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            1 => Ok(PdrType::TerminusLocator),
+            2 => Ok(PdrType::NumericSensor),
+            3 => Ok(PdrType::NumericSensorInitialization),
+            4 => Ok(PdrType::StateSensor),
+            5 => Ok(PdrType::StateSensorInitialization),
+            6 => Ok(PdrType::SensorAuxiliaryNames),
+            7 => Ok(PdrType::OemUnitNames),
+            8 => Ok(PdrType::OemStateSet),
+            9 => Ok(PdrType::NumericEffecter),
+            10 => Ok(PdrType::NumericEffecterInitialization),
+            11 => Ok(PdrType::StateEffecter),
+            12 => Ok(PdrType::StateEffecterInitialization),
+            13 => Ok(PdrType::EffecterAuxiliaryNames),
+            14 => Ok(PdrType::EffecterOemSemantic),
+            15 => Ok(PdrType::EntityAssociation),
+            16 => Ok(PdrType::EntityAuxiliaryNames),
+            17 => Ok(PdrType::OemEntityIdAssociation),
+            18 => Ok(PdrType::InterruptAssociation),
+            19 => Ok(PdrType::EventLog),
+            20 => Ok(PdrType::FruRecordSet),
+            21 => Ok(PdrType::CompactNumericSensor),
+            127 => Ok(PdrType::Oem),
+            _ => Err(PldmError::InvalidPdrType),
+        }
+    }
+}
+
+/// Common header carried by every PDR record.
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct PdrHeader {
+    pub record_handle: u32,
+    pub header_version: u8,
+    pub pdr_type: u8,
+    pub record_change_number: u16,
+    pub data_length: u16,
+}
+
+impl PdrHeader {
+    pub fn new(
+        record_handle: u32,
+        pdr_type: PdrType,
+        record_change_number: u16,
+        data_length: u16,
+    ) -> Self {
+        PdrHeader {
+            record_handle,
+            header_version: PDR_HEADER_VERSION,
+            pdr_type: pdr_type as u8,
+            record_change_number,
+            data_length,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum SensorDataSize {
+    Uint8 = 0,
+    Sint8 = 1,
+    Uint16 = 2,
+    Sint16 = 3,
+    Uint32 = 4,
+    Sint32 = 5,
+}
+
+impl SensorDataSize {
+    pub fn size_in_bytes(&self) -> usize {
+        match self {
+            SensorDataSize::Uint8 | SensorDataSize::Sint8 => 1,
+            SensorDataSize::Uint16 | SensorDataSize::Sint16 => 2,
+            SensorDataSize::Uint32 | SensorDataSize::Sint32 => 4,
+        }
+    }
+}
+
+impl TryFrom<u8> for SensorDataSize {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0 => Ok(SensorDataSize::Uint8),
+            1 => Ok(SensorDataSize::Sint8),
+            2 => Ok(SensorDataSize::Uint16),
+            3 => Ok(SensorDataSize::Sint16),
+            4 => Ok(SensorDataSize::Uint32),
+            5 => Ok(SensorDataSize::Sint32),
+            _ => Err(PldmError::InvalidSensorDataSize),
+        }
+    }
+}
+
+/// Numeric sensor reading, sized according to the sensor's `sensorDataSize`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SensorReading {
+    Uint8(u8),
+    Sint8(i8),
+    Uint16(u16),
+    Sint16(i16),
+    Uint32(u32),
+    Sint32(i32),
+}
+
+impl SensorReading {
+    pub fn data_size(&self) -> SensorDataSize {
+        match self {
+            SensorReading::Uint8(_) => SensorDataSize::Uint8,
+            SensorReading::Sint8(_) => SensorDataSize::Sint8,
+            SensorReading::Uint16(_) => SensorDataSize::Uint16,
+            SensorReading::Sint16(_) => SensorDataSize::Sint16,
+            SensorReading::Uint32(_) => SensorDataSize::Uint32,
+            SensorReading::Sint32(_) => SensorDataSize::Sint32,
+        }
+    }
+
+    /// Writes the little-endian reading into `buffer` and returns the number of bytes written.
+    pub fn write_le(&self, buffer: &mut [u8]) -> Result<usize, PldmError> {
+        let len = self.data_size().size_in_bytes();
+        let dst = buffer.get_mut(..len).ok_or(PldmError::InvalidLength)?;
+        match *self {
+            SensorReading::Uint8(v) => dst.copy_from_slice(&v.to_le_bytes()),
+            SensorReading::Sint8(v) => dst.copy_from_slice(&v.to_le_bytes()),
+            SensorReading::Uint16(v) => dst.copy_from_slice(&v.to_le_bytes()),
+            SensorReading::Sint16(v) => dst.copy_from_slice(&v.to_le_bytes()),
+            SensorReading::Uint32(v) => dst.copy_from_slice(&v.to_le_bytes()),
+            SensorReading::Sint32(v) => dst.copy_from_slice(&v.to_le_bytes()),
+        }
+        Ok(len)
+    }
+
+    pub fn read_le(data_size: SensorDataSize, buffer: &[u8]) -> Result<Self, PldmError> {
+        let src = buffer
+            .get(..data_size.size_in_bytes())
+            .ok_or(PldmError::InvalidLength)?;
+        Ok(match data_size {
+            SensorDataSize::Uint8 => SensorReading::Uint8(src[0]),
+            SensorDataSize::Sint8 => SensorReading::Sint8(src[0] as i8),
+            SensorDataSize::Uint16 => SensorReading::Uint16(u16::from_le_bytes([src[0], src[1]])),
+            SensorDataSize::Sint16 => SensorReading::Sint16(i16::from_le_bytes([src[0], src[1]])),
+            SensorDataSize::Uint32 => {
+                SensorReading::Uint32(u32::from_le_bytes([src[0], src[1], src[2], src[3]]))
+            }
+            SensorDataSize::Sint32 => {
+                SensorReading::Sint32(i32::from_le_bytes([src[0], src[1], src[2], src[3]]))
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum SensorOperationalState {
+    Enabled = 0,
+    Disabled = 1,
+    Unavailable = 2,
+    StatusUnknown = 3,
+    Failed = 4,
+    Initializing = 5,
+    ShuttingDown = 6,
+    InTest = 7,
+}
+
+impl TryFrom<u8> for SensorOperationalState {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0 => Ok(SensorOperationalState::Enabled),
+            1 => Ok(SensorOperationalState::Disabled),
+            2 => Ok(SensorOperationalState::Unavailable),
+            3 => Ok(SensorOperationalState::StatusUnknown),
+            4 => Ok(SensorOperationalState::Failed),
+            5 => Ok(SensorOperationalState::Initializing),
+            6 => Ok(SensorOperationalState::ShuttingDown),
+            7 => Ok(SensorOperationalState::InTest),
+            _ => Err(PldmError::InvalidSensorState),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum SensorEventMessageEnable {
+    NoEventGeneration = 0,
+    EventsDisabled = 1,
+    EventsEnabled = 2,
+    OpEventsOnlyEnabled = 3,
+    StateEventsOnlyEnabled = 4,
+}
+
+impl TryFrom<u8> for SensorEventMessageEnable {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0 => Ok(SensorEventMessageEnable::NoEventGeneration),
+            1 => Ok(SensorEventMessageEnable::EventsDisabled),
+            2 => Ok(SensorEventMessageEnable::EventsEnabled),
+            3 => Ok(SensorEventMessageEnable::OpEventsOnlyEnabled),
+            4 => Ok(SensorEventMessageEnable::StateEventsOnlyEnabled),
+            _ => Err(PldmError::InvalidSensorState),
+        }
+    }
+}
+
+/// Numeric sensor threshold state reported in presentState, previousState and eventState.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum SensorState {
+    Unknown = 0,
+    Normal = 1,
+    Warning = 2,
+    Critical = 3,
+    Fatal = 4,
+    LowerWarning = 5,
+    LowerCritical = 6,
+    LowerFatal = 7,
+    UpperWarning = 8,
+    UpperCritical = 9,
+    UpperFatal = 10,
+}
+
+impl TryFrom<u8> for SensorState {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0 => Ok(SensorState::Unknown),
+            1 => Ok(SensorState::Normal),
+            2 => Ok(SensorState::Warning),
+            3 => Ok(SensorState::Critical),
+            4 => Ok(SensorState::Fatal),
+            5 => Ok(SensorState::LowerWarning),
+            6 => Ok(SensorState::LowerCritical),
+            7 => Ok(SensorState::LowerFatal),
+            8 => Ok(SensorState::UpperWarning),
+            9 => Ok(SensorState::UpperCritical),
+            10 => Ok(SensorState::UpperFatal),
+            _ => Err(PldmError::InvalidSensorState),
+        }
+    }
+}
+
+/// Per-sensor state field returned by GetStateSensorReadings.
+#[derive(Debug, Clone, Copy, Default, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct StateField {
+    pub sensor_op_state: u8,
+    pub present_state: u8,
+    pub previous_state: u8,
+    pub event_state: u8,
+}
+
+impl StateField {
+    pub fn new(
+        sensor_op_state: SensorOperationalState,
+        present_state: u8,
+        previous_state: u8,
+        event_state: u8,
+    ) -> Self {
+        StateField {
+            sensor_op_state: sensor_op_state as u8,
+            present_state,
+            previous_state,
+            event_state,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum PlatformEventClass {
+    SensorEvent = 0x00,
+    EffecterEvent = 0x01,
+    RedfishTaskExecutedEvent = 0x02,
+    RedfishMessageEvent = 0x03,
+    PldmMessagePollEvent = 0x04,
+    CperEvent = 0x07,
+    PdrRepositoryChgEvent = 0x20,
+    MessagePollEvent = 0x21,
+    HeartbeatTimerElapsedEvent = 0x22,
+}
+
+impl TryFrom<u8> for PlatformEventClass {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0x00 => Ok(PlatformEventClass::SensorEvent),
+            0x01 => Ok(PlatformEventClass::EffecterEvent),
+            0x02 => Ok(PlatformEventClass::RedfishTaskExecutedEvent),
+            0x03 => Ok(PlatformEventClass::RedfishMessageEvent),
+            0x04 => Ok(PlatformEventClass::PldmMessagePollEvent),
+            0x07 => Ok(PlatformEventClass::CperEvent),
+            0x20 => Ok(PlatformEventClass::PdrRepositoryChgEvent),
+            0x21 => Ok(PlatformEventClass::MessagePollEvent),
+            0x22 => Ok(PlatformEventClass::HeartbeatTimerElapsedEvent),
+            _ => Err(PldmError::InvalidEventClass),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum PlatformEventStatus {
+    NoLogging = 0,
+    LoggingDisabled = 1,
+    LogFull = 2,
+    AcceptedForLogging = 3,
+    Logged = 4,
+    LoggingRejected = 5,
+}
+
+impl TryFrom<u8> for PlatformEventStatus {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0 => Ok(PlatformEventStatus::NoLogging),
+            1 => Ok(PlatformEventStatus::LoggingDisabled),
+            2 => Ok(PlatformEventStatus::LogFull),
+            3 => Ok(PlatformEventStatus::AcceptedForLogging),
+            4 => Ok(PlatformEventStatus::Logged),
+            5 => Ok(PlatformEventStatus::LoggingRejected),
+            _ => Err(PldmError::InvalidData),
+        }
+    }
+}
+
+/// transferOperationFlag of PollForPlatformEventMessage, which extends the base
+/// flags with an acknowledgement-only operation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum PollTransferOperationFlag {
+    GetNextPart = 0,
+    GetFirstPart = 1,
+    AcknowledgementOnly = 2,
+}
+
+impl TryFrom<u8> for PollTransferOperationFlag {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0 => Ok(PollTransferOperationFlag::GetNextPart),
+            1 => Ok(PollTransferOperationFlag::GetFirstPart),
+            2 => Ok(PollTransferOperationFlag::AcknowledgementOnly),
+            _ => Err(PldmError::InvalidTransferOpFlag),
+        }
+    }
+}
@@ -15,6 +15,8 @@ pub struct FirmwareComponent {
     pub comp_version: PldmFirmwareString,
     pub comp_image_size: Option<u32>,
     pub update_option_flags: Option<UpdateOptionFlags>,
+    // Set when the component targets the downstream devices (RequestDownstreamDeviceUpdate)
+    pub downstream_device_update: bool,
 }
 
 impl FirmwareComponent {
@@ -35,6 +37,7 @@ impl FirmwareComponent {
             comp_version,
             comp_image_size,
             update_option_flags,
+            downstream_device_update: false,
         }
     }
 
@@ -94,14 +94,23 @@ bitfield! {
     pub u8, data_length, set_data_length: 7, 0;
 }
 
+/// Direct SETMWL CCC: sets the maximum write length of the target.
+pub const CCC_DIRECT_SETMWL: u8 = 0x89;
+/// Direct SETMRL CCC: sets the maximum read length of the target.
+pub const CCC_DIRECT_SETMRL: u8 = 0x8A;
+/// Direct GETMWL CCC: reads the maximum write length of the target.
+pub const CCC_DIRECT_GETMWL: u8 = 0x8B;
+/// Direct GETMRL CCC: reads the maximum read length of the target.
+pub const CCC_DIRECT_GETMRL: u8 = 0x8C;
+
 bitfield! {
     #[derive(Clone, FromBytes, IntoBytes)]
     pub struct ImmediateDataTransferCommand(u64);
     impl Debug;
     u8, cmd_attr, set_cmd_attr: 2, 0;
     u8, tid, set_tid: 6, 3;
-    u8, cmd, set_cmd: 14, 7;
-    u8, cp, set_cp: 15, 15;
+    pub u8, cmd, set_cmd: 14, 7;
+    pub u8, cp, set_cp: 15, 15;
     u8, dev_index, set_dev_index: 20, 16;
     u8, ddt, set_ddt: 25, 23;
     u8, mode, set_mode: 28, 26;
@@ -120,8 +129,8 @@ bitfield! {
     impl Debug;
     u8, cmd_attr, set_cmd_attr: 2, 0;
     u8, tid, set_tid: 6, 3;
-    u8, cmd, set_cmd: 14, 7;
-    u8, cp, set_cp: 15, 15;
+    pub u8, cmd, set_cmd: 14, 7;
+    pub u8, cp, set_cp: 15, 15;
     u8, dev_index, set_dev_index: 20, 16;
     u8, short_read_err, set_short_read_err: 24, 24;
     u8, dbp, set_dbp: 25, 25;
@@ -219,6 +228,15 @@ impl I3cTcriCommand {
             Self::Combo(combo) => combo.data_length().into(),
         }
     }
+    /// Returns the Common Command Code if this command carries one.
+    pub fn ccc(&self) -> Option<u8> {
+        match self {
+            Self::Immediate(imm) if imm.cp() == 1 => Some(imm.cmd()),
+            Self::Regular(regular) if regular.cp() == 1 => Some(regular.cmd()),
+            Self::Combo(combo) if combo.cp() == 1 => Some(combo.cmd()),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
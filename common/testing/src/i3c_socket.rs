@@ -206,6 +206,15 @@ impl BufferedStream {
         true
     }
 
+    /// Send a direct SET CCC (e.g. SETMWL/SETMRL) with its defining data to the target.
+    pub fn send_direct_ccc(&mut self, target_addr: u8, ccc: u8, data: &[u8]) {
+        let ccc_cmd = prepare_direct_ccc_cmd(target_addr, ccc, data.len() as u16);
+        self.stream.set_nonblocking(false).unwrap();
+        self.stream.write_all(&ccc_cmd).unwrap();
+        self.stream.set_nonblocking(true).unwrap();
+        self.stream.write_all(data).unwrap();
+    }
+
     /// Send a command with payload using the packetized protocol.
     ///
     /// Each packet has a 4-byte header `[cmd, payload_len, seq_num, total_seqs]`
@@ -327,6 +336,21 @@ fn prepare_private_write_cmd(to_addr: u8, data_len: u16) -> [u8; 9] {
     transmute!(cmd_hdr)
 }
 
+fn prepare_direct_ccc_cmd(to_addr: u8, ccc: u8, data_len: u16) -> [u8; 9] {
+    let mut ccc_cmd = ReguDataTransferCommand::read_from_bytes(&[0; 8]).unwrap();
+    ccc_cmd.set_cp(1);
+    ccc_cmd.set_cmd(ccc);
+    ccc_cmd.set_rnw(0);
+    ccc_cmd.set_data_length(data_len);
+
+    let cmd_words: [u32; 2] = transmute!(ccc_cmd);
+    let cmd_hdr = IncomingHeader {
+        to_addr,
+        command: cmd_words,
+    };
+    transmute!(cmd_hdr)
+}
+
 fn prepare_private_read_cmd(to_addr: u8) -> [u8; 9] {
     let mut read_cmd = ReguDataTransferCommand::read_from_bytes(&[0; 8]).unwrap();
     read_cmd.set_rnw(1);
@@ -376,6 +400,13 @@ mod tests {
         assert_eq!("100000000000002000", hex::encode(cmd));
     }
 
+    #[test]
+    fn test_prepare_direct_ccc_cmd() {
+        // to_addr = 0x10, cmd_desc = [0x0000c480, 0x00020000] (SETMWL, CP = 1)
+        let cmd = prepare_direct_ccc_cmd(0x10, crate::i3c::CCC_DIRECT_SETMWL, 2);
+        assert_eq!("1080c4000000000200", hex::encode(cmd));
+    }
+
     #[test]
     fn test_prepare_private_read_cmd() {
         // to_addr = 0x10, cmd_desc = [0x20000000, 0x00000000]
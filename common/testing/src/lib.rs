@@ -11,6 +11,9 @@ pub mod mctp_transport;
 pub mod mctp_vdm_transport;
 #[macro_use]
 pub mod mctp_util;
+pub mod pcie_vdm_socket;
+pub mod pcie_vdm_socket_server;
+pub mod spdm_requester;
 pub mod spdm_responder_validator;
 
 pub use caliptra_api_types::DeviceLifecycle;
@@ -18,6 +18,8 @@ use std::net::{SocketAddr, TcpStream};
 use std::sync::{Arc, Condvar, Mutex};
 
 pub const MCTP_TAG_MASK: u8 = 0x07;
+const PLDM_INSTANCE_ID_MASK: u8 = 0x1F;
+const PLDM_INSTANCE_ID_COUNT: usize = 32;
 
 #[derive(Debug, PartialEq, Clone)]
 enum MctpPldmSocketState {
@@ -33,7 +35,9 @@ pub struct MctpPldmSocket {
     msg_tag: u8,
     context: Arc<(Mutex<MctpPldmSocketData>, Condvar)>,
     stream: BufferedStream,
-    response_msg_tag: Arc<Mutex<u8>>,
+    // MCTP tag of the last request received for each PLDM instance ID. Responses carry the
+    // tag of the request they answer, so several requests can be outstanding at once.
+    response_msg_tags: Arc<Mutex<[u8; PLDM_INSTANCE_ID_COUNT]>>,
 }
 
 struct MctpPldmSocketData {
@@ -81,7 +85,8 @@ impl PldmSocket for MctpPldmSocket {
                 self.target_addr,
             );
         } else {
-            let msg_tag = *self.response_msg_tag.lock().unwrap();
+            let instance_id = (payload[0] & PLDM_INSTANCE_ID_MASK) as usize;
+            let msg_tag = self.response_msg_tags.lock().unwrap()[instance_id];
             mctp_util.set_src_eid(self.dest.0);
             mctp_util.set_dest_eid(self.source.0);
             mctp_util.set_msg_tag(msg_tag & MCTP_TAG_MASK);
@@ -140,7 +145,10 @@ impl PldmSocket for MctpPldmSocket {
         // Skip the first byte containing the MCTP common header
         // and only return the PLDM payload
         data[..len].copy_from_slice(&raw_pkt[1..]);
-        *self.response_msg_tag.lock().unwrap() = mctp_util.get_msg_tag();
+        if raw_pkt[1] & 0x80 == 0x80 {
+            let instance_id = (raw_pkt[1] & PLDM_INSTANCE_ID_MASK) as usize;
+            self.response_msg_tags.lock().unwrap()[instance_id] = mctp_util.get_msg_tag();
+        }
         Ok(RxPacket {
             src: self.dest,
             payload: Payload { data, len },
@@ -164,7 +172,7 @@ impl PldmSocket for MctpPldmSocket {
             msg_tag: self.msg_tag,
             context: self.context.clone(),
             stream: self.stream.try_clone().unwrap(),
-            response_msg_tag: self.response_msg_tag.clone(),
+            response_msg_tags: self.response_msg_tags.clone(),
         }
     }
 }
@@ -204,7 +212,7 @@ impl PldmTransport<MctpPldmSocket> for MctpTransport {
                 }),
                 Condvar::new(),
             )),
-            response_msg_tag: Arc::new(Mutex::new(msg_tag)),
+            response_msg_tags: Arc::new(Mutex::new([msg_tag; PLDM_INSTANCE_ID_COUNT])),
         })
     }
 }
@@ -20,6 +20,8 @@ pub struct MctpUtil {
     msg_tag: u8,
     tag_owner: u8,
     pkt_payload_size: usize,
+    // Payload size of the first packet of the last received message
+    rx_pkt_payload_size: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -50,6 +52,7 @@ impl MctpUtil {
             msg_tag: DEFAULT_MSG_TAG,
             tag_owner: 1,
             pkt_payload_size: DEFAULT_PKT_PAYLOAD_SIZE,
+            rx_pkt_payload_size: 0,
         }
     }
 
@@ -100,6 +103,12 @@ impl MctpUtil {
         self.pkt_payload_size
     }
 
+    /// Returns the payload size of the first packet of the last received message.
+    /// For multi-packet messages, this is the packet size used by the sender.
+    pub fn get_rx_pkt_payload_size(&self) -> usize {
+        self.rx_pkt_payload_size
+    }
+
     #[allow(dead_code)]
     pub fn set_msg_tag(&mut self, tag: u8) {
         self.msg_tag = tag;
@@ -223,6 +232,30 @@ impl MctpUtil {
         self.send_packets(pkts, stream, target_addr);
     }
 
+    /// Send a response whose first packet is followed by the remaining packets
+    /// only after the specified number of emulator ticks.
+    /// This function will block until the response message is sent
+    ///
+    /// # Arguments
+    /// * `msg` - The message to be sent
+    /// * `stream` - The TCP stream to I3C socket
+    /// * `target_addr` - The target address of the I3C device
+    /// * `delay_ticks` - Emulator ticks to wait after the first packet
+    pub fn send_split_response(
+        &mut self,
+        msg: &[u8],
+        stream: &mut BufferedStream,
+        target_addr: u8,
+        delay_ticks: u32,
+    ) {
+        self.new_resp();
+        let mut pkts = self.packetize(msg);
+        let first_pkt = pkts.drain(..1).collect();
+        self.send_packets(first_pkt, stream, target_addr);
+        sleep_emulator_ticks(delay_ticks);
+        self.send_packets(pkts, stream, target_addr);
+    }
+
     /// Receive a response from target address and return the assembled message
     /// Blocks until a response is received or the specified timeout is reached.
     /// If no timeout is provided, it will wait indefinitely for a response.
@@ -382,6 +415,7 @@ impl MctpUtil {
             message_identifier.src_eid = mctp_hdr.src_eid();
             message_identifier.msg_tag = mctp_hdr.msg_tag();
             message_identifier.tag_owner = mctp_hdr.tag_owner();
+            self.rx_pkt_payload_size = data.len() - MCTP_HDR_SIZE;
         } else {
             // Check if this packet belongs to the message we are currently collecting
             if message_identifier.msg_tag != mctp_hdr.msg_tag()
@@ -5,6 +5,7 @@ use bitfield::bitfield;
 use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
 
 pub const MCTP_CTRL_MSG_HDR_SIZE: usize = 2;
+pub const MCTP_UUID_LEN: usize = 16;
 
 pub fn set_eid_req_bytes(op: SetEIDOp, eid: u8) -> Vec<u8> {
     let mut req_bytes: [u8; 2] = [0; 2];
@@ -59,6 +60,28 @@ pub fn generate_msg_type_support_resp_bytes(cc: u8, supported_types: &[MctpMsgTy
     resp_bytes
 }
 
+pub fn get_uuid_resp_bytes(cc: CmdCompletionCode, uuid: &[u8; MCTP_UUID_LEN]) -> Vec<u8> {
+    let mut resp_bytes = Vec::new();
+    resp_bytes.push(cc as u8); // completion code
+    resp_bytes.extend_from_slice(uuid);
+    resp_bytes
+}
+
+pub fn get_vendor_defined_msg_support_resp_bytes(
+    cc: CmdCompletionCode,
+    next_selector: u8,
+    vendor_id: u16,
+    cmd_set_version: u16,
+) -> Vec<u8> {
+    let mut resp_bytes = Vec::new();
+    resp_bytes.push(cc as u8); // completion code
+    resp_bytes.push(next_selector); // Vendor ID set selector of the next set
+    resp_bytes.push(VendorIDFormat::PciVendorID as u8);
+    resp_bytes.extend_from_slice(&vendor_id.to_be_bytes());
+    resp_bytes.extend_from_slice(&cmd_set_version.to_be_bytes());
+    resp_bytes
+}
+
 bitfield! {
     #[repr(C)]
     #[derive(Clone, FromBytes, IntoBytes, Immutable)]
@@ -88,8 +111,13 @@ impl MCTPCtrlMsgHdr<[u8; MCTP_CTRL_MSG_HDR_SIZE]> {
 pub enum MCTPCtrlCmd {
     SetEID = 1,
     GetEID = 2,
+    GetEndpointUUID = 3,
     GetMctpVersionSupport = 4,
     GetMsgTypeSupport = 5,
+    GetVendorDefinedMsgSupport = 6,
+    PrepareForEndpointDiscovery = 0x0B,
+    EndpointDiscovery = 0x0C,
+    GetNetworkID = 0x0E,
     Unsupported,
 }
 
@@ -98,8 +126,13 @@ impl From<u8> for MCTPCtrlCmd {
         match val {
             1 => MCTPCtrlCmd::SetEID,
             2 => MCTPCtrlCmd::GetEID,
+            3 => MCTPCtrlCmd::GetEndpointUUID,
             4 => MCTPCtrlCmd::GetMctpVersionSupport,
             5 => MCTPCtrlCmd::GetMsgTypeSupport,
+            6 => MCTPCtrlCmd::GetVendorDefinedMsgSupport,
+            0x0B => MCTPCtrlCmd::PrepareForEndpointDiscovery,
+            0x0C => MCTPCtrlCmd::EndpointDiscovery,
+            0x0E => MCTPCtrlCmd::GetNetworkID,
             _ => MCTPCtrlCmd::Unsupported,
         }
     }
@@ -144,7 +177,7 @@ pub enum SetEIDOp {
     SetEID = 0,
     ForceEID = 1,
     // ResetEID = 2,
-    // SetDiscoveredFlag = 3,
+    SetDiscoveredFlag = 3,
 }
 
 // Set EID Response
@@ -247,6 +280,11 @@ impl From<u8> for EIDType {
     }
 }
 
+pub enum VendorIDFormat {
+    PciVendorID = 0,
+    IanaEnterpriseID = 1,
+}
+
 pub enum VersionSupportMessageType {
     MctpBase = 0xFF,
     MctpControlProtocol = 0x00,
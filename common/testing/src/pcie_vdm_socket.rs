@@ -0,0 +1,66 @@
+// Licensed under the Apache-2.0 license
+
+//! Client side of the MCTP over PCIe VDM socket. Exchanges MCTP packets with the
+//! emulated endpoint, acting as the root complex that hosts the MCTP bus owner.
+
+use crate::pcie_vdm_socket_server::{decode_tlp, encode_tlp, PcieVdmHeader, PcieVdmRouting};
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::time::Duration;
+
+/// PCI ID used by the root complex.
+pub const PCIE_VDM_ROOT_COMPLEX_ID: u16 = 0x0000;
+/// PCI ID assigned to the emulated endpoint.
+pub const PCIE_VDM_ENDPOINT_ID: u16 = 0x0100;
+
+pub struct PcieVdmSocket {
+    stream: TcpStream,
+    requester_id: u16,
+    target_id: u16,
+}
+
+impl PcieVdmSocket {
+    pub fn connect(port: u16) -> std::io::Result<Self> {
+        let stream = TcpStream::connect(SocketAddr::from(([127, 0, 0, 1], port)))?;
+        Ok(Self {
+            stream,
+            requester_id: PCIE_VDM_ROOT_COMPLEX_ID,
+            target_id: PCIE_VDM_ENDPOINT_ID,
+        })
+    }
+
+    /// Sends an MCTP packet (transport header and payload), routed by ID to the endpoint.
+    pub fn send(&mut self, mctp_pkt: &[u8]) -> std::io::Result<()> {
+        let hdr = PcieVdmHeader {
+            routing: PcieVdmRouting::RouteById,
+            requester_id: self.requester_id,
+            target_id: self.target_id,
+        };
+        let tlp = encode_tlp(&hdr, mctp_pkt);
+        self.stream.write_all(&(tlp.len() as u16).to_le_bytes())?;
+        self.stream.write_all(&tlp)
+    }
+
+    /// Receives the next MCTP packet sent by the endpoint. Returns `None` on timeout.
+    /// TLPs that are not MCTP VDMs are skipped.
+    pub fn receive(&mut self, timeout: Option<Duration>) -> std::io::Result<Option<Vec<u8>>> {
+        self.stream.set_read_timeout(timeout)?;
+        loop {
+            let mut len_bytes = [0u8; 2];
+            match self.stream.read_exact(&mut len_bytes) {
+                Ok(()) => {}
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    return Ok(None);
+                }
+                Err(e) => return Err(e),
+            }
+            let mut tlp = vec![0u8; u16::from_le_bytes(len_bytes) as usize];
+            self.stream.set_read_timeout(None)?;
+            self.stream.read_exact(&mut tlp)?;
+            self.stream.set_read_timeout(timeout)?;
+            if let Some((_, pkt)) = decode_tlp(&tlp) {
+                return Ok(Some(pkt.to_vec()));
+            }
+        }
+    }
+}
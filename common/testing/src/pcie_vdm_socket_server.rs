@@ -0,0 +1,226 @@
+/*++
+
+Licensed under the Apache-2.0 license.
+
+File Name:
+
+    pcie_vdm_socket_server.rs
+
+Abstract:
+
+    MCTP over PCIe VDM (DSP0238) over TCP socket implementation.
+
+    Each PCIe Vendor Defined Message TLP is framed on the socket as:
+    len: u16 (little endian, length of the TLP in bytes)
+    tlp: [u8; len]
+
+    The TLP is the 12-byte PCIe VDM header followed by the MCTP transport header and the
+    MCTP packet payload, padded to a dword boundary. The server forwards TLPs written by
+    the client to the emulated PCIe endpoint, and TLPs sent by the endpoint to the client.
+
+--*/
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::mpsc::{Receiver, Sender};
+
+pub const PCIE_VDM_HDR_SIZE: usize = 12;
+pub const PCIE_VDM_DMTF_VENDOR_ID: u16 = 0x1AB4;
+const MCTP_HDR_SIZE: usize = 4;
+const PCIE_VDM_FMT_TYPE_MSG: u8 = 0b0111_0000;
+const PCIE_VDM_MSG_CODE: u8 = 0x7F;
+
+/// PCIe message routing types allowed for MCTP VDMs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PcieVdmRouting {
+    RouteToRootComplex = 0b000,
+    RouteById = 0b010,
+    BroadcastFromRootComplex = 0b011,
+}
+
+impl TryFrom<u8> for PcieVdmRouting {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0b000 => Ok(PcieVdmRouting::RouteToRootComplex),
+            0b010 => Ok(PcieVdmRouting::RouteById),
+            0b011 => Ok(PcieVdmRouting::BroadcastFromRootComplex),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PcieVdmHeader {
+    pub routing: PcieVdmRouting,
+    pub requester_id: u16,
+    pub target_id: u16,
+}
+
+/// Wraps an MCTP packet (transport header and payload) in a PCIe VDM TLP.
+pub fn encode_tlp(hdr: &PcieVdmHeader, mctp_pkt: &[u8]) -> Vec<u8> {
+    let payload_len = mctp_pkt.len().saturating_sub(MCTP_HDR_SIZE);
+    let pad_len = payload_len.wrapping_neg() % 4;
+    let length_dw = (payload_len + pad_len) / 4;
+
+    let mut tlp = Vec::with_capacity(PCIE_VDM_HDR_SIZE + mctp_pkt.len() + pad_len);
+    tlp.push(PCIE_VDM_FMT_TYPE_MSG | hdr.routing as u8);
+    tlp.push(0);
+    tlp.push(((length_dw >> 8) & 0x03) as u8);
+    tlp.push(length_dw as u8);
+    tlp.extend_from_slice(&hdr.requester_id.to_be_bytes());
+    tlp.push((pad_len as u8) << 4);
+    tlp.push(PCIE_VDM_MSG_CODE);
+    tlp.extend_from_slice(&hdr.target_id.to_be_bytes());
+    tlp.extend_from_slice(&PCIE_VDM_DMTF_VENDOR_ID.to_be_bytes());
+    tlp.extend_from_slice(mctp_pkt);
+    tlp.resize(tlp.len() + pad_len, 0);
+    tlp
+}
+
+/// Validates a PCIe VDM TLP and returns its header and the MCTP packet it carries,
+/// with the padding removed.
+pub fn decode_tlp(tlp: &[u8]) -> Option<(PcieVdmHeader, &[u8])> {
+    if tlp.len() < PCIE_VDM_HDR_SIZE + MCTP_HDR_SIZE
+        || tlp[0] & !0x07 != PCIE_VDM_FMT_TYPE_MSG
+        || tlp[6] & 0x0F != 0
+        || tlp[7] != PCIE_VDM_MSG_CODE
+        || u16::from_be_bytes([tlp[10], tlp[11]]) != PCIE_VDM_DMTF_VENDOR_ID
+    {
+        return None;
+    }
+    let hdr = PcieVdmHeader {
+        routing: (tlp[0] & 0x07).try_into().ok()?,
+        requester_id: u16::from_be_bytes([tlp[4], tlp[5]]),
+        target_id: u16::from_be_bytes([tlp[8], tlp[9]]),
+    };
+    let length_dw = (((tlp[2] & 0x03) as usize) << 8) | tlp[3] as usize;
+    let pad_len = ((tlp[6] >> 4) & 0x03) as usize;
+    let pkt_len = (MCTP_HDR_SIZE + length_dw * 4).checked_sub(pad_len)?;
+    let pkt = tlp.get(PCIE_VDM_HDR_SIZE..PCIE_VDM_HDR_SIZE + pkt_len)?;
+    Some((hdr, pkt))
+}
+
+/// Starts the socket server on `port`. Returns a receiver of the TLPs written by the client
+/// and a sender for the TLPs to be forwarded to the client.
+pub fn start_pcie_vdm_socket(
+    running: &'static AtomicBool,
+    port: u16,
+) -> (Receiver<Vec<u8>>, Sender<Vec<u8>>) {
+    let listener = TcpListener::bind(format!("127.0.0.1:{}", port))
+        .expect("Failed to bind TCP socket for port");
+
+    let (incoming_tx, incoming_rx) = mpsc::channel::<Vec<u8>>();
+    let (outgoing_tx, outgoing_rx) = mpsc::channel::<Vec<u8>>();
+    std::thread::spawn(move || {
+        handle_pcie_vdm_socket_loop(running, listener, outgoing_rx, incoming_tx)
+    });
+
+    (incoming_rx, outgoing_tx)
+}
+
+fn handle_pcie_vdm_socket_loop(
+    running: &'static AtomicBool,
+    listener: TcpListener,
+    outgoing_rx: Receiver<Vec<u8>>,
+    incoming_tx: Sender<Vec<u8>>,
+) {
+    listener
+        .set_nonblocking(true)
+        .expect("Could not set non-blocking");
+    while running.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, addr)) => {
+                println!("Accepting PCIe VDM socket connection from {:?}", addr);
+                handle_pcie_vdm_socket_connection(running, stream, &outgoing_rx, &incoming_tx);
+            }
+            Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
+                std::thread::sleep(std::time::Duration::from_millis(10));
+            }
+            Err(e) => panic!("Error accepting connection: {}", e),
+        }
+    }
+}
+
+fn handle_pcie_vdm_socket_connection(
+    running: &'static AtomicBool,
+    mut stream: TcpStream,
+    outgoing_rx: &Receiver<Vec<u8>>,
+    incoming_tx: &Sender<Vec<u8>>,
+) {
+    stream.set_nonblocking(true).unwrap();
+
+    while running.load(Ordering::Relaxed) {
+        let mut len_bytes = [0u8; 2];
+        match stream.read_exact(&mut len_bytes) {
+            Ok(()) => {
+                let mut tlp = vec![0u8; u16::from_le_bytes(len_bytes) as usize];
+                stream.set_nonblocking(false).unwrap();
+                stream
+                    .read_exact(&mut tlp)
+                    .expect("Failed to read TLP from socket");
+                stream.set_nonblocking(true).unwrap();
+                if decode_tlp(&tlp).is_none() {
+                    println!("handle_pcie_vdm_socket_connection: Dropping invalid TLP");
+                    continue;
+                }
+                if incoming_tx.send(tlp).is_err() {
+                    break;
+                }
+            }
+            Err(ref e) if e.kind() == ErrorKind::WouldBlock => {}
+            Err(ref e)
+                if e.kind() == ErrorKind::ConnectionReset
+                    || e.kind() == ErrorKind::UnexpectedEof =>
+            {
+                println!("handle_pcie_vdm_socket_connection: Client disconnected");
+                break;
+            }
+            Err(e) => panic!("Error reading TLP from socket: {}", e),
+        }
+
+        match outgoing_rx.try_recv() {
+            Ok(tlp) => {
+                let len = u16::try_from(tlp.len()).expect("TLP too large for socket framing");
+                stream.write_all(&len.to_le_bytes()).unwrap();
+                stream.write_all(&tlp).unwrap();
+            }
+            Err(mpsc::TryRecvError::Empty) => {
+                std::thread::sleep(std::time::Duration::from_millis(1));
+            }
+            Err(mpsc::TryRecvError::Disconnected) => break,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tlp_roundtrip() {
+        let hdr = PcieVdmHeader {
+            routing: PcieVdmRouting::RouteById,
+            requester_id: 0x0008,
+            target_id: 0x0100,
+        };
+        let pkt = [0x01, 0x0A, 0x08, 0xC8, 1, 2, 3, 4, 5];
+        let tlp = encode_tlp(&hdr, &pkt);
+        assert_eq!(tlp.len(), 24);
+        assert_eq!(
+            &tlp[..PCIE_VDM_HDR_SIZE],
+            &[0x72, 0x00, 0x00, 0x02, 0x00, 0x08, 0x30, 0x7F, 0x01, 0x00, 0x1A, 0xB4]
+        );
+        assert_eq!(decode_tlp(&tlp), Some((hdr, &pkt[..])));
+
+        // Truncated TLP
+        assert_eq!(decode_tlp(&tlp[..20]), None);
+        // Not the DMTF vendor ID
+        let mut bad = tlp.clone();
+        bad[11] = 0;
+        assert_eq!(decode_tlp(&bad), None);
+    }
+}
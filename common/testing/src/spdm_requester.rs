@@ -0,0 +1,405 @@
+// Licensed under the Apache-2.0 license
+
+//! Transport bindings for `caliptra-mcu-spdm-requester` to reach the emulator SPDM
+//! Responder over MCTP (I3C socket) or DOE.
+
+use crate::doe_util::protocol::{DataObjectType, DoeHeader, DOE_DATA_OBJECT_HEADER_LEN};
+use crate::i3c_socket::BufferedStream;
+use crate::spdm_responder_validator::doe::DoeTransport;
+use crate::spdm_responder_validator::mctp::MctpTransport;
+use crate::spdm_responder_validator::transport::Transport;
+use crate::{sleep_emulator_ticks, wait_for_runtime_start, MCU_RUNNING};
+use caliptra_mcu_spdm_requester::{
+    RequesterConfig, RequesterIdentity, SpdmRequester, SpdmRequesterError, SpdmRequesterResult,
+    SpdmTransport,
+};
+use std::process::exit;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::{Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+use zerocopy::IntoBytes;
+
+const MCTP_MSG_TYPE_SPDM: u8 = 0x05;
+const MCTP_MSG_TYPE_SECURE_SPDM: u8 = 0x06;
+
+// Data object type is bits [23:16] of the first DOE header dword
+const DOE_DATA_OBJECT_TYPE_OFFSET: usize = 2;
+
+const GET_DIGESTS: u8 = 0x81;
+const DIGESTS: u8 = 0x01;
+const SPDM_MSG_HEADER_LEN: usize = 4;
+
+// The emulator MCU timer runs at 1 MHz (TIMER_FREQUENCY_HZ)
+const EMULATOR_TICKS_PER_SECOND: u32 = 1_000_000;
+
+const DOE_SPDM_REQUESTER_TEST_NAME: &str = "DOE-SPDM-REQUESTER";
+
+/// Root CA of the slot 0 certificate chain provisioned by the emulator user app
+/// (`SLOT0_ECC_TEST_ROOT_CA_CERT_DER`).
+pub static EMULATOR_ROOT_CA_CERT_DER: [u8; 552] = [
+    0x30, 0x82, 0x02, 0x24, 0x30, 0x82, 0x01, 0xaa, 0xa0, 0x03, 0x02, 0x01, 0x02, 0x02, 0x01, 0x00,
+    0x30, 0x0a, 0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x03, 0x30, 0x5e, 0x31, 0x1a,
+    0x30, 0x18, 0x06, 0x03, 0x55, 0x04, 0x03, 0x0c, 0x11, 0x77, 0x77, 0x77, 0x2e, 0x6d, 0x69, 0x63,
+    0x72, 0x6f, 0x73, 0x6f, 0x66, 0x74, 0x2e, 0x63, 0x6f, 0x6d, 0x31, 0x1e, 0x30, 0x1c, 0x06, 0x03,
+    0x55, 0x04, 0x0a, 0x0c, 0x15, 0x4d, 0x69, 0x63, 0x72, 0x6f, 0x73, 0x6f, 0x66, 0x74, 0x20, 0x43,
+    0x6f, 0x72, 0x70, 0x6f, 0x72, 0x61, 0x74, 0x69, 0x6f, 0x6e, 0x31, 0x0b, 0x30, 0x09, 0x06, 0x03,
+    0x55, 0x04, 0x06, 0x13, 0x02, 0x55, 0x53, 0x31, 0x13, 0x30, 0x11, 0x06, 0x03, 0x55, 0x04, 0x08,
+    0x0c, 0x0a, 0x57, 0x61, 0x73, 0x68, 0x69, 0x6e, 0x67, 0x74, 0x6f, 0x6e, 0x30, 0x1e, 0x17, 0x0d,
+    0x32, 0x36, 0x30, 0x34, 0x31, 0x31, 0x30, 0x30, 0x35, 0x39, 0x31, 0x32, 0x5a, 0x17, 0x0d, 0x32,
+    0x37, 0x30, 0x34, 0x31, 0x31, 0x30, 0x30, 0x35, 0x39, 0x31, 0x32, 0x5a, 0x30, 0x5e, 0x31, 0x1a,
+    0x30, 0x18, 0x06, 0x03, 0x55, 0x04, 0x03, 0x0c, 0x11, 0x77, 0x77, 0x77, 0x2e, 0x6d, 0x69, 0x63,
+    0x72, 0x6f, 0x73, 0x6f, 0x66, 0x74, 0x2e, 0x63, 0x6f, 0x6d, 0x31, 0x1e, 0x30, 0x1c, 0x06, 0x03,
+    0x55, 0x04, 0x0a, 0x0c, 0x15, 0x4d, 0x69, 0x63, 0x72, 0x6f, 0x73, 0x6f, 0x66, 0x74, 0x20, 0x43,
+    0x6f, 0x72, 0x70, 0x6f, 0x72, 0x61, 0x74, 0x69, 0x6f, 0x6e, 0x31, 0x0b, 0x30, 0x09, 0x06, 0x03,
+    0x55, 0x04, 0x06, 0x13, 0x02, 0x55, 0x53, 0x31, 0x13, 0x30, 0x11, 0x06, 0x03, 0x55, 0x04, 0x08,
+    0x0c, 0x0a, 0x57, 0x61, 0x73, 0x68, 0x69, 0x6e, 0x67, 0x74, 0x6f, 0x6e, 0x30, 0x76, 0x30, 0x10,
+    0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x22,
+    0x03, 0x62, 0x00, 0x04, 0x4c, 0x38, 0x7e, 0x2c, 0x6c, 0x76, 0xa0, 0xa1, 0x2f, 0xa5, 0x34, 0x23,
+    0x4e, 0xc8, 0x30, 0x94, 0x80, 0xbd, 0x05, 0x2a, 0x22, 0x86, 0xaf, 0x23, 0x27, 0xf7, 0x08, 0x0d,
+    0x1f, 0x52, 0x17, 0x90, 0xef, 0xef, 0xa1, 0x8a, 0xf1, 0x6a, 0xeb, 0x02, 0x40, 0x55, 0xbe, 0x67,
+    0x7b, 0x35, 0x54, 0xe7, 0x03, 0x6c, 0xfa, 0xe1, 0xd6, 0x8a, 0xa0, 0xe2, 0x72, 0x90, 0x38, 0xe4,
+    0xdc, 0xcc, 0x0b, 0xaf, 0x2c, 0xf5, 0x4b, 0xcc, 0x53, 0xc9, 0x36, 0xfa, 0x20, 0x2a, 0x0c, 0xf4,
+    0xb6, 0x54, 0x7f, 0xfe, 0x6e, 0xc9, 0x16, 0xdb, 0xde, 0x50, 0xc1, 0xd1, 0xcd, 0xbb, 0x60, 0x44,
+    0x40, 0x0d, 0xa7, 0x38, 0xa3, 0x3c, 0x30, 0x3a, 0x30, 0x12, 0x06, 0x03, 0x55, 0x1d, 0x13, 0x01,
+    0x01, 0xff, 0x04, 0x08, 0x30, 0x06, 0x01, 0x01, 0xff, 0x02, 0x01, 0x06, 0x30, 0x14, 0x06, 0x03,
+    0x55, 0x1d, 0x11, 0x04, 0x0d, 0x30, 0x0b, 0x82, 0x09, 0x53, 0x50, 0x41, 0x52, 0x43, 0x2e, 0x63,
+    0x6f, 0x6d, 0x30, 0x0e, 0x06, 0x03, 0x55, 0x1d, 0x0f, 0x01, 0x01, 0xff, 0x04, 0x04, 0x03, 0x02,
+    0x01, 0x86, 0x30, 0x0a, 0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x03, 0x03, 0x68,
+    0x00, 0x30, 0x65, 0x02, 0x30, 0x7e, 0x20, 0xf0, 0x70, 0x02, 0xf7, 0xea, 0x27, 0x22, 0x63, 0x41,
+    0xe5, 0x70, 0x32, 0x84, 0xf4, 0x66, 0x9e, 0x00, 0xb8, 0x06, 0x07, 0xdd, 0x00, 0xf0, 0xa9, 0xfd,
+    0xc2, 0x34, 0xc3, 0x4b, 0x25, 0x2e, 0xd5, 0x49, 0xd6, 0xd5, 0x9d, 0xcc, 0x04, 0xe7, 0x47, 0xe3,
+    0xa6, 0xe6, 0xb9, 0x29, 0xb0, 0x02, 0x31, 0x00, 0xa7, 0xf5, 0x5b, 0x3f, 0x3f, 0x1b, 0x3e, 0x14,
+    0x7f, 0xf9, 0x36, 0x36, 0x56, 0xd5, 0x2a, 0x9c, 0xd2, 0x00, 0xe6, 0xff, 0x3a, 0xdd, 0x5b, 0xcb,
+    0x00, 0xaf, 0x35, 0x5d, 0x5f, 0xcd, 0x73, 0xf4, 0x2c, 0x37, 0xa3, 0x06, 0x46, 0xee, 0x44, 0xdd,
+    0x91, 0x54, 0x86, 0x39, 0xc7, 0xde, 0x6e, 0xc2,
+];
+
+/// Test root CA of the Requester certificate chain. The emulator DOE responder trusts it
+/// for session-based mutual authentication (`EMULATOR_SPDM_REQUESTER_ROOT_CA_HASHES`).
+pub static EMULATOR_REQUESTER_ROOT_CA_CERT_DER: [u8; 502] = [
+    0x30, 0x82, 0x01, 0xf2, 0x30, 0x82, 0x01, 0x78, 0xa0, 0x03, 0x02, 0x01, 0x02, 0x02, 0x01, 0x01,
+    0x30, 0x0a, 0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x03, 0x30, 0x41, 0x31, 0x2c,
+    0x30, 0x2a, 0x06, 0x03, 0x55, 0x04, 0x03, 0x0c, 0x23, 0x43, 0x61, 0x6c, 0x69, 0x70, 0x74, 0x72,
+    0x61, 0x20, 0x4d, 0x43, 0x55, 0x20, 0x54, 0x65, 0x73, 0x74, 0x20, 0x52, 0x65, 0x71, 0x75, 0x65,
+    0x73, 0x74, 0x65, 0x72, 0x20, 0x52, 0x6f, 0x6f, 0x74, 0x20, 0x43, 0x41, 0x31, 0x11, 0x30, 0x0f,
+    0x06, 0x03, 0x55, 0x04, 0x0a, 0x0c, 0x08, 0x43, 0x61, 0x6c, 0x69, 0x70, 0x74, 0x72, 0x61, 0x30,
+    0x20, 0x17, 0x0d, 0x32, 0x36, 0x31, 0x30, 0x31, 0x37, 0x30, 0x33, 0x32, 0x36, 0x31, 0x34, 0x5a,
+    0x18, 0x0f, 0x32, 0x31, 0x32, 0x36, 0x30, 0x39, 0x32, 0x33, 0x30, 0x33, 0x32, 0x36, 0x31, 0x34,
+    0x5a, 0x30, 0x41, 0x31, 0x2c, 0x30, 0x2a, 0x06, 0x03, 0x55, 0x04, 0x03, 0x0c, 0x23, 0x43, 0x61,
+    0x6c, 0x69, 0x70, 0x74, 0x72, 0x61, 0x20, 0x4d, 0x43, 0x55, 0x20, 0x54, 0x65, 0x73, 0x74, 0x20,
+    0x52, 0x65, 0x71, 0x75, 0x65, 0x73, 0x74, 0x65, 0x72, 0x20, 0x52, 0x6f, 0x6f, 0x74, 0x20, 0x43,
+    0x41, 0x31, 0x11, 0x30, 0x0f, 0x06, 0x03, 0x55, 0x04, 0x0a, 0x0c, 0x08, 0x43, 0x61, 0x6c, 0x69,
+    0x70, 0x74, 0x72, 0x61, 0x30, 0x76, 0x30, 0x10, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02,
+    0x01, 0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x22, 0x03, 0x62, 0x00, 0x04, 0x0e, 0x5d, 0x5d, 0xec,
+    0xbe, 0xca, 0x06, 0x76, 0x2e, 0x23, 0x9f, 0x10, 0x3c, 0x7c, 0x34, 0x33, 0xcc, 0x6d, 0x93, 0x15,
+    0x50, 0xce, 0x40, 0x23, 0x9b, 0x15, 0x3c, 0x26, 0x90, 0xb5, 0x62, 0x0a, 0x7f, 0x41, 0xad, 0x9a,
+    0x1a, 0xbd, 0x33, 0xe1, 0x00, 0x24, 0x50, 0x67, 0xd0, 0x58, 0xf8, 0x23, 0x3b, 0x50, 0x97, 0x23,
+    0x13, 0x92, 0x20, 0x00, 0x32, 0x40, 0x4d, 0x21, 0x76, 0x8b, 0x2d, 0xa4, 0x6c, 0xcf, 0x9e, 0x1c,
+    0x8a, 0x9c, 0x83, 0x7f, 0x22, 0x6b, 0x6c, 0x25, 0x1f, 0x8b, 0x65, 0xc8, 0xf5, 0x20, 0x21, 0x55,
+    0xcf, 0xd7, 0x5f, 0x71, 0xaa, 0x69, 0xee, 0xae, 0x95, 0xca, 0x77, 0x89, 0xa3, 0x42, 0x30, 0x40,
+    0x30, 0x0f, 0x06, 0x03, 0x55, 0x1d, 0x13, 0x01, 0x01, 0xff, 0x04, 0x05, 0x30, 0x03, 0x01, 0x01,
+    0xff, 0x30, 0x0e, 0x06, 0x03, 0x55, 0x1d, 0x0f, 0x01, 0x01, 0xff, 0x04, 0x04, 0x03, 0x02, 0x01,
+    0x06, 0x30, 0x1d, 0x06, 0x03, 0x55, 0x1d, 0x0e, 0x04, 0x16, 0x04, 0x14, 0x08, 0x91, 0xbf, 0x3d,
+    0x3d, 0xb3, 0x21, 0x7c, 0xf4, 0xa2, 0x0d, 0xce, 0x3e, 0x5a, 0x01, 0x48, 0xb4, 0x0a, 0x67, 0x5a,
+    0x30, 0x0a, 0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x03, 0x03, 0x68, 0x00, 0x30,
+    0x65, 0x02, 0x30, 0x79, 0xbf, 0x75, 0xf9, 0x1c, 0x17, 0x31, 0x56, 0x8e, 0xfb, 0x27, 0xb5, 0xf4,
+    0xc5, 0x73, 0xc3, 0x6a, 0x53, 0xee, 0xee, 0x95, 0xd5, 0x92, 0x87, 0xe0, 0x6f, 0xb9, 0xfb, 0xd3,
+    0x81, 0xa8, 0xbe, 0x35, 0xfe, 0xdc, 0x73, 0xe8, 0x89, 0x67, 0x33, 0xa5, 0x91, 0x1d, 0x29, 0xc8,
+    0x3c, 0xad, 0x4e, 0x02, 0x31, 0x00, 0x84, 0xab, 0x6c, 0x9c, 0xda, 0x7a, 0xb3, 0x5b, 0x9d, 0xa0,
+    0x55, 0x10, 0x88, 0x66, 0x22, 0x7e, 0xc5, 0x4f, 0xad, 0x0f, 0x35, 0x2e, 0xd9, 0xff, 0xe5, 0xef,
+    0xa1, 0x29, 0xed, 0xb1, 0xd9, 0x35, 0xf1, 0xc8, 0x8e, 0xcd, 0x40, 0xc6, 0xa3, 0x42, 0x9a, 0xc4,
+    0xcb, 0x19, 0xed, 0xcc, 0x0d, 0xa8,
+];
+
+/// Requester leaf certificate issued by `EMULATOR_REQUESTER_ROOT_CA_CERT_DER`.
+pub static EMULATOR_REQUESTER_LEAF_CERT_DER: [u8; 525] = [
+    0x30, 0x82, 0x02, 0x09, 0x30, 0x82, 0x01, 0x8e, 0xa0, 0x03, 0x02, 0x01, 0x02, 0x02, 0x01, 0x02,
+    0x30, 0x0a, 0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x03, 0x30, 0x41, 0x31, 0x2c,
+    0x30, 0x2a, 0x06, 0x03, 0x55, 0x04, 0x03, 0x0c, 0x23, 0x43, 0x61, 0x6c, 0x69, 0x70, 0x74, 0x72,
+    0x61, 0x20, 0x4d, 0x43, 0x55, 0x20, 0x54, 0x65, 0x73, 0x74, 0x20, 0x52, 0x65, 0x71, 0x75, 0x65,
+    0x73, 0x74, 0x65, 0x72, 0x20, 0x52, 0x6f, 0x6f, 0x74, 0x20, 0x43, 0x41, 0x31, 0x11, 0x30, 0x0f,
+    0x06, 0x03, 0x55, 0x04, 0x0a, 0x0c, 0x08, 0x43, 0x61, 0x6c, 0x69, 0x70, 0x74, 0x72, 0x61, 0x30,
+    0x20, 0x17, 0x0d, 0x32, 0x36, 0x31, 0x30, 0x31, 0x37, 0x30, 0x33, 0x32, 0x36, 0x31, 0x34, 0x5a,
+    0x18, 0x0f, 0x32, 0x31, 0x32, 0x36, 0x30, 0x39, 0x32, 0x33, 0x30, 0x33, 0x32, 0x36, 0x31, 0x34,
+    0x5a, 0x30, 0x39, 0x31, 0x24, 0x30, 0x22, 0x06, 0x03, 0x55, 0x04, 0x03, 0x0c, 0x1b, 0x43, 0x61,
+    0x6c, 0x69, 0x70, 0x74, 0x72, 0x61, 0x20, 0x4d, 0x43, 0x55, 0x20, 0x54, 0x65, 0x73, 0x74, 0x20,
+    0x52, 0x65, 0x71, 0x75, 0x65, 0x73, 0x74, 0x65, 0x72, 0x31, 0x11, 0x30, 0x0f, 0x06, 0x03, 0x55,
+    0x04, 0x0a, 0x0c, 0x08, 0x43, 0x61, 0x6c, 0x69, 0x70, 0x74, 0x72, 0x61, 0x30, 0x76, 0x30, 0x10,
+    0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x22,
+    0x03, 0x62, 0x00, 0x04, 0x59, 0xa6, 0x0a, 0x00, 0x24, 0x6d, 0x04, 0x9c, 0x53, 0xa3, 0xb5, 0xf8,
+    0xb6, 0x96, 0xad, 0x00, 0x29, 0x28, 0x94, 0xd0, 0xb4, 0xee, 0x4b, 0x60, 0x5a, 0xad, 0x64, 0x91,
+    0x79, 0x0b, 0xb4, 0xea, 0xc5, 0x4e, 0x63, 0xf6, 0x68, 0xf5, 0x16, 0x51, 0x8d, 0x3d, 0x14, 0x78,
+    0x54, 0xac, 0xb5, 0xd4, 0xc2, 0x7d, 0x87, 0x4f, 0xe1, 0x7a, 0x60, 0xc4, 0x93, 0xb0, 0xb9, 0xc6,
+    0x76, 0xdf, 0xd1, 0x18, 0xb9, 0xab, 0xc2, 0x8a, 0xd5, 0xfc, 0xe7, 0x8d, 0x32, 0xf1, 0x44, 0x7d,
+    0xee, 0xb0, 0xa1, 0xac, 0xa5, 0xc3, 0x3c, 0x8b, 0xd0, 0x4c, 0xaf, 0x9c, 0xae, 0xe9, 0x09, 0xd4,
+    0xc6, 0x51, 0xa1, 0xdb, 0xa3, 0x60, 0x30, 0x5e, 0x30, 0x0c, 0x06, 0x03, 0x55, 0x1d, 0x13, 0x01,
+    0x01, 0xff, 0x04, 0x02, 0x30, 0x00, 0x30, 0x0e, 0x06, 0x03, 0x55, 0x1d, 0x0f, 0x01, 0x01, 0xff,
+    0x04, 0x04, 0x03, 0x02, 0x07, 0x80, 0x30, 0x1d, 0x06, 0x03, 0x55, 0x1d, 0x0e, 0x04, 0x16, 0x04,
+    0x14, 0xea, 0x8a, 0xba, 0x5b, 0x15, 0xe1, 0xc8, 0xbc, 0xb0, 0x4f, 0xc4, 0x98, 0x32, 0x46, 0x32,
+    0x85, 0x41, 0xbe, 0x73, 0x12, 0x30, 0x1f, 0x06, 0x03, 0x55, 0x1d, 0x23, 0x04, 0x18, 0x30, 0x16,
+    0x80, 0x14, 0x08, 0x91, 0xbf, 0x3d, 0x3d, 0xb3, 0x21, 0x7c, 0xf4, 0xa2, 0x0d, 0xce, 0x3e, 0x5a,
+    0x01, 0x48, 0xb4, 0x0a, 0x67, 0x5a, 0x30, 0x0a, 0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04,
+    0x03, 0x03, 0x03, 0x69, 0x00, 0x30, 0x66, 0x02, 0x31, 0x00, 0xec, 0x51, 0xdb, 0xad, 0xb9, 0x83,
+    0x2c, 0x20, 0x6e, 0xf3, 0x7e, 0x81, 0xa0, 0xc6, 0x87, 0xa8, 0xa9, 0xff, 0x21, 0x1c, 0x74, 0x62,
+    0x8d, 0x4f, 0xfc, 0xf7, 0x12, 0xe3, 0x08, 0xda, 0xb9, 0xfb, 0x0b, 0xb6, 0x7f, 0x8b, 0x57, 0x0b,
+    0xc1, 0xb4, 0xf7, 0x35, 0x5b, 0x1e, 0x6a, 0x3f, 0x9e, 0xdc, 0x02, 0x31, 0x00, 0x88, 0x46, 0x1c,
+    0x8d, 0x05, 0x52, 0xc0, 0xb4, 0xc6, 0x91, 0x59, 0x4e, 0xef, 0x0e, 0x03, 0xda, 0x3b, 0xfa, 0x13,
+    0x1b, 0x1a, 0x37, 0xf1, 0xb6, 0x71, 0x7a, 0x99, 0x4a, 0x75, 0x9d, 0xaf, 0x0b, 0x86, 0x51, 0xec,
+    0x73, 0xbb, 0x8e, 0x32, 0x6f, 0x84, 0x42, 0xf9, 0x6f, 0xad, 0x4e, 0xbb, 0xaa,
+];
+
+/// PKCS#8 ECC P-384 private key of `EMULATOR_REQUESTER_LEAF_CERT_DER`.
+pub static EMULATOR_REQUESTER_LEAF_KEY_DER: [u8; 185] = [
+    0x30, 0x81, 0xb6, 0x02, 0x01, 0x00, 0x30, 0x10, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02,
+    0x01, 0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x22, 0x04, 0x81, 0x9e, 0x30, 0x81, 0x9b, 0x02, 0x01,
+    0x01, 0x04, 0x30, 0xae, 0xe9, 0x85, 0xf3, 0x89, 0x81, 0x59, 0x2b, 0x45, 0x16, 0xee, 0x46, 0x3f,
+    0x15, 0xd5, 0x10, 0xab, 0xac, 0xb3, 0x03, 0xe9, 0x9f, 0xad, 0x76, 0x96, 0x5f, 0x89, 0x3e, 0xb0,
+    0x14, 0x47, 0x84, 0xee, 0x3e, 0xea, 0x2b, 0xd7, 0x32, 0xb1, 0x34, 0xc1, 0x21, 0xf8, 0xd7, 0x3a,
+    0x40, 0x61, 0x55, 0xa1, 0x64, 0x03, 0x62, 0x00, 0x04, 0x59, 0xa6, 0x0a, 0x00, 0x24, 0x6d, 0x04,
+    0x9c, 0x53, 0xa3, 0xb5, 0xf8, 0xb6, 0x96, 0xad, 0x00, 0x29, 0x28, 0x94, 0xd0, 0xb4, 0xee, 0x4b,
+    0x60, 0x5a, 0xad, 0x64, 0x91, 0x79, 0x0b, 0xb4, 0xea, 0xc5, 0x4e, 0x63, 0xf6, 0x68, 0xf5, 0x16,
+    0x51, 0x8d, 0x3d, 0x14, 0x78, 0x54, 0xac, 0xb5, 0xd4, 0xc2, 0x7d, 0x87, 0x4f, 0xe1, 0x7a, 0x60,
+    0xc4, 0x93, 0xb0, 0xb9, 0xc6, 0x76, 0xdf, 0xd1, 0x18, 0xb9, 0xab, 0xc2, 0x8a, 0xd5, 0xfc, 0xe7,
+    0x8d, 0x32, 0xf1, 0x44, 0x7d, 0xee, 0xb0, 0xa1, 0xac, 0xa5, 0xc3, 0x3c, 0x8b, 0xd0, 0x4c, 0xaf,
+    0x9c, 0xae, 0xe9, 0x09, 0xd4, 0xc6, 0x51, 0xa1, 0xdb,
+];
+
+/// Requester configuration that trusts the emulator's slot 0 root CA. The slot 0 chain
+/// is ECC P-384 only, so ML-DSA-87 is not offered. The Requester authenticates with the
+/// test Requester certificate chain if the Responder requests mutual authentication.
+pub fn emulator_requester_config() -> RequesterConfig {
+    let mut config = RequesterConfig {
+        trusted_roots: vec![EMULATOR_ROOT_CA_CERT_DER.to_vec()],
+        identity: Some(RequesterIdentity {
+            certs: vec![
+                EMULATOR_REQUESTER_ROOT_CA_CERT_DER.to_vec(),
+                EMULATOR_REQUESTER_LEAF_CERT_DER.to_vec(),
+            ],
+            private_key: EMULATOR_REQUESTER_LEAF_KEY_DER.to_vec(),
+        }),
+        ..Default::default()
+    };
+    config.capabilities.flags.set_cert_cap(1);
+    config.capabilities.flags.set_mut_auth_cap(1);
+    config.capabilities.flags.set_encap_cap(1);
+    config.algorithms.set_req_base_asym_algo();
+    config.algorithms.pqc_asym_algo = Default::default();
+    config
+}
+
+/// SPDM over MCTP. Secured messages use the secured SPDM MCTP message type.
+pub struct MctpSpdmTransport {
+    transport: MctpTransport,
+}
+
+impl MctpSpdmTransport {
+    pub fn new(stream: BufferedStream, target_addr: u8, retry_count: usize) -> Self {
+        Self {
+            transport: MctpTransport::new(stream, target_addr, retry_count),
+        }
+    }
+}
+
+impl SpdmTransport for MctpSpdmTransport {
+    fn send_receive(&mut self, msg: &[u8], secure: bool) -> SpdmRequesterResult<Vec<u8>> {
+        let msg_type = if secure {
+            MCTP_MSG_TYPE_SECURE_SPDM
+        } else {
+            MCTP_MSG_TYPE_SPDM
+        };
+        let mut req = vec![msg_type];
+        req.extend_from_slice(msg);
+
+        let rsp = self
+            .transport
+            .target_send_and_receive(&req, false)
+            .ok_or(SpdmRequesterError::Transport)?;
+        match rsp.split_first() {
+            Some((rsp_type, payload)) if *rsp_type == msg_type => Ok(payload.to_vec()),
+            _ => Err(SpdmRequesterError::Transport),
+        }
+    }
+}
+
+/// SPDM over DOE. Messages are padded to a dword boundary and secured messages use
+/// the secured SPDM data object type.
+pub struct DoeSpdmTransport {
+    transport: DoeTransport,
+}
+
+impl DoeSpdmTransport {
+    pub fn new(tx: Sender<Vec<u8>>, rx: Receiver<Vec<u8>>, retry_count: usize) -> Self {
+        Self {
+            transport: DoeTransport::new(tx, rx, retry_count),
+        }
+    }
+}
+
+impl SpdmTransport for DoeSpdmTransport {
+    fn send_receive(&mut self, msg: &[u8], secure: bool) -> SpdmRequesterResult<Vec<u8>> {
+        let object_type = if secure {
+            DataObjectType::DoeSecureSpdm
+        } else {
+            DataObjectType::DoeSpdm
+        };
+        let padded_len = msg.len().next_multiple_of(4);
+        let header = DoeHeader::new(
+            object_type,
+            (DOE_DATA_OBJECT_HEADER_LEN + padded_len) as u32,
+        );
+        let mut req = header.as_bytes().to_vec();
+        req.extend_from_slice(msg);
+        req.resize(DOE_DATA_OBJECT_HEADER_LEN + padded_len, 0);
+
+        let rsp = self
+            .transport
+            .target_send_and_receive(&req, false)
+            .ok_or(SpdmRequesterError::Transport)?;
+        if rsp.len() <= DOE_DATA_OBJECT_HEADER_LEN
+            || rsp[DOE_DATA_OBJECT_TYPE_OFFSET] != object_type as u8
+        {
+            Err(SpdmRequesterError::Transport)?;
+        }
+        Ok(rsp[DOE_DATA_OBJECT_HEADER_LEN..].to_vec())
+    }
+}
+
+/// Sets up two mutually authenticated sessions with interleaved handshakes and exchanges
+/// secured messages on both, then checks that each session still decrypts its own
+/// traffic, also after KEY_UPDATE, and that an idle session is torn down once its
+/// heartbeat timeout passes.
+fn run_concurrent_sessions<T: SpdmTransport>(
+    requester: &mut SpdmRequester<T>,
+) -> SpdmRequesterResult<()> {
+    requester.init_connection()?;
+    requester.get_digests()?;
+    requester.get_certificate(0)?;
+
+    // Both handshakes are in progress before either completes, and finish in the
+    // reverse order.
+    let session_a = requester.key_exchange(0, 0)?;
+    let session_b = requester.key_exchange(0, 0)?;
+    println!(
+        "[{}]: Sessions {:#x} and {:#x} in the handshake phase",
+        DOE_SPDM_REQUESTER_TEST_NAME, session_a, session_b
+    );
+    // The Responder retrieved the Requester certificate chain of both sessions, which
+    // FINISH is then signed with
+    for session_id in [session_a, session_b] {
+        if requester
+            .session(session_id)
+            .and_then(|session| session.requester_slot_id())
+            .is_none()
+        {
+            Err(SpdmRequesterError::NegotiationFailed)?;
+        }
+    }
+    requester.finish(session_b)?;
+    requester.finish(session_a)?;
+
+    let version = u8::from(
+        requester
+            .version()
+            .ok_or(SpdmRequesterError::UnexpectedState)?,
+    );
+    let digest = *requester
+        .digest(0)
+        .ok_or(SpdmRequesterError::UnexpectedState)?;
+    for session_id in [session_a, session_b, session_a, session_b] {
+        let rsp = requester.send_secured(session_id, &[version, GET_DIGESTS, 0, 0])?;
+        if rsp.get(1) != Some(&DIGESTS)
+            || rsp.get(SPDM_MSG_HEADER_LEN..SPDM_MSG_HEADER_LEN + digest.len()) != Some(&digest[..])
+        {
+            Err(SpdmRequesterError::InvalidResponse)?;
+        }
+        println!(
+            "[{}]: Secured GET_DIGESTS on session {:#x} succeeded",
+            DOE_SPDM_REQUESTER_TEST_NAME, session_id
+        );
+    }
+
+    // Session A updates the request direction key only, session B both directions
+    requester.key_update(session_a, false)?;
+    requester.key_update(session_b, true)?;
+    for session_id in [session_a, session_b] {
+        requester.heartbeat(session_id)?;
+        println!(
+            "[{}]: KEY_UPDATE and HEARTBEAT on session {:#x} succeeded",
+            DOE_SPDM_REQUESTER_TEST_NAME, session_id
+        );
+    }
+    requester.end_session(session_a)?;
+
+    // Session B is torn down if no message is received within twice its heartbeat period
+    let heartbeat_period = requester
+        .session(session_b)
+        .ok_or(SpdmRequesterError::UnexpectedState)?
+        .heartbeat_period();
+    if heartbeat_period == 0 {
+        Err(SpdmRequesterError::NegotiationFailed)?;
+    }
+    sleep_emulator_ticks((2 * u32::from(heartbeat_period) + 1) * EMULATOR_TICKS_PER_SECOND);
+    match requester.heartbeat(session_b) {
+        // The Responder does not answer secured messages of unknown sessions
+        Err(SpdmRequesterError::Transport) => {
+            println!(
+                "[{}]: Session {:#x} expired after its heartbeat timeout",
+                DOE_SPDM_REQUESTER_TEST_NAME, session_b
+            );
+        }
+        _ => Err(SpdmRequesterError::InvalidResponse)?,
+    }
+
+    // The Responder keeps serving requests outside of the expired session
+    requester.get_digests()?;
+    Ok(())
+}
+
+/// Runs the host-side SPDM requester against the emulator DOE responder with two
+/// concurrent secure sessions.
+pub fn run_doe_spdm_requester_test(
+    tx: Sender<Vec<u8>>,
+    rx: Receiver<Vec<u8>>,
+    test_timeout_seconds: Duration,
+) {
+    let transport = DoeSpdmTransport::new(tx, rx, 1);
+
+    thread::spawn(move || {
+        thread::sleep(test_timeout_seconds);
+        println!(
+            "[{}] TIMED OUT AFTER {:?} SECONDS",
+            DOE_SPDM_REQUESTER_TEST_NAME,
+            test_timeout_seconds.as_secs()
+        );
+        exit(-1);
+    });
+
+    thread::spawn(move || {
+        wait_for_runtime_start();
+        // give time for the app to be loaded and ready
+        sleep_emulator_ticks(1_000_000);
+
+        if !MCU_RUNNING.load(Ordering::Relaxed) {
+            exit(-1);
+        }
+
+        let mut requester = SpdmRequester::new(transport, emulator_requester_config());
+        match run_concurrent_sessions(&mut requester) {
+            Ok(()) => {
+                println!(
+                    "[{}]: Spdm Requester Test Passed",
+                    DOE_SPDM_REQUESTER_TEST_NAME
+                );
+                exit(0);
+            }
+            Err(e) => {
+                println!(
+                    "[{}]: Spdm Requester Test Failed: {:?}",
+                    DOE_SPDM_REQUESTER_TEST_NAME, e
+                );
+                exit(-1);
+            }
+        }
+    });
+}
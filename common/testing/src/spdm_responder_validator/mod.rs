@@ -3,7 +3,7 @@
 mod common;
 pub mod doe;
 pub mod mctp;
-mod transport;
+pub(crate) mod transport;
 
 pub enum SpdmTestType {
     SpdmResponderConformance,
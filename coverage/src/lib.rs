@@ -1,5 +1,11 @@
 // Licensed under the Apache-2.0 license
 
+mod report;
+mod source;
+
+pub use report::{FileCoverage, SourceCoverage};
+pub use source::{Function, LineRange, SourceMap};
+
 use anyhow::Context;
 use bit_vec::BitVec;
 use elf::endian::AnyEndian;
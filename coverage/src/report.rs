@@ -0,0 +1,417 @@
+// Licensed under the Apache-2.0 license
+
+//! Line and function coverage of the source files, written as lcov,
+//! Cobertura XML or HTML.
+
+use crate::source::SourceMap;
+use bit_vec::BitVec;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{self, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Default)]
+pub struct FileCoverage {
+    /// Hit count of each line with code.
+    pub lines: BTreeMap<u32, u64>,
+    /// First line and hit count of each function.
+    pub functions: BTreeMap<String, (u32, u64)>,
+}
+
+impl FileCoverage {
+    pub fn line_totals(&self) -> (usize, usize) {
+        let hit = self.lines.values().filter(|hits| **hits > 0).count();
+        (hit, self.lines.len())
+    }
+
+    pub fn function_totals(&self) -> (usize, usize) {
+        let hit = self.functions.values().filter(|f| f.1 > 0).count();
+        (hit, self.functions.len())
+    }
+}
+
+/// Coverage of the source files, summed over the images and runs added.
+/// A hit count is the number of bitmaps that executed the line or function.
+#[derive(Debug, Default)]
+pub struct SourceCoverage {
+    pub files: BTreeMap<PathBuf, FileCoverage>,
+}
+
+fn executed(base_addr: u32, bitmap: &BitVec, addrs: &Range<u32>) -> bool {
+    addrs.clone().any(|addr| {
+        bitmap
+            .get(addr.wrapping_sub(base_addr) as usize)
+            .unwrap_or(false)
+    })
+}
+
+fn rate(hit: usize, total: usize) -> f64 {
+    if total > 0 {
+        hit as f64 / total as f64
+    } else {
+        0.0
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl SourceCoverage {
+    /// Add the coverage of an image. Bit `n` of `bitmap` is set if the
+    /// instruction at `base_addr + n` was executed.
+    pub fn add(&mut self, map: &SourceMap, base_addr: u32, bitmap: &BitVec) {
+        let mut lines = HashMap::<(usize, u32), bool>::new();
+        for line in map.lines.iter() {
+            *lines.entry((line.file, line.line)).or_default() |=
+                executed(base_addr, bitmap, &line.addrs);
+        }
+        for ((file, line), hit) in lines {
+            let file = self.files.entry(map.files[file].clone()).or_default();
+            *file.lines.entry(line).or_default() += hit as u64;
+        }
+        for function in map.functions.iter() {
+            let Some((file, line)) = function.location else {
+                continue;
+            };
+            let hit = executed(base_addr, bitmap, &function.addrs);
+            let file = self.files.entry(map.files[file].clone()).or_default();
+            file.functions
+                .entry(function.name.clone())
+                .or_insert((line, 0))
+                .1 += hit as u64;
+        }
+    }
+
+    /// Keep the files under `root`, with paths relative to it.
+    pub fn retain_under(&mut self, root: &Path) {
+        self.files = std::mem::take(&mut self.files)
+            .into_iter()
+            .filter_map(|(path, file)| Some((path.strip_prefix(root).ok()?.to_path_buf(), file)))
+            .collect();
+    }
+
+    pub fn line_totals(&self) -> (usize, usize) {
+        self.files
+            .values()
+            .map(FileCoverage::line_totals)
+            .fold((0, 0), |a, b| (a.0 + b.0, a.1 + b.1))
+    }
+
+    pub fn function_totals(&self) -> (usize, usize) {
+        self.files
+            .values()
+            .map(FileCoverage::function_totals)
+            .fold((0, 0), |a, b| (a.0 + b.0, a.1 + b.1))
+    }
+
+    pub fn write_lcov(&self, out: &mut impl Write) -> io::Result<()> {
+        writeln!(out, "TN:")?;
+        for (path, file) in self.files.iter() {
+            writeln!(out, "SF:{}", path.display())?;
+            for (name, (line, _)) in file.functions.iter() {
+                writeln!(out, "FN:{},{}", line, name)?;
+            }
+            for (name, (_, hits)) in file.functions.iter() {
+                writeln!(out, "FNDA:{},{}", hits, name)?;
+            }
+            let (hit, total) = file.function_totals();
+            writeln!(out, "FNF:{}", total)?;
+            writeln!(out, "FNH:{}", hit)?;
+            for (line, hits) in file.lines.iter() {
+                writeln!(out, "DA:{},{}", line, hits)?;
+            }
+            let (hit, total) = file.line_totals();
+            writeln!(out, "LF:{}", total)?;
+            writeln!(out, "LH:{}", hit)?;
+            writeln!(out, "end_of_record")?;
+        }
+        Ok(())
+    }
+
+    /// Cobertura XML with one package per directory and one class per file.
+    pub fn write_cobertura(&self, out: &mut impl Write, source_root: &Path) -> io::Result<()> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let (hit, total) = self.line_totals();
+        writeln!(out, r#"<?xml version="1.0" ?>"#)?;
+        writeln!(
+            out,
+            r#"<!DOCTYPE coverage SYSTEM "http://cobertura.sourceforge.net/xml/coverage-04.dtd">"#
+        )?;
+        writeln!(
+            out,
+            r#"<coverage line-rate="{:.4}" branch-rate="0" lines-covered="{}" lines-valid="{}" branches-covered="0" branches-valid="0" complexity="0" version="1.9" timestamp="{}">"#,
+            rate(hit, total),
+            hit,
+            total,
+            timestamp
+        )?;
+        writeln!(out, "  <sources>")?;
+        writeln!(
+            out,
+            "    <source>{}</source>",
+            escape(&source_root.display().to_string())
+        )?;
+        writeln!(out, "  </sources>")?;
+        writeln!(out, "  <packages>")?;
+
+        let mut packages = BTreeMap::<String, Vec<(&PathBuf, &FileCoverage)>>::new();
+        for (path, file) in self.files.iter() {
+            let package = path
+                .parent()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default();
+            packages.entry(package).or_default().push((path, file));
+        }
+        for (package, files) in packages {
+            let (hit, total) = files
+                .iter()
+                .map(|(_, f)| f.line_totals())
+                .fold((0, 0), |a, b| (a.0 + b.0, a.1 + b.1));
+            writeln!(
+                out,
+                r#"    <package name="{}" line-rate="{:.4}" branch-rate="0" complexity="0">"#,
+                escape(&package),
+                rate(hit, total)
+            )?;
+            writeln!(out, "      <classes>")?;
+            for (path, file) in files {
+                let (hit, total) = file.line_totals();
+                let name = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                writeln!(
+                    out,
+                    r#"        <class name="{}" filename="{}" line-rate="{:.4}" branch-rate="0" complexity="0">"#,
+                    escape(&name),
+                    escape(&path.display().to_string()),
+                    rate(hit, total)
+                )?;
+                writeln!(out, "          <methods>")?;
+                for (name, (line, hits)) in file.functions.iter() {
+                    writeln!(
+                        out,
+                        r#"            <method name="{}" signature="" line-rate="{}" branch-rate="0" complexity="0">"#,
+                        escape(name),
+                        if *hits > 0 { 1 } else { 0 }
+                    )?;
+                    writeln!(
+                        out,
+                        r#"              <lines><line number="{}" hits="{}"/></lines>"#,
+                        line, hits
+                    )?;
+                    writeln!(out, "            </method>")?;
+                }
+                writeln!(out, "          </methods>")?;
+                writeln!(out, "          <lines>")?;
+                for (line, hits) in file.lines.iter() {
+                    writeln!(
+                        out,
+                        r#"            <line number="{}" hits="{}"/>"#,
+                        line, hits
+                    )?;
+                }
+                writeln!(out, "          </lines>")?;
+                writeln!(out, "        </class>")?;
+            }
+            writeln!(out, "      </classes>")?;
+            writeln!(out, "    </package>")?;
+        }
+        writeln!(out, "  </packages>")?;
+        writeln!(out, "</coverage>")?;
+        Ok(())
+    }
+
+    /// Write an HTML report to `dir`: `index.html` with the totals of each
+    /// file, and a page per file with its source annotated when the file can
+    /// be read from `source_root`.
+    pub fn write_html(&self, dir: &Path, source_root: &Path) -> io::Result<()> {
+        const STYLE: &str = "<style>\
+            body { font-family: sans-serif; }\
+            table { border-collapse: collapse; }\
+            td, th { padding: 0 8px; text-align: left; }\
+            pre { margin: 0; }\
+            .hit { background: #c8f0c8; }\
+            .miss { background: #f8c8c8; }\
+            .num { text-align: right; color: #666; }\
+            </style>";
+
+        fs::create_dir_all(dir.join("files"))?;
+        let (lines_hit, lines_total) = self.line_totals();
+        let (functions_hit, functions_total) = self.function_totals();
+        let mut index = String::new();
+        index.push_str(
+            "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>Coverage</title>",
+        );
+        index.push_str(STYLE);
+        index.push_str("</head><body>\n<h1>Coverage</h1>\n");
+        index.push_str(&format!(
+            "<p>Lines: {} / {} ({:.1}%), functions: {} / {} ({:.1}%)</p>\n",
+            lines_hit,
+            lines_total,
+            100.0 * rate(lines_hit, lines_total),
+            functions_hit,
+            functions_total,
+            100.0 * rate(functions_hit, functions_total)
+        ));
+        index
+            .push_str("<table>\n<tr><th>File</th><th>Lines</th><th></th><th>Functions</th></tr>\n");
+
+        for (i, (path, file)) in self.files.iter().enumerate() {
+            let page = format!("files/{}.html", i);
+            let (hit, total) = file.line_totals();
+            let (fn_hit, fn_total) = file.function_totals();
+            let class = if hit == total { "hit" } else { "miss" };
+            index.push_str(&format!(
+                "<tr><td><a href=\"{}\">{}</a></td><td class=\"{}\">{:.1}%</td><td class=\"num\">{} / {}</td><td class=\"num\">{} / {}</td></tr>\n",
+                page,
+                escape(&path.display().to_string()),
+                class,
+                100.0 * rate(hit, total),
+                hit,
+                total,
+                fn_hit,
+                fn_total
+            ));
+
+            let mut html = String::new();
+            html.push_str(&format!(
+                "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{0}</title>{1}</head><body>\n<p><a href=\"../index.html\">index</a></p>\n<h1>{0}</h1>\n<table>\n",
+                escape(&path.display().to_string()),
+                STYLE
+            ));
+            match fs::read_to_string(source_root.join(path)) {
+                Ok(source) => {
+                    for (n, text) in source.lines().enumerate() {
+                        let n = n as u32 + 1;
+                        let (class, hits) = match file.lines.get(&n) {
+                            Some(0) => ("miss", "0".to_string()),
+                            Some(hits) => ("hit", hits.to_string()),
+                            None => ("", String::new()),
+                        };
+                        html.push_str(&format!(
+                            "<tr class=\"{}\"><td class=\"num\">{}</td><td class=\"num\">{}</td><td><pre>{}</pre></td></tr>\n",
+                            class,
+                            n,
+                            hits,
+                            escape(text)
+                        ));
+                    }
+                }
+                Err(_) => {
+                    for (n, hits) in file.lines.iter() {
+                        let class = if *hits > 0 { "hit" } else { "miss" };
+                        html.push_str(&format!(
+                            "<tr class=\"{}\"><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+                            class, n, hits
+                        ));
+                    }
+                }
+            }
+            html.push_str("</table>\n</body></html>\n");
+            fs::write(dir.join(page), html)?;
+        }
+
+        index.push_str("</table>\n</body></html>\n");
+        fs::write(dir.join("index.html"), index)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::source::{Function, LineRange};
+
+    fn source_map() -> SourceMap {
+        SourceMap {
+            files: vec![PathBuf::from("/src/rom/main.rs")],
+            lines: vec![
+                LineRange {
+                    addrs: 0x100..0x104,
+                    file: 0,
+                    line: 10,
+                },
+                LineRange {
+                    addrs: 0x104..0x108,
+                    file: 0,
+                    line: 11,
+                },
+                LineRange {
+                    addrs: 0x108..0x10a,
+                    file: 0,
+                    line: 10,
+                },
+            ],
+            functions: vec![
+                Function {
+                    name: "main".into(),
+                    addrs: 0x100..0x108,
+                    location: Some((0, 10)),
+                },
+                Function {
+                    name: "unused".into(),
+                    addrs: 0x108..0x10a,
+                    location: Some((0, 10)),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_lcov() {
+        let map = source_map();
+        let mut bitmap = BitVec::from_elem(0x20, false);
+        // Only the instruction at 0x104.
+        bitmap.set(0x4, true);
+
+        let mut coverage = SourceCoverage::default();
+        coverage.add(&map, 0x100, &bitmap);
+        coverage.add(&map, 0x100, &bitmap);
+        coverage.retain_under(Path::new("/src"));
+        assert_eq!(coverage.line_totals(), (1, 2));
+        assert_eq!(coverage.function_totals(), (1, 2));
+
+        let mut lcov = vec![];
+        coverage.write_lcov(&mut lcov).unwrap();
+        assert_eq!(
+            String::from_utf8(lcov).unwrap(),
+            "TN:\n\
+             SF:rom/main.rs\n\
+             FN:10,main\n\
+             FN:10,unused\n\
+             FNDA:2,main\n\
+             FNDA:0,unused\n\
+             FNF:2\n\
+             FNH:1\n\
+             DA:10,0\n\
+             DA:11,2\n\
+             LF:2\n\
+             LH:1\n\
+             end_of_record\n"
+        );
+    }
+
+    #[test]
+    fn test_cobertura() {
+        let mut bitmap = BitVec::from_elem(0x20, false);
+        bitmap.set(0x0, true);
+        let mut coverage = SourceCoverage::default();
+        coverage.add(&source_map(), 0x100, &bitmap);
+
+        let mut xml = vec![];
+        coverage.write_cobertura(&mut xml, Path::new("/")).unwrap();
+        let xml = String::from_utf8(xml).unwrap();
+        assert!(xml.contains(r#"lines-covered="1" lines-valid="2""#));
+        assert!(xml.contains(r#"<package name="/src/rom" line-rate="0.5000""#));
+        assert!(xml.contains(r#"<line number="10" hits="1"/>"#));
+        assert!(xml.contains(r#"<line number="11" hits="0"/>"#));
+    }
+}
@@ -0,0 +1,215 @@
+// Licensed under the Apache-2.0 license
+
+//! Mapping of instruction addresses to source lines and functions, read from
+//! the DWARF line tables and the symbol table of an ELF file.
+
+use anyhow::{bail, Context};
+use elf::abi::{SHF_ALLOC, SHF_EXECINSTR, STT_FUNC};
+use elf::endian::AnyEndian;
+use elf::ElfBytes;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::PathBuf;
+
+/// Instructions generated for one source line.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LineRange {
+    pub addrs: Range<u32>,
+    /// Index in [`SourceMap::files`].
+    pub file: usize,
+    pub line: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Function {
+    pub name: String,
+    pub addrs: Range<u32>,
+    /// File index and line of the first instruction, if known.
+    pub location: Option<(usize, u32)>,
+}
+
+/// Source lines and functions of the code in an ELF file.
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    pub files: Vec<PathBuf>,
+    /// Sorted by start address.
+    pub lines: Vec<LineRange>,
+    pub functions: Vec<Function>,
+}
+
+impl SourceMap {
+    pub fn from_elf(elf_bytes: &[u8]) -> anyhow::Result<Self> {
+        let elf_file = ElfBytes::<AnyEndian>::minimal_parse(elf_bytes)
+            .with_context(|| "Failed to parse ELF file")?;
+
+        // Line table entries of code discarded by the linker point outside
+        // of the loaded code, so only the executable sections are kept.
+        let code: Vec<Range<u32>> = elf_file
+            .section_headers()
+            .with_context(|| "No section headers found")?
+            .iter()
+            .filter(|s| {
+                s.sh_flags & (SHF_ALLOC | SHF_EXECINSTR) as u64
+                    == (SHF_ALLOC | SHF_EXECINSTR) as u64
+            })
+            .map(|s| s.sh_addr as u32..(s.sh_addr + s.sh_size) as u32)
+            .collect();
+        let in_code = |addrs: &Range<u32>| {
+            code.iter()
+                .any(|c| c.start <= addrs.start && addrs.end <= c.end)
+        };
+
+        let mut map = Self::default();
+        map.read_lines(&elf_file)?;
+        map.lines.retain(|l| in_code(&l.addrs));
+        map.lines.sort_by_key(|l| l.addrs.start);
+
+        let (symtab, strtab) = elf_file
+            .symbol_table()
+            .with_context(|| "Failed to read symbol table")?
+            .with_context(|| "No symbol table found")?;
+        for sym in symtab.iter() {
+            if sym.st_symtype() != STT_FUNC || sym.st_size == 0 {
+                continue;
+            }
+            let addrs = sym.st_value as u32..(sym.st_value + sym.st_size) as u32;
+            if !in_code(&addrs) {
+                continue;
+            }
+            map.functions.push(Function {
+                name: strtab
+                    .get(sym.st_name as usize)
+                    .unwrap_or("???")
+                    .to_string(),
+                location: map.location(addrs.start),
+                addrs,
+            });
+        }
+        map.functions.sort_by_key(|f| f.addrs.start);
+        map.functions.dedup_by_key(|f| f.addrs.start);
+        Ok(map)
+    }
+
+    fn read_lines(&mut self, elf_file: &ElfBytes<AnyEndian>) -> anyhow::Result<()> {
+        let endian = match elf_file.ehdr.endianness {
+            AnyEndian::Little => gimli::RunTimeEndian::Little,
+            AnyEndian::Big => gimli::RunTimeEndian::Big,
+        };
+        let load_section = |id: gimli::SectionId| -> anyhow::Result<Cow<[u8]>> {
+            let Some(header) = elf_file.section_header_by_name(id.name())? else {
+                return Ok(Cow::Borrowed(&[]));
+            };
+            let (data, compression) = elf_file.section_data(&header)?;
+            if compression.is_some() {
+                bail!("Compressed section {} is not supported", id.name());
+            }
+            Ok(Cow::Borrowed(data))
+        };
+        let sections = gimli::DwarfSections::load(load_section)?;
+        let dwarf = sections.borrow(|section| gimli::EndianSlice::new(section, endian));
+
+        let mut file_indices = HashMap::new();
+        let mut units = dwarf.units();
+        while let Some(header) = units.next()? {
+            let unit = dwarf.unit(header)?;
+            let Some(program) = unit.line_program.clone() else {
+                continue;
+            };
+            let comp_dir = unit
+                .comp_dir
+                .map(|dir| PathBuf::from(dir.to_string_lossy().as_ref()))
+                .unwrap_or_default();
+
+            // File index in the line program header to index in self.files.
+            let mut unit_files = HashMap::new();
+            let mut rows = program.rows();
+            let mut prev: Option<(u64, usize, u32)> = None;
+            while let Some((header, row)) = rows.next_row()? {
+                if let Some((start, file, line)) = prev.take() {
+                    if row.address() > start && line != 0 {
+                        self.lines.push(LineRange {
+                            addrs: start as u32..row.address() as u32,
+                            file,
+                            line,
+                        });
+                    }
+                }
+                if row.end_sequence() {
+                    continue;
+                }
+                let file = match unit_files.get(&row.file_index()) {
+                    Some(&file) => file,
+                    None => {
+                        let Some(entry) = row.file(header) else {
+                            continue;
+                        };
+                        let mut path = comp_dir.clone();
+                        if let Some(dir) = entry.directory(header) {
+                            path.push(dwarf.attr_string(&unit, dir)?.to_string_lossy().as_ref());
+                        }
+                        path.push(
+                            dwarf
+                                .attr_string(&unit, entry.path_name())?
+                                .to_string_lossy()
+                                .as_ref(),
+                        );
+                        let next = self.files.len();
+                        let file = *file_indices.entry(path.clone()).or_insert(next);
+                        if file == next {
+                            self.files.push(path);
+                        }
+                        unit_files.insert(row.file_index(), file);
+                        file
+                    }
+                };
+                let line = row.line().map_or(0, |line| line.get() as u32);
+                prev = Some((row.address(), file, line));
+            }
+        }
+        Ok(())
+    }
+
+    /// File index and line of the instruction at `addr`.
+    pub fn location(&self, addr: u32) -> Option<(usize, u32)> {
+        let i = self.lines.partition_point(|l| l.addrs.start <= addr);
+        let line = self.lines.get(i.checked_sub(1)?)?;
+        line.addrs.contains(&addr).then_some((line.file, line.line))
+    }
+
+    /// Addresses covered by the line tables.
+    pub fn address_range(&self) -> Option<Range<u32>> {
+        let start = self.lines.first()?.addrs.start;
+        let end = self.lines.iter().map(|l| l.addrs.end).max()?;
+        Some(start..end)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_source_map_from_test_binary() {
+        let exe = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+        let map = SourceMap::from_elf(&exe).unwrap();
+
+        let file = map
+            .files
+            .iter()
+            .position(|f| f.ends_with("src/source.rs"))
+            .expect("source.rs not in the line tables");
+        let function = map
+            .functions
+            .iter()
+            .find(|f| f.name.contains("test_source_map_from_test_binary"))
+            .expect("test function not in the symbol table");
+        let (location_file, _) = function.location.unwrap();
+        assert_eq!(location_file, file);
+        assert!(map
+            .lines
+            .windows(2)
+            .all(|w| w[0].addrs.start <= w[1].addrs.start));
+        assert!(map.address_range().unwrap().contains(&function.addrs.start));
+    }
+}
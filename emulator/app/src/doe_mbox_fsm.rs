@@ -10,6 +10,9 @@ use std::time::Duration;
 
 const TEST_TIMEOUT: u64 = 120;
 
+/// Emulator ticks between two rounds of the state machine.
+pub const DOE_FSM_POLL_TICKS: u64 = 1000;
+
 #[derive(Debug, Clone, PartialEq)]
 enum DoeMboxState {
     Idle,
@@ -21,16 +24,35 @@ enum DoeMboxState {
 
 pub struct DoeMboxFsm {
     doe_mbox: DoeMboxPeriph,
+    stepped: bool,
+    // State machine and test channel when the emulator thread drives the FSM.
+    stepped_fsm: Option<(DoeMboxStateMachine, Receiver<Vec<u8>>)>,
 }
 
 impl DoeMboxFsm {
     pub fn new(doe_mbox: DoeMboxPeriph) -> Self {
-        Self { doe_mbox }
+        Self {
+            doe_mbox,
+            stepped: false,
+            stepped_fsm: None,
+        }
+    }
+
+    /// Run the state machine from the emulator thread through
+    /// [`Self::receive_message`], [`Self::deliver_message`] and
+    /// [`Self::on_event`] instead of a thread of its own.
+    pub fn set_stepped(&mut self) {
+        self.stepped = true;
     }
 
     pub fn start(&mut self) -> (Receiver<Vec<u8>>, Sender<Vec<u8>>) {
         let (test_to_fsm_tx, test_to_fsm_rx) = std::sync::mpsc::channel::<Vec<u8>>();
         let (fsm_to_test_tx, fsm_to_test_rx) = std::sync::mpsc::channel::<Vec<u8>>();
+        if self.stepped {
+            let fsm = DoeMboxStateMachine::new(self.doe_mbox.clone(), fsm_to_test_tx);
+            self.stepped_fsm = Some((fsm, test_to_fsm_rx));
+            return (fsm_to_test_rx, test_to_fsm_tx);
+        }
         let doe_mbox_clone = self.doe_mbox.clone();
 
         thread::spawn(move || {
@@ -46,11 +68,32 @@ impl DoeMboxFsm {
                 fsm.on_event();
 
                 // Small delay to prevent busy waiting
-                sleep_emulator_ticks(1000);
+                sleep_emulator_ticks(DOE_FSM_POLL_TICKS);
             }
         });
         (fsm_to_test_rx, test_to_fsm_tx)
     }
+
+    /// Takes the next message sent by the test, if the stepped FSM is running.
+    pub fn receive_message(&mut self) -> Option<Vec<u8>> {
+        let (_, rx) = self.stepped_fsm.as_ref()?;
+        rx.try_recv().ok()
+    }
+
+    /// Hands a message to the stepped FSM as if the test had sent it.
+    pub fn deliver_message(&mut self, message: Vec<u8>) {
+        match self.stepped_fsm.as_mut() {
+            Some((fsm, _)) => fsm.handle_outgoing_message(message),
+            None => println!("DOE_MBOX_FSM: Dropping message, the FSM is not running"),
+        }
+    }
+
+    /// Runs one round of the stepped FSM.
+    pub fn on_event(&mut self) {
+        if let Some((fsm, _)) = self.stepped_fsm.as_mut() {
+            fsm.on_event();
+        }
+    }
 }
 
 struct DoeMboxStateMachine {
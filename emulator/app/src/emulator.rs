@@ -15,6 +15,8 @@ Abstract:
 use crate::dis;
 use crate::doe_mbox_fsm;
 use crate::elf;
+use crate::io_replay::{IoInput, IoLog};
+use crate::profiler::{Profiler, SymbolTable};
 use crate::tests;
 use caliptra_api_types::DeviceLifecycle;
 use caliptra_emu_bus::BusMmio;
@@ -31,8 +33,10 @@ use caliptra_mcu_emulator_consts::{DEFAULT_CPU_ARGS, RAM_ORG, ROM_SIZE};
 #[allow(unused_imports)]
 use caliptra_mcu_emulator_periph::MciMailboxRequester;
 use caliptra_mcu_emulator_periph::{
-    CaliptraToExtBus, DoeMboxPeriph, DummyDoeMbox, DummyFlashCtrl, I3c, I3cController, LcCtrl, Mci,
-    McuRootBus, McuRootBusArgs, McuRootBusOffsets, Otp, OtpArgs,
+    snapshot::{McuMemories, SystemSnapshot},
+    CaliptraToExtBus, DoeMboxPeriph, DummyDoeMbox, DummyFlashCtrl, FaultHook, FaultInjector,
+    FaultPlan, I3c, I3cController, LcCtrl, Mci, McuRootBus, McuRootBusArgs, McuRootBusOffsets, Otp,
+    OtpArgs, PcieVdmController,
 };
 use caliptra_mcu_emulator_registers_generated::axicdma::AxicdmaPeripheral;
 use caliptra_mcu_emulator_registers_generated::root_bus::{AutoRootBus, AutoRootBusOffsets};
@@ -43,6 +47,7 @@ use caliptra_mcu_testing_common::i3c_socket;
 use caliptra_mcu_testing_common::i3c_socket_server::start_i3c_socket;
 use caliptra_mcu_testing_common::mctp_transport::MctpTransport;
 use caliptra_mcu_testing_common::mctp_util::base_protocol::LOCAL_TEST_ENDPOINT_EID;
+use caliptra_mcu_testing_common::pcie_vdm_socket_server::start_pcie_vdm_socket;
 use caliptra_mcu_testing_common::spdm_responder_validator::SpdmTestType;
 use caliptra_mcu_testing_common::{MCU_RUNNING, MCU_RUNTIME_STARTED, MCU_TICKS, TICK_COND};
 use clap::{ArgAction, Parser};
@@ -52,6 +57,7 @@ use std::cell::Cell;
 use std::cell::RefCell;
 use std::fs::File;
 use std::io::{self, IsTerminal, Read, Write};
+use std::mem::offset_of;
 use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::process::exit;
@@ -153,6 +159,12 @@ pub struct EmulatorArgs {
     #[arg(long)]
     pub i3c_port: Option<u16>,
 
+    /// TCP port for MCTP over PCIe VDM, carried through the DOE mailbox.
+    /// The runtime must be built with the `mctp-pcie-vdm` feature, which disables
+    /// SPDM over DOE.
+    #[arg(long)]
+    pub pcie_vdm_port: Option<u16>,
+
     /// Device lifecycle value (0=Unprovisioned, 1=Manufacturing, 2=Reserved, 3=Production).
     #[arg(long, value_parser = maybe_hex::<u32>, default_value_t = DeviceLifecycle::Production as u32)]
     pub device_security_state: u32,
@@ -295,6 +307,44 @@ pub struct EmulatorArgs {
     /// Selects which I3C core is used for MCTP transport.
     #[arg(long, default_value_t = false)]
     pub active_i3c1: bool,
+
+    /// Save a snapshot of the emulator state to this file when the emulator stops.
+    #[arg(long)]
+    pub save_snapshot: Option<PathBuf>,
+
+    /// Restore the emulator state from a snapshot file before running. The other
+    /// options must match the ones used when the snapshot was saved.
+    #[arg(long)]
+    pub load_snapshot: Option<PathBuf>,
+
+    /// JSON fault plan describing peripheral faults to inject while running.
+    #[arg(long)]
+    pub fault_plan: Option<PathBuf>,
+
+    /// Record every external input (I3C socket, console, DOE transport and BMC
+    /// recovery images) with the cycle at which it was consumed to this file.
+    #[arg(long)]
+    pub record_io: Option<PathBuf>,
+
+    /// Feed the inputs recorded with --record-io back at the same cycles instead
+    /// of reading the sockets and the console. The other options must match the
+    /// recorded run.
+    #[arg(long)]
+    pub replay_io: Option<PathBuf>,
+
+    /// Profile the MCU and Caliptra cores and write folded stacks, a Chrome
+    /// trace and the boot cycle budget to this directory when the emulator stops.
+    #[arg(long)]
+    pub profile_dir: Option<PathBuf>,
+
+    /// ELF files with MCU symbols for the profiler, in addition to the ROM and
+    /// firmware when those are ELF files.
+    #[arg(long)]
+    pub profile_elf: Vec<PathBuf>,
+
+    /// ELF files with Caliptra symbols for the profiler.
+    #[arg(long)]
+    pub caliptra_profile_elf: Vec<PathBuf>,
 }
 
 pub struct Emulator {
@@ -315,6 +365,8 @@ pub struct Emulator {
     pub i3c_controller: I3cController,
     #[allow(dead_code)]
     pub doe_mbox_fsm: doe_mbox_fsm::DoeMboxFsm,
+    #[allow(dead_code)]
+    pub pcie_vdm_controller: Option<PcieVdmController>,
     pub i3c_address: Option<u8>,
     pub i3c_controller_join_handle: Option<JoinHandle<()>>,
     // Synchronizes cross-thread timer scheduling (I3C / DOE controller
@@ -322,6 +374,16 @@ pub struct Emulator {
     pub step_lock: Arc<Mutex<()>>,
     /// Caliptra CPU is held until MCU ROM writes CPTRA_BOOT_GO
     pub cptra_boot_go: Rc<Cell<bool>>,
+    /// MCU memories and PIC location captured in snapshots.
+    pub memories: McuMemories,
+    pub pic_offset: u32,
+    /// Recording or replay of the external inputs, if enabled.
+    pub io_log: Option<IoLog>,
+    // Slot filled by the console thread. Separate from stdin_uart when recording.
+    console_input: Option<Arc<Mutex<Option<u8>>>>,
+    next_doe_fsm_poll: u64,
+    /// Guest profiler, if enabled.
+    pub profiler: Option<Profiler>,
 }
 
 impl Emulator {
@@ -456,7 +518,30 @@ impl Emulator {
             None
         };
 
-        let stdin_uart = if cli.stdin_uart && std::io::stdin().is_terminal() {
+        let mut io_log = match (&cli.record_io, &cli.replay_io) {
+            (Some(_), Some(_)) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--record-io and --replay-io cannot be used together",
+            ))?,
+            (Some(path), None) => {
+                println!("Recording external inputs to {}", path.display());
+                Some(IoLog::record(path)?)
+            }
+            (None, Some(path)) => {
+                println!("Replaying external inputs from {}", path.display());
+                Some(IoLog::replay(path)?)
+            }
+            (None, None) => None,
+        };
+        if io_log.is_some() && cli.pcie_vdm_port.is_some() {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "PCIe VDM input cannot be recorded or replayed",
+            ))?;
+        }
+        let replaying = io_log.as_ref().is_some_and(IoLog::is_replay);
+
+        let stdin_uart = if cli.stdin_uart && (std::io::stdin().is_terminal() || replaying) {
             Some(Arc::new(Mutex::new(None)))
         } else {
             None
@@ -556,6 +641,47 @@ impl Emulator {
             auto_root_bus_offsets.lc_size = lc_size;
         }
 
+        let profiler = match &cli.profile_dir {
+            Some(dir) => {
+                let mut mcu_symbols = SymbolTable::default();
+                for path in [args_rom, &cli.firmware] {
+                    mcu_symbols.add_file(path)?;
+                }
+                let mut caliptra_symbols = SymbolTable::default();
+                for (symbols, paths) in [
+                    (&mut mcu_symbols, &cli.profile_elf),
+                    (&mut caliptra_symbols, &cli.caliptra_profile_elf),
+                ] {
+                    for path in paths {
+                        if !symbols.add_file(path)? {
+                            Err(io::Error::new(
+                                io::ErrorKind::InvalidInput,
+                                format!("{} is not an ELF file", path.display()),
+                            ))?;
+                        }
+                    }
+                }
+                println!(
+                    "Profiling to {} with {} MCU and {} Caliptra symbols",
+                    dir.display(),
+                    mcu_symbols.len(),
+                    caliptra_symbols.len()
+                );
+                let flow_status_addr = auto_root_bus_offsets.mci_offset
+                    + offset_of!(
+                        caliptra_mcu_registers_generated::mci::regs::Mci,
+                        mci_reg_fw_flow_status
+                    ) as u32;
+                Some(Profiler::new(
+                    dir.clone(),
+                    flow_status_addr,
+                    mcu_symbols,
+                    caliptra_symbols,
+                ))
+            }
+            None => None,
+        };
+
         let mut straps = caliptra_mcu_config_emulator::EMULATOR_MCU_STRAPS;
         if cli.active_i3c1 {
             straps.active_i3c = 1;
@@ -590,6 +716,32 @@ impl Emulator {
                 .test_mcu_mbox_driver = true;
         }
 
+        let fault_injector = match &cli.fault_plan {
+            Some(path) => {
+                println!("Loading fault plan from {}", path.display());
+                Some(FaultInjector::new(FaultPlan::load(path)?))
+            }
+            None => None,
+        };
+        let fault_hook = |peripheral: &'static str| -> FaultHook {
+            fault_injector
+                .as_ref()
+                .map(|injector| injector.hook(peripheral))
+                .unwrap_or_default()
+        };
+        root_bus
+            .mcu_mailbox0
+            .regs
+            .lock()
+            .unwrap()
+            .set_fault_hook(fault_hook("mcu_mbox0"));
+        root_bus
+            .mcu_mailbox1
+            .regs
+            .lock()
+            .unwrap()
+            .set_fault_hook(fault_hook("mcu_mbox1"));
+
         // Create external communication bus
         let mut caliptra_to_ext = CaliptraToExtBus::new();
 
@@ -604,6 +756,7 @@ impl Emulator {
         let dma_ram = root_bus.ram.clone();
         let dma_rom_sram = root_bus.rom_sram.clone();
         let direct_read_flash = root_bus.direct_read_flash.clone();
+        let memories = McuMemories::new(&root_bus);
 
         let i3c_irq = pic.register_irq(McuRootBus::I3C_IRQ);
 
@@ -615,16 +768,20 @@ impl Emulator {
         } else {
             I3cController::default()
         };
+        if io_log.is_some() {
+            i3c_controller.set_stepped();
+        }
 
         let step_lock = Arc::new(Mutex::new(()));
 
-        let i3c = I3c::new(
+        let mut i3c = I3c::new(
             &clock.clone(),
             &mut i3c_controller,
             i3c_irq,
             cli.hw_revision.clone(),
             step_lock.clone(),
         );
+        i3c.set_fault_hook(fault_hook("i3c"));
 
         let i3c_dynamic_address = i3c.get_dynamic_address().unwrap();
 
@@ -632,6 +789,17 @@ impl Emulator {
         let doe_mbox_periph = DoeMboxPeriph::default();
 
         let mut doe_mbox_fsm = doe_mbox_fsm::DoeMboxFsm::new(doe_mbox_periph.clone());
+        if io_log.is_some() {
+            doe_mbox_fsm.set_stepped();
+        }
+
+        let pcie_vdm_controller = cli.pcie_vdm_port.map(|pcie_vdm_port| {
+            println!("Starting PCIe VDM Socket, port {}", pcie_vdm_port);
+            let (rx, tx) = start_pcie_vdm_socket(&MCU_RUNNING, pcie_vdm_port);
+            let mut controller = PcieVdmController::new(doe_mbox_periph.clone(), rx, tx);
+            controller.start();
+            controller
+        });
 
         let doe_mbox = DummyDoeMbox::new(
             &clock.clone(),
@@ -678,6 +846,10 @@ impl Emulator {
                 None,
             );
         }
+        if test_feature == "test-mctp-pcie-vdm-ctrl-cmds" {
+            println!("Starting test-mctp-pcie-vdm-ctrl-cmds test thread");
+            tests::mctp_pcie_vdm_ctrl_cmd::run_tests(cli.pcie_vdm_port.unwrap());
+        }
         if test_feature == "test-mctp-user-loopback" {
             i3c_controller_join_handle = Some(i3c_controller.start());
             println!(
@@ -709,6 +881,14 @@ impl Emulator {
                 std::time::Duration::from_secs(9000), // timeout in seconds
             );
         }
+        if test_feature == "test-doe-spdm-requester" {
+            let (test_rx, test_tx) = doe_mbox_fsm.start();
+            caliptra_mcu_testing_common::spdm_requester::run_doe_spdm_requester_test(
+                test_tx,
+                test_rx,
+                std::time::Duration::from_secs(3000), // timeout in seconds
+            );
+        }
         if test_feature == "test-doe-spdm-tdisp-ide-validator" {
             if std::env::var("SPDM_VALIDATOR_DIR").is_err() {
                 println!("SPDM_VALIDATOR_DIR environment variable is not set. Skipping test");
@@ -794,13 +974,14 @@ impl Emulator {
             None
         };
 
-        let primary_flash_controller = create_flash_controller(
+        let mut primary_flash_controller = create_flash_controller(
             "primary_flash",
             McuRootBus::PRIMARY_FLASH_CTRL_ERROR_IRQ,
             McuRootBus::PRIMARY_FLASH_CTRL_EVENT_IRQ,
             primary_flash_initial_content.as_deref(),
             Some(direct_read_flash.clone()),
         );
+        primary_flash_controller.set_fault_hook(fault_hook("primary_flash"));
 
         let secondary_flash_initial_content = if cli.secondary_flash_image.is_some() {
             let flash_image_path = cli.secondary_flash_image.as_ref().unwrap();
@@ -819,13 +1000,14 @@ impl Emulator {
             None
         };
 
-        let secondary_flash_controller = create_flash_controller(
+        let mut secondary_flash_controller = create_flash_controller(
             "secondary_flash",
             McuRootBus::SECONDARY_FLASH_CTRL_ERROR_IRQ,
             McuRootBus::SECONDARY_FLASH_CTRL_EVENT_IRQ,
             secondary_flash_initial_content.as_deref(),
             None,
         );
+        secondary_flash_controller.set_fault_hook(fault_hook("secondary_flash"));
 
         let mut dma_ctrl = caliptra_mcu_emulator_periph::AxiCDMA::new(
             &clock.clone(),
@@ -869,7 +1051,7 @@ impl Emulator {
         // The lifecycle state was already resolved above from fuses or CLI arg.
         let lc = LcCtrl::with_state(lc_state_index, lc_transition_cnt);
 
-        let otp = Otp::new(
+        let mut otp = Otp::new(
             &clock.clone(),
             OtpArgs {
                 file_name: cli.otp,
@@ -884,6 +1066,7 @@ impl Emulator {
                 ..Default::default()
             },
         )?;
+        otp.set_fault_hook(fault_hook("otp"));
 
         // Share OTP partition data with the LC controller for transitions.
         let mut lc = lc;
@@ -1003,11 +1186,16 @@ impl Emulator {
             // load the firmware images and SoC manifest into the recovery interface emulator
             let caliptra_firmware = read_binary(&cli.caliptra_firmware, RAM_ORG).unwrap();
             let soc_manifest = read_binary(&cli.soc_manifest, 0).unwrap();
+            let mut recovery_images = vec![caliptra_firmware, soc_manifest, mcu_firmware];
+            if let Some(io_log) = io_log.as_mut() {
+                recovery_images = io_log.bmc_recovery_images(recovery_images)?;
+            }
             let bmc = bmc.as_mut().unwrap();
-            bmc.push_recovery_image(caliptra_firmware);
-            bmc.push_recovery_image(soc_manifest);
-            bmc.push_recovery_image(mcu_firmware);
-            println!("Active mode enabled with 3 recovery images");
+            let image_count = recovery_images.len();
+            for image in recovery_images {
+                bmc.push_recovery_image(image);
+            }
+            println!("Active mode enabled with {} recovery images", image_count);
         }
 
         if test_feature == "test-mcu-mbox-soc-requester-loopback"
@@ -1059,21 +1247,23 @@ impl Emulator {
                 let _ = PldmDaemon::run(
                     pldm_socket,
                     caliptra_mcu_pldm_ua::daemon::Options {
-                        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.unwrap()),
+                        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.unwrap().into()),
                         discovery_sm_actions: caliptra_mcu_pldm_ua::discovery_sm::DefaultActions {},
                         update_sm_actions:
                             caliptra_mcu_pldm_ua::update_sm::DefaultActionsExitOnError {},
                         fd_tid: 0x01,
+                        trust_anchor: None,
                     },
                 );
             } else {
                 let _ = PldmDaemon::run(
                     pldm_socket,
                     caliptra_mcu_pldm_ua::daemon::Options {
-                        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.unwrap()),
+                        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.unwrap().into()),
                         discovery_sm_actions: caliptra_mcu_pldm_ua::discovery_sm::DefaultActions {},
                         update_sm_actions: caliptra_mcu_pldm_ua::update_sm::DefaultActions {},
                         fd_tid: 0x01,
+                        trust_anchor: None,
                     },
                 );
             };
@@ -1101,10 +1291,15 @@ impl Emulator {
             uart_output,
             i3c_controller,
             doe_mbox_fsm,
+            pcie_vdm_controller,
             Some(i3c_dynamic_address.into()),
             i3c_controller_join_handle,
             step_lock,
             cptra_boot_go,
+            memories,
+            mcu_root_bus_offsets.pic_offset,
+            io_log,
+            profiler,
         ))
     }
 
@@ -1121,14 +1316,27 @@ impl Emulator {
         uart_output: Option<Rc<RefCell<Vec<u8>>>>,
         i3c_controller: I3cController,
         doe_mbox_fsm: doe_mbox_fsm::DoeMboxFsm,
+        pcie_vdm_controller: Option<PcieVdmController>,
         i3c_address: Option<u8>,
         i3c_controller_join_handle: Option<JoinHandle<()>>,
         step_lock: Arc<Mutex<()>>,
         cptra_boot_go: Rc<Cell<bool>>,
+        memories: McuMemories,
+        pic_offset: u32,
+        io_log: Option<IoLog>,
+        profiler: Option<Profiler>,
     ) -> Self {
+        // When recording, console bytes are handed to the UART by step() so
+        // that they can be logged. When replaying, the console is not read.
+        let console_input = match &io_log {
+            None => stdin_uart.clone(),
+            Some(IoLog::Record(_)) => stdin_uart.as_ref().map(|_| Arc::new(Mutex::new(None))),
+            Some(IoLog::Replay(_)) => None,
+        };
+
         // read from the console in a separate thread to prevent blocking
-        let stdin_uart_clone = stdin_uart.clone();
-        std::thread::spawn(move || read_console(stdin_uart_clone));
+        let console_input_clone = console_input.clone();
+        std::thread::spawn(move || read_console(console_input_clone));
 
         let timer = Timer::new(&mcu_cpu.clock.clone());
         let trace_file = trace_path.map(|path| File::create(path).unwrap());
@@ -1146,10 +1354,17 @@ impl Emulator {
             uart_output,
             i3c_controller,
             doe_mbox_fsm,
+            pcie_vdm_controller,
             i3c_address,
             i3c_controller_join_handle,
             step_lock,
             cptra_boot_go,
+            memories,
+            pic_offset,
+            io_log,
+            console_input,
+            next_doe_fsm_poll: 0,
+            profiler,
         }
     }
 
@@ -1174,6 +1389,10 @@ impl Emulator {
             TICK_COND.notify_all();
         }
 
+        if self.io_log.is_some() {
+            self.pump_external_inputs(now);
+        }
+
         if let Some(ref stdin_uart) = self.stdin_uart {
             if stdin_uart.lock().unwrap().is_some() {
                 self.timer.schedule_poll_in(1);
@@ -1185,15 +1404,16 @@ impl Emulator {
         // mid-step clock value.
         let _step_guard = self.step_lock.lock().unwrap();
 
-        let action = if let Some(ref mut trace_file) = self.trace_file {
-            let trace_fn: &mut dyn FnMut(u32, RvInstr) = &mut |pc, instr| match instr {
-                RvInstr::Instr32(instr32) => {
-                    let _ = writeln!(trace_file, "{}", disassemble(pc, instr32));
-                    println!("{{mcu cpu}}      {}", disassemble(pc, instr32));
-                }
-                RvInstr::Instr16(instr16) => {
-                    let _ = writeln!(trace_file, "{}", disassemble(pc, instr16 as u32));
-                    println!("{{mcu cpu}}      {}", disassemble(pc, instr16 as u32));
+        let mcu_pc = self.mcu_cpu.read_pc();
+        let mut mcu_instr = None;
+        let action = if self.trace_file.is_some() || self.profiler.is_some() {
+            let trace_file = &mut self.trace_file;
+            let trace_fn: &mut dyn FnMut(u32, RvInstr) = &mut |pc, instr| {
+                let instr = instr_bits(instr);
+                mcu_instr = Some(instr);
+                if let Some(trace_file) = trace_file.as_mut() {
+                    let _ = writeln!(trace_file, "{}", disassemble(pc, instr));
+                    println!("{{mcu cpu}}      {}", disassemble(pc, instr));
                 }
             };
             self.mcu_cpu.step(Some(trace_fn))
@@ -1201,6 +1421,18 @@ impl Emulator {
             self.mcu_cpu.step(None)
         };
 
+        if let Some(profiler) = self.profiler.as_mut() {
+            let end = self.mcu_cpu.clock.now();
+            let next_pc = self.mcu_cpu.read_pc();
+            profiler.mcu.step(mcu_pc, mcu_instr, next_pc, now, end);
+            if let Ok(flow_status) = self.mcu_cpu.bus.read(
+                caliptra_emu_types::RvSize::Word,
+                profiler.flow_status_addr(),
+            ) {
+                profiler.flow_status(end, flow_status);
+            }
+        }
+
         if action != StepAction::Continue {
             return action;
         }
@@ -1210,14 +1442,17 @@ impl Emulator {
         }
 
         if self.cptra_boot_go.get() {
-            let caliptra_action = if self.trace_file.is_some() {
+            let caliptra_pc = self.caliptra_cpu.read_pc();
+            let caliptra_start = self.caliptra_cpu.clock.now();
+            let mut caliptra_instr = None;
+            let tracing = self.trace_file.is_some();
+            let caliptra_action = if tracing || self.profiler.is_some() {
                 let caliptra_trace_fn: &mut dyn FnMut(u32, caliptra_emu_cpu::RvInstr) =
-                    &mut |pc, instr| match instr {
-                        caliptra_emu_cpu::RvInstr::Instr32(instr32) => {
-                            println!("{{caliptra cpu}} {}", disassemble(pc, instr32));
-                        }
-                        caliptra_emu_cpu::RvInstr::Instr16(instr16) => {
-                            println!("{{caliptra cpu}} {}", disassemble(pc, instr16 as u32));
+                    &mut |pc, instr| {
+                        let instr = instr_bits(instr);
+                        caliptra_instr = Some(instr);
+                        if tracing {
+                            println!("{{caliptra cpu}} {}", disassemble(pc, instr));
                         }
                     };
                 self.caliptra_cpu.step(Some(caliptra_trace_fn))
@@ -1225,6 +1460,16 @@ impl Emulator {
                 self.caliptra_cpu.step(None)
             };
 
+            if let Some(profiler) = self.profiler.as_mut() {
+                // The Caliptra clock only runs while the core is stepped, so
+                // its cycles are placed on the MCU timeline.
+                let cycles = self.caliptra_cpu.clock.now() - caliptra_start;
+                let next_pc = self.caliptra_cpu.read_pc();
+                profiler
+                    .caliptra
+                    .step(caliptra_pc, caliptra_instr, next_pc, now, now + cycles);
+            }
+
             match caliptra_action {
                 StepAction::Continue => {}
                 _ => {
@@ -1240,10 +1485,128 @@ impl Emulator {
         action
     }
 
+    /// Hand the external inputs to the emulator from this thread, so that each
+    /// one is consumed at a known cycle. When recording, the inputs come from
+    /// the console, the I3C socket and the DOE transport and are logged. When
+    /// replaying, those sources are drained and the logged inputs are used.
+    fn pump_external_inputs(&mut self, cycle: u64) {
+        let mut inputs = vec![];
+        if let (Some(console), Some(uart)) = (&self.console_input, &self.stdin_uart) {
+            if uart.lock().unwrap().is_none() {
+                if let Some(byte) = console.lock().unwrap().take() {
+                    inputs.push(IoInput::Uart { byte });
+                }
+            }
+        }
+        while let Some(cmd) = self.i3c_controller.receive_command() {
+            inputs.push(IoInput::from_i3c_command(&cmd));
+        }
+        while let Some(data) = self.doe_mbox_fsm.receive_message() {
+            inputs.push(IoInput::Doe { data });
+        }
+
+        let Some(io_log) = self.io_log.as_mut() else {
+            return;
+        };
+        if io_log.is_replay() {
+            inputs.clear();
+            while let Some(input) = io_log.next_due(cycle) {
+                inputs.push(input);
+            }
+        } else {
+            for input in inputs.iter() {
+                if let Err(err) = io_log.log(cycle, input) {
+                    println!("Failed to record external input: {}", err);
+                }
+            }
+        }
+
+        for input in inputs {
+            match input {
+                IoInput::Uart { byte } => match self.stdin_uart.as_ref() {
+                    Some(uart) => *uart.lock().unwrap() = Some(byte),
+                    None => println!("Dropping console input, the UART has no input"),
+                },
+                IoInput::I3c {
+                    addr,
+                    descriptor,
+                    data,
+                } => match IoInput::to_i3c_command(addr, descriptor, data) {
+                    Ok(cmd) => self.i3c_controller.deliver_command(cmd),
+                    Err(err) => println!("Dropping I3C input: {}", err),
+                },
+                IoInput::Doe { data } => self.doe_mbox_fsm.deliver_message(data),
+                IoInput::BmcRecoveryImage { .. } => {
+                    println!("Dropping BMC recovery image, the BMC is not enabled")
+                }
+            }
+        }
+
+        self.i3c_controller.send_responses();
+        if cycle >= self.next_doe_fsm_poll {
+            self.doe_mbox_fsm.on_event();
+            self.next_doe_fsm_poll = cycle + doe_mbox_fsm::DOE_FSM_POLL_TICKS;
+        }
+    }
+
     /// Get the current program counter (PC) of the MCU CPU
     pub fn get_pc(&self) -> u32 {
         self.mcu_cpu.read_pc()
     }
+
+    /// Write the profile collected with --profile-dir, if enabled.
+    pub fn write_profile(&mut self) -> io::Result<()> {
+        let cycle = self.mcu_cpu.clock.now();
+        match self.profiler.as_mut() {
+            Some(profiler) => profiler.write(cycle),
+            None => Ok(()),
+        }
+    }
+
+    /// Save the CPU, memory and peripheral state to `path`. Fails while the
+    /// Caliptra mailbox is processing a command.
+    pub fn save_snapshot(&mut self, path: &Path) -> io::Result<()> {
+        let _step_guard = self.step_lock.lock().unwrap();
+        let mut snapshot = SystemSnapshot::capture(
+            &mut self.mcu_cpu,
+            |bus| bus,
+            &mut self.caliptra_cpu,
+            &self.memories,
+            self.pic_offset,
+        )?;
+        snapshot.bmc = self.bmc.as_ref().map(Bmc::recovery_state);
+        snapshot.save(path)
+    }
+
+    /// Restore the state saved by [`Emulator::save_snapshot`]. The emulator
+    /// must have been created with the same arguments and not yet stepped.
+    pub fn load_snapshot(&mut self, path: &Path) -> io::Result<()> {
+        let snapshot = SystemSnapshot::load(path)?;
+        let _step_guard = self.step_lock.lock().unwrap();
+        snapshot.restore(
+            &mut self.mcu_cpu,
+            |bus| bus,
+            &mut self.caliptra_cpu,
+            &self.memories,
+            self.pic_offset,
+        )?;
+        if let (Some(bmc), Some(state)) = (self.bmc.as_mut(), snapshot.bmc.as_deref()) {
+            bmc.restore_recovery_state(state)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        }
+        if self.sram_range.contains(&self.mcu_cpu.read_pc()) {
+            MCU_RUNTIME_STARTED.store(true, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+}
+
+/// Raw bits of an instruction, with compressed ones zero-extended.
+fn instr_bits(instr: RvInstr) -> u32 {
+    match instr {
+        RvInstr::Instr32(instr32) => instr32,
+        RvInstr::Instr16(instr16) => instr16 as u32,
+    }
 }
 
 fn disassemble(pc: u32, instr: u32) -> String {
@@ -0,0 +1,256 @@
+/*++
+
+Licensed under the Apache-2.0 license.
+
+File Name:
+
+    io_replay.rs
+
+Abstract:
+
+    Recording and replay of the external inputs of the emulator, so that a
+    run driven by sockets or the console can be reproduced without them.
+
+--*/
+
+use caliptra_mcu_testing_common::i3c::{I3cBusCommand, I3cTcriCommand, I3cTcriCommandXfer};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// External input consumed by the emulator.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IoInput {
+    /// Console byte for the MCU UART.
+    Uart { byte: u8 },
+    /// Command from the I3C bus controller, with its raw 64-bit TCRI
+    /// command descriptor.
+    I3c {
+        addr: u8,
+        descriptor: u64,
+        data: Vec<u8>,
+    },
+    /// Message from the DOE transport to the DOE mailbox.
+    Doe { data: Vec<u8> },
+    /// Image the BMC streams to the Caliptra core over the recovery
+    /// interface. These are logged at cycle 0, before any other input.
+    BmcRecoveryImage { data: Vec<u8> },
+}
+
+impl IoInput {
+    pub fn from_i3c_command(cmd: &I3cBusCommand) -> Self {
+        IoInput::I3c {
+            addr: cmd.addr.into(),
+            descriptor: cmd.cmd.cmd.clone().into(),
+            data: cmd.cmd.data.clone(),
+        }
+    }
+
+    pub fn to_i3c_command(addr: u8, descriptor: u64, data: Vec<u8>) -> io::Result<I3cBusCommand> {
+        let cmd: I3cTcriCommand = [descriptor as u32, (descriptor >> 32) as u32]
+            .try_into()
+            .map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid I3C descriptor {:#x}: {:?}", descriptor, e),
+                )
+            })?;
+        Ok(I3cBusCommand {
+            addr: addr.into(),
+            cmd: I3cTcriCommandXfer { cmd, data },
+        })
+    }
+}
+
+/// An input and the MCU cycle at which the emulator consumed it.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct IoEvent {
+    pub cycle: u64,
+    pub input: IoInput,
+}
+
+/// Source of the external inputs when recording or replaying.
+pub enum IoLog {
+    /// Inputs come from the live sources and are appended to a file, one JSON
+    /// event per line.
+    Record(BufWriter<File>),
+    /// Inputs come from a recording. The live sources are drained and their
+    /// inputs dropped.
+    Replay(VecDeque<IoEvent>),
+}
+
+impl IoLog {
+    pub fn record(path: &Path) -> io::Result<Self> {
+        Ok(IoLog::Record(BufWriter::new(File::create(path)?)))
+    }
+
+    pub fn replay(path: &Path) -> io::Result<Self> {
+        let mut events = VecDeque::new();
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                events.push_back(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(IoLog::Replay(events))
+    }
+
+    pub fn is_replay(&self) -> bool {
+        matches!(self, IoLog::Replay(_))
+    }
+
+    /// Append `input` to the recording. Every line is flushed so that the
+    /// recording survives a crash of the emulator.
+    pub fn log(&mut self, cycle: u64, input: &IoInput) -> io::Result<()> {
+        if let IoLog::Record(writer) = self {
+            let event = IoEvent {
+                cycle,
+                input: input.clone(),
+            };
+            serde_json::to_writer(&mut *writer, &event)?;
+            writer.write_all(b"\n")?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Recovery images for the BMC. When recording, `images` are logged and
+    /// returned. When replaying, the recorded images are returned instead.
+    pub fn bmc_recovery_images(&mut self, images: Vec<Vec<u8>>) -> io::Result<Vec<Vec<u8>>> {
+        let IoLog::Replay(events) = self else {
+            for data in images.iter() {
+                self.log(0, &IoInput::BmcRecoveryImage { data: data.clone() })?;
+            }
+            return Ok(images);
+        };
+        let mut recorded = vec![];
+        while let Some(IoEvent {
+            input: IoInput::BmcRecoveryImage { .. },
+            ..
+        }) = events.front()
+        {
+            if let Some(IoEvent {
+                input: IoInput::BmcRecoveryImage { data },
+                ..
+            }) = events.pop_front()
+            {
+                recorded.push(data);
+            }
+        }
+        if recorded.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the recording has no BMC recovery images",
+            ));
+        }
+        Ok(recorded)
+    }
+
+    /// Next recorded input that is due at `cycle`.
+    pub fn next_due(&mut self, cycle: u64) -> Option<IoInput> {
+        let IoLog::Replay(events) = self else {
+            return None;
+        };
+        let due = events.front()?.cycle;
+        if due > cycle {
+            return None;
+        }
+        if due < cycle {
+            println!(
+                "I/O replay diverged: input recorded at cycle {} delivered at cycle {}",
+                due, cycle
+            );
+        }
+        let event = events.pop_front()?;
+        if events.is_empty() {
+            println!(
+                "I/O replay: last recorded input delivered at cycle {}",
+                cycle
+            );
+        }
+        Some(event.input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_record_then_replay() {
+        let path = NamedTempFile::new().unwrap().into_temp_path();
+        let inputs = [
+            (10, IoInput::Uart { byte: b'a' }),
+            (
+                10,
+                IoInput::I3c {
+                    addr: 8,
+                    descriptor: 0x0004_0000_0000_0000,
+                    data: vec![1, 2, 3, 4],
+                },
+            ),
+            (2000, IoInput::Doe { data: vec![0; 8] }),
+        ];
+
+        let mut log = IoLog::record(&path).unwrap();
+        for (cycle, input) in inputs.iter() {
+            log.log(*cycle, input).unwrap();
+        }
+        drop(log);
+
+        let mut log = IoLog::replay(&path).unwrap();
+        assert!(log.is_replay());
+        assert_eq!(log.next_due(9), None);
+        assert_eq!(log.next_due(10), Some(inputs[0].1.clone()));
+        assert_eq!(log.next_due(10), Some(inputs[1].1.clone()));
+        assert_eq!(log.next_due(10), None);
+        assert_eq!(log.next_due(2000), Some(inputs[2].1.clone()));
+        assert_eq!(log.next_due(u64::MAX), None);
+    }
+
+    #[test]
+    fn test_bmc_recovery_images() {
+        let path = NamedTempFile::new().unwrap().into_temp_path();
+        let images = vec![vec![1, 2, 3], vec![4], vec![5, 6]];
+
+        let mut log = IoLog::record(&path).unwrap();
+        assert_eq!(log.bmc_recovery_images(images.clone()).unwrap(), images);
+        log.log(10, &IoInput::Uart { byte: b'a' }).unwrap();
+        drop(log);
+
+        // The recorded images are used, not the ones from the command line.
+        let mut log = IoLog::replay(&path).unwrap();
+        assert_eq!(log.bmc_recovery_images(vec![vec![7]]).unwrap(), images);
+        assert_eq!(log.next_due(10), Some(IoInput::Uart { byte: b'a' }));
+
+        // A recording without the BMC cannot be replayed with it.
+        let mut log = IoLog::record(&path).unwrap();
+        log.log(10, &IoInput::Uart { byte: b'a' }).unwrap();
+        drop(log);
+        let mut log = IoLog::replay(&path).unwrap();
+        assert!(log.bmc_recovery_images(images).is_err());
+    }
+
+    #[test]
+    fn test_i3c_command_round_trip() {
+        let input = IoInput::I3c {
+            addr: 8,
+            descriptor: 0x0004_0000_0000_0000,
+            data: vec![1, 2, 3, 4],
+        };
+        let IoInput::I3c {
+            addr,
+            descriptor,
+            data,
+        } = input.clone()
+        else {
+            unreachable!();
+        };
+        let cmd = IoInput::to_i3c_command(addr, descriptor, data).unwrap();
+        assert_eq!(IoInput::from_i3c_command(&cmd), input);
+    }
+}
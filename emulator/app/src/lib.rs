@@ -18,6 +18,8 @@ pub mod doe_mbox_fsm;
 pub mod elf;
 pub mod emulator;
 pub mod gdb;
+pub mod io_replay;
+pub mod profiler;
 pub mod tests;
 
 pub use emulator::{Emulator, EmulatorArgs, ExternalReadCallback, ExternalWriteCallback};
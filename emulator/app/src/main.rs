@@ -22,7 +22,7 @@ use std::io::IsTerminal;
 use std::rc::Rc;
 
 // CPU Main Loop (free_run no GDB)
-fn free_run(mut emulator: Emulator) {
+fn free_run(emulator: &mut Emulator) {
     while MCU_RUNNING.load(std::sync::atomic::Ordering::Relaxed) {
         match emulator.step() {
             StepAction::Break => break,
@@ -52,22 +52,32 @@ fn run(cli: EmulatorArgs, capture_uart_output: bool) -> io::Result<Vec<u8>> {
         None
     };
 
-    let emulator = Emulator::from_args(cli.clone(), capture_uart_output)?;
+    let mut emulator = Emulator::from_args(cli.clone(), capture_uart_output)?;
+    if let Some(path) = cli.load_snapshot.as_ref() {
+        emulator.load_snapshot(path)?;
+    }
 
     // Check if Optional GDB Port is passed
-    match cli.gdb_port {
+    let mut emulator = match cli.gdb_port {
         Some(port) => {
             // Create GDB Target Instance
             let mut gdb_target = gdb::gdb_target::GdbTarget::new(emulator);
 
             // Execute CPU through GDB State Machine
             gdb::gdb_state::wait_for_gdb_run(&mut gdb_target, port);
+            gdb_target.into_emulator()
         }
         _ => {
             // Create the emulator with all the setup
-            free_run(emulator);
+            free_run(&mut emulator);
+            emulator
         }
+    };
+
+    if let Some(path) = cli.save_snapshot.as_ref() {
+        emulator.save_snapshot(path)?;
     }
+    emulator.write_profile()?;
 
     Ok(uart_output.map(|o| o.borrow().clone()).unwrap_or_default())
 }
@@ -0,0 +1,825 @@
+/*++
+
+Licensed under the Apache-2.0 license.
+
+File Name:
+
+    profiler.rs
+
+Abstract:
+
+    Guest profiler for the MCU and Caliptra cores. Cycles are attributed to
+    the functions of the ELF images, with call stacks rebuilt from the
+    call, return and trap instructions.
+
+--*/
+
+use caliptra_mcu_romtime::{McuBootMilestones, McuRomBootStatus};
+use elf::abi::{SHF_EXECINSTR, STT_FUNC, STT_NOTYPE};
+use elf::endian::AnyEndian;
+use elf::ElfBytes;
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// Chrome trace events kept per core. Later calls are still counted in the
+/// folded stacks.
+pub const MAX_TRACE_EVENTS: usize = 1_000_000;
+
+/// Calls nested deeper than this replace the innermost frame instead.
+const MAX_STACK_DEPTH: usize = 512;
+
+/// Functions listed for each boot checkpoint in the budget report.
+const TOP_FUNCTIONS: usize = 3;
+
+const MRET: u32 = 0x3020_0073;
+
+/// Function symbol of a guest image.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Symbol {
+    pub addr: u32,
+    pub size: u32,
+    pub name: String,
+}
+
+/// Function symbols of a core, sorted by address.
+#[derive(Default)]
+pub struct SymbolTable {
+    symbols: Vec<Symbol>,
+}
+
+impl SymbolTable {
+    pub fn new(mut symbols: Vec<Symbol>) -> Self {
+        // Keep one symbol per address, preferring the ones with a size.
+        symbols.sort_by_key(|s| (s.addr, s.size == 0));
+        symbols.dedup_by_key(|s| s.addr);
+        Self { symbols }
+    }
+
+    /// Read the function symbols of an ELF file. Assembly labels in
+    /// executable sections are included as well.
+    pub fn from_elf(elf_bytes: &[u8]) -> io::Result<Self> {
+        let invalid =
+            |e: elf::ParseError| io::Error::new(io::ErrorKind::InvalidData, e.to_string());
+        let elf_file = ElfBytes::<AnyEndian>::minimal_parse(elf_bytes).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Failed to parse ELF file: {:?}", e),
+            )
+        })?;
+        let Some((symtab, strtab)) = elf_file.symbol_table().map_err(invalid)? else {
+            return Ok(Self::default());
+        };
+        let sections = elf_file.section_headers();
+
+        let mut symbols = vec![];
+        for sym in symtab.iter() {
+            if sym.is_undefined() {
+                continue;
+            }
+            let in_code = sections
+                .and_then(|s| s.get(sym.st_shndx as usize).ok())
+                .is_some_and(|s| s.sh_flags & SHF_EXECINSTR as u64 != 0);
+            let name = strtab.get(sym.st_name as usize).map_err(invalid)?;
+            let is_function = match sym.st_symtype() {
+                STT_FUNC => true,
+                // Skip the RISC-V mapping symbols and local labels.
+                STT_NOTYPE => in_code && !name.is_empty() && !name.starts_with(['$', '.']),
+                _ => false,
+            };
+            if is_function {
+                symbols.push(Symbol {
+                    addr: sym.st_value as u32,
+                    size: sym.st_size as u32,
+                    name: demangle(name),
+                });
+            }
+        }
+        Ok(Self::new(symbols))
+    }
+
+    /// Add the symbols of the file at `path`. Returns false if it is not an
+    /// ELF file.
+    pub fn add_file(&mut self, path: &Path) -> io::Result<bool> {
+        let mut buffer = Vec::new();
+        File::open(path)?.read_to_end(&mut buffer)?;
+        if !buffer.starts_with(&[0x7f, 0x45, 0x4c, 0x46]) {
+            return Ok(false);
+        }
+        let other = Self::from_elf(&buffer)?;
+        let mut symbols = std::mem::take(&mut self.symbols);
+        symbols.extend(other.symbols);
+        *self = Self::new(symbols);
+        Ok(true)
+    }
+
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    /// Function containing `addr`, as its start address and address range.
+    /// Addresses outside of any symbol belong to the gap between symbols.
+    pub fn resolve(&self, addr: u32) -> (u32, Range<u32>) {
+        let i = self.symbols.partition_point(|s| s.addr <= addr);
+        let mut start = 0;
+        if i > 0 {
+            let sym = &self.symbols[i - 1];
+            let end = match (sym.size, self.symbols.get(i)) {
+                (0, Some(next)) => next.addr,
+                (0, None) => u32::MAX,
+                (size, _) => sym.addr.saturating_add(size),
+            };
+            if addr < end {
+                return (sym.addr, sym.addr..end);
+            }
+            start = end;
+        }
+        let end = self.symbols.get(i).map_or(u32::MAX, |s| s.addr);
+        (start, start..end)
+    }
+
+    /// Name of the function starting at `addr`.
+    pub fn name(&self, addr: u32) -> String {
+        match self.symbols.binary_search_by_key(&addr, |s| s.addr) {
+            Ok(i) => self.symbols[i].name.clone(),
+            Err(_) => format!("0x{:08x}", addr),
+        }
+    }
+}
+
+/// Undo the legacy Rust symbol mangling, dropping the hash.
+fn demangle(name: &str) -> String {
+    let Some(mut rest) = name.strip_prefix("_ZN") else {
+        return name.to_string();
+    };
+    let mut parts = vec![];
+    while !rest.starts_with('E') {
+        let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+        let Ok(len) = rest[..digits].parse::<usize>() else {
+            return name.to_string();
+        };
+        let Some(part) = rest.get(digits..digits + len) else {
+            return name.to_string();
+        };
+        parts.push(part);
+        rest = &rest[digits + len..];
+    }
+    if parts.last().is_some_and(|p| {
+        p.len() == 17 && p.starts_with('h') && p[1..].bytes().all(|b| b.is_ascii_hexdigit())
+    }) {
+        parts.pop();
+    }
+
+    const ESCAPES: [(&str, &str); 15] = [
+        ("$LT$", "<"),
+        ("$GT$", ">"),
+        ("$RF$", "&"),
+        ("$BP$", "*"),
+        ("$LP$", "("),
+        ("$RP$", ")"),
+        ("$C$", ","),
+        ("$SP$", "@"),
+        ("$u20$", " "),
+        ("$u27$", "'"),
+        ("$u5b$", "["),
+        ("$u5d$", "]"),
+        ("$u7b$", "{"),
+        ("$u7d$", "}"),
+        ("$u7e$", "~"),
+    ];
+    let parts: Vec<String> = parts
+        .iter()
+        .map(|part| {
+            let mut part = part
+                .strip_prefix("_$")
+                .map_or(part.to_string(), |p| format!("${p}"));
+            for (from, to) in ESCAPES {
+                part = part.replace(from, to);
+            }
+            part.replace("..", "::")
+        })
+        .collect();
+    parts.join("::")
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Flow {
+    Call,
+    Return,
+    Jump,
+    Branch,
+    Other,
+}
+
+/// x1 (ra) and x5 (t0) hold return addresses by convention.
+fn is_link(reg: u32) -> bool {
+    reg == 1 || reg == 5
+}
+
+fn classify(instr: u32) -> Flow {
+    if instr & 3 == 3 {
+        let rd = (instr >> 7) & 0x1f;
+        let rs1 = (instr >> 15) & 0x1f;
+        match instr & 0x7f {
+            // jal
+            0x6f if is_link(rd) => Flow::Call,
+            0x6f => Flow::Jump,
+            // jalr
+            0x67 if is_link(rd) => Flow::Call,
+            0x67 if rd == 0 && is_link(rs1) => Flow::Return,
+            0x67 => Flow::Jump,
+            0x63 => Flow::Branch,
+            _ if instr == MRET => Flow::Return,
+            _ => Flow::Other,
+        }
+    } else {
+        let funct3 = (instr >> 13) & 7;
+        let rs1 = (instr >> 7) & 0x1f;
+        let rs2 = (instr >> 2) & 0x1f;
+        match (instr & 3, funct3) {
+            // c.jal
+            (1, 0b001) => Flow::Call,
+            // c.j
+            (1, 0b101) => Flow::Jump,
+            // c.beqz, c.bnez
+            (1, 0b110 | 0b111) => Flow::Branch,
+            // c.jalr, c.jr
+            (2, 0b100) if rs1 != 0 && rs2 == 0 => {
+                if instr & (1 << 12) != 0 {
+                    Flow::Call
+                } else if is_link(rs1) {
+                    Flow::Return
+                } else {
+                    Flow::Jump
+                }
+            }
+            _ => Flow::Other,
+        }
+    }
+}
+
+/// Call tree node: one function reached through one call path.
+struct Node {
+    func: u32,
+    parent: usize,
+    children: HashMap<u32, usize>,
+    self_cycles: u64,
+}
+
+struct Frame {
+    node: usize,
+    start: u64,
+    range: Range<u32>,
+}
+
+struct TraceEvent {
+    func: u32,
+    start: u64,
+    duration: u64,
+}
+
+/// Profile of one core.
+pub struct CoreProfiler {
+    name: &'static str,
+    symbols: SymbolTable,
+    // Node 0 is the root of the call tree and is never on the stack.
+    nodes: Vec<Node>,
+    stack: Vec<Frame>,
+    events: Vec<TraceEvent>,
+    dropped_events: u64,
+}
+
+impl CoreProfiler {
+    pub fn new(name: &'static str, symbols: SymbolTable) -> Self {
+        Self {
+            name,
+            symbols,
+            nodes: vec![Node {
+                func: 0,
+                parent: 0,
+                children: HashMap::new(),
+                self_cycles: 0,
+            }],
+            stack: vec![],
+            events: vec![],
+            dropped_events: 0,
+        }
+    }
+
+    /// Account for one CPU step. `instr` is the instruction executed at `pc`,
+    /// if any, and `next_pc` the PC after the step. `start` and `end` are the
+    /// cycles before and after the step.
+    pub fn step(&mut self, pc: u32, instr: Option<u32>, next_pc: u32, start: u64, end: u64) {
+        if self.stack.is_empty() {
+            self.call(pc, start);
+        }
+        let top = self.stack.len() - 1;
+        let node = self.stack[top].node;
+        self.nodes[node].self_cycles += end.saturating_sub(start);
+
+        let Some(instr) = instr else {
+            // An interrupt was taken without executing an instruction.
+            if next_pc != pc {
+                self.call(next_pc, end);
+            }
+            return;
+        };
+        match classify(instr) {
+            Flow::Call => self.call(next_pc, end),
+            Flow::Return => self.ret(next_pc, end),
+            flow => {
+                let len = if instr & 3 == 3 { 4 } else { 2 };
+                if flow == Flow::Other && next_pc != pc.wrapping_add(len) {
+                    // Exception or interrupt entry.
+                    self.call(next_pc, end);
+                } else if !self.stack[top].range.contains(&next_pc) {
+                    // Tail call or fall through into the next function.
+                    self.pop(end);
+                    self.call(next_pc, end);
+                }
+            }
+        }
+    }
+
+    fn call(&mut self, target: u32, cycle: u64) {
+        if self.stack.len() >= MAX_STACK_DEPTH {
+            self.pop(cycle);
+        }
+        let (func, range) = self.symbols.resolve(target);
+        let parent = self.stack.last().map_or(0, |f| f.node);
+        let node = match self.nodes[parent].children.get(&func) {
+            Some(&node) => node,
+            None => {
+                let node = self.nodes.len();
+                self.nodes.push(Node {
+                    func,
+                    parent,
+                    children: HashMap::new(),
+                    self_cycles: 0,
+                });
+                self.nodes[parent].children.insert(func, node);
+                node
+            }
+        };
+        self.stack.push(Frame {
+            node,
+            start: cycle,
+            range,
+        });
+    }
+
+    fn ret(&mut self, target: u32, cycle: u64) {
+        self.pop(cycle);
+        // Unwind to the caller that contains the return address. If there is
+        // none, e.g. after a context switch, continue in a new frame.
+        match self.stack.iter().rposition(|f| f.range.contains(&target)) {
+            Some(caller) => {
+                while self.stack.len() > caller + 1 {
+                    self.pop(cycle);
+                }
+            }
+            None => {
+                self.pop(cycle);
+                self.call(target, cycle);
+            }
+        }
+    }
+
+    fn pop(&mut self, cycle: u64) {
+        let Some(frame) = self.stack.pop() else {
+            return;
+        };
+        if self.events.len() < MAX_TRACE_EVENTS {
+            self.events.push(TraceEvent {
+                func: self.nodes[frame.node].func,
+                start: frame.start,
+                duration: cycle.saturating_sub(frame.start),
+            });
+        } else {
+            self.dropped_events += 1;
+        }
+    }
+
+    /// Close the open frames at `cycle`.
+    pub fn finish(&mut self, cycle: u64) {
+        while !self.stack.is_empty() {
+            self.pop(cycle);
+        }
+    }
+
+    pub fn total_cycles(&self) -> u64 {
+        self.nodes.iter().map(|n| n.self_cycles).sum()
+    }
+
+    fn self_cycles(&self) -> Vec<u64> {
+        self.nodes.iter().map(|n| n.self_cycles).collect()
+    }
+
+    fn stack_name(&self, mut node: usize) -> String {
+        let mut names = vec![];
+        while node != 0 {
+            names.push(self.symbols.name(self.nodes[node].func));
+            node = self.nodes[node].parent;
+        }
+        names.reverse();
+        names.join(";")
+    }
+
+    /// Stacks in the folded format of flamegraph.pl and inferno, one
+    /// `caller;callee cycles` line per call path.
+    pub fn write_folded(&self, out: &mut impl Write) -> io::Result<()> {
+        let mut lines: Vec<(String, u64)> = (1..self.nodes.len())
+            .filter(|&n| self.nodes[n].self_cycles > 0)
+            .map(|n| (self.stack_name(n), self.nodes[n].self_cycles))
+            .collect();
+        lines.sort();
+        for (stack, cycles) in lines {
+            writeln!(out, "{} {}", stack, cycles)?;
+        }
+        Ok(())
+    }
+
+    /// Functions with the most self cycles between two snapshots of
+    /// [`CoreProfiler::self_cycles`].
+    fn top_functions(&self, before: &[u64], after: &[u64]) -> Vec<(String, u64)> {
+        let mut cycles: HashMap<u32, u64> = HashMap::new();
+        for (node, &total) in after.iter().enumerate().skip(1) {
+            let delta = total - before.get(node).copied().unwrap_or(0);
+            if delta > 0 {
+                *cycles.entry(self.nodes[node].func).or_default() += delta;
+            }
+        }
+        let mut cycles: Vec<(u32, u64)> = cycles.into_iter().collect();
+        cycles.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        cycles
+            .into_iter()
+            .take(TOP_FUNCTIONS)
+            .map(|(func, cycles)| (self.symbols.name(func), cycles))
+            .collect()
+    }
+
+    fn write_trace_events(
+        &self,
+        pid: u32,
+        out: &mut impl Write,
+        first: &mut bool,
+    ) -> io::Result<()> {
+        write_event(
+            out,
+            first,
+            &json!({"name": "process_name", "ph": "M", "pid": pid, "args": {"name": self.name}}),
+        )?;
+        for event in self.events.iter() {
+            write_event(
+                out,
+                first,
+                &json!({
+                    "name": self.symbols.name(event.func),
+                    "cat": self.name,
+                    "ph": "X",
+                    "ts": event.start,
+                    "dur": event.duration,
+                    "pid": pid,
+                    "tid": 0,
+                }),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn write_event(
+    out: &mut impl Write,
+    first: &mut bool,
+    event: &serde_json::Value,
+) -> io::Result<()> {
+    if !*first {
+        out.write_all(b",\n")?;
+    }
+    *first = false;
+    serde_json::to_writer(&mut *out, event)?;
+    Ok(())
+}
+
+/// Change of the MCI flow status register, with the self cycles of each
+/// call tree node at that point.
+struct Checkpoint {
+    cycle: u64,
+    flow_status: u32,
+    mcu_cycles: Vec<u64>,
+    caliptra_cycles: Vec<u64>,
+}
+
+/// Profiler of both cores, with the boot checkpoints reported by the MCU ROM.
+pub struct Profiler {
+    dir: PathBuf,
+    flow_status_addr: u32,
+    pub mcu: CoreProfiler,
+    pub caliptra: CoreProfiler,
+    flow_status: u32,
+    checkpoints: Vec<Checkpoint>,
+}
+
+impl Profiler {
+    /// `flow_status_addr` is the MCU bus address of the MCI flow status
+    /// register written at each boot checkpoint.
+    pub fn new(
+        dir: PathBuf,
+        flow_status_addr: u32,
+        mcu_symbols: SymbolTable,
+        caliptra_symbols: SymbolTable,
+    ) -> Self {
+        Self {
+            dir,
+            flow_status_addr,
+            mcu: CoreProfiler::new("mcu", mcu_symbols),
+            caliptra: CoreProfiler::new("caliptra", caliptra_symbols),
+            flow_status: 0,
+            checkpoints: vec![],
+        }
+    }
+
+    pub fn flow_status_addr(&self) -> u32 {
+        self.flow_status_addr
+    }
+
+    /// Record the flow status register read at `cycle`.
+    pub fn flow_status(&mut self, cycle: u64, value: u32) {
+        if value == self.flow_status {
+            return;
+        }
+        self.flow_status = value;
+        self.checkpoints.push(Checkpoint {
+            cycle,
+            flow_status: value,
+            mcu_cycles: self.mcu.self_cycles(),
+            caliptra_cycles: self.caliptra.self_cycles(),
+        });
+    }
+
+    /// Cycles spent reaching each boot checkpoint and milestone, with the
+    /// functions that used most of them.
+    pub fn write_budget(&self, cycle: u64, out: &mut impl Write) -> io::Result<()> {
+        writeln!(out, "Boot cycle budget (MCU cycles)")?;
+        writeln!(out, "{:>12} {:>12}  checkpoint", "cycle", "delta")?;
+        let end = Checkpoint {
+            cycle,
+            flow_status: self.flow_status,
+            mcu_cycles: self.mcu.self_cycles(),
+            caliptra_cycles: self.caliptra.self_cycles(),
+        };
+        let mut prev_cycle = 0;
+        let mut prev_status = 0;
+        let (mut prev_mcu, mut prev_caliptra): (&[u64], &[u64]) = (&[], &[]);
+        for (i, checkpoint) in self.checkpoints.iter().chain([&end]).enumerate() {
+            let label = if i == self.checkpoints.len() {
+                "end of run".to_string()
+            } else {
+                checkpoint_label(prev_status, checkpoint.flow_status)
+            };
+            writeln!(
+                out,
+                "{:>12} {:>12}  {}",
+                checkpoint.cycle,
+                checkpoint.cycle - prev_cycle,
+                label
+            )?;
+            for (core, before, after) in [
+                (&self.mcu, prev_mcu, &checkpoint.mcu_cycles),
+                (&self.caliptra, prev_caliptra, &checkpoint.caliptra_cycles),
+            ] {
+                let top = core.top_functions(before, after);
+                if !top.is_empty() {
+                    let top: Vec<String> = top
+                        .iter()
+                        .map(|(name, cycles)| format!("{} ({})", name, cycles))
+                        .collect();
+                    writeln!(out, "{:>27}{}: {}", "", core.name, top.join(", "))?;
+                }
+            }
+            prev_cycle = checkpoint.cycle;
+            prev_status = checkpoint.flow_status;
+            prev_mcu = &checkpoint.mcu_cycles;
+            prev_caliptra = &checkpoint.caliptra_cycles;
+        }
+        Ok(())
+    }
+
+    /// Write the folded stacks of each core, a Chrome trace of both cores
+    /// and the boot cycle budget to the profile directory. `cycle` is the
+    /// current MCU cycle.
+    pub fn write(&mut self, cycle: u64) -> io::Result<()> {
+        self.mcu.finish(cycle);
+        self.caliptra.finish(cycle);
+        fs::create_dir_all(&self.dir)?;
+
+        for core in [&self.mcu, &self.caliptra] {
+            let mut out = BufWriter::new(File::create(
+                self.dir.join(format!("{}.folded", core.name)),
+            )?);
+            core.write_folded(&mut out)?;
+            out.flush()?;
+            if core.dropped_events > 0 {
+                println!(
+                    "Profiler: {} trace events of the {} core dropped after the first {}",
+                    core.dropped_events, core.name, MAX_TRACE_EVENTS
+                );
+            }
+        }
+
+        // Timestamps are in cycles, so 1 us in the trace viewer is 1 cycle.
+        let mut out = BufWriter::new(File::create(self.dir.join("trace.json"))?);
+        out.write_all(b"{\"traceEvents\":[\n")?;
+        let mut first = true;
+        self.mcu.write_trace_events(0, &mut out, &mut first)?;
+        self.caliptra.write_trace_events(1, &mut out, &mut first)?;
+        let mut prev_status = 0;
+        for checkpoint in self.checkpoints.iter() {
+            write_event(
+                &mut out,
+                &mut first,
+                &json!({
+                    "name": checkpoint_label(prev_status, checkpoint.flow_status),
+                    "ph": "i",
+                    "s": "g",
+                    "ts": checkpoint.cycle,
+                    "pid": 0,
+                    "tid": 0,
+                }),
+            )?;
+            prev_status = checkpoint.flow_status;
+        }
+        out.write_all(b"\n]}\n")?;
+        out.flush()?;
+
+        let mut budget = vec![];
+        self.write_budget(cycle, &mut budget)?;
+        fs::write(self.dir.join("boot_budget.txt"), &budget)?;
+        print!("{}", String::from_utf8_lossy(&budget));
+        println!("Profile written to {}", self.dir.display());
+        Ok(())
+    }
+}
+
+/// Name of the checkpoint in `flow_status` and of the milestones set since
+/// `prev`.
+fn checkpoint_label(prev: u32, flow_status: u32) -> String {
+    let code = flow_status as u16;
+    let mut label = match McuRomBootStatus::try_from(code) {
+        Ok(status) => format!("{:?} ({})", status, code),
+        Err(code) => format!("checkpoint {}", code),
+    };
+    let milestones = McuBootMilestones::from((flow_status >> 16) as u16);
+    let reached = McuBootMilestones::from((prev >> 16) as u16);
+    for (name, milestone) in milestones.iter_names() {
+        if !reached.contains(milestone) {
+            label.push_str(" +");
+            label.push_str(name);
+        }
+    }
+    label
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JAL_RA: u32 = 0x0000_00ef;
+    const RET: u32 = 0x0000_8067;
+    const C_RET: u32 = 0x8082;
+    const C_J: u32 = 0xa001;
+    const ADDI: u32 = 0x0000_0013;
+    const ECALL: u32 = 0x0000_0073;
+
+    fn symbols() -> SymbolTable {
+        SymbolTable::new(vec![
+            Symbol {
+                addr: 0x300,
+                size: 0x100,
+                name: "handler".into(),
+            },
+            Symbol {
+                addr: 0x200,
+                size: 0x100,
+                name: "helper".into(),
+            },
+            Symbol {
+                addr: 0x100,
+                size: 0x100,
+                name: "main".into(),
+            },
+            Symbol {
+                addr: 0x100,
+                size: 0,
+                name: "_start".into(),
+            },
+        ])
+    }
+
+    #[test]
+    fn test_resolve() {
+        let symbols = symbols();
+        assert_eq!(symbols.len(), 3);
+        assert_eq!(symbols.resolve(0x104), (0x100, 0x100..0x200));
+        assert_eq!(symbols.resolve(0x2fe), (0x200, 0x200..0x300));
+        assert_eq!(symbols.resolve(0x40), (0, 0..0x100));
+        assert_eq!(symbols.resolve(0x400), (0x400, 0x400..u32::MAX));
+        assert_eq!(symbols.name(0x100), "main");
+        assert_eq!(symbols.name(0x400), "0x00000400");
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify(JAL_RA), Flow::Call);
+        assert_eq!(classify(RET), Flow::Return);
+        assert_eq!(classify(C_RET), Flow::Return);
+        assert_eq!(classify(C_J), Flow::Jump);
+        assert_eq!(classify(MRET), Flow::Return);
+        assert_eq!(classify(ADDI), Flow::Other);
+        // c.jalr a5
+        assert_eq!(classify(0x9782), Flow::Call);
+        // beq x0, x0, 0
+        assert_eq!(classify(0x0000_0063), Flow::Branch);
+    }
+
+    #[test]
+    fn test_demangle() {
+        assert_eq!(
+            demangle("_ZN4core3ptr13drop_in_place17h0123456789abcdefE"),
+            "core::ptr::drop_in_place"
+        );
+        assert_eq!(
+            demangle("_ZN42_$LT$romtime..Mci$u20$as$u20$core..Foo$GT$3bar17h0123456789abcdefE"),
+            "<romtime::Mci as core::Foo>::bar"
+        );
+        assert_eq!(demangle("memcpy"), "memcpy");
+        assert_eq!(demangle("_ZN3foo"), "_ZN3foo");
+    }
+
+    #[test]
+    fn test_call_stacks() {
+        let mut core = CoreProfiler::new("mcu", symbols());
+        let steps = [
+            // main calls helper, which takes a trap and tail calls main.
+            (0x100, Some(ADDI), 0x104),
+            (0x104, Some(JAL_RA), 0x200),
+            (0x200, Some(ECALL), 0x300),
+            (0x300, Some(MRET), 0x204),
+            (0x204, Some(C_J), 0x100),
+            (0x100, Some(RET), 0x108),
+            // Interrupt without an instruction, then a return from main.
+            (0x108, None, 0x300),
+            (0x300, Some(MRET), 0x108),
+            (0x108, Some(C_RET), 0x0),
+        ];
+        for (cycle, (pc, instr, next_pc)) in steps.into_iter().enumerate() {
+            let cycle = cycle as u64 * 10;
+            core.step(pc, instr, next_pc, cycle, cycle + 10);
+        }
+        core.finish(90);
+        assert_eq!(core.total_cycles(), 90);
+
+        let mut folded = vec![];
+        core.write_folded(&mut folded).unwrap();
+        assert_eq!(
+            String::from_utf8(folded).unwrap(),
+            "main 40\n\
+             main;handler 10\n\
+             main;helper 20\n\
+             main;helper;handler 10\n\
+             main;main 10\n"
+        );
+        assert_eq!(core.events.len(), 6);
+        assert!(core.stack.is_empty());
+    }
+
+    #[test]
+    fn test_budget() {
+        let mut profiler = Profiler::new(PathBuf::new(), 0, symbols(), SymbolTable::default());
+        profiler.mcu.step(0x100, Some(ADDI), 0x104, 0, 100);
+        profiler.flow_status(100, 0x0001_0001);
+        profiler.mcu.step(0x104, Some(JAL_RA), 0x200, 100, 110);
+        profiler.mcu.step(0x200, Some(ADDI), 0x204, 110, 150);
+        profiler.flow_status(150, 0x0003_00c1);
+
+        let mut budget = vec![];
+        profiler.write_budget(200, &mut budget).unwrap();
+        assert_eq!(
+            String::from_utf8(budget).unwrap(),
+            "Boot cycle budget (MCU cycles)\n\
+             \x20      cycle        delta  checkpoint\n\
+             \x20        100          100  RomStarted (1) +ROM_STARTED\n\
+             \x20                          mcu: main (100)\n\
+             \x20        150           50  CaliptraBootGoAsserted (193) +CPTRA_BOOT_GO_ASSERTED\n\
+             \x20                          mcu: helper (40), main (10)\n\
+             \x20        200           50  end of run\n"
+        );
+    }
+}
@@ -13,6 +13,12 @@ use strum_macros::EnumIter;
 use zerocopy::IntoBytes;
 
 const TEST_TARGET_EID: u8 = 0xA;
+const MCTP_BROADCAST_EID: u8 = 0xFF;
+const CALIPTRA_PCI_VENDOR_ID: u16 = 0x1414;
+const CALIPTRA_VDM_CMD_SET_VERSION: u16 = 0x0001;
+// The emulator OTP does not provision the IDevID UEID
+const EMULATOR_IDEVID_UEID: [u8; caliptra_mcu_romtime::DEVICE_UUID_LEN] =
+    [0; caliptra_mcu_romtime::DEVICE_UUID_LEN];
 
 type MCTPCtrlMsg = (
     MCTPMsgHdr<[u8; MCTP_MSG_HDR_SIZE]>,
@@ -37,6 +43,13 @@ pub(crate) enum MCTPCtrlCmdTests {
     GetMctpVersionSupportUnspecified,
     GetMctpVersionSupportUnsupported,
     GetMsgTypeSupport,
+    GetEndpointUUID,
+    GetVendorDefinedMsgSupport,
+    GetVendorDefinedMsgSupportInvalidSelector,
+    PrepareForEndpointDiscovery,
+    EndpointDiscovery,
+    SetEIDDiscoveredFlag,
+    GetNetworkID,
 }
 
 impl MCTPCtrlCmdTests {
@@ -48,13 +61,14 @@ impl MCTPCtrlCmdTests {
                 let req_msg = test_id.generate_request_msg();
                 let resp_msg = test_id.generate_response_msg();
                 let msg_tag = (i % 4) as u8;
-                Box::new(Test::new(test_name, req_msg, resp_msg, msg_tag))
+                let dest_eid = test_id.dest_eid();
+                Box::new(Test::new(test_name, req_msg, resp_msg, msg_tag, dest_eid))
                     as Box<dyn MctpTransportTest + Send>
             })
             .collect()
     }
 
-    fn generate_request_msg(&self) -> Vec<u8> {
+    pub(crate) fn generate_request_msg(&self) -> Vec<u8> {
         let mctp_common_msg_hdr = MCTPMsgHdr::new();
 
         let mut mctp_ctrl_msg_hdr = MCTPCtrlMsgHdr::new();
@@ -87,11 +101,29 @@ impl MCTPCtrlCmdTests {
             MCTPCtrlCmdTests::GetMsgTypeSupport => {
                 vec![]
             }
+            MCTPCtrlCmdTests::GetEndpointUUID => {
+                vec![]
+            }
+            MCTPCtrlCmdTests::GetVendorDefinedMsgSupport => {
+                vec![0x00]
+            }
+            MCTPCtrlCmdTests::GetVendorDefinedMsgSupportInvalidSelector => {
+                vec![0x01]
+            }
+            MCTPCtrlCmdTests::PrepareForEndpointDiscovery | MCTPCtrlCmdTests::EndpointDiscovery => {
+                vec![]
+            }
+            MCTPCtrlCmdTests::SetEIDDiscoveredFlag => {
+                set_eid_req_bytes(SetEIDOp::SetDiscoveredFlag, 0)
+            }
+            MCTPCtrlCmdTests::GetNetworkID => {
+                vec![]
+            }
         };
         MCTPCtrlCmdTests::generate_msg((mctp_common_msg_hdr, mctp_ctrl_msg_hdr, req_data))
     }
 
-    fn generate_response_msg(&self) -> Vec<u8> {
+    pub(crate) fn generate_response_msg(&self) -> Vec<u8> {
         let mctp_common_msg_hdr = MCTPMsgHdr::new();
 
         let mut mctp_ctrl_msg_hdr = MCTPCtrlMsgHdr::new();
@@ -161,6 +193,39 @@ impl MCTPCtrlCmdTests {
                 ];
                 generate_msg_type_support_resp_bytes(CmdCompletionCode::Success as u8, &msg_types)
             }
+            MCTPCtrlCmdTests::GetEndpointUUID => get_uuid_resp_bytes(
+                CmdCompletionCode::Success,
+                &caliptra_mcu_romtime::device_uuid(&EMULATOR_IDEVID_UEID),
+            ),
+            MCTPCtrlCmdTests::GetVendorDefinedMsgSupport => {
+                get_vendor_defined_msg_support_resp_bytes(
+                    CmdCompletionCode::Success,
+                    0xFF, // No more vendor ID sets
+                    CALIPTRA_PCI_VENDOR_ID,
+                    CALIPTRA_VDM_CMD_SET_VERSION,
+                )
+            }
+            MCTPCtrlCmdTests::GetVendorDefinedMsgSupportInvalidSelector => {
+                get_vendor_defined_msg_support_resp_bytes(
+                    CmdCompletionCode::ErrorInvalidData,
+                    0,
+                    0,
+                    0,
+                )
+            }
+            MCTPCtrlCmdTests::PrepareForEndpointDiscovery | MCTPCtrlCmdTests::EndpointDiscovery => {
+                vec![CmdCompletionCode::Success as u8]
+            }
+            MCTPCtrlCmdTests::SetEIDDiscoveredFlag => set_eid_resp_bytes(
+                CmdCompletionCode::Success,
+                SetEIDStatus::Accepted,
+                SetEIDAllocStatus::NoEIDPool,
+                TEST_TARGET_EID + 1,
+            ),
+            MCTPCtrlCmdTests::GetNetworkID => get_uuid_resp_bytes(
+                CmdCompletionCode::Success,
+                &caliptra_mcu_romtime::device_network_id(&EMULATOR_IDEVID_UEID),
+            ),
         };
 
         MCTPCtrlCmdTests::generate_msg((mctp_common_msg_hdr, mctp_ctrl_msg_hdr, resp_data))
@@ -184,6 +249,19 @@ impl MCTPCtrlCmdTests {
         pkt
     }
 
+    /// EID the request is sent to. SetEID goes to the null EID as the endpoint
+    /// has not been assigned one yet, and discovery requests are broadcast by the
+    /// bus owner.
+    pub(crate) fn dest_eid(&self) -> u8 {
+        match self {
+            MCTPCtrlCmdTests::SetEID => 0,
+            MCTPCtrlCmdTests::PrepareForEndpointDiscovery | MCTPCtrlCmdTests::EndpointDiscovery => {
+                MCTP_BROADCAST_EID
+            }
+            _ => TEST_TARGET_EID,
+        }
+    }
+
     fn name(&self) -> String {
         format!("{:?}", self) // Uses the Debug implementation
     }
@@ -194,7 +272,8 @@ impl MCTPCtrlCmdTests {
             | MCTPCtrlCmdTests::SetEIDForce
             | MCTPCtrlCmdTests::SetEIDNullFail
             | MCTPCtrlCmdTests::SetEIDBroadcastFail
-            | MCTPCtrlCmdTests::SetEIDInvalidFail => MCTPCtrlCmd::SetEID as u8,
+            | MCTPCtrlCmdTests::SetEIDInvalidFail
+            | MCTPCtrlCmdTests::SetEIDDiscoveredFlag => MCTPCtrlCmd::SetEID as u8,
             MCTPCtrlCmdTests::GetEID => MCTPCtrlCmd::GetEID as u8,
             MCTPCtrlCmdTests::GetMctpVersionSupportMctpBase
             | MCTPCtrlCmdTests::GetMctpVersionSupportMctpControlProtocol
@@ -203,6 +282,16 @@ impl MCTPCtrlCmdTests {
                 MCTPCtrlCmd::GetMctpVersionSupport as u8
             }
             MCTPCtrlCmdTests::GetMsgTypeSupport => MCTPCtrlCmd::GetMsgTypeSupport as u8,
+            MCTPCtrlCmdTests::GetEndpointUUID => MCTPCtrlCmd::GetEndpointUUID as u8,
+            MCTPCtrlCmdTests::GetVendorDefinedMsgSupport
+            | MCTPCtrlCmdTests::GetVendorDefinedMsgSupportInvalidSelector => {
+                MCTPCtrlCmd::GetVendorDefinedMsgSupport as u8
+            }
+            MCTPCtrlCmdTests::PrepareForEndpointDiscovery => {
+                MCTPCtrlCmd::PrepareForEndpointDiscovery as u8
+            }
+            MCTPCtrlCmdTests::EndpointDiscovery => MCTPCtrlCmd::EndpointDiscovery as u8,
+            MCTPCtrlCmdTests::GetNetworkID => MCTPCtrlCmd::GetNetworkID as u8,
         }
     }
 }
@@ -214,18 +303,20 @@ struct Test {
     req_msg: Vec<u8>,
     resp_msg: Vec<u8>,
     msg_tag: u8,
+    dest_eid: u8,
     mctp_util: MctpUtil,
     passed: bool,
 }
 
 impl Test {
-    fn new(name: String, req_msg: Vec<u8>, resp_msg: Vec<u8>, msg_tag: u8) -> Self {
+    fn new(name: String, req_msg: Vec<u8>, resp_msg: Vec<u8>, msg_tag: u8, dest_eid: u8) -> Self {
         Self {
             name,
             test_state: MctpTestState::Start,
             req_msg,
             resp_msg,
             msg_tag,
+            dest_eid,
             mctp_util: MctpUtil::new(),
             passed: false,
         }
@@ -238,10 +329,7 @@ impl Test {
     }
 
     fn pre_process(&mut self) {
-        match self.name.as_str() {
-            "SetEID" => {}
-            _ => self.mctp_util.set_dest_eid(TEST_TARGET_EID),
-        }
+        self.mctp_util.set_dest_eid(self.dest_eid);
     }
 }
 
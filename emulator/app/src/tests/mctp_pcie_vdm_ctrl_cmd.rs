@@ -0,0 +1,102 @@
+// Licensed under the Apache-2.0 license
+
+//! MCTP control commands sent by the root complex over the PCIe VDM socket.
+//! The runtime must be built with the `mctp-pcie-vdm` binding.
+
+use crate::tests::mctp_ctrl_cmd::MCTPCtrlCmdTests;
+use caliptra_mcu_testing_common::i3c_socket::DEFAULT_TEST_TIMEOUT_TICKS;
+use caliptra_mcu_testing_common::mctp_util::base_protocol::{
+    MCTPHdr, LOCAL_TEST_ENDPOINT_EID, MCTP_HDR_SIZE,
+};
+use caliptra_mcu_testing_common::pcie_vdm_socket::PcieVdmSocket;
+use caliptra_mcu_testing_common::{wait_emulator_ticks, wait_for_runtime_start, MCU_RUNNING};
+use std::process::exit;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use zerocopy::{FromBytes, IntoBytes};
+
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Discovery requests are skipped: on PCIe they are broadcast by the root complex,
+// which the socket client does not route.
+const TESTS: [MCTPCtrlCmdTests; 6] = [
+    MCTPCtrlCmdTests::SetEID,
+    MCTPCtrlCmdTests::SetEIDForce,
+    MCTPCtrlCmdTests::GetEID,
+    MCTPCtrlCmdTests::GetMsgTypeSupport,
+    MCTPCtrlCmdTests::GetEndpointUUID,
+    MCTPCtrlCmdTests::GetNetworkID,
+];
+
+pub(crate) fn run_tests(port: u16) {
+    std::thread::spawn(move || {
+        if !wait_emulator_ticks(DEFAULT_TEST_TIMEOUT_TICKS) {
+            return;
+        }
+        println!(
+            "INTEGRATION TEST ON MCTP-PCIE-VDM TIMED OUT AFTER {} TICKS",
+            DEFAULT_TEST_TIMEOUT_TICKS
+        );
+        exit(-1);
+    });
+    std::thread::spawn(move || {
+        wait_for_runtime_start();
+        if !MCU_RUNNING.load(Ordering::Relaxed) {
+            exit(-1);
+        }
+        let mut socket = PcieVdmSocket::connect(port).unwrap();
+        let mut passed = 0;
+        for (i, test) in TESTS.iter().enumerate() {
+            println!("Starting test: {:?}", test);
+            let result = run_test(&mut socket, test, (i % 4) as u8);
+            println!(
+                "Test {:?} : {}",
+                test,
+                if result { "PASSED" } else { "FAILED" }
+            );
+            if result {
+                passed += 1;
+            }
+        }
+        println!("Test Result: {}/{} tests passed", passed, TESTS.len());
+        MCU_RUNNING.store(false, Ordering::Relaxed);
+        if passed == TESTS.len() {
+            exit(0);
+        } else {
+            exit(-1);
+        }
+    });
+}
+
+/// Sends the request as a single packet and checks that the response carries the
+/// same tag and the expected message.
+fn run_test(socket: &mut PcieVdmSocket, test: &MCTPCtrlCmdTests, msg_tag: u8) -> bool {
+    let mut hdr = MCTPHdr::new();
+    hdr.prepare_header(
+        test.dest_eid(),
+        LOCAL_TEST_ENDPOINT_EID,
+        1,
+        1,
+        0,
+        1,
+        msg_tag,
+    );
+    let mut pkt = hdr.as_bytes().to_vec();
+    pkt.extend_from_slice(&test.generate_request_msg());
+    socket.send(&pkt).unwrap();
+
+    let Some(resp) = socket.receive(Some(RESPONSE_TIMEOUT)).unwrap() else {
+        println!("No response to {:?}", test);
+        return false;
+    };
+    if resp.len() < MCTP_HDR_SIZE {
+        return false;
+    }
+    let resp_hdr: MCTPHdr<[u8; MCTP_HDR_SIZE]> =
+        MCTPHdr::read_from_bytes(&resp[..MCTP_HDR_SIZE]).unwrap();
+    resp_hdr.som() == 1
+        && resp_hdr.eom() == 1
+        && resp_hdr.tag_owner() == 0
+        && resp_hdr.msg_tag() == msg_tag
+        && resp[MCTP_HDR_SIZE..] == test.generate_response_msg()[..]
+}
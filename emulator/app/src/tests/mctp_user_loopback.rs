@@ -1,12 +1,17 @@
 // Licensed under the Apache-2.0 license
 
+use caliptra_mcu_testing_common::i3c::{CCC_DIRECT_SETMRL, CCC_DIRECT_SETMWL};
 use caliptra_mcu_testing_common::i3c_socket::{BufferedStream, MctpTestState, MctpTransportTest};
+use caliptra_mcu_testing_common::mctp_util::base_protocol::MCTP_HDR_SIZE;
 use caliptra_mcu_testing_common::mctp_util::common::MctpUtil;
 use caliptra_mcu_testing_common::MCU_RUNNING;
 use std::sync::atomic::Ordering;
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
+// Largest transfer the MCU I3C target can buffer
+const NEGOTIATED_XFER_LEN: u16 = 250;
+
 #[derive(EnumIter, Debug)]
 pub(crate) enum MctpUserAppTests {
     MctpAppResponderReady,
@@ -15,6 +20,8 @@ pub(crate) enum MctpUserAppTests {
     MctpAppLoopbackTest256,
     MctpAppLoopbackTest1000,
     MctpAppLoopbackTest1024,
+    // Must stay last: the negotiated MTU applies to all later messages.
+    MctpAppLoopbackTestNegotiatedMtu,
 }
 
 impl MctpUserAppTests {
@@ -26,8 +33,9 @@ impl MctpUserAppTests {
                 let msg_tag = (i % 4) as u8;
                 let req_msg_buf = test_id.generate_req_msg(msg_type);
 
-                Box::new(Test::new(test_name, msg_type, msg_tag, req_msg_buf))
-                    as Box<dyn MctpTransportTest + Send>
+                let mut test = Test::new(test_name, msg_type, msg_tag, req_msg_buf);
+                test.negotiated_xfer_len = test_id.negotiated_xfer_len();
+                Box::new(test) as Box<dyn MctpTransportTest + Send>
             })
             .collect()
     }
@@ -40,6 +48,9 @@ impl MctpUserAppTests {
             MctpUserAppTests::MctpAppLoopbackTest256 => "MctpAppLoopbackTest256",
             MctpUserAppTests::MctpAppLoopbackTest1000 => "MctpAppLoopbackTest1000",
             MctpUserAppTests::MctpAppLoopbackTest1024 => "MctpAppLoopbackTest1024",
+            MctpUserAppTests::MctpAppLoopbackTestNegotiatedMtu => {
+                "MctpAppLoopbackTestNegotiatedMtu"
+            }
         }
     }
 
@@ -51,6 +62,15 @@ impl MctpUserAppTests {
             MctpUserAppTests::MctpAppLoopbackTest256 => 256,
             MctpUserAppTests::MctpAppLoopbackTest1000 => 1000,
             MctpUserAppTests::MctpAppLoopbackTest1024 => 1024,
+            MctpUserAppTests::MctpAppLoopbackTestNegotiatedMtu => 1024,
+        }
+    }
+
+    /// Max write/read length the controller negotiates with SETMWL/SETMRL before the test.
+    fn negotiated_xfer_len(&self) -> Option<u16> {
+        match self {
+            MctpUserAppTests::MctpAppLoopbackTestNegotiatedMtu => Some(NEGOTIATED_XFER_LEN),
+            _ => None,
         }
     }
 
@@ -67,6 +87,7 @@ struct Test {
     msg_type: u8,
     msg_tag: u8,
     req_msg_buf: Vec<u8>,
+    negotiated_xfer_len: Option<u16>,
     passed: bool,
     mctp_util: MctpUtil,
 }
@@ -79,6 +100,7 @@ impl Test {
             msg_type,
             msg_tag,
             req_msg_buf,
+            negotiated_xfer_len: None,
             passed: false,
             mctp_util: MctpUtil::new(),
         }
@@ -106,11 +128,22 @@ impl Test {
         );
     }
 
+    fn negotiate_xfer_len(&mut self, stream: &mut BufferedStream, target_addr: u8, xfer_len: u16) {
+        stream.send_direct_ccc(target_addr, CCC_DIRECT_SETMWL, &xfer_len.to_be_bytes());
+        stream.send_direct_ccc(target_addr, CCC_DIRECT_SETMRL, &xfer_len.to_be_bytes());
+        // Each transfer carries the MCTP header and the trailing PEC byte
+        self.mctp_util
+            .set_pkt_payload_size(xfer_len as usize - MCTP_HDR_SIZE - 1);
+    }
+
     fn run_loopback_test(&mut self, stream: &mut BufferedStream, target_addr: u8) {
         stream.set_nonblocking(true).unwrap();
         while MCU_RUNNING.load(Ordering::Relaxed) {
             match self.test_state {
                 MctpTestState::Start => {
+                    if let Some(xfer_len) = self.negotiated_xfer_len {
+                        self.negotiate_xfer_len(stream, target_addr, xfer_len);
+                    }
                     self.test_state = MctpTestState::SendReq;
                 }
                 MctpTestState::SendReq => {
@@ -126,7 +159,10 @@ impl Test {
                     let resp_msg = self.mctp_util.receive_response(stream, target_addr, None);
                     if !resp_msg.is_empty() {
                         assert!(self.req_msg_buf == resp_msg);
-                        self.passed = true;
+                        // The response must be packetized with the negotiated MTU
+                        self.passed = self.negotiated_xfer_len.is_none()
+                            || self.mctp_util.get_rx_pkt_payload_size()
+                                == self.mctp_util.get_pkt_payload_size();
                     }
                     self.test_state = MctpTestState::Finish;
                 }
@@ -6,5 +6,6 @@ pub mod doe_transport_loopback;
 pub mod doe_user_loopback;
 pub mod emulator_mcu_mailbox_test;
 pub mod mctp_ctrl_cmd;
+pub mod mctp_pcie_vdm_ctrl_cmd;
 pub mod mctp_user_loopback;
 pub mod pldm_request_response_test;
@@ -10,8 +10,21 @@ use caliptra_mcu_pldm_common::message::firmware_update::get_fw_params::{
 use caliptra_mcu_pldm_common::message::firmware_update::query_devid::{
     QueryDeviceIdentifiersRequest, QueryDeviceIdentifiersResponse,
 };
+use caliptra_mcu_pldm_common::message::platform::get_pdr_repo_info::{
+    GetPdrRepositoryInfoRequest, GetPdrRepositoryInfoResponse, PdrRepositoryInfo,
+};
+use caliptra_mcu_pldm_common::message::platform::get_sensor_reading::{
+    GetSensorReadingRequest, GetSensorReadingResponse, NumericSensorReading,
+};
+use caliptra_mcu_pldm_common::message::platform::get_state_sensor_readings::{
+    GetStateSensorReadingsRequest, GetStateSensorReadingsResponse,
+};
 use caliptra_mcu_pldm_common::protocol::base::*;
 use caliptra_mcu_pldm_common::protocol::firmware_update::*;
+use caliptra_mcu_pldm_common::protocol::platform::{
+    PdrRepositoryState, PlatformCmd, SensorCompletionCode, SensorEventMessageEnable,
+    SensorOperationalState, SensorReading, SensorState, StateField,
+};
 use caliptra_mcu_pldm_ua::transport::PldmSocket;
 use caliptra_mcu_testing_common::mctp_transport::MctpPldmSocket;
 use caliptra_mcu_testing_common::{wait_for_runtime_start, MCU_RUNNING};
@@ -129,12 +142,13 @@ impl PldmRequestResponseTest {
         Self::add_test_message(
             test_messages,
             GetPldmTypeRequest::new(4u8, PldmMsgType::Request),
-            // PLDM types supported by the device are 0x0 and 0x5
+            // PLDM types supported by the device are 0x0, 0x2 and 0x5
             GetPldmTypeResponse::new(
                 4u8,
                 0u8,
                 &[
                     PldmSupportedType::Base as u8,
+                    PldmSupportedType::Platform as u8,
                     PldmSupportedType::FwUpdate as u8,
                 ],
             ),
@@ -216,6 +230,97 @@ impl PldmRequestResponseTest {
                 ],
             ),
         );
+
+        Self::add_pldm_platform_test_message(test_messages);
+    }
+
+    fn add_pldm_platform_test_message(test_messages: &mut Vec<PldmExpectedMessagePair>) {
+        Self::add_test_message(
+            test_messages,
+            GetPldmVersionRequest::new(
+                9u8,
+                PldmMsgType::Request,
+                0,
+                TransferOperationFlag::GetFirstPart,
+                PldmSupportedType::Platform,
+            ),
+            GetPldmVersionResponse::new(9u8, 0u8, 0, TransferRespFlag::StartAndEnd, "1.2.0")
+                .unwrap(),
+        );
+
+        Self::add_test_message(
+            test_messages,
+            GetPldmCommandsRequest::new(
+                10u8,
+                PldmMsgType::Request,
+                PldmSupportedType::Platform as u8,
+                "1.2.0",
+            ),
+            GetPldmCommandsResponse::new(
+                10u8,
+                0u8,
+                &[
+                    PlatformCmd::PlatformEventMessage as u8,
+                    PlatformCmd::PollForPlatformEventMessage as u8,
+                    PlatformCmd::GetSensorReading as u8,
+                    PlatformCmd::GetStateSensorReadings as u8,
+                    PlatformCmd::GetPdrRepositoryInfo as u8,
+                    PlatformCmd::GetPdr as u8,
+                ],
+            ),
+        );
+
+        // The device exposes a compact numeric sensor PDR (49 bytes) and a state
+        // sensor PDR (27 bytes).
+        Self::add_test_message(
+            test_messages,
+            GetPdrRepositoryInfoRequest::new(11u8, PldmMsgType::Request),
+            GetPdrRepositoryInfoResponse::new(
+                11u8,
+                0u8,
+                &PdrRepositoryInfo::new(PdrRepositoryState::Available, 2, 76, 49),
+            ),
+        );
+
+        Self::add_test_message(
+            test_messages,
+            GetSensorReadingRequest::new(12u8, PldmMsgType::Request, 1, false),
+            GetSensorReadingResponse::new(
+                12u8,
+                0u8,
+                &NumericSensorReading {
+                    sensor_op_state: SensorOperationalState::Enabled,
+                    event_message_enable: SensorEventMessageEnable::NoEventGeneration,
+                    present_state: SensorState::Normal,
+                    previous_state: SensorState::Normal,
+                    event_state: SensorState::Normal,
+                    present_reading: SensorReading::Sint8(40),
+                },
+            ),
+        );
+
+        Self::add_test_message(
+            test_messages,
+            GetStateSensorReadingsRequest::new(13u8, PldmMsgType::Request, 2, 0),
+            GetStateSensorReadingsResponse::new(
+                13u8,
+                0u8,
+                &[StateField::new(SensorOperationalState::Enabled, 1, 1, 1)],
+            )
+            .unwrap(),
+        );
+
+        // Sensor 2 is a state sensor, not a numeric sensor
+        Self::add_test_message(
+            test_messages,
+            GetSensorReadingRequest::new(14u8, PldmMsgType::Request, 2, false),
+            PldmFailureResponse::new(
+                14u8,
+                PldmSupportedType::Platform,
+                PlatformCmd::GetSensorReading as u8,
+                SensorCompletionCode::InvalidSensorId as u8,
+            ),
+        );
     }
 
     fn add_pldm_fw_update_test_message(test_messages: &mut Vec<PldmExpectedMessagePair>) {
@@ -13,6 +13,10 @@ Licensed under the Apache-2.0 license.
 /// This CLI tool provides the following subcommands:
 /// - `encode`: Convert a manifest TOML file into a firmware package.
 /// - `decode`: Convert a firmware package back into a manifest TOML file and its firmware components.
+/// - `verify`: Verify the signature of a signed firmware package against trust anchor public keys.
+///
+/// Signing and trust anchor keys are raw binary files: a 48-byte P-384 private scalar, a P-384
+/// public key as SEC1 or X || Y (96 bytes), and ML-DSA-87 keys in FIPS 204 encoding.
 ///
 /// # Examples
 ///
@@ -21,12 +25,22 @@ Licensed under the Apache-2.0 license.
 /// caliptra_mcu_pldm_fw_pkg encode --manifest manifest.toml --file firmware.bin
 /// ```
 ///
+/// Encode and sign a manifest file:
+/// ```bash
+/// caliptra_mcu_pldm_fw_pkg encode --manifest manifest.toml --file firmware.bin --ecc-key ecc.key --mldsa-key mldsa.key
+/// ```
+///
 /// Decode a firmware package:
 /// ```bash
 /// caliptra_mcu_pldm_fw_pkg decode --file firmware.bin --directory output
 /// ```
 ///
-use caliptra_mcu_pldm_fw_pkg::FirmwareManifest;
+/// Verify a signed firmware package:
+/// ```bash
+/// caliptra_mcu_pldm_fw_pkg verify --package firmware.bin --ecc-public-key ecc.pub
+/// ```
+///
+use caliptra_mcu_pldm_fw_pkg::{FirmwareManifest, PackageSigningKeys, TrustAnchor};
 use clap::{Arg, Command};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -51,6 +65,18 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                         .value_name("FILE")
                         .help("Output file for the firmware package")
                         .required(true),
+                )
+                .arg(
+                    Arg::new("ecc-key")
+                        .long("ecc-key")
+                        .value_name("ECC_KEY")
+                        .help("P-384 private key used to sign the package (ECDSA)"),
+                )
+                .arg(
+                    Arg::new("mldsa-key")
+                        .long("mldsa-key")
+                        .value_name("MLDSA_KEY")
+                        .help("ML-DSA-87 private key used to sign the package"),
                 ),
         )
         .subcommand(
@@ -73,6 +99,30 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                         .required(true),
                 ),
         )
+        .subcommand(
+            Command::new("verify")
+                .about("Verifies the signature of a firmware package against a trust anchor")
+                .arg(
+                    Arg::new("package")
+                        .short('p')
+                        .long("package")
+                        .value_name("PACKAGE")
+                        .help("Path to the signed firmware package file")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("ecc-public-key")
+                        .long("ecc-public-key")
+                        .value_name("ECC_PUBLIC_KEY")
+                        .help("Trusted P-384 public key, an ECDSA signature is then required"),
+                )
+                .arg(
+                    Arg::new("mldsa-public-key")
+                        .long("mldsa-public-key")
+                        .value_name("MLDSA_PUBLIC_KEY")
+                        .help("Trusted ML-DSA-87 public key, an ML-DSA signature is then required"),
+                ),
+        )
         .get_matches();
 
     // Match on the subcommand and handle the arguments
@@ -83,7 +133,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             let firmware_manifest: FirmwareManifest =
                 FirmwareManifest::parse_manifest_file(manifest_path)
                     .expect("Failed to parse the manifest file");
-            firmware_manifest.generate_firmware_package(output_path)?;
+            let signing_keys = PackageSigningKeys::from_files(
+                sub_matches.get_one::<String>("ecc-key"),
+                sub_matches.get_one::<String>("mldsa-key"),
+            )?;
+            if signing_keys.is_empty() {
+                firmware_manifest.generate_firmware_package(output_path)?;
+            } else {
+                firmware_manifest.generate_signed_firmware_package(output_path, &signing_keys)?;
+            }
             println!("Encoded FirmwarePackage to binary file: {}", output_path);
         }
         Some(("decode", sub_matches)) => {
@@ -93,8 +151,23 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .expect("Failed to decode the firmware package");
             println!("Decoded FirmwarePackage to directory: {}", output_dir);
         }
+        Some(("verify", sub_matches)) => {
+            let package_path = sub_matches.get_one::<String>("package").unwrap();
+            let trust_anchor = TrustAnchor::from_files(
+                sub_matches.get_one::<String>("ecc-public-key"),
+                sub_matches.get_one::<String>("mldsa-public-key"),
+            )?;
+            let package = std::fs::read(package_path)?;
+            if let Err(e) =
+                FirmwareManifest::verify_signed_firmware_package(&package, &trust_anchor)
+            {
+                println!("FirmwarePackage signature verification failed: {}", e);
+                std::process::exit(1);
+            }
+            println!("Verified FirmwarePackage signature: {}", package_path);
+        }
         _ => {
-            println!("Use either 'encode' or 'decode' subcommands.");
+            println!("Use one of the 'encode', 'decode' or 'verify' subcommands.");
             std::process::exit(1);
         }
     }
@@ -17,6 +17,8 @@ use std::io::{BufReader, BufWriter};
 use std::str::FromStr;
 use uuid::Uuid;
 
+use crate::signature::{PackageSigningKeys, TrustAnchor};
+
 use crc::{Crc, CRC_32_ISO_HDLC};
 
 #[derive(Debug, Deserialize, Serialize, Default, Clone)]
@@ -415,8 +417,36 @@ impl FirmwareManifest {
 
     pub fn generate_firmware_package(&self, output_file_path: &String) -> io::Result<()> {
         println!("Generating firmware package: {}", output_file_path);
+        let package = self.encode_firmware_package()?;
+        let file = File::create(output_file_path)?;
+        let mut writer = BufWriter::new(file);
+        writer.write_all(&package)?;
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Generates a firmware package followed by a signature trailer over the whole
+    /// package (header, checksums and payload), see [`crate::signature`].
+    pub fn generate_signed_firmware_package(
+        &self,
+        output_file_path: &String,
+        signing_keys: &PackageSigningKeys,
+    ) -> io::Result<()> {
+        println!("Generating signed firmware package: {}", output_file_path);
+        let mut package = self.encode_firmware_package()?;
+        let trailer = signing_keys.sign(&package)?;
+        package.extend_from_slice(&trailer);
         let file = File::create(output_file_path)?;
         let mut writer = BufWriter::new(file);
+        writer.write_all(&package)?;
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Encodes the manifest and its component images into a firmware package.
+    pub fn encode_firmware_package(&self) -> io::Result<Vec<u8>> {
         let mut buffer: Vec<u8> = Vec::new();
 
         // Encode package_header_information
@@ -489,18 +519,14 @@ impl FirmwareManifest {
         // Calculate the checksum of the image data
         let pldm_fw_package_payload_checksum = crc32.checksum(&image_data);
 
-        // Write the package header to the writer
-        writer.write_all(&buffer)?;
-
-        // Write the checksums to the writer
-        writer.write_all(&package_header_checksum.to_le_bytes())?;
-        writer.write_all(&pldm_fw_package_payload_checksum.to_le_bytes())?;
+        // Append the checksums to the package header
+        buffer.write_all(&package_header_checksum.to_le_bytes())?;
+        buffer.write_all(&pldm_fw_package_payload_checksum.to_le_bytes())?;
 
-        // Write the image data to the writer
-        writer.write_all(&image_data)?;
-        writer.flush()?;
+        // Append the image data
+        buffer.append(&mut image_data);
 
-        Ok(())
+        Ok(buffer)
     }
 
     pub fn parse_manifest_file(file_path: &String) -> io::Result<Self> {
@@ -538,10 +564,44 @@ impl FirmwareManifest {
 
         let bin_file = File::open(fw_package_file_path)?;
         let mut reader = BufReader::new(bin_file);
+        Self::decode_from_reader(&mut reader, output_dir_path)
+    }
+
+    /// Decodes a firmware package held in memory. A signature trailer, if any, is ignored.
+    pub fn decode_firmware_package_bytes(package: &[u8]) -> io::Result<Self> {
+        Self::decode_from_reader(&mut &package[..], None)
+    }
 
+    /// Verifies the signature trailer of a package against `trust_anchor` and decodes the
+    /// signed package. Unsigned packages and packages whose signature does not verify
+    /// are rejected.
+    pub fn verify_signed_firmware_package(
+        package: &[u8],
+        trust_anchor: &TrustAnchor,
+    ) -> io::Result<Self> {
+        let signed_len = trust_anchor.verify_package(package)?;
+        let manifest = Self::decode_firmware_package_bytes(&package[..signed_len])?;
+        if get_pldm_version(
+            manifest
+                .package_header_information
+                .package_header_identifier,
+        ) != PldmVersion::Version13
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Package signatures require a v1.3 package header",
+            ));
+        }
+        Ok(manifest)
+    }
+
+    fn decode_from_reader<R: Read>(
+        reader: &mut R,
+        output_dir_path: Option<&String>,
+    ) -> io::Result<Self> {
         // Decode package_header_information
         let (package_header_information, component_bitmap_length) =
-            PackageHeaderInformation::decode(&mut reader)?;
+            PackageHeaderInformation::decode(reader)?;
 
         let pldm_version = get_pldm_version(package_header_information.package_header_identifier);
 
@@ -563,7 +623,7 @@ impl FirmwareManifest {
         let num_firmware_records = buffer[0];
         for _ in 0..num_firmware_records {
             firmware_device_id_records.push(FirmwareDeviceIdRecord::decode(
-                &mut reader,
+                reader,
                 component_bitmap_length,
                 &pldm_version,
             )?);
@@ -577,7 +637,7 @@ impl FirmwareManifest {
                 let num_downstream_records = buffer[0];
                 for _ in 0..num_downstream_records {
                     downstream_device_id_records.push(DownstreamDeviceIdRecord::decode(
-                        &mut reader,
+                        reader,
                         component_bitmap_length,
                         &pldm_version,
                     )?);
@@ -598,10 +658,8 @@ impl FirmwareManifest {
         let num_components = u16::from_le_bytes(buffer);
 
         for _ in 0..num_components {
-            component_image_information.push(ComponentImageInformation::decode(
-                &mut reader,
-                &pldm_version,
-            )?);
+            component_image_information
+                .push(ComponentImageInformation::decode(reader, &pldm_version)?);
         }
 
         // Read the package header checksum
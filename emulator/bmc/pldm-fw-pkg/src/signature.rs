@@ -0,0 +1,321 @@
+// Licensed under the Apache-2.0 license
+
+//! Package signatures for PLDM firmware packages.
+//!
+//! A signed package is a regular v1.3 package followed by a signature trailer:
+//!
+//! ```text
+//! +------------------------------------------------------------+
+//! | Package header | header checksum | payload checksum | images | <- signed data
+//! +------------------------------------------------------------+
+//! | algorithm (u8) | signature length (u16) | signature         | <- repeated per signature
+//! +------------------------------------------------------------+
+//! | signature count (u8) | trailer length (u32) | magic (8)     | <- footer
+//! +------------------------------------------------------------+
+//! ```
+//!
+//! The trailer length covers the signature entries and the footer, so the end of the
+//! signed data is found from the end of the file. Parsers that only read the component
+//! images by offset and size are not affected by the trailer.
+
+use ecdsa::signature::hazmat::{PrehashSigner, PrehashVerifier};
+use ecdsa::{Signature, SigningKey, VerifyingKey};
+use fips204::ml_dsa_87;
+use fips204::traits::{SerDes, Signer, Verifier};
+use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::FromPrimitive;
+use p384::NistP384;
+use sha2::{Digest, Sha384, Sha512};
+use std::fs;
+use std::io;
+
+pub const PACKAGE_SIGNATURE_MAGIC: [u8; 8] = *b"PLDMSIGN";
+const SIGNATURE_FOOTER_LEN: usize = 1 + 4 + PACKAGE_SIGNATURE_MAGIC.len();
+const SIGNATURE_ENTRY_HEADER_LEN: usize = 1 + 2;
+
+pub const ECC_PRIVATE_KEY_LEN: usize = 48;
+pub const ECC_RAW_PUBLIC_KEY_LEN: usize = 96;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+pub enum SignatureAlgorithm {
+    /// ECDSA P-384 over the SHA-384 digest of the signed data.
+    EcdsaP384Sha384 = 1,
+    /// ML-DSA-87 over the SHA-512 digest of the signed data, empty context.
+    MlDsa87Sha512 = 2,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackageSignature {
+    pub algorithm: SignatureAlgorithm,
+    pub signature: Vec<u8>,
+}
+
+/// Private keys used to sign a firmware package. At least one key must be present.
+#[derive(Clone, Default)]
+pub struct PackageSigningKeys {
+    /// P-384 private key (48 bytes, big-endian scalar).
+    pub ecc_private_key: Option<[u8; ECC_PRIVATE_KEY_LEN]>,
+    /// ML-DSA-87 private key bytes (FIPS 204 format).
+    pub mldsa_private_key: Option<Vec<u8>>,
+}
+
+/// Public keys a signed package is verified against. Every key present in the trust
+/// anchor requires a valid signature of the matching algorithm in the package.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrustAnchor {
+    /// P-384 public key, either SEC1 encoded or the raw X || Y coordinates (96 bytes).
+    pub ecc_public_key: Option<Vec<u8>>,
+    /// ML-DSA-87 public key bytes (FIPS 204 format).
+    pub mldsa_public_key: Option<Vec<u8>>,
+}
+
+fn invalid_data(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
+
+impl PackageSigningKeys {
+    /// Loads the raw key files given on the command line.
+    pub fn from_files(
+        ecc_private_key_path: Option<&String>,
+        mldsa_private_key_path: Option<&String>,
+    ) -> io::Result<Self> {
+        let ecc_private_key = match ecc_private_key_path {
+            Some(path) => Some(fs::read(path)?.as_slice().try_into().map_err(|_| {
+                invalid_data(format!(
+                    "ECC private key {} must be {} bytes",
+                    path, ECC_PRIVATE_KEY_LEN
+                ))
+            })?),
+            None => None,
+        };
+        let mldsa_private_key = match mldsa_private_key_path {
+            Some(path) => Some(fs::read(path)?),
+            None => None,
+        };
+        Ok(Self {
+            ecc_private_key,
+            mldsa_private_key,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ecc_private_key.is_none() && self.mldsa_private_key.is_none()
+    }
+
+    /// Signs `data` with every key present and returns the signature trailer to append.
+    pub fn sign(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        if self.is_empty() {
+            return Err(invalid_data("No package signing key provided"));
+        }
+
+        let mut signatures = Vec::new();
+        if let Some(ecc_private_key) = &self.ecc_private_key {
+            let secret = p384::SecretKey::from_slice(ecc_private_key)
+                .map_err(|e| invalid_data(format!("Invalid ECC private key: {}", e)))?;
+            let signing_key = SigningKey::<NistP384>::from(&secret);
+            let digest: [u8; 48] = Sha384::digest(data).into();
+            let signature: Signature<NistP384> = signing_key
+                .sign_prehash(&digest)
+                .map_err(|e| invalid_data(format!("ECDSA signing failed: {}", e)))?;
+            signatures.push(PackageSignature {
+                algorithm: SignatureAlgorithm::EcdsaP384Sha384,
+                signature: signature.to_bytes().to_vec(),
+            });
+        }
+        if let Some(mldsa_private_key) = &self.mldsa_private_key {
+            let key_bytes: [u8; ml_dsa_87::SK_LEN] =
+                mldsa_private_key.as_slice().try_into().map_err(|_| {
+                    invalid_data(format!(
+                        "Invalid ML-DSA-87 private key size: expected {}, got {}",
+                        ml_dsa_87::SK_LEN,
+                        mldsa_private_key.len()
+                    ))
+                })?;
+            let private_key = ml_dsa_87::PrivateKey::try_from_bytes(key_bytes)
+                .map_err(|_| invalid_data("Failed to parse ML-DSA-87 private key"))?;
+            let digest: [u8; 64] = Sha512::digest(data).into();
+            let signature = private_key
+                .try_sign_with_seed(&[0u8; 32], &digest, &[])
+                .map_err(|_| invalid_data("ML-DSA-87 signing failed"))?;
+            signatures.push(PackageSignature {
+                algorithm: SignatureAlgorithm::MlDsa87Sha512,
+                signature: signature.to_vec(),
+            });
+        }
+
+        Ok(encode_signature_trailer(&signatures))
+    }
+}
+
+impl TrustAnchor {
+    /// Loads the raw public key files given on the command line.
+    pub fn from_files(
+        ecc_public_key_path: Option<&String>,
+        mldsa_public_key_path: Option<&String>,
+    ) -> io::Result<Self> {
+        Ok(Self {
+            ecc_public_key: ecc_public_key_path.map(fs::read).transpose()?,
+            mldsa_public_key: mldsa_public_key_path.map(fs::read).transpose()?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ecc_public_key.is_none() && self.mldsa_public_key.is_none()
+    }
+
+    fn ecc_verifying_key(&self, public_key: &[u8]) -> io::Result<VerifyingKey<NistP384>> {
+        let result = if public_key.len() == ECC_RAW_PUBLIC_KEY_LEN {
+            // Raw X || Y coordinates, add the SEC1 uncompressed point tag.
+            let mut sec1 = vec![0x04];
+            sec1.extend_from_slice(public_key);
+            VerifyingKey::<NistP384>::from_sec1_bytes(&sec1)
+        } else {
+            VerifyingKey::<NistP384>::from_sec1_bytes(public_key)
+        };
+        result.map_err(|e| invalid_data(format!("Invalid ECC public key: {}", e)))
+    }
+
+    fn verify_signature(&self, data: &[u8], signature: &PackageSignature) -> io::Result<bool> {
+        match signature.algorithm {
+            SignatureAlgorithm::EcdsaP384Sha384 => {
+                let Some(public_key) = &self.ecc_public_key else {
+                    return Ok(false);
+                };
+                let verifying_key = self.ecc_verifying_key(public_key)?;
+                let ecdsa_signature = Signature::<NistP384>::from_slice(&signature.signature)
+                    .map_err(|_| invalid_data("Malformed ECDSA P-384 signature"))?;
+                let digest: [u8; 48] = Sha384::digest(data).into();
+                Ok(verifying_key
+                    .verify_prehash(&digest, &ecdsa_signature)
+                    .is_ok())
+            }
+            SignatureAlgorithm::MlDsa87Sha512 => {
+                let Some(public_key) = &self.mldsa_public_key else {
+                    return Ok(false);
+                };
+                let key_bytes: [u8; ml_dsa_87::PK_LEN] =
+                    public_key.as_slice().try_into().map_err(|_| {
+                        invalid_data(format!(
+                            "Invalid ML-DSA-87 public key size: expected {}, got {}",
+                            ml_dsa_87::PK_LEN,
+                            public_key.len()
+                        ))
+                    })?;
+                let public_key = ml_dsa_87::PublicKey::try_from_bytes(key_bytes)
+                    .map_err(|_| invalid_data("Failed to parse ML-DSA-87 public key"))?;
+                let mldsa_signature: [u8; ml_dsa_87::SIG_LEN] = signature
+                    .signature
+                    .as_slice()
+                    .try_into()
+                    .map_err(|_| invalid_data("Malformed ML-DSA-87 signature"))?;
+                let digest: [u8; 64] = Sha512::digest(data).into();
+                Ok(public_key.verify(&digest, &mldsa_signature, &[]))
+            }
+        }
+    }
+
+    /// Verifies a signed package and returns the length of the signed data, i.e. the
+    /// unsigned package. Fails if the package is unsigned, if a signature required by the
+    /// trust anchor is missing, or if any required signature does not verify.
+    pub fn verify_package(&self, package: &[u8]) -> io::Result<usize> {
+        if self.is_empty() {
+            return Err(invalid_data("No trust anchor public key provided"));
+        }
+
+        let (signed_len, signatures) = decode_signature_trailer(package)?;
+        let signed_data = &package[..signed_len];
+
+        let mut required = Vec::new();
+        if self.ecc_public_key.is_some() {
+            required.push(SignatureAlgorithm::EcdsaP384Sha384);
+        }
+        if self.mldsa_public_key.is_some() {
+            required.push(SignatureAlgorithm::MlDsa87Sha512);
+        }
+        for algorithm in required {
+            let signature = signatures
+                .iter()
+                .find(|signature| signature.algorithm == algorithm)
+                .ok_or_else(|| {
+                    invalid_data(format!("Package is missing a {:?} signature", algorithm))
+                })?;
+            if !self.verify_signature(signed_data, signature)? {
+                return Err(invalid_data(format!(
+                    "{:?} package signature verification failed",
+                    algorithm
+                )));
+            }
+        }
+
+        Ok(signed_len)
+    }
+}
+
+/// Encodes the signature trailer appended to a signed package.
+pub fn encode_signature_trailer(signatures: &[PackageSignature]) -> Vec<u8> {
+    let mut trailer = Vec::new();
+    for signature in signatures {
+        trailer.push(signature.algorithm as u8);
+        trailer.extend_from_slice(&(signature.signature.len() as u16).to_le_bytes());
+        trailer.extend_from_slice(&signature.signature);
+    }
+    let trailer_len = (trailer.len() + SIGNATURE_FOOTER_LEN) as u32;
+    trailer.push(signatures.len() as u8);
+    trailer.extend_from_slice(&trailer_len.to_le_bytes());
+    trailer.extend_from_slice(&PACKAGE_SIGNATURE_MAGIC);
+    trailer
+}
+
+/// Splits a signed package into the length of the signed data and its signatures.
+pub fn decode_signature_trailer(package: &[u8]) -> io::Result<(usize, Vec<PackageSignature>)> {
+    if package.len() < SIGNATURE_FOOTER_LEN
+        || package[package.len() - PACKAGE_SIGNATURE_MAGIC.len()..] != PACKAGE_SIGNATURE_MAGIC
+    {
+        return Err(invalid_data("Package is not signed"));
+    }
+
+    let footer = &package[package.len() - SIGNATURE_FOOTER_LEN..];
+    let signature_count = footer[0] as usize;
+    let trailer_len = u32::from_le_bytes(footer[1..5].try_into().unwrap()) as usize;
+    if trailer_len < SIGNATURE_FOOTER_LEN || trailer_len > package.len() {
+        return Err(invalid_data("Invalid package signature trailer length"));
+    }
+
+    let signed_len = package.len() - trailer_len;
+    let mut entries = &package[signed_len..package.len() - SIGNATURE_FOOTER_LEN];
+    let mut signatures = Vec::with_capacity(signature_count);
+    for _ in 0..signature_count {
+        if entries.len() < SIGNATURE_ENTRY_HEADER_LEN {
+            return Err(invalid_data("Truncated package signature entry"));
+        }
+        let algorithm = SignatureAlgorithm::from_u8(entries[0]).ok_or_else(|| {
+            invalid_data(format!(
+                "Unknown package signature algorithm {}",
+                entries[0]
+            ))
+        })?;
+        let len = u16::from_le_bytes([entries[1], entries[2]]) as usize;
+        let signature = entries
+            .get(SIGNATURE_ENTRY_HEADER_LEN..SIGNATURE_ENTRY_HEADER_LEN + len)
+            .ok_or_else(|| invalid_data("Truncated package signature entry"))?;
+        if signatures
+            .iter()
+            .any(|s: &PackageSignature| s.algorithm == algorithm)
+        {
+            return Err(invalid_data(format!(
+                "Duplicate {:?} package signature",
+                algorithm
+            )));
+        }
+        signatures.push(PackageSignature {
+            algorithm,
+            signature: signature.to_vec(),
+        });
+        entries = &entries[SIGNATURE_ENTRY_HEADER_LEN + len..];
+    }
+    if !entries.is_empty() {
+        return Err(invalid_data("Unexpected data in package signature trailer"));
+    }
+
+    Ok((signed_len, signatures))
+}
@@ -0,0 +1,191 @@
+// Licensed under the Apache-2.0 license
+
+use caliptra_mcu_pldm_fw_pkg::{
+    manifest::{
+        ComponentImageInformation, Descriptor, DescriptorType, FirmwareDeviceIdRecord,
+        PackageHeaderInformation, StringType,
+    },
+    FirmwareManifest, PackageSigningKeys, TrustAnchor,
+};
+use chrono::Utc;
+use fips204::ml_dsa_87;
+use fips204::traits::SerDes;
+use p384::elliptic_curve::sec1::ToEncodedPoint;
+use uuid::Uuid;
+
+fn test_manifest() -> FirmwareManifest {
+    FirmwareManifest {
+        package_header_information: PackageHeaderInformation {
+            package_header_identifier: Uuid::parse_str("7B291C996DB64208801B02026E463C78").unwrap(),
+            package_header_format_revision: 1,
+            package_release_date_time: Utc::now(),
+            package_version_string_type: StringType::Utf8,
+            package_version_string: Some("1.0.0".to_string()),
+            package_header_size: 0,
+        },
+        firmware_device_id_records: vec![FirmwareDeviceIdRecord {
+            firmware_device_package_data: None,
+            device_update_option_flags: 0,
+            component_image_set_version_string_type: StringType::Ascii,
+            component_image_set_version_string: Some("ComponentV1".to_string()),
+            applicable_components: Some(vec![0x00]),
+            initial_descriptor: Descriptor {
+                descriptor_type: DescriptorType::Uuid,
+                descriptor_data: vec![0xAA; 16],
+            },
+            additional_descriptors: None,
+            reference_manifest_data: None,
+        }],
+        downstream_device_id_records: None,
+        component_image_information: vec![ComponentImageInformation {
+            image_location: None,
+            classification: 0x000A,
+            identifier: 0x0001,
+            comparison_stamp: Some(1),
+            options: 0,
+            requested_activation_method: 0,
+            version_string_type: StringType::Utf8,
+            version_string: Some("FirmwareV1".to_string()),
+            opaque_data: None,
+            offset: 0,
+            size: 512,
+            image_data: Some((0..512).map(|i| i as u8).collect()),
+        }],
+    }
+}
+
+fn generate_keys() -> (PackageSigningKeys, TrustAnchor) {
+    let ecc_secret = p384::SecretKey::random(&mut rand::thread_rng());
+    let ecc_public_key = ecc_secret
+        .public_key()
+        .to_encoded_point(false)
+        .as_bytes()
+        .to_vec();
+    let (mldsa_public_key, mldsa_private_key) =
+        ml_dsa_87::try_keygen_with_rng(&mut rand::thread_rng()).expect("MLDSA keygen failed");
+
+    (
+        PackageSigningKeys {
+            ecc_private_key: Some(ecc_secret.to_bytes().into()),
+            mldsa_private_key: Some(mldsa_private_key.into_bytes().to_vec()),
+        },
+        TrustAnchor {
+            ecc_public_key: Some(ecc_public_key),
+            mldsa_public_key: Some(mldsa_public_key.into_bytes().to_vec()),
+        },
+    )
+}
+
+fn generate_signed_package(manifest: &FirmwareManifest, keys: &PackageSigningKeys) -> Vec<u8> {
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    let temp_path = temp_file.path().to_str().unwrap().to_string();
+    manifest
+        .generate_signed_firmware_package(&temp_path, keys)
+        .unwrap();
+    std::fs::read(&temp_path).unwrap()
+}
+
+#[test]
+fn test_signed_package_verifies() {
+    let manifest = test_manifest();
+    let (keys, trust_anchor) = generate_keys();
+    let package = generate_signed_package(&manifest, &keys);
+
+    let verified = FirmwareManifest::verify_signed_firmware_package(&package, &trust_anchor)
+        .expect("Signed package should verify");
+    assert_eq!(
+        verified.component_image_information[0].image_data,
+        manifest.component_image_information[0].image_data
+    );
+
+    // Each algorithm can be enforced on its own
+    let ecc_only = TrustAnchor {
+        mldsa_public_key: None,
+        ..trust_anchor.clone()
+    };
+    assert!(FirmwareManifest::verify_signed_firmware_package(&package, &ecc_only).is_ok());
+    let mldsa_only = TrustAnchor {
+        ecc_public_key: None,
+        ..trust_anchor
+    };
+    assert!(FirmwareManifest::verify_signed_firmware_package(&package, &mldsa_only).is_ok());
+}
+
+#[test]
+fn test_signed_package_decodes_as_unsigned() {
+    let manifest = test_manifest();
+    let (keys, _) = generate_keys();
+    let package = generate_signed_package(&manifest, &keys);
+
+    // The signature trailer follows the payload and is ignored by the decoder
+    let decoded = FirmwareManifest::decode_firmware_package_bytes(&package).unwrap();
+    assert_eq!(
+        decoded.component_image_information[0].image_data,
+        manifest.component_image_information[0].image_data
+    );
+    assert_eq!(
+        package[..manifest.encode_firmware_package().unwrap().len()],
+        manifest.encode_firmware_package().unwrap()[..]
+    );
+}
+
+#[test]
+fn test_unsigned_package_rejected() {
+    let manifest = test_manifest();
+    let (_, trust_anchor) = generate_keys();
+    let package = manifest.encode_firmware_package().unwrap();
+
+    assert!(FirmwareManifest::verify_signed_firmware_package(&package, &trust_anchor).is_err());
+}
+
+#[test]
+fn test_tampered_package_rejected() {
+    let manifest = test_manifest();
+    let (keys, trust_anchor) = generate_keys();
+    let package = generate_signed_package(&manifest, &keys);
+
+    // Flip a byte of the payload
+    let mut tampered = package.clone();
+    let last_payload_byte = manifest.encode_firmware_package().unwrap().len() - 1;
+    tampered[last_payload_byte] ^= 0xFF;
+    assert!(FirmwareManifest::verify_signed_firmware_package(&tampered, &trust_anchor).is_err());
+
+    // Flip a byte of the package header
+    let mut tampered = package.clone();
+    tampered[20] ^= 0xFF;
+    assert!(FirmwareManifest::verify_signed_firmware_package(&tampered, &trust_anchor).is_err());
+
+    // Truncate the signature trailer
+    let tampered = &package[..package.len() - 1];
+    assert!(FirmwareManifest::verify_signed_firmware_package(tampered, &trust_anchor).is_err());
+}
+
+#[test]
+fn test_untrusted_key_rejected() {
+    let manifest = test_manifest();
+    let (keys, _) = generate_keys();
+    let (_, other_trust_anchor) = generate_keys();
+    let package = generate_signed_package(&manifest, &keys);
+
+    assert!(
+        FirmwareManifest::verify_signed_firmware_package(&package, &other_trust_anchor).is_err()
+    );
+}
+
+#[test]
+fn test_missing_required_signature_rejected() {
+    let manifest = test_manifest();
+    let (keys, trust_anchor) = generate_keys();
+    let ecc_only_keys = PackageSigningKeys {
+        mldsa_private_key: None,
+        ..keys
+    };
+    let package = generate_signed_package(&manifest, &ecc_only_keys);
+
+    // The trust anchor holds an ML-DSA-87 key, so an ML-DSA-87 signature is required
+    assert!(FirmwareManifest::verify_signed_firmware_package(&package, &trust_anchor).is_err());
+    assert!(
+        FirmwareManifest::verify_signed_firmware_package(&package, &TrustAnchor::default())
+            .is_err()
+    );
+}
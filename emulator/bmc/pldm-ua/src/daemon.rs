@@ -5,7 +5,7 @@ use crate::events::PldmEvents;
 use crate::transport::{PldmSocket, RxPacket};
 use crate::update_sm;
 use caliptra_mcu_pldm_fw_pkg::manifest::FirmwareDeviceIdRecord;
-use caliptra_mcu_pldm_fw_pkg::FirmwareManifest;
+use caliptra_mcu_pldm_fw_pkg::TrustAnchor;
 use log::{debug, error, info, warn};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{self, Receiver, Sender};
@@ -75,6 +75,7 @@ impl<
                 opts.update_sm_actions,
                 socket_clone1.clone(),
                 opts.caliptra_mcu_pldm_fw_pkg.unwrap(),
+                opts.trust_anchor,
                 event_queue_tx_clone4,
             ),
         )));
@@ -225,6 +226,12 @@ impl<
         let update_sm = &*self.update_sm.lock().unwrap();
         update_sm.context().inner_ctx.device_id.clone()
     }
+
+    /// Returns the firmware data transfer statistics of the component being downloaded.
+    pub fn get_transfer_metrics(&self) -> update_sm::TransferMetrics {
+        let update_sm = &*self.update_sm.lock().unwrap();
+        update_sm.context().inner_ctx.transfer_metrics.clone()
+    }
 }
 
 pub struct Options<D: discovery_sm::StateMachineActions, U: update_sm::StateMachineActions> {
@@ -236,7 +243,10 @@ pub struct Options<D: discovery_sm::StateMachineActions, U: update_sm::StateMach
     pub fd_tid: u8,
     // Actions for the update state machine that can be customized as needed
     pub update_sm_actions: U,
-    pub caliptra_mcu_pldm_fw_pkg: Option<FirmwareManifest>,
+    pub caliptra_mcu_pldm_fw_pkg: Option<update_sm::FirmwarePackage>,
+    // When set, `caliptra_mcu_pldm_fw_pkg` must be an encoded package whose signature
+    // verifies against this trust anchor before the update starts
+    pub trust_anchor: Option<TrustAnchor>,
 }
 
 impl Default for Options<discovery_sm::DefaultActions, update_sm::DefaultActions> {
@@ -246,6 +256,7 @@ impl Default for Options<discovery_sm::DefaultActions, update_sm::DefaultActions
             update_sm_actions: update_sm::DefaultActions {},
             caliptra_mcu_pldm_fw_pkg: None,
             fd_tid: 0,
+            trust_anchor: None,
         }
     }
 }
@@ -6,20 +6,27 @@ use crate::transport::{PldmSocket, RxPacket, MAX_PLDM_PAYLOAD_SIZE};
 use caliptra_mcu_pldm_common::codec::PldmCodec;
 use caliptra_mcu_pldm_common::message::firmware_update as pldm_packet;
 use caliptra_mcu_pldm_common::message::firmware_update::activate_fw::SelfContainedActivationRequest;
-use caliptra_mcu_pldm_common::message::firmware_update::request_update::REQUEST_UPDATE_REQUEST_FIXED_HEADER_LEN;
+use caliptra_mcu_pldm_common::message::firmware_update::query_downstream_devices::DownstreamDeviceUpdateSupported;
+use caliptra_mcu_pldm_common::message::firmware_update::query_downstream_identifiers::DownstreamDevice;
+use caliptra_mcu_pldm_common::message::firmware_update::request_update::{
+    FdWillSendPkgDataCmd, REQUEST_UPDATE_REQUEST_FIXED_HEADER_LEN,
+};
 use caliptra_mcu_pldm_common::message::firmware_update::transfer_complete::TransferResult;
 use caliptra_mcu_pldm_common::message::firmware_update::verify_complete::VerifyResult;
 use caliptra_mcu_pldm_common::protocol::base::{
     InstanceId, PldmBaseCompletionCode, PldmMsgHeader, PldmMsgType, PldmSupportedType,
-    TransferRespFlag,
+    TransferOperationFlag, TransferRespFlag,
 };
 use caliptra_mcu_pldm_common::protocol::firmware_update::{
     ComponentClassification, ComponentCompatibilityResponse, ComponentParameterEntry,
     ComponentResponseCode, FirmwareDeviceState, FwUpdateCmd, FwUpdateCompletionCode,
     PldmFirmwareString, UpdateOptionFlags, VersionStringType, PLDM_FWUP_IMAGE_SET_VER_STR_MAX_LEN,
+    PLDM_FWUP_MAX_DATA_PORTION_SIZE,
+};
+use caliptra_mcu_pldm_fw_pkg::manifest::{
+    ComponentImageInformation, DownstreamDeviceIdRecord, FirmwareDeviceIdRecord,
 };
-use caliptra_mcu_pldm_fw_pkg::manifest::{ComponentImageInformation, FirmwareDeviceIdRecord};
-use caliptra_mcu_pldm_fw_pkg::FirmwareManifest;
+use caliptra_mcu_pldm_fw_pkg::{FirmwareManifest, TrustAnchor};
 use log::{debug, error, info};
 use smlang::statemachine;
 use std::cmp::{max, min};
@@ -31,7 +38,7 @@ const MAX_TRANSFER_SIZE: u32 =
     (250 - REQUEST_UPDATE_REQUEST_FIXED_HEADER_LEN - PLDM_FWUP_IMAGE_SET_VER_STR_MAX_LEN - 8)
         as u32; // Maximum bytes to transfer in one request
 const BASELINE_TRANSFER_SIZE: u32 = 32; // Minimum bytes to transfer in one request
-const MAX_OUTSTANDING_TRANSFER_REQ: u8 = 1;
+const MAX_OUTSTANDING_TRANSFER_REQ: u8 = 4; // RequestFirmwareData requests the device may pipeline
 const GET_STATUS_ACTIVATION_POLL_INTERVAL: Duration = Duration::from_secs(1);
 const SELF_ACTIVATION_FIELD_BIT: u16 = 0x0001;
 const SELF_ACTIVATION_FIELD_MASK: u16 = 0x0001;
@@ -48,16 +55,28 @@ statemachine! {
         ReceivedQueryDeviceIdentifiers + SendGetFirmwareParameters / on_send_get_firmware_parameters = GetFirmwareParametersSent,
         GetFirmwareParametersSent + GetFirmwareParametersResponse(pldm_packet::get_fw_params::GetFirmwareParametersResponse)  / on_get_firmware_parameters_response = ReceivedFirmwareParameters,
         ReceivedFirmwareParameters + SendRequestUpdate / on_send_request_update = RequestUpdateSent,
+        ReceivedFirmwareParameters + SendQueryDownstreamDevices / on_send_query_downstream_devices = DownstreamDiscovery,
+        DownstreamDiscovery + QueryDownstreamDevicesResponse(pldm_packet::query_downstream_devices::QueryDownstreamDevicesResponse) / on_query_downstream_devices_response = DownstreamDiscovery,
+        DownstreamDiscovery + SendQueryDownstreamIdentifiers / on_send_query_downstream_identifiers = DownstreamDiscovery,
+        DownstreamDiscovery + QueryDownstreamIdentifiersResponse(pldm_packet::query_downstream_identifiers::QueryDownstreamIdentifiersResponse) / on_query_downstream_identifiers_response = DownstreamDiscovery,
+        DownstreamDiscovery + SendGetDownstreamFirmwareParameters / on_send_get_downstream_firmware_parameters = DownstreamDiscovery,
+        DownstreamDiscovery + GetDownstreamFirmwareParametersResponse(pldm_packet::get_downstream_fw_params::GetDownstreamFirmwareParametersResponse) / on_get_downstream_firmware_parameters_response = DownstreamDiscovery,
+        DownstreamDiscovery + SendRequestDownstreamDeviceUpdate / on_send_request_downstream_update = RequestUpdateSent,
         RequestUpdateSent + RequestUpdateResponse(pldm_packet::request_update::RequestUpdateResponse) / on_request_update_response = LearnComponents,
+        RequestUpdateSent + RequestDownstreamDeviceUpdateResponse(pldm_packet::request_downstream_update::RequestDownstreamDeviceUpdateResponse) / on_request_downstream_update_response = LearnComponents,
         LearnComponents + SendPassComponentRequest [!are_all_components_passed] / on_send_pass_component_request = LearnComponents,
         LearnComponents + SendPassComponentRequest [are_all_components_passed]  / on_all_components_passed = ReadyXfer,
         LearnComponents + PassComponentResponse(pldm_packet::pass_component::PassComponentTableResponse) / on_pass_component_response = LearnComponents,
+        LearnComponents + GetPackageData(pldm_packet::get_package_data::GetPackageDataRequest) / on_get_package_data_request = LearnComponents,
+        LearnComponents + GetMetaData(pldm_packet::get_metadata::GetMetaDataRequest) / on_get_metadata_request = LearnComponents,
         LearnComponents + CancelUpdateOrTimeout  / on_stop_update = Idle,
 
         ReadyXfer + SendUpdateComponent / on_send_update_component = ReadyXfer,
         ReadyXfer + UpdateComponentResponse(pldm_packet::update_component::UpdateComponentResponse) / on_update_component_response = ReadyXfer,
         ReadyXfer + StartDownload / on_start_download = Download,
         ReadyXfer + RequestFirmwareData(pldm_packet::request_fw_data::RequestFirmwareDataRequest) / on_request_firmware_from_ready_xfer = Download,
+        ReadyXfer + SendGetDeviceMetaData / on_send_get_device_metadata = ReadyXfer,
+        ReadyXfer + GetDeviceMetaDataResponse(pldm_packet::get_metadata::GetDeviceMetaDataResponse) / on_get_device_metadata_response = ReadyXfer,
         ReadyXfer + CancelUpdateComponent  / on_stop_update = Idle,
         ReadyXfer + ActivateFirmware / on_activate_firmware = Activate,
 
@@ -125,6 +144,52 @@ fn send_message_helper<P: PldmCodec>(
     Ok(())
 }
 
+// Responses to FD-initiated requests are not retried and leave any outstanding request's retry
+// timer running.
+fn send_response_helper<P: PldmCodec>(
+    ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
+    message: &P,
+) -> Result<(), ()> {
+    let mut buffer = [0u8; MAX_PLDM_PAYLOAD_SIZE];
+    let sz = message.encode(&mut buffer).map_err(|_| ())?;
+    ctx.socket.send(&buffer[..sz]).map_err(|_| ())?;
+    debug!("Sent response: {:?}", std::any::type_name::<P>());
+    Ok(())
+}
+
+// Selects the portion of `data` requested by a multipart transfer. The data transfer handle is
+// the offset of the portion, so the next handle is 0 once the last portion has been sent.
+fn get_data_portion(
+    data: &[u8],
+    data_transfer_handle: u32,
+    transfer_op_flag: u8,
+    max_portion_size: usize,
+) -> Result<(u32, TransferRespFlag, &[u8]), FwUpdateCompletionCode> {
+    let offset = match TransferOperationFlag::try_from(transfer_op_flag) {
+        Ok(TransferOperationFlag::GetFirstPart) => 0,
+        Ok(TransferOperationFlag::GetNextPart) if data_transfer_handle != 0 => {
+            data_transfer_handle as usize
+        }
+        Ok(TransferOperationFlag::GetNextPart) => {
+            return Err(FwUpdateCompletionCode::InvalidTransferHandle)
+        }
+        Err(_) => return Err(FwUpdateCompletionCode::InvalidTransferOperationFlag),
+    };
+    if offset >= data.len() {
+        return Err(FwUpdateCompletionCode::InvalidTransferHandle);
+    }
+
+    let end = min(offset + max_portion_size, data.len());
+    let next_handle = if end == data.len() { 0 } else { end as u32 };
+    let transfer_flag = match (offset == 0, end == data.len()) {
+        (true, true) => TransferRespFlag::StartAndEnd,
+        (true, false) => TransferRespFlag::Start,
+        (false, false) => TransferRespFlag::Middle,
+        (false, true) => TransferRespFlag::End,
+    };
+    Ok((next_handle, transfer_flag, &data[offset..end]))
+}
+
 fn is_pkg_descriptor_in_response_descriptor(
     pkg_descriptor: &caliptra_mcu_pldm_fw_pkg::manifest::Descriptor,
     response_descriptor: &caliptra_mcu_pldm_common::protocol::firmware_update::Descriptor,
@@ -190,6 +255,76 @@ fn is_pkg_device_id_in_response(
     }
     true
 }
+fn is_pkg_downstream_device_in_response(
+    pkg_downstream_dev_id: &DownstreamDeviceIdRecord,
+    downstream_device: &DownstreamDevice,
+) -> bool {
+    !pkg_downstream_dev_id.record_descriptors.is_empty()
+        && pkg_downstream_dev_id
+            .record_descriptors
+            .iter()
+            .all(|pkg_descriptor| {
+                downstream_device.descriptors().iter().any(|descriptor| {
+                    is_pkg_descriptor_in_response_descriptor(pkg_descriptor, descriptor)
+                })
+            })
+}
+
+// The package data sent to the device, taken from the downstream device record when the update
+// targets the downstream devices
+fn package_data(ctx: &InnerContext<impl PldmSocket + Send + 'static>) -> &[u8] {
+    let package_data = if ctx.downstream_update {
+        ctx.downstream_device_id
+            .as_ref()
+            .and_then(|dev_id| dev_id.package_data.as_ref())
+    } else {
+        ctx.device_id
+            .as_ref()
+            .and_then(|dev_id| dev_id.firmware_device_package_data.as_ref())
+    };
+    package_data.map(Vec::as_slice).unwrap_or(&[])
+}
+
+// Appends a portion of a multipart downstream device response. Returns whether the transfer is
+// complete, otherwise the next portion is requested with the returned handle.
+fn collect_downstream_portion(
+    ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
+    next_data_transfer_handle: u32,
+    transfer_flag: u8,
+    portion: &[u8],
+) -> Result<bool, ()> {
+    let first = ctx.downstream_xfer_handle.is_none();
+    let complete = match TransferRespFlag::try_from(transfer_flag) {
+        Ok(TransferRespFlag::StartAndEnd) if first => true,
+        Ok(TransferRespFlag::Start) if first => false,
+        Ok(TransferRespFlag::Middle) if !first => false,
+        Ok(TransferRespFlag::End) if !first => true,
+        _ => {
+            error!("Unexpected transfer flag: {}", transfer_flag);
+            return Err(());
+        }
+    };
+    if first {
+        ctx.downstream_data.clear();
+    }
+    ctx.downstream_data.extend_from_slice(portion);
+    ctx.downstream_xfer_handle = if complete {
+        None
+    } else {
+        Some(next_data_transfer_handle)
+    };
+    Ok(complete)
+}
+
+fn downstream_xfer_op(
+    ctx: &InnerContext<impl PldmSocket + Send + 'static>,
+) -> (u32, TransferOperationFlag) {
+    match ctx.downstream_xfer_handle {
+        Some(handle) => (handle, TransferOperationFlag::GetNextPart),
+        None => (0, TransferOperationFlag::GetFirstPart),
+    }
+}
+
 pub trait StateMachineActions {
     // Guards
     fn are_all_components_passed(
@@ -203,11 +338,46 @@ pub trait StateMachineActions {
         }
     }
 
+    // Decodes the package served to the device before the update starts. With a trust
+    // anchor configured, the encoded package must verify and the manifest is decoded from
+    // the verified bytes.
+    fn load_package(
+        &mut self,
+        ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
+    ) -> Result<(), ()> {
+        let manifest = match (&ctx.encoded_package, &ctx.trust_anchor) {
+            (None, None) => return Ok(()),
+            (None, Some(_)) => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Signature verification requires the encoded package",
+            )),
+            (Some(package), None) => FirmwareManifest::decode_firmware_package_bytes(package),
+            (Some(package), Some(trust_anchor)) => {
+                FirmwareManifest::verify_signed_firmware_package(package, trust_anchor)
+                    .inspect(|_| info!("Firmware package signature verified"))
+            }
+        };
+        match manifest {
+            Ok(manifest) => {
+                ctx.caliptra_mcu_pldm_fw_pkg = manifest;
+                Ok(())
+            }
+            Err(e) => {
+                error!("Firmware package rejected: {}", e);
+                ctx.event_queue
+                    .send(PldmEvents::Update(Events::StopUpdateOnError))
+                    .map_err(|_| ())?;
+                Err(())
+            }
+        }
+    }
+
     // Actions
     fn on_start_update(
         &mut self,
         ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
     ) -> Result<(), ()> {
+        self.load_package(ctx)?;
         send_message_helper(
             ctx,
             &pldm_packet::query_devid::QueryDeviceIdentifiersRequest::new(
@@ -224,12 +394,37 @@ pub trait StateMachineActions {
         ctx.instance_id = ctx.instance_id.wrapping_add(1); // Response received, increment instance id
         if response.fixed.completion_code == PldmBaseCompletionCode::Success as u8 {
             debug!("RequestUpdate response success");
+            self.start_learn_components(
+                ctx,
+                response.fixed.fd_meta_data_len,
+                response.fixed.fd_will_send_pkg_data_cmd,
+                response.get_pkg_data_max_transfer_size,
+            )
+        } else {
+            error!("RequestUpdate response failed");
             ctx.event_queue
-                .send(PldmEvents::Update(Events::SendPassComponentRequest))
+                .send(PldmEvents::Update(Events::StopUpdate))
                 .map_err(|_| ())?;
-            Ok(())
+            Err(())
+        }
+    }
+
+    fn on_request_downstream_update_response(
+        &mut self,
+        ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
+        response: pldm_packet::request_downstream_update::RequestDownstreamDeviceUpdateResponse,
+    ) -> Result<(), ()> {
+        ctx.instance_id = ctx.instance_id.wrapping_add(1); // Response received, increment instance id
+        if response.fixed.completion_code == PldmBaseCompletionCode::Success as u8 {
+            debug!("RequestDownstreamDeviceUpdate response success");
+            self.start_learn_components(
+                ctx,
+                response.fixed.downstream_device_meta_data_len,
+                response.fixed.downstream_device_will_send_pkg_data_cmd,
+                response.get_pkg_data_max_transfer_size,
+            )
         } else {
-            error!("RequestUpdate response failed");
+            error!("RequestDownstreamDeviceUpdate response failed");
             ctx.event_queue
                 .send(PldmEvents::Update(Events::StopUpdate))
                 .map_err(|_| ())?;
@@ -237,6 +432,35 @@ pub trait StateMachineActions {
         }
     }
 
+    fn start_learn_components(
+        &mut self,
+        ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
+        meta_data_len: u16,
+        will_send_pkg_data_cmd: u8,
+        get_pkg_data_max_transfer_size: Option<u32>,
+    ) -> Result<(), ()> {
+        ctx.fd_meta_data_len = meta_data_len;
+        ctx.device_metadata.clear();
+        ctx.device_metadata_complete = false;
+
+        if !package_data(ctx).is_empty()
+            && will_send_pkg_data_cmd != FdWillSendPkgDataCmd::NoPackageData as u8
+        {
+            // Wait for the device to retrieve the package data before passing components
+            debug!("Waiting for GetPackageData");
+            ctx.response_timer.cancel();
+            ctx.pkg_data_max_transfer_size = get_pkg_data_max_transfer_size
+                .map_or(PLDM_FWUP_MAX_DATA_PORTION_SIZE, |size| {
+                    min(size as usize, PLDM_FWUP_MAX_DATA_PORTION_SIZE)
+                });
+            return Ok(());
+        }
+
+        ctx.event_queue
+            .send(PldmEvents::Update(Events::SendPassComponentRequest))
+            .map_err(|_| ())
+    }
+
     fn on_send_pass_component_request(
         &mut self,
         ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
@@ -306,8 +530,14 @@ pub trait StateMachineActions {
         ctx.current_component_index = self.find_next_component_to_update(ctx);
         if ctx.current_component_index.is_none() {
             debug!("No more component to update");
+            let event = if ctx.fd_meta_data_len > 0 && !ctx.device_metadata_complete {
+                // Retrieve the device metadata before the activation reset
+                Events::SendGetDeviceMetaData
+            } else {
+                Events::ActivateFirmware
+            };
             ctx.event_queue
-                .send(PldmEvents::Update(Events::ActivateFirmware))
+                .send(PldmEvents::Update(event))
                 .map_err(|_| ())?;
         } else {
             ctx.event_queue
@@ -469,7 +699,10 @@ pub trait StateMachineActions {
                     MAX_TRANSFER_SIZE,
                     ctx.components.len() as u16,
                     MAX_OUTSTANDING_TRANSFER_REQ,
-                    0, // pkg_data_len is optional, not supported
+                    dev_id_record
+                        .firmware_device_package_data
+                        .as_ref()
+                        .map_or(0, |data| data.len() as u16),
                     &version_string,
                 ),
             )
@@ -583,6 +816,17 @@ pub trait StateMachineActions {
             ctx.event_queue
                 .send(PldmEvents::Update(Events::SendRequestUpdate))
                 .map_err(|_| ())
+        } else if ctx
+            .caliptra_mcu_pldm_fw_pkg
+            .downstream_device_id_records
+            .as_ref()
+            .is_some_and(|records| !records.is_empty())
+        {
+            // The device is up to date, check whether its downstream devices need an update
+            debug!("No device component needs update, querying downstream devices");
+            ctx.event_queue
+                .send(PldmEvents::Update(Events::SendQueryDownstreamDevices))
+                .map_err(|_| ())
         } else {
             debug!("No component needs update");
             ctx.event_queue
@@ -592,6 +836,247 @@ pub trait StateMachineActions {
         }
     }
 
+    fn on_send_query_downstream_devices(
+        &mut self,
+        ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
+    ) -> Result<(), ()> {
+        send_message_helper(
+            ctx,
+            &pldm_packet::query_downstream_devices::QueryDownstreamDevicesRequest::new(
+                ctx.instance_id,
+                PldmMsgType::Request,
+            ),
+        )
+    }
+
+    fn on_query_downstream_devices_response(
+        &mut self,
+        ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
+        response: pldm_packet::query_downstream_devices::QueryDownstreamDevicesResponse,
+    ) -> Result<(), ()> {
+        ctx.instance_id = ctx.instance_id.wrapping_add(1); // Response received, increment instance id
+        if response.completion_code != PldmBaseCompletionCode::Success as u8
+            || response.downstream_device_update_supported
+                != DownstreamDeviceUpdateSupported::Supported as u8
+            || response.number_of_downstream_devices == 0
+        {
+            error!("Device does not support downstream device update");
+            ctx.event_queue
+                .send(PldmEvents::Update(Events::StopUpdateOnError))
+                .map_err(|_| ())?;
+            return Err(());
+        }
+
+        let number_of_downstream_devices = response.number_of_downstream_devices;
+        debug!("Downstream devices: {}", number_of_downstream_devices);
+        ctx.downstream_xfer_handle = None;
+        ctx.event_queue
+            .send(PldmEvents::Update(Events::SendQueryDownstreamIdentifiers))
+            .map_err(|_| ())
+    }
+
+    fn on_send_query_downstream_identifiers(
+        &mut self,
+        ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
+    ) -> Result<(), ()> {
+        let (data_transfer_handle, transfer_op_flag) = downstream_xfer_op(ctx);
+        send_message_helper(
+            ctx,
+            &pldm_packet::query_downstream_identifiers::QueryDownstreamIdentifiersRequest::new(
+                ctx.instance_id,
+                PldmMsgType::Request,
+                data_transfer_handle,
+                transfer_op_flag,
+            ),
+        )
+    }
+
+    fn on_query_downstream_identifiers_response(
+        &mut self,
+        ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
+        response: pldm_packet::query_downstream_identifiers::QueryDownstreamIdentifiersResponse,
+    ) -> Result<(), ()> {
+        ctx.instance_id = ctx.instance_id.wrapping_add(1); // Response received, increment instance id
+        if response.fixed.completion_code != PldmBaseCompletionCode::Success as u8 {
+            error!("QueryDownstreamIdentifiers response failed");
+            ctx.event_queue
+                .send(PldmEvents::Update(Events::StopUpdateOnError))
+                .map_err(|_| ())?;
+            return Err(());
+        }
+
+        if !collect_downstream_portion(
+            ctx,
+            response.fixed.next_data_transfer_handle,
+            response.fixed.transfer_flag,
+            response.portion(),
+        )? {
+            return ctx
+                .event_queue
+                .send(PldmEvents::Update(Events::SendQueryDownstreamIdentifiers))
+                .map_err(|_| ());
+        }
+
+        let identifiers =
+            pldm_packet::query_downstream_identifiers::DownstreamDeviceIdentifiers::decode(
+                &ctx.downstream_data,
+            )
+            .map_err(|_| error!("Invalid downstream device identifiers"))?;
+
+        // Select the first downstream device matching a downstream device record of the package
+        ctx.downstream_device_id = None;
+        for pkg_downstream_dev_id in ctx
+            .caliptra_mcu_pldm_fw_pkg
+            .downstream_device_id_records
+            .iter()
+            .flatten()
+        {
+            if let Some(device) = identifiers
+                .devices()
+                .iter()
+                .find(|device| is_pkg_downstream_device_in_response(pkg_downstream_dev_id, device))
+            {
+                ctx.downstream_device_index = device.downstream_device_index;
+                ctx.downstream_device_id = Some(pkg_downstream_dev_id.clone());
+                break;
+            }
+        }
+
+        if ctx.downstream_device_id.is_some() {
+            debug!(
+                "Downstream device index: {} matches the package",
+                ctx.downstream_device_index
+            );
+            ctx.event_queue
+                .send(PldmEvents::Update(
+                    Events::SendGetDownstreamFirmwareParameters,
+                ))
+                .map_err(|_| ())
+        } else {
+            error!("No matching downstream device id found");
+            ctx.event_queue
+                .send(PldmEvents::Update(Events::StopUpdateOnError))
+                .map_err(|_| ())?;
+            Err(())
+        }
+    }
+
+    fn on_send_get_downstream_firmware_parameters(
+        &mut self,
+        ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
+    ) -> Result<(), ()> {
+        let (data_transfer_handle, transfer_op_flag) = downstream_xfer_op(ctx);
+        send_message_helper(
+            ctx,
+            &pldm_packet::get_downstream_fw_params::GetDownstreamFirmwareParametersRequest::new(
+                ctx.instance_id,
+                PldmMsgType::Request,
+                data_transfer_handle,
+                transfer_op_flag,
+            ),
+        )
+    }
+
+    fn on_get_downstream_firmware_parameters_response(
+        &mut self,
+        ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
+        response: pldm_packet::get_downstream_fw_params::GetDownstreamFirmwareParametersResponse,
+    ) -> Result<(), ()> {
+        ctx.instance_id = ctx.instance_id.wrapping_add(1); // Response received, increment instance id
+        if response.fixed.completion_code != PldmBaseCompletionCode::Success as u8 {
+            error!("GetDownstreamFirmwareParameters response failed");
+            ctx.event_queue
+                .send(PldmEvents::Update(Events::StopUpdateOnError))
+                .map_err(|_| ())?;
+            return Err(());
+        }
+
+        if !collect_downstream_portion(
+            ctx,
+            response.fixed.next_data_transfer_handle,
+            response.fixed.transfer_flag,
+            response.portion(),
+        )? {
+            return ctx
+                .event_queue
+                .send(PldmEvents::Update(
+                    Events::SendGetDownstreamFirmwareParameters,
+                ))
+                .map_err(|_| ());
+        }
+
+        let params = pldm_packet::get_downstream_fw_params::DownstreamFirmwareParameters::decode(
+            &ctx.downstream_data,
+        )
+        .map_err(|_| error!("Invalid downstream firmware parameters"))?;
+        let downstream_device_index = ctx.downstream_device_index;
+        let entry = params
+            .entries()
+            .iter()
+            .find(|entry| entry.entry_fixed.downstream_device_index == downstream_device_index)
+            .ok_or_else(|| error!("No parameters for downstream device"))?;
+        let active_comp_comparison_stamp = entry.entry_fixed.active_comp_comparison_stamp;
+
+        // Select the applicable components newer than the active downstream device firmware
+        if let Some(applicable_components) = ctx
+            .downstream_device_id
+            .as_ref()
+            .and_then(|dev_id| dev_id.applicable_components.as_ref())
+        {
+            for comp_idx in applicable_components {
+                let Some(component) = ctx
+                    .caliptra_mcu_pldm_fw_pkg
+                    .component_image_information
+                    .get(*comp_idx as usize)
+                else {
+                    continue;
+                };
+                if component
+                    .comparison_stamp
+                    .is_none_or(|stamp| stamp > active_comp_comparison_stamp)
+                {
+                    debug!(
+                        "Downstream component id: {} will be updated",
+                        component.identifier
+                    );
+                    ctx.components.push(component.clone());
+                }
+            }
+        }
+
+        if !ctx.components.is_empty() {
+            ctx.event_queue
+                .send(PldmEvents::Update(
+                    Events::SendRequestDownstreamDeviceUpdate,
+                ))
+                .map_err(|_| ())
+        } else {
+            debug!("No downstream component needs update");
+            ctx.event_queue
+                .send(PldmEvents::Update(Events::StopUpdateOnError))
+                .map_err(|_| ())?;
+            Err(())
+        }
+    }
+
+    fn on_send_request_downstream_update(
+        &mut self,
+        ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
+    ) -> Result<(), ()> {
+        ctx.downstream_update = true;
+        let pkg_data_len = package_data(ctx).len() as u16;
+        send_message_helper(
+            ctx,
+            &pldm_packet::request_downstream_update::RequestDownstreamDeviceUpdateRequest::new(
+                ctx.instance_id,
+                PldmMsgType::Request,
+                MAX_TRANSFER_SIZE,
+                MAX_OUTSTANDING_TRANSFER_REQ,
+                pkg_data_len,
+            ),
+        )
+    }
+
     fn on_pass_component_response(
         &mut self,
         ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
@@ -619,13 +1104,177 @@ pub trait StateMachineActions {
         Ok(())
     }
 
+    fn on_get_package_data_request(
+        &mut self,
+        ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
+        request: pldm_packet::get_package_data::GetPackageDataRequest,
+    ) -> Result<(), ()> {
+        let package_data = package_data(ctx).to_vec();
+        let portion = if package_data.is_empty() {
+            Err(FwUpdateCompletionCode::NoPackageData)
+        } else {
+            get_data_portion(
+                &package_data,
+                request.data_transfer_handle,
+                request.transfer_op_flag,
+                ctx.pkg_data_max_transfer_size,
+            )
+        };
+
+        match portion {
+            Ok((next_handle, transfer_flag, data)) => {
+                debug!("Sending package data portion: {:?}", transfer_flag);
+                let response = pldm_packet::get_package_data::GetPackageDataResponse::new(
+                    request.hdr.instance_id(),
+                    PldmBaseCompletionCode::Success as u8,
+                    next_handle,
+                    transfer_flag,
+                    data,
+                )
+                .map_err(|_| ())?;
+                send_response_helper(ctx, &response)?;
+
+                if matches!(
+                    transfer_flag,
+                    TransferRespFlag::End | TransferRespFlag::StartAndEnd
+                ) {
+                    info!("Package data transferred");
+                    ctx.event_queue
+                        .send(PldmEvents::Update(Events::SendPassComponentRequest))
+                        .map_err(|_| ())?;
+                }
+                Ok(())
+            }
+            Err(completion_code) => {
+                let completion_code = completion_code as u8;
+                error!("GetPackageData request failed: {:#x}", completion_code);
+                let response = pldm_packet::get_package_data::GetPackageDataResponse::new(
+                    request.hdr.instance_id(),
+                    completion_code,
+                    0,
+                    TransferRespFlag::StartAndEnd,
+                    &[],
+                )
+                .map_err(|_| ())?;
+                send_response_helper(ctx, &response)
+            }
+        }
+    }
+
+    fn on_get_metadata_request(
+        &mut self,
+        ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
+        request: pldm_packet::get_metadata::GetMetaDataRequest,
+    ) -> Result<(), ()> {
+        let portion = if ctx.device_metadata.is_empty() {
+            Err(FwUpdateCompletionCode::NoDeviceMetadata)
+        } else {
+            get_data_portion(
+                &ctx.device_metadata,
+                request.data_transfer_handle,
+                request.transfer_op_flag,
+                PLDM_FWUP_MAX_DATA_PORTION_SIZE,
+            )
+        };
+
+        let response = match portion {
+            Ok((next_handle, transfer_flag, data)) => {
+                debug!("Sending metadata portion: {:?}", transfer_flag);
+                pldm_packet::get_metadata::GetMetaDataResponse::new(
+                    request.hdr.instance_id(),
+                    PldmBaseCompletionCode::Success as u8,
+                    next_handle,
+                    transfer_flag,
+                    data,
+                )
+            }
+            Err(completion_code) => {
+                let completion_code = completion_code as u8;
+                error!("GetMetaData request failed: {:#x}", completion_code);
+                pldm_packet::get_metadata::GetMetaDataResponse::new(
+                    request.hdr.instance_id(),
+                    completion_code,
+                    0,
+                    TransferRespFlag::StartAndEnd,
+                    &[],
+                )
+            }
+        }
+        .map_err(|_| ())?;
+        send_response_helper(ctx, &response)
+    }
+
+    fn on_send_get_device_metadata(
+        &mut self,
+        ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
+    ) -> Result<(), ()> {
+        let transfer_op_flag = if ctx.device_metadata.is_empty() {
+            TransferOperationFlag::GetFirstPart
+        } else {
+            TransferOperationFlag::GetNextPart
+        };
+        send_message_helper(
+            ctx,
+            &pldm_packet::get_metadata::GetDeviceMetaDataRequest::new(
+                ctx.instance_id,
+                PldmMsgType::Request,
+                ctx.device_metadata_handle,
+                transfer_op_flag,
+            ),
+        )
+    }
+
+    fn on_get_device_metadata_response(
+        &mut self,
+        ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
+        response: pldm_packet::get_metadata::GetDeviceMetaDataResponse,
+    ) -> Result<(), ()> {
+        ctx.instance_id = ctx.instance_id.wrapping_add(1); // Response received, increment instance id
+        ctx.response_timer.cancel();
+        if response.fixed.completion_code != PldmBaseCompletionCode::Success as u8 {
+            error!("GetDeviceMetaData response failed");
+            ctx.event_queue
+                .send(PldmEvents::Update(Events::StopUpdateOnError))
+                .map_err(|_| ())?;
+            return Err(());
+        }
+
+        ctx.device_metadata.extend_from_slice(response.portion());
+        ctx.device_metadata_handle = response.fixed.next_data_transfer_handle;
+        match TransferRespFlag::try_from(response.fixed.transfer_flag) {
+            Ok(TransferRespFlag::End) | Ok(TransferRespFlag::StartAndEnd) => {
+                info!(
+                    "Device metadata received: {} bytes",
+                    ctx.device_metadata.len()
+                );
+                ctx.device_metadata_complete = true;
+                ctx.device_metadata_handle = 0;
+                ctx.event_queue
+                    .send(PldmEvents::Update(Events::ActivateFirmware))
+                    .map_err(|_| ())?;
+            }
+            Ok(TransferRespFlag::Start) | Ok(TransferRespFlag::Middle) => {
+                ctx.event_queue
+                    .send(PldmEvents::Update(Events::SendGetDeviceMetaData))
+                    .map_err(|_| ())?;
+            }
+            Err(_) => {
+                error!("GetDeviceMetaData response has an invalid transfer flag");
+                ctx.event_queue
+                    .send(PldmEvents::Update(Events::StopUpdateOnError))
+                    .map_err(|_| ())?;
+                return Err(());
+            }
+        }
+        Ok(())
+    }
+
     fn on_start_download(
         &mut self,
         ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
     ) -> Result<(), ()> {
         // Reset transfer tracking for new download
-        ctx.transferred_bytes = 0;
-        ctx.transfer_start_time = None;
+        ctx.transfer_metrics = TransferMetrics::default();
         Ok(())
     }
 
@@ -638,8 +1287,7 @@ pub trait StateMachineActions {
         request: pldm_packet::request_fw_data::RequestFirmwareDataRequest,
     ) -> Result<(), ()> {
         // First, perform the download initialization (same as on_start_download)
-        ctx.transferred_bytes = 0;
-        ctx.transfer_start_time = None;
+        ctx.transfer_metrics = TransferMetrics::default();
         // Then handle the firmware request
         self.on_request_firmware(ctx, request)
     }
@@ -656,6 +1304,7 @@ pub trait StateMachineActions {
                 PldmBaseCompletionCode::InvalidLength as u8,
                 &[],
             );
+            ctx.transfer_metrics.failed_requests += 1;
             return send_message_helper(ctx, &response);
         }
 
@@ -670,6 +1319,7 @@ pub trait StateMachineActions {
                     FwUpdateCompletionCode::DataOutOfRange as u8,
                     &[],
                 );
+                ctx.transfer_metrics.failed_requests += 1;
                 return send_message_helper(ctx, &response);
             }
             let mut buffer = [0u8; MAX_TRANSFER_SIZE as usize];
@@ -699,28 +1349,18 @@ pub trait StateMachineActions {
                 &buffer[..request.length as usize],
             );
 
-            ctx.transferred_bytes += request.length;
-            // Initialize transfer start time on first chunk
-            if ctx.transfer_start_time.is_none() {
-                ctx.transfer_start_time = Some(Instant::now());
-            }
-            if ctx.transferred_bytes % 1024 < request.length {
-                let elapsed = ctx
-                    .transfer_start_time
-                    .map(|t| t.elapsed())
-                    .unwrap_or_default();
-                let elapsed_secs = elapsed.as_secs_f64();
-                let speed_bytes_per_sec = if elapsed_secs > 0.0 {
-                    ctx.transferred_bytes as f64 / elapsed_secs
-                } else {
-                    0.0
-                };
+            let metrics = &mut ctx.transfer_metrics;
+            metrics.record_request(request.offset, request.length);
+            if metrics.bytes_transferred % 1024 < request.length as u64 {
+                let speed_bytes_per_sec = metrics.throughput();
                 info!(
-                    "Transferred {} / {} bytes ({:.1} B/s, {:.1} KB/s)",
-                    ctx.transferred_bytes,
+                    "Transferred {} / {} bytes ({:.1} B/s, {:.1} KB/s, {} requests, {} retransmitted)",
+                    metrics.bytes_transferred,
                     data.len(),
                     speed_bytes_per_sec,
-                    speed_bytes_per_sec / 1024.0
+                    speed_bytes_per_sec / 1024.0,
+                    metrics.requests,
+                    metrics.retransmitted_requests
                 );
             }
 
@@ -1164,6 +1804,39 @@ pub fn process_packet(packet: &RxPacket) -> Result<PldmEvents, ()> {
             FwUpdateCmd::CancelUpdateComponent => {
                 packet_to_event(&header, packet, true, Events::CancelUpdateComponentResponse)
             }
+            FwUpdateCmd::GetPackageData => {
+                packet_to_event(&header, packet, false, Events::GetPackageData)
+            }
+            FwUpdateCmd::GetDeviceMetaData => {
+                packet_to_event(&header, packet, true, Events::GetDeviceMetaDataResponse)
+            }
+            FwUpdateCmd::GetMetaData => {
+                packet_to_event(&header, packet, false, Events::GetMetaData)
+            }
+            FwUpdateCmd::QueryDownstreamDevices => packet_to_event(
+                &header,
+                packet,
+                true,
+                Events::QueryDownstreamDevicesResponse,
+            ),
+            FwUpdateCmd::QueryDownstreamIdentifiers => packet_to_event(
+                &header,
+                packet,
+                true,
+                Events::QueryDownstreamIdentifiersResponse,
+            ),
+            FwUpdateCmd::GetDownstreamFirmwareParameters => packet_to_event(
+                &header,
+                packet,
+                true,
+                Events::GetDownstreamFirmwareParametersResponse,
+            ),
+            FwUpdateCmd::RequestDownstreamDeviceUpdate => packet_to_event(
+                &header,
+                packet,
+                true,
+                Events::RequestDownstreamDeviceUpdateResponse,
+            ),
             _ => {
                 debug!("Unknown firmware update command");
                 Err(())
@@ -1189,9 +1862,81 @@ impl StateMachineActions for DefaultActionsExitOnError {
     }
 }
 
+/// Statistics of the RequestFirmwareData requests served for a component.
+#[derive(Debug, Clone, Default)]
+pub struct TransferMetrics {
+    /// Firmware data bytes sent, including padding
+    pub bytes_transferred: u64,
+    /// Number of requests served
+    pub requests: u32,
+    /// Requests for data that had already been sent
+    pub retransmitted_requests: u32,
+    /// Requests rejected with an error completion code
+    pub failed_requests: u32,
+    /// Smallest and largest chunk requested by the device
+    pub min_chunk_size: u32,
+    pub max_chunk_size: u32,
+    /// Time between the first and the latest request
+    pub elapsed: Duration,
+    start_time: Option<Instant>,
+    // End of the highest range sent so far
+    high_water_offset: u32,
+}
+
+impl TransferMetrics {
+    fn record_request(&mut self, offset: u32, length: u32) {
+        let now = Instant::now();
+        let start_time = *self.start_time.get_or_insert(now);
+        self.elapsed = now - start_time;
+        if offset < self.high_water_offset {
+            self.retransmitted_requests += 1;
+        }
+        self.high_water_offset = max(self.high_water_offset, offset + length);
+        self.min_chunk_size = if self.requests == 0 {
+            length
+        } else {
+            min(self.min_chunk_size, length)
+        };
+        self.max_chunk_size = max(self.max_chunk_size, length);
+        self.requests += 1;
+        self.bytes_transferred += length as u64;
+    }
+
+    /// Average throughput in bytes per second.
+    pub fn throughput(&self) -> f64 {
+        let elapsed_secs = self.elapsed.as_secs_f64();
+        if elapsed_secs > 0.0 {
+            self.bytes_transferred as f64 / elapsed_secs
+        } else {
+            0.0
+        }
+    }
+}
+
+/// The firmware package served to the device.
+#[derive(Debug, Clone)]
+pub enum FirmwarePackage {
+    /// A decoded package.
+    Manifest(FirmwareManifest),
+    /// A package image as produced by `pldm-fw-pkg encode`. It is decoded when the update
+    /// starts. Signature verification requires this form, as the signature trailer is not
+    /// part of the decoded manifest.
+    Encoded(Vec<u8>),
+}
+
+impl From<FirmwareManifest> for FirmwarePackage {
+    fn from(manifest: FirmwareManifest) -> Self {
+        FirmwarePackage::Manifest(manifest)
+    }
+}
+
 pub struct InnerContext<S: PldmSocket> {
     socket: S,
     pub caliptra_mcu_pldm_fw_pkg: FirmwareManifest,
+    // The encoded package, decoded into `caliptra_mcu_pldm_fw_pkg` when the update starts
+    encoded_package: Option<Vec<u8>>,
+    // Trust anchor the package signature is verified against before StartUpdate, if configured
+    pub trust_anchor: Option<TrustAnchor>,
     pub event_queue: Sender<PldmEvents>,
     instance_id: InstanceId,
     // The device id of the firmware device
@@ -1206,11 +1951,29 @@ pub struct InnerContext<S: PldmSocket> {
     timer: Timer,
     activation_time: Option<Instant>,
 
-    transferred_bytes: u32,
-    transfer_start_time: Option<Instant>,
+    // Statistics of the firmware data served for the current component
+    pub transfer_metrics: TransferMetrics,
     response_timer: Timer,
     retry_count: Arc<Mutex<u8>>,
     is_initiator: bool,
+
+    // Maximum package data portion the device accepts in a GetPackageData response
+    pkg_data_max_transfer_size: usize,
+    // Length of the device metadata reported in the RequestUpdate response
+    fd_meta_data_len: u16,
+    // The device metadata retrieved before activation, returned to the device by GetMetaData
+    pub device_metadata: Vec<u8>,
+    device_metadata_handle: u32,
+    device_metadata_complete: bool,
+
+    // The downstream device record targeted when the device itself is up to date
+    pub downstream_device_id: Option<DownstreamDeviceIdRecord>,
+    downstream_device_index: u16,
+    // Set once the update targets the downstream devices with RequestDownstreamDeviceUpdate
+    pub downstream_update: bool,
+    // Reassembly of the multipart downstream device responses
+    downstream_data: Vec<u8>,
+    downstream_xfer_handle: Option<u32>,
 }
 
 pub struct Context<T: StateMachineActions, S: PldmSocket> {
@@ -1222,14 +1985,21 @@ impl<T: StateMachineActions, S: PldmSocket> Context<T, S> {
     pub fn new(
         context: T,
         socket: S,
-        caliptra_mcu_pldm_fw_pkg: FirmwareManifest,
+        package: FirmwarePackage,
+        trust_anchor: Option<TrustAnchor>,
         event_queue: Sender<PldmEvents>,
     ) -> Self {
+        let (caliptra_mcu_pldm_fw_pkg, encoded_package) = match package {
+            FirmwarePackage::Manifest(manifest) => (manifest, None),
+            FirmwarePackage::Encoded(package) => (FirmwareManifest::default(), Some(package)),
+        };
         Self {
             inner: context,
             inner_ctx: InnerContext {
                 socket,
                 caliptra_mcu_pldm_fw_pkg,
+                encoded_package,
+                trust_anchor,
                 event_queue,
                 instance_id: 0,
                 device_id: None,
@@ -1238,11 +2008,20 @@ impl<T: StateMachineActions, S: PldmSocket> Context<T, S> {
                 current_component_index: None,
                 timer: Timer::new(),
                 activation_time: None,
-                transferred_bytes: 0,
-                transfer_start_time: None,
+                transfer_metrics: TransferMetrics::default(),
                 response_timer: Timer::new(),
                 retry_count: Arc::new(Mutex::new(0)),
                 is_initiator: true,
+                pkg_data_max_transfer_size: PLDM_FWUP_MAX_DATA_PORTION_SIZE,
+                fd_meta_data_len: 0,
+                device_metadata: Vec::new(),
+                device_metadata_handle: 0,
+                device_metadata_complete: false,
+                downstream_device_id: None,
+                downstream_device_index: 0,
+                downstream_update: false,
+                downstream_data: Vec::new(),
+                downstream_xfer_handle: None,
             },
         }
     }
@@ -1281,10 +2060,22 @@ impl<T: StateMachineActions, S: PldmSocket + Send + 'static> StateMachineContext
         on_send_request_update() -> Result<(),()>,
         on_get_firmware_parameters_response(response : pldm_packet::get_fw_params::GetFirmwareParametersResponse) -> Result<(), ()>,
         on_request_update_response(response: pldm_packet::request_update::RequestUpdateResponse) -> Result<(),()>,
+        on_send_query_downstream_devices() -> Result<(),()>,
+        on_query_downstream_devices_response(response: pldm_packet::query_downstream_devices::QueryDownstreamDevicesResponse) -> Result<(),()>,
+        on_send_query_downstream_identifiers() -> Result<(),()>,
+        on_query_downstream_identifiers_response(response: pldm_packet::query_downstream_identifiers::QueryDownstreamIdentifiersResponse) -> Result<(),()>,
+        on_send_get_downstream_firmware_parameters() -> Result<(),()>,
+        on_get_downstream_firmware_parameters_response(response: pldm_packet::get_downstream_fw_params::GetDownstreamFirmwareParametersResponse) -> Result<(),()>,
+        on_send_request_downstream_update() -> Result<(),()>,
+        on_request_downstream_update_response(response: pldm_packet::request_downstream_update::RequestDownstreamDeviceUpdateResponse) -> Result<(),()>,
         on_send_pass_component_request() -> Result<(),()>,
         on_all_components_passed() -> Result<(),()>,
         on_send_update_component() -> Result<(),()>,
         on_pass_component_response(response : pldm_packet::pass_component::PassComponentTableResponse) -> Result<(),()>,
+        on_get_package_data_request(request: pldm_packet::get_package_data::GetPackageDataRequest) -> Result<(),()>,
+        on_get_metadata_request(request: pldm_packet::get_metadata::GetMetaDataRequest) -> Result<(),()>,
+        on_send_get_device_metadata() -> Result<(),()>,
+        on_get_device_metadata_response(response: pldm_packet::get_metadata::GetDeviceMetaDataResponse) -> Result<(),()>,
         on_start_download() -> Result<(),()>,
         on_update_component_response(response : pldm_packet::update_component::UpdateComponentResponse) -> Result<(),()>,
         on_request_firmware(request: pldm_packet::request_fw_data::RequestFirmwareDataRequest) -> Result<(),()>,
@@ -319,10 +319,11 @@ impl discovery_sm::StateMachineActions for CustomDiscoverySm {
 #[test]
 fn test_pldm_daemon_setup() {
     let setup = setup(Options {
-        caliptra_mcu_pldm_fw_pkg: Some(FirmwareManifest::default()),
+        caliptra_mcu_pldm_fw_pkg: Some(FirmwareManifest::default().into()),
         discovery_sm_actions: CustomDiscoverySm {},
         update_sm_actions: update_sm::DefaultActions {},
         fd_tid: 0x02,
+        trust_anchor: None,
     });
 
     let _: QueryDeviceIdentifiersRequest = setup.receive_request(&setup.fd_sock, 1u8).unwrap();
@@ -61,10 +61,11 @@ fn test_valid_device_identifier_one_descriptor() {
 
     // Setup the test environment
     let mut setup = common::setup(Options {
-        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone()),
+        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone().into()),
         discovery_sm_actions: common::CustomDiscoverySm {},
         update_sm_actions: update_sm::DefaultActions {},
         fd_tid: 0x02,
+        trust_anchor: None,
     });
 
     // Receive QueryDeviceIdentifiers request
@@ -110,10 +111,11 @@ fn test_valid_device_identifier_not_matched() {
 
     // Setup the test environment
     let mut setup = common::setup(Options {
-        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone()),
+        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone().into()),
         discovery_sm_actions: common::CustomDiscoverySm {},
         update_sm_actions: update_sm::DefaultActions {},
         fd_tid: 0x02,
+        trust_anchor: None,
     });
 
     // Receive QueryDeviceIdentifiers request
@@ -173,10 +175,11 @@ fn test_multiple_device_identifiers() {
 
     // Setup the test environment
     let mut setup = common::setup(Options {
-        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone()),
+        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone().into()),
         discovery_sm_actions: common::CustomDiscoverySm {},
         update_sm_actions: update_sm::DefaultActions {},
         fd_tid: 0x02,
+        trust_anchor: None,
     });
 
     // Receive QueryDeviceIdentifiers request
@@ -246,10 +249,11 @@ fn test_send_get_fw_parameter_after_response() {
     }
 
     let mut setup = common::setup(Options {
-        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone()),
+        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone().into()),
         discovery_sm_actions: common::CustomDiscoverySm {},
         update_sm_actions: UpdateSmIgnoreFirmwareParamsResponse {},
         fd_tid: 0x02,
+        trust_anchor: None,
     });
 
     // Receive QueryDeviceIdentifiers request
@@ -56,12 +56,13 @@ impl update_sm::StateMachineActions for UpdateSmStopAfterRequest {
 #[test]
 fn test_discovery() {
     let mut setup = common::setup(Options {
-        caliptra_mcu_pldm_fw_pkg: Some(FirmwareManifest::default()),
+        caliptra_mcu_pldm_fw_pkg: Some(FirmwareManifest::default().into()),
         discovery_sm_actions: discovery_sm::DefaultActions {},
         update_sm_actions: UpdateSmStopAfterRequest {
             is_fw_update_started: false,
         },
         fd_tid: DEVICE_TID,
+        trust_anchor: None,
     });
 
     // TID to be assigned to the device
@@ -207,12 +208,13 @@ fn test_discovery() {
 #[test]
 fn test_discovery_with_retry() {
     let mut setup = common::setup(Options {
-        caliptra_mcu_pldm_fw_pkg: Some(FirmwareManifest::default()),
+        caliptra_mcu_pldm_fw_pkg: Some(FirmwareManifest::default().into()),
         discovery_sm_actions: discovery_sm::DefaultActions {},
         update_sm_actions: UpdateSmStopAfterRequest {
             is_fw_update_started: false,
         },
         fd_tid: DEVICE_TID,
+        trust_anchor: None,
     });
 
     // TID to be assigned to the device
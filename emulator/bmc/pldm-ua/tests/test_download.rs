@@ -191,10 +191,11 @@ fn test_download_size_divisible_by_transfer_size() {
 
     // Setup the test environment
     let mut setup = common::setup(Options {
-        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone()),
+        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone().into()),
         discovery_sm_actions: CustomDiscoverySm {},
         update_sm_actions: UpdateSmBypassed {},
         fd_tid: 0x01,
+        trust_anchor: None,
     });
 
     setup.wait_for_state_transition(update_sm::States::Download);
@@ -309,10 +310,11 @@ fn test_download_size_not_divisible_by_transfer_size() {
 
     // Setup the test environment
     let mut setup = common::setup(Options {
-        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone()),
+        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone().into()),
         discovery_sm_actions: CustomDiscoverySm {},
         update_sm_actions: UpdateSmBypassed {},
         fd_tid: 0x01,
+        trust_anchor: None,
     });
 
     setup.wait_for_state_transition(update_sm::States::Download);
@@ -381,3 +383,107 @@ fn test_download_size_not_divisible_by_transfer_size() {
 
     setup.daemon.stop();
 }
+
+#[test]
+fn test_download_with_outstanding_requests() {
+    let image_data: Vec<u8> = (0..=255u8).collect();
+    let caliptra_mcu_pldm_fw_pkg = FirmwareManifest {
+        package_header_information: PackageHeaderInformation {
+            package_header_identifier: Uuid::parse_str("7B291C996DB64208801B02026E463C78").unwrap(),
+            package_header_format_revision: 1,
+            package_release_date_time: Utc::now(),
+            package_version_string_type: StringType::Utf8,
+            package_version_string: Some("1.0.0".to_string()),
+            package_header_size: 0, // This will be computed during encoding
+        },
+        firmware_device_id_records: vec![FirmwareDeviceIdRecord {
+            firmware_device_package_data: Some(vec![0x01, 0x02, 0x03, 0x04]),
+            device_update_option_flags: 0xFFFF_FFFF,
+            component_image_set_version_string_type: StringType::Ascii,
+            component_image_set_version_string: Some("ComponentV1".to_string()),
+            applicable_components: Some(vec![0x00]),
+            initial_descriptor: Descriptor {
+                descriptor_type: DescriptorType::Uuid,
+                descriptor_data: vec![0xAA, 0xBB, 0xCC],
+            },
+            additional_descriptors: None,
+            reference_manifest_data: None,
+        }],
+        downstream_device_id_records: None,
+        component_image_information: vec![ComponentImageInformation {
+            image_location: None, // Use image_data
+            classification: 0x0001,
+            identifier: 0x0002,
+            comparison_stamp: Some(999),
+            options: 0xAABB,
+            requested_activation_method: 0x1122,
+            version_string_type: StringType::Utf8,
+            version_string: Some("FirmwareV1".to_string()),
+            opaque_data: Some(vec![0x77, 0x88, 0x99]),
+            offset: 0, // Will be calculated in encoding
+            size: image_data.len() as u32,
+            image_data: Some(image_data.clone()),
+        }],
+    };
+
+    // Setup the test environment
+    let mut setup = common::setup(Options {
+        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone().into()),
+        discovery_sm_actions: CustomDiscoverySm {},
+        update_sm_actions: UpdateSmBypassed {},
+        fd_tid: 0x01,
+        trust_anchor: None,
+    });
+
+    setup.wait_for_state_transition(update_sm::States::Download);
+
+    // The device keeps several requests outstanding and requests the second chunk again
+    let requests: [(u32, u32); 5] = [(0, 64), (64, 64), (128, 64), (64, 64), (192, 64)];
+    for (instance_id, (offset, length)) in requests.iter().enumerate() {
+        let request = RequestFirmwareDataRequest::new(
+            instance_id as u8,
+            PldmMsgType::Request,
+            *offset,
+            *length,
+        );
+        setup.send_response(&setup.fd_sock, &request);
+    }
+
+    // Reassemble the image from the responses, matched to their requests by instance ID
+    let mut downloaded_data = vec![0u8; image_data.len()];
+    for _ in 0..requests.len() {
+        let response = setup.fd_sock.receive(None).unwrap();
+        let header = PldmMsgHeader::decode(&response.payload.data[..response.payload.len])
+            .map_err(|_| ())
+            .unwrap();
+        assert!(!header.is_request());
+        assert_eq!(header.cmd_code(), FwUpdateCmd::RequestFirmwareData as u8);
+
+        let (offset, length) = requests[header.instance_id() as usize];
+        let data = &response.payload.data
+            [core::mem::size_of::<RequestFirmwareDataResponseFixed>()..response.payload.len];
+        assert_eq!(data.len(), length as usize);
+        downloaded_data[offset as usize..(offset + length) as usize].copy_from_slice(data);
+    }
+    assert_eq!(downloaded_data, image_data);
+
+    let metrics = setup.daemon.get_transfer_metrics();
+    assert_eq!(metrics.requests, requests.len() as u32);
+    assert_eq!(metrics.bytes_transferred, 64 * requests.len() as u64);
+    assert_eq!(metrics.retransmitted_requests, 1);
+    assert_eq!(metrics.failed_requests, 0);
+    assert_eq!(metrics.min_chunk_size, 64);
+    assert_eq!(metrics.max_chunk_size, 64);
+
+    let request = TransferCompleteRequest::new(
+        requests.len() as u8,
+        PldmMsgType::Request,
+        TransferResult::TransferSuccess,
+    );
+
+    setup.send_response(&setup.fd_sock, &request);
+
+    setup.wait_for_state_transition(update_sm::States::Verify);
+
+    setup.daemon.stop();
+}
@@ -0,0 +1,308 @@
+// Licensed under the Apache-2.0 license
+
+#[cfg(test)]
+mod common;
+
+use caliptra_mcu_pldm_common::codec::PldmCodec;
+use caliptra_mcu_pldm_common::message::firmware_update::get_downstream_fw_params::{
+    DownstreamFirmwareParameters, GetDownstreamFirmwareParametersRequest,
+    GetDownstreamFirmwareParametersResponse,
+};
+use caliptra_mcu_pldm_common::message::firmware_update::get_fw_params::GetFirmwareParametersResponse;
+use caliptra_mcu_pldm_common::message::firmware_update::pass_component::PassComponentTableRequest;
+use caliptra_mcu_pldm_common::message::firmware_update::query_devid::QueryDeviceIdentifiersResponse;
+use caliptra_mcu_pldm_common::message::firmware_update::query_downstream_devices::{
+    DownstreamDeviceUpdateSupported, QueryDownstreamDevicesRequest, QueryDownstreamDevicesResponse,
+};
+use caliptra_mcu_pldm_common::message::firmware_update::query_downstream_identifiers::{
+    DownstreamDevice, DownstreamDeviceIdentifiers, QueryDownstreamIdentifiersRequest,
+    QueryDownstreamIdentifiersResponse,
+};
+use caliptra_mcu_pldm_common::message::firmware_update::request_downstream_update::{
+    RequestDownstreamDeviceUpdateRequest, RequestDownstreamDeviceUpdateResponse,
+};
+use caliptra_mcu_pldm_common::message::firmware_update::request_update::FdWillSendPkgDataCmd;
+use caliptra_mcu_pldm_common::protocol::base::{
+    PldmBaseCompletionCode, TransferOperationFlag, TransferRespFlag,
+};
+use caliptra_mcu_pldm_common::protocol::firmware_update::{
+    ComponentActivationMethods, ComponentClassification, Descriptor as PldmDescriptor,
+    DescriptorType as PldmDescriptorType, DownstreamDeviceParameterEntry, FirmwareDeviceCapability,
+    FwUpdateCmd, PldmFirmwareString, PldmFirmwareVersion, MAX_DOWNSTREAM_DEVICE_COUNT,
+};
+use caliptra_mcu_pldm_fw_pkg::manifest::{
+    ComponentImageInformation, Descriptor, DescriptorType, DownstreamDeviceIdRecord,
+    FirmwareDeviceIdRecord, StringType,
+};
+use caliptra_mcu_pldm_fw_pkg::FirmwareManifest;
+use caliptra_mcu_pldm_ua::events::PldmEvents;
+use common::CustomDiscoverySm;
+
+use caliptra_mcu_pldm_ua::daemon::Options;
+use caliptra_mcu_pldm_ua::transport::PldmSocket;
+use caliptra_mcu_pldm_ua::update_sm;
+
+// Test UUIDs of the firmware device and its downstream device
+pub const TEST_UUID: [u8; 16] = [
+    0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xF0, 0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xF0,
+];
+pub const TEST_DOWNSTREAM_UUID: [u8; 16] = [
+    0x0F, 0xED, 0xCB, 0xA9, 0x87, 0x65, 0x43, 0x21, 0x0F, 0xED, 0xCB, 0xA9, 0x87, 0x65, 0x43, 0x21,
+];
+
+/* Override the Update SM, bypass QueryDeviceIdentifiers and report no device component */
+struct UpdateSmBypassed {}
+impl update_sm::StateMachineActions for UpdateSmBypassed {
+    fn on_start_update(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket>,
+    ) -> Result<(), ()> {
+        ctx.device_id = Some(ctx.caliptra_mcu_pldm_fw_pkg.firmware_device_id_records[0].clone());
+        ctx.event_queue
+            .send(PldmEvents::Update(
+                update_sm::Events::QueryDeviceIdentifiersResponse(QueryDeviceIdentifiersResponse {
+                    ..Default::default()
+                }),
+            ))
+            .map_err(|_| ())?;
+        Ok(())
+    }
+    fn on_query_device_identifiers_response(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket>,
+        _response: QueryDeviceIdentifiersResponse,
+    ) -> Result<(), ()> {
+        ctx.event_queue
+            .send(PldmEvents::Update(
+                update_sm::Events::SendGetFirmwareParameters,
+            ))
+            .map_err(|_| ())?;
+        Ok(())
+    }
+    fn on_send_get_firmware_parameters(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket>,
+    ) -> Result<(), ()> {
+        ctx.event_queue
+            .send(PldmEvents::Update(
+                update_sm::Events::GetFirmwareParametersResponse(GetFirmwareParametersResponse {
+                    ..Default::default()
+                }),
+            ))
+            .map_err(|_| ())
+    }
+}
+
+const SOC_IMAGE_COMP_IDENTIFIER: u16 = 0x1000;
+const SOC_IMAGE_ACTIVE_COMP_STAMP: u32 = 0x00010000;
+
+fn get_pldm_fw_pkg_with_downstream_device(
+    soc_image_comp_stamp: u32,
+    package_data: Vec<u8>,
+) -> FirmwareManifest {
+    FirmwareManifest {
+        firmware_device_id_records: vec![FirmwareDeviceIdRecord {
+            initial_descriptor: Descriptor {
+                descriptor_type: DescriptorType::Uuid,
+                descriptor_data: TEST_UUID.to_vec(),
+            },
+            component_image_set_version_string_type: StringType::Utf8,
+            applicable_components: Some(vec![]),
+            ..Default::default()
+        }],
+        downstream_device_id_records: Some(vec![DownstreamDeviceIdRecord {
+            update_option_flags: 0,
+            self_contained_activation_min_version_string_type: StringType::Utf8,
+            applicable_components: Some(vec![0]),
+            self_contained_activation_min_version_string: None,
+            self_contained_activation_min_version_comparison_stamp: None,
+            record_descriptors: vec![Descriptor {
+                descriptor_type: DescriptorType::Uuid,
+                descriptor_data: TEST_DOWNSTREAM_UUID.to_vec(),
+            }],
+            package_data: Some(package_data),
+            reference_manifest_data: None,
+        }]),
+        component_image_information: vec![ComponentImageInformation {
+            classification: ComponentClassification::Firmware as u16,
+            identifier: SOC_IMAGE_COMP_IDENTIFIER,
+            comparison_stamp: Some(soc_image_comp_stamp),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+}
+
+// Answers the downstream device discovery of the UA, returning the identifiers in two portions
+fn respond_downstream_discovery<U: update_sm::StateMachineActions + Send + 'static>(
+    setup: &common::TestSetup<CustomDiscoverySm, U>,
+) {
+    let request: QueryDownstreamDevicesRequest = setup
+        .receive_request(&setup.fd_sock, FwUpdateCmd::QueryDownstreamDevices as u8)
+        .unwrap();
+    let response = QueryDownstreamDevicesResponse::new(
+        request.hdr.instance_id(),
+        PldmBaseCompletionCode::Success as u8,
+        DownstreamDeviceUpdateSupported::Supported,
+        1,
+        MAX_DOWNSTREAM_DEVICE_COUNT as u16,
+        0,
+    );
+    setup.send_response(&setup.fd_sock, &response);
+
+    let descriptor = PldmDescriptor::new(PldmDescriptorType::Uuid, &TEST_DOWNSTREAM_UUID).unwrap();
+    let identifiers =
+        DownstreamDeviceIdentifiers::new(&[DownstreamDevice::new(0, &[descriptor]).unwrap()])
+            .unwrap();
+    let mut data = [0u8; 512];
+    let data_len = identifiers.encode(&mut data).unwrap();
+    let split = data_len / 2;
+
+    let request: QueryDownstreamIdentifiersRequest = setup
+        .receive_request(
+            &setup.fd_sock,
+            FwUpdateCmd::QueryDownstreamIdentifiers as u8,
+        )
+        .unwrap();
+    assert_eq!(
+        request.transfer_op_flag,
+        TransferOperationFlag::GetFirstPart as u8
+    );
+    let response = QueryDownstreamIdentifiersResponse::new(
+        request.hdr.instance_id(),
+        PldmBaseCompletionCode::Success as u8,
+        split as u32,
+        TransferRespFlag::Start,
+        &data[..split],
+    )
+    .unwrap();
+    setup.send_response(&setup.fd_sock, &response);
+
+    let request: QueryDownstreamIdentifiersRequest = setup
+        .receive_request(
+            &setup.fd_sock,
+            FwUpdateCmd::QueryDownstreamIdentifiers as u8,
+        )
+        .unwrap();
+    assert_eq!(
+        request.transfer_op_flag,
+        TransferOperationFlag::GetNextPart as u8
+    );
+    let data_transfer_handle = request.data_transfer_handle;
+    assert_eq!(data_transfer_handle, split as u32);
+    let response = QueryDownstreamIdentifiersResponse::new(
+        request.hdr.instance_id(),
+        PldmBaseCompletionCode::Success as u8,
+        0,
+        TransferRespFlag::End,
+        &data[split..data_len],
+    )
+    .unwrap();
+    setup.send_response(&setup.fd_sock, &response);
+
+    let request: GetDownstreamFirmwareParametersRequest = setup
+        .receive_request(
+            &setup.fd_sock,
+            FwUpdateCmd::GetDownstreamFirmwareParameters as u8,
+        )
+        .unwrap();
+    let active_firmware_string = PldmFirmwareString::new("ASCII", "soc-fw-1.0").unwrap();
+    let active_firmware_version = PldmFirmwareVersion::new(
+        SOC_IMAGE_ACTIVE_COMP_STAMP,
+        &active_firmware_string,
+        Some("20250210"),
+    );
+    let params = DownstreamFirmwareParameters::new(
+        FirmwareDeviceCapability(0),
+        &[DownstreamDeviceParameterEntry::new(
+            0,
+            &active_firmware_version,
+            &PldmFirmwareVersion::default(),
+            ComponentActivationMethods(0),
+            FirmwareDeviceCapability(0),
+        )],
+    )
+    .unwrap();
+    let data_len = params.encode(&mut data).unwrap();
+    let response = GetDownstreamFirmwareParametersResponse::new(
+        request.hdr.instance_id(),
+        PldmBaseCompletionCode::Success as u8,
+        0,
+        TransferRespFlag::StartAndEnd,
+        &data[..data_len],
+    )
+    .unwrap();
+    setup.send_response(&setup.fd_sock, &response);
+}
+
+#[test]
+fn test_downstream_device_update() {
+    let package_data = vec![0x5A; 24];
+    let caliptra_mcu_pldm_fw_pkg = get_pldm_fw_pkg_with_downstream_device(
+        SOC_IMAGE_ACTIVE_COMP_STAMP + 1,
+        package_data.clone(),
+    );
+
+    // Setup the test environment
+    let mut setup = common::setup(Options {
+        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone().into()),
+        discovery_sm_actions: CustomDiscoverySm {},
+        update_sm_actions: UpdateSmBypassed {},
+        fd_tid: 0x01,
+        trust_anchor: None,
+    });
+
+    respond_downstream_discovery(&setup);
+
+    // The UA targets the downstream device with the package data of its record
+    let request: RequestDownstreamDeviceUpdateRequest = setup
+        .receive_request(
+            &setup.fd_sock,
+            FwUpdateCmd::RequestDownstreamDeviceUpdate as u8,
+        )
+        .unwrap();
+    assert_eq!(
+        request.downstream_device_pkg_data_len as usize,
+        package_data.len()
+    );
+    let response = RequestDownstreamDeviceUpdateResponse::new(
+        request.hdr.instance_id(),
+        PldmBaseCompletionCode::Success as u8,
+        0,
+        FdWillSendPkgDataCmd::NoPackageData as u8,
+        None,
+    );
+    setup.send_response(&setup.fd_sock, &response);
+    setup.wait_for_state_transition(update_sm::States::LearnComponents);
+
+    // The downstream device component is passed
+    let request: PassComponentTableRequest = setup
+        .receive_request(&setup.fd_sock, FwUpdateCmd::PassComponentTable as u8)
+        .unwrap();
+    let comp_identifier = request.fixed.comp_identifier;
+    assert_eq!(comp_identifier, SOC_IMAGE_COMP_IDENTIFIER);
+
+    setup.daemon.stop();
+}
+
+#[test]
+fn test_downstream_device_up_to_date() {
+    let caliptra_mcu_pldm_fw_pkg =
+        get_pldm_fw_pkg_with_downstream_device(SOC_IMAGE_ACTIVE_COMP_STAMP, vec![]);
+
+    // Setup the test environment
+    let mut setup = common::setup(Options {
+        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone().into()),
+        discovery_sm_actions: CustomDiscoverySm {},
+        update_sm_actions: UpdateSmBypassed {},
+        fd_tid: 0x01,
+        trust_anchor: None,
+    });
+
+    respond_downstream_discovery(&setup);
+
+    // No component is newer than the active downstream device firmware
+    setup.wait_for_state_transition(update_sm::States::Done);
+
+    setup.daemon.stop();
+}
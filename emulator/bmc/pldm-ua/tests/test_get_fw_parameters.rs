@@ -208,12 +208,13 @@ fn test_caliptra_fw_update() {
 
     // Setup the test environment
     let mut setup = common::setup(Options {
-        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone()),
+        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone().into()),
         discovery_sm_actions: CustomDiscoverySm {},
         update_sm_actions: UpdateSmBypassQueryDevId {
             expected_num_components_to_update: 1,
         },
         fd_tid: 0x01,
+        trust_anchor: None,
     });
 
     // Receive QueryDeviceIdentifiers request
@@ -251,12 +252,13 @@ fn test_caliptra_fw_update_with_timeout() {
 
     // Setup the test environment
     let mut setup = common::setup(Options {
-        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone()),
+        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone().into()),
         discovery_sm_actions: CustomDiscoverySm {},
         update_sm_actions: UpdateSmBypassQueryDevId {
             expected_num_components_to_update: 1,
         },
         fd_tid: 0x01,
+        trust_anchor: None,
     });
 
     // Receive QueryDeviceIdentifiers request
@@ -300,12 +302,13 @@ fn test_caliptra_fw_incorrect_id() {
 
     // Setup the test environment
     let mut setup = common::setup(Options {
-        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone()),
+        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone().into()),
         discovery_sm_actions: CustomDiscoverySm {},
         update_sm_actions: UpdateSmBypassQueryDevId {
             expected_num_components_to_update: 0,
         },
         fd_tid: 0x01,
+        trust_anchor: None,
     });
 
     // Receive QueryDeviceIdentifiers request
@@ -345,12 +348,13 @@ fn test_caliptra_fw_update_same_version() {
 
     // Setup the test environment
     let mut setup = common::setup(Options {
-        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone()),
+        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone().into()),
         discovery_sm_actions: CustomDiscoverySm {},
         update_sm_actions: UpdateSmBypassQueryDevId {
             expected_num_components_to_update: 0,
         },
         fd_tid: 0x01,
+        trust_anchor: None,
     });
 
     // Receive QueryDeviceIdentifiers request
@@ -389,12 +393,13 @@ fn test_caliptra_fw_caliptra_and_manifest() {
 
     // Setup the test environment
     let mut setup = common::setup(Options {
-        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone()),
+        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone().into()),
         discovery_sm_actions: CustomDiscoverySm {},
         update_sm_actions: UpdateSmBypassQueryDevId {
             expected_num_components_to_update: 2,
         },
         fd_tid: 0x01,
+        trust_anchor: None,
     });
 
     // Receive QueryDeviceIdentifiers request
@@ -434,12 +439,13 @@ fn test_caliptra_fw_caliptra_same_version_and_manifest_diff_version() {
 
     // Setup the test environment
     let mut setup = common::setup(Options {
-        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone()),
+        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone().into()),
         discovery_sm_actions: CustomDiscoverySm {},
         update_sm_actions: UpdateSmBypassQueryDevId {
             expected_num_components_to_update: 1,
         },
         fd_tid: 0x01,
+        trust_anchor: None,
     });
 
     // Receive QueryDeviceIdentifiers request
@@ -0,0 +1,270 @@
+// Licensed under the Apache-2.0 license
+
+#[cfg(test)]
+mod common;
+
+use caliptra_mcu_pldm_common::message::firmware_update::get_fw_params::GetFirmwareParametersResponse;
+use caliptra_mcu_pldm_common::message::firmware_update::get_metadata::{
+    GetMetaDataRequest, GetMetaDataResponse,
+};
+use caliptra_mcu_pldm_common::message::firmware_update::get_package_data::{
+    GetPackageDataRequest, GetPackageDataResponse,
+};
+use caliptra_mcu_pldm_common::message::firmware_update::pass_component::PassComponentTableRequest;
+use caliptra_mcu_pldm_common::message::firmware_update::query_devid::QueryDeviceIdentifiersResponse;
+use caliptra_mcu_pldm_common::message::firmware_update::request_update::{
+    FdWillSendPkgDataCmd, RequestUpdateRequest, RequestUpdateResponse,
+};
+use caliptra_mcu_pldm_common::protocol::base::{
+    PldmBaseCompletionCode, PldmMsgType, TransferOperationFlag, TransferRespFlag,
+};
+use caliptra_mcu_pldm_common::protocol::firmware_update::{
+    ComponentClassification, FwUpdateCmd, FwUpdateCompletionCode,
+};
+use caliptra_mcu_pldm_fw_pkg::manifest::{
+    ComponentImageInformation, Descriptor, DescriptorType, FirmwareDeviceIdRecord,
+};
+use caliptra_mcu_pldm_fw_pkg::FirmwareManifest;
+use caliptra_mcu_pldm_ua::events::PldmEvents;
+use common::CustomDiscoverySm;
+
+use caliptra_mcu_pldm_ua::daemon::Options;
+use caliptra_mcu_pldm_ua::transport::PldmSocket;
+use caliptra_mcu_pldm_ua::update_sm;
+
+// Test UUID
+pub const TEST_UUID: [u8; 16] = [
+    0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xF0, 0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xF0,
+];
+
+/* Override the Update SM, bypass QueryDeviceIdentifiers and GetFirmwareParameters */
+struct UpdateSmBypassed {}
+impl update_sm::StateMachineActions for UpdateSmBypassed {
+    fn on_start_update(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket>,
+    ) -> Result<(), ()> {
+        ctx.device_id = Some(ctx.caliptra_mcu_pldm_fw_pkg.firmware_device_id_records[0].clone());
+        ctx.components = ctx
+            .caliptra_mcu_pldm_fw_pkg
+            .component_image_information
+            .clone();
+        ctx.event_queue
+            .send(PldmEvents::Update(
+                update_sm::Events::QueryDeviceIdentifiersResponse(QueryDeviceIdentifiersResponse {
+                    ..Default::default()
+                }),
+            ))
+            .map_err(|_| ())?;
+        Ok(())
+    }
+    fn on_query_device_identifiers_response(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket>,
+        _response: QueryDeviceIdentifiersResponse,
+    ) -> Result<(), ()> {
+        ctx.event_queue
+            .send(PldmEvents::Update(
+                update_sm::Events::SendGetFirmwareParameters,
+            ))
+            .map_err(|_| ())?;
+        Ok(())
+    }
+    fn on_send_get_firmware_parameters(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket>,
+    ) -> Result<(), ()> {
+        ctx.event_queue
+            .send(PldmEvents::Update(
+                update_sm::Events::GetFirmwareParametersResponse(GetFirmwareParametersResponse {
+                    ..Default::default()
+                }),
+            ))
+            .map_err(|_| ())
+    }
+    fn on_get_firmware_parameters_response(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket>,
+        _response: caliptra_mcu_pldm_common::message::firmware_update::get_fw_params::GetFirmwareParametersResponse,
+    ) -> Result<(), ()> {
+        ctx.event_queue
+            .send(PldmEvents::Update(update_sm::Events::SendRequestUpdate))
+            .map_err(|_| ())
+    }
+}
+
+const COMPONENT_ACTIVE_VER_STR: &str = "1.1.0";
+const CALIPTRA_FW_COMP_IDENTIFIER: u16 = 0x0001;
+const CALIPTRA_FW_ACTIVE_COMP_STAMP: u32 = 0x00010105;
+const SOC_MANIFEST_COMP_IDENTIFIER: u16 = 0x0003;
+const SOC_MANIFEST_ACTIVE_COMP_STAMP: u32 = 0x00010101;
+
+fn get_pldm_fw_pkg_with_package_data(
+    caliptra_comp_stamp: Option<u32>,
+    manifest_comp_stamp: Option<u32>,
+    package_data: Vec<u8>,
+) -> FirmwareManifest {
+    FirmwareManifest {
+        firmware_device_id_records: vec![FirmwareDeviceIdRecord {
+            initial_descriptor: Descriptor {
+                descriptor_type: DescriptorType::Uuid,
+                descriptor_data: TEST_UUID.to_vec(),
+            },
+            component_image_set_version_string_type:
+                caliptra_mcu_pldm_fw_pkg::manifest::StringType::Utf8,
+            component_image_set_version_string: Some(COMPONENT_ACTIVE_VER_STR.to_string()),
+            applicable_components: Some(vec![0, 1]),
+            firmware_device_package_data: Some(package_data),
+            ..Default::default()
+        }],
+        component_image_information: vec![
+            ComponentImageInformation {
+                classification: ComponentClassification::Firmware as u16,
+                identifier: CALIPTRA_FW_COMP_IDENTIFIER,
+                comparison_stamp: caliptra_comp_stamp,
+                ..Default::default()
+            },
+            ComponentImageInformation {
+                classification: ComponentClassification::Other as u16,
+                identifier: SOC_MANIFEST_COMP_IDENTIFIER,
+                comparison_stamp: manifest_comp_stamp,
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    }
+}
+
+const PKG_DATA_MAX_TRANSFER_SIZE: u32 = 64;
+
+#[test]
+fn test_get_package_data_round_trip() {
+    let package_data: Vec<u8> = (0..150u8).collect();
+    let caliptra_mcu_pldm_fw_pkg = get_pldm_fw_pkg_with_package_data(
+        Some(CALIPTRA_FW_ACTIVE_COMP_STAMP + 1),
+        Some(SOC_MANIFEST_ACTIVE_COMP_STAMP + 1),
+        package_data.clone(),
+    );
+
+    // Setup the test environment
+    let mut setup = common::setup(Options {
+        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone().into()),
+        discovery_sm_actions: CustomDiscoverySm {},
+        update_sm_actions: UpdateSmBypassed {},
+        fd_tid: 0x01,
+        trust_anchor: None,
+    });
+
+    // Receive RequestUpdate request advertising the package data
+    let request: RequestUpdateRequest = setup
+        .receive_request(&setup.fd_sock, FwUpdateCmd::RequestUpdate as u8)
+        .unwrap();
+    assert_eq!(request.fixed.pkg_data_len as usize, package_data.len());
+
+    // The device will retrieve the package data
+    let response = RequestUpdateResponse::new(
+        request.fixed.hdr.instance_id(),
+        PldmBaseCompletionCode::Success as u8,
+        0,
+        FdWillSendPkgDataCmd::WillSendWithMaxTransferSize as u8,
+        Some(PKG_DATA_MAX_TRANSFER_SIZE),
+    );
+    setup.send_response(&setup.fd_sock, &response);
+    setup.wait_for_state_transition(update_sm::States::LearnComponents);
+
+    // Retrieve the package data with GetPackageData
+    let mut received = Vec::new();
+    let mut data_transfer_handle = 0;
+    let mut transfer_op_flag = TransferOperationFlag::GetFirstPart;
+    let mut instance_id = 0x10;
+    loop {
+        let request = GetPackageDataRequest::new(
+            instance_id,
+            PldmMsgType::Request,
+            data_transfer_handle,
+            transfer_op_flag,
+        );
+        setup.send_response(&setup.fd_sock, &request);
+        let response: GetPackageDataResponse = setup
+            .receive_request(&setup.fd_sock, FwUpdateCmd::GetPackageData as u8)
+            .unwrap();
+        assert_eq!(
+            response.fixed.completion_code,
+            PldmBaseCompletionCode::Success as u8
+        );
+        assert!(response.portion_len <= PKG_DATA_MAX_TRANSFER_SIZE as usize);
+        received.extend_from_slice(response.portion());
+
+        let transfer_flag = TransferRespFlag::try_from(response.fixed.transfer_flag).unwrap();
+        if transfer_flag == TransferRespFlag::End {
+            assert_eq!(response.fixed.next_data_transfer_handle, 0);
+            break;
+        }
+        data_transfer_handle = response.fixed.next_data_transfer_handle;
+        transfer_op_flag = TransferOperationFlag::GetNextPart;
+        instance_id += 1;
+    }
+    assert_eq!(received, package_data);
+
+    // Components are passed once the package data has been retrieved
+    let _: PassComponentTableRequest = setup
+        .receive_request(&setup.fd_sock, FwUpdateCmd::PassComponentTable as u8)
+        .unwrap();
+
+    setup.daemon.stop();
+}
+
+#[test]
+fn test_get_metadata_no_device_metadata() {
+    let caliptra_mcu_pldm_fw_pkg = get_pldm_fw_pkg_with_package_data(
+        Some(CALIPTRA_FW_ACTIVE_COMP_STAMP + 1),
+        Some(SOC_MANIFEST_ACTIVE_COMP_STAMP + 1),
+        vec![0xA5; 16],
+    );
+
+    // Setup the test environment
+    let mut setup = common::setup(Options {
+        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.clone().into()),
+        discovery_sm_actions: CustomDiscoverySm {},
+        update_sm_actions: UpdateSmBypassed {},
+        fd_tid: 0x01,
+        trust_anchor: None,
+    });
+
+    // The device does not retrieve the package data
+    let request: RequestUpdateRequest = setup
+        .receive_request(&setup.fd_sock, FwUpdateCmd::RequestUpdate as u8)
+        .unwrap();
+    let response = RequestUpdateResponse::new(
+        request.fixed.hdr.instance_id(),
+        PldmBaseCompletionCode::Success as u8,
+        0,
+        FdWillSendPkgDataCmd::NoPackageData as u8,
+        None,
+    );
+    setup.send_response(&setup.fd_sock, &response);
+    setup.wait_for_state_transition(update_sm::States::LearnComponents);
+
+    let _: PassComponentTableRequest = setup
+        .receive_request(&setup.fd_sock, FwUpdateCmd::PassComponentTable as u8)
+        .unwrap();
+
+    // No metadata was retrieved from the device during a previous update
+    let request = GetMetaDataRequest::new(
+        0x10,
+        PldmMsgType::Request,
+        0,
+        TransferOperationFlag::GetFirstPart,
+    );
+    setup.send_response(&setup.fd_sock, &request);
+    let response: GetMetaDataResponse = setup
+        .receive_request(&setup.fd_sock, FwUpdateCmd::GetMetaData as u8)
+        .unwrap();
+    assert_eq!(
+        response.fixed.completion_code,
+        FwUpdateCompletionCode::NoDeviceMetadata as u8
+    );
+    assert_eq!(response.portion_len, 0);
+
+    setup.daemon.stop();
+}
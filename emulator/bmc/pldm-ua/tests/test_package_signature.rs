@@ -0,0 +1,202 @@
+// Licensed under the Apache-2.0 license
+
+#[cfg(test)]
+mod common;
+
+use caliptra_mcu_pldm_common::message::firmware_update::query_devid::{
+    QueryDeviceIdentifiersRequest, QueryDeviceIdentifiersResponse,
+};
+use caliptra_mcu_pldm_common::protocol::base::PldmBaseCompletionCode;
+use caliptra_mcu_pldm_common::protocol::firmware_update::{Descriptor, FwUpdateCmd};
+use caliptra_mcu_pldm_fw_pkg::manifest::{
+    ComponentImageInformation, Descriptor as PkgDescriptor, DescriptorType, FirmwareDeviceIdRecord,
+    PackageHeaderInformation, StringType,
+};
+use caliptra_mcu_pldm_fw_pkg::signature::decode_signature_trailer;
+use caliptra_mcu_pldm_fw_pkg::{FirmwareManifest, PackageSigningKeys, TrustAnchor};
+use caliptra_mcu_pldm_ua::daemon::Options;
+use caliptra_mcu_pldm_ua::update_sm::{self, FirmwarePackage};
+use p384::elliptic_curve::sec1::ToEncodedPoint;
+use uuid::Uuid;
+
+const TEST_UUID: [u8; 16] = [
+    0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xF0, 0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xF0,
+];
+
+fn signed_package() -> (Vec<u8>, TrustAnchor) {
+    let manifest = FirmwareManifest {
+        package_header_information: PackageHeaderInformation {
+            package_header_identifier: Uuid::parse_str("7B291C996DB64208801B02026E463C78").unwrap(),
+            package_header_format_revision: 1,
+            package_release_date_time: chrono::Utc::now(),
+            package_version_string_type: StringType::Utf8,
+            package_version_string: Some("1.0.0".to_string()),
+            package_header_size: 0,
+        },
+        firmware_device_id_records: vec![FirmwareDeviceIdRecord {
+            component_image_set_version_string_type: StringType::Ascii,
+            component_image_set_version_string: Some("ImageSetV1".to_string()),
+            applicable_components: Some(vec![0x00]),
+            initial_descriptor: PkgDescriptor {
+                descriptor_type: DescriptorType::Uuid,
+                descriptor_data: TEST_UUID.to_vec(),
+            },
+            ..Default::default()
+        }],
+        component_image_information: vec![ComponentImageInformation {
+            classification: 0x000A,
+            identifier: 0x0001,
+            comparison_stamp: Some(1),
+            version_string_type: StringType::Utf8,
+            version_string: Some("FirmwareV1".to_string()),
+            size: 256,
+            image_data: Some(vec![0x55; 256]),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+
+    let ecc_secret = p384::SecretKey::random(&mut rand::thread_rng());
+    let keys = PackageSigningKeys {
+        ecc_private_key: Some(ecc_secret.to_bytes().into()),
+        mldsa_private_key: None,
+    };
+    let trust_anchor = TrustAnchor {
+        ecc_public_key: Some(
+            ecc_secret
+                .public_key()
+                .to_encoded_point(false)
+                .as_bytes()
+                .to_vec(),
+        ),
+        mldsa_public_key: None,
+    };
+
+    let mut package = manifest.encode_firmware_package().unwrap();
+    let trailer = keys.sign(&package).unwrap();
+    package.extend_from_slice(&trailer);
+    (package, trust_anchor)
+}
+
+#[test]
+fn test_signed_package_accepted() {
+    let (package, trust_anchor) = signed_package();
+
+    let setup = common::setup(Options {
+        caliptra_mcu_pldm_fw_pkg: Some(FirmwarePackage::Encoded(package)),
+        discovery_sm_actions: common::CustomDiscoverySm {},
+        update_sm_actions: update_sm::DefaultActions {},
+        fd_tid: 0x02,
+        trust_anchor: Some(trust_anchor),
+    });
+
+    expect_device_identified(setup);
+}
+
+#[test]
+fn test_encoded_package_without_trust_anchor() {
+    let (package, _) = signed_package();
+
+    // Without a trust anchor the package is decoded and the trailer ignored
+    let setup = common::setup(Options {
+        caliptra_mcu_pldm_fw_pkg: Some(FirmwarePackage::Encoded(package)),
+        discovery_sm_actions: common::CustomDiscoverySm {},
+        update_sm_actions: update_sm::DefaultActions {},
+        fd_tid: 0x02,
+        trust_anchor: None,
+    });
+
+    expect_device_identified(setup);
+}
+
+// Answers QueryDeviceIdentifiers with the device of the signed package, which only
+// matches if the manifest was decoded from it.
+fn expect_device_identified(
+    mut setup: common::TestSetup<common::CustomDiscoverySm, update_sm::DefaultActions>,
+) {
+    let request: QueryDeviceIdentifiersRequest = setup
+        .receive_request(&setup.fd_sock, FwUpdateCmd::QueryDeviceIdentifiers as u8)
+        .unwrap();
+
+    let mut descriptor_data = [0u8; 64];
+    descriptor_data[..TEST_UUID.len()].copy_from_slice(&TEST_UUID);
+    let descriptor = Descriptor {
+        descriptor_type: DescriptorType::Uuid as u16,
+        descriptor_length: TEST_UUID.len() as u16,
+        descriptor_data,
+    };
+    let response = QueryDeviceIdentifiersResponse::new(
+        request.hdr.instance_id(),
+        PldmBaseCompletionCode::Success as u8,
+        &descriptor,
+        None,
+    )
+    .unwrap();
+    setup.send_response(&setup.fd_sock, &response);
+
+    setup.wait_for_state_transition(update_sm::States::GetFirmwareParametersSent);
+    assert!(setup.daemon.get_device_id().is_some());
+
+    setup.daemon.stop();
+}
+
+#[test]
+fn test_tampered_package_rejected() {
+    let (mut package, trust_anchor) = signed_package();
+
+    // Flip the last byte of the component image, just before the signature trailer
+    let (signed_len, _) = decode_signature_trailer(&package).unwrap();
+    package[signed_len - 1] ^= 0xFF;
+
+    let mut setup = common::setup(Options {
+        caliptra_mcu_pldm_fw_pkg: Some(FirmwarePackage::Encoded(package)),
+        discovery_sm_actions: common::CustomDiscoverySm {},
+        update_sm_actions: update_sm::DefaultActions {},
+        fd_tid: 0x02,
+        trust_anchor: Some(trust_anchor),
+    });
+
+    // The update is stopped before QueryDeviceIdentifiers is sent
+    setup.wait_for_state_transition(update_sm::States::Done);
+
+    setup.daemon.stop();
+}
+
+#[test]
+fn test_unsigned_package_rejected() {
+    let (package, trust_anchor) = signed_package();
+
+    // Strip the signature trailer
+    let (signed_len, _) = decode_signature_trailer(&package).unwrap();
+
+    let mut setup = common::setup(Options {
+        caliptra_mcu_pldm_fw_pkg: Some(FirmwarePackage::Encoded(package[..signed_len].to_vec())),
+        discovery_sm_actions: common::CustomDiscoverySm {},
+        update_sm_actions: update_sm::DefaultActions {},
+        fd_tid: 0x02,
+        trust_anchor: Some(trust_anchor),
+    });
+
+    setup.wait_for_state_transition(update_sm::States::Done);
+
+    setup.daemon.stop();
+}
+
+#[test]
+fn test_decoded_package_rejected_with_trust_anchor() {
+    let (package, trust_anchor) = signed_package();
+
+    // A decoded manifest carries no signature to verify
+    let manifest = FirmwareManifest::decode_firmware_package_bytes(&package).unwrap();
+    let mut setup = common::setup(Options {
+        caliptra_mcu_pldm_fw_pkg: Some(manifest.into()),
+        discovery_sm_actions: common::CustomDiscoverySm {},
+        update_sm_actions: update_sm::DefaultActions {},
+        fd_tid: 0x02,
+        trust_anchor: Some(trust_anchor),
+    });
+
+    setup.wait_for_state_transition(update_sm::States::Done);
+
+    setup.daemon.stop();
+}
@@ -0,0 +1,429 @@
+// Licensed under the Apache-2.0 license
+
+//! Host cryptography used by the requester: SHA-384 transcripts, certificate chain and
+//! signature verification, ECDH P-384 key exchange and the HMAC/HKDF primitives of the
+//! SPDM key schedule.
+
+use crate::error::{SpdmRequesterError, SpdmRequesterResult};
+use hmac::{Hmac, Mac};
+use openssl::bn::BigNum;
+use openssl::ecdsa::EcdsaSig;
+use openssl::pkey::{Id, PKey, Public};
+use openssl::sign::Verifier;
+use openssl::x509::X509;
+use p384::ecdh::EphemeralSecret;
+use p384::elliptic_curve::sec1::ToEncodedPoint;
+use p384::PublicKey;
+use sha2::{Digest, Sha384};
+
+pub const SHA384_HASH_SIZE: usize = 48;
+pub const ECC_P384_SIGNATURE_SIZE: usize = 96;
+pub const MLDSA87_SIGNATURE_SIZE: usize = 4627;
+pub const ECDH_P384_EXCHANGE_DATA_SIZE: usize = 96;
+
+/// Length of the SPDM certificate chain header: Length, Reserved and RootHash.
+pub const SPDM_CERT_CHAIN_HEADER_SIZE: usize = 4 + SHA384_HASH_SIZE;
+
+/// Asymmetric signature algorithm selected by the Responder.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AsymAlgo {
+    EccP384,
+    MlDsa87,
+}
+
+impl AsymAlgo {
+    pub fn signature_size(&self) -> usize {
+        match self {
+            AsymAlgo::EccP384 => ECC_P384_SIGNATURE_SIZE,
+            AsymAlgo::MlDsa87 => MLDSA87_SIGNATURE_SIZE,
+        }
+    }
+}
+
+pub fn sha384(data: &[u8]) -> [u8; SHA384_HASH_SIZE] {
+    Sha384::digest(data).into()
+}
+
+pub fn hmac_sha384(key: &[u8], data: &[u8]) -> SpdmRequesterResult<[u8; SHA384_HASH_SIZE]> {
+    let mut mac = Hmac::<Sha384>::new_from_slice(key).map_err(|_| SpdmRequesterError::Crypto)?;
+    mac.update(data);
+    Ok(mac.finalize().into_bytes().into())
+}
+
+pub fn hkdf_extract(salt: &[u8], ikm: &[u8]) -> SpdmRequesterResult<[u8; SHA384_HASH_SIZE]> {
+    hmac_sha384(salt, ikm)
+}
+
+pub fn hkdf_expand(prk: &[u8], info: &[u8], okm: &mut [u8]) -> SpdmRequesterResult<()> {
+    hkdf::Hkdf::<Sha384>::from_prk(prk)
+        .map_err(|_| SpdmRequesterError::Crypto)?
+        .expand(info, okm)
+        .map_err(|_| SpdmRequesterError::Crypto)
+}
+
+/// Ephemeral ECDH P-384 key pair used for KEY_EXCHANGE.
+pub struct EcdhP384 {
+    secret: EphemeralSecret,
+}
+
+impl Default for EcdhP384 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EcdhP384 {
+    pub fn new() -> Self {
+        Self {
+            secret: EphemeralSecret::random(&mut rand::rngs::OsRng),
+        }
+    }
+
+    /// Returns the public key as the KEY_EXCHANGE ExchangeData (X || Y).
+    pub fn exchange_data(&self) -> [u8; ECDH_P384_EXCHANGE_DATA_SIZE] {
+        let point = self.secret.public_key().to_encoded_point(false);
+        let mut exchange_data = [0u8; ECDH_P384_EXCHANGE_DATA_SIZE];
+        // Skip the SEC1 uncompressed point tag
+        exchange_data.copy_from_slice(&point.as_bytes()[1..]);
+        exchange_data
+    }
+
+    /// Computes the DHE secret from the Responder's ExchangeData.
+    pub fn compute_shared_secret(
+        &self,
+        peer_exchange_data: &[u8],
+    ) -> SpdmRequesterResult<[u8; SHA384_HASH_SIZE]> {
+        if peer_exchange_data.len() != ECDH_P384_EXCHANGE_DATA_SIZE {
+            Err(SpdmRequesterError::InvalidResponse)?;
+        }
+        let mut sec1 = [0u8; ECDH_P384_EXCHANGE_DATA_SIZE + 1];
+        sec1[0] = 0x04;
+        sec1[1..].copy_from_slice(peer_exchange_data);
+        let peer_key =
+            PublicKey::from_sec1_bytes(&sec1).map_err(|_| SpdmRequesterError::InvalidResponse)?;
+        let shared_secret = self.secret.diffie_hellman(&peer_key);
+        let mut dhe_secret = [0u8; SHA384_HASH_SIZE];
+        dhe_secret.copy_from_slice(shared_secret.raw_secret_bytes());
+        Ok(dhe_secret)
+    }
+}
+
+/// SPDM certificate chain as returned by GET_CERTIFICATE.
+pub struct SpdmCertChain {
+    raw: Vec<u8>,
+    certs: Vec<X509>,
+}
+
+impl SpdmCertChain {
+    /// Parses an SPDM certificate chain and checks that the header is consistent with the
+    /// certificates it carries.
+    pub fn parse(raw: Vec<u8>) -> SpdmRequesterResult<Self> {
+        if raw.len() <= SPDM_CERT_CHAIN_HEADER_SIZE {
+            Err(SpdmRequesterError::CertChain)?;
+        }
+        let length = u16::from_le_bytes([raw[0], raw[1]]) as usize;
+        if length != raw.len() {
+            Err(SpdmRequesterError::CertChain)?;
+        }
+
+        let mut certs = Vec::new();
+        let mut offset = SPDM_CERT_CHAIN_HEADER_SIZE;
+        while offset < raw.len() {
+            let cert_len = der_len(&raw[offset..]).ok_or(SpdmRequesterError::CertChain)?;
+            let cert = X509::from_der(&raw[offset..offset + cert_len])
+                .map_err(|_| SpdmRequesterError::CertChain)?;
+            // The RootHash is the digest of the first certificate in the chain
+            if certs.is_empty()
+                && sha384(&raw[offset..offset + cert_len])[..]
+                    != raw[4..SPDM_CERT_CHAIN_HEADER_SIZE]
+            {
+                Err(SpdmRequesterError::CertChain)?;
+            }
+            certs.push(cert);
+            offset += cert_len;
+        }
+
+        Ok(Self { raw, certs })
+    }
+
+    /// Verifies that every certificate is signed by its predecessor and that the chain
+    /// starts at, or is issued by, one of the trusted root certificates (DER). A chain
+    /// cannot be trusted without at least one trust anchor.
+    pub fn verify(&self, trusted_roots: &[Vec<u8>]) -> SpdmRequesterResult<()> {
+        if trusted_roots.is_empty() {
+            Err(SpdmRequesterError::CertChain)?;
+        }
+
+        for pair in self.certs.windows(2) {
+            let issuer_key = pair[0]
+                .public_key()
+                .map_err(|_| SpdmRequesterError::CertChain)?;
+            if !pair[1].verify(&issuer_key).unwrap_or(false) {
+                Err(SpdmRequesterError::CertChain)?;
+            }
+        }
+
+        let first = &self.certs[0];
+        let first_der = first.to_der().map_err(|_| SpdmRequesterError::CertChain)?;
+        let trusted = trusted_roots.iter().any(|root_der| {
+            if *root_der == first_der {
+                return true;
+            }
+            X509::from_der(root_der)
+                .and_then(|root| root.public_key())
+                .and_then(|root_key| first.verify(&root_key))
+                .unwrap_or(false)
+        });
+        if !trusted {
+            Err(SpdmRequesterError::CertChain)?;
+        }
+        Ok(())
+    }
+
+    /// Digest of the whole SPDM certificate chain as reported in DIGESTS and used in
+    /// CHALLENGE_AUTH and the session transcript.
+    pub fn digest(&self) -> [u8; SHA384_HASH_SIZE] {
+        sha384(&self.raw)
+    }
+
+    pub fn raw(&self) -> &[u8] {
+        &self.raw
+    }
+
+    pub fn certs(&self) -> &[X509] {
+        &self.certs
+    }
+
+    pub fn leaf_public_key(&self) -> SpdmRequesterResult<PKey<Public>> {
+        self.certs
+            .last()
+            .ok_or(SpdmRequesterError::CertChain)?
+            .public_key()
+            .map_err(|_| SpdmRequesterError::CertChain)
+    }
+}
+
+/// Builds an SPDM certificate chain from DER-encoded certificates, starting with the
+/// root certificate: the Length, Reserved and RootHash header followed by the
+/// certificates.
+pub fn spdm_cert_chain(certs: &[Vec<u8>]) -> SpdmRequesterResult<Vec<u8>> {
+    let root_cert = certs.first().ok_or(SpdmRequesterError::CertChain)?;
+    let total_len = SPDM_CERT_CHAIN_HEADER_SIZE + certs.iter().map(Vec::len).sum::<usize>();
+    let length = u16::try_from(total_len).map_err(|_| SpdmRequesterError::CertChain)?;
+
+    let mut chain = Vec::with_capacity(total_len);
+    chain.extend_from_slice(&length.to_le_bytes());
+    chain.extend_from_slice(&[0, 0]);
+    chain.extend_from_slice(&sha384(root_cert));
+    certs.iter().for_each(|cert| chain.extend_from_slice(cert));
+    Ok(chain)
+}
+
+/// Signs the signing message, i.e. the signing context followed by the transcript hash,
+/// with an ECC P-384 private key in PKCS#8 DER format. The signature is raw `r || s`
+/// over the SHA-384 digest of the message.
+pub fn sign_ecc_p384(
+    private_key: &[u8],
+    message: &[u8],
+) -> SpdmRequesterResult<[u8; ECC_P384_SIGNATURE_SIZE]> {
+    let ec_key = PKey::private_key_from_pkcs8(private_key)
+        .and_then(|key| key.ec_key())
+        .map_err(|_| SpdmRequesterError::Crypto)?;
+    let sig = EcdsaSig::sign(&sha384(message), &ec_key).map_err(|_| SpdmRequesterError::Crypto)?;
+
+    let coord_size = ECC_P384_SIGNATURE_SIZE / 2;
+    let mut signature = [0u8; ECC_P384_SIGNATURE_SIZE];
+    for (coord, value) in signature
+        .chunks_exact_mut(coord_size)
+        .zip([sig.r(), sig.s()])
+    {
+        let value = value
+            .to_vec_padded(coord_size as i32)
+            .map_err(|_| SpdmRequesterError::Crypto)?;
+        coord.copy_from_slice(&value);
+    }
+    Ok(signature)
+}
+
+/// Verifies a Responder signature over the signing message, i.e. the signing context
+/// followed by the transcript hash.
+///
+/// ECDSA P-384 signatures are raw `r || s` over the SHA-384 digest of the message.
+/// ML-DSA-87 signatures are over the message itself with an empty context.
+pub fn verify_signature(
+    asym_algo: AsymAlgo,
+    public_key: &PKey<Public>,
+    message: &[u8],
+    signature: &[u8],
+) -> SpdmRequesterResult<()> {
+    if signature.len() != asym_algo.signature_size() {
+        Err(SpdmRequesterError::SignatureVerification)?;
+    }
+
+    let valid = match asym_algo {
+        AsymAlgo::EccP384 => {
+            if public_key.id() != Id::EC {
+                Err(SpdmRequesterError::SignatureVerification)?;
+            }
+            let ec_key = public_key
+                .ec_key()
+                .map_err(|_| SpdmRequesterError::Crypto)?;
+            let coord_size = ECC_P384_SIGNATURE_SIZE / 2;
+            let r = BigNum::from_slice(&signature[..coord_size])
+                .map_err(|_| SpdmRequesterError::Crypto)?;
+            let s = BigNum::from_slice(&signature[coord_size..])
+                .map_err(|_| SpdmRequesterError::Crypto)?;
+            EcdsaSig::from_private_components(r, s)
+                .and_then(|sig| sig.verify(&sha384(message), &ec_key))
+                .map_err(|_| SpdmRequesterError::SignatureVerification)?
+        }
+        AsymAlgo::MlDsa87 => Verifier::new_without_digest(public_key)
+            .and_then(|mut verifier| verifier.verify_oneshot(signature, message))
+            .map_err(|_| SpdmRequesterError::SignatureVerification)?,
+    };
+
+    if !valid {
+        Err(SpdmRequesterError::SignatureVerification)?;
+    }
+    Ok(())
+}
+
+/// Returns the length of the DER-encoded certificate at the start of `buf`.
+fn der_len(buf: &[u8]) -> Option<usize> {
+    // Certificates are a SEQUENCE with a definite length of at most 64 KiB
+    if buf.len() < 2 || buf[0] != 0x30 {
+        return None;
+    }
+    let (len, hdr_len) = match buf[1] {
+        len if len < 0x80 => (len as usize, 2),
+        0x81 => (*buf.get(2)? as usize, 3),
+        0x82 => (u16::from_be_bytes([*buf.get(2)?, *buf.get(3)?]) as usize, 4),
+        _ => return None,
+    };
+    let total_len = hdr_len + len;
+    (total_len <= buf.len()).then_some(total_len)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use openssl::asn1::Asn1Time;
+    use openssl::ec::{EcGroup, EcKey};
+    use openssl::hash::MessageDigest;
+    use openssl::nid::Nid;
+    use openssl::pkey::Private;
+    use openssl::x509::{X509Builder, X509NameBuilder};
+
+    fn p384_key() -> PKey<Private> {
+        let group = EcGroup::from_curve_name(Nid::SECP384R1).unwrap();
+        PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap()
+    }
+
+    fn cert(subject: &str, key: &PKey<Private>, issuer: &str, issuer_key: &PKey<Private>) -> X509 {
+        let mut subject_name = X509NameBuilder::new().unwrap();
+        subject_name.append_entry_by_text("CN", subject).unwrap();
+        let mut issuer_name = X509NameBuilder::new().unwrap();
+        issuer_name.append_entry_by_text("CN", issuer).unwrap();
+
+        let mut builder = X509Builder::new().unwrap();
+        builder.set_version(2).unwrap();
+        builder.set_subject_name(&subject_name.build()).unwrap();
+        builder.set_issuer_name(&issuer_name.build()).unwrap();
+        builder.set_pubkey(key).unwrap();
+        builder
+            .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&Asn1Time::days_from_now(1).unwrap())
+            .unwrap();
+        builder.sign(issuer_key, MessageDigest::sha384()).unwrap();
+        builder.build()
+    }
+
+    fn spdm_cert_chain(certs: &[&X509]) -> Vec<u8> {
+        let ders: Vec<Vec<u8>> = certs.iter().map(|c| c.to_der().unwrap()).collect();
+        super::spdm_cert_chain(&ders).unwrap()
+    }
+
+    #[test]
+    fn test_cert_chain_verify() {
+        let root_key = p384_key();
+        let leaf_key = p384_key();
+        let root = cert("root", &root_key, "root", &root_key);
+        let leaf = cert("leaf", &leaf_key, "root", &root_key);
+
+        let chain = SpdmCertChain::parse(spdm_cert_chain(&[&root, &leaf])).unwrap();
+        assert_eq!(chain.certs().len(), 2);
+        assert_eq!(chain.verify(&[]), Err(SpdmRequesterError::CertChain));
+        assert!(chain.verify(&[root.to_der().unwrap()]).is_ok());
+
+        let other_key = p384_key();
+        let other_root = cert("root", &other_key, "root", &other_key);
+        assert_eq!(
+            chain.verify(&[other_root.to_der().unwrap()]),
+            Err(SpdmRequesterError::CertChain)
+        );
+
+        // A leaf that was not issued by the previous certificate is rejected
+        let chain = SpdmCertChain::parse(spdm_cert_chain(&[&other_root, &leaf])).unwrap();
+        assert_eq!(
+            chain.verify(&[other_root.to_der().unwrap()]),
+            Err(SpdmRequesterError::CertChain)
+        );
+
+        // The RootHash must match the first certificate
+        let mut raw = spdm_cert_chain(&[&root, &leaf]);
+        raw[4] ^= 0xFF;
+        assert!(SpdmCertChain::parse(raw).is_err());
+    }
+
+    #[test]
+    fn test_ecdsa_signature_verify() {
+        let key = p384_key();
+        let message = b"signing context || transcript hash";
+        let sig = EcdsaSig::sign(&sha384(message), &key.ec_key().unwrap()).unwrap();
+        let mut raw_sig = [0u8; ECC_P384_SIGNATURE_SIZE];
+        raw_sig[..48].copy_from_slice(&sig.r().to_vec_padded(48).unwrap());
+        raw_sig[48..].copy_from_slice(&sig.s().to_vec_padded(48).unwrap());
+
+        let public_key = PKey::public_key_from_der(&key.public_key_to_der().unwrap()).unwrap();
+        assert!(verify_signature(AsymAlgo::EccP384, &public_key, message, &raw_sig).is_ok());
+
+        assert_eq!(
+            verify_signature(
+                AsymAlgo::EccP384,
+                &public_key,
+                b"other transcript hash",
+                &raw_sig
+            ),
+            Err(SpdmRequesterError::SignatureVerification)
+        );
+    }
+
+    #[test]
+    fn test_ecdsa_sign() {
+        let key = p384_key();
+        let message = b"signing context || transcript hash";
+        let raw_sig = sign_ecc_p384(&key.private_key_to_pkcs8().unwrap(), message).unwrap();
+
+        let public_key = PKey::public_key_from_der(&key.public_key_to_der().unwrap()).unwrap();
+        assert!(verify_signature(AsymAlgo::EccP384, &public_key, message, &raw_sig).is_ok());
+        assert_eq!(
+            sign_ecc_p384(&[0; 16], message),
+            Err(SpdmRequesterError::Crypto)
+        );
+    }
+
+    #[test]
+    fn test_ecdh_shared_secret() {
+        let requester = EcdhP384::new();
+        let responder = EcdhP384::new();
+        let secret1 = requester
+            .compute_shared_secret(&responder.exchange_data())
+            .unwrap();
+        let secret2 = responder
+            .compute_shared_secret(&requester.exchange_data())
+            .unwrap();
+        assert_eq!(secret1, secret2);
+    }
+}
@@ -0,0 +1,35 @@
+// Licensed under the Apache-2.0 license
+
+use caliptra_mcu_spdm_lib::codec::CodecError;
+
+pub type SpdmRequesterResult<T> = Result<T, SpdmRequesterError>;
+
+#[derive(Debug, PartialEq)]
+pub enum SpdmRequesterError {
+    /// The transport failed to deliver the request or no response was received.
+    Transport,
+    /// The Responder returned an ERROR message with the given code and data.
+    ErrorResponse { code: u8, data: u8 },
+    /// The response could not be decoded or does not match the request.
+    InvalidResponse,
+    /// Encoding or decoding of a message failed.
+    Codec(CodecError),
+    /// No common version, capability or algorithm with the Responder.
+    NegotiationFailed,
+    /// The request is not allowed in the current connection or session state.
+    UnexpectedState,
+    /// The certificate chain is malformed or is not rooted in a trusted anchor.
+    CertChain,
+    /// A Responder signature did not verify.
+    SignatureVerification,
+    /// ResponderVerifyData or a secured message did not authenticate.
+    MacVerification,
+    /// A cryptographic primitive failed.
+    Crypto,
+}
+
+impl From<CodecError> for SpdmRequesterError {
+    fn from(e: CodecError) -> Self {
+        SpdmRequesterError::Codec(e)
+    }
+}
@@ -0,0 +1,21 @@
+// Licensed under the Apache-2.0 license
+
+//! Host-side SPDM requester.
+//!
+//! The requester reuses the message definitions and codec of `caliptra-mcu-spdm-lib`
+//! and drives the connection and session establishment flows against an SPDM Responder
+//! over a pluggable transport. Signatures, certificate chains, transcripts and
+//! session HMACs are verified on the host.
+
+pub mod crypto;
+pub mod error;
+mod messages;
+pub mod requester;
+pub mod session;
+pub mod transport;
+
+pub use error::{SpdmRequesterError, SpdmRequesterResult};
+pub use requester::{
+    MeasurementBlock, Measurements, RequesterConfig, RequesterIdentity, SpdmRequester,
+};
+pub use transport::SpdmTransport;
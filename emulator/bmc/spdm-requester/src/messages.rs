@@ -0,0 +1,341 @@
+// Licensed under the Apache-2.0 license
+
+//! Request and response payloads that follow the common SPDM header. The responder
+//! implementation in spdm-lib keeps its wire structures private, so the requester
+//! defines the counterparts here on top of the shared codec and protocol types.
+
+use crate::error::{SpdmRequesterError, SpdmRequesterResult};
+use bitfield::bitfield;
+use caliptra_mcu_spdm_lib::codec::{decode_u8_slice, Codec, CommonCodec, MessageBuf};
+use caliptra_mcu_spdm_lib::protocol::*;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+pub(crate) const RANDOM_DATA_LEN: usize = 32;
+pub(crate) const DMTF_STANDARDS_BODY_ID: u8 = 0;
+pub(crate) const SM_DATA_VERSION: u8 = 1;
+pub(crate) const SM_DATA_ID_VERSION_SELECTION: u8 = 0;
+pub(crate) const SM_DATA_ID_SUPPORTED_VERSION_LIST: u8 = 1;
+
+#[derive(FromBytes, IntoBytes, Immutable, Default)]
+#[repr(C)]
+pub(crate) struct ReqParams {
+    pub param1: u8,
+    pub param2: u8,
+}
+impl CommonCodec for ReqParams {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+pub(crate) struct VersionRspCommon {
+    pub param1: u8,
+    pub param2: u8,
+    pub reserved: u8,
+    pub version_num_entry_count: u8,
+}
+impl CommonCodec for VersionRspCommon {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub(crate) struct CapabilitiesV12 {
+    pub param1: u8,
+    pub param2: u8,
+    pub reserved: u8,
+    pub ct_exponent: u8,
+    pub reserved2: u16,
+    pub flags: CapabilityFlags,
+    pub data_transfer_size: u32,
+    pub max_spdm_msg_size: u32,
+}
+impl CommonCodec for CapabilitiesV12 {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub(crate) struct NegotiateAlgorithmsReq {
+    pub num_alg_struct_tables: u8,
+    pub param2: u8,
+    pub length: u16,
+    pub measurement_specification: MeasurementSpecification,
+    pub other_param_support: OtherParamSupport,
+    pub base_asym_algo: BaseAsymAlgo,
+    pub base_hash_algo: BaseHashAlgo,
+    pub pqc_asym_algo: PqcAsymAlgo,
+    pub reserved_1: [u8; 8],
+    pub ext_asym_count: u8,
+    pub ext_hash_count: u8,
+    pub reserved_2: u8,
+    pub mel_specification: MelSpecification,
+}
+impl CommonCodec for NegotiateAlgorithmsReq {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub(crate) struct AlgorithmsRsp {
+    pub num_alg_struct_tables: u8,
+    pub reserved_1: u8,
+    pub length: u16,
+    pub measurement_specification_sel: MeasurementSpecification,
+    pub other_params_selection: OtherParamSupport,
+    pub measurement_hash_algo: MeasurementHashAlgo,
+    pub base_asym_sel: BaseAsymAlgo,
+    pub base_hash_sel: BaseHashAlgo,
+    pub pqc_asym_sel: PqcAsymAlgo,
+    pub reserved_2: [u8; 7],
+    pub mel_specification_sel: MelSpecification,
+    pub ext_asym_sel_count: u8,
+    pub ext_hash_sel_count: u8,
+    pub reserved_3: [u8; 2],
+}
+impl CommonCodec for AlgorithmsRsp {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum AlgType {
+    Dhe = 2,
+    AeadCipherSuite = 3,
+    ReqBaseAsymAlg = 4,
+    KeySchedule = 5,
+}
+
+bitfield! {
+    #[derive(FromBytes, IntoBytes, Immutable, Default, Clone, Copy)]
+    #[repr(C)]
+    pub struct AlgStructure(u32);
+    impl Debug;
+    u8;
+        pub alg_type, set_alg_type: 7, 0;
+        pub ext_alg_count, set_ext_alg_count: 11, 8;
+        pub fixed_alg_count, set_fixed_alg_count: 15, 12;
+    u16;
+        pub alg_supported, set_alg_supported: 31, 16;
+}
+impl CommonCodec for AlgStructure {}
+
+impl AlgStructure {
+    pub fn new(alg_type: AlgType, alg_supported: u16) -> Self {
+        let mut alg_struct = AlgStructure::default();
+        alg_struct.set_alg_type(alg_type as u8);
+        alg_struct.set_fixed_alg_count(2);
+        alg_struct.set_ext_alg_count(0);
+        alg_struct.set_alg_supported(alg_supported);
+        alg_struct
+    }
+}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub(crate) struct CertificateRspCommon {
+    pub param1: u8,
+    pub param2: u8,
+    pub portion_length: u16,
+    pub remainder_length: u16,
+}
+impl CommonCodec for CertificateRspCommon {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+pub(crate) struct ChallengeReq {
+    pub slot_id: u8,
+    pub meas_summary_hash_type: u8,
+    pub nonce: [u8; SPDM_NONCE_LEN],
+}
+impl CommonCodec for ChallengeReq {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+pub(crate) struct ChallengeAuthRspCommon {
+    pub attr: u8,
+    pub slot_mask: u8,
+}
+impl CommonCodec for ChallengeAuthRspCommon {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+pub(crate) struct GetMeasurementsSignatureFields {
+    pub nonce: [u8; SPDM_NONCE_LEN],
+    pub slot_id: u8,
+}
+impl CommonCodec for GetMeasurementsSignatureFields {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+pub(crate) struct MeasurementsRspCommon {
+    pub param1: u8,
+    pub param2: u8,
+    pub num_blocks: u8,
+    pub measurement_record_len: [u8; 3],
+}
+impl CommonCodec for MeasurementsRspCommon {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub(crate) struct MeasurementBlockHdr {
+    pub index: u8,
+    pub measurement_specification: u8,
+    pub measurement_size: u16,
+}
+impl CommonCodec for MeasurementBlockHdr {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub(crate) struct DmtfMeasurementValueHdr {
+    pub value_type: u8,
+    pub value_size: u16,
+}
+impl CommonCodec for DmtfMeasurementValueHdr {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub(crate) struct KeyExchangeReqCommon {
+    pub meas_summary_hash_type: u8,
+    pub slot_id: u8,
+    pub req_session_id: u16,
+    pub session_policy: u8,
+    pub reserved: u8,
+    pub random_data: [u8; RANDOM_DATA_LEN],
+}
+impl CommonCodec for KeyExchangeReqCommon {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub(crate) struct KeyExchangeRspCommon {
+    pub heartbeat_period: u8,
+    pub reserved: u8,
+    pub rsp_session_id: u16,
+    pub mut_auth_requested: u8,
+    pub slot_id_param: u8,
+    pub random_data: [u8; RANDOM_DATA_LEN],
+}
+impl CommonCodec for KeyExchangeRspCommon {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+pub(crate) struct ChunkGetReq {
+    pub param1: u8,
+    pub handle: u8,
+    pub chunk_seq_num: u16,
+}
+impl CommonCodec for ChunkGetReq {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub(crate) struct ChunkResponseCommon {
+    pub chunk_sender_attr: u8,
+    pub handle: u8,
+    pub chunk_seq_num: u16,
+    pub reserved: u16,
+    pub chunk_size: u32,
+}
+impl CommonCodec for ChunkResponseCommon {}
+
+/// Builds an SPDM request with the codec. The encoder closure appends the fields that
+/// follow the common header.
+pub(crate) fn encode_request<F>(
+    version: SpdmVersion,
+    code: ReqRespCode,
+    encode_payload: F,
+) -> SpdmRequesterResult<Vec<u8>>
+where
+    F: FnOnce(&mut MessageBuf) -> SpdmRequesterResult<()>,
+{
+    let mut raw = vec![0u8; MAX_MCTP_SPDM_MSG_SIZE];
+    let mut buf = MessageBuf::new(&mut raw);
+    SpdmMsgHdr::new(version, code).encode(&mut buf)?;
+    encode_payload(&mut buf)?;
+    let len = buf.data_offset();
+    raw.truncate(len);
+    Ok(raw)
+}
+
+/// Sequential reader over a received message.
+///
+/// Responses may carry trailing transport padding, e.g. DOE dword alignment, so
+/// transcripts are built from the decoded part of a message rather than the whole buffer.
+pub(crate) struct MessageReader {
+    data: Vec<u8>,
+    offset: usize,
+}
+
+impl MessageReader {
+    pub fn new(msg: &[u8]) -> Self {
+        Self {
+            data: msg.to_vec(),
+            offset: 0,
+        }
+    }
+
+    pub fn decode<T: Codec>(&mut self) -> SpdmRequesterResult<T> {
+        let remaining = self.data.len() - self.offset;
+        let mut buf = MessageBuf::new(&mut self.data[self.offset..]);
+        buf.put_data(remaining)?;
+        let value = T::decode(&mut buf)?;
+        self.offset += buf.data_offset();
+        Ok(value)
+    }
+
+    pub fn read_bytes(&mut self, len: usize) -> SpdmRequesterResult<Vec<u8>> {
+        let mut bytes = vec![0u8; len];
+        let remaining = self.data.len() - self.offset;
+        let mut buf = MessageBuf::new(&mut self.data[self.offset..]);
+        buf.put_data(remaining)?;
+        decode_u8_slice(&mut buf, &mut bytes)?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    pub fn read_array<const N: usize>(&mut self) -> SpdmRequesterResult<[u8; N]> {
+        let bytes = self.read_bytes(N)?;
+        Ok(bytes.try_into().unwrap())
+    }
+
+    /// Number of bytes consumed so far.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The message up to the current position.
+    pub fn consumed(&self) -> &[u8] {
+        &self.data[..self.offset]
+    }
+}
+
+/// Opaque data advertising the supported secured message versions (DSP0277) in the
+/// general opaque data format.
+pub(crate) fn sm_version_list_opaque_data(versions: &[SpdmVersion]) -> Vec<u8> {
+    let mut element = vec![
+        SM_DATA_VERSION,
+        SM_DATA_ID_SUPPORTED_VERSION_LIST,
+        versions.len() as u8,
+    ];
+    for version in versions {
+        element.extend_from_slice(&sm_version(*version).to_le_bytes());
+    }
+
+    // GeneralOpaqueDataHdr, then a single DMTF opaque element padded to 4 bytes
+    let mut opaque_data = vec![1, 0, 0, 0, DMTF_STANDARDS_BODY_ID, 0];
+    opaque_data.extend_from_slice(&(element.len() as u16).to_le_bytes());
+    opaque_data.extend_from_slice(&element);
+    opaque_data.resize(opaque_data.len().next_multiple_of(4), 0);
+    opaque_data
+}
+
+/// Extracts the secured message version selected by the Responder from the
+/// KEY_EXCHANGE_RSP opaque data.
+pub(crate) fn sm_selected_version(opaque_data: &[u8]) -> SpdmRequesterResult<SpdmVersion> {
+    // GeneralOpaqueDataHdr (4) | OpaqueElementHdr (4) | SMDataVersion | SMDataID | Version (2)
+    const SELECTION_LEN: usize = 12;
+    if opaque_data.len() < SELECTION_LEN
+        || opaque_data[0] != 1
+        || opaque_data[4] != DMTF_STANDARDS_BODY_ID
+        || opaque_data[5] != 0
+        || opaque_data[8] != SM_DATA_VERSION
+        || opaque_data[9] != SM_DATA_ID_VERSION_SELECTION
+    {
+        Err(SpdmRequesterError::InvalidResponse)?;
+    }
+    let version = u16::from_le_bytes([opaque_data[10], opaque_data[11]]);
+    SpdmVersion::new((version >> 12) as u8, ((version >> 8) & 0xF) as u8)
+        .map_err(|_| SpdmRequesterError::NegotiationFailed)
+}
+
+fn sm_version(version: SpdmVersion) -> u16 {
+    ((version.major() as u16) << 12) | ((version.minor() as u16) << 8)
+}
@@ -0,0 +1,1347 @@
+// Licensed under the Apache-2.0 license
+
+//! SPDM requester flows: connection setup (VCA), authentication, measurements and
+//! session establishment.
+
+use crate::crypto::*;
+use crate::error::{SpdmRequesterError, SpdmRequesterResult};
+use crate::messages::*;
+use crate::session::{SecureSession, SessionState};
+use crate::transport::SpdmTransport;
+use caliptra_mcu_spdm_lib::codec::{encode_u8_slice, Codec};
+use caliptra_mcu_spdm_lib::commands::certificate_rsp::{
+    CertificateReqAttributes, GetCertificateReq, SlotId,
+};
+use caliptra_mcu_spdm_lib::commands::error_rsp::ErrorCode;
+use caliptra_mcu_spdm_lib::protocol::*;
+use rand::RngCore;
+use std::collections::BTreeMap;
+use zerocopy::{FromZeros, Immutable, IntoBytes};
+
+/// Maximum number of certificate slots defined by SPDM.
+pub const MAX_SPDM_CERT_SLOTS: u8 = 8;
+
+/// Size of the certificate chain portion requested per GET_CERTIFICATE.
+const CERT_PORTION_LEN: u16 = 0x200;
+
+/// GET_MEASUREMENTS operation requesting the total number of measurement indices.
+pub const MEASUREMENT_OPERATION_TOTAL_NUMBER: u8 = 0;
+/// GET_MEASUREMENTS operation requesting all measurement blocks.
+pub const MEASUREMENT_OPERATION_ALL: u8 = 0xFF;
+
+const MEASUREMENT_ATTR_SIGNATURE_REQUESTED: u8 = 1 << 0;
+const MEASUREMENT_VALUE_RAW_BIT_STREAM: u8 = 1 << 7;
+const CHUNK_ATTR_LAST_CHUNK: u8 = 1 << 0;
+
+const KEY_UPDATE_OPERATION_UPDATE_KEY: u8 = 1;
+const KEY_UPDATE_OPERATION_UPDATE_ALL_KEYS: u8 = 2;
+const KEY_UPDATE_OPERATION_VERIFY_NEW_KEY: u8 = 3;
+
+// MutAuthRequested of KEY_EXCHANGE_RSP: mutual authentication with the encapsulated
+// request flow
+const MUT_AUTH_REQUESTED_ENCAP_REQUEST_FLOW: u8 = 1 << 1;
+const ENCAP_PAYLOAD_TYPE_PRESENT: u8 = 1;
+const ENCAP_PAYLOAD_TYPE_REQ_SLOT_NUMBER: u8 = 2;
+const FINISH_ATTR_SIGNATURE_INCLUDED: u8 = 1 << 0;
+
+// The Requester certificate chain is provisioned in slot 0
+const REQUESTER_CERT_SLOT_ID: u8 = 0;
+
+/// Local configuration of the Requester.
+#[derive(Debug, Clone)]
+pub struct RequesterConfig {
+    /// SPDM versions supported by the Requester. Only 1.2 and later are supported.
+    pub versions: Vec<SpdmVersion>,
+    /// Secured message versions (DSP0277) offered in KEY_EXCHANGE.
+    pub secure_versions: Vec<SpdmVersion>,
+    /// Capabilities advertised in GET_CAPABILITIES.
+    pub capabilities: DeviceCapabilities,
+    /// Algorithms offered in NEGOTIATE_ALGORITHMS.
+    pub algorithms: DeviceAlgorithms,
+    /// DER encoded trust anchors for the Responder certificate chains. Certificate
+    /// retrieval fails if none is configured.
+    pub trusted_roots: Vec<Vec<u8>>,
+    /// Certificate chain and key to authenticate with if the Responder requests mutual
+    /// authentication in KEY_EXCHANGE_RSP. Advertising it requires MUT_AUTH_CAP,
+    /// ENCAP_CAP and CERT_CAP in `capabilities` and the ECC P-384 ReqBaseAsymAlg in
+    /// `algorithms`.
+    pub identity: Option<RequesterIdentity>,
+}
+
+/// Certificate chain and key of the Requester for session-based mutual authentication.
+#[derive(Debug, Clone)]
+pub struct RequesterIdentity {
+    /// DER encoded certificates, starting with the root certificate.
+    pub certs: Vec<Vec<u8>>,
+    /// PKCS#8 DER encoded ECC P-384 private key of the leaf certificate.
+    pub private_key: Vec<u8>,
+}
+
+impl Default for RequesterConfig {
+    fn default() -> Self {
+        let mut flags = CapabilityFlags::new(0);
+        flags.set_encrypt_cap(1);
+        flags.set_mac_cap(1);
+        flags.set_key_ex_cap(1);
+        flags.set_hbeat_cap(1);
+        flags.set_key_upd_cap(1);
+        flags.set_chunk_cap(1);
+
+        let mut algorithms = DeviceAlgorithms::default();
+        algorithms.set_pqc_asym_algo();
+        algorithms.set_other_param_support();
+        algorithms.set_dhe_group();
+        algorithms.set_aead_cipher_suite();
+        algorithms.set_spdm_key_schedule();
+
+        Self {
+            versions: vec![SpdmVersion::V12, SpdmVersion::V13, SpdmVersion::V14],
+            secure_versions: vec![SpdmVersion::V10, SpdmVersion::V11, SpdmVersion::V12],
+            capabilities: DeviceCapabilities {
+                ct_exponent: 0,
+                flags,
+                data_transfer_size: MAX_MCTP_SPDM_MSG_SIZE as u32,
+                max_spdm_msg_size: 0x10000,
+            },
+            algorithms,
+            trusted_roots: Vec::new(),
+            identity: None,
+        }
+    }
+}
+
+/// A measurement block returned in MEASUREMENTS.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeasurementBlock {
+    pub index: u8,
+    /// DMTFSpecMeasurementValueType, bits [6:0].
+    pub value_type: u8,
+    /// Whether the value is a raw bit stream rather than a digest.
+    pub raw_bit_stream: bool,
+    pub value: Vec<u8>,
+}
+
+/// Measurements returned by the Responder.
+#[derive(Debug, Clone, Default)]
+pub struct Measurements {
+    /// Total number of measurement indices, only set for
+    /// `MEASUREMENT_OPERATION_TOTAL_NUMBER`.
+    pub total_indices: u8,
+    pub content_changed: u8,
+    pub blocks: Vec<MeasurementBlock>,
+    /// Whether the response was signed and the signature verified.
+    pub verified: bool,
+}
+
+/// Algorithms selected by the Responder in ALGORITHMS.
+#[derive(Debug, Clone, Copy)]
+struct SelectedAlgorithms {
+    asym_algo: AsymAlgo,
+    measurement_hash_algo: MeasurementHashAlgo,
+    key_exchange: bool,
+}
+
+/// An SPDM Requester connected to a single Responder.
+pub struct SpdmRequester<T: SpdmTransport> {
+    transport: T,
+    config: RequesterConfig,
+    version: Option<SpdmVersion>,
+    peer_capabilities: Option<DeviceCapabilities>,
+    algorithms: Option<SelectedAlgorithms>,
+    // Transcripts: VCA is kept separately and prefixes M1, L1 and TH
+    vca: Vec<u8>,
+    m1: Vec<u8>,
+    l1: Vec<u8>,
+    authenticated: bool,
+    digests: BTreeMap<u8, [u8; SHA384_HASH_SIZE]>,
+    cert_chains: BTreeMap<u8, SpdmCertChain>,
+    // Sessions by session ID, with the TH transcript of those in the handshake phase
+    sessions: BTreeMap<u32, (SecureSession, Vec<u8>)>,
+}
+
+impl<T: SpdmTransport> SpdmRequester<T> {
+    pub fn new(transport: T, config: RequesterConfig) -> Self {
+        Self {
+            transport,
+            config,
+            version: None,
+            peer_capabilities: None,
+            algorithms: None,
+            vca: Vec::new(),
+            m1: Vec::new(),
+            l1: Vec::new(),
+            authenticated: false,
+            digests: BTreeMap::new(),
+            cert_chains: BTreeMap::new(),
+            sessions: BTreeMap::new(),
+        }
+    }
+
+    /// Negotiated SPDM version, once VERSION has been received.
+    pub fn version(&self) -> Option<SpdmVersion> {
+        self.version
+    }
+
+    pub fn peer_capabilities(&self) -> Option<DeviceCapabilities> {
+        self.peer_capabilities
+    }
+
+    /// Signature algorithm selected by the Responder.
+    pub fn asym_algo(&self) -> Option<AsymAlgo> {
+        self.algorithms.map(|algorithms| algorithms.asym_algo)
+    }
+
+    pub fn digest(&self, slot_id: u8) -> Option<&[u8; SHA384_HASH_SIZE]> {
+        self.digests.get(&slot_id)
+    }
+
+    pub fn cert_chain(&self, slot_id: u8) -> Option<&SpdmCertChain> {
+        self.cert_chains.get(&slot_id)
+    }
+
+    pub fn session(&self, session_id: u32) -> Option<&SecureSession> {
+        self.sessions.get(&session_id).map(|(session, _)| session)
+    }
+
+    pub fn transport_mut(&mut self) -> &mut T {
+        &mut self.transport
+    }
+
+    /// Runs GET_VERSION, GET_CAPABILITIES and NEGOTIATE_ALGORITHMS.
+    pub fn init_connection(&mut self) -> SpdmRequesterResult<()> {
+        self.get_version()?;
+        self.get_capabilities()?;
+        self.negotiate_algorithms()
+    }
+
+    /// Sends GET_VERSION and selects the highest common version. This resets the
+    /// connection state.
+    pub fn get_version(&mut self) -> SpdmRequesterResult<SpdmVersion> {
+        self.reset_connection();
+
+        let req = encode_request(SpdmVersion::V10, ReqRespCode::GetVersion, |buf| {
+            ReqParams::default().encode(buf)?;
+            Ok(())
+        })?;
+        let rsp = self.send_request(&req, SpdmVersion::V10, ReqRespCode::Version, None)?;
+
+        let mut reader = MessageReader::new(&rsp);
+        reader.decode::<SpdmMsgHdr>()?;
+        let version_rsp = reader.decode::<VersionRspCommon>()?;
+        let mut peer_versions = Vec::new();
+        for _ in 0..version_rsp.version_num_entry_count {
+            let entry = reader.decode::<u16>()?;
+            if let Ok(version) = SpdmVersion::try_from((entry >> 8) as u8) {
+                peer_versions.push(version);
+            }
+        }
+
+        let version = self
+            .config
+            .versions
+            .iter()
+            .filter(|v| **v >= SpdmVersion::V12 && peer_versions.contains(v))
+            .fold(None, |best: Option<SpdmVersion>, v| match best {
+                Some(best) if best >= *v => Some(best),
+                _ => Some(*v),
+            })
+            .ok_or(SpdmRequesterError::NegotiationFailed)?;
+
+        self.vca.extend_from_slice(&req);
+        self.vca.extend_from_slice(reader.consumed());
+        self.version = Some(version);
+        Ok(version)
+    }
+
+    /// Sends GET_CAPABILITIES with the configured capabilities.
+    pub fn get_capabilities(&mut self) -> SpdmRequesterResult<DeviceCapabilities> {
+        let version = self.connection_version()?;
+        if self.peer_capabilities.is_some() {
+            Err(SpdmRequesterError::UnexpectedState)?;
+        }
+
+        let local = self.config.capabilities;
+        let req = encode_request(version, ReqRespCode::GetCapabilities, |buf| {
+            CapabilitiesV12 {
+                param1: 0,
+                param2: 0,
+                reserved: 0,
+                ct_exponent: local.ct_exponent,
+                reserved2: 0,
+                flags: local.flags,
+                data_transfer_size: local.data_transfer_size,
+                max_spdm_msg_size: local.max_spdm_msg_size,
+            }
+            .encode(buf)?;
+            Ok(())
+        })?;
+        let rsp = self.send_request(&req, version, ReqRespCode::Capabilities, None)?;
+
+        let mut reader = MessageReader::new(&rsp);
+        reader.decode::<SpdmMsgHdr>()?;
+        let caps = reader.decode::<CapabilitiesV12>()?;
+        let peer = DeviceCapabilities {
+            ct_exponent: caps.ct_exponent,
+            flags: caps.flags,
+            data_transfer_size: caps.data_transfer_size,
+            max_spdm_msg_size: caps.max_spdm_msg_size,
+        };
+        if peer.data_transfer_size < MIN_DATA_TRANSFER_SIZE_V12
+            || peer.max_spdm_msg_size < peer.data_transfer_size
+        {
+            Err(SpdmRequesterError::InvalidResponse)?;
+        }
+
+        self.vca.extend_from_slice(&req);
+        self.vca.extend_from_slice(reader.consumed());
+        self.peer_capabilities = Some(peer);
+        Ok(peer)
+    }
+
+    /// Sends NEGOTIATE_ALGORITHMS with the configured algorithms and validates the
+    /// Responder selection.
+    pub fn negotiate_algorithms(&mut self) -> SpdmRequesterResult<()> {
+        let version = self.connection_version()?;
+        let peer_caps = self
+            .peer_capabilities
+            .ok_or(SpdmRequesterError::UnexpectedState)?;
+        if self.algorithms.is_some() {
+            Err(SpdmRequesterError::UnexpectedState)?;
+        }
+
+        let local = self.config.algorithms.clone();
+        let key_exchange =
+            self.config.capabilities.flags.key_ex_cap() != 0 && peer_caps.flags.key_ex_cap() != 0;
+        let mut alg_structs = Vec::new();
+        if key_exchange {
+            alg_structs.push(AlgStructure::new(
+                AlgType::Dhe,
+                bits(&local.dhe_group) as u16,
+            ));
+            alg_structs.push(AlgStructure::new(
+                AlgType::AeadCipherSuite,
+                bits(&local.aead_cipher_suite) as u16,
+            ));
+            alg_structs.push(AlgStructure::new(
+                AlgType::ReqBaseAsymAlg,
+                bits(&local.req_base_asym_algo) as u16,
+            ));
+            alg_structs.push(AlgStructure::new(
+                AlgType::KeySchedule,
+                bits(&local.key_schedule) as u16,
+            ));
+        }
+
+        let pqc_asym_algo = if version >= SpdmVersion::V14 {
+            local.pqc_asym_algo
+        } else {
+            PqcAsymAlgo::default()
+        };
+        let mel_specification = if version >= SpdmVersion::V13 {
+            local.mel_specification
+        } else {
+            MelSpecification::default()
+        };
+        let length = size_of::<SpdmMsgHdr>()
+            + size_of::<NegotiateAlgorithmsReq>()
+            + alg_structs.len() * size_of::<AlgStructure>();
+
+        let req = encode_request(version, ReqRespCode::NegotiateAlgorithms, |buf| {
+            NegotiateAlgorithmsReq {
+                num_alg_struct_tables: alg_structs.len() as u8,
+                param2: 0,
+                length: length as u16,
+                measurement_specification: local.measurement_spec,
+                other_param_support: local.other_param_support,
+                base_asym_algo: local.base_asym_algo,
+                base_hash_algo: local.base_hash_algo,
+                pqc_asym_algo,
+                reserved_1: [0; 8],
+                ext_asym_count: 0,
+                ext_hash_count: 0,
+                reserved_2: 0,
+                mel_specification,
+            }
+            .encode(buf)?;
+            for alg_struct in alg_structs.iter() {
+                alg_struct.encode(buf)?;
+            }
+            Ok(())
+        })?;
+        let rsp = self.send_request(&req, version, ReqRespCode::Algorithms, None)?;
+
+        let mut reader = MessageReader::new(&rsp);
+        reader.decode::<SpdmMsgHdr>()?;
+        let algs = reader.decode::<AlgorithmsRsp>()?;
+        if algs.ext_asym_sel_count != 0 || algs.ext_hash_sel_count != 0 {
+            Err(SpdmRequesterError::InvalidResponse)?;
+        }
+
+        // Exactly one of the base and PQC asymmetric algorithms is selected
+        let base_asym_sel = algs.base_asym_sel;
+        let pqc_asym_sel = algs.pqc_asym_sel;
+        let asym_algo = match (bits(&base_asym_sel), bits(&pqc_asym_sel)) {
+            (sel, 0)
+                if sel.count_ones() == 1 && base_asym_sel.tpm_alg_ecdsa_ecc_nist_p384() != 0 =>
+            {
+                AsymAlgo::EccP384
+            }
+            (0, sel) if sel.count_ones() == 1 && pqc_asym_sel.ml_dsa_87() != 0 => AsymAlgo::MlDsa87,
+            _ => Err(SpdmRequesterError::NegotiationFailed)?,
+        };
+        let base_hash_sel = algs.base_hash_sel;
+        if bits(&base_hash_sel).count_ones() != 1 || base_hash_sel.tpm_alg_sha_384() == 0 {
+            Err(SpdmRequesterError::NegotiationFailed)?;
+        }
+        let measurement_hash_algo = algs.measurement_hash_algo;
+        if peer_caps.flags.meas_cap() != MeasCapability::NoMeasurement as u8
+            && (algs.measurement_specification_sel.dmtf_measurement_spec() == 0
+                || (measurement_hash_algo.tpm_alg_sha_384() == 0
+                    && measurement_hash_algo.raw_bit_stream() == 0))
+        {
+            Err(SpdmRequesterError::NegotiationFailed)?;
+        }
+
+        let mut selected = BTreeMap::new();
+        for _ in 0..algs.num_alg_struct_tables {
+            let alg_struct = reader.decode::<AlgStructure>()?;
+            if alg_struct.ext_alg_count() != 0 {
+                Err(SpdmRequesterError::InvalidResponse)?;
+            }
+            selected.insert(alg_struct.alg_type(), alg_struct.alg_supported());
+        }
+
+        if key_exchange {
+            let dhe = selected.get(&(AlgType::Dhe as u8)).copied().unwrap_or(0);
+            let aead = selected
+                .get(&(AlgType::AeadCipherSuite as u8))
+                .copied()
+                .unwrap_or(0);
+            let key_schedule = selected
+                .get(&(AlgType::KeySchedule as u8))
+                .copied()
+                .unwrap_or(0);
+            if dhe != bits(&local.dhe_group) as u16
+                || aead != bits(&local.aead_cipher_suite) as u16
+                || key_schedule != bits(&local.key_schedule) as u16
+            {
+                Err(SpdmRequesterError::NegotiationFailed)?;
+            }
+        }
+
+        self.vca.extend_from_slice(&req);
+        self.vca.extend_from_slice(reader.consumed());
+        self.algorithms = Some(SelectedAlgorithms {
+            asym_algo,
+            measurement_hash_algo,
+            key_exchange,
+        });
+        Ok(())
+    }
+
+    /// Sends GET_DIGESTS and returns the provisioned slot mask.
+    pub fn get_digests(&mut self) -> SpdmRequesterResult<u8> {
+        let version = self.negotiated_version()?;
+        self.reset_transcripts(ReqRespCode::GetDigests);
+
+        let req = encode_request(version, ReqRespCode::GetDigests, |buf| {
+            ReqParams::default().encode(buf)?;
+            Ok(())
+        })?;
+        let rsp = self.send_request(&req, version, ReqRespCode::Digests, None)?;
+
+        let mut reader = MessageReader::new(&rsp);
+        reader.decode::<SpdmMsgHdr>()?;
+        let params = reader.decode::<ReqParams>()?;
+        let slot_mask = params.param2;
+        self.digests.clear();
+        for slot_id in 0..MAX_SPDM_CERT_SLOTS {
+            if slot_mask & (1 << slot_id) != 0 {
+                let digest = reader.read_array::<SHA384_HASH_SIZE>()?;
+                self.digests.insert(slot_id, digest);
+            }
+        }
+
+        self.m1.extend_from_slice(&req);
+        self.m1.extend_from_slice(reader.consumed());
+        Ok(slot_mask)
+    }
+
+    /// Retrieves the certificate chain in `slot_id` with GET_CERTIFICATE, checks it
+    /// against the slot digest and verifies it against the configured trust anchors.
+    pub fn get_certificate(&mut self, slot_id: u8) -> SpdmRequesterResult<&SpdmCertChain> {
+        let version = self.negotiated_version()?;
+        if slot_id >= MAX_SPDM_CERT_SLOTS {
+            Err(SpdmRequesterError::UnexpectedState)?;
+        }
+        self.reset_transcripts(ReqRespCode::GetCertificate);
+
+        let mut raw_chain = Vec::new();
+        loop {
+            let offset = raw_chain.len() as u16;
+            let req = encode_request(version, ReqRespCode::GetCertificate, |buf| {
+                let mut slot = SlotId::new_zeroed();
+                slot.set_slot_id(slot_id);
+                GetCertificateReq {
+                    slot_id: slot,
+                    param2: CertificateReqAttributes::new_zeroed(),
+                    offset,
+                    length: CERT_PORTION_LEN,
+                }
+                .encode(buf)?;
+                Ok(())
+            })?;
+            let rsp = self.send_request(&req, version, ReqRespCode::Certificate, None)?;
+
+            let mut reader = MessageReader::new(&rsp);
+            reader.decode::<SpdmMsgHdr>()?;
+            let cert_rsp = reader.decode::<CertificateRspCommon>()?;
+            let portion_length = cert_rsp.portion_length as usize;
+            let remainder_length = cert_rsp.remainder_length;
+            if cert_rsp.param1 & 0x0F != slot_id || portion_length == 0 {
+                Err(SpdmRequesterError::InvalidResponse)?;
+            }
+            raw_chain.extend_from_slice(&reader.read_bytes(portion_length)?);
+
+            self.m1.extend_from_slice(&req);
+            self.m1.extend_from_slice(reader.consumed());
+            if remainder_length == 0 {
+                break;
+            }
+        }
+
+        let cert_chain = SpdmCertChain::parse(raw_chain)?;
+        if let Some(digest) = self.digests.get(&slot_id) {
+            if *digest != cert_chain.digest() {
+                Err(SpdmRequesterError::CertChain)?;
+            }
+        }
+        cert_chain.verify(&self.config.trusted_roots)?;
+
+        self.cert_chains.insert(slot_id, cert_chain);
+        Ok(&self.cert_chains[&slot_id])
+    }
+
+    /// Authenticates the Responder with CHALLENGE. The certificate chain in `slot_id`
+    /// must have been retrieved first. Returns the measurement summary hash, if requested.
+    pub fn challenge(
+        &mut self,
+        slot_id: u8,
+        meas_summary_hash_type: u8,
+    ) -> SpdmRequesterResult<Option<[u8; SHA384_HASH_SIZE]>> {
+        let version = self.negotiated_version()?;
+        let asym_algo = self.selected_algorithms()?.asym_algo;
+        let cert_chain_digest = self
+            .cert_chains
+            .get(&slot_id)
+            .ok_or(SpdmRequesterError::UnexpectedState)?
+            .digest();
+        self.reset_transcripts(ReqRespCode::Challenge);
+
+        let mut nonce = [0u8; SPDM_NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let requester_context = Self::requester_context(version);
+
+        let req = encode_request(version, ReqRespCode::Challenge, |buf| {
+            ChallengeReq {
+                slot_id,
+                meas_summary_hash_type,
+                nonce,
+            }
+            .encode(buf)?;
+            if let Some(context) = requester_context.as_ref() {
+                encode_u8_slice(context, buf)?;
+            }
+            Ok(())
+        })?;
+        let rsp = self.send_request(&req, version, ReqRespCode::ChallengeAuth, None)?;
+
+        let mut reader = MessageReader::new(&rsp);
+        reader.decode::<SpdmMsgHdr>()?;
+        let auth_rsp = reader.decode::<ChallengeAuthRspCommon>()?;
+        if auth_rsp.attr & 0x0F != slot_id || auth_rsp.slot_mask & (1 << slot_id) == 0 {
+            Err(SpdmRequesterError::InvalidResponse)?;
+        }
+        if reader.read_array::<SHA384_HASH_SIZE>()? != cert_chain_digest {
+            Err(SpdmRequesterError::CertChain)?;
+        }
+        let _responder_nonce = reader.read_array::<SPDM_NONCE_LEN>()?;
+        let meas_summary_hash = if meas_summary_hash_type != 0 {
+            Some(reader.read_array::<SHA384_HASH_SIZE>()?)
+        } else {
+            None
+        };
+        let opaque_len = reader.decode::<u16>()?;
+        reader.read_bytes(opaque_len as usize)?;
+        self.check_requester_context(&mut reader, requester_context)?;
+
+        // M1 covers CHALLENGE_AUTH up to the signature
+        self.m1.extend_from_slice(&req);
+        self.m1.extend_from_slice(reader.consumed());
+        let signature = reader.read_bytes(asym_algo.signature_size())?;
+
+        let m1 = self.take_transcript(ReqRespCode::ChallengeAuth);
+        self.verify_responder_signature(
+            version,
+            slot_id,
+            ReqRespCode::ChallengeAuth,
+            &m1,
+            &signature,
+        )?;
+
+        self.authenticated = true;
+        Ok(meas_summary_hash)
+    }
+
+    /// Sends GET_MEASUREMENTS for `operation`. If `signed` is set, the response is
+    /// signed with the key in `slot_id` and the signature is verified over the L1
+    /// transcript.
+    pub fn get_measurements(
+        &mut self,
+        operation: u8,
+        signed: bool,
+        slot_id: u8,
+    ) -> SpdmRequesterResult<Measurements> {
+        let version = self.negotiated_version()?;
+        let algorithms = self.selected_algorithms()?;
+        if signed && !self.cert_chains.contains_key(&slot_id) {
+            Err(SpdmRequesterError::UnexpectedState)?;
+        }
+        self.reset_transcripts(ReqRespCode::GetMeasurements);
+
+        let mut nonce = [0u8; SPDM_NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let requester_context = Self::requester_context(version);
+
+        let req = encode_request(version, ReqRespCode::GetMeasurements, |buf| {
+            ReqParams {
+                param1: if signed {
+                    MEASUREMENT_ATTR_SIGNATURE_REQUESTED
+                } else {
+                    0
+                },
+                param2: operation,
+            }
+            .encode(buf)?;
+            if signed {
+                GetMeasurementsSignatureFields { nonce, slot_id }.encode(buf)?;
+            }
+            if let Some(context) = requester_context.as_ref() {
+                encode_u8_slice(context, buf)?;
+            }
+            Ok(())
+        })?;
+        let rsp = self.send_request(&req, version, ReqRespCode::Measurements, None)?;
+
+        let mut reader = MessageReader::new(&rsp);
+        reader.decode::<SpdmMsgHdr>()?;
+        let meas_rsp = reader.decode::<MeasurementsRspCommon>()?;
+        let record_len = u32::from_le_bytes([
+            meas_rsp.measurement_record_len[0],
+            meas_rsp.measurement_record_len[1],
+            meas_rsp.measurement_record_len[2],
+            0,
+        ]) as usize;
+        if signed && meas_rsp.param2 & 0x0F != slot_id {
+            Err(SpdmRequesterError::InvalidResponse)?;
+        }
+
+        let mut measurements = Measurements {
+            total_indices: if operation == MEASUREMENT_OPERATION_TOTAL_NUMBER {
+                meas_rsp.param1
+            } else {
+                0
+            },
+            content_changed: (meas_rsp.param2 >> 4) & 0x3,
+            blocks: Vec::new(),
+            verified: false,
+        };
+
+        let record_start = reader.offset();
+        for _ in 0..meas_rsp.num_blocks {
+            let block_hdr = reader.decode::<MeasurementBlockHdr>()?;
+            let measurement_size = block_hdr.measurement_size as usize;
+            if block_hdr.measurement_specification != 1
+                || measurement_size < size_of::<DmtfMeasurementValueHdr>()
+            {
+                Err(SpdmRequesterError::InvalidResponse)?;
+            }
+            let value_hdr = reader.decode::<DmtfMeasurementValueHdr>()?;
+            let value_size = value_hdr.value_size as usize;
+            if value_size + size_of::<DmtfMeasurementValueHdr>() != measurement_size {
+                Err(SpdmRequesterError::InvalidResponse)?;
+            }
+            let raw_bit_stream = value_hdr.value_type & MEASUREMENT_VALUE_RAW_BIT_STREAM != 0;
+            if !raw_bit_stream
+                && algorithms.measurement_hash_algo.tpm_alg_sha_384() != 0
+                && value_size != SHA384_HASH_SIZE
+            {
+                Err(SpdmRequesterError::InvalidResponse)?;
+            }
+            measurements.blocks.push(MeasurementBlock {
+                index: block_hdr.index,
+                value_type: value_hdr.value_type & !MEASUREMENT_VALUE_RAW_BIT_STREAM,
+                raw_bit_stream,
+                value: reader.read_bytes(value_size)?,
+            });
+        }
+        if reader.offset() - record_start != record_len {
+            Err(SpdmRequesterError::InvalidResponse)?;
+        }
+
+        let _responder_nonce = reader.read_array::<SPDM_NONCE_LEN>()?;
+        let opaque_len = reader.decode::<u16>()?;
+        reader.read_bytes(opaque_len as usize)?;
+        self.check_requester_context(&mut reader, requester_context)?;
+
+        // L1 covers all GET_MEASUREMENTS exchanges since the last other request, up to
+        // the signature of the final response
+        self.l1.extend_from_slice(&req);
+        self.l1.extend_from_slice(reader.consumed());
+        if signed {
+            let signature = reader.read_bytes(algorithms.asym_algo.signature_size())?;
+
+            let l1 = self.take_transcript(ReqRespCode::Measurements);
+            self.verify_responder_signature(
+                version,
+                slot_id,
+                ReqRespCode::Measurements,
+                &l1,
+                &signature,
+            )?;
+            measurements.verified = true;
+        }
+
+        Ok(measurements)
+    }
+
+    /// Starts a session with KEY_EXCHANGE using the certificate chain in `slot_id` and
+    /// returns its session ID. The session is in the handshake phase until `finish`
+    /// completes. Several sessions may be set up and used concurrently.
+    pub fn key_exchange(
+        &mut self,
+        slot_id: u8,
+        meas_summary_hash_type: u8,
+    ) -> SpdmRequesterResult<u32> {
+        let version = self.negotiated_version()?;
+        let algorithms = self.selected_algorithms()?;
+        if !algorithms.key_exchange {
+            Err(SpdmRequesterError::UnexpectedState)?;
+        }
+        let cert_chain_hash = sha384(
+            self.cert_chains
+                .get(&slot_id)
+                .ok_or(SpdmRequesterError::UnexpectedState)?
+                .raw(),
+        );
+        self.reset_transcripts(ReqRespCode::KeyExchange);
+
+        let ecdh = EcdhP384::new();
+        let mut random_data = [0u8; RANDOM_DATA_LEN];
+        rand::thread_rng().fill_bytes(&mut random_data);
+        // The Requester half of the session ID must be unique among the open sessions
+        let req_session_id = loop {
+            let req_session_id = rand::random::<u16>();
+            if !self
+                .sessions
+                .keys()
+                .any(|session_id| *session_id as u16 == req_session_id)
+            {
+                break req_session_id;
+            }
+        };
+        let opaque_data = sm_version_list_opaque_data(&self.config.secure_versions);
+
+        let req = encode_request(version, ReqRespCode::KeyExchange, |buf| {
+            KeyExchangeReqCommon {
+                meas_summary_hash_type,
+                slot_id,
+                req_session_id,
+                session_policy: 0,
+                reserved: 0,
+                random_data,
+            }
+            .encode(buf)?;
+            encode_u8_slice(&ecdh.exchange_data(), buf)?;
+            (opaque_data.len() as u16).encode(buf)?;
+            encode_u8_slice(&opaque_data, buf)?;
+            Ok(())
+        })?;
+        let rsp = self.send_request(&req, version, ReqRespCode::KeyExchangeRsp, None)?;
+
+        let mut reader = MessageReader::new(&rsp);
+        reader.decode::<SpdmMsgHdr>()?;
+        let exch_rsp = reader.decode::<KeyExchangeRspCommon>()?;
+        // Mutual authentication is only supported with the encapsulated request flow
+        let mut_auth = match exch_rsp.mut_auth_requested {
+            0 => false,
+            MUT_AUTH_REQUESTED_ENCAP_REQUEST_FLOW if self.config.identity.is_some() => true,
+            _ => Err(SpdmRequesterError::InvalidResponse)?,
+        };
+        let rsp_exchange_data = reader.read_bytes(ECDH_P384_EXCHANGE_DATA_SIZE)?;
+        if meas_summary_hash_type != 0 {
+            reader.read_array::<SHA384_HASH_SIZE>()?;
+        }
+        let rsp_opaque_len = reader.decode::<u16>()?;
+        let rsp_opaque_data = reader.read_bytes(rsp_opaque_len as usize)?;
+        let sm_version = sm_selected_version(&rsp_opaque_data)?;
+        if !self.config.secure_versions.contains(&sm_version) {
+            Err(SpdmRequesterError::NegotiationFailed)?;
+        }
+
+        // TH = VCA | Hash(cert chain) | KEY_EXCHANGE | KEY_EXCHANGE_RSP
+        let mut th = self.vca.clone();
+        th.extend_from_slice(&cert_chain_hash);
+        th.extend_from_slice(&req);
+        th.extend_from_slice(reader.consumed());
+
+        let signature = reader.read_bytes(algorithms.asym_algo.signature_size())?;
+        self.verify_responder_signature(
+            version,
+            slot_id,
+            ReqRespCode::KeyExchangeRsp,
+            &th,
+            &signature,
+        )?;
+        th.extend_from_slice(&signature);
+
+        let dhe_secret = ecdh.compute_shared_secret(&rsp_exchange_data)?;
+        let session_id = (u32::from(exch_rsp.rsp_session_id) << 16) | u32::from(req_session_id);
+        let th1_hash = sha384(&th);
+        let session = SecureSession::new(
+            session_id,
+            sm_version,
+            exch_rsp.heartbeat_period,
+            &dhe_secret,
+            &th1_hash,
+        )?;
+
+        let responder_verify_data = reader.read_array::<SHA384_HASH_SIZE>()?;
+        if session.responder_verify_data(&th1_hash)? != responder_verify_data {
+            Err(SpdmRequesterError::MacVerification)?;
+        }
+        th.extend_from_slice(&responder_verify_data);
+
+        self.sessions.insert(session_id, (session, th));
+        if mut_auth {
+            // The Responder retrieves the Requester certificate chain within the session
+            // before FINISH
+            match self.encapsulated_request_flow(version, session_id) {
+                Ok(slot_id) => {
+                    let (session, _) = self.sessions.get_mut(&session_id).unwrap();
+                    session.set_requester_slot_id(slot_id);
+                }
+                Err(e) => {
+                    self.sessions.remove(&session_id);
+                    Err(e)?
+                }
+            }
+        }
+        Ok(session_id)
+    }
+
+    /// Completes the handshake of a session with FINISH and switches to the application
+    /// data keys. FINISH is signed with the Requester identity key if the Responder
+    /// requested mutual authentication.
+    pub fn finish(&mut self, session_id: u32) -> SpdmRequesterResult<()> {
+        let version = self.negotiated_version()?;
+        self.check_session_state(session_id, SessionState::Handshake)?;
+        self.reset_transcripts(ReqRespCode::Finish);
+
+        let (session, th) = self.sessions.get_mut(&session_id).unwrap();
+        let requester_slot_id = session.requester_slot_id();
+        let finish_params = match requester_slot_id {
+            Some(slot_id) => ReqParams {
+                param1: FINISH_ATTR_SIGNATURE_INCLUDED,
+                param2: slot_id,
+            },
+            None => ReqParams::default(),
+        };
+        let mut req = encode_request(version, ReqRespCode::Finish, |buf| {
+            finish_params.encode(buf)?;
+            Ok(())
+        })?;
+
+        // TH = TH1 transcript | [Hash(Requester cert chain)] | FINISH | [Signature]
+        let identity = requester_slot_id.and(self.config.identity.as_ref());
+        if let Some(identity) = identity {
+            th.extend_from_slice(&sha384(&spdm_cert_chain(&identity.certs)?));
+        }
+        th.extend_from_slice(&req);
+        if let Some(identity) = identity {
+            let signing_context = create_responder_signing_context(version, ReqRespCode::Finish)
+                .map_err(|_| SpdmRequesterError::Crypto)?;
+            let mut tbs_message = signing_context.to_vec();
+            tbs_message.extend_from_slice(&sha384(th));
+            let signature = sign_ecc_p384(&identity.private_key, &tbs_message)?;
+            req.extend_from_slice(&signature);
+            th.extend_from_slice(&signature);
+        }
+        let requester_verify_data = session.requester_verify_data(&sha384(th))?;
+        req.extend_from_slice(&requester_verify_data);
+        th.extend_from_slice(&requester_verify_data);
+
+        let rsp = match self.send_request(&req, version, ReqRespCode::FinishRsp, Some(session_id)) {
+            Ok(rsp) => rsp,
+            Err(e) => {
+                self.sessions.remove(&session_id);
+                Err(e)?
+            }
+        };
+        let mut reader = MessageReader::new(&rsp);
+        reader.decode::<SpdmMsgHdr>()?;
+        reader.decode::<ReqParams>()?;
+
+        // TH2 = TH1 transcript | FINISH | FINISH_RSP
+        let (session, th) = self.sessions.get_mut(&session_id).unwrap();
+        th.extend_from_slice(reader.consumed());
+        let th2_hash = sha384(th);
+        session.generate_data_keys(&th2_hash)?;
+        th.clear();
+        Ok(())
+    }
+
+    /// Sends an SPDM request inside an established session and returns the response.
+    pub fn send_secured(&mut self, session_id: u32, req: &[u8]) -> SpdmRequesterResult<Vec<u8>> {
+        self.check_session_state(session_id, SessionState::Established)?;
+        self.exchange(req, Some(session_id))
+    }
+
+    /// Updates the request direction keys of a session with KEY_UPDATE, and the response
+    /// direction keys as well if `update_all_keys` is set, then verifies the new keys.
+    pub fn key_update(
+        &mut self,
+        session_id: u32,
+        update_all_keys: bool,
+    ) -> SpdmRequesterResult<()> {
+        let version = self.negotiated_version()?;
+        self.check_session_state(session_id, SessionState::Established)?;
+
+        let key_operation = if update_all_keys {
+            KEY_UPDATE_OPERATION_UPDATE_ALL_KEYS
+        } else {
+            KEY_UPDATE_OPERATION_UPDATE_KEY
+        };
+        // KEY_UPDATE_ACK is protected with the keys in use before the update
+        self.send_key_update(version, session_id, key_operation)?;
+        let (session, _) = self.sessions.get_mut(&session_id).unwrap();
+        session.update_data_keys(update_all_keys)?;
+
+        self.send_key_update(version, session_id, KEY_UPDATE_OPERATION_VERIFY_NEW_KEY)
+    }
+
+    /// Keeps a session alive with HEARTBEAT.
+    pub fn heartbeat(&mut self, session_id: u32) -> SpdmRequesterResult<()> {
+        let version = self.negotiated_version()?;
+        self.check_session_state(session_id, SessionState::Established)?;
+
+        let req = encode_request(version, ReqRespCode::Heartbeat, |buf| {
+            ReqParams::default().encode(buf)?;
+            Ok(())
+        })?;
+        self.send_request(&req, version, ReqRespCode::HeartbeatAck, Some(session_id))
+            .map(|_| ())
+    }
+
+    /// Terminates a session with END_SESSION.
+    pub fn end_session(&mut self, session_id: u32) -> SpdmRequesterResult<()> {
+        let version = self.negotiated_version()?;
+        self.check_session_state(session_id, SessionState::Established)?;
+        self.reset_transcripts(ReqRespCode::EndSession);
+
+        let req = encode_request(version, ReqRespCode::EndSession, |buf| {
+            ReqParams::default().encode(buf)?;
+            Ok(())
+        })?;
+        let result = self.send_request(&req, version, ReqRespCode::EndSessionAck, Some(session_id));
+        self.sessions.remove(&session_id);
+        result.map(|_| ())
+    }
+
+    fn send_key_update(
+        &mut self,
+        version: SpdmVersion,
+        session_id: u32,
+        key_operation: u8,
+    ) -> SpdmRequesterResult<()> {
+        let key_update = ReqParams {
+            param1: key_operation,
+            param2: rand::random::<u8>(),
+        };
+        let req = encode_request(version, ReqRespCode::KeyUpdate, |buf| {
+            key_update.encode(buf)?;
+            Ok(())
+        })?;
+        let rsp = self.send_request(&req, version, ReqRespCode::KeyUpdateAck, Some(session_id))?;
+
+        // KEY_UPDATE_ACK echoes the operation and tag of the request
+        let mut reader = MessageReader::new(&rsp);
+        reader.decode::<SpdmMsgHdr>()?;
+        let ack = reader.decode::<ReqParams>()?;
+        if ack.param1 != key_update.param1 || ack.param2 != key_update.param2 {
+            Err(SpdmRequesterError::InvalidResponse)?;
+        }
+        Ok(())
+    }
+
+    /// Runs the encapsulated request flow of session-based mutual authentication: answers
+    /// the encapsulated GET_DIGESTS and GET_CERTIFICATE requests of the Responder with the
+    /// Requester certificate chain, and returns the slot the Responder selected.
+    fn encapsulated_request_flow(
+        &mut self,
+        version: SpdmVersion,
+        session_id: u32,
+    ) -> SpdmRequesterResult<u8> {
+        self.reset_transcripts(ReqRespCode::GetEncapsulatedRequest);
+        let req = encode_request(version, ReqRespCode::GetEncapsulatedRequest, |buf| {
+            ReqParams::default().encode(buf)?;
+            Ok(())
+        })?;
+        let rsp = self.send_request(
+            &req,
+            version,
+            ReqRespCode::EncapsulatedRequest,
+            Some(session_id),
+        )?;
+        let mut reader = MessageReader::new(&rsp);
+        reader.decode::<SpdmMsgHdr>()?;
+        let mut request_id = reader.decode::<ReqParams>()?.param1;
+        let mut encap_req = rsp[reader.offset()..].to_vec();
+
+        loop {
+            let encap_rsp = self.encapsulated_response(version, &encap_req)?;
+            self.reset_transcripts(ReqRespCode::DeliverEncapsulatedResponse);
+            let req = encode_request(version, ReqRespCode::DeliverEncapsulatedResponse, |buf| {
+                ReqParams {
+                    param1: request_id,
+                    param2: 0,
+                }
+                .encode(buf)?;
+                encode_u8_slice(&encap_rsp, buf)?;
+                Ok(())
+            })?;
+            let rsp = self.send_request(
+                &req,
+                version,
+                ReqRespCode::EncapsulatedResponseAck,
+                Some(session_id),
+            )?;
+
+            // ENCAPSULATED_RESPONSE_ACK acknowledges the delivered response (SPDM 1.2 and
+            // later) and carries the next encapsulated request or the selected slot
+            let mut reader = MessageReader::new(&rsp);
+            reader.decode::<SpdmMsgHdr>()?;
+            let ack = reader.decode::<ReqParams>()?;
+            let ack_request_id = reader.decode::<u8>()?;
+            reader.read_bytes(3)?;
+            if ack_request_id != request_id {
+                Err(SpdmRequesterError::InvalidResponse)?;
+            }
+            match ack.param2 {
+                ENCAP_PAYLOAD_TYPE_PRESENT => {
+                    request_id = ack.param1;
+                    encap_req = rsp[reader.offset()..].to_vec();
+                }
+                ENCAP_PAYLOAD_TYPE_REQ_SLOT_NUMBER => {
+                    let slot_id = reader.decode::<u8>()?;
+                    if slot_id != REQUESTER_CERT_SLOT_ID {
+                        Err(SpdmRequesterError::InvalidResponse)?;
+                    }
+                    return Ok(slot_id);
+                }
+                _ => Err(SpdmRequesterError::InvalidResponse)?,
+            }
+        }
+    }
+
+    /// Generates the response to an encapsulated request of the Responder.
+    fn encapsulated_response(
+        &self,
+        version: SpdmVersion,
+        encap_req: &[u8],
+    ) -> SpdmRequesterResult<Vec<u8>> {
+        let identity = self
+            .config
+            .identity
+            .as_ref()
+            .ok_or(SpdmRequesterError::UnexpectedState)?;
+        let cert_chain = spdm_cert_chain(&identity.certs)?;
+
+        let mut reader = MessageReader::new(encap_req);
+        let hdr = reader.decode::<SpdmMsgHdr>()?;
+        if hdr.version().ok() != Some(version) {
+            Err(SpdmRequesterError::InvalidResponse)?;
+        }
+        match hdr.req_resp_code() {
+            Ok(ReqRespCode::GetDigests) => {
+                let slot_mask = 1 << REQUESTER_CERT_SLOT_ID;
+                encode_request(version, ReqRespCode::Digests, |buf| {
+                    // SupportedSlotMask was introduced in SPDM 1.3
+                    ReqParams {
+                        param1: if version >= SpdmVersion::V13 {
+                            slot_mask
+                        } else {
+                            0
+                        },
+                        param2: slot_mask,
+                    }
+                    .encode(buf)?;
+                    encode_u8_slice(&sha384(&cert_chain), buf)?;
+                    Ok(())
+                })
+            }
+            Ok(ReqRespCode::GetCertificate) => {
+                let get_cert = reader.decode::<GetCertificateReq>()?;
+                let slot_id = get_cert.slot_id.slot_id();
+                let offset = get_cert.offset as usize;
+                if slot_id != REQUESTER_CERT_SLOT_ID || offset > cert_chain.len() {
+                    Err(SpdmRequesterError::InvalidResponse)?;
+                }
+                let portion_len = (get_cert.length as usize).min(cert_chain.len() - offset);
+                encode_request(version, ReqRespCode::Certificate, |buf| {
+                    CertificateRspCommon {
+                        param1: slot_id,
+                        param2: 0,
+                        portion_length: portion_len as u16,
+                        remainder_length: (cert_chain.len() - offset - portion_len) as u16,
+                    }
+                    .encode(buf)?;
+                    encode_u8_slice(&cert_chain[offset..offset + portion_len], buf)?;
+                    Ok(())
+                })
+            }
+            _ => Err(SpdmRequesterError::InvalidResponse),
+        }
+    }
+
+    fn check_session_state(&self, session_id: u32, state: SessionState) -> SpdmRequesterResult<()> {
+        match self.session(session_id).map(|session| session.state()) {
+            Some(session_state) if session_state == state => Ok(()),
+            _ => Err(SpdmRequesterError::UnexpectedState),
+        }
+    }
+
+    fn reset_connection(&mut self) {
+        self.version = None;
+        self.peer_capabilities = None;
+        self.algorithms = None;
+        self.vca.clear();
+        self.m1.clear();
+        self.l1.clear();
+        self.authenticated = false;
+        self.digests.clear();
+        self.cert_chains.clear();
+        self.sessions.clear();
+    }
+
+    /// Mirrors the Responder transcript resets for the request about to be sent.
+    fn reset_transcripts(&mut self, req_code: ReqRespCode) {
+        if req_code != ReqRespCode::GetMeasurements {
+            self.l1.clear();
+        }
+        match req_code {
+            ReqRespCode::GetMeasurements
+            | ReqRespCode::KeyExchange
+            | ReqRespCode::Finish
+            | ReqRespCode::EndSession
+                if !self.authenticated =>
+            {
+                self.m1.clear()
+            }
+            ReqRespCode::GetDigests => self.m1.clear(),
+            _ => {}
+        }
+    }
+
+    /// Returns the VCA-prefixed transcript signed in the given response and resets it.
+    fn take_transcript(&mut self, rsp_code: ReqRespCode) -> Vec<u8> {
+        let messages = match rsp_code {
+            ReqRespCode::ChallengeAuth => &mut self.m1,
+            _ => &mut self.l1,
+        };
+        let mut transcript = self.vca.clone();
+        transcript.append(messages);
+        transcript
+    }
+
+    fn verify_responder_signature(
+        &self,
+        version: SpdmVersion,
+        slot_id: u8,
+        rsp_code: ReqRespCode,
+        transcript: &[u8],
+        signature: &[u8],
+    ) -> SpdmRequesterResult<()> {
+        let asym_algo = self.selected_algorithms()?.asym_algo;
+        let public_key = self
+            .cert_chains
+            .get(&slot_id)
+            .ok_or(SpdmRequesterError::UnexpectedState)?
+            .leaf_public_key()?;
+
+        let signing_context = create_responder_signing_context(version, rsp_code)
+            .map_err(|_| SpdmRequesterError::Crypto)?;
+        let mut tbs_message = signing_context.to_vec();
+        tbs_message.extend_from_slice(&sha384(transcript));
+
+        verify_signature(asym_algo, &public_key, &tbs_message, signature)
+    }
+
+    fn requester_context(version: SpdmVersion) -> Option<[u8; REQUESTER_CONTEXT_LEN]> {
+        if version >= SpdmVersion::V13 {
+            let mut context = [0u8; REQUESTER_CONTEXT_LEN];
+            rand::thread_rng().fill_bytes(&mut context);
+            Some(context)
+        } else {
+            None
+        }
+    }
+
+    fn check_requester_context(
+        &self,
+        reader: &mut MessageReader,
+        requester_context: Option<[u8; REQUESTER_CONTEXT_LEN]>,
+    ) -> SpdmRequesterResult<()> {
+        if let Some(context) = requester_context {
+            if reader.read_array::<REQUESTER_CONTEXT_LEN>()? != context {
+                Err(SpdmRequesterError::InvalidResponse)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn connection_version(&self) -> SpdmRequesterResult<SpdmVersion> {
+        self.version.ok_or(SpdmRequesterError::UnexpectedState)
+    }
+
+    fn negotiated_version(&self) -> SpdmRequesterResult<SpdmVersion> {
+        self.selected_algorithms()?;
+        self.connection_version()
+    }
+
+    fn selected_algorithms(&self) -> SpdmRequesterResult<SelectedAlgorithms> {
+        self.algorithms.ok_or(SpdmRequesterError::UnexpectedState)
+    }
+
+    /// Sends a request, within the session `session_id` if set, reassembles a large
+    /// response with CHUNK_GET if needed, and checks the response header.
+    fn send_request(
+        &mut self,
+        req: &[u8],
+        version: SpdmVersion,
+        rsp_code: ReqRespCode,
+        session_id: Option<u32>,
+    ) -> SpdmRequesterResult<Vec<u8>> {
+        let mut rsp = self.exchange(req, session_id)?;
+
+        if let Err(SpdmRequesterError::ErrorResponse { code, .. }) =
+            check_response(&rsp, version, rsp_code)
+        {
+            if code == ErrorCode::LargeResponse as u8 {
+                let handle = *rsp.get(4).ok_or(SpdmRequesterError::InvalidResponse)?;
+                rsp = self.get_large_response(version, handle, session_id)?;
+            }
+        }
+
+        check_response(&rsp, version, rsp_code)?;
+        Ok(rsp)
+    }
+
+    fn get_large_response(
+        &mut self,
+        version: SpdmVersion,
+        handle: u8,
+        session_id: Option<u32>,
+    ) -> SpdmRequesterResult<Vec<u8>> {
+        let mut large_rsp = Vec::new();
+        let mut large_rsp_size = 0;
+        for chunk_seq_num in 0..=u16::MAX {
+            let req = encode_request(version, ReqRespCode::ChunkGet, |buf| {
+                ChunkGetReq {
+                    param1: 0,
+                    handle,
+                    chunk_seq_num,
+                }
+                .encode(buf)?;
+                Ok(())
+            })?;
+            let rsp = self.exchange(&req, session_id)?;
+            check_response(&rsp, version, ReqRespCode::ChunkResponse)?;
+
+            let mut reader = MessageReader::new(&rsp);
+            reader.decode::<SpdmMsgHdr>()?;
+            let chunk_rsp = reader.decode::<ChunkResponseCommon>()?;
+            let chunk_seq = chunk_rsp.chunk_seq_num;
+            if chunk_rsp.handle != handle || chunk_seq != chunk_seq_num {
+                Err(SpdmRequesterError::InvalidResponse)?;
+            }
+            if chunk_seq_num == 0 {
+                large_rsp_size = reader.decode::<u32>()? as usize;
+                if large_rsp_size > self.config.capabilities.max_spdm_msg_size as usize {
+                    Err(SpdmRequesterError::InvalidResponse)?;
+                }
+            }
+            large_rsp.extend_from_slice(&reader.read_bytes(chunk_rsp.chunk_size as usize)?);
+
+            if large_rsp.len() > large_rsp_size {
+                Err(SpdmRequesterError::InvalidResponse)?;
+            }
+            if chunk_rsp.chunk_sender_attr & CHUNK_ATTR_LAST_CHUNK != 0 {
+                break;
+            }
+        }
+
+        if large_rsp.len() != large_rsp_size {
+            Err(SpdmRequesterError::InvalidResponse)?;
+        }
+        Ok(large_rsp)
+    }
+
+    /// Sends a single message, encrypting it within the session `session_id` if set.
+    fn exchange(&mut self, req: &[u8], session_id: Option<u32>) -> SpdmRequesterResult<Vec<u8>> {
+        let Some(session_id) = session_id else {
+            return self.transport.send_receive(req, false);
+        };
+
+        let (session, _) = self
+            .sessions
+            .get_mut(&session_id)
+            .ok_or(SpdmRequesterError::UnexpectedState)?;
+        let secure_req = session.encode_secure_message(req)?;
+        let secure_rsp = self.transport.send_receive(&secure_req, true)?;
+        session.decode_secure_message(&secure_rsp)
+    }
+}
+
+/// Checks the response header. ERROR responses are returned as `ErrorResponse`.
+fn check_response(
+    rsp: &[u8],
+    version: SpdmVersion,
+    rsp_code: ReqRespCode,
+) -> SpdmRequesterResult<()> {
+    let mut reader = MessageReader::new(rsp);
+    let hdr = reader.decode::<SpdmMsgHdr>()?;
+    if hdr.version().ok() != Some(version) {
+        Err(SpdmRequesterError::InvalidResponse)?;
+    }
+    match hdr.req_resp_code() {
+        Ok(ReqRespCode::Error) => {
+            let params = reader.decode::<ReqParams>()?;
+            Err(SpdmRequesterError::ErrorResponse {
+                code: params.param1,
+                data: params.param2,
+            })
+        }
+        Ok(code) if code == rsp_code => Ok(()),
+        _ => Err(SpdmRequesterError::InvalidResponse),
+    }
+}
+
+/// Little-endian value of an algorithm bitfield.
+fn bits<B: IntoBytes + Immutable>(bitfield: &B) -> u32 {
+    bitfield
+        .as_bytes()
+        .iter()
+        .rev()
+        .fold(0, |acc, byte| (acc << 8) | u32::from(*byte))
+}
@@ -0,0 +1,441 @@
+// Licensed under the Apache-2.0 license
+
+//! Requester side of an SPDM secure session: the DSP0274 key schedule and the DSP0277
+//! secured message format used by the Caliptra MCU Responder.
+
+use crate::crypto::{hkdf_expand, hkdf_extract, hmac_sha384, SHA384_HASH_SIZE};
+use crate::error::{SpdmRequesterError, SpdmRequesterResult};
+use aes_gcm::aead::{AeadInPlace, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce, Tag};
+use caliptra_mcu_spdm_lib::protocol::SpdmVersion;
+
+const AEAD_KEY_SIZE: usize = 32;
+const AEAD_IV_SIZE: usize = 12;
+const AEAD_TAG_SIZE: usize = 16;
+const SESSION_ID_SIZE: usize = 4;
+const LENGTH_SIZE: usize = 2;
+const APP_DATA_LENGTH_SIZE: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SessionState {
+    /// KEY_EXCHANGE_RSP was received, messages are protected with the handshake keys.
+    Handshake,
+    /// FINISH_RSP was received, messages are protected with the application data keys.
+    Established,
+}
+
+#[derive(Clone, Copy)]
+enum Direction {
+    Request,
+    Response,
+}
+
+struct DirectionKeys {
+    secret: [u8; SHA384_HASH_SIZE],
+    key: [u8; AEAD_KEY_SIZE],
+    iv: [u8; AEAD_IV_SIZE],
+    sequence_num: u64,
+}
+
+impl DirectionKeys {
+    fn new(secret: &[u8], version: SpdmVersion) -> SpdmRequesterResult<Self> {
+        let mut key = [0u8; AEAD_KEY_SIZE];
+        let mut iv = [0u8; AEAD_IV_SIZE];
+        hkdf_expand(
+            secret,
+            &bin_concat(version, AEAD_KEY_SIZE, "key", None),
+            &mut key,
+        )?;
+        hkdf_expand(
+            secret,
+            &bin_concat(version, AEAD_IV_SIZE, "iv", None),
+            &mut iv,
+        )?;
+        Ok(Self {
+            secret: secret.try_into().map_err(|_| SpdmRequesterError::Crypto)?,
+            key,
+            iv,
+            sequence_num: 0,
+        })
+    }
+
+    /// Updated-Data-Secret = HKDF-Expand(Current-Data-Secret, bin_str9, Hash.Length).
+    /// The sequence number restarts from zero with the updated keys.
+    fn update(&mut self, version: SpdmVersion) -> SpdmRequesterResult<()> {
+        let mut secret = [0u8; SHA384_HASH_SIZE];
+        hkdf_expand(
+            &self.secret,
+            &bin_concat(version, SHA384_HASH_SIZE, "traffic upd", None),
+            &mut secret,
+        )?;
+        *self = Self::new(&secret, version)?;
+        Ok(())
+    }
+
+    /// Per-message nonce: the sequence number in little-endian order is XORed into the
+    /// leading bytes of the IV, as done by the Caliptra cryptographic mailbox.
+    fn next_nonce(&mut self) -> [u8; AEAD_IV_SIZE] {
+        let mut nonce = self.iv;
+        for (n, s) in nonce.iter_mut().zip(self.sequence_num.to_le_bytes()) {
+            *n ^= s;
+        }
+        self.sequence_num += 1;
+        nonce
+    }
+}
+
+/// A session established with KEY_EXCHANGE and FINISH.
+pub struct SecureSession {
+    session_id: u32,
+    version: SpdmVersion,
+    state: SessionState,
+    heartbeat_period: u8,
+    requester_slot_id: Option<u8>,
+    handshake_secret: [u8; SHA384_HASH_SIZE],
+    request_finished_key: [u8; SHA384_HASH_SIZE],
+    response_finished_key: [u8; SHA384_HASH_SIZE],
+    request_keys: DirectionKeys,
+    response_keys: DirectionKeys,
+}
+
+impl SecureSession {
+    /// Derives the handshake secrets from the DHE secret and the TH1 transcript hash.
+    pub fn new(
+        session_id: u32,
+        version: SpdmVersion,
+        heartbeat_period: u8,
+        dhe_secret: &[u8],
+        th1_hash: &[u8; SHA384_HASH_SIZE],
+    ) -> SpdmRequesterResult<Self> {
+        // Handshake-Secret = HKDF-Extract(Salt_0, DHE-Secret)
+        let handshake_secret = hkdf_extract(&[0u8; SHA384_HASH_SIZE], dhe_secret)?;
+
+        let request_secret = expand_secret(&handshake_secret, version, "req hs data", th1_hash)?;
+        let response_secret = expand_secret(&handshake_secret, version, "rsp hs data", th1_hash)?;
+
+        Ok(Self {
+            session_id,
+            version,
+            state: SessionState::Handshake,
+            heartbeat_period,
+            requester_slot_id: None,
+            handshake_secret,
+            request_finished_key: finished_key(&request_secret, version)?,
+            response_finished_key: finished_key(&response_secret, version)?,
+            request_keys: DirectionKeys::new(&request_secret, version)?,
+            response_keys: DirectionKeys::new(&response_secret, version)?,
+        })
+    }
+
+    pub fn session_id(&self) -> u32 {
+        self.session_id
+    }
+
+    pub fn state(&self) -> SessionState {
+        self.state
+    }
+
+    /// Heartbeat period in seconds granted in KEY_EXCHANGE_RSP, 0 if disabled.
+    pub fn heartbeat_period(&self) -> u8 {
+        self.heartbeat_period
+    }
+
+    /// Slot of the Requester certificate chain selected by the Responder for mutual
+    /// authentication, `None` if mutual authentication was not requested.
+    pub fn requester_slot_id(&self) -> Option<u8> {
+        self.requester_slot_id
+    }
+
+    pub(crate) fn set_requester_slot_id(&mut self, slot_id: u8) {
+        self.requester_slot_id = Some(slot_id);
+    }
+
+    /// RequesterVerifyData for the FINISH request.
+    pub fn requester_verify_data(
+        &self,
+        th_hash: &[u8; SHA384_HASH_SIZE],
+    ) -> SpdmRequesterResult<[u8; SHA384_HASH_SIZE]> {
+        hmac_sha384(&self.request_finished_key, th_hash)
+    }
+
+    /// Expected ResponderVerifyData of KEY_EXCHANGE_RSP or FINISH_RSP.
+    pub fn responder_verify_data(
+        &self,
+        th_hash: &[u8; SHA384_HASH_SIZE],
+    ) -> SpdmRequesterResult<[u8; SHA384_HASH_SIZE]> {
+        hmac_sha384(&self.response_finished_key, th_hash)
+    }
+
+    /// Switches to the application data keys derived from the TH2 transcript hash.
+    pub fn generate_data_keys(
+        &mut self,
+        th2_hash: &[u8; SHA384_HASH_SIZE],
+    ) -> SpdmRequesterResult<()> {
+        // Salt_1 = HKDF-Expand(Handshake-Secret, bin_str0, Hash.Length)
+        let mut salt_1 = [0u8; SHA384_HASH_SIZE];
+        hkdf_expand(
+            &self.handshake_secret,
+            &bin_concat(self.version, SHA384_HASH_SIZE, "derived", None),
+            &mut salt_1,
+        )?;
+
+        // Master-Secret = HKDF-Extract(Salt_1, 0_filled)
+        let master_secret = hkdf_extract(&salt_1, &[0u8; SHA384_HASH_SIZE])?;
+
+        let request_secret = expand_secret(&master_secret, self.version, "req app data", th2_hash)?;
+        let response_secret =
+            expand_secret(&master_secret, self.version, "rsp app data", th2_hash)?;
+        self.request_keys = DirectionKeys::new(&request_secret, self.version)?;
+        self.response_keys = DirectionKeys::new(&response_secret, self.version)?;
+        self.state = SessionState::Established;
+        Ok(())
+    }
+
+    /// Switches the request direction, and the response direction if `update_response_key`
+    /// is set, to the keys updated with KEY_UPDATE.
+    pub fn update_data_keys(&mut self, update_response_key: bool) -> SpdmRequesterResult<()> {
+        if self.state != SessionState::Established {
+            Err(SpdmRequesterError::UnexpectedState)?;
+        }
+        self.request_keys.update(self.version)?;
+        if update_response_key {
+            self.response_keys.update(self.version)?;
+        }
+        Ok(())
+    }
+
+    /// Wraps an SPDM request into a secured message.
+    pub fn encode_secure_message(&mut self, app_data: &[u8]) -> SpdmRequesterResult<Vec<u8>> {
+        seal(self.session_id, self.keys(Direction::Request), app_data)
+    }
+
+    /// Authenticates and decrypts a secured message from the Responder.
+    pub fn decode_secure_message(&mut self, secure_msg: &[u8]) -> SpdmRequesterResult<Vec<u8>> {
+        let session_id = self.session_id;
+        open(session_id, self.keys(Direction::Response), secure_msg)
+    }
+
+    fn keys(&mut self, direction: Direction) -> &mut DirectionKeys {
+        match direction {
+            Direction::Request => &mut self.request_keys,
+            Direction::Response => &mut self.response_keys,
+        }
+    }
+}
+
+/// Secured message layout: SessionID (4) | Length (2) | AEAD(AppDataLength (2) | AppData) | MAC (16).
+/// The transports used with the MCU Responder carry no sequence number and no random padding.
+fn seal(
+    session_id: u32,
+    keys: &mut DirectionKeys,
+    app_data: &[u8],
+) -> SpdmRequesterResult<Vec<u8>> {
+    let length = APP_DATA_LENGTH_SIZE + app_data.len() + AEAD_TAG_SIZE;
+    let length = u16::try_from(length).map_err(|_| SpdmRequesterError::Crypto)?;
+
+    let mut msg = Vec::with_capacity(SESSION_ID_SIZE + LENGTH_SIZE + length as usize);
+    msg.extend_from_slice(&session_id.to_le_bytes());
+    msg.extend_from_slice(&length.to_le_bytes());
+    let aad_len = msg.len();
+
+    let mut payload = Vec::with_capacity(APP_DATA_LENGTH_SIZE + app_data.len());
+    payload.extend_from_slice(&(app_data.len() as u16).to_le_bytes());
+    payload.extend_from_slice(app_data);
+
+    let nonce = keys.next_nonce();
+    let tag = Aes256Gcm::new_from_slice(&keys.key)
+        .map_err(|_| SpdmRequesterError::Crypto)?
+        .encrypt_in_place_detached(Nonce::from_slice(&nonce), &msg[..aad_len], &mut payload)
+        .map_err(|_| SpdmRequesterError::Crypto)?;
+
+    msg.extend_from_slice(&payload);
+    msg.extend_from_slice(&tag);
+    Ok(msg)
+}
+
+fn open(
+    session_id: u32,
+    keys: &mut DirectionKeys,
+    secure_msg: &[u8],
+) -> SpdmRequesterResult<Vec<u8>> {
+    let hdr_len = SESSION_ID_SIZE + LENGTH_SIZE;
+    if secure_msg.len() < hdr_len {
+        Err(SpdmRequesterError::InvalidResponse)?;
+    }
+    let rsp_session_id = u32::from_le_bytes(secure_msg[..SESSION_ID_SIZE].try_into().unwrap());
+    if rsp_session_id != session_id {
+        Err(SpdmRequesterError::InvalidResponse)?;
+    }
+    let length = u16::from_le_bytes([secure_msg[4], secure_msg[5]]) as usize;
+    // The transport may pad the message beyond Length for alignment
+    if length < APP_DATA_LENGTH_SIZE + AEAD_TAG_SIZE || secure_msg.len() < hdr_len + length {
+        Err(SpdmRequesterError::InvalidResponse)?;
+    }
+
+    let aad = &secure_msg[..hdr_len];
+    let ciphertext_end = hdr_len + length - AEAD_TAG_SIZE;
+    let mut payload = secure_msg[hdr_len..ciphertext_end].to_vec();
+    let tag = Tag::from_slice(&secure_msg[ciphertext_end..hdr_len + length]);
+
+    let nonce = keys.next_nonce();
+    Aes256Gcm::new_from_slice(&keys.key)
+        .map_err(|_| SpdmRequesterError::Crypto)?
+        .decrypt_in_place_detached(Nonce::from_slice(&nonce), aad, &mut payload, tag)
+        .map_err(|_| SpdmRequesterError::MacVerification)?;
+
+    let app_data_len = u16::from_le_bytes([payload[0], payload[1]]) as usize;
+    if APP_DATA_LENGTH_SIZE + app_data_len > payload.len() {
+        Err(SpdmRequesterError::InvalidResponse)?;
+    }
+    Ok(payload[APP_DATA_LENGTH_SIZE..APP_DATA_LENGTH_SIZE + app_data_len].to_vec())
+}
+
+fn expand_secret(
+    secret: &[u8],
+    version: SpdmVersion,
+    label: &str,
+    th_hash: &[u8; SHA384_HASH_SIZE],
+) -> SpdmRequesterResult<[u8; SHA384_HASH_SIZE]> {
+    let mut okm = [0u8; SHA384_HASH_SIZE];
+    hkdf_expand(
+        secret,
+        &bin_concat(version, SHA384_HASH_SIZE, label, Some(th_hash)),
+        &mut okm,
+    )?;
+    Ok(okm)
+}
+
+fn finished_key(
+    secret: &[u8],
+    version: SpdmVersion,
+) -> SpdmRequesterResult<[u8; SHA384_HASH_SIZE]> {
+    let mut okm = [0u8; SHA384_HASH_SIZE];
+    hkdf_expand(
+        secret,
+        &bin_concat(version, SHA384_HASH_SIZE, "finished", None),
+        &mut okm,
+    )?;
+    Ok(okm)
+}
+
+// BinConcat(Length, Version, Label, Context)
+fn bin_concat(version: SpdmVersion, length: usize, label: &str, context: Option<&[u8]>) -> Vec<u8> {
+    let mut bin_str = Vec::new();
+    bin_str.extend_from_slice(&(length as u16).to_le_bytes());
+    bin_str.extend_from_slice(format!("spdm{}.{} ", version.major(), version.minor()).as_bytes());
+    bin_str.extend_from_slice(label.as_bytes());
+    if let Some(context) = context {
+        bin_str.extend_from_slice(context);
+    }
+    bin_str
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn session_pair() -> (SecureSession, SecureSession) {
+        let th1_hash = [0x5A; SHA384_HASH_SIZE];
+        let dhe_secret = [0xA5; SHA384_HASH_SIZE];
+        let requester =
+            SecureSession::new(0x0001_FFFE, SpdmVersion::V12, 0, &dhe_secret, &th1_hash).unwrap();
+        let mut responder =
+            SecureSession::new(0x0001_FFFE, SpdmVersion::V12, 0, &dhe_secret, &th1_hash).unwrap();
+        // The responder view seals with the response keys and opens with the request keys
+        core::mem::swap(&mut responder.request_keys, &mut responder.response_keys);
+        (requester, responder)
+    }
+
+    #[test]
+    fn test_bin_concat() {
+        let bin_str = bin_concat(SpdmVersion::V12, SHA384_HASH_SIZE, "derived", None);
+        assert_eq!(&bin_str[..2], &[48, 0]);
+        assert_eq!(&bin_str[2..], b"spdm1.2 derived");
+    }
+
+    #[test]
+    fn test_secure_message_round_trip() {
+        let (mut requester, mut responder) = session_pair();
+        let request = [0x12, 0xE5, 0x00, 0x00];
+
+        let secure_msg = requester.encode_secure_message(&request).unwrap();
+        assert_eq!(&secure_msg[..4], &0x0001_FFFEu32.to_le_bytes());
+        assert_eq!(
+            u16::from_le_bytes([secure_msg[4], secure_msg[5]]) as usize,
+            2 + request.len() + AEAD_TAG_SIZE
+        );
+        assert_eq!(
+            responder.decode_secure_message(&secure_msg).unwrap(),
+            request
+        );
+
+        // Sequence numbers advance per message, so a replayed message does not authenticate
+        assert_eq!(
+            responder.decode_secure_message(&secure_msg),
+            Err(SpdmRequesterError::MacVerification)
+        );
+
+        let response = [0x12, 0x65, 0x00, 0x00];
+        let secure_rsp = responder.encode_secure_message(&response).unwrap();
+        assert_eq!(
+            requester.decode_secure_message(&secure_rsp).unwrap(),
+            response
+        );
+    }
+
+    #[test]
+    fn test_data_keys() {
+        let (mut requester, _) = session_pair();
+        let handshake_msg = requester
+            .encode_secure_message(&[0x12, 0xE8, 0, 0])
+            .unwrap();
+
+        requester
+            .generate_data_keys(&[0x33; SHA384_HASH_SIZE])
+            .unwrap();
+        assert_eq!(requester.state(), SessionState::Established);
+        let data_msg = requester
+            .encode_secure_message(&[0x12, 0xE8, 0, 0])
+            .unwrap();
+        assert_ne!(handshake_msg, data_msg);
+    }
+
+    #[test]
+    fn test_update_data_keys() {
+        let (mut requester, mut responder) = session_pair();
+        assert_eq!(
+            requester.update_data_keys(false),
+            Err(SpdmRequesterError::UnexpectedState)
+        );
+        let th2_hash = [0x33; SHA384_HASH_SIZE];
+        requester.generate_data_keys(&th2_hash).unwrap();
+        responder.generate_data_keys(&th2_hash).unwrap();
+        core::mem::swap(&mut responder.request_keys, &mut responder.response_keys);
+
+        let request = [0x12, 0xE8, 0x00, 0x00];
+        let secure_msg = requester.encode_secure_message(&request).unwrap();
+        responder.decode_secure_message(&secure_msg).unwrap();
+
+        // The updated request key restarts from sequence number zero
+        requester.update_data_keys(false).unwrap();
+        assert_eq!(requester.request_keys.sequence_num, 0);
+        let secure_msg = requester.encode_secure_message(&request).unwrap();
+        assert_eq!(
+            responder.decode_secure_message(&secure_msg),
+            Err(SpdmRequesterError::MacVerification)
+        );
+        responder.response_keys.update(SpdmVersion::V12).unwrap();
+        assert_eq!(
+            responder.decode_secure_message(&secure_msg).unwrap(),
+            request
+        );
+
+        // The response key is only updated with UpdateAllKeys
+        let response = [0x12, 0x68, 0x00, 0x00];
+        let secure_rsp = responder.encode_secure_message(&response).unwrap();
+        assert_eq!(
+            requester.decode_secure_message(&secure_rsp).unwrap(),
+            response
+        );
+    }
+}
@@ -0,0 +1,23 @@
+// Licensed under the Apache-2.0 license
+
+use crate::error::SpdmRequesterResult;
+
+/// Transport binding used by the requester to reach an SPDM Responder.
+///
+/// Implementations add and strip their own transport headers, e.g. the MCTP message
+/// type byte or the DOE data object header. The requester only ever sees SPDM messages
+/// or secured messages.
+pub trait SpdmTransport {
+    /// Sends a message to the Responder and waits for its response.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The SPDM message, or the secured message if `secure` is set.
+    /// * `secure` - Whether the message is an SPDM secured message (DSP0277) and must be
+    ///   carried with the secured message binding of the transport.
+    ///
+    /// # Returns
+    ///
+    /// * `SpdmRequesterResult<Vec<u8>>` - The response without transport headers.
+    fn send_receive(&mut self, msg: &[u8], secure: bool) -> SpdmRequesterResult<Vec<u8>>;
+}
@@ -38,6 +38,23 @@ impl Bmc {
             .push(image);
     }
 
+    /// Name of the current recovery state, saved in emulator snapshots.
+    pub fn recovery_state(&self) -> String {
+        format!("{:?}", self.recovery_state_machine.state())
+    }
+
+    /// Resume the recovery flow from a state returned by
+    /// [`Bmc::recovery_state`].
+    pub fn restore_recovery_state(&mut self, name: &str) -> Result<(), String> {
+        let state = recovery::state_from_name(name)
+            .ok_or_else(|| format!("unknown BMC recovery state {name:?}"))?;
+        let mut context = recovery::Context::new(self.events_to_caliptra.clone());
+        context.recovery_images =
+            std::mem::take(&mut self.recovery_state_machine.context_mut().recovery_images);
+        self.recovery_state_machine = recovery::StateMachine::new_with_state(context, state);
+        Ok(())
+    }
+
     /// Called once every clock cycle by the emulator so the BMC can do work
     pub fn step(&mut self) {
         let prev_state = *self.recovery_state_machine.state();
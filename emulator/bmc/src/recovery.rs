@@ -66,6 +66,20 @@ pub(crate) fn state_to_read_request(state: States) -> Option<Event> {
     })
 }
 
+/// Inverse of the `Debug` names of [`States`], used when restoring snapshots.
+pub(crate) fn state_from_name(name: &str) -> Option<States> {
+    [
+        States::ReadProtCap,
+        States::ReadDeviceStatus,
+        States::WaitForRecoveryStatus,
+        States::WaitForRecoveryPending,
+        States::Activate,
+        States::Done,
+    ]
+    .into_iter()
+    .find(|state| format!("{state:?}") == name)
+}
+
 register_bitfields! [
     u32,
     pub ProtCap2 [
@@ -261,4 +275,12 @@ mod tests {
         assert!(sm.process_event(Events::ProtCap(0u32.into())).is_ok());
         assert_eq!(*sm.state(), States::Done);
     }
+
+    #[test]
+    fn test_state_from_name() {
+        for state in [States::ReadProtCap, States::Activate, States::Done] {
+            assert_eq!(state_from_name(&format!("{state:?}")), Some(state));
+        }
+        assert!(state_from_name("Unknown").is_none());
+    }
 }
@@ -317,6 +317,7 @@ pub unsafe extern "C" fn emulator_init(
         } else {
             Some(config.i3c_port as u16)
         },
+        pcie_vdm_port: None,
         device_security_state: DeviceLifecycle::try_from(config.device_security_state)
             .unwrap_or(DeviceLifecycle::Production) as u32,
         test_feature: None,
@@ -378,6 +379,14 @@ pub unsafe extern "C" fn emulator_init(
         fuse_vendor_test_partition: convert_optional_c_string(config.fuse_vendor_test_partition),
         stub_warnings: config.stub_warnings != 0,
         active_i3c1: config.active_i3c1 != 0,
+        save_snapshot: None,
+        load_snapshot: None,
+        fault_plan: None,
+        record_io: None,
+        replay_io: None,
+        profile_dir: None,
+        profile_elf: vec![],
+        caliptra_profile_elf: vec![],
     };
 
     // Convert C callbacks to Rust callbacks if provided
@@ -48,6 +48,7 @@ fn test_emulator_args_creation() {
         allow_sideloaded_rom: false,
         flash_based_boot: false,
         i3c_port: None,
+        pcie_vdm_port: None,
         device_security_state: DeviceLifecycle::Production as u32,
         test_feature: None,
         vendor_pk_hash: None,
@@ -94,6 +95,14 @@ fn test_emulator_args_creation() {
         fuse_vendor_test_partition: None,
         stub_warnings: false,
         active_i3c1: false,
+        save_snapshot: None,
+        load_snapshot: None,
+        fault_plan: None,
+        record_io: None,
+        replay_io: None,
+        profile_dir: None,
+        profile_elf: vec![],
+        caliptra_profile_elf: vec![],
     };
 
     println!("EmulatorArgs created successfully");
@@ -3,6 +3,7 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
+use crate::snapshot::{decode_state, encode_state};
 use crate::McuMailbox0Internal;
 use caliptra_emu_bus::{ActionHandle, Clock, Ram, ReadWriteRegister, Timer};
 use caliptra_emu_cpu::Irq;
@@ -11,6 +12,7 @@ use caliptra_mcu_emulator_registers_generated::axicdma::{AxicdmaGenerated, Axicd
 use caliptra_mcu_registers_generated::axicdma::bits::{
     AxicdmaBytesToTransfer, AxicdmaControl, AxicdmaStatus,
 };
+use serde::{Deserialize, Serialize};
 use tock_registers::interfaces::{ReadWriteable, Readable, Writeable};
 
 pub enum DmaCtrlIntType {
@@ -48,6 +50,19 @@ const MCU_MBOX0_SRAM_END_ADDR: u64 = 0xA860_0000;
 const MCU_MBOX1_SRAM_START_ADDR: u64 = 0xA880_0000;
 const MCU_MBOX1_SRAM_END_ADDR: u64 = 0xA8A0_0000;
 
+/// Register state captured in emulator snapshots.
+#[derive(Deserialize, Serialize)]
+struct AxiCDMAState {
+    control: u32,
+    status: u32,
+    src_addr_lsb: u32,
+    src_addr_msb: u32,
+    dst_addr_lsb: u32,
+    dst_addr_msb: u32,
+    btt: u32,
+    operation_pending: bool,
+}
+
 pub struct AxiCDMA {
     // Register emulation
     control: ReadWriteRegister<u32, AxicdmaControl::Register>, // 0x00
@@ -299,6 +314,42 @@ impl AxicdmaPeripheral for AxiCDMA {
         }
     }
 
+    fn save_state(&self) -> Option<Vec<u8>> {
+        Some(encode_state(&AxiCDMAState {
+            control: self.control.reg.get(),
+            status: self.status.reg.get(),
+            src_addr_lsb: self.src_addr_lsb.reg.get(),
+            src_addr_msb: self.src_addr_msb.reg.get(),
+            dst_addr_lsb: self.dst_addr_lsb.reg.get(),
+            dst_addr_msb: self.dst_addr_msb.reg.get(),
+            btt: self.btt.reg.get(),
+            operation_pending: self.operation_start.is_some(),
+        }))
+    }
+
+    fn restore_state(&mut self, state: &[u8]) -> std::io::Result<()> {
+        let state: AxiCDMAState = decode_state(state)?;
+        self.control.reg.set(state.control);
+        self.status.reg.set(state.status);
+        self.src_addr_lsb.reg.set(state.src_addr_lsb);
+        self.src_addr_msb.reg.set(state.src_addr_msb);
+        self.dst_addr_lsb.reg.set(state.dst_addr_lsb);
+        self.dst_addr_msb.reg.set(state.dst_addr_msb);
+        self.btt.reg.set(state.btt);
+        self.operation_start = state
+            .operation_pending
+            .then(|| self.timer.schedule_poll_in(Self::IO_START_DELAY));
+
+        let error = self.status.reg.is_set(AxicdmaStatus::IrqError);
+        let event = self.status.reg.is_set(AxicdmaStatus::IrqIoc);
+        self.error_irq.set_level(error);
+        self.event_irq.set_level(event);
+        if error || event {
+            self.timer.schedule_poll_in(1);
+        }
+        Ok(())
+    }
+
     fn read_axicdma_control(
         &mut self,
     ) -> caliptra_emu_bus::ReadWriteRegister<u32, AxicdmaControl::Register> {
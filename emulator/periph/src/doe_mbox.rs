@@ -1,11 +1,22 @@
 // Licensed under the Apache-2.0 license
+use crate::snapshot::{decode_state, encode_state, invalid_data, MemoryImage};
 use caliptra_emu_bus::{Clock, ReadWriteRegister, Timer};
 use caliptra_emu_cpu::Irq;
 use caliptra_mcu_emulator_registers_generated::doe_mbox::{DoeMboxGenerated, DoeMboxPeripheral};
 use caliptra_mcu_registers_generated::doe_mbox::bits::{DoeMboxEvent, DoeMboxStatus};
+use serde::{Deserialize, Serialize};
 use std::sync::{Arc, Mutex};
 use tock_registers::interfaces::{Readable, Writeable};
 
+/// Mailbox registers and SRAM captured in emulator snapshots.
+#[derive(Deserialize, Serialize)]
+struct DoeMboxState {
+    sram: MemoryImage,
+    dlen: u32,
+    event: u32,
+    status: u32,
+}
+
 pub struct DummyDoeMbox {
     timer: Timer,
     event_irq: Irq,
@@ -67,6 +78,41 @@ impl DoeMboxPeripheral for DummyDoeMbox {
         self.timer.schedule_poll_in(Self::DOE_MBOX_TICKS);
     }
 
+    fn save_state(&self) -> Option<Vec<u8>> {
+        let inner = self.periph.inner.lock().unwrap();
+        let sram = inner
+            .mbox_sram
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect::<Vec<u8>>();
+        Some(encode_state(&DoeMboxState {
+            sram: MemoryImage::capture(&sram, 0),
+            dlen: inner.mbox_dlen.reg.get(),
+            event: inner.mbox_event.reg.get(),
+            status: inner.mbox_status.reg.get(),
+        }))
+    }
+
+    fn restore_state(&mut self, state: &[u8]) -> std::io::Result<()> {
+        let state: DoeMboxState = decode_state(state)?;
+        let irq_status = {
+            let mut inner = self.periph.inner.lock().unwrap();
+            if state.sram.len() != inner.mbox_sram.len() * 4 {
+                return Err(invalid_data("DOE mailbox SRAM size mismatch".into()));
+            }
+            let sram = state.sram.to_vec()?;
+            for (word, bytes) in inner.mbox_sram.iter_mut().zip(sram.chunks_exact(4)) {
+                *word = u32::from_le_bytes(bytes.try_into().unwrap());
+            }
+            inner.mbox_dlen.reg.set(state.dlen);
+            inner.mbox_event.reg.set(state.event);
+            inner.mbox_status.reg.set(state.status);
+            inner.check_interrupts()
+        };
+        self.event_irq.set_level(irq_status);
+        Ok(())
+    }
+
     fn read_doe_mbox_dlen(&mut self) -> caliptra_emu_types::RvData {
         self.periph.inner.lock().unwrap().mbox_dlen.reg.get()
     }
@@ -188,6 +234,12 @@ impl DoeMboxPeriph {
         Ok(())
     }
 
+    /// Returns true while the MCU has not yet picked up the last data object written by the SoC.
+    pub fn is_data_pending(&self) -> bool {
+        let inner = self.inner.lock().unwrap();
+        inner.mbox_event.reg.get() & DoeMboxEvent::DataReady::SET.value != 0
+    }
+
     pub fn request_reset(&mut self) {
         {
             let mut inner = self.inner.lock().unwrap();
@@ -606,4 +658,41 @@ mod tests {
             DoeMboxStatus::Error::CLEAR.value
         );
     }
+
+    #[test]
+    fn test_doe_mbox_snapshot() {
+        let dummy_clock = Clock::new();
+        let mut autobus = test_helper_setup_autobus(&dummy_clock);
+        for i in 0..4u32 {
+            autobus
+                .write(RvSize::Word, DOE_MBOX_SRAM_BASE_ADDR + i * 4, 0x1000 + i)
+                .unwrap();
+        }
+        autobus
+            .write(
+                RvSize::Word,
+                DOE_MBOX_BASE_ADDR + DOE_MBOX_DLEN_REG_OFFSET,
+                4,
+            )
+            .unwrap();
+        let states = autobus.save_peripheral_states();
+        assert!(states.contains_key("doe_mbox"));
+
+        let mut restored = test_helper_setup_autobus(&dummy_clock);
+        restored.restore_peripheral_states(&states).unwrap();
+        for i in 0..4u32 {
+            assert_eq!(
+                restored
+                    .read(RvSize::Word, DOE_MBOX_SRAM_BASE_ADDR + i * 4)
+                    .unwrap(),
+                0x1000 + i
+            );
+        }
+        assert_eq!(
+            restored
+                .read(RvSize::Word, DOE_MBOX_BASE_ADDR + DOE_MBOX_DLEN_REG_OFFSET)
+                .unwrap(),
+            4
+        );
+    }
 }
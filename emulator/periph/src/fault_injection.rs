@@ -0,0 +1,437 @@
+/*++
+
+Licensed under the Apache-2.0 license.
+
+File Name:
+
+    fault_injection.rs
+
+Abstract:
+
+    Deterministic fault injection for emulator peripherals, driven by a
+    declarative fault plan.
+
+--*/
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Peripheral operation that a fault can be attached to.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FaultOperation {
+    /// Flash page read or OTP direct access read.
+    Read,
+    /// Flash page program.
+    Write,
+    /// Flash page erase.
+    Erase,
+    /// I3C private write received from the controller.
+    RxPacket,
+    /// I3C private read response sent to the controller.
+    TxPacket,
+    /// Mailbox command handed to the MCU.
+    Execute,
+}
+
+/// What happens when a fault fires.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FaultKind {
+    /// The operation fails and reports an error.
+    Error,
+    /// The operation succeeds, but one bit of the data read is inverted.
+    BitFlip { bit: u32 },
+    /// The packet is silently discarded.
+    Drop,
+    /// The packet is delivered with `data[offset] ^= xor`.
+    Corrupt { offset: usize, xor: u8 },
+    /// The mailbox never notifies the MCU, so the requester times out.
+    Timeout,
+    /// Power is lost after `bytes_written` bytes of the page have been
+    /// updated. The rest of the page is left untouched and the MCU halts.
+    PowerLoss { bytes_written: usize },
+}
+
+/// Half-open address range `[start, end)`.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AddressRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+/// One entry in a [`FaultPlan`].
+///
+/// An operation matches when the peripheral and operation are equal, its
+/// address falls in `address` (if given) and the clock is at or past
+/// `after_cycle` (if given). The fault fires on the `occurrence`-th match and
+/// on the following matches, `count` times in total (0 means every time).
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct FaultSpec {
+    pub peripheral: String,
+    pub operation: FaultOperation,
+    pub fault: FaultKind,
+    #[serde(default)]
+    pub address: Option<AddressRange>,
+    #[serde(default)]
+    pub after_cycle: Option<u64>,
+    #[serde(default = "FaultSpec::default_occurrence")]
+    pub occurrence: u32,
+    #[serde(default = "FaultSpec::default_count")]
+    pub count: u32,
+}
+
+impl FaultSpec {
+    fn default_occurrence() -> u32 {
+        1
+    }
+
+    fn default_count() -> u32 {
+        1
+    }
+}
+
+/// Peripherals that accept faults, with the operations and fault kinds each
+/// one implements.
+const FAULT_TARGETS: &[(&str, &[FaultOperation])] = &[
+    (
+        "primary_flash",
+        &[
+            FaultOperation::Read,
+            FaultOperation::Write,
+            FaultOperation::Erase,
+        ],
+    ),
+    (
+        "secondary_flash",
+        &[
+            FaultOperation::Read,
+            FaultOperation::Write,
+            FaultOperation::Erase,
+        ],
+    ),
+    ("otp", &[FaultOperation::Read]),
+    ("i3c", &[FaultOperation::RxPacket, FaultOperation::TxPacket]),
+    ("mcu_mbox0", &[FaultOperation::Execute]),
+    ("mcu_mbox1", &[FaultOperation::Execute]),
+];
+
+fn kind_supported(operation: FaultOperation, fault: &FaultKind) -> bool {
+    match fault {
+        FaultKind::Error => matches!(
+            operation,
+            FaultOperation::Read | FaultOperation::Write | FaultOperation::Erase
+        ),
+        FaultKind::BitFlip { .. } => operation == FaultOperation::Read,
+        FaultKind::Drop | FaultKind::Corrupt { .. } => matches!(
+            operation,
+            FaultOperation::RxPacket | FaultOperation::TxPacket
+        ),
+        FaultKind::Timeout => operation == FaultOperation::Execute,
+        FaultKind::PowerLoss { .. } => {
+            matches!(operation, FaultOperation::Write | FaultOperation::Erase)
+        }
+    }
+}
+
+/// A list of faults to inject, usually loaded from a JSON file such as:
+///
+/// ```json
+/// { "faults": [
+///     { "peripheral": "primary_flash", "operation": "write",
+///       "address": { "start": 0, "end": 4096 }, "occurrence": 2,
+///       "fault": "error" },
+///     { "peripheral": "i3c", "operation": "rx_packet",
+///       "fault": { "corrupt": { "offset": 3, "xor": 255 } } }
+/// ] }
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct FaultPlan {
+    pub faults: Vec<FaultSpec>,
+}
+
+impl FaultPlan {
+    pub fn from_json(json: &str) -> std::io::Result<Self> {
+        let plan: Self = serde_json::from_str(json)?;
+        plan.validate()?;
+        Ok(plan)
+    }
+
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Reject faults that no peripheral would ever fire.
+    pub fn validate(&self) -> std::io::Result<()> {
+        for (i, spec) in self.faults.iter().enumerate() {
+            let invalid = |msg: String| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("fault {i}: {msg}"),
+                )
+            };
+            let Some((_, operations)) = FAULT_TARGETS
+                .iter()
+                .find(|(name, _)| *name == spec.peripheral)
+            else {
+                return Err(invalid(format!("unknown peripheral {}", spec.peripheral)));
+            };
+            if !operations.contains(&spec.operation) {
+                return Err(invalid(format!(
+                    "{} has no {:?} operation",
+                    spec.peripheral, spec.operation
+                )));
+            }
+            if !kind_supported(spec.operation, &spec.fault) {
+                return Err(invalid(format!(
+                    "{:?} cannot be injected into {:?}",
+                    spec.fault, spec.operation
+                )));
+            }
+            if spec.occurrence == 0 {
+                return Err(invalid("occurrence is 1-based".into()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Record of a fault that was injected.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct InjectedFault {
+    /// Index of the matching entry in the plan.
+    pub index: usize,
+    pub cycle: u64,
+    pub peripheral: &'static str,
+    pub operation: FaultOperation,
+    pub address: Option<u32>,
+    pub fault: FaultKind,
+}
+
+impl fmt::Display for InjectedFault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cycle {}: {} {:?}",
+            self.cycle, self.peripheral, self.operation
+        )?;
+        if let Some(address) = self.address {
+            write!(f, " at {:#x}", address)?;
+        }
+        write!(f, ": {:?} (plan entry {})", self.fault, self.index)
+    }
+}
+
+struct FaultSlot {
+    spec: FaultSpec,
+    matched: u32,
+    fired: u32,
+}
+
+struct FaultState {
+    slots: Vec<FaultSlot>,
+    injected: Vec<InjectedFault>,
+}
+
+/// Shared fault injection state for one emulator instance.
+#[derive(Clone)]
+pub struct FaultInjector {
+    state: Arc<Mutex<FaultState>>,
+}
+
+impl FaultInjector {
+    pub fn new(plan: FaultPlan) -> Self {
+        let slots = plan
+            .faults
+            .into_iter()
+            .map(|spec| FaultSlot {
+                spec,
+                matched: 0,
+                fired: 0,
+            })
+            .collect();
+        Self {
+            state: Arc::new(Mutex::new(FaultState {
+                slots,
+                injected: vec![],
+            })),
+        }
+    }
+
+    /// Hook to hand to the peripheral called `peripheral` in the plan.
+    pub fn hook(&self, peripheral: &'static str) -> FaultHook {
+        FaultHook {
+            injector: Some(self.clone()),
+            peripheral,
+        }
+    }
+
+    /// Every fault injected so far, in order.
+    pub fn injected(&self) -> Vec<InjectedFault> {
+        self.state.lock().unwrap().injected.clone()
+    }
+}
+
+/// Per-peripheral view of a [`FaultInjector`]. The default hook never fires.
+#[derive(Clone, Default)]
+pub struct FaultHook {
+    injector: Option<FaultInjector>,
+    peripheral: &'static str,
+}
+
+impl FaultHook {
+    /// Called by a peripheral before performing `operation`. Returns the
+    /// fault to apply, if any, and logs it.
+    pub fn check(
+        &self,
+        operation: FaultOperation,
+        address: Option<u32>,
+        cycle: u64,
+    ) -> Option<FaultKind> {
+        let injector = self.injector.as_ref()?;
+        let mut state = injector.state.lock().unwrap();
+        let mut result = None;
+        for (index, slot) in state.slots.iter_mut().enumerate() {
+            let spec = &slot.spec;
+            if spec.peripheral != self.peripheral || spec.operation != operation {
+                continue;
+            }
+            if let Some(range) = spec.address {
+                match address {
+                    Some(address) if address >= range.start && address < range.end => {}
+                    _ => continue,
+                }
+            }
+            if spec.after_cycle.is_some_and(|after| cycle < after) {
+                continue;
+            }
+            // Every matching entry counts the operation, but only the first
+            // one that is due fires.
+            slot.matched += 1;
+            let exhausted = spec.count != 0 && slot.fired >= spec.count;
+            if result.is_none() && slot.matched >= spec.occurrence && !exhausted {
+                slot.fired += 1;
+                result = Some((index, spec.fault.clone()));
+            }
+        }
+        let (index, fault) = result?;
+        let injected = InjectedFault {
+            index,
+            cycle,
+            peripheral: self.peripheral,
+            operation,
+            address,
+            fault: fault.clone(),
+        };
+        println!("Fault injected: {}", injected);
+        state.injected.push(injected);
+        Some(fault)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_parsing() {
+        let plan = FaultPlan::from_json(
+            r#"{ "faults": [
+                { "peripheral": "primary_flash", "operation": "write",
+                  "address": { "start": 256, "end": 512 }, "occurrence": 2,
+                  "fault": { "power_loss": { "bytes_written": 16 } } },
+                { "peripheral": "otp", "operation": "read", "after_cycle": 100,
+                  "count": 0, "fault": { "bit_flip": { "bit": 3 } } }
+            ] }"#,
+        )
+        .unwrap();
+        assert_eq!(plan.faults.len(), 2);
+        assert_eq!(plan.faults[0].count, 1);
+        assert_eq!(
+            plan.faults[0].fault,
+            FaultKind::PowerLoss { bytes_written: 16 }
+        );
+        assert_eq!(plan.faults[1].occurrence, 1);
+
+        // Unknown peripherals and unsupported fault kinds are rejected.
+        assert!(FaultPlan::from_json(
+            r#"{ "faults": [ { "peripheral": "uart", "operation": "read", "fault": "error" } ] }"#
+        )
+        .is_err());
+        assert!(FaultPlan::from_json(
+            r#"{ "faults": [ { "peripheral": "otp", "operation": "read", "fault": "drop" } ] }"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_occurrence_and_count() {
+        let injector = FaultInjector::new(FaultPlan {
+            faults: vec![FaultSpec {
+                peripheral: "primary_flash".into(),
+                operation: FaultOperation::Write,
+                fault: FaultKind::Error,
+                address: Some(AddressRange {
+                    start: 0x100,
+                    end: 0x200,
+                }),
+                after_cycle: None,
+                occurrence: 2,
+                count: 2,
+            }],
+        });
+        let hook = injector.hook("primary_flash");
+        let other = injector.hook("secondary_flash");
+
+        // Out of range and other peripherals don't count.
+        assert_eq!(hook.check(FaultOperation::Write, Some(0x200), 1), None);
+        assert_eq!(other.check(FaultOperation::Write, Some(0x100), 2), None);
+        assert_eq!(hook.check(FaultOperation::Erase, Some(0x100), 3), None);
+
+        assert_eq!(hook.check(FaultOperation::Write, Some(0x100), 4), None);
+        assert_eq!(
+            hook.check(FaultOperation::Write, Some(0x100), 5),
+            Some(FaultKind::Error)
+        );
+        assert_eq!(
+            hook.check(FaultOperation::Write, Some(0x1ff), 6),
+            Some(FaultKind::Error)
+        );
+        assert_eq!(hook.check(FaultOperation::Write, Some(0x100), 7), None);
+
+        let injected = injector.injected();
+        assert_eq!(injected.len(), 2);
+        assert_eq!(injected[0].cycle, 5);
+        assert_eq!(injected[1].address, Some(0x1ff));
+    }
+
+    #[test]
+    fn test_after_cycle() {
+        let injector = FaultInjector::new(FaultPlan {
+            faults: vec![FaultSpec {
+                peripheral: "mcu_mbox0".into(),
+                operation: FaultOperation::Execute,
+                fault: FaultKind::Timeout,
+                address: None,
+                after_cycle: Some(1000),
+                occurrence: 1,
+                count: 0,
+            }],
+        });
+        let hook = injector.hook("mcu_mbox0");
+        assert_eq!(hook.check(FaultOperation::Execute, None, 999), None);
+        for cycle in 1000..1003 {
+            assert_eq!(
+                hook.check(FaultOperation::Execute, None, cycle),
+                Some(FaultKind::Timeout)
+            );
+        }
+        assert!(FaultHook::default()
+            .check(FaultOperation::Execute, None, 2000)
+            .is_none());
+    }
+}
@@ -12,7 +12,11 @@ Abstract:
 
 --*/
 
-use caliptra_emu_bus::{ActionHandle, Bus, Clock, Ram, ReadOnlyRegister, ReadWriteRegister, Timer};
+use crate::fault_injection::{FaultHook, FaultKind, FaultOperation};
+use crate::snapshot::{decode_state, encode_state, invalid_data, MemoryImage};
+use caliptra_emu_bus::{
+    ActionHandle, Bus, Clock, Ram, ReadOnlyRegister, ReadWriteRegister, Timer, TimerAction,
+};
 use caliptra_emu_cpu::Irq;
 use caliptra_emu_types::{RvData, RvSize};
 use caliptra_mcu_emulator_consts::{
@@ -29,6 +33,7 @@ use caliptra_mcu_registers_generated::primary_flash_ctrl::bits::{
     CtrlRegwen, FlControl, FlInterruptEnable, FlInterruptState, OpStatus,
 };
 use core::convert::TryInto;
+use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
 use std::fs::File;
 use std::io::{Read, Seek, Write};
@@ -77,6 +82,21 @@ pub enum FlashOpError {
     DmaRamAccessError = 4,
 }
 
+/// Controller state and storage contents captured in emulator snapshots.
+#[derive(Deserialize, Serialize)]
+struct FlashCtrlState {
+    interrupt_state: u32,
+    interrupt_enable: u32,
+    page_size: u32,
+    page_num: u32,
+    page_addr: u32,
+    control: u32,
+    op_status: u32,
+    ctrl_regwen: u32,
+    operation_pending: bool,
+    storage: Option<MemoryImage>,
+}
+
 /// A dummy flash controller peripheral for emulation purposes.
 pub struct DummyFlashCtrl {
     interrupt_state: ReadWriteRegister<u32, FlInterruptState::Register>,
@@ -98,6 +118,7 @@ pub struct DummyFlashCtrl {
     event_irq: Irq,
     primary_generated: PrimaryFlashGenerated,
     secondary_generated: SecondaryFlashGenerated,
+    fault_hook: FaultHook,
 }
 
 impl DummyFlashCtrl {
@@ -190,9 +211,14 @@ impl DummyFlashCtrl {
             event_irq,
             primary_generated: PrimaryFlashGenerated::default(),
             secondary_generated: SecondaryFlashGenerated::default(),
+            fault_hook: FaultHook::default(),
         })
     }
 
+    pub fn set_fault_hook(&mut self, hook: FaultHook) {
+        self.fault_hook = hook;
+    }
+
     fn raise_interrupt(&mut self, interrupt_type: FlashCtrlIntType) {
         match interrupt_type {
             FlashCtrlIntType::Error => {
@@ -266,7 +292,7 @@ impl DummyFlashCtrl {
         }
     }
 
-    fn read_page(&mut self) -> Result<(), FlashOpError> {
+    fn read_page(&mut self, flip_bit: Option<u32>) -> Result<(), FlashOpError> {
         let page_num = self.page_num.reg.get();
         let page_addr = self.page_addr.reg.get();
 
@@ -292,6 +318,10 @@ impl DummyFlashCtrl {
                 .and_then(|_| file.read_exact(&mut self.buffer))
                 .map_err(|_| FlashOpError::ReadError)?;
         }
+        if let Some(bit) = flip_bit {
+            let bit = bit as usize % (Self::PAGE_SIZE * 8);
+            self.buffer[bit / 8] ^= 1 << (bit % 8);
+        }
 
         let access_type = self.dma_ram_access_check(page_addr);
         let (dma_ram, dma_start_addr) = match access_type {
@@ -322,7 +352,9 @@ impl DummyFlashCtrl {
         Ok(())
     }
 
-    fn write_page(&mut self) -> Result<(), FlashOpError> {
+    /// Program the page. Only the first `len` bytes reach the storage, which
+    /// is less than a page when power is lost part way through.
+    fn write_page(&mut self, len: usize) -> Result<(), FlashOpError> {
         // Get the page number from the register
         let page_num = self.page_num.reg.get();
         // Get the address from the register
@@ -369,7 +401,7 @@ impl DummyFlashCtrl {
         // Write to file first
         let file = self.file.as_mut().unwrap();
         file.seek(std::io::SeekFrom::Start(offset as u64))
-            .and_then(|_| file.write_all(&self.buffer))
+            .and_then(|_| file.write_all(&self.buffer[..len]))
             .map_err(|_| FlashOpError::WriteError)?;
 
         // If direct_read_region is present, update it only if file write succeeded.
@@ -378,13 +410,14 @@ impl DummyFlashCtrl {
             if offset + Self::PAGE_SIZE > region.len() as usize {
                 return Err(FlashOpError::WriteError);
             }
-            region.data_mut()[offset..offset + Self::PAGE_SIZE].copy_from_slice(&self.buffer);
+            region.data_mut()[offset..offset + len].copy_from_slice(&self.buffer[..len]);
         }
 
         Ok(())
     }
 
-    fn erase_page(&mut self) -> Result<(), FlashOpError> {
+    /// Erase the first `len` bytes of the page.
+    fn erase_page(&mut self, len: usize) -> Result<(), FlashOpError> {
         // Get the page number from the register
         let page_num = self.page_num.reg.get();
 
@@ -399,7 +432,7 @@ impl DummyFlashCtrl {
         let offset = (page_num * Self::PAGE_SIZE as u32) as usize;
         let file = self.file.as_mut().unwrap();
         file.seek(std::io::SeekFrom::Start(offset as u64))
-            .and_then(|_| file.write_all(&vec![0xFF; Self::PAGE_SIZE]))
+            .and_then(|_| file.write_all(&vec![0xFF; len]))
             .map_err(|_| FlashOpError::EraseError)?;
 
         // If direct_read_region is present, update it only if file erase succeeded
@@ -408,12 +441,75 @@ impl DummyFlashCtrl {
             if offset + Self::PAGE_SIZE > region.len() as usize {
                 return Err(FlashOpError::EraseError);
             }
-            region.data_mut()[offset..offset + Self::PAGE_SIZE].fill(0xFF);
+            region.data_mut()[offset..offset + len].fill(0xFF);
         }
 
         Ok(())
     }
 
+    fn save_snapshot(&self) -> Vec<u8> {
+        // The file is only accessed through seek + read/write, so sharing the
+        // cursor through `&File` is fine.
+        let storage = self.file.as_ref().map(|mut file| {
+            let mut contents = Vec::new();
+            file.seek(std::io::SeekFrom::Start(0))
+                .and_then(|_| file.read_to_end(&mut contents))
+                .expect("failed to read flash storage");
+            MemoryImage::capture(&contents, 0xff)
+        });
+        encode_state(&FlashCtrlState {
+            interrupt_state: self.interrupt_state.reg.get(),
+            interrupt_enable: self.interrupt_enable.reg.get(),
+            page_size: self.page_size.reg.get(),
+            page_num: self.page_num.reg.get(),
+            page_addr: self.page_addr.reg.get(),
+            control: self.control.reg.get(),
+            op_status: self.op_status.reg.get(),
+            ctrl_regwen: self.ctrl_regwen.reg.get(),
+            operation_pending: self.operation_start.is_some(),
+            storage,
+        })
+    }
+
+    fn restore_snapshot(&mut self, state: &[u8]) -> std::io::Result<()> {
+        let state: FlashCtrlState = decode_state(state)?;
+        if let (Some(file), Some(storage)) = (self.file.as_mut(), state.storage.as_ref()) {
+            let contents = storage.to_vec()?;
+            if let Some(region) = self.direct_read_region.as_ref() {
+                storage.restore(region.borrow_mut().data_mut())?;
+            }
+            file.seek(std::io::SeekFrom::Start(0))?;
+            file.write_all(&contents)?;
+        } else if state.storage.is_some() != self.file.is_some() {
+            return Err(invalid_data(
+                "flash snapshot does not match the storage backend".into(),
+            ));
+        }
+
+        self.interrupt_state.reg.set(state.interrupt_state);
+        self.interrupt_enable.reg.set(state.interrupt_enable);
+        self.page_size.reg.set(state.page_size);
+        self.page_num.reg.set(state.page_num);
+        self.page_addr.reg.set(state.page_addr);
+        self.control.reg.set(state.control);
+        self.op_status.reg.set(state.op_status);
+        self.ctrl_regwen.reg.set(state.ctrl_regwen);
+        self.operation_start = state
+            .operation_pending
+            .then(|| self.timer.schedule_poll_in(Self::IO_START_DELAY));
+
+        let error = self.interrupt_state.reg.is_set(FlInterruptState::Error)
+            && self.interrupt_enable.reg.is_set(FlInterruptEnable::Error);
+        let event = self.interrupt_state.reg.is_set(FlInterruptState::Event)
+            && self.interrupt_enable.reg.is_set(FlInterruptEnable::Event);
+        self.error_irq.set_level(error);
+        self.event_irq.set_level(event);
+        if error || event {
+            self.timer.schedule_poll_in(1);
+        }
+        Ok(())
+    }
+
     fn process_io(&mut self) {
         if !self.control.reg.is_set(FlControl::Start) {
             return;
@@ -421,10 +517,35 @@ impl DummyFlashCtrl {
 
         match self.control.reg.read(FlControl::Op).try_into() {
             Ok(op) => {
-                let io_compl = match op {
-                    FlashOperation::ReadPage => self.read_page(),
-                    FlashOperation::WritePage => self.write_page(),
-                    FlashOperation::ErasePage => self.erase_page(),
+                let (operation, error) = match op {
+                    FlashOperation::ReadPage => (FaultOperation::Read, FlashOpError::ReadError),
+                    FlashOperation::WritePage => (FaultOperation::Write, FlashOpError::WriteError),
+                    FlashOperation::ErasePage => (FaultOperation::Erase, FlashOpError::EraseError),
+                };
+                let address = self.page_num.reg.get().wrapping_mul(Self::PAGE_SIZE as u32);
+                let fault = self
+                    .fault_hook
+                    .check(operation, Some(address), self.timer.now());
+
+                let io_compl = match (op, fault) {
+                    (_, Some(FaultKind::Error)) => Err(error),
+                    (_, Some(FaultKind::PowerLoss { bytes_written })) => {
+                        // The page is left partially updated, the operation
+                        // never completes and the MCU stops.
+                        let len = bytes_written.min(Self::PAGE_SIZE);
+                        let _ = match operation {
+                            FaultOperation::Write => self.write_page(len),
+                            _ => self.erase_page(len),
+                        };
+                        self.timer.schedule_action_in(0, TimerAction::Halt);
+                        return;
+                    }
+                    (FlashOperation::ReadPage, Some(FaultKind::BitFlip { bit })) => {
+                        self.read_page(Some(bit))
+                    }
+                    (FlashOperation::ReadPage, _) => self.read_page(None),
+                    (FlashOperation::WritePage, _) => self.write_page(Self::PAGE_SIZE),
+                    (FlashOperation::ErasePage, _) => self.erase_page(Self::PAGE_SIZE),
                 };
 
                 self.handle_io_completion(io_compl);
@@ -456,6 +577,14 @@ impl PrimaryFlashPeripheral for DummyFlashCtrl {
         }
     }
 
+    fn save_state(&self) -> Option<Vec<u8>> {
+        Some(self.save_snapshot())
+    }
+
+    fn restore_state(&mut self, state: &[u8]) -> std::io::Result<()> {
+        self.restore_snapshot(state)
+    }
+
     fn read_fl_interrupt_state(
         &mut self,
     ) -> caliptra_emu_bus::ReadWriteRegister<
@@ -626,6 +755,14 @@ impl SecondaryFlashPeripheral for DummyFlashCtrl {
         }
     }
 
+    fn save_state(&self) -> Option<Vec<u8>> {
+        Some(self.save_snapshot())
+    }
+
+    fn restore_state(&mut self, state: &[u8]) -> std::io::Result<()> {
+        self.restore_snapshot(state)
+    }
+
     fn warm_reset(&mut self) {}
     fn update_reset(&mut self) {}
 
@@ -1611,4 +1748,117 @@ mod test {
     fn test_secondary_flash_erase_page_error() {
         test_erase_page_error(FlashType::ImagePartitionB);
     }
+
+    #[test]
+    fn test_flash_snapshot_round_trip() {
+        let clock = Clock::new();
+        let pic = Pic::new();
+        let content = vec![0xa5u8; 3 * DummyFlashCtrl::PAGE_SIZE];
+
+        let src_path = NamedTempFile::new().unwrap().into_temp_path();
+        let mut src = DummyFlashCtrl::new(
+            &clock,
+            None,
+            Some(src_path.to_path_buf()),
+            pic.register_irq(19),
+            pic.register_irq(20),
+            Some(&content),
+        )
+        .unwrap();
+        PrimaryFlashPeripheral::write_page_num(&mut src, 2);
+        let state = PrimaryFlashPeripheral::save_state(&src).unwrap();
+
+        let dst_path = NamedTempFile::new().unwrap().into_temp_path();
+        let mut dst = DummyFlashCtrl::new(
+            &clock,
+            None,
+            Some(dst_path.to_path_buf()),
+            pic.register_irq(21),
+            pic.register_irq(22),
+            None,
+        )
+        .unwrap();
+        PrimaryFlashPeripheral::restore_state(&mut dst, &state).unwrap();
+        assert_eq!(PrimaryFlashPeripheral::read_page_num(&mut dst), 2);
+
+        let restored = std::fs::read(&dst_path).unwrap();
+        assert_eq!(&restored[..content.len()], &content[..]);
+        assert!(restored[content.len()..].iter().all(|&b| b == 0xff));
+    }
+
+    #[test]
+    fn test_flash_fault_injection() {
+        use crate::fault_injection::{FaultInjector, FaultPlan};
+
+        let clock = Clock::new();
+        let pic = Pic::new();
+        let dma_ram = test_helper_setup_dummy_dma_ram();
+        let path = NamedTempFile::new().unwrap().into_temp_path();
+        let mut flash = DummyFlashCtrl::new(
+            &clock,
+            None,
+            Some(path.to_path_buf()),
+            pic.register_irq(19),
+            pic.register_irq(20),
+            None,
+        )
+        .unwrap();
+        PrimaryFlashPeripheral::set_dma_ram(&mut flash, dma_ram.clone());
+        let injector = FaultInjector::new(
+            FaultPlan::from_json(
+                r#"{ "faults": [
+                    { "peripheral": "primary_flash", "operation": "write",
+                      "occurrence": 1, "fault": "error" },
+                    { "peripheral": "primary_flash", "operation": "write",
+                      "occurrence": 2, "fault": { "power_loss": { "bytes_written": 16 } } }
+                ] }"#,
+            )
+            .unwrap(),
+        );
+        flash.set_fault_hook(injector.hook("primary_flash"));
+
+        let page_addr = test_helper_prepare_io_page_buffer(
+            0x4005_1000,
+            dma_ram,
+            DummyFlashCtrl::PAGE_SIZE,
+            Some(&[0x5au8; DummyFlashCtrl::PAGE_SIZE]),
+        )
+        .unwrap();
+        let start_write = || {
+            ReadWriteRegister::new(
+                (FlControl::Start::SET + FlControl::Op.val(FlashOperation::WritePage as u32)).value,
+            )
+        };
+        PrimaryFlashPeripheral::write_page_addr(&mut flash, page_addr);
+        PrimaryFlashPeripheral::write_page_size(&mut flash, DummyFlashCtrl::PAGE_SIZE as u32);
+        PrimaryFlashPeripheral::write_page_num(&mut flash, 4);
+
+        // First write reports an error and leaves the page erased.
+        PrimaryFlashPeripheral::write_fl_control(&mut flash, start_write());
+        flash.process_io();
+        assert_eq!(
+            flash.op_status.reg.read(OpStatus::Err),
+            FlashOpError::WriteError as u32
+        );
+        assert!(test_helper_verify_file_data(
+            &path.to_path_buf(),
+            4,
+            &[0xffu8; DummyFlashCtrl::PAGE_SIZE]
+        ));
+
+        // Second write loses power after 16 bytes and never completes.
+        flash.op_status.reg.set(0);
+        flash.ctrl_regwen.reg.set(CtrlRegwen::En::SET.value);
+        PrimaryFlashPeripheral::write_fl_control(&mut flash, start_write());
+        flash.process_io();
+        assert_eq!(flash.op_status.reg.get(), 0);
+        let mut expected = [0xffu8; DummyFlashCtrl::PAGE_SIZE];
+        expected[..16].fill(0x5a);
+        assert!(test_helper_verify_file_data(
+            &path.to_path_buf(),
+            4,
+            &expected
+        ));
+        assert_eq!(injector.injected().len(), 2);
+    }
 }
@@ -6,7 +6,9 @@ Abstract:
     File contains I3C peripheral implementation.
 --*/
 
+use crate::fault_injection::{FaultHook, FaultKind, FaultOperation};
 use crate::i3c_protocol::I3cController;
+use crate::snapshot::{decode_state, encode_state};
 use crate::{I3cIncomingCommandClient, I3cTarget};
 use caliptra_emu_bus::{Clock, ReadWriteRegister, Timer};
 use caliptra_emu_bus::{Device, Event, EventData};
@@ -16,12 +18,13 @@ use caliptra_mcu_emulator_registers_generated::i3c::{I3cGenerated, I3cPeripheral
 use caliptra_mcu_registers_generated::i3c::bits::{
     DeviceStatus0, ExtcapHeader, IndirectFifoCtrl0, IndirectFifoStatus0, InterruptEnable,
     InterruptStatus, RecIntfCfg, RecoveryCtrl, Status, StbyCrCapabilities, StbyCrDeviceAddr,
-    TtiQueueSize,
+    StbyCrIntrStatus, StbyCrMrl, StbyCrMwl, TtiQueueSize,
 };
 use caliptra_mcu_testing_common::i3c::{
     DynamicI3cAddress, I3cTcriCommand, I3cTcriResponseXfer, IbiDescriptor, ResponseDescriptor,
 };
 use semver::Version;
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::sync::mpsc;
 use std::sync::{Arc, Mutex};
@@ -44,6 +47,38 @@ impl I3cIncomingCommandClient for PollScheduler {
     }
 }
 
+/// TTI queues and register state captured in emulator snapshots.
+///
+/// Transfers still sitting in the bus-side target buffers are not captured, so
+/// snapshots should be taken while the I3C bus is idle.
+#[derive(Deserialize, Serialize)]
+struct I3cState {
+    tti_rx_desc_queue_raw: VecDeque<u32>,
+    tti_rx_data_raw: VecDeque<Vec<u8>>,
+    tti_rx_current: VecDeque<u8>,
+    tti_tx_desc_queue_raw: VecDeque<u32>,
+    tti_tx_data_raw: VecDeque<Vec<u8>>,
+    tti_ibi_buffer: Vec<u8>,
+    prot_cap_2: u32,
+    device_status_0: u32,
+    recovery_status: u32,
+    indirect_fifo_ctrl_0: u32,
+    indirect_fifo_ctrl_1: u32,
+    indirect_fifo_status_0: u32,
+    indirect_fifo_status_1: u32,
+    indirect_fifo_status_2: u32,
+    recovery_ctrl: u32,
+    rec_intf_cfg: u32,
+    rec_intf_reg_w1_c_access: u32,
+    indirect_fifo_data: Vec<u8>,
+    interrupt_status: u32,
+    interrupt_enable: u32,
+    ibi_status: Option<u32>,
+    stby_cr_intr_status: u32,
+    max_write_len: u16,
+    max_read_len: u16,
+}
+
 pub struct I3c {
     /// Timer
     timer: Timer,
@@ -95,17 +130,22 @@ pub struct I3c {
     interrupt_status: ReadWriteRegister<u32, InterruptStatus::Register>,
     interrupt_enable: ReadWriteRegister<u32, InterruptEnable::Register>,
     ibi_status: Option<ReadWriteRegister<u32, Status::Register>>,
+    stby_cr_intr_status: ReadWriteRegister<u32, StbyCrIntrStatus::Register>,
     generated: I3cGenerated,
 
     events_to_caliptra: Option<mpsc::Sender<Event>>,
     events_from_caliptra: Option<mpsc::Receiver<Event>>,
     events_to_mcu: Option<mpsc::Sender<Event>>,
     events_from_mcu: Option<mpsc::Receiver<Event>>,
+
+    /// Fault injection hook for packets in both directions
+    fault_hook: FaultHook,
 }
 
 impl I3c {
     const HCI_VERSION: u32 = 0x120;
     const HCI_TICKS: u64 = 1000;
+    const IBI_PAYLOAD_LIMIT: u32 = 0x10;
 
     pub fn new(
         clock: &Clock,
@@ -153,11 +193,13 @@ impl I3c {
             interrupt_status: ReadWriteRegister::new(0),
             interrupt_enable: ReadWriteRegister::new(0),
             ibi_status: None,
+            stby_cr_intr_status: ReadWriteRegister::new(0),
             generated: I3cGenerated::default(),
             events_to_caliptra: None,
             events_from_caliptra: None,
             events_to_mcu: None,
             events_from_mcu: None,
+            fault_hook: FaultHook::default(),
         }
     }
 
@@ -165,6 +207,10 @@ impl I3c {
         self.i3c_target.get_address()
     }
 
+    pub fn set_fault_hook(&mut self, hook: FaultHook) {
+        self.fault_hook = hook;
+    }
+
     fn write_tx_data_into_target(&mut self) {
         if !self.tti_tx_desc_queue_raw.is_empty() {
             let resp_desc = ResponseDescriptor::read_from_bytes(
@@ -175,9 +221,22 @@ impl I3c {
             if let Some(_data) = self.tti_tx_data_raw.front() {
                 if self.tti_tx_data_raw[0].len() >= data_size {
                     self.tti_tx_desc_queue_raw.pop_front();
+                    let mut data = self.tti_tx_data_raw.pop_front().unwrap();
+                    match self
+                        .fault_hook
+                        .check(FaultOperation::TxPacket, None, self.timer.now())
+                    {
+                        Some(FaultKind::Drop) => return,
+                        Some(FaultKind::Corrupt { offset, xor }) => {
+                            if let Some(byte) = data.get_mut(offset) {
+                                *byte ^= xor;
+                            }
+                        }
+                        _ => {}
+                    }
                     let resp = I3cTcriResponseXfer {
                         resp: resp_desc,
-                        data: self.tti_tx_data_raw.pop_front().unwrap(),
+                        data,
                     };
                     self.i3c_target.set_response(resp);
                 }
@@ -187,11 +246,17 @@ impl I3c {
 
     fn read_rx_data_into_buffer(&mut self) {
         if let Some(xfer) = self.i3c_target.read_command() {
+            let fault = self
+                .fault_hook
+                .check(FaultOperation::RxPacket, None, self.timer.now());
+            if fault == Some(FaultKind::Drop) {
+                return;
+            }
             // TODO: we don't request data using rnw
             let rnw = (u64::from(xfer.cmd.clone()) & (1 << 29)) as u32;
             self.tti_rx_desc_queue_raw
                 .push_back(xfer.cmd.raw_data_len() as u32 | rnw);
-            let data = match xfer.cmd.clone() {
+            let mut data = match xfer.cmd.clone() {
                 I3cTcriCommand::Immediate(imm) => vec![
                     imm.data_byte_1(),
                     imm.data_byte_2(),
@@ -200,6 +265,11 @@ impl I3c {
                 ],
                 _ => xfer.data,
             };
+            if let Some(FaultKind::Corrupt { offset, xor }) = fault {
+                if let Some(byte) = data.get_mut(offset) {
+                    *byte ^= xor;
+                }
+            }
             self.tti_rx_data_raw.push_back(data);
         }
     }
@@ -534,6 +604,88 @@ impl I3cPeripheral for I3c {
         self.events_to_mcu = Some(events_to_mcu);
         self.events_from_mcu = Some(events_from_mcu);
     }
+
+    fn save_state(&self) -> Option<Vec<u8>> {
+        Some(encode_state(&I3cState {
+            tti_rx_desc_queue_raw: self.tti_rx_desc_queue_raw.clone(),
+            tti_rx_data_raw: self.tti_rx_data_raw.clone(),
+            tti_rx_current: self.tti_rx_current.clone(),
+            tti_tx_desc_queue_raw: self.tti_tx_desc_queue_raw.clone(),
+            tti_tx_data_raw: self.tti_tx_data_raw.clone(),
+            tti_ibi_buffer: self.tti_ibi_buffer.clone(),
+            prot_cap_2: self.i3c_ec_sec_fw_recovery_if_prot_cap_2.reg.get(),
+            device_status_0: self.i3c_ec_sec_fw_recovery_if_device_status_0.reg.get(),
+            recovery_status: self.i3c_ec_sec_fw_recovery_if_recovery_status.reg.get(),
+            indirect_fifo_ctrl_0: self
+                .i3c_ec_sec_fw_recovery_if_indirect_fifo_ctrl_0
+                .reg
+                .get(),
+            indirect_fifo_ctrl_1: self
+                .i3c_ec_sec_fw_recovery_if_indirect_fifo_ctrl_1
+                .reg
+                .get(),
+            indirect_fifo_status_0: self
+                .i3c_ec_sec_fw_recovery_if_indirect_fifo_status_0
+                .reg
+                .get(),
+            indirect_fifo_status_1: self
+                .i3c_ec_sec_fw_recovery_if_indirect_fifo_status_1
+                .reg
+                .get(),
+            indirect_fifo_status_2: self
+                .i3c_ec_sec_fw_recovery_if_indirect_fifo_status_2
+                .reg
+                .get(),
+            recovery_ctrl: self.i3c_ec_sec_fw_recovery_if_recovery_ctrl.reg.get(),
+            rec_intf_cfg: self.i3c_ec_soc_mgmt_if_rec_intf_cfg.reg.get(),
+            rec_intf_reg_w1_c_access: self.i3c_ec_soc_mgmt_if_rec_intf_reg_w1_c_access.reg.get(),
+            indirect_fifo_data: self.indirect_fifo_data.clone(),
+            interrupt_status: self.interrupt_status.reg.get(),
+            interrupt_enable: self.interrupt_enable.reg.get(),
+            ibi_status: self.ibi_status.as_ref().map(|status| status.reg.get()),
+            stby_cr_intr_status: self.stby_cr_intr_status.reg.get(),
+            max_write_len: self.i3c_target.get_max_write_len(),
+            max_read_len: self.i3c_target.get_max_read_len(),
+        }))
+    }
+
+    fn restore_state(&mut self, state: &[u8]) -> std::io::Result<()> {
+        let state: I3cState = decode_state(state)?;
+        self.tti_rx_desc_queue_raw = state.tti_rx_desc_queue_raw;
+        self.tti_rx_data_raw = state.tti_rx_data_raw;
+        self.tti_rx_current = state.tti_rx_current;
+        self.tti_tx_desc_queue_raw = state.tti_tx_desc_queue_raw;
+        self.tti_tx_data_raw = state.tti_tx_data_raw;
+        self.tti_ibi_buffer = state.tti_ibi_buffer;
+        self.i3c_ec_sec_fw_recovery_if_prot_cap_2 = ReadWriteRegister::new(state.prot_cap_2);
+        self.i3c_ec_sec_fw_recovery_if_device_status_0 =
+            ReadWriteRegister::new(state.device_status_0);
+        self.i3c_ec_sec_fw_recovery_if_recovery_status =
+            ReadWriteRegister::new(state.recovery_status);
+        self.i3c_ec_sec_fw_recovery_if_indirect_fifo_ctrl_0 =
+            ReadWriteRegister::new(state.indirect_fifo_ctrl_0);
+        self.i3c_ec_sec_fw_recovery_if_indirect_fifo_ctrl_1 =
+            ReadWriteRegister::new(state.indirect_fifo_ctrl_1);
+        self.i3c_ec_sec_fw_recovery_if_indirect_fifo_status_0 =
+            ReadWriteRegister::new(state.indirect_fifo_status_0);
+        self.i3c_ec_sec_fw_recovery_if_indirect_fifo_status_1 =
+            ReadWriteRegister::new(state.indirect_fifo_status_1);
+        self.i3c_ec_sec_fw_recovery_if_indirect_fifo_status_2 =
+            ReadWriteRegister::new(state.indirect_fifo_status_2);
+        self.i3c_ec_sec_fw_recovery_if_recovery_ctrl = ReadWriteRegister::new(state.recovery_ctrl);
+        self.i3c_ec_soc_mgmt_if_rec_intf_cfg = ReadWriteRegister::new(state.rec_intf_cfg);
+        self.i3c_ec_soc_mgmt_if_rec_intf_reg_w1_c_access =
+            ReadWriteRegister::new(state.rec_intf_reg_w1_c_access);
+        self.indirect_fifo_data = state.indirect_fifo_data;
+        self.interrupt_status = ReadWriteRegister::new(state.interrupt_status);
+        self.interrupt_enable = ReadWriteRegister::new(state.interrupt_enable);
+        self.ibi_status = state.ibi_status.map(ReadWriteRegister::new);
+        self.stby_cr_intr_status = ReadWriteRegister::new(state.stby_cr_intr_status);
+        self.i3c_target
+            .set_max_xfer_lens(state.max_write_len, state.max_read_len);
+        self.check_interrupts();
+        Ok(())
+    }
     fn read_i3c_base_hci_version(&mut self) -> RvData {
         RvData::from(Self::HCI_VERSION)
     }
@@ -619,6 +771,46 @@ impl I3cPeripheral for I3c {
         ReadWriteRegister::new(val.value)
     }
 
+    fn read_i3c_ec_stdby_ctrl_mode_stby_cr_intr_status(
+        &mut self,
+    ) -> ReadWriteRegister<u32, StbyCrIntrStatus::Register> {
+        if self.i3c_target.take_ccc_params_modified() {
+            self.stby_cr_intr_status
+                .reg
+                .modify(StbyCrIntrStatus::CccParamModifiedStat::SET);
+        }
+        ReadWriteRegister::new(self.stby_cr_intr_status.reg.get())
+    }
+
+    fn write_i3c_ec_stdby_ctrl_mode_stby_cr_intr_status(
+        &mut self,
+        val: ReadWriteRegister<u32, StbyCrIntrStatus::Register>,
+    ) {
+        // write 1 to clear
+        let current = self.stby_cr_intr_status.reg.get();
+        self.stby_cr_intr_status.reg.set(current & !val.reg.get());
+    }
+
+    fn read_i3c_ec_stdby_ctrl_mode_stby_cr_mwl(
+        &mut self,
+    ) -> ReadWriteRegister<u32, StbyCrMwl::Register> {
+        ReadWriteRegister::new(
+            StbyCrMwl::Mwl
+                .val(self.i3c_target.get_max_write_len().into())
+                .value,
+        )
+    }
+
+    fn read_i3c_ec_stdby_ctrl_mode_stby_cr_mrl(
+        &mut self,
+    ) -> ReadWriteRegister<u32, StbyCrMrl::Register> {
+        ReadWriteRegister::new(
+            (StbyCrMrl::Mrl.val(self.i3c_target.get_max_read_len().into())
+                + StbyCrMrl::Ibil.val(Self::IBI_PAYLOAD_LIMIT))
+            .value,
+        )
+    }
+
     fn read_i3c_ec_tti_extcap_header(&mut self) -> ReadWriteRegister<u32, ExtcapHeader::Register> {
         ReadWriteRegister::new(ExtcapHeader::CapId.val(0xc4).value)
     }
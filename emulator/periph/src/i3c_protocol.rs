@@ -13,8 +13,9 @@ Abstract:
 --*/
 
 use caliptra_mcu_testing_common::i3c::{
-    DynamicI3cAddress, I3cBusCommand, I3cBusResponse, I3cError, I3cTcriCommandXfer,
-    I3cTcriResponseXfer,
+    DynamicI3cAddress, I3cBusCommand, I3cBusResponse, I3cError, I3cTcriCommand, I3cTcriCommandXfer,
+    I3cTcriResponseXfer, ResponseDescriptor, CCC_DIRECT_GETMRL, CCC_DIRECT_GETMWL,
+    CCC_DIRECT_SETMRL, CCC_DIRECT_SETMWL,
 };
 use std::collections::VecDeque;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
@@ -31,6 +32,8 @@ pub struct I3cController {
     running: Arc<AtomicBool>,
     // used for testing
     incoming_counter: Arc<AtomicUsize>,
+    // commands are pumped by the owner rather than a controller thread
+    stepped: bool,
 }
 
 impl Drop for I3cController {
@@ -47,9 +50,18 @@ impl I3cController {
             tx: Some(tx),
             running: Arc::new(AtomicBool::new(false)),
             incoming_counter: Arc::new(AtomicUsize::new(0)),
+            stepped: false,
         }
     }
 
+    /// Leaves command delivery to the owner, which calls [`Self::receive_command`],
+    /// [`Self::deliver_command`] and [`Self::send_responses`] itself so that
+    /// commands reach the targets at reproducible points. [`Self::start`] then
+    /// does not spawn a processing thread.
+    pub fn set_stepped(&mut self) {
+        self.stepped = true;
+    }
+
     /// Stops the thread that processes incoming commands and sends responses.
     pub fn stop(&mut self) {
         self.running.store(false, Ordering::Relaxed);
@@ -58,6 +70,10 @@ impl I3cController {
     /// Spawns a thread that processes incoming commands and sends outgoing responses as
     /// long as this I3cController is in scope.
     pub fn start(&mut self) -> JoinHandle<()> {
+        if self.stepped {
+            self.running.store(true, Ordering::Relaxed);
+            return thread::spawn(|| {});
+        }
         let rx = self.rx.take().unwrap();
         let tx = self.tx.take().unwrap();
         self.running.store(true, Ordering::Relaxed);
@@ -81,11 +97,24 @@ impl I3cController {
     /// Run the one round of the incoming loop (without blocking or sleeping).
     /// This is useful for testing or running in a polling loop, rather than spawning a thread.
     pub fn run_once(&mut self) {
-        if let Some(rx) = self.rx.as_ref() {
-            if let Ok(cmd) = rx.try_recv() {
-                I3cController::incoming(self.targets.clone(), self.incoming_counter.clone(), cmd);
-            }
+        if let Some(cmd) = self.receive_command() {
+            self.deliver_command(cmd);
         }
+        self.send_responses();
+    }
+
+    /// Takes the next command sent to the bus, if any, without delivering it.
+    pub fn receive_command(&mut self) -> Option<I3cBusCommand> {
+        self.rx.as_ref()?.try_recv().ok()
+    }
+
+    /// Relays a command to the target it is addressed to.
+    pub fn deliver_command(&mut self, cmd: I3cBusCommand) {
+        I3cController::incoming(self.targets.clone(), self.incoming_counter.clone(), cmd);
+    }
+
+    /// Forwards pending target responses and IBIs to the bus.
+    pub fn send_responses(&mut self) {
         I3cController::tcri_receive_all(self.targets.clone())
             .iter()
             .for_each(|resp| {
@@ -230,6 +259,11 @@ impl I3cTarget {
     }
 
     pub fn send_command(&mut self, cmd: I3cTcriCommandXfer) {
+        // CCCs are handled by the target interface itself and never reach the RX queue.
+        if let Some(ccc) = cmd.cmd.ccc() {
+            self.target.lock().unwrap().handle_ccc(ccc, &cmd);
+            return;
+        }
         // Release the target lock before calling incoming() to avoid a
         // lock-ordering inversion with the emulator step lock.
         self.target.lock().unwrap().rx_buffer.push_back(cmd);
@@ -262,20 +296,95 @@ impl I3cTarget {
     pub fn send_ibi(&mut self, mdb: u8) {
         self.target.lock().unwrap().ibi_buffer.push_back(mdb)
     }
+
+    /// Maximum write length, as last set by SETMWL.
+    pub fn get_max_write_len(&self) -> u16 {
+        self.target.lock().unwrap().max_write_len
+    }
+
+    /// Maximum read length, as last set by SETMRL.
+    pub fn get_max_read_len(&self) -> u16 {
+        self.target.lock().unwrap().max_read_len
+    }
+
+    /// Restore the maximum write and read lengths without flagging a CCC update.
+    pub fn set_max_xfer_lens(&mut self, max_write_len: u16, max_read_len: u16) {
+        let mut target = self.target.lock().unwrap();
+        target.max_write_len = max_write_len;
+        target.max_read_len = max_read_len;
+    }
+
+    /// Returns whether SETMWL or SETMRL was received since the last call.
+    pub fn take_ccc_params_modified(&mut self) -> bool {
+        std::mem::take(&mut self.target.lock().unwrap().ccc_params_modified)
+    }
 }
 
-#[derive(Clone, Default)]
+/// Reset value of the target's maximum write and read lengths.
+const DEFAULT_MAX_XFER_LEN: u16 = 0x100;
+
+#[derive(Clone)]
 pub struct I3cTargetDevice {
     dynamic_address: Option<DynamicI3cAddress>,
     rx_buffer: VecDeque<I3cTcriCommandXfer>,
     tx_buffer: VecDeque<I3cTcriResponseXfer>,
     ibi_buffer: VecDeque<u8>,
+    max_write_len: u16,
+    max_read_len: u16,
+    ccc_params_modified: bool,
+}
+
+impl Default for I3cTargetDevice {
+    fn default() -> Self {
+        Self {
+            dynamic_address: None,
+            rx_buffer: VecDeque::new(),
+            tx_buffer: VecDeque::new(),
+            ibi_buffer: VecDeque::new(),
+            max_write_len: DEFAULT_MAX_XFER_LEN,
+            max_read_len: DEFAULT_MAX_XFER_LEN,
+            ccc_params_modified: false,
+        }
+    }
+}
+
+impl I3cTargetDevice {
+    fn handle_ccc(&mut self, ccc: u8, cmd: &I3cTcriCommandXfer) {
+        let data = match &cmd.cmd {
+            I3cTcriCommand::Immediate(imm) => vec![imm.data_byte_1(), imm.data_byte_2()],
+            _ => cmd.data.clone(),
+        };
+        match ccc {
+            // The defining byte of SETMRL may be followed by the IBI payload size, which is ignored.
+            CCC_DIRECT_SETMWL | CCC_DIRECT_SETMRL if data.len() >= 2 => {
+                let len = u16::from_be_bytes([data[0], data[1]]);
+                if ccc == CCC_DIRECT_SETMWL {
+                    self.max_write_len = len;
+                } else {
+                    self.max_read_len = len;
+                }
+                self.ccc_params_modified = true;
+            }
+            CCC_DIRECT_GETMWL => self.push_ccc_response(&self.max_write_len.to_be_bytes()),
+            CCC_DIRECT_GETMRL => self.push_ccc_response(&self.max_read_len.to_be_bytes()),
+            _ => println!("[I3C] Ignoring unsupported CCC {:#04x}", ccc),
+        }
+    }
+
+    fn push_ccc_response(&mut self, data: &[u8]) {
+        let mut resp = ResponseDescriptor::default();
+        resp.set_data_length(data.len() as u16);
+        self.tx_buffer.push_back(I3cTcriResponseXfer {
+            resp,
+            data: data.to_vec(),
+        });
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use caliptra_mcu_testing_common::i3c::{I3cTcriCommand, ImmediateDataTransferCommand};
+    use caliptra_mcu_testing_common::i3c::{ImmediateDataTransferCommand, ReguDataTransferCommand};
     use std::sync::mpsc::channel;
     use zerocopy::FromBytes;
 
@@ -302,4 +411,36 @@ mod test {
         controller.run_once();
         assert_eq!(1, controller.incoming_counter.load(Ordering::Relaxed));
     }
+
+    #[test]
+    fn i3c_ccc_max_len_test() {
+        let mut target = I3cTarget::default();
+        target.set_address(DynamicI3cAddress::new(8).unwrap());
+        assert_eq!(DEFAULT_MAX_XFER_LEN, target.get_max_write_len());
+        assert!(!target.take_ccc_params_modified());
+
+        let ccc_xfer = |ccc: u8, data: Vec<u8>| {
+            let mut cmd = ReguDataTransferCommand::read_from_bytes(&[0; 8]).unwrap();
+            cmd.set_cp(1);
+            cmd.set_cmd(ccc);
+            cmd.set_data_length(data.len() as u16);
+            I3cTcriCommandXfer {
+                cmd: I3cTcriCommand::Regular(cmd),
+                data,
+            }
+        };
+        target.send_command(ccc_xfer(CCC_DIRECT_SETMWL, vec![0x00, 0xfa]));
+        target.send_command(ccc_xfer(CCC_DIRECT_SETMRL, vec![0x00, 0xc8, 0x10]));
+        assert_eq!(250, target.get_max_write_len());
+        assert_eq!(200, target.get_max_read_len());
+        assert!(target.take_ccc_params_modified());
+        assert!(!target.take_ccc_params_modified());
+        // CCCs are not queued for the target's RX handling
+        assert!(target.read_command().is_none());
+
+        target.send_command(ccc_xfer(CCC_DIRECT_GETMWL, vec![]));
+        let resp = target.get_response().unwrap();
+        assert_eq!(2, resp.resp.data_length());
+        assert_eq!(vec![0x00, 0xfa], resp.data);
+    }
 }
@@ -20,8 +20,11 @@ use std::rc::Rc;
 use caliptra_emu_bus::ReadWriteRegister;
 use caliptra_mcu_emulator_registers_generated::lc::LcGenerated;
 use caliptra_mcu_registers_generated::lc_ctrl;
+use serde::{Deserialize, Serialize};
 use tock_registers::interfaces::Readable;
 
+use crate::snapshot::{decode_state, encode_state};
+
 #[cfg(test)]
 use crate::otp_scramble;
 use crate::otp_unscramble;
@@ -139,6 +142,18 @@ fn validate_transition(from: u32, to: u32) -> Option<TokenRequirement> {
     }
 }
 
+/// Controller state captured in emulator snapshots. The fuses themselves are
+/// saved with the OTP controller.
+#[derive(Deserialize, Serialize)]
+struct LcCtrlState {
+    status: u32,
+    lc_state_index: u32,
+    lc_transition_cnt: u32,
+    mutex_claimed: bool,
+    transition_target: u32,
+    token: [u32; 4],
+}
+
 pub struct LcCtrl {
     status: ReadWriteRegister<u32, lc_ctrl::bits::Status::Register>,
     /// Current lifecycle state index (5-bit, 0-21).
@@ -334,6 +349,28 @@ impl caliptra_mcu_emulator_registers_generated::lc::LcPeripheral for LcCtrl {
         self.reload_from_otp();
     }
 
+    fn save_state(&self) -> Option<Vec<u8>> {
+        Some(encode_state(&LcCtrlState {
+            status: self.status.reg.get(),
+            lc_state_index: self.lc_state_index,
+            lc_transition_cnt: self.lc_transition_cnt,
+            mutex_claimed: self.mutex_claimed,
+            transition_target: self.transition_target,
+            token: self.token,
+        }))
+    }
+
+    fn restore_state(&mut self, state: &[u8]) -> std::io::Result<()> {
+        let state: LcCtrlState = decode_state(state)?;
+        self.status = state.status.into();
+        self.lc_state_index = state.lc_state_index;
+        self.lc_transition_cnt = state.lc_transition_cnt;
+        self.mutex_claimed = state.mutex_claimed;
+        self.transition_target = state.transition_target;
+        self.token = state.token;
+        Ok(())
+    }
+
     fn read_status(&mut self) -> ReadWriteRegister<u32, lc_ctrl::bits::Status::Register> {
         ReadWriteRegister::new(self.status.reg.get())
     }
@@ -17,6 +17,7 @@ mod caliptra_to_ext_bus;
 mod doe_mbox;
 pub mod ecc_ram;
 mod emu_ctrl;
+mod fault_injection;
 mod flash_ctrl;
 mod i3c;
 pub(crate) mod i3c_protocol;
@@ -24,17 +25,23 @@ mod lc_ctrl;
 mod mci;
 mod mcu_mbox0;
 mod otp;
+mod pcie_vdm;
 pub use caliptra_mcu_otp_digest::{
     caliptra_mcu_otp_digest, otp_scramble, otp_unscramble, OTP_SCRAMBLE_KEYS,
 };
 mod reset_reason;
 mod root_bus;
+pub mod snapshot;
 mod uart;
 
 pub use axicdma::AxiCDMA;
 pub use caliptra_to_ext_bus::CaliptraToExtBus;
 pub use doe_mbox::{DoeMboxPeriph, DummyDoeMbox};
 pub use emu_ctrl::EmuCtrl;
+pub use fault_injection::{
+    AddressRange, FaultHook, FaultInjector, FaultKind, FaultOperation, FaultPlan, FaultSpec,
+    InjectedFault,
+};
 pub use flash_ctrl::DummyFlashCtrl;
 pub use i3c::I3c;
 pub use i3c_protocol::*;
@@ -42,6 +49,7 @@ pub use lc_ctrl::LcCtrl;
 pub use mci::Mci;
 pub use mcu_mbox0::{MciMailboxRequester, McuMailbox0External, McuMailbox0Internal};
 pub use otp::{Otp, OtpArgs};
+pub use pcie_vdm::PcieVdmController;
 pub use reset_reason::ResetReasonEmulator;
 pub use root_bus::{McuRootBus, McuRootBusArgs, McuRootBusOffsets};
 pub use uart::Uart;
@@ -2,6 +2,7 @@
 
 use crate::mcu_mbox0::McuMailbox0Internal;
 use crate::reset_reason::ResetReasonEmulator;
+use crate::snapshot::{decode_state, encode_state};
 use caliptra_emu_bus::{ActionHandle, BusMmio, Clock, ReadWriteRegister, Timer, TimerAction};
 use caliptra_emu_cpu::Irq;
 use caliptra_emu_periph::SocToCaliptraBus;
@@ -12,6 +13,7 @@ use caliptra_mcu_registers_generated::mci::bits::{
     SecurityState, WdtStatus, WdtTimer1Ctrl, WdtTimer1En, WdtTimer2Ctrl, WdtTimer2En,
 };
 use caliptra_registers::soc_ifc::RegisterBlock;
+use serde::{Deserialize, Serialize};
 use std::{cell::Cell, cell::RefCell, rc::Rc};
 use tock_registers::interfaces::{ReadWriteable, Readable};
 
@@ -24,6 +26,43 @@ fn clamp_timer_period(period: u64) -> u64 {
     period.min((i64::MAX as u64) - 1)
 }
 
+/// MCI state captured in emulator snapshots.
+///
+/// Watchdog timers that were running are re-armed with their full timeout
+/// period on restore, since the time already elapsed is not tracked.
+#[derive(Deserialize, Serialize)]
+struct MciState {
+    flow_status: u32,
+    fw_error_fatal: u32,
+    fw_error_non_fatal: u32,
+    generic_input_wires: [u32; 2],
+    reset_reason: u32,
+    reset_request: u32,
+    reset_status: u32,
+    security_state: u32,
+    wdt_cfg: Vec<u32>,
+    wdt_status: u32,
+    wdt_timer1_en: u32,
+    wdt_timer1_ctrl: u32,
+    wdt_timer1_timeout_period: [u32; 2],
+    wdt_timer2_en: u32,
+    wdt_timer2_ctrl: u32,
+    wdt_timer2_timeout_period: [u32; 2],
+    global_intr_en: u32,
+    error0_internal_intr: u32,
+    error0_intr_en: u32,
+    error0_intr_trig: u32,
+    notif0_internal_intr: u32,
+    notif0_intr_en: u32,
+    notif0_intr_trig: u32,
+    wdt_timer1_pending: bool,
+    wdt_timer2_pending: bool,
+    reset_cycle_complete: bool,
+    reset_requested: bool,
+    mtimecmp: u64,
+    cptra_boot_go: bool,
+}
+
 pub struct Mci {
     ext_mci_regs: caliptra_emu_periph::mci::Mci,
     generated: MciGenerated,
@@ -184,6 +223,109 @@ impl MciPeripheral for Mci {
         self.cptra_boot_go.set(true);
     }
 
+    fn save_state(&self) -> Option<Vec<u8>> {
+        let regs = self.ext_mci_regs.regs.borrow();
+        Some(encode_state(&MciState {
+            flow_status: regs.flow_status,
+            fw_error_fatal: regs.fw_error_fatal,
+            fw_error_non_fatal: regs.fw_error_non_fatal,
+            generic_input_wires: regs.generic_input_wires,
+            reset_reason: regs.reset_reason,
+            reset_request: regs.reset_request,
+            reset_status: regs.reset_status,
+            security_state: regs.security_state,
+            wdt_cfg: regs.wdt_cfg.to_vec(),
+            wdt_status: regs.wdt_status,
+            wdt_timer1_en: regs.wdt_timer1_en,
+            wdt_timer1_ctrl: regs.wdt_timer1_ctrl,
+            wdt_timer1_timeout_period: regs.wdt_timer1_timeout_period,
+            wdt_timer2_en: regs.wdt_timer2_en,
+            wdt_timer2_ctrl: regs.wdt_timer2_ctrl,
+            wdt_timer2_timeout_period: regs.wdt_timer2_timeout_period,
+            global_intr_en: regs.intr_block_rf_global_intr_en_r,
+            error0_internal_intr: regs.intr_block_rf_error0_internal_intr_r,
+            error0_intr_en: regs.intr_block_rf_error0_intr_en_r,
+            error0_intr_trig: regs.intr_block_rf_error0_intr_trig_r,
+            notif0_internal_intr: regs.intr_block_rf_notif0_internal_intr_r,
+            notif0_intr_en: regs.intr_block_rf_notif0_intr_en_r,
+            notif0_intr_trig: regs.intr_block_rf_notif0_intr_trig_r,
+            wdt_timer1_pending: self.op_wdt_timer1_expired_action.is_some(),
+            wdt_timer2_pending: self.op_wdt_timer2_expired_action.is_some(),
+            reset_cycle_complete: self.reset_cycle_complete,
+            reset_requested: self.reset_requested,
+            mtimecmp: self.mtimecmp,
+            cptra_boot_go: self.cptra_boot_go.get(),
+        }))
+    }
+
+    fn restore_state(&mut self, state: &[u8]) -> std::io::Result<()> {
+        let state: MciState = decode_state(state)?;
+        {
+            let mut regs = self.ext_mci_regs.regs.borrow_mut();
+            if state.wdt_cfg.len() != regs.wdt_cfg.len() {
+                return Err(crate::snapshot::invalid_data(
+                    "MCI snapshot has a mismatched WDT_CFG size".into(),
+                ));
+            }
+            regs.flow_status = state.flow_status;
+            regs.fw_error_fatal = state.fw_error_fatal;
+            regs.fw_error_non_fatal = state.fw_error_non_fatal;
+            regs.generic_input_wires = state.generic_input_wires;
+            regs.reset_reason = state.reset_reason;
+            regs.reset_request = state.reset_request;
+            regs.reset_status = state.reset_status;
+            regs.security_state = state.security_state;
+            regs.wdt_cfg.copy_from_slice(&state.wdt_cfg);
+            regs.wdt_status = state.wdt_status;
+            regs.wdt_timer1_en = state.wdt_timer1_en;
+            regs.wdt_timer1_ctrl = state.wdt_timer1_ctrl;
+            regs.wdt_timer1_timeout_period = state.wdt_timer1_timeout_period;
+            regs.wdt_timer2_en = state.wdt_timer2_en;
+            regs.wdt_timer2_ctrl = state.wdt_timer2_ctrl;
+            regs.wdt_timer2_timeout_period = state.wdt_timer2_timeout_period;
+            regs.intr_block_rf_global_intr_en_r = state.global_intr_en;
+            regs.intr_block_rf_error0_internal_intr_r = state.error0_internal_intr;
+            regs.intr_block_rf_error0_intr_en_r = state.error0_intr_en;
+            regs.intr_block_rf_error0_intr_trig_r = state.error0_intr_trig;
+            regs.intr_block_rf_notif0_internal_intr_r = state.notif0_internal_intr;
+            regs.intr_block_rf_notif0_intr_en_r = state.notif0_intr_en;
+            regs.intr_block_rf_notif0_intr_trig_r = state.notif0_intr_trig;
+        }
+        self.reset_cycle_complete = state.reset_cycle_complete;
+        self.reset_requested = state.reset_requested;
+        self.mtimecmp = state.mtimecmp;
+        self.cptra_boot_go.set(state.cptra_boot_go);
+        self.arm_mtime_interrupt();
+
+        if state.wdt_timer1_pending {
+            let period = ((state.wdt_timer1_timeout_period[1] as u64) << 32)
+                | state.wdt_timer1_timeout_period[0] as u64;
+            Mci::reschedule_poll(
+                &mut self.timer,
+                &mut self.op_wdt_timer1_expired_action,
+                period,
+            );
+        } else {
+            Mci::cancel_poll(&mut self.timer, &mut self.op_wdt_timer1_expired_action);
+        }
+        if state.wdt_timer2_pending {
+            let period = ((state.wdt_timer2_timeout_period[1] as u64) << 32)
+                | state.wdt_timer2_timeout_period[0] as u64;
+            Mci::reschedule_poll(
+                &mut self.timer,
+                &mut self.op_wdt_timer2_expired_action,
+                period,
+            );
+        } else {
+            Mci::cancel_poll(&mut self.timer, &mut self.op_wdt_timer2_expired_action);
+        }
+        if self.reset_requested {
+            self.timer.schedule_poll_in(1);
+        }
+        self.update_mci_irq();
+        Ok(())
+    }
+
     fn read_mci_reg_generic_input_wires(&mut self, index: usize) -> caliptra_emu_types::RvData {
         self.ext_mci_regs.regs.borrow().generic_input_wires[index]
     }
@@ -1,5 +1,6 @@
 // Licensed under the Apache-2.0 license
 
+use crate::fault_injection::{FaultHook, FaultKind, FaultOperation};
 use caliptra_emu_bus::BusError;
 use caliptra_emu_bus::{Bus, Clock, Ram, ReadOnlyRegister, ReadWriteRegister, Timer};
 use caliptra_emu_types::{RvAddr, RvSize};
@@ -128,6 +129,9 @@ pub struct MciMailboxImpl {
 
     /// Workaround: temporarily lift the check for mailbox requester to support integration tests
     pub test_mcu_mbox_driver: bool,
+
+    /// Fault injection hook for commands handed to the MCU
+    fault_hook: FaultHook,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -192,6 +196,7 @@ impl MciMailboxImpl {
             timer: Timer::new(clock),
             max_dlen_in_lock_session: 0,
             test_mcu_mbox_driver: false,
+            fault_hook: FaultHook::default(),
         }
     }
 
@@ -207,6 +212,10 @@ impl MciMailboxImpl {
         ));
     }
 
+    pub fn set_fault_hook(&mut self, hook: FaultHook) {
+        self.fault_hook = hook;
+    }
+
     pub fn set_requester(&mut self, requester: MciMailboxRequester) {
         self.requester = requester;
     }
@@ -396,6 +405,13 @@ impl MciMailboxImpl {
             if self.test_mcu_mbox_driver
                 || matches!(self.user.reg.get().into(), MciMailboxRequester::SocAgent(_))
             {
+                let fault = self
+                    .fault_hook
+                    .check(FaultOperation::Execute, None, self.timer.now());
+                if fault == Some(FaultKind::Timeout) {
+                    // The MCU is never told about the command.
+                    return;
+                }
                 self.irq = true;
                 self.last_irq_event = Some(IrqEventToMcu::Mbox0CmdAvailable);
                 self.timer.schedule_poll_in(1);
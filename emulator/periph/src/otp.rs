@@ -14,6 +14,8 @@ Abstract:
 
 --*/
 use crate::ecc_ram::EccRam;
+use crate::fault_injection::{FaultHook, FaultKind, FaultOperation};
+use crate::snapshot::{decode_state, encode_state, invalid_data};
 use caliptra_emu_bus::{Clock, ReadWriteRegister, Timer};
 use caliptra_emu_types::{RvAddr, RvData};
 use caliptra_image_types::FwVerificationPqcKeyType;
@@ -48,6 +50,19 @@ struct OtpState {
     ecc_rams: Vec<Option<EccRam>>,
 }
 
+/// Controller state captured in emulator snapshots, on top of the fuse
+/// contents that are persisted between runs.
+#[derive(Deserialize, Serialize)]
+struct OtpSnapshot {
+    fuses: OtpState,
+    direct_access_address: u32,
+    direct_access_buffer: u32,
+    direct_access_buffer_hi: u32,
+    direct_access_cmd: u32,
+    status: u32,
+    err_codes: Vec<u32>,
+}
+
 #[derive(Default, Clone)]
 pub struct OtpArgs {
     pub file_name: Option<PathBuf>,
@@ -93,6 +108,7 @@ pub struct Otp {
     /// Per-partition ECC RAM. `ecc_rams[i]` is `Some(…)` when partition `i`
     /// has ECC protection enabled.
     ecc_rams: Vec<Option<EccRam>>,
+    fault_hook: FaultHook,
 }
 
 // Ensure that we save the state before we drop the OTP instance.
@@ -173,6 +189,7 @@ impl Otp {
             digests: [0; fuses::OTP_PARTITIONS.len() * 2],
             generated: OtpGenerated::default(),
             ecc_rams,
+            fault_hook: FaultHook::default(),
         };
         otp.read_from_file()?;
         if let Some(mut vendor_pk_hash) = args.vendor_pk_hash {
@@ -307,6 +324,11 @@ impl Otp {
         self.ecc_rams.get_mut(partition_idx)?.as_mut()
     }
 
+    /// Attach a fault-injection hook that is consulted on every DAI read.
+    pub fn set_fault_hook(&mut self, hook: FaultHook) {
+        self.fault_hook = hook;
+    }
+
     /// Read the current DAI error code from the configured register.
     fn dai_err_code(&self) -> u32 {
         self.err_codes[self.dai_err_code_register]
@@ -426,6 +448,7 @@ impl Otp {
 }
 
 /// OTP error codes matching the hardware definition.
+const OTP_ERR_MACRO_ECC_UNCORR: u32 = 3;
 const OTP_ERR_MACRO_WRITE_BLANK: u32 = 4;
 
 /// Number of OTP err_code registers (one per partition + DAI + LCI agents).
@@ -497,6 +520,40 @@ impl caliptra_mcu_emulator_registers_generated::otp::OtpPeripheral for Otp {
         Some(&mut self.generated)
     }
 
+    fn save_state(&self) -> Option<Vec<u8>> {
+        Some(encode_state(&OtpSnapshot {
+            fuses: self.get_state(),
+            direct_access_address: self.direct_access_address,
+            direct_access_buffer: self.direct_access_buffer,
+            direct_access_buffer_hi: self.direct_access_buffer_hi,
+            direct_access_cmd: self.direct_access_cmd.reg.get(),
+            status: self.status.reg.get(),
+            err_codes: self.err_codes.clone(),
+        }))
+    }
+
+    fn restore_state(&mut self, state: &[u8]) -> std::io::Result<()> {
+        let state: OtpSnapshot = decode_state(state)?;
+        if state.fuses.partitions.len() != TOTAL_SIZE
+            || state.fuses.digests.len() != self.digests.len()
+            || state.err_codes.len() != NUM_ERR_CODE_REGISTERS
+        {
+            return Err(invalid_data("OTP snapshot has the wrong layout".into()));
+        }
+        self.load_state(&state.fuses);
+        self.direct_access_address = state.direct_access_address;
+        self.direct_access_buffer = state.direct_access_buffer;
+        self.direct_access_buffer_hi = state.direct_access_buffer_hi;
+        self.direct_access_cmd.reg.set(state.direct_access_cmd);
+        self.status.reg.set(state.status);
+        self.err_codes = state.err_codes;
+        // A DAI command was in flight; finish it on the next poll.
+        if state.direct_access_cmd != 0 {
+            self.timer.schedule_poll_in(2);
+        }
+        Ok(())
+    }
+
     fn read_otp_status(&mut self) -> caliptra_emu_bus::ReadWriteRegister<u32, OtpStatus::Register> {
         ReadWriteRegister::new(self.status.reg.get())
     }
@@ -852,6 +909,28 @@ impl caliptra_mcu_emulator_registers_generated::otp::OtpPeripheral for Otp {
                     }
                 }
             }
+            match self
+                .fault_hook
+                .check(FaultOperation::Read, Some(addr as u32), self.timer.now())
+            {
+                Some(FaultKind::Error) => {
+                    self.direct_access_buffer = 0;
+                    self.direct_access_buffer_hi = 0;
+                    self.set_dai_err_code(OTP_ERR_MACRO_ECC_UNCORR);
+                    self.status
+                        .reg
+                        .set(OtpStatus::DaiIdle::SET.value | OtpStatus::DaiError::SET.value);
+                }
+                Some(FaultKind::BitFlip { bit }) => {
+                    let bit = bit % 64;
+                    if bit < 32 {
+                        self.direct_access_buffer ^= 1 << bit;
+                    } else {
+                        self.direct_access_buffer_hi ^= 1 << (bit - 32);
+                    }
+                }
+                _ => {}
+            }
             // reset direct access
             self.direct_access_cmd.reg.set(0);
             self.direct_access_address = 0;
@@ -1115,6 +1194,38 @@ mod test {
         assert_eq!(otp.dai_err_code(), OTP_ERR_MACRO_WRITE_BLANK);
     }
 
+    #[test]
+    fn test_read_fault_injection() {
+        use crate::fault_injection::{FaultInjector, FaultPlan};
+
+        let clock = Clock::new();
+        let mut otp = Otp::new(&clock, OtpArgs::default()).unwrap();
+        let addr = fuses::VENDOR_TEST_PARTITION_BYTE_OFFSET as u32;
+        dai_write(&mut otp, addr, 0x1234_5678);
+
+        let injector = FaultInjector::new(
+            FaultPlan::from_json(&format!(
+                r#"{{ "faults": [
+                    {{ "peripheral": "otp", "operation": "read",
+                       "address": {{ "start": {addr}, "end": {} }},
+                       "fault": {{ "bit_flip": {{ "bit": 4 }} }} }},
+                    {{ "peripheral": "otp", "operation": "read",
+                       "occurrence": 3, "fault": "error" }}
+                ] }}"#,
+                addr + 4
+            ))
+            .unwrap(),
+        );
+        otp.set_fault_hook(injector.hook("otp"));
+
+        assert_eq!(dai_read(&mut otp, addr), 0x1234_5668);
+        assert_eq!(dai_read(&mut otp, addr), 0x1234_5678);
+        dai_read(&mut otp, addr);
+        assert_ne!(otp.status.reg.get() & OtpStatus::DaiError::SET.value, 0);
+        assert_eq!(otp.dai_err_code(), OTP_ERR_MACRO_ECC_UNCORR);
+        assert_eq!(injector.injected().len(), 2);
+    }
+
     /// Helper: DAI write a 32-bit value at the given byte address.
     fn dai_write(otp: &mut Otp, addr: u32, val: u32) {
         otp.write_dai_wdata_rf_direct_access_wdata_0(val);
@@ -1366,4 +1477,31 @@ mod test {
         assert_eq!(read_lo, plaintext_lo, "DAI read lo should return plaintext");
         assert_eq!(read_hi, plaintext_hi, "DAI read hi should return plaintext");
     }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let clock = Clock::new();
+        let mut otp = Otp::new(
+            &clock,
+            OtpArgs {
+                raw_memory: Some(vec![0x5a; 64]),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        otp.write_direct_access_address(0x40u32.into());
+        let state = otp.save_state().unwrap();
+
+        let mut restored = Otp::new(&clock, OtpArgs::default()).unwrap();
+        restored.restore_state(&state).unwrap();
+        assert_eq!(
+            *restored.partitions.borrow(),
+            *otp.partitions.borrow(),
+            "Fuse contents should be restored"
+        );
+        assert_eq!(restored.direct_access_address, 0x40);
+        assert_eq!(restored.status.reg.get(), otp.status.reg.get());
+
+        assert!(restored.restore_state(b"{}").is_err());
+    }
 }
@@ -0,0 +1,102 @@
+/*++
+
+Licensed under the Apache-2.0 license.
+
+File Name:
+
+    pcie_vdm.rs
+
+Abstract:
+
+    PCIe VDM endpoint for the Caliptra Emulator Library. Carries MCTP over PCIe VDM
+    TLPs between the PCIe VDM socket and the DOE mailbox.
+
+--*/
+
+use crate::DoeMboxPeriph;
+use caliptra_mcu_testing_common::pcie_vdm_socket_server::decode_tlp;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+pub struct PcieVdmController {
+    doe_mbox: DoeMboxPeriph,
+    rx: Option<Receiver<Vec<u8>>>,
+    tx: Option<Sender<Vec<u8>>>,
+    running: Arc<AtomicBool>,
+}
+
+impl Drop for PcieVdmController {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl PcieVdmController {
+    pub fn new(
+        doe_mbox: DoeMboxPeriph,
+        rx: Receiver<Vec<u8>>,
+        tx: Sender<Vec<u8>>,
+    ) -> PcieVdmController {
+        PcieVdmController {
+            doe_mbox,
+            rx: Some(rx),
+            tx: Some(tx),
+            running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Stops the thread that moves TLPs between the socket and the mailbox.
+    pub fn stop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+
+    /// Spawns a thread that moves TLPs between the socket and the DOE mailbox as
+    /// long as this PcieVdmController is in scope.
+    ///
+    /// The DOE mailbox is half duplex: TLPs from the endpoint are drained first, and a
+    /// TLP from the host is only written once the MCU has picked up the previous one.
+    pub fn start(&mut self) -> JoinHandle<()> {
+        let rx = self.rx.take().unwrap();
+        let tx = self.tx.take().unwrap();
+        self.running.store(true, Ordering::Relaxed);
+        let running = self.running.clone();
+        let mut doe_mbox = self.doe_mbox.clone();
+        thread::spawn(move || {
+            let mut pending = VecDeque::new();
+            while running.load(Ordering::Relaxed) {
+                match doe_mbox.read_data() {
+                    Ok(Some(tlp)) => match decode_tlp(&tlp) {
+                        Some(_) => {
+                            if tx.send(tlp).is_err() {
+                                break;
+                            }
+                        }
+                        None => println!("PCIE_VDM: Dropping invalid TLP from endpoint"),
+                    },
+                    Ok(None) => {}
+                    Err(e) => println!("PCIE_VDM: {}", e),
+                }
+
+                if let Ok(tlp) = rx.recv_timeout(Duration::from_millis(1)) {
+                    pending.push_back(tlp);
+                }
+                if !doe_mbox.is_data_pending() {
+                    if let Some(tlp) = pending.pop_front() {
+                        if let Err(e) = doe_mbox.write_data(Self::pad_to_dword(tlp)) {
+                            println!("PCIE_VDM: {}", e);
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn pad_to_dword(mut tlp: Vec<u8>) -> Vec<u8> {
+        tlp.resize(tlp.len().next_multiple_of(4), 0);
+        tlp
+    }
+}
@@ -0,0 +1,589 @@
+/*++
+
+Licensed under the Apache-2.0 license.
+
+File Name:
+
+    snapshot.rs
+
+Abstract:
+
+    Emulator snapshots: serializable copies of CPU registers, memories and
+    peripheral state, plus helpers shared by peripherals that save and
+    restore their own state.
+
+--*/
+
+use crate::root_bus::McuRootBus;
+use caliptra_emu_bus::{Bus, Ram};
+use caliptra_emu_cpu::xreg_file::XReg;
+use caliptra_emu_cpu::Cpu;
+use caliptra_emu_periph::{CaliptraRootBus, MailboxRequester};
+use caliptra_emu_types::{RvAddr, RvSize};
+use caliptra_mcu_emulator_registers_generated::root_bus::AutoRootBus;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+use std::rc::Rc;
+
+/// Bumped whenever the snapshot format changes incompatibly.
+pub const SNAPSHOT_VERSION: u32 = 2;
+
+/// Granularity at which a [`MemoryImage`] skips unused memory.
+const CHUNK_SIZE: usize = 4096;
+
+/// Serializable copy of a RAM or storage region.
+///
+/// Only chunks that contain something other than the fill value are stored,
+/// so mostly-erased flash and mostly-zero RAMs stay small.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct MemoryImage {
+    len: usize,
+    fill: u8,
+    chunks: Vec<MemoryChunk>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+struct MemoryChunk {
+    offset: usize,
+    /// Hex-encoded chunk contents.
+    data: String,
+}
+
+impl MemoryImage {
+    /// Capture `data`, treating bytes equal to `fill` as the default content.
+    pub fn capture(data: &[u8], fill: u8) -> Self {
+        let chunks = data
+            .chunks(CHUNK_SIZE)
+            .enumerate()
+            .filter(|(_, chunk)| chunk.iter().any(|&b| b != fill))
+            .map(|(i, chunk)| MemoryChunk {
+                offset: i * CHUNK_SIZE,
+                data: hex::encode(chunk),
+            })
+            .collect();
+        Self {
+            len: data.len(),
+            fill,
+            chunks,
+        }
+    }
+
+    /// Size of the captured region in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Write the captured contents back into `data`, which must be the same
+    /// size as the captured region.
+    pub fn restore(&self, data: &mut [u8]) -> std::io::Result<()> {
+        if data.len() != self.len {
+            return Err(invalid_data(format!(
+                "memory image is {} bytes but the target region is {} bytes",
+                self.len,
+                data.len()
+            )));
+        }
+        data.fill(self.fill);
+        for chunk in self.chunks.iter() {
+            let bytes = hex::decode(&chunk.data).map_err(|e| invalid_data(e.to_string()))?;
+            let end = chunk
+                .offset
+                .checked_add(bytes.len())
+                .filter(|&end| end <= data.len())
+                .ok_or_else(|| invalid_data("memory image chunk out of range".into()))?;
+            data[chunk.offset..end].copy_from_slice(&bytes);
+        }
+        Ok(())
+    }
+
+    /// Expand the captured contents into a new buffer.
+    pub fn to_vec(&self) -> std::io::Result<Vec<u8>> {
+        let mut data = vec![0; self.len];
+        self.restore(&mut data)?;
+        Ok(data)
+    }
+}
+
+/// Architectural state of one CPU.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct CpuState {
+    /// Value of the CPU clock when the snapshot was taken.
+    pub ticks: u64,
+    pub pc: u32,
+    pub xregs: Vec<u32>,
+    /// Every machine-mode CSR that could be read, as (address, value) pairs.
+    pub csrs: Vec<(RvAddr, u32)>,
+}
+
+impl CpuState {
+    pub fn capture<TBus: Bus>(cpu: &Cpu<TBus>) -> Self {
+        let xregs = (0..32u16)
+            .map(|idx| cpu.read_xreg(XReg::from(idx)).unwrap_or_default())
+            .collect();
+        let csrs = (0..CSR_COUNT)
+            .filter_map(|addr| cpu.read_csr_machine(addr).ok().map(|val| (addr, val)))
+            .collect();
+        Self {
+            ticks: cpu.clock.now(),
+            pc: cpu.read_pc(),
+            xregs,
+            csrs,
+        }
+    }
+
+    /// Restore the registers. The clock is moved separately by
+    /// [`advance_clock`], before any peripheral timers are re-armed.
+    ///
+    /// Read-only CSRs are skipped silently.
+    pub fn restore<TBus: Bus>(&self, cpu: &mut Cpu<TBus>) -> std::io::Result<()> {
+        if self.xregs.len() != 32 {
+            return Err(invalid_data(format!(
+                "CPU snapshot has {} integer registers",
+                self.xregs.len()
+            )));
+        }
+        for &(addr, val) in self.csrs.iter() {
+            let _ = cpu.write_csr_machine(addr, val);
+        }
+        for (idx, &val) in self.xregs.iter().enumerate().skip(1) {
+            cpu.write_xreg(XReg::from(idx as u16), val)
+                .map_err(|_| invalid_data(format!("cannot restore register x{idx}")))?;
+        }
+        cpu.write_pc(self.pc);
+        Ok(())
+    }
+}
+
+/// Number of CSR addresses probed when capturing a [`CpuState`].
+const CSR_COUNT: RvAddr = 4096;
+
+/// Run `cpu`'s clock forward to `ticks`, firing any timer actions that fall
+/// due on the way. Snapshots can only be loaded into an emulator whose clock
+/// has not yet passed the saved time.
+pub fn advance_clock<TBus: Bus>(cpu: &mut Cpu<TBus>, ticks: u64) -> std::io::Result<()> {
+    let now = cpu.clock.now();
+    if now > ticks {
+        return Err(invalid_data(format!(
+            "snapshot was taken at tick {ticks} but the emulator is already at tick {now}"
+        )));
+    }
+    let clock = cpu.clock.clone();
+    clock.increment_and_process_timer_actions(ticks - now, &mut cpu.bus);
+    Ok(())
+}
+
+/// Handles to the MCU memories that sit directly on [`McuRootBus`].
+#[derive(Clone)]
+pub struct McuMemories {
+    pub ram: Rc<RefCell<Ram>>,
+    pub rom_sram: Rc<RefCell<Ram>>,
+    pub external_test_sram: Rc<RefCell<Ram>>,
+    pub direct_read_flash: Rc<RefCell<Ram>>,
+    pub dot_flash: Rc<RefCell<Ram>>,
+}
+
+impl McuMemories {
+    pub fn new(root_bus: &McuRootBus) -> Self {
+        Self {
+            ram: root_bus.ram.clone(),
+            rom_sram: root_bus.rom_sram.clone(),
+            external_test_sram: root_bus.external_test_sram.clone(),
+            direct_read_flash: root_bus.direct_read_flash.clone(),
+            dot_flash: root_bus.dot_flash.clone(),
+        }
+    }
+
+    fn regions(&self) -> [(&'static str, &Rc<RefCell<Ram>>, u8); 5] {
+        [
+            ("ram", &self.ram, 0),
+            ("rom_sram", &self.rom_sram, 0),
+            ("external_test_sram", &self.external_test_sram, 0),
+            ("direct_read_flash", &self.direct_read_flash, 0xff),
+            ("dot_flash", &self.dot_flash, 0),
+        ]
+    }
+}
+
+/// Caliptra core memories, captured through the Caliptra CPU's bus.
+const CALIPTRA_MEMORIES: [(&str, RvAddr, usize); 3] = [
+    ("iccm", 0x4000_0000, 256 * 1024),
+    ("dccm", 0x5000_0000, 256 * 1024),
+    ("mbox_sram", 0x3000_0000, 256 * 1024),
+];
+
+/// Caliptra mailbox status register, as seen from the Caliptra core.
+const CALIPTRA_MBOX_STATUS: RvAddr = 0x3002_001c;
+/// MBOX_FSM_PS field of the mailbox status register.
+const CALIPTRA_MBOX_FSM_SHIFT: u32 = 6;
+const CALIPTRA_MBOX_FSM_MASK: u32 = 0x7;
+
+const CALIPTRA_SOC_IFC_BASE: RvAddr = 0x3003_0000;
+const CALIPTRA_PIC_BASE: RvAddr = 0x6000_0000;
+
+/// The SoC agent MCU uses to reach the Caliptra SoC interface.
+const CALIPTRA_SOC_REQUESTER: MailboxRequester = MailboxRequester::SocUser(1);
+
+/// SoC interface registers written over the SoC side of the bus, as
+/// (offset, word count), in the order MCU ROM programs them: fuses and PK
+/// hashes, then the AXI user configuration that locks them, then
+/// CPTRA_FUSE_WR_DONE and CPTRA_BOOTFSM_GO.
+///
+/// The UDS seed and field entropy fuses are write-only and are only consumed
+/// while Caliptra ROM runs, so they are not saved.
+const SOC_IFC_SOC_REGISTERS: &[(u32, u32)] = &[
+    (0x260, 12), // FUSE_VENDOR_PK_HASH
+    (0x290, 1),  // FUSE_ECC_REVOCATION
+    (0x2b4, 1),  // FUSE_FMC_KEY_MANIFEST_SVN
+    (0x2b8, 4),  // FUSE_RUNTIME_SVN
+    (0x2c8, 1),  // FUSE_ANTI_ROLLBACK_DISABLE
+    (0x2cc, 24), // FUSE_IDEVID_CERT_ATTR
+    (0x32c, 4),  // FUSE_IDEVID_MANUF_HSM_ID
+    (0x340, 3),  // FUSE_LMS_REVOCATION .. FUSE_SOC_STEPPING_ID
+    (0x34c, 16), // FUSE_MANUF_DBG_UNLOCK_TOKEN
+    (0x38c, 1),  // FUSE_PQC_KEY_TYPE
+    (0x390, 5),  // FUSE_SOC_MANIFEST_SVN, FUSE_SOC_MANIFEST_MAX_SVN
+    (0x140, 13), // CPTRA_OWNER_PK_HASH, CPTRA_OWNER_PK_HASH_LOCK
+    (0x500, 13), // SS_*_BASE_ADDR, SS_PROD_DEBUG_UNLOCK_*
+    (0x534, 1),  // SS_CALIPTRA_DMA_AXI_USER
+    (0x5a0, 4),  // SS_STRAP_GENERIC
+    (0x5c0, 1),  // SS_DBG_MANUF_SERVICE_REG_REQ
+    (0x5c8, 2),  // SS_SOC_DBG_UNLOCK_LEVEL
+    (0xb4, 1),   // CPTRA_TIMER_CONFIG
+    (0xbc, 2),   // CPTRA_DBG_MANUF_SERVICE_REG, CPTRA_CLK_GATING_EN
+    (0x110, 4),  // CPTRA_WDT_CFG, CPTRA_ITRNG_ENTROPY_CONFIG
+    (0x48, 10),  // CPTRA_MBOX_VALID_AXI_USER, CPTRA_MBOX_AXI_USER_LOCK
+    (0x70, 2),   // CPTRA_TRNG_VALID_AXI_USER, CPTRA_TRNG_AXI_USER_LOCK
+    (0x108, 2),  // CPTRA_FUSE_VALID_AXI_USER, CPTRA_FUSE_AXI_USER_LOCK
+    (0xb0, 1),   // CPTRA_FUSE_WR_DONE
+    (0xb8, 1),   // CPTRA_BOOTFSM_GO
+];
+
+/// SoC interface registers written by Caliptra firmware, as (offset, word
+/// count). ICCM lock comes last so that the ICCM has already been restored.
+///
+/// CPTRA_GENERIC_OUTPUT_WIRES is skipped: writing it drives the emulator's
+/// test bench services, which print or exit.
+const SOC_IFC_CALIPTRA_REGISTERS: &[(u32, u32)] = &[
+    (0x8, 4),   // CPTRA_FW_ERROR_FATAL .. CPTRA_FW_ERROR_ENC
+    (0x18, 10), // CPTRA_FW_EXTENDED_ERROR_INFO, CPTRA_BOOT_STATUS, CPTRA_FLOW_STATUS
+    (0xd8, 2),  // CPTRA_FW_REV_ID
+    (0x5c4, 1), // SS_DBG_MANUF_SERVICE_REG_RSP
+    (0x5d0, 4), // SS_GENERIC_FW_EXEC_CTRL
+    (0x62c, 5), // INTERNAL_NMI_VECTOR, INTERNAL_*_ERROR_*_MASK
+    (0x648, 2), // INTERNAL_RV_MTIMECMP
+    (0x800, 3), // GLOBAL_INTR_EN_R, ERROR_INTR_EN_R, NOTIF_INTR_EN_R
+    (0x620, 1), // INTERNAL_ICCM_LOCK
+];
+
+fn soc_ifc_offsets(registers: &'static [(u32, u32)]) -> impl Iterator<Item = u32> {
+    registers
+        .iter()
+        .flat_map(|&(offset, count)| (0..count).map(move |i| offset + i * 4))
+}
+
+/// Whether the Caliptra mailbox is idle. Its internal state machine cannot
+/// be restored, so snapshots are only taken between mailbox commands.
+pub fn caliptra_mailbox_idle(caliptra_cpu: &mut Cpu<CaliptraRootBus>) -> bool {
+    caliptra_cpu
+        .read_bus(RvSize::Word, CALIPTRA_MBOX_STATUS)
+        .is_ok_and(|status| (status >> CALIPTRA_MBOX_FSM_SHIFT) & CALIPTRA_MBOX_FSM_MASK == 0)
+}
+
+/// Write a saved register value, skipping registers that already hold it so
+/// that registers with write side effects are only touched when needed.
+fn restore_register(bus: &mut impl Bus, addr: RvAddr, val: u32) {
+    if bus.read(RvSize::Word, addr).ok() != Some(val) {
+        let _ = bus.write(RvSize::Word, addr, val);
+    }
+}
+
+/// Offsets within the PIC of the configuration registers that are saved:
+/// MPICCFG, then MEIPL, MEIGWCTRL and MEIE for each interrupt source.
+fn pic_config_offsets() -> impl Iterator<Item = u32> {
+    const MAX_IRQS: u32 = 256;
+    std::iter::once(0x3000)
+        .chain((1..MAX_IRQS).map(|id| id * 4))
+        .chain((1..MAX_IRQS).map(|id| 0x4000 + id * 4))
+        .chain((1..MAX_IRQS).map(|id| 0x2000 + id * 4))
+}
+
+/// Complete saved state of an emulated MCU subsystem.
+///
+/// Pending timer events are not stored directly. Each peripheral records
+/// what it was waiting for and re-arms its timers when restored.
+///
+/// The Caliptra core is captured through its bus: CPU registers, ICCM, DCCM,
+/// mailbox SRAM, PIC configuration and the SoC interface registers. The
+/// Caliptra mailbox state machine is not visible there, so snapshots are
+/// refused while a mailbox command is in flight. Key vault slots and the
+/// crypto engines cannot be read back either and come back at their reset
+/// values. Messages in flight on the emulator's external transports (I3C,
+/// DOE and PLDM sockets) are not saved.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct SystemSnapshot {
+    pub version: u32,
+    pub mcu_cpu: CpuState,
+    pub caliptra_cpu: CpuState,
+    pub mcu_memories: BTreeMap<String, MemoryImage>,
+    pub caliptra_memories: BTreeMap<String, MemoryImage>,
+    /// PIC configuration registers as (offset, value) pairs.
+    pub pic: Vec<(u32, u32)>,
+    /// Caliptra PIC configuration registers as (offset, value) pairs.
+    pub caliptra_pic: Vec<(u32, u32)>,
+    /// Caliptra SoC interface registers as (offset, value) pairs.
+    pub caliptra_soc_ifc: Vec<(u32, u32)>,
+    /// Hex-encoded peripheral states, keyed by peripheral name.
+    pub peripherals: BTreeMap<String, String>,
+    /// Recovery state of the emulated BMC, if there is one.
+    pub bmc: Option<String>,
+}
+
+impl SystemSnapshot {
+    /// Capture the state of a running emulator.
+    ///
+    /// `root_bus` picks the [`AutoRootBus`] out of the MCU CPU's bus, and
+    /// `pic_offset` is the address of the PIC on that bus.
+    pub fn capture<TMcuBus: Bus>(
+        mcu_cpu: &mut Cpu<TMcuBus>,
+        root_bus: fn(&mut TMcuBus) -> &mut AutoRootBus,
+        caliptra_cpu: &mut Cpu<CaliptraRootBus>,
+        memories: &McuMemories,
+        pic_offset: u32,
+    ) -> std::io::Result<Self> {
+        if !caliptra_mailbox_idle(caliptra_cpu) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::WouldBlock,
+                "cannot take a snapshot while the Caliptra mailbox is busy",
+            ));
+        }
+
+        let mcu_memories = memories
+            .regions()
+            .into_iter()
+            .map(|(name, ram, fill)| {
+                (
+                    name.to_string(),
+                    MemoryImage::capture(ram.borrow().data(), fill),
+                )
+            })
+            .collect();
+        let caliptra_memories = CALIPTRA_MEMORIES
+            .iter()
+            .map(|&(name, base, size)| {
+                let mut data = Vec::with_capacity(size);
+                for offset in (0..size as u32).step_by(4) {
+                    let word = caliptra_cpu
+                        .read_bus(RvSize::Word, base + offset)
+                        .unwrap_or_default();
+                    data.extend_from_slice(&word.to_le_bytes());
+                }
+                (name.to_string(), MemoryImage::capture(&data, 0))
+            })
+            .collect();
+        let pic = pic_config_offsets()
+            .filter_map(|offset| {
+                mcu_cpu
+                    .read_bus(RvSize::Word, pic_offset + offset)
+                    .ok()
+                    .map(|val| (offset, val))
+            })
+            .collect();
+        let caliptra_pic = pic_config_offsets()
+            .filter_map(|offset| {
+                caliptra_cpu
+                    .read_bus(RvSize::Word, CALIPTRA_PIC_BASE + offset)
+                    .ok()
+                    .map(|val| (offset, val))
+            })
+            .collect();
+        let caliptra_soc_ifc = soc_ifc_offsets(SOC_IFC_SOC_REGISTERS)
+            .chain(soc_ifc_offsets(SOC_IFC_CALIPTRA_REGISTERS))
+            .filter_map(|offset| {
+                caliptra_cpu
+                    .read_bus(RvSize::Word, CALIPTRA_SOC_IFC_BASE + offset)
+                    .ok()
+                    .map(|val| (offset, val))
+            })
+            .collect();
+        let peripherals = root_bus(&mut mcu_cpu.bus)
+            .save_peripheral_states()
+            .into_iter()
+            .map(|(name, state)| (name, hex::encode(state)))
+            .collect();
+        Ok(Self {
+            version: SNAPSHOT_VERSION,
+            mcu_cpu: CpuState::capture(mcu_cpu),
+            caliptra_cpu: CpuState::capture(caliptra_cpu),
+            mcu_memories,
+            caliptra_memories,
+            pic,
+            caliptra_pic,
+            caliptra_soc_ifc,
+            peripherals,
+            bmc: None,
+        })
+    }
+
+    /// Load this snapshot into a freshly constructed emulator with the same
+    /// configuration as the one it was captured from.
+    pub fn restore<TMcuBus: Bus>(
+        &self,
+        mcu_cpu: &mut Cpu<TMcuBus>,
+        root_bus: fn(&mut TMcuBus) -> &mut AutoRootBus,
+        caliptra_cpu: &mut Cpu<CaliptraRootBus>,
+        memories: &McuMemories,
+        pic_offset: u32,
+    ) -> std::io::Result<()> {
+        if self.version != SNAPSHOT_VERSION {
+            return Err(invalid_data(format!(
+                "unsupported snapshot version {} (expected {SNAPSHOT_VERSION})",
+                self.version
+            )));
+        }
+        // Move time forward first so that timers re-armed below are
+        // relative to the saved clock.
+        advance_clock(mcu_cpu, self.mcu_cpu.ticks)?;
+        advance_clock(caliptra_cpu, self.caliptra_cpu.ticks)?;
+
+        for (name, ram, _) in memories.regions() {
+            let image = self
+                .mcu_memories
+                .get(name)
+                .ok_or_else(|| invalid_data(format!("snapshot is missing MCU memory {name}")))?;
+            image.restore(ram.borrow_mut().data_mut())?;
+        }
+        for &(name, base, size) in CALIPTRA_MEMORIES.iter() {
+            let image = self.caliptra_memories.get(name).ok_or_else(|| {
+                invalid_data(format!("snapshot is missing Caliptra memory {name}"))
+            })?;
+            if image.len() != size {
+                return Err(invalid_data(format!(
+                    "Caliptra {name} is {} bytes in the snapshot, expected {size}",
+                    image.len()
+                )));
+            }
+            for (i, word) in image.to_vec()?.chunks_exact(4).enumerate() {
+                let addr = base + (i * 4) as u32;
+                let word = u32::from_le_bytes(word.try_into().unwrap());
+                caliptra_cpu
+                    .write_bus(RvSize::Word, addr, word)
+                    .map_err(|_| {
+                        invalid_data(format!("cannot write Caliptra {name} at {addr:#x}"))
+                    })?;
+            }
+        }
+        // Like CSRs, any PIC register that does not accept the write is
+        // left at its reset value.
+        for &(offset, val) in self.pic.iter() {
+            let _ = mcu_cpu.write_bus(RvSize::Word, pic_offset + offset, val);
+        }
+        for &(offset, val) in self.caliptra_pic.iter() {
+            let _ = caliptra_cpu.write_bus(RvSize::Word, CALIPTRA_PIC_BASE + offset, val);
+        }
+        // SoC-side registers go through the SoC interface, as MCU ROM wrote
+        // them; the rest through the Caliptra core's own bus.
+        let soc_ifc: BTreeMap<u32, u32> = self.caliptra_soc_ifc.iter().copied().collect();
+        let mut soc_bus = caliptra_cpu.bus.soc_to_caliptra_bus(CALIPTRA_SOC_REQUESTER);
+        for offset in soc_ifc_offsets(SOC_IFC_SOC_REGISTERS) {
+            if let Some(&val) = soc_ifc.get(&offset) {
+                restore_register(&mut soc_bus, CALIPTRA_SOC_IFC_BASE + offset, val);
+            }
+        }
+        for offset in soc_ifc_offsets(SOC_IFC_CALIPTRA_REGISTERS) {
+            if let Some(&val) = soc_ifc.get(&offset) {
+                restore_register(&mut caliptra_cpu.bus, CALIPTRA_SOC_IFC_BASE + offset, val);
+            }
+        }
+
+        let mut states = BTreeMap::new();
+        for (name, state) in self.peripherals.iter() {
+            let state = hex::decode(state).map_err(|e| invalid_data(e.to_string()))?;
+            states.insert(name.clone(), state);
+        }
+        root_bus(&mut mcu_cpu.bus).restore_peripheral_states(&states)?;
+
+        self.mcu_cpu.restore(mcu_cpu)?;
+        self.caliptra_cpu.restore(caliptra_cpu)
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut writer, self)?;
+        std::io::Write::flush(&mut writer)
+    }
+
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+    }
+}
+
+/// Serialize a peripheral state structure for `save_state()`.
+pub fn encode_state<T: Serialize>(state: &T) -> Vec<u8> {
+    serde_json::to_vec(state).expect("peripheral state is always serializable")
+}
+
+/// Deserialize a peripheral state structure for `restore_state()`.
+pub fn decode_state<T: DeserializeOwned>(state: &[u8]) -> std::io::Result<T> {
+    Ok(serde_json::from_slice(state)?)
+}
+
+pub(crate) fn invalid_data(msg: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_image_round_trip() {
+        let mut data = vec![0xffu8; 3 * CHUNK_SIZE + 100];
+        data[5] = 0x12;
+        data[2 * CHUNK_SIZE + 7] = 0x34;
+        data[3 * CHUNK_SIZE + 99] = 0x56;
+
+        let image = MemoryImage::capture(&data, 0xff);
+        assert_eq!(image.len(), data.len());
+        // The untouched second chunk is not stored.
+        assert_eq!(image.chunks.len(), 3);
+
+        let json = serde_json::to_vec(&image).unwrap();
+        let image: MemoryImage = serde_json::from_slice(&json).unwrap();
+        let mut restored = vec![0u8; data.len()];
+        image.restore(&mut restored).unwrap();
+        assert_eq!(restored, data);
+        assert_eq!(image.to_vec().unwrap(), data);
+    }
+
+    #[test]
+    fn test_memory_image_size_mismatch() {
+        let image = MemoryImage::capture(&[1, 2, 3, 4], 0);
+        let mut data = [0u8; 8];
+        assert!(image.restore(&mut data).is_err());
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        #[derive(Debug, PartialEq, Deserialize, Serialize)]
+        struct State {
+            a: u32,
+            b: Vec<u8>,
+        }
+        let state = State {
+            a: 7,
+            b: vec![1, 2, 3],
+        };
+        let decoded: State = decode_state(&encode_state(&state)).unwrap();
+        assert_eq!(decoded, state);
+        assert!(decode_state::<State>(b"not json").is_err());
+    }
+}
@@ -13,6 +13,7 @@ use caliptra_hw_model_types::{
     EtrngResponse, HexBytes, HexSlice, RandomEtrngResponses, RandomNibbles, DEFAULT_CPTRA_OBF_KEY,
 };
 use caliptra_image_types::FwVerificationPqcKeyType;
+pub use caliptra_mcu_emulator_periph::{FaultPlan, InjectedFault};
 use caliptra_mcu_mbox_common::messages::calc_checksum;
 pub use caliptra_mcu_otp_lifecycle::LifecycleControllerState;
 use caliptra_mcu_romtime::McuBootMilestones;
@@ -234,6 +235,9 @@ pub struct InitParams<'a> {
     /// When true, set secrets_valid so DOE reads UDS/FE from strap registers
     /// for deterministic IDevID on FPGA (needed for attestation tests).
     pub use_strap_secrets: bool,
+
+    /// Peripheral faults to inject while the model runs (emulator only).
+    pub fault_plan: Option<FaultPlan>,
 }
 
 impl InitParams<'_> {
@@ -309,6 +313,7 @@ impl Default for InitParams<'_> {
             active_i3c1: false,
             vendor_test_partition: None,
             use_strap_secrets: false,
+            fault_plan: None,
         }
     }
 }
@@ -372,6 +377,26 @@ pub trait McuHwModel {
     fn read_dot_flash(&self) -> Vec<u8>;
     fn write_dot_flash(&mut self, data: &[u8]) -> Result<()>;
 
+    /// Save the full model state (CPUs, memories, fuses, flash and
+    /// peripherals) to `path`, so that tests can fork from it later.
+    /// Fails with [`std::io::ErrorKind::WouldBlock`] while the Caliptra
+    /// mailbox is processing a command; step the model and try again.
+    fn save_snapshot(&mut self, _path: &Path) -> Result<()> {
+        bail!("{} does not support snapshots", self.type_name())
+    }
+
+    /// Restore a state saved by [`McuHwModel::save_snapshot`]. The model must
+    /// have been created unbooted, with the same parameters as the model that
+    /// saved the snapshot.
+    fn load_snapshot(&mut self, _path: &Path) -> Result<()> {
+        bail!("{} does not support snapshots", self.type_name())
+    }
+
+    /// Faults injected so far from [`InitParams::fault_plan`], in order.
+    fn injected_faults(&self) -> Vec<InjectedFault> {
+        vec![]
+    }
+
     /// The type name of this model
     fn type_name(&self) -> &'static str;
 
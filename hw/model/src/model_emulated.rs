@@ -30,11 +30,13 @@ use caliptra_mcu_emulator_bmc::Bmc;
 use caliptra_mcu_emulator_caliptra::start_caliptra;
 use caliptra_mcu_emulator_caliptra::BytesOrPath;
 use caliptra_mcu_emulator_caliptra::StartCaliptraArgs;
+use caliptra_mcu_emulator_periph::snapshot::{McuMemories, SystemSnapshot};
 use caliptra_mcu_emulator_periph::DummyFlashCtrl;
 use caliptra_mcu_emulator_periph::LcCtrl;
 use caliptra_mcu_emulator_periph::McuRootBusOffsets;
 use caliptra_mcu_emulator_periph::{
-    I3c, I3cController, Mci, McuRootBus, McuRootBusArgs, Otp, OtpArgs,
+    FaultHook, FaultInjector, I3c, I3cController, InjectedFault, Mci, McuRootBus, McuRootBusArgs,
+    Otp, OtpArgs,
 };
 use caliptra_mcu_emulator_registers_generated::axicdma::AxicdmaPeripheral;
 use caliptra_mcu_emulator_registers_generated::primary_flash::PrimaryFlashPeripheral;
@@ -92,6 +94,9 @@ pub struct ModelEmulated {
     // Synchronises cross-thread timer scheduling (I3C controller thread)
     // with the CPU step that advances the clock.
     step_lock: Arc<Mutex<()>>,
+    memories: McuMemories,
+    pic_offset: u32,
+    fault_injector: Option<FaultInjector>,
 }
 
 fn hash_slice(slice: &[u8]) -> u64 {
@@ -140,8 +145,29 @@ impl McuHwModel for ModelEmulated {
             straps,
             ..Default::default()
         };
+        let pic_offset = bus_args.offsets.pic_offset;
         let mcu_root_bus = McuRootBus::new(bus_args).unwrap();
 
+        let fault_injector = params.fault_plan.map(FaultInjector::new);
+        let fault_hook = |peripheral: &'static str| -> FaultHook {
+            fault_injector
+                .as_ref()
+                .map(|injector| injector.hook(peripheral))
+                .unwrap_or_default()
+        };
+        mcu_root_bus
+            .mcu_mailbox0
+            .regs
+            .lock()
+            .unwrap()
+            .set_fault_hook(fault_hook("mcu_mbox0"));
+        mcu_root_bus
+            .mcu_mailbox1
+            .regs
+            .lock()
+            .unwrap()
+            .set_fault_hook(fault_hook("mcu_mbox1"));
+
         let mut i3c_controller = if let Some(i3c_port) = params.i3c_port {
             let (rx, tx) = start_i3c_socket(&MCU_RUNNING, i3c_port);
             I3cController::new(rx, tx)
@@ -155,6 +181,7 @@ impl McuHwModel for ModelEmulated {
         let rom_sram = mcu_root_bus.rom_sram.clone();
         let direct_read_flash = mcu_root_bus.direct_read_flash.clone();
         let dot_flash = mcu_root_bus.dot_flash.clone();
+        let memories = McuMemories::new(&mcu_root_bus);
 
         // Use HW 2.1.0 for flash-based boot, otherwise 2.0.0
         let hw_version = if params.flash_boot {
@@ -165,13 +192,14 @@ impl McuHwModel for ModelEmulated {
 
         let step_lock = Arc::new(Mutex::new(()));
 
-        let i3c = I3c::new(
+        let mut i3c = I3c::new(
             &clock.clone(),
             &mut i3c_controller,
             i3c_irq,
             hw_version.clone(),
             step_lock.clone(),
         );
+        i3c.set_fault_hook(fault_hook("i3c"));
 
         let i3c_dynamic_address = i3c.get_dynamic_address().unwrap();
 
@@ -217,7 +245,7 @@ impl McuHwModel for ModelEmulated {
 
         let lc = LcCtrl::with_state(lc_state_index, lc_transition_cnt);
 
-        let otp = Otp::new(
+        let mut otp = Otp::new(
             &clock.clone(),
             OtpArgs {
                 raw_memory: Some(otp_mem),
@@ -227,6 +255,7 @@ impl McuHwModel for ModelEmulated {
                 ..Default::default()
             },
         )?;
+        otp.set_fault_hook(fault_hook("otp"));
 
         // Get the partitions reference before passing OTP to the bus
         let otp_partitions = otp.partitions_ref();
@@ -263,6 +292,7 @@ impl McuHwModel for ModelEmulated {
             Some(direct_read_flash.clone()),
         );
         primary_flash_controller.set_dma_rom_sram(rom_sram.clone());
+        primary_flash_controller.set_fault_hook(fault_hook("primary_flash"));
 
         let mut secondary_flash_controller = create_flash_controller(
             "secondary_flash",
@@ -272,6 +302,7 @@ impl McuHwModel for ModelEmulated {
             None,
         );
         secondary_flash_controller.set_dma_rom_sram(rom_sram.clone());
+        secondary_flash_controller.set_fault_hook(fault_hook("secondary_flash"));
 
         let mut dma_ctrl = caliptra_mcu_emulator_periph::AxiCDMA::new(
             &clock.clone(),
@@ -460,6 +491,9 @@ impl McuHwModel for ModelEmulated {
             mci_regs,
             check_booted_to_runtime: params.check_booted_to_runtime,
             step_lock,
+            memories,
+            pic_offset,
+            fault_injector,
         };
         // Turn tracing on if the trace path was set
         m.tracing_hint(true);
@@ -614,6 +648,46 @@ impl McuHwModel for ModelEmulated {
         Ok(())
     }
 
+    fn save_snapshot(&mut self, path: &Path) -> Result<()> {
+        let _guard = self.step_lock.lock().unwrap();
+        let mut snapshot = SystemSnapshot::capture(
+            &mut self.cpu,
+            |bus| &mut bus.bus,
+            &mut self.caliptra_cpu,
+            &self.memories,
+            self.pic_offset,
+        )?;
+        snapshot.bmc = self.bmc.as_ref().map(Bmc::recovery_state);
+        snapshot.save(path)?;
+        Ok(())
+    }
+
+    fn load_snapshot(&mut self, path: &Path) -> Result<()> {
+        let snapshot = SystemSnapshot::load(path)?;
+        let _guard = self.step_lock.lock().unwrap();
+        snapshot.restore(
+            &mut self.cpu,
+            |bus| &mut bus.bus,
+            &mut self.caliptra_cpu,
+            &self.memories,
+            self.pic_offset,
+        )?;
+        if let (Some(bmc), Some(state)) = (self.bmc.as_mut(), snapshot.bmc.as_deref()) {
+            bmc.restore_recovery_state(state)
+                .map_err(anyhow::Error::msg)?;
+        }
+        // Snapshots are always taken from a running model.
+        self.cpu_enabled.set(true);
+        Ok(())
+    }
+
+    fn injected_faults(&self) -> Vec<InjectedFault> {
+        self.fault_injector
+            .as_ref()
+            .map(|injector| injector.injected())
+            .unwrap_or_default()
+    }
+
     fn mcu_manager(&mut self) -> impl McuManager {
         self
     }
@@ -8,6 +8,7 @@ use caliptra_mcu_capsules_runtime::doe::driver::DoeDriver;
 use caliptra_mcu_capsules_runtime::flash_partition::FlashPartition;
 use caliptra_mcu_capsules_runtime::mctp::base_protocol::MessageType;
 use caliptra_mcu_capsules_runtime::mcu_mbox::McuMboxDriver;
+#[cfg(not(feature = "mctp-pcie-vdm"))]
 use caliptra_mcu_components::mctp_mux_component_static;
 use caliptra_mcu_components::{
     doe_component_static, flash_partition_component_static, instantiate_flash_partitions,
@@ -86,6 +87,16 @@ pub static mut PROCESSES: [Option<&'static dyn kernel::process::Process>; NUM_PR
 
 pub type VeeRChip = caliptra_mcu_tock_veer::chip::VeeR<'static, VeeRDefaultPeripherals<'static>>;
 
+/// MCTP transport binding selected for this build.
+#[cfg(not(feature = "mctp-pcie-vdm"))]
+pub type MctpBinding =
+    caliptra_mcu_capsules_runtime::mctp::transport_binding::MCTPI3CBinding<'static>;
+#[cfg(feature = "mctp-pcie-vdm")]
+pub type MctpBinding = caliptra_mcu_capsules_runtime::mctp::pcie_vdm::MCTPPcieVdmBinding<
+    'static,
+    EmulatedDoeTransport<'static, InternalTimers<'static>>,
+>;
+
 // Reference to the chip and peripherals for panic dumps and tests.
 pub static mut CHIP: Option<&'static VeeRChip> = None;
 pub static mut EMULATOR_PERIPHERALS: Option<&'static EmulatorPeripherals> = None;
@@ -583,6 +594,12 @@ pub unsafe fn main() {
         let _ = process_console.start();
     }
 
+    // The MCTP endpoint UUID and network ID are derived from the IDevID UEID fuses.
+    let ueid = peripherals.otp.read_idevid_ueid().unwrap_or_else(|_| {
+        caliptra_mcu_romtime::println!("[mcu-runtime] WARNING: failed to read the IDevID UEID");
+        [0; caliptra_mcu_romtime::DEVICE_UUID_LEN]
+    });
+
     // Select which I3C core to use for MCTP transport based on platform strap.
     if MCU_STRAPS.active_i3c > 1 {
         caliptra_mcu_romtime::println!(
@@ -590,6 +607,7 @@ pub unsafe fn main() {
             MCU_STRAPS.active_i3c
         );
     }
+    #[cfg_attr(feature = "mctp-pcie-vdm", allow(unused_variables))]
     let active_i3c_core = if MCU_STRAPS.active_i3c == 1 {
         &peripherals.i3c1
     } else {
@@ -599,9 +617,40 @@ pub unsafe fn main() {
         "[mcu-runtime] Active I3C core for MCTP: {}",
         MCU_STRAPS.active_i3c
     );
-    let mux_mctp =
-        caliptra_mcu_components::mux_mctp::MCTPMuxComponent::new(active_i3c_core, mux_alarm)
-            .finalize(mctp_mux_component_static!(InternalTimers, MCTPI3CBinding));
+    // Set up a SPDM over DOE capsule.
+    let doe_spdm = caliptra_mcu_components::doe::DoeComponent::new(
+        board_kernel,
+        caliptra_mcu_capsules_runtime::doe::driver::DOE_SPDM_DRIVER_NUM,
+        &emulator_peripherals.doe_transport,
+    )
+    .finalize(doe_component_static!(
+        caliptra_mcu_doe_mbox_driver::EmulatedDoeTransport<'static, InternalTimers<'static>>
+    ));
+
+    #[cfg(not(feature = "mctp-pcie-vdm"))]
+    let mux_mctp = caliptra_mcu_components::mux_mctp::MCTPMuxComponent::new(
+        active_i3c_core,
+        mux_alarm,
+        caliptra_mcu_romtime::device_uuid(&ueid),
+        caliptra_mcu_romtime::device_network_id(&ueid),
+    )
+    .finalize(mctp_mux_component_static!(InternalTimers, MCTPI3CBinding));
+
+    // MCTP over PCIe VDM shares the DOE mailbox with SPDM over DOE. The MCTP binding
+    // takes over the mailbox clients, so DOE data objects are no longer delivered.
+    #[cfg(feature = "mctp-pcie-vdm")]
+    let mux_mctp = caliptra_mcu_components::mux_mctp::MCTPPcieVdmMuxComponent::new(
+        &emulator_peripherals.doe_transport,
+        mux_alarm,
+        caliptra_mcu_romtime::device_uuid(&ueid),
+        caliptra_mcu_romtime::device_network_id(&ueid),
+    )
+    .finalize(
+        caliptra_mcu_components::mctp_pcie_vdm_mux_component_static!(
+            InternalTimers,
+            EmulatedDoeTransport<'static, InternalTimers<'static>>
+        ),
+    );
 
     let mctp_spdm = caliptra_mcu_components::mctp_driver::MCTPDriverComponent::new(
         board_kernel,
@@ -637,16 +686,6 @@ pub unsafe fn main() {
     .finalize(mctp_driver_component_static!(InternalTimers));
     caliptra_mcu_romtime::println!("[mcu-runtime] MCTP Caliptra driver component initialized");
 
-    // Set up a SPDM over DOE capsule.
-    let doe_spdm = caliptra_mcu_components::doe::DoeComponent::new(
-        board_kernel,
-        caliptra_mcu_capsules_runtime::doe::driver::DOE_SPDM_DRIVER_NUM,
-        &emulator_peripherals.doe_transport,
-    )
-    .finalize(doe_component_static!(
-        caliptra_mcu_doe_mbox_driver::EmulatedDoeTransport<'static, InternalTimers<'static>>
-    ));
-
     peripherals.init();
 
     // Create a mux for the physical flash controller
@@ -862,7 +901,7 @@ fn run_kernel_tests(
     mux_mctp: &'static caliptra_mcu_capsules_runtime::mctp::mux::MuxMCTPDriver<
         'static,
         VirtualMuxAlarm<'static, InternalTimers<'static>>,
-        caliptra_mcu_capsules_runtime::mctp::transport_binding::MCTPI3CBinding<'static>,
+        MctpBinding,
     >,
 ) {
     let exit = if cfg!(feature = "test-exit-immediately") {
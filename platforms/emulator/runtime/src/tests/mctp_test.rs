@@ -1,7 +1,6 @@
 // Licensed under the Apache-2.0 license
 
 use caliptra_mcu_capsules_runtime::mctp::mux::MuxMCTPDriver;
-use caliptra_mcu_capsules_runtime::mctp::transport_binding::MCTPI3CBinding;
 use caliptra_mcu_capsules_runtime::test::mctp::MockMctp;
 use caliptra_mcu_capsules_runtime::test::mctp::TestClient;
 use caliptra_mcu_components::mock_mctp::MockMctpComponent;
@@ -18,7 +17,7 @@ pub fn test_mctp_capsule_loopback(
     mux_mctp: &'static MuxMCTPDriver<
         'static,
         VirtualMuxAlarm<'static, InternalTimers>,
-        MCTPI3CBinding<'static>,
+        crate::board::MctpBinding,
     >,
 ) -> Option<u32> {
     // set local EID here if needed.
@@ -6,6 +6,12 @@
     feature = "test-pldm-fw-update-e2e"
 ))]
 mod pldm_fdops_mock;
+#[cfg(any(
+    feature = "test-pldm-discovery",
+    feature = "test-pldm-fw-update",
+    feature = "test-pldm-fw-update-e2e"
+))]
+mod pldm_platform_mock;
 
 mod config;
 
@@ -212,7 +218,9 @@ async fn image_loading<D: DMAMapping>(dma_mapping: &'static D) -> Result<(), Err
     ))]
     {
         let fdops = pldm_fdops_mock::FdOpsObject::new();
-        let mut pldm_service = PldmService::init(&fdops, EXECUTOR.get().spawner());
+        let platform_ops = pldm_platform_mock::PlatformOpsObject::new();
+        let mut pldm_service =
+            PldmService::init_with_platform(&fdops, &platform_ops, EXECUTOR.get().spawner());
         writeln!(
             console_writer,
             "PLDM_APP: Starting PLDM service for testing..."
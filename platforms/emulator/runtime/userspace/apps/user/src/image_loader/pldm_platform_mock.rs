@@ -0,0 +1,151 @@
+// Licensed under the Apache-2.0 license
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use async_trait::async_trait;
+use caliptra_mcu_pldm_common::message::platform::get_pdr_repo_info::PdrRepositoryInfo;
+use caliptra_mcu_pldm_common::message::platform::get_sensor_reading::NumericSensorReading;
+use caliptra_mcu_pldm_common::protocol::platform::{
+    PdrRepositoryState, SensorEventMessageEnable, SensorOperationalState, SensorReading,
+    SensorState, StateField,
+};
+use caliptra_mcu_pldm_lib::platform::platform_ops::{PdrRecordInfo, PlatformOps, PlatformOpsError};
+
+pub const NUMERIC_SENSOR_ID: u16 = 1;
+pub const STATE_SENSOR_ID: u16 = 2;
+pub const NUMERIC_SENSOR_PDR_HANDLE: u32 = 1;
+pub const STATE_SENSOR_PDR_HANDLE: u32 = 2;
+
+// Health State (state set 1): Normal
+const HEALTH_STATE_NORMAL: u8 = 1;
+
+// This is a dummy PDR repository for development. The actual sensors and their PDRs
+// are defined by the platform integrator.
+//
+// Compact Numeric Sensor PDR for a temperature sensor in degrees C with no thresholds.
+const NUMERIC_SENSOR_PDR: [u8; 49] = [
+    // Common PDR header: recordHandle, PDRHeaderVersion, PDRType, recordChangeNumber, dataLength
+    0x01, 0x00, 0x00, 0x00, 0x01, 0x15, 0x00, 0x00, 0x27, 0x00,
+    // PLDMTerminusHandle, sensorID, entityType (processor), entityInstanceNumber, containerID
+    0x00, 0x00, 0x01, 0x00, 0x87, 0x00, 0x01, 0x00, 0x00, 0x00,
+    // sensorNameStringByteLength, baseUnit, unitModifier, occurrenceRate, rangeFieldSupport
+    0x00, 0x02, 0x00, 0x00, 0x00,
+    // warningHigh, warningLow, criticalHigh, criticalLow, fatalHigh, fatalLow
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+// State Sensor PDR for the health state of the same entity.
+const STATE_SENSOR_PDR: [u8; 27] = [
+    // Common PDR header: recordHandle, PDRHeaderVersion, PDRType, recordChangeNumber, dataLength
+    0x02, 0x00, 0x00, 0x00, 0x01, 0x04, 0x00, 0x00, 0x11, 0x00,
+    // PLDMTerminusHandle, sensorID, entityType (processor), entityInstanceNumber, containerID
+    0x00, 0x00, 0x02, 0x00, 0x87, 0x00, 0x01, 0x00, 0x00, 0x00,
+    // sensorInit, sensorAuxiliaryNamesPDR, compositeSensorCount
+    0x00, 0x00, 0x01,
+    // stateSetID (Health State), possibleStatesSize, possibleStates (Normal to Fatal)
+    0x01, 0x00, 0x01, 0x1E,
+];
+
+const PDRS: [(u32, &[u8]); 2] = [
+    (NUMERIC_SENSOR_PDR_HANDLE, &NUMERIC_SENSOR_PDR),
+    (STATE_SENSOR_PDR_HANDLE, &STATE_SENSOR_PDR),
+];
+
+// This is a dummy temperature reading for development.
+pub const TEST_TEMPERATURE: i8 = 40;
+
+pub struct PlatformOpsObject;
+
+impl Default for PlatformOpsObject {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PlatformOpsObject {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait(?Send)]
+impl PlatformOps for PlatformOpsObject {
+    async fn get_pdr_repository_info(
+        &self,
+        info: &mut PdrRepositoryInfo,
+    ) -> Result<(), PlatformOpsError> {
+        let repository_size = PDRS.iter().map(|(_, pdr)| pdr.len()).sum::<usize>();
+        let largest_record_size = PDRS.iter().map(|(_, pdr)| pdr.len()).max().unwrap_or(0);
+        *info = PdrRepositoryInfo::new(
+            PdrRepositoryState::Available,
+            PDRS.len() as u32,
+            repository_size as u32,
+            largest_record_size as u32,
+        );
+        Ok(())
+    }
+
+    async fn get_pdr(
+        &self,
+        record_handle: u32,
+        record: &mut [u8],
+    ) -> Result<PdrRecordInfo, PlatformOpsError> {
+        let index = match record_handle {
+            0 => 0,
+            handle => PDRS
+                .iter()
+                .position(|(pdr_handle, _)| *pdr_handle == handle)
+                .ok_or(PlatformOpsError::InvalidRecordHandle)?,
+        };
+        let pdr = PDRS[index].1;
+        record
+            .get_mut(..pdr.len())
+            .ok_or(PlatformOpsError::BufferTooSmall)?
+            .copy_from_slice(pdr);
+        Ok(PdrRecordInfo {
+            size: pdr.len(),
+            next_record_handle: PDRS.get(index + 1).map_or(0, |(handle, _)| *handle),
+        })
+    }
+
+    async fn get_sensor_reading(
+        &self,
+        sensor_id: u16,
+        _rearm_event_state: bool,
+    ) -> Result<NumericSensorReading, PlatformOpsError> {
+        if sensor_id != NUMERIC_SENSOR_ID {
+            return Err(PlatformOpsError::InvalidSensorId);
+        }
+        Ok(NumericSensorReading {
+            sensor_op_state: SensorOperationalState::Enabled,
+            event_message_enable: SensorEventMessageEnable::NoEventGeneration,
+            present_state: SensorState::Normal,
+            previous_state: SensorState::Normal,
+            event_state: SensorState::Normal,
+            present_reading: SensorReading::Sint8(TEST_TEMPERATURE),
+        })
+    }
+
+    async fn get_state_sensor_readings(
+        &self,
+        sensor_id: u16,
+        _sensor_rearm: u8,
+        state_fields: &mut [StateField],
+    ) -> Result<usize, PlatformOpsError> {
+        if sensor_id != STATE_SENSOR_ID {
+            return Err(PlatformOpsError::InvalidSensorId);
+        }
+        let field = state_fields
+            .first_mut()
+            .ok_or(PlatformOpsError::BufferTooSmall)?;
+        *field = StateField::new(
+            SensorOperationalState::Enabled,
+            HEALTH_STATE_NORMAL,
+            HEALTH_STATE_NORMAL,
+            HEALTH_STATE_NORMAL,
+        );
+        Ok(1)
+    }
+}
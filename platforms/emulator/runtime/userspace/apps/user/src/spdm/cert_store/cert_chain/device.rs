@@ -1,65 +1,119 @@
 // Licensed under the Apache-2.0 license
 
+use crate::spdm::cert_store::cert_chain::der_cert_len;
 use caliptra_mcu_libapi_caliptra::certificate::CertContext;
 use caliptra_mcu_libapi_caliptra::crypto::asym::AsymAlgo;
 use caliptra_mcu_spdm_lib::cert_store::{CertStoreError, CertStoreResult};
 
 const MAX_CERT_PORTION_SIZE: usize = 1024;
 
+/// The device certificate that the endorsement portion of the chain ends with
 pub enum DeviceCertIndex {
     IdevId, // Device Identity Certificate
+    LdevId, // Locally significant Device Identity Certificate (issued by the owner)
             // Other device certificate indices can be added here in the future
 }
 
+/// Read a chunk of the DPE cert chain of the asymmetric algorithm.
+async fn read_dpe_cert_chain(
+    asym_algo: AsymAlgo,
+    offset: usize,
+    cert_portion: &mut [u8],
+) -> CertStoreResult<usize> {
+    let mut cert_ctx = CertContext::new();
+    match asym_algo {
+        AsymAlgo::EccP384 => cert_ctx.cert_chain_chunk(offset, cert_portion).await,
+        AsymAlgo::MlDsa87 => {
+            cert_ctx
+                .mldsa87_cert_chain_chunk(offset, cert_portion)
+                .await
+        }
+    }
+    .map_err(CertStoreError::CaliptraApi)
+}
+
+/// Read the LDevID certificate of the device, the first certificate of the DPE cert chain.
+///
+/// # Arguments
+/// * `asym_algo` - The asymmetric algorithm of the LDevID key.
+/// * `buf` - The buffer to read the certificate into.
+///
+/// # Returns
+/// The size of the certificate in bytes.
+pub(crate) async fn read_ldevid_cert(
+    asym_algo: AsymAlgo,
+    buf: &mut [u8],
+) -> CertStoreResult<usize> {
+    let mut hdr = [0u8; 4];
+    read_dpe_cert_chain(asym_algo, 0, &mut hdr).await?;
+    let cert_len = der_cert_len(&hdr)?;
+    if cert_len > buf.len() {
+        return Err(CertStoreError::BufferTooSmall);
+    }
+
+    let mut offset = 0;
+    while offset < cert_len {
+        let end = cert_len.min(offset + MAX_CERT_PORTION_SIZE);
+        let size = read_dpe_cert_chain(asym_algo, offset, &mut buf[offset..end]).await?;
+        if size == 0 {
+            return Err(CertStoreError::CertReadError);
+        }
+        offset += size;
+    }
+    Ok(cert_len)
+}
+
 pub(crate) struct DpeCertChain {
     cert_id: DeviceCertIndex,
-    cert_chain_len: Option<usize>,
+    ecc_cert_chain_len: Option<usize>,
+    mldsa87_cert_chain_len: Option<usize>,
 }
 
 impl DpeCertChain {
     pub fn new(cert_id: DeviceCertIndex) -> Self {
         Self {
             cert_id,
-            cert_chain_len: None,
+            ecc_cert_chain_len: None,
+            mldsa87_cert_chain_len: None,
         }
     }
 
-    async fn read_device_ecc_cert_chain(
-        &self,
-        offset: usize,
-        cert_portion: &mut [u8],
-    ) -> CertStoreResult<usize> {
-        CertContext::new()
-            .cert_chain_chunk(offset, cert_portion)
-            .await
-            .map_err(CertStoreError::CaliptraApi)
+    fn cert_chain_len(&mut self, asym_algo: AsymAlgo) -> &mut Option<usize> {
+        match asym_algo {
+            AsymAlgo::EccP384 => &mut self.ecc_cert_chain_len,
+            AsymAlgo::MlDsa87 => &mut self.mldsa87_cert_chain_len,
+        }
     }
 
-    async fn cert_chain_offset(&self) -> usize {
+    async fn cert_chain_offset(&self, asym_algo: AsymAlgo) -> CertStoreResult<usize> {
         match self.cert_id {
-            DeviceCertIndex::IdevId => 0,
+            DeviceCertIndex::IdevId => Ok(0),
+            // The DPE cert chain starts with the LDevID certificate, skip it
+            DeviceCertIndex::LdevId => {
+                let mut hdr = [0u8; 4];
+                read_dpe_cert_chain(asym_algo, 0, &mut hdr).await?;
+                der_cert_len(&hdr)
+            }
         }
     }
 
     pub fn refresh(&mut self) {
-        self.cert_chain_len = None; // Reset the certificate chain length
+        // Reset the certificate chain lengths
+        self.ecc_cert_chain_len = None;
+        self.mldsa87_cert_chain_len = None;
     }
 
     pub async fn size(&mut self, asym_algo: AsymAlgo) -> CertStoreResult<usize> {
-        if asym_algo != AsymAlgo::EccP384 {
-            return Err(CertStoreError::UnsupportedAsymAlgo);
-        }
-
-        if let Some(len) = self.cert_chain_len {
+        if let Some(len) = *self.cert_chain_len(asym_algo) {
             return Ok(len);
         }
 
         let mut cert_chain_len = 0;
-        let mut offset = self.cert_chain_offset().await;
+        let mut offset = self.cert_chain_offset(asym_algo).await?;
         let mut buf = [0u8; MAX_CERT_PORTION_SIZE];
 
         loop {
-            let size = self.read_device_ecc_cert_chain(offset, &mut buf).await?;
+            let size = read_dpe_cert_chain(asym_algo, offset, &mut buf).await?;
             cert_chain_len += size;
             offset += size;
             if size < MAX_CERT_PORTION_SIZE {
@@ -67,7 +121,7 @@ impl DpeCertChain {
             }
         }
 
-        self.cert_chain_len = Some(cert_chain_len);
+        *self.cert_chain_len(asym_algo) = Some(cert_chain_len);
         Ok(cert_chain_len)
     }
 
@@ -77,19 +131,12 @@ impl DpeCertChain {
         offset: usize,
         buf: &mut [u8],
     ) -> CertStoreResult<usize> {
-        if asym_algo != AsymAlgo::EccP384 {
-            return Err(CertStoreError::UnsupportedAsymAlgo);
-        }
-
-        if self.cert_chain_len.is_none() {
-            self.size(asym_algo).await?;
-        }
-
-        let cert_chain_len = self.cert_chain_len.ok_or(CertStoreError::CertReadError)?;
+        let cert_chain_len = self.size(asym_algo).await?;
         if offset >= cert_chain_len {
             return Err(CertStoreError::InvalidOffset);
         }
 
-        self.read_device_ecc_cert_chain(offset, buf).await
+        let base_offset = self.cert_chain_offset(asym_algo).await?;
+        read_dpe_cert_chain(asym_algo, base_offset + offset, buf).await
     }
 }
@@ -10,6 +10,12 @@ use caliptra_mcu_spdm_lib::cert_store::CertStoreResult;
 
 #[async_trait]
 pub trait EndorsementCertChainTrait: Send + Sync {
+    /// Check if the endorsement cert chain is provisioned for the asymmetric algorithm.
+    ///
+    /// # Arguments
+    /// * `asym_algo` - The asymmetric algorithm to indicate the type of endorsement cert
+    fn supports(&self, asym_algo: AsymAlgo) -> bool;
+
     /// Get the root cert hash of the endorsement cert chain.
     ///
     /// # Arguments
@@ -1,13 +1,14 @@
 // Licensed under the Apache-2.0 license
 
 use caliptra_mcu_libapi_caliptra::certificate::CertContext;
-use caliptra_mcu_libapi_caliptra::crypto::asym::{AsymAlgo, ECC_P384_SIGNATURE_SIZE};
+use caliptra_mcu_libapi_caliptra::crypto::asym::{AsymAlgo, MLDSA87_PUBLIC_KEY_SIZE};
 use caliptra_mcu_libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
 use caliptra_mcu_spdm_lib::cert_store::{CertStoreError, CertStoreResult};
 use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
 use embassy_sync::mutex::Mutex;
 
 const DPE_LEAF_CERT_SIZE: usize = 2048; // Size of the DPE leaf certificate buffer.
+const DPE_MLDSA87_LEAF_CERT_SIZE: usize = 8192; // Size of the ML-DSA-87 DPE leaf certificate buffer.
 
 pub const DPE_LEAF_CERT_LABEL: [u8; SHA384_HASH_SIZE] = [
     0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
@@ -15,9 +16,12 @@ pub const DPE_LEAF_CERT_LABEL: [u8; SHA384_HASH_SIZE] = [
     0x20, 0x21, 0x22, 0x23, 0x24, 0x25, 0x26, 0x27, 0x28, 0x29, 0x2a, 0x2b, 0x2c, 0x2d, 0x2e, 0x2f,
 ];
 
-static SHARED_DPE_LEAF_CERT: Mutex<CriticalSectionRawMutex, DpeLeafCertBuf> =
+static SHARED_DPE_LEAF_CERT: Mutex<CriticalSectionRawMutex, DpeLeafCertBuf<DPE_LEAF_CERT_SIZE>> =
     Mutex::new(DpeLeafCertBuf::new());
 
+static SHARED_DPE_MLDSA87_LEAF_CERT: Mutex<CriticalSectionRawMutex, DpeMldsa87LeafCertBuf> =
+    Mutex::new(DpeMldsa87LeafCertBuf::new());
+
 pub(crate) struct DpeLeafCert;
 
 impl DpeLeafCert {
@@ -28,16 +32,27 @@ impl DpeLeafCert {
 
 impl DpeLeafCert {
     pub async fn refresh(&self) {
-        let mut dpe_leaf = SHARED_DPE_LEAF_CERT.lock().await;
-        dpe_leaf.reset();
+        SHARED_DPE_LEAF_CERT.lock().await.reset();
+        SHARED_DPE_MLDSA87_LEAF_CERT.lock().await.reset();
     }
 
     pub async fn size(&mut self, asym_algo: AsymAlgo) -> CertStoreResult<usize> {
-        let mut dpe_leaf = SHARED_DPE_LEAF_CERT.lock().await;
-        if dpe_leaf.size().is_none() {
-            dpe_leaf.fetch_cert(asym_algo).await?;
+        match asym_algo {
+            AsymAlgo::EccP384 => {
+                let mut dpe_leaf = SHARED_DPE_LEAF_CERT.lock().await;
+                if dpe_leaf.size().is_none() {
+                    dpe_leaf.fetch_cert().await?;
+                }
+                Ok(dpe_leaf.size().unwrap_or(0))
+            }
+            AsymAlgo::MlDsa87 => {
+                let mut dpe_leaf = SHARED_DPE_MLDSA87_LEAF_CERT.lock().await;
+                if dpe_leaf.cert.size().is_none() {
+                    dpe_leaf.fetch_cert().await?;
+                }
+                Ok(dpe_leaf.cert.size().unwrap_or(0))
+            }
         }
-        Ok(dpe_leaf.size().unwrap_or(0))
     }
 
     pub async fn read(
@@ -46,42 +61,69 @@ impl DpeLeafCert {
         offset: usize,
         buf: &mut [u8],
     ) -> CertStoreResult<usize> {
-        let mut dpe_leaf = SHARED_DPE_LEAF_CERT.lock().await;
-        if dpe_leaf.size().is_none() {
-            dpe_leaf.fetch_cert(asym_algo).await?;
+        match asym_algo {
+            AsymAlgo::EccP384 => {
+                let mut dpe_leaf = SHARED_DPE_LEAF_CERT.lock().await;
+                if dpe_leaf.size().is_none() {
+                    dpe_leaf.fetch_cert().await?;
+                }
+                dpe_leaf.read(offset, buf)
+            }
+            AsymAlgo::MlDsa87 => {
+                let mut dpe_leaf = SHARED_DPE_MLDSA87_LEAF_CERT.lock().await;
+                if dpe_leaf.cert.size().is_none() {
+                    dpe_leaf.fetch_cert().await?;
+                }
+                dpe_leaf.cert.read(offset, buf)
+            }
         }
-        dpe_leaf.read(offset, buf)
     }
 
     pub async fn sign(
         &self,
         asym_algo: AsymAlgo,
         hash: &[u8; SHA384_HASH_SIZE],
-        signature: &mut [u8; ECC_P384_SIGNATURE_SIZE],
+        signature: &mut [u8],
     ) -> CertStoreResult<()> {
+        if asym_algo != AsymAlgo::EccP384 {
+            return Err(CertStoreError::UnsupportedAsymAlgo);
+        }
         let dpe_leaf = SHARED_DPE_LEAF_CERT.lock().await;
-        dpe_leaf.sign(asym_algo, hash, signature).await
+        dpe_leaf.sign(hash, signature).await
+    }
+
+    pub async fn sign_message(
+        &self,
+        asym_algo: AsymAlgo,
+        message: &[u8],
+        signature: &mut [u8],
+    ) -> CertStoreResult<()> {
+        if asym_algo != AsymAlgo::MlDsa87 {
+            return Err(CertStoreError::UnsupportedAsymAlgo);
+        }
+        let mut dpe_leaf = SHARED_DPE_MLDSA87_LEAF_CERT.lock().await;
+        if dpe_leaf.cert.size().is_none() {
+            dpe_leaf.fetch_cert().await?;
+        }
+        dpe_leaf.sign(message, signature).await
     }
 }
 
-struct DpeLeafCertBuf {
-    buffer: [u8; DPE_LEAF_CERT_SIZE],
+struct DpeLeafCertBuf<const SIZE: usize> {
+    buffer: [u8; SIZE],
     size: Option<usize>,
 }
 
-impl Default for DpeLeafCertBuf {
+impl<const SIZE: usize> Default for DpeLeafCertBuf<SIZE> {
     fn default() -> Self {
-        Self {
-            buffer: [0; DPE_LEAF_CERT_SIZE],
-            size: None,
-        }
+        Self::new()
     }
 }
 
-impl DpeLeafCertBuf {
+impl<const SIZE: usize> DpeLeafCertBuf<SIZE> {
     const fn new() -> Self {
         Self {
-            buffer: [0; DPE_LEAF_CERT_SIZE],
+            buffer: [0; SIZE],
             size: None,
         }
     }
@@ -91,26 +133,11 @@ impl DpeLeafCertBuf {
         self.size = None;
     }
 
-    async fn fetch_cert(&mut self, asym_algo: AsymAlgo) -> CertStoreResult<()> {
-        if asym_algo != AsymAlgo::EccP384 {
-            return Err(CertStoreError::UnsupportedAsymAlgo);
-        }
-
-        self.reset();
-
-        let mut cert_ctx = CertContext::new();
-
-        let size = cert_ctx
-            .certify_key(&mut self.buffer, Some(&DPE_LEAF_CERT_LABEL), None, None)
-            .await
-            .map_err(CertStoreError::CaliptraApi)?;
-
-        if size > DPE_LEAF_CERT_SIZE {
+    fn set_size(&mut self, size: usize) -> CertStoreResult<()> {
+        if size > SIZE {
             return Err(CertStoreError::BufferTooSmall);
         }
-
         self.size = Some(size);
-
         Ok(())
     }
 
@@ -126,16 +153,27 @@ impl DpeLeafCertBuf {
         buf[..size_to_read].copy_from_slice(&self.buffer[offset..offset + size_to_read]);
         Ok(size_to_read)
     }
+}
+
+impl DpeLeafCertBuf<DPE_LEAF_CERT_SIZE> {
+    async fn fetch_cert(&mut self) -> CertStoreResult<()> {
+        self.reset();
+
+        let mut cert_ctx = CertContext::new();
+
+        let size = cert_ctx
+            .certify_key(&mut self.buffer, Some(&DPE_LEAF_CERT_LABEL), None, None)
+            .await
+            .map_err(CertStoreError::CaliptraApi)?;
+
+        self.set_size(size)
+    }
 
     async fn sign(
         &self,
-        asym_algo: AsymAlgo,
         hash: &[u8; SHA384_HASH_SIZE],
-        signature: &mut [u8; ECC_P384_SIGNATURE_SIZE],
+        signature: &mut [u8],
     ) -> CertStoreResult<()> {
-        if asym_algo != AsymAlgo::EccP384 {
-            return Err(CertStoreError::UnsupportedAsymAlgo);
-        }
         let mut cert_ctx = CertContext::new();
         cert_ctx
             .sign(Some(&DPE_LEAF_CERT_LABEL), hash, signature)
@@ -144,3 +182,55 @@ impl DpeLeafCertBuf {
         Ok(())
     }
 }
+
+/// ML-DSA-87 DPE leaf certificate. The public key of the leaf key is kept with the
+/// certificate, as it is needed to sign with the key.
+struct DpeMldsa87LeafCertBuf {
+    cert: DpeLeafCertBuf<DPE_MLDSA87_LEAF_CERT_SIZE>,
+    public_key: [u8; MLDSA87_PUBLIC_KEY_SIZE],
+}
+
+impl DpeMldsa87LeafCertBuf {
+    const fn new() -> Self {
+        Self {
+            cert: DpeLeafCertBuf::new(),
+            public_key: [0; MLDSA87_PUBLIC_KEY_SIZE],
+        }
+    }
+
+    fn reset(&mut self) {
+        self.cert.reset();
+        self.public_key.fill(0);
+    }
+
+    async fn fetch_cert(&mut self) -> CertStoreResult<()> {
+        self.reset();
+
+        let mut cert_ctx = CertContext::new();
+
+        let size = cert_ctx
+            .certify_key_mldsa87(
+                &mut self.cert.buffer,
+                Some(&DPE_LEAF_CERT_LABEL),
+                Some(&mut self.public_key),
+            )
+            .await
+            .map_err(CertStoreError::CaliptraApi)?;
+
+        self.cert.set_size(size)
+    }
+
+    async fn sign(&self, message: &[u8], signature: &mut [u8]) -> CertStoreResult<()> {
+        let mut cert_ctx = CertContext::new();
+        cert_ctx
+            .sign_mldsa87(
+                Some(&DPE_LEAF_CERT_LABEL),
+                &self.public_key,
+                message,
+                signature,
+            )
+            .await
+            .map_err(CertStoreError::CaliptraApi)?;
+        Ok(())
+    }
+}
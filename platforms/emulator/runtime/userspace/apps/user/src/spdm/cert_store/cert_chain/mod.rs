@@ -18,11 +18,113 @@ pub(crate) mod leaf;
 use crate::spdm::cert_store::cert_chain::device::{DeviceCertIndex, DpeCertChain};
 pub use crate::spdm::cert_store::cert_chain::endorsement::EndorsementCertChainTrait;
 use crate::spdm::cert_store::cert_chain::leaf::DpeLeafCert;
-use caliptra_mcu_libapi_caliptra::crypto::asym::{AsymAlgo, ECC_P384_SIGNATURE_SIZE};
+use caliptra_mcu_libapi_caliptra::crypto::asym::AsymAlgo;
 use caliptra_mcu_libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
 use caliptra_mcu_spdm_lib::cert_store::CertStoreError;
 use caliptra_mcu_spdm_lib::cert_store::CertStoreResult;
 
+/// Get the length of the ASN.1 DER-encoded certificate at the start of the buffer.
+///
+/// # Arguments
+/// * `der` - The buffer starting with a DER-encoded certificate (SEQUENCE).
+///
+/// # Returns
+/// The length of the certificate in bytes, including the tag and length fields.
+pub(crate) fn der_cert_len(der: &[u8]) -> CertStoreResult<usize> {
+    match der {
+        [0x30, 0x82, len_hi, len_lo, ..] => Ok(4 + u16::from_be_bytes([*len_hi, *len_lo]) as usize),
+        [0x30, 0x81, len, ..] => Ok(3 + *len as usize),
+        _ => Err(CertStoreError::InvalidCertChain),
+    }
+}
+
+/// Split the ASN.1 DER TLV at the start of the buffer.
+///
+/// # Returns
+/// The tag, the length of the tag and length fields and the length of the value.
+fn der_tlv(der: &[u8]) -> CertStoreResult<(u8, usize, usize)> {
+    let (tag, hdr_len, len) = match der {
+        [tag, 0x82, len_hi, len_lo, ..] => {
+            (*tag, 4, u16::from_be_bytes([*len_hi, *len_lo]) as usize)
+        }
+        [tag, 0x81, len, ..] => (*tag, 3, *len as usize),
+        [tag, len, ..] if *len < 0x80 => (*tag, 2, *len as usize),
+        _ => return Err(CertStoreError::InvalidCertChain),
+    };
+    if der.len() < hdr_len + len {
+        return Err(CertStoreError::InvalidCertChain);
+    }
+    Ok((tag, hdr_len, len))
+}
+
+/// Get the DER-encoded SubjectPublicKeyInfo of a certificate.
+///
+/// # Arguments
+/// * `cert` - The DER-encoded certificate.
+///
+/// # Returns
+/// The SubjectPublicKeyInfo, including its tag and length fields.
+pub(crate) fn subject_public_key_info(cert: &[u8]) -> CertStoreResult<&[u8]> {
+    const DER_SEQUENCE: u8 = 0x30;
+    const DER_VERSION: u8 = 0xA0;
+
+    // Certificate ::= SEQUENCE { tbsCertificate SEQUENCE { ... }, ... }
+    let (tag, cert_hdr_len, _) = der_tlv(cert)?;
+    if tag != DER_SEQUENCE {
+        return Err(CertStoreError::InvalidCertChain);
+    }
+    let (tag, tbs_hdr_len, tbs_len) = der_tlv(&cert[cert_hdr_len..])?;
+    if tag != DER_SEQUENCE {
+        return Err(CertStoreError::InvalidCertChain);
+    }
+    let mut tbs = &cert[cert_hdr_len + tbs_hdr_len..cert_hdr_len + tbs_hdr_len + tbs_len];
+
+    // Skip the optional version, then serialNumber, signature, issuer, validity and subject
+    let mut skip = usize::from(tbs.first() == Some(&DER_VERSION)) + 5;
+    while skip > 0 {
+        let (_, hdr_len, len) = der_tlv(tbs)?;
+        tbs = &tbs[hdr_len + len..];
+        skip -= 1;
+    }
+
+    let (tag, hdr_len, len) = der_tlv(tbs)?;
+    if tag != DER_SEQUENCE {
+        return Err(CertStoreError::InvalidCertChain);
+    }
+    Ok(&tbs[..hdr_len + len])
+}
+
+/// Get the asymmetric algorithm of a DER-encoded SubjectPublicKeyInfo.
+///
+/// # Arguments
+/// * `spki` - The SubjectPublicKeyInfo, including its tag and length fields.
+///
+/// # Returns
+/// The asymmetric algorithm of the key, or `CertStoreError::UnsupportedAsymAlgo`.
+pub(crate) fn spki_asym_algo(spki: &[u8]) -> CertStoreResult<AsymAlgo> {
+    // id-ecPublicKey (1.2.840.10045.2.1) with the secp384r1 curve (1.3.132.0.34)
+    const ECC_P384_ALGORITHM: &[u8] = &[
+        0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x05, 0x2b, 0x81, 0x04, 0x00,
+        0x22,
+    ];
+    // id-ml-dsa-87 (2.16.840.1.101.3.4.3.19)
+    const MLDSA87_ALGORITHM: &[u8] = &[
+        0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x03, 0x13,
+    ];
+
+    // SubjectPublicKeyInfo ::= SEQUENCE { algorithm AlgorithmIdentifier, subjectPublicKey BIT STRING }
+    let (_, spki_hdr_len, _) = der_tlv(spki)?;
+    let (_, alg_hdr_len, alg_len) = der_tlv(&spki[spki_hdr_len..])?;
+    let algorithm = &spki[spki_hdr_len + alg_hdr_len..spki_hdr_len + alg_hdr_len + alg_len];
+    if algorithm == ECC_P384_ALGORITHM {
+        Ok(AsymAlgo::EccP384)
+    } else if algorithm == MLDSA87_ALGORITHM {
+        Ok(AsymAlgo::MlDsa87)
+    } else {
+        Err(CertStoreError::UnsupportedAsymAlgo)
+    }
+}
+
 /// Generic certificate chain that combines all certificate components
 pub struct CertChain {
     endorsement_cert_chain: &'static mut dyn EndorsementCertChainTrait,
@@ -42,6 +144,13 @@ impl CertChain {
         }
     }
 
+    /// Check if the chain can be provided for the asymmetric algorithm.
+    /// The DPE certificate chain and leaf key are available for ECC P-384 and ML-DSA-87,
+    /// so this depends on the algorithms the endorsement is provisioned for.
+    pub fn supports(&self, asym_algo: AsymAlgo) -> bool {
+        self.endorsement_cert_chain.supports(asym_algo)
+    }
+
     #[allow(dead_code)]
     pub async fn refresh(&mut self) {
         self.endorsement_cert_chain.refresh().await;
@@ -124,8 +233,19 @@ impl CertChain {
         &self,
         asym_algo: AsymAlgo,
         hash: &'a [u8; SHA384_HASH_SIZE],
-        signature: &'a mut [u8; ECC_P384_SIGNATURE_SIZE],
+        signature: &'a mut [u8],
     ) -> CertStoreResult<()> {
         self.leaf_cert.sign(asym_algo, hash, signature).await
     }
+
+    pub async fn sign_message<'a>(
+        &self,
+        asym_algo: AsymAlgo,
+        message: &'a [u8],
+        signature: &'a mut [u8],
+    ) -> CertStoreResult<()> {
+        self.leaf_cert
+            .sign_message(asym_algo, message, signature)
+            .await
+    }
 }
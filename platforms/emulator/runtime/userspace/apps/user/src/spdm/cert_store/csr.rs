@@ -0,0 +1,79 @@
+// Licensed under the Apache-2.0 license
+
+use caliptra_mcu_libapi_caliptra::certificate::{CertContext, MAX_ATTESTED_CSR_SIZE};
+use caliptra_mcu_libapi_caliptra::crypto::asym::AsymAlgo;
+use caliptra_mcu_libapi_caliptra::crypto::rng::Rng;
+use caliptra_mcu_libapi_caliptra::error::CaliptraApiError;
+use caliptra_mcu_spdm_lib::cert_store::{CertStoreError, CertStoreResult};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::mutex::Mutex;
+
+// Caliptra key ID of the LDevID key (matches DeviceKeyId in VDM protocol)
+const CSR_KEY_ID_LDEVID: u32 = 0x0001;
+
+static SHARED_CSR: Mutex<CriticalSectionRawMutex, CsrBuf> = Mutex::new(CsrBuf::new());
+
+struct CsrBuf {
+    buffer: [u8; MAX_ATTESTED_CSR_SIZE],
+    size: usize,
+}
+
+impl CsrBuf {
+    const fn new() -> Self {
+        Self {
+            buffer: [0; MAX_ATTESTED_CSR_SIZE],
+            size: 0,
+        }
+    }
+}
+
+/// Generate a Caliptra attested CSR for the LDevID key. The CSR replaces the
+/// previously generated one and is read back with `read_csr`.
+///
+/// # Arguments
+/// * `asym_algo` - The asymmetric algorithm of the key to certify.
+///
+/// # Returns
+/// The size of the attested CSR in bytes.
+pub(crate) async fn generate_attested_csr(asym_algo: AsymAlgo) -> CertStoreResult<usize> {
+    let mut nonce = [0u8; 32];
+    Rng::generate_random_number(&mut nonce)
+        .await
+        .map_err(CertStoreError::CaliptraApi)?;
+
+    let mut csr = SHARED_CSR.lock().await;
+    csr.size = 0;
+
+    let mut cert_ctx = CertContext::new();
+    let size = loop {
+        match cert_ctx
+            .get_attested_csr(asym_algo, CSR_KEY_ID_LDEVID, &nonce, &mut csr.buffer)
+            .await
+        {
+            Ok(size) => break size,
+            Err(CaliptraApiError::MailboxBusy) => continue, // Retry if the mailbox is busy
+            Err(e) => Err(CertStoreError::CaliptraApi(e))?,
+        }
+    };
+
+    csr.size = size;
+    Ok(size)
+}
+
+/// Read the last generated attested CSR in portion.
+///
+/// # Arguments
+/// * `offset` - The offset to start reading from.
+/// * `buf` - The buffer to read the CSR portion into.
+///
+/// # Returns
+/// The number of bytes read.
+pub(crate) async fn read_csr(offset: usize, buf: &mut [u8]) -> CertStoreResult<usize> {
+    let csr = SHARED_CSR.lock().await;
+    if offset >= csr.size {
+        return Err(CertStoreError::InvalidOffset);
+    }
+    let len = (csr.size - offset).min(buf.len());
+    buf[..len].copy_from_slice(&csr.buffer[offset..offset + len]);
+    Ok(len)
+}
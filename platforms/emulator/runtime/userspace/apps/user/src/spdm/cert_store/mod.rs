@@ -1,14 +1,23 @@
 // Licensed under the Apache-2.0 license
 
+extern crate alloc;
+
 pub(crate) mod cert_chain;
+mod csr;
 
+use crate::spdm::cert_store::cert_chain::device::DeviceCertIndex;
 use crate::spdm::cert_store::cert_chain::CertChain;
-use caliptra_mcu_libapi_caliptra::crypto::asym::{AsymAlgo, ECC_P384_SIGNATURE_SIZE};
+use crate::spdm::endorsement_certs::owner::OwnerCertChain;
+use alloc::boxed::Box;
+use caliptra_mcu_libapi_caliptra::crypto::asym::AsymAlgo;
 use caliptra_mcu_libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
 use caliptra_mcu_spdm_lib::cert_store::{
     CertStoreError, CertStoreResult, MAX_CERT_SLOTS_SUPPORTED,
 };
 
+/// Slot provisioned in the field with an owner-issued cert chain
+const OWNER_CERT_SLOT: u8 = 1;
+
 pub struct DeviceCertStore {
     cert_chains: [Option<CertChain>; MAX_CERT_SLOTS_SUPPORTED as usize],
 }
@@ -55,8 +64,9 @@ impl DeviceCertStore {
         MAX_CERT_SLOTS_SUPPORTED
     }
 
-    pub fn is_provisioned(&self, slot: u8) -> bool {
-        self.cert_chain(slot).is_ok()
+    pub fn is_provisioned(&self, slot: u8, asym_algo: AsymAlgo) -> bool {
+        self.cert_chain(slot)
+            .is_ok_and(|cert_chain| cert_chain.supports(asym_algo))
     }
 
     pub async fn cert_chain_len(
@@ -94,9 +104,66 @@ impl DeviceCertStore {
         asym_algo: AsymAlgo,
         slot_id: u8,
         hash: &'a [u8; SHA384_HASH_SIZE],
-        signature: &'a mut [u8; ECC_P384_SIGNATURE_SIZE],
+        signature: &'a mut [u8],
     ) -> CertStoreResult<()> {
         let cert_chain = self.cert_chain(slot_id)?;
         cert_chain.sign(asym_algo, hash, signature).await
     }
+
+    pub async fn sign_message<'a>(
+        &self,
+        asym_algo: AsymAlgo,
+        slot_id: u8,
+        message: &'a [u8],
+        signature: &'a mut [u8],
+    ) -> CertStoreResult<()> {
+        let cert_chain = self.cert_chain(slot_id)?;
+        cert_chain.sign_message(asym_algo, message, signature).await
+    }
+
+    pub async fn generate_csr(&self) -> CertStoreResult<usize> {
+        csr::generate_attested_csr(AsymAlgo::EccP384).await
+    }
+
+    pub async fn get_csr(&self, offset: usize, csr_portion: &mut [u8]) -> CertStoreResult<usize> {
+        csr::read_csr(offset, csr_portion).await
+    }
+
+    pub async fn install_owner_cert_chain(
+        &mut self,
+        slot_id: u8,
+        cert_chain: &[u8],
+    ) -> CertStoreResult<()> {
+        if slot_id >= MAX_CERT_SLOTS_SUPPORTED {
+            return Err(CertStoreError::InvalidSlotId);
+        }
+        // Slot 0 holds the vendor endorsed chain and is read-only
+        if slot_id != OWNER_CERT_SLOT {
+            return Err(CertStoreError::UnsupportedOperation);
+        }
+
+        // Drop the current chain before the owner chain buffer is overwritten
+        self.cert_chains[slot_id as usize] = None;
+        let owner_cert_chain = OwnerCertChain::install(cert_chain).await?;
+
+        // The owner chain ends with the LDevID certificate, the device provides the rest
+        let cert_chain = CertChain::new(
+            Box::leak(Box::new(owner_cert_chain)),
+            DeviceCertIndex::LdevId,
+        );
+        self.set_cert_chain(slot_id, cert_chain)
+    }
+
+    pub async fn erase_owner_cert_chain(&mut self, slot_id: u8) -> CertStoreResult<()> {
+        if slot_id >= MAX_CERT_SLOTS_SUPPORTED {
+            return Err(CertStoreError::InvalidSlotId);
+        }
+        if slot_id != OWNER_CERT_SLOT {
+            return Err(CertStoreError::UnsupportedOperation);
+        }
+
+        self.cert_chains[slot_id as usize] = None;
+        OwnerCertChain::erase().await;
+        Ok(())
+    }
 }
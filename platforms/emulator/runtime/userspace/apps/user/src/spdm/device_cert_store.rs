@@ -8,10 +8,10 @@ use crate::spdm::cert_store::DeviceCertStore;
 use crate::spdm::endorsement_certs::EndorsementCertChain;
 use alloc::boxed::Box;
 use async_trait::async_trait;
-use caliptra_mcu_libapi_caliptra::crypto::asym::{AsymAlgo, ECC_P384_SIGNATURE_SIZE};
+use caliptra_mcu_libapi_caliptra::crypto::asym::AsymAlgo;
 use caliptra_mcu_libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
 use caliptra_mcu_spdm_lib::cert_store::{CertStoreError, CertStoreResult, SpdmCertStore};
-use caliptra_mcu_spdm_lib::protocol::{CertificateInfo, KeyUsageMask};
+use caliptra_mcu_spdm_lib::protocol::{CertModel, CertificateInfo, KeyPairInfo, KeyUsageMask};
 use core::mem::MaybeUninit;
 use core::sync::atomic::{AtomicBool, Ordering};
 use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
@@ -112,10 +112,10 @@ impl SpdmCertStore for SharedCertStore {
         }
     }
 
-    async fn is_provisioned(&self, slot: u8) -> bool {
+    async fn is_provisioned(&self, slot: u8, asym_algo: AsymAlgo) -> bool {
         let cert_store = SHARED_CERT_STORE.lock().await;
         if let Some(cert_store) = cert_store.as_ref() {
-            cert_store.is_provisioned(slot)
+            cert_store.is_provisioned(slot, asym_algo)
         } else {
             false
         }
@@ -168,7 +168,7 @@ impl SpdmCertStore for SharedCertStore {
         slot_id: u8,
         asym_algo: AsymAlgo,
         hash: &'a [u8; SHA384_HASH_SIZE],
-        signature: &'a mut [u8; ECC_P384_SIGNATURE_SIZE],
+        signature: &'a mut [u8],
     ) -> CertStoreResult<()> {
         let cert_store = SHARED_CERT_STORE.lock().await;
         if let Some(cert_store) = cert_store.as_ref() {
@@ -180,6 +180,23 @@ impl SpdmCertStore for SharedCertStore {
         }
     }
 
+    async fn sign_message<'a>(
+        &self,
+        slot_id: u8,
+        asym_algo: AsymAlgo,
+        message: &'a [u8],
+        signature: &'a mut [u8],
+    ) -> CertStoreResult<()> {
+        let cert_store = SHARED_CERT_STORE.lock().await;
+        if let Some(cert_store) = cert_store.as_ref() {
+            cert_store
+                .sign_message(asym_algo, slot_id, message, signature)
+                .await
+        } else {
+            Err(CertStoreError::NotInitialized)
+        }
+    }
+
     async fn key_pair_id(&self, _slot_id: u8) -> Option<u8> {
         None
     }
@@ -191,4 +208,81 @@ impl SpdmCertStore for SharedCertStore {
     async fn key_usage_mask(&self, _slot_id: u8) -> Option<KeyUsageMask> {
         None
     }
+
+    async fn total_key_pairs(&self) -> u8 {
+        // Multiple asymmetric keys are not supported
+        0
+    }
+
+    async fn key_pair_info<'a>(
+        &self,
+        _key_pair_id: u8,
+        _public_key_info: &'a mut [u8],
+    ) -> CertStoreResult<(KeyPairInfo, usize)> {
+        Err(CertStoreError::UnsupportedOperation)
+    }
+
+    async fn generate_csr<'a>(
+        &self,
+        key_pair_id: u8,
+        _cert_model: CertModel,
+        _requester_info: &'a [u8],
+    ) -> CertStoreResult<usize> {
+        // Multiple asymmetric keys are not supported
+        if key_pair_id != 0 {
+            return Err(CertStoreError::UnsupportedOperation);
+        }
+
+        // The attested CSR is built by Caliptra, the RequesterInfo is not used
+        let cert_store = SHARED_CERT_STORE.lock().await;
+        if let Some(cert_store) = cert_store.as_ref() {
+            cert_store.generate_csr().await
+        } else {
+            Err(CertStoreError::NotInitialized)
+        }
+    }
+
+    async fn get_csr<'a>(
+        &self,
+        offset: usize,
+        csr_portion: &'a mut [u8],
+    ) -> CertStoreResult<usize> {
+        let cert_store = SHARED_CERT_STORE.lock().await;
+        if let Some(cert_store) = cert_store.as_ref() {
+            cert_store.get_csr(offset, csr_portion).await
+        } else {
+            Err(CertStoreError::NotInitialized)
+        }
+    }
+
+    async fn set_cert_chain<'a>(
+        &self,
+        slot_id: u8,
+        key_pair_id: u8,
+        _cert_model: CertModel,
+        cert_chain: &'a [u8],
+    ) -> CertStoreResult<()> {
+        // Multiple asymmetric keys are not supported
+        if key_pair_id != 0 {
+            return Err(CertStoreError::UnsupportedOperation);
+        }
+
+        let mut cert_store = SHARED_CERT_STORE.lock().await;
+        if let Some(cert_store) = cert_store.as_mut() {
+            cert_store
+                .install_owner_cert_chain(slot_id, cert_chain)
+                .await
+        } else {
+            Err(CertStoreError::NotInitialized)
+        }
+    }
+
+    async fn erase_cert_chain(&self, slot_id: u8) -> CertStoreResult<()> {
+        let mut cert_store = SHARED_CERT_STORE.lock().await;
+        if let Some(cert_store) = cert_store.as_mut() {
+            cert_store.erase_owner_cert_chain(slot_id).await
+        } else {
+            Err(CertStoreError::NotInitialized)
+        }
+    }
 }
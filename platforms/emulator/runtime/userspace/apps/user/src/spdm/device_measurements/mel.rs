@@ -0,0 +1,139 @@
+// Licensed under the Apache-2.0 license
+
+extern crate alloc;
+
+use crate::soc_env::{NUM_DEFAULT_FW_COMPONENTS, NUM_SOC_FW_COMPONENTS, SOC_FW_IDS};
+use crate::spdm::device_measurements::ocp_eat::claims::digest_words_to_bytes;
+use alloc::boxed::Box;
+use async_trait::async_trait;
+use caliptra_mcu_libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
+use caliptra_mcu_libapi_caliptra::evidence::device_state::DeviceState;
+use caliptra_mcu_spdm_lib::measurements::{MeasurementsError, MeasurementsResult, SpdmMelSource};
+use caliptra_mcu_spdm_lib::protocol::{MeasurementValueType, SPDM_MEASUREMENT_MANIFEST_INDEX};
+use core::mem::size_of;
+use zerocopy::{Immutable, IntoBytes};
+
+const MAX_MEL_ENTRIES: usize = NUM_DEFAULT_FW_COMPONENTS + NUM_SOC_FW_COMPONENTS;
+
+const MEL_ENTRY_SIZE: usize = size_of::<DmtfMelEntryHdr>() + SHA384_HASH_SIZE;
+const MAX_MEL_SIZE: usize = size_of::<DmtfMelHdr>() + MAX_MEL_ENTRIES * MEL_ENTRY_SIZE;
+
+/// Header of the Measurement Extension Log in DMTF MEL format
+#[derive(IntoBytes, Immutable)]
+#[repr(C, packed)]
+struct DmtfMelHdr {
+    number_of_entries: u32,
+    mel_entries_len: u32,
+    reserved: u32,
+}
+
+/// Header of a DMTF MEL entry, followed by the measurement value
+#[derive(IntoBytes, Immutable)]
+#[repr(C, packed)]
+struct DmtfMelEntryHdr {
+    mel_index: u32,
+    meas_index: u32,
+    reserved: [u8; 8],
+    meas_value_type: u8,
+    meas_value_size: u16,
+}
+
+/// Measurement Extension Log of the firmware measured into the manifest measurement
+/// block.
+///
+/// Each entry is one measurement event: the digest of a firmware component as it was
+/// measured by Caliptra, in load order. The Caliptra FMC and runtime come first,
+/// followed by the SoC images that have been authorized so far. A verifier can replay
+/// these events against the journey digests reported in the manifest.
+///
+/// The log is snapshotted when a Requester starts reading it at offset 0, so that all
+/// the portions of one transfer are read from the same log.
+pub struct FwMelSource {
+    mel: [u8; MAX_MEL_SIZE],
+    mel_len: usize,
+}
+
+impl FwMelSource {
+    pub fn new() -> Self {
+        FwMelSource {
+            mel: [0; MAX_MEL_SIZE],
+            mel_len: 0,
+        }
+    }
+
+    fn append_entry(&mut self, num_entries: &mut usize, digest: &[u8; SHA384_HASH_SIZE]) {
+        let entry_hdr = DmtfMelEntryHdr {
+            mel_index: *num_entries as u32,
+            meas_index: SPDM_MEASUREMENT_MANIFEST_INDEX as u32,
+            reserved: [0; 8],
+            // Digest representation (bit 7 cleared)
+            meas_value_type: MeasurementValueType::MutableFirmware as u8,
+            meas_value_size: SHA384_HASH_SIZE as u16,
+        };
+        let offset = size_of::<DmtfMelHdr>() + *num_entries * MEL_ENTRY_SIZE;
+        self.mel[offset..offset + size_of::<DmtfMelEntryHdr>()]
+            .copy_from_slice(entry_hdr.as_bytes());
+        self.mel[offset + size_of::<DmtfMelEntryHdr>()..offset + MEL_ENTRY_SIZE]
+            .copy_from_slice(digest);
+        *num_entries += 1;
+    }
+
+    async fn snapshot(&mut self) -> MeasurementsResult<()> {
+        let fw_info = DeviceState::fw_info()
+            .await
+            .map_err(MeasurementsError::CaliptraApi)?;
+
+        let mut num_entries = 0;
+        self.append_entry(
+            &mut num_entries,
+            &digest_words_to_bytes(&fw_info.fmc_sha384_digest),
+        );
+        self.append_entry(
+            &mut num_entries,
+            &digest_words_to_bytes(&fw_info.runtime_sha384_digest),
+        );
+
+        // SoC images are only measured once they have been authorized
+        for image_id in SOC_FW_IDS.iter() {
+            if let Ok(image_info) = DeviceState::image_info(*image_id).await {
+                self.append_entry(&mut num_entries, &image_info.digest);
+            }
+        }
+
+        let mel_hdr = DmtfMelHdr {
+            number_of_entries: num_entries as u32,
+            mel_entries_len: (num_entries * MEL_ENTRY_SIZE) as u32,
+            reserved: 0,
+        };
+        self.mel[..size_of::<DmtfMelHdr>()].copy_from_slice(mel_hdr.as_bytes());
+        self.mel_len = size_of::<DmtfMelHdr>() + num_entries * MEL_ENTRY_SIZE;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SpdmMelSource for FwMelSource {
+    async fn refresh(&mut self) -> MeasurementsResult<()> {
+        self.snapshot().await
+    }
+
+    async fn mel_size(&mut self) -> MeasurementsResult<usize> {
+        if self.mel_len == 0 {
+            self.snapshot().await?;
+        }
+        Ok(self.mel_len)
+    }
+
+    async fn read_mel(
+        &mut self,
+        offset: usize,
+        mel_portion: &mut [u8],
+    ) -> MeasurementsResult<usize> {
+        if offset >= self.mel_len {
+            return Err(MeasurementsError::InvalidOffset);
+        }
+        let len = (self.mel_len - offset).min(mel_portion.len());
+        mel_portion[..len].copy_from_slice(&self.mel[offset..offset + len]);
+        Ok(len)
+    }
+}
@@ -1,4 +1,5 @@
 // Licensed under the Apache-2.0 license
 
+pub mod mel;
 pub mod ocp_eat;
 pub mod pcr_quote;
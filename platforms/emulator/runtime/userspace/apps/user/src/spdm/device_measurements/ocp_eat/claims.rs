@@ -265,7 +265,7 @@ fn version_to_str(ver: u32) -> ArrayString<MAX_SEMVER_LEN> {
     s
 }
 
-fn digest_words_to_bytes(words: &[u32; SHA384_HASH_WORDS]) -> [u8; SHA384_HASH_SIZE] {
+pub(crate) fn digest_words_to_bytes(words: &[u32; SHA384_HASH_WORDS]) -> [u8; SHA384_HASH_SIZE] {
     let mut digest = [0u8; SHA384_HASH_SIZE];
     for (i, word) in words.iter().enumerate() {
         digest[i * 4..(i + 1) * 4].copy_from_slice(&word.to_be_bytes());
@@ -2,6 +2,7 @@
 
 extern crate alloc;
 
+pub(crate) mod owner;
 mod slot0;
 
 use crate::spdm::cert_store::cert_chain::EndorsementCertChainTrait;
@@ -79,6 +80,11 @@ impl EndorsementCertChain<'_> {
 
 #[async_trait]
 impl EndorsementCertChainTrait for EndorsementCertChain<'_> {
+    fn supports(&self, asym_algo: AsymAlgo) -> bool {
+        // The vendor endorsement is only provisioned for ECC P-384
+        asym_algo == AsymAlgo::EccP384
+    }
+
     async fn root_cert_hash(
         &self,
         asym_algo: AsymAlgo,
@@ -0,0 +1,157 @@
+// Licensed under the Apache-2.0 license
+
+extern crate alloc;
+
+use crate::spdm::cert_store::cert_chain::device::read_ldevid_cert;
+use crate::spdm::cert_store::cert_chain::{
+    der_cert_len, spki_asym_algo, subject_public_key_info, EndorsementCertChainTrait,
+};
+use alloc::boxed::Box;
+use async_trait::async_trait;
+use caliptra_mcu_libapi_caliptra::crypto::asym::AsymAlgo;
+use caliptra_mcu_libapi_caliptra::crypto::hash::{HashAlgoType, HashContext, SHA384_HASH_SIZE};
+use caliptra_mcu_libapi_caliptra::error::CaliptraApiError;
+use caliptra_mcu_spdm_lib::cert_store::{CertStoreError, CertStoreResult};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::mutex::Mutex;
+
+const MAX_OWNER_CERT_CHAIN_SIZE: usize = 16384; // Size of the owner cert chain buffer.
+const MAX_LDEVID_CERT_SIZE: usize = 8192; // Size of the device LDevID cert buffer.
+
+static SHARED_OWNER_CERT_CHAIN: Mutex<CriticalSectionRawMutex, OwnerCertChainBuf> =
+    Mutex::new(OwnerCertChainBuf::new());
+
+struct OwnerCertChainBuf {
+    buffer: [u8; MAX_OWNER_CERT_CHAIN_SIZE],
+    size: usize,
+    root_cert_hash: [u8; SHA384_HASH_SIZE],
+}
+
+impl OwnerCertChainBuf {
+    const fn new() -> Self {
+        Self {
+            buffer: [0; MAX_OWNER_CERT_CHAIN_SIZE],
+            size: 0,
+            root_cert_hash: [0; SHA384_HASH_SIZE],
+        }
+    }
+}
+
+/// Owner-issued endorsement cert chain installed with SET_CERTIFICATE.
+/// The chain starts with the owner root certificate and ends with the
+/// owner-issued LDevID certificate. The chain is ECC P-384 or ML-DSA-87,
+/// depending on the LDevID key it certifies.
+pub struct OwnerCertChain {
+    asym_algo: AsymAlgo,
+}
+
+impl OwnerCertChain {
+    /// Install the owner-issued cert chain. The leaf certificate of the chain must
+    /// certify the device LDevID key.
+    ///
+    /// # Arguments
+    /// * `cert_chain` - The DER-encoded cert chain, starting with the root certificate.
+    pub async fn install(cert_chain: &[u8]) -> CertStoreResult<Self> {
+        if cert_chain.len() > MAX_OWNER_CERT_CHAIN_SIZE {
+            return Err(CertStoreError::BufferTooSmall);
+        }
+
+        // The chain must be a sequence of complete DER certificates
+        let mut offset = 0;
+        let mut leaf_offset = 0;
+        while offset < cert_chain.len() {
+            leaf_offset = offset;
+            offset += der_cert_len(&cert_chain[offset..])?;
+        }
+        if offset != cert_chain.len() {
+            return Err(CertStoreError::InvalidCertChain);
+        }
+
+        // The owner-issued leaf certificate must certify the LDevID key of the device,
+        // which signs the rest of the slot's chain
+        let leaf_spki = subject_public_key_info(&cert_chain[leaf_offset..])?;
+        let asym_algo = spki_asym_algo(leaf_spki)?;
+        let mut ldevid_cert = [0u8; MAX_LDEVID_CERT_SIZE];
+        let ldevid_cert_len = read_ldevid_cert(asym_algo, &mut ldevid_cert).await?;
+        if leaf_spki != subject_public_key_info(&ldevid_cert[..ldevid_cert_len])? {
+            return Err(CertStoreError::InvalidCertChain);
+        }
+
+        let root_cert_len = der_cert_len(cert_chain)?;
+        let mut root_hash = [0; SHA384_HASH_SIZE];
+        while let Err(e) = HashContext::hash_all(
+            HashAlgoType::SHA384,
+            &cert_chain[..root_cert_len],
+            &mut root_hash,
+        )
+        .await
+        {
+            match e {
+                CaliptraApiError::MailboxBusy => continue, // Retry if the mailbox is busy
+                _ => Err(CertStoreError::CaliptraApi(e))?,
+            }
+        }
+
+        let mut owner_chain = SHARED_OWNER_CERT_CHAIN.lock().await;
+        owner_chain.buffer[..cert_chain.len()].copy_from_slice(cert_chain);
+        owner_chain.size = cert_chain.len();
+        owner_chain.root_cert_hash = root_hash;
+        Ok(Self { asym_algo })
+    }
+
+    /// Erase the owner-issued cert chain.
+    pub async fn erase() {
+        let mut owner_chain = SHARED_OWNER_CERT_CHAIN.lock().await;
+        *owner_chain = OwnerCertChainBuf::new();
+    }
+}
+
+#[async_trait]
+impl EndorsementCertChainTrait for OwnerCertChain {
+    fn supports(&self, asym_algo: AsymAlgo) -> bool {
+        asym_algo == self.asym_algo
+    }
+
+    async fn root_cert_hash(
+        &self,
+        asym_algo: AsymAlgo,
+        root_hash: &mut [u8; SHA384_HASH_SIZE],
+    ) -> CertStoreResult<()> {
+        if !self.supports(asym_algo) {
+            return Err(CertStoreError::UnsupportedAsymAlgo);
+        }
+        let owner_chain = SHARED_OWNER_CERT_CHAIN.lock().await;
+        root_hash.copy_from_slice(&owner_chain.root_cert_hash);
+        Ok(())
+    }
+
+    async fn refresh(&mut self) {
+        // No-op for owner certs, as they only change with SET_CERTIFICATE
+    }
+
+    async fn size(&mut self, asym_algo: AsymAlgo) -> CertStoreResult<usize> {
+        if !self.supports(asym_algo) {
+            return Err(CertStoreError::UnsupportedAsymAlgo);
+        }
+        let owner_chain = SHARED_OWNER_CERT_CHAIN.lock().await;
+        Ok(owner_chain.size)
+    }
+
+    async fn read(
+        &mut self,
+        asym_algo: AsymAlgo,
+        offset: usize,
+        buf: &mut [u8],
+    ) -> CertStoreResult<usize> {
+        if !self.supports(asym_algo) {
+            return Err(CertStoreError::UnsupportedAsymAlgo);
+        }
+        let owner_chain = SHARED_OWNER_CERT_CHAIN.lock().await;
+        if offset >= owner_chain.size {
+            return Err(CertStoreError::InvalidOffset);
+        }
+        let len = (owner_chain.size - offset).min(buf.len());
+        buf[..len].copy_from_slice(&owner_chain.buffer[offset..offset + len]);
+        Ok(len)
+    }
+}
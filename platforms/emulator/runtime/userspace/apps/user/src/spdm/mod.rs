@@ -7,7 +7,11 @@ mod endorsement_certs;
 #[cfg(feature = "test-doe-spdm-tdisp-ide-validator")]
 mod integration_example;
 
+use crate::spdm::device_measurements::mel::FwMelSource;
 use crate::spdm::device_measurements::ocp_eat::init_target_env_claims;
+use caliptra_mcu_libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
+#[cfg(feature = "test-spdm-psk")]
+use caliptra_mcu_libapi_caliptra::crypto::import::{CmKeyUsage, Import};
 use caliptra_mcu_libsyscall_caliptra::doe;
 use caliptra_mcu_libsyscall_caliptra::mctp;
 use caliptra_mcu_libsyscall_caliptra::DefaultSyscalls;
@@ -18,21 +22,63 @@ use caliptra_mcu_spdm_lib::context::{SpdmContext, MAX_SPDM_RESPONDER_BUF_SIZE};
 use caliptra_mcu_spdm_lib::error::SpdmError;
 use caliptra_mcu_spdm_lib::measurements::SpdmMeasurements;
 use caliptra_mcu_spdm_lib::protocol::*;
+use caliptra_mcu_spdm_lib::psk_store::HkdfPskStore;
+use caliptra_mcu_spdm_lib::session::SessionSlot;
 use caliptra_mcu_spdm_lib::transport::common::SpdmTransport;
 use caliptra_mcu_spdm_lib::transport::common::TransportError;
 use caliptra_mcu_spdm_lib::transport::doe::DoeTransport;
 use caliptra_mcu_spdm_lib::transport::mctp::MctpTransport;
+use caliptra_mcu_spdm_lib::trust_anchor_store::RootCertHashTrustAnchors;
 use core::fmt::Write;
 use device_cert_store::{initialize_cert_store, SharedCertStore};
 use embassy_executor::Spawner;
 
 // Caliptra supported SPDM and Secure SPDM versions
-const SPDM_VERSIONS: &[SpdmVersion] = &[SpdmVersion::V12, SpdmVersion::V13];
+const SPDM_VERSIONS: &[SpdmVersion] = &[SpdmVersion::V12, SpdmVersion::V13, SpdmVersion::V14];
 const SECURE_SPDM_VERSIONS: &[SpdmVersion] = &[SpdmVersion::V12];
 
 // Caliptra Crypto timeout exponent (2^20 us)
 const CALIPTRA_SPDM_CT_EXPONENT: u8 = 20;
 
+// Maximum number of concurrent SPDM secure sessions per responder
+const MAX_SPDM_SESSIONS: usize = 2;
+
+// Maximum size of a large request received in chunks with CHUNK_SEND
+// (e.g. SET_CERTIFICATE with an owner-issued ML-DSA-87 cert chain)
+const MAX_SPDM_LARGE_REQUEST_SIZE: usize = 16384;
+
+// Maximum size of a response generated in full and sent in chunks with CHUNK_GET
+// (e.g. CHALLENGE_AUTH, KEY_EXCHANGE_RSP or MEASUREMENTS signed with ML-DSA-87)
+const MAX_SPDM_LARGE_RESPONSE_SIZE: usize = 8192;
+
+// Heartbeat period (in seconds) granted to the secure sessions on the DOE responder.
+// A session is torn down if no message is received within twice this period.
+const SPDM_HEARTBEAT_PERIOD: u8 = 10;
+
+// PSK hints accepted in PSK_EXCHANGE on the DOE responder
+#[cfg(feature = "test-spdm-psk")]
+const SPDM_PSK_HINTS: &[&[u8]] = &[b"Caliptra MCU PSK"];
+
+// Base key the PSKs are derived from. This is a fixed, publicly known test key and is
+// only built into test images. Production builds must provision their own base key
+// (or their own `PskStore` backed by provisioned storage), share the derived PSKs with
+// their requesters out of band and register the store with `set_psk_store`.
+#[cfg(feature = "test-spdm-psk")]
+const EMULATOR_SPDM_PSK_BASE_KEY: [u8; 48] = [0xA5; 48];
+
+// Maximum size of the Requester certificate chain retrieved for session-based mutual
+// authentication on the DOE responder
+const MAX_SPDM_REQUESTER_CERT_CHAIN_SIZE: usize = 2048;
+
+// SHA-384 digests of the root CAs trusted to issue Requester certificate chains. The
+// emulator trusts the test root CA of the host requester tests
+// (`EMULATOR_REQUESTER_ROOT_CA_CERT_DER`).
+const EMULATOR_SPDM_REQUESTER_ROOT_CA_HASHES: &[[u8; SHA384_HASH_SIZE]] = &[[
+    0x37, 0x44, 0x31, 0x32, 0xc3, 0x5e, 0x74, 0x46, 0x57, 0xaf, 0x6c, 0xbe, 0x1f, 0xbd, 0xa4, 0xe0,
+    0x94, 0xf5, 0xeb, 0x34, 0x3a, 0xbc, 0x26, 0x8b, 0xd6, 0x74, 0xa7, 0xd8, 0xea, 0x44, 0x1d, 0xc6,
+    0x20, 0x8e, 0x6b, 0xb9, 0x01, 0x23, 0x2c, 0xc9, 0xb5, 0x19, 0xb9, 0xfb, 0x57, 0x02, 0x86, 0xa4,
+]];
+
 #[embassy_executor::task]
 pub(crate) async fn spdm_task(spawner: Spawner) {
     let mut console_writer = Console::<DefaultSyscalls>::writer();
@@ -74,21 +120,35 @@ pub(crate) async fn spdm_task(spawner: Spawner) {
 
 #[embassy_executor::task]
 async fn spdm_mctp_responder() {
-    let mut raw_buffer = [0; MAX_SPDM_RESPONDER_BUF_SIZE];
+    // Responses are generated in full in the message buffer before they are chunked
+    let mut raw_buffer = [0; MAX_SPDM_LARGE_RESPONSE_SIZE];
+    let mut large_request_buffer = [0; MAX_SPDM_LARGE_REQUEST_SIZE];
+    let mut large_response_buffer = [0; MAX_SPDM_LARGE_RESPONSE_SIZE];
     let mut cw = Console::<DefaultSyscalls>::writer();
     let mut mctp_spdm_transport: MctpTransport = MctpTransport::new(mctp::driver_num::MCTP_SPDM);
 
     let max_mctp_spdm_msg_size =
         (MAX_SPDM_RESPONDER_BUF_SIZE - mctp_spdm_transport.header_size()) as u32;
 
+    // Slot 1 can be provisioned with an owner-issued cert chain over SPDM
+    let mut mctp_capability_flags = CapabilityFlags::default();
+    mctp_capability_flags.set_csr_cap(1);
+    mctp_capability_flags.set_set_certificate_cap(1);
+    // The firmware measurement events are served as Measurement Extension Log
+    mctp_capability_flags.set_mel_cap(1);
+
     let local_capabilities = DeviceCapabilities {
         ct_exponent: CALIPTRA_SPDM_CT_EXPONENT,
-        flags: CapabilityFlags::default(),
+        flags: mctp_capability_flags,
         data_transfer_size: max_mctp_spdm_msg_size,
-        max_spdm_msg_size: max_mctp_spdm_msg_size,
+        max_spdm_msg_size: MAX_SPDM_LARGE_REQUEST_SIZE as u32,
     };
 
-    let local_algorithms = LocalDeviceAlgorithms::default();
+    // ML-DSA-87 is offered in addition to ECC P-384
+    let mut device_mctp_algorithms = DeviceAlgorithms::default();
+    device_mctp_algorithms.set_pqc_asym_algo();
+
+    let local_algorithms = LocalDeviceAlgorithms::new(device_mctp_algorithms);
 
     // Create a wrapper for the global certificate store
     let shared_cert_store = SharedCertStore::new();
@@ -96,7 +156,11 @@ async fn spdm_mctp_responder() {
     // Measurements in OCP EAT format
     let (mut device_ocp_eat, meas_value_info) =
         device_measurements::ocp_eat::create_manifest_with_ocp_eat();
-    let device_measurements = SpdmMeasurements::new(&meas_value_info, &mut device_ocp_eat);
+    let mut mel_source = FwMelSource::new();
+    let mut device_measurements = SpdmMeasurements::new(&meas_value_info, &mut device_ocp_eat);
+    device_measurements.set_mel_source(&mut mel_source);
+
+    let mut session_slots: [SessionSlot; MAX_SPDM_SESSIONS] = Default::default();
 
     let mut ctx = match SpdmContext::new(
         SPDM_VERSIONS,
@@ -106,6 +170,7 @@ async fn spdm_mctp_responder() {
         local_algorithms,
         &shared_cert_store,
         device_measurements,
+        &mut session_slots,
         None, // VDM handlers are not supported for MCTP transport in this configuration
     ) {
         Ok(ctx) => ctx,
@@ -119,6 +184,8 @@ async fn spdm_mctp_responder() {
             return;
         }
     };
+    ctx.set_large_request_buffer(&mut large_request_buffer);
+    ctx.set_large_response_buffer(&mut large_response_buffer);
 
     let mut msg_buffer = MessageBuf::new(&mut raw_buffer);
     loop {
@@ -136,17 +203,45 @@ async fn spdm_mctp_responder() {
 
 #[embassy_executor::task]
 async fn spdm_doe_responder() {
-    let mut raw_buffer = [0; MAX_SPDM_RESPONDER_BUF_SIZE];
+    // Responses are generated in full in the message buffer before they are chunked
+    let mut raw_buffer = [0; MAX_SPDM_LARGE_RESPONSE_SIZE];
+    let mut large_response_buffer = [0; MAX_SPDM_LARGE_RESPONSE_SIZE];
+    let mut requester_cert_chain_buffer = [0; MAX_SPDM_REQUESTER_CERT_CHAIN_SIZE];
     let mut cw = Console::<DefaultSyscalls>::writer();
     let mut doe_spdm_transport: DoeTransport = DoeTransport::new(doe::driver_num::DOE_SPDM);
 
     let max_doe_spdm_msg_size =
         (MAX_SPDM_RESPONDER_BUF_SIZE - doe_spdm_transport.header_size()) as u32;
 
+    // Test images can also set up sessions with PSK_EXCHANGE
+    #[cfg(feature = "test-spdm-psk")]
+    let psk_store = match Import::import(CmKeyUsage::Hmac, &EMULATOR_SPDM_PSK_BASE_KEY).await {
+        Ok(rsp) => Some(HkdfPskStore::new(rsp.cmk, SPDM_PSK_HINTS)),
+        Err(e) => {
+            writeln!(
+                cw,
+                "SPDM_DOE_RESPONDER: Failed to import PSK base key: {:?}",
+                e
+            )
+            .unwrap();
+            None
+        }
+    };
+    #[cfg(not(feature = "test-spdm-psk"))]
+    let psk_store: Option<HkdfPskStore> = None;
+
     let mut doe_capability_flags = CapabilityFlags::default();
     doe_capability_flags.set_key_ex_cap(1);
     doe_capability_flags.set_mac_cap(1);
     doe_capability_flags.set_encrypt_cap(1);
+    doe_capability_flags.set_hbeat_cap(1);
+    doe_capability_flags.set_key_upd_cap(1);
+    // Requesters authenticate with a certificate chain retrieved by encapsulated requests
+    doe_capability_flags.set_mut_auth_cap(1);
+    doe_capability_flags.set_encap_cap(1);
+    if psk_store.is_some() {
+        doe_capability_flags.set_psk_cap(PskCapability::PskWithContext as u8);
+    }
 
     let local_capabilities = DeviceCapabilities {
         ct_exponent: CALIPTRA_SPDM_CT_EXPONENT,
@@ -160,8 +255,12 @@ async fn spdm_doe_responder() {
     device_doe_algorithms.set_aead_cipher_suite();
     device_doe_algorithms.set_spdm_key_schedule();
     device_doe_algorithms.set_other_param_support();
+    device_doe_algorithms.set_pqc_asym_algo();
+    device_doe_algorithms.set_req_base_asym_algo();
 
     let local_algorithms = LocalDeviceAlgorithms::new(device_doe_algorithms);
+    let requester_trust_anchors =
+        RootCertHashTrustAnchors::new(EMULATOR_SPDM_REQUESTER_ROOT_CA_HASHES);
 
     // Create a wrapper for the global certificate store
     let shared_cert_store = SharedCertStore::new();
@@ -171,6 +270,8 @@ async fn spdm_doe_responder() {
         device_measurements::pcr_quote::create_manifest_with_pcr_quote();
     let device_measurements = SpdmMeasurements::new(&meas_value_info, &mut device_pcr_quote);
 
+    let mut session_slots: [SessionSlot; MAX_SPDM_SESSIONS] = Default::default();
+
     // Create test drivers and VDM handlers locally for integration testing
     #[cfg(feature = "test-doe-spdm-tdisp-ide-validator")]
     let (mut tdisp_driver, mut ide_km_driver) =
@@ -227,6 +328,7 @@ async fn spdm_doe_responder() {
         local_algorithms,
         &shared_cert_store,
         device_measurements,
+        &mut session_slots,
         vdm_handlers,
     ) {
         Ok(ctx) => ctx,
@@ -240,6 +342,15 @@ async fn spdm_doe_responder() {
             return;
         }
     };
+    ctx.set_heartbeat_period(SPDM_HEARTBEAT_PERIOD);
+    if let Some(psk_store) = psk_store.as_ref() {
+        ctx.set_psk_store(psk_store);
+    }
+    ctx.set_requester_trust_anchor_store(
+        &requester_trust_anchors,
+        &mut requester_cert_chain_buffer,
+    );
+    ctx.set_large_response_buffer(&mut large_response_buffer);
 
     let mut msg_buffer = MessageBuf::new(&mut raw_buffer);
     loop {
@@ -5,8 +5,9 @@ extern crate alloc;
 use alloc::boxed::Box;
 use async_trait::async_trait;
 use caliptra_mcu_external_cmds_common::{
-    AttestedCsrData, CommandError, DeviceCapabilities, DeviceId, DeviceInfo, FirmwareVersion, Uid,
-    UnifiedCommandHandler, MAX_FW_VERSION_LEN, MAX_UID_LEN,
+    AttestationData, AttestedCsrData, CertSlotState, CommandError, DebugUnlockChallenge,
+    DeviceCapabilities, DeviceId, DeviceInfo, DotRespData, FirmwareVersion, IdevidCsrData, LogData,
+    LogType, Uid, UnifiedCommandHandler, FIELD_ENTROPY_LEN, MAX_FW_VERSION_LEN, MAX_UID_LEN,
 };
 use caliptra_mcu_mbox_common::config;
 
@@ -93,4 +94,65 @@ impl UnifiedCommandHandler for NonCryptoCmdHandlerMock {
     ) -> Result<(), CommandError> {
         Err(CommandError::NotSupported)
     }
+
+    async fn get_log(&self, _log_type: LogType, _log: &mut LogData) -> Result<(), CommandError> {
+        Err(CommandError::NotSupported)
+    }
+
+    async fn clear_log(&self, _log_type: LogType) -> Result<(), CommandError> {
+        Err(CommandError::NotSupported)
+    }
+
+    async fn get_attestation(
+        &self,
+        _format: u32,
+        _nonce: &[u8],
+        _evidence: &mut AttestationData,
+    ) -> Result<(), CommandError> {
+        Err(CommandError::NotSupported)
+    }
+
+    async fn request_debug_unlock(
+        &self,
+        _unlock_level: u8,
+        _challenge: &mut DebugUnlockChallenge,
+    ) -> Result<(), CommandError> {
+        Err(CommandError::NotSupported)
+    }
+
+    async fn authorize_debug_unlock_token(&self, _token: &[u8]) -> Result<(), CommandError> {
+        Err(CommandError::NotSupported)
+    }
+
+    async fn export_idevid_csr(
+        &self,
+        _index: u32,
+        _csr_data: &mut IdevidCsrData,
+    ) -> Result<(), CommandError> {
+        Err(CommandError::NotSupported)
+    }
+
+    async fn set_slot0_cert(&self, _cert: &[u8]) -> Result<(), CommandError> {
+        Err(CommandError::NotSupported)
+    }
+
+    async fn get_slot0_state(&self, _state: &mut CertSlotState) -> Result<(), CommandError> {
+        Err(CommandError::NotSupported)
+    }
+
+    async fn program_field_entropy(
+        &self,
+        _entropy: &[u8; FIELD_ENTROPY_LEN],
+    ) -> Result<(), CommandError> {
+        Err(CommandError::NotSupported)
+    }
+
+    async fn device_ownership_transfer(
+        &self,
+        _subcommand: u32,
+        _data: &[u8],
+        _resp: &mut DotRespData,
+    ) -> Result<(), CommandError> {
+        Err(CommandError::NotSupported)
+    }
 }
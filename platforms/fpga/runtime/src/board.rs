@@ -542,6 +542,12 @@ pub unsafe fn main() {
         caliptra_mcu_romtime::println!("[mcu-runtime] ProcessPrinter initialized");
     }
 
+    // The MCTP endpoint UUID and network ID are derived from the IDevID UEID fuses.
+    let ueid = peripherals.otp.read_idevid_ueid().unwrap_or_else(|_| {
+        caliptra_mcu_romtime::println!("[mcu-runtime] WARNING: failed to read the IDevID UEID");
+        [0; caliptra_mcu_romtime::DEVICE_UUID_LEN]
+    });
+
     // Select which I3C core to use for MCTP transport based on platform strap.
     if MCU_STRAPS.active_i3c > 1 {
         caliptra_mcu_romtime::println!(
@@ -558,9 +564,13 @@ pub unsafe fn main() {
         "[mcu-runtime] Active I3C core for MCTP: {}",
         MCU_STRAPS.active_i3c
     );
-    let mux_mctp =
-        caliptra_mcu_components::mux_mctp::MCTPMuxComponent::new(active_i3c_core, mux_alarm)
-            .finalize(mctp_mux_component_static!(InternalTimers, MCTPI3CBinding));
+    let mux_mctp = caliptra_mcu_components::mux_mctp::MCTPMuxComponent::new(
+        active_i3c_core,
+        mux_alarm,
+        caliptra_mcu_romtime::device_uuid(&ueid),
+        caliptra_mcu_romtime::device_network_id(&ueid),
+    )
+    .finalize(mctp_mux_component_static!(InternalTimers, MCTPI3CBinding));
     caliptra_mcu_romtime::println!("[mcu-runtime] MCTP mux initialized");
 
     let mctp_spdm = caliptra_mcu_components::mctp_driver::MCTPDriverComponent::new(
@@ -18,6 +18,12 @@ pub trait DoeMboxPeripheral {
     fn poll(&mut self) {}
     fn warm_reset(&mut self) {}
     fn update_reset(&mut self) {}
+    fn save_state(&self) -> Option<Vec<u8>> {
+        None
+    }
+    fn restore_state(&mut self, _state: &[u8]) -> std::io::Result<()> {
+        Ok(())
+    }
     fn generated(&mut self) -> Option<&mut DoeMboxGenerated> {
         None
     }
@@ -121,6 +121,141 @@ impl AutoRootBus {
             axicdma_periph: axicdma_periph.map(|p| crate::axicdma::AxicdmaBus { periph: p }),
         }
     }
+
+    /// Save the state of every mounted peripheral that supports snapshots,
+    /// keyed by peripheral name.
+    pub fn save_peripheral_states(&self) -> std::collections::BTreeMap<String, Vec<u8>> {
+        let mut states = std::collections::BTreeMap::new();
+        if let Some(state) = self.i3c_periph.as_ref().and_then(|p| p.periph.save_state()) {
+            states.insert("i3c".to_string(), state);
+        }
+        if let Some(state) = self
+            .i3c1_periph
+            .as_ref()
+            .and_then(|p| p.periph.save_state())
+        {
+            states.insert("i3c1".to_string(), state);
+        }
+        if let Some(state) = self
+            .primary_flash_periph
+            .as_ref()
+            .and_then(|p| p.periph.save_state())
+        {
+            states.insert("primary_flash".to_string(), state);
+        }
+        if let Some(state) = self
+            .secondary_flash_periph
+            .as_ref()
+            .and_then(|p| p.periph.save_state())
+        {
+            states.insert("secondary_flash".to_string(), state);
+        }
+        if let Some(state) = self.mci_periph.as_ref().and_then(|p| p.periph.save_state()) {
+            states.insert("mci".to_string(), state);
+        }
+        if let Some(state) = self
+            .doe_mbox_periph
+            .as_ref()
+            .and_then(|p| p.periph.save_state())
+        {
+            states.insert("doe_mbox".to_string(), state);
+        }
+        if let Some(state) = self
+            .el2_pic_periph
+            .as_ref()
+            .and_then(|p| p.periph.save_state())
+        {
+            states.insert("el2_pic".to_string(), state);
+        }
+        if let Some(state) = self.otp_periph.as_ref().and_then(|p| p.periph.save_state()) {
+            states.insert("otp".to_string(), state);
+        }
+        if let Some(state) = self.lc_periph.as_ref().and_then(|p| p.periph.save_state()) {
+            states.insert("lc".to_string(), state);
+        }
+        if let Some(state) = self
+            .mbox_periph
+            .as_ref()
+            .and_then(|p| p.periph.save_state())
+        {
+            states.insert("mbox".to_string(), state);
+        }
+        if let Some(state) = self
+            .sha512_acc_periph
+            .as_ref()
+            .and_then(|p| p.periph.save_state())
+        {
+            states.insert("sha512_acc".to_string(), state);
+        }
+        if let Some(state) = self.soc_periph.as_ref().and_then(|p| p.periph.save_state()) {
+            states.insert("soc".to_string(), state);
+        }
+        if let Some(state) = self
+            .axicdma_periph
+            .as_ref()
+            .and_then(|p| p.periph.save_state())
+        {
+            states.insert("axicdma".to_string(), state);
+        }
+        states
+    }
+
+    /// Restore peripheral state previously returned by `save_peripheral_states()`.
+    /// Peripherals without a saved state are left untouched.
+    pub fn restore_peripheral_states(
+        &mut self,
+        states: &std::collections::BTreeMap<String, Vec<u8>>,
+    ) -> std::io::Result<()> {
+        if let (Some(periph), Some(state)) = (self.i3c_periph.as_mut(), states.get("i3c")) {
+            periph.periph.restore_state(state)?;
+        }
+        if let (Some(periph), Some(state)) = (self.i3c1_periph.as_mut(), states.get("i3c1")) {
+            periph.periph.restore_state(state)?;
+        }
+        if let (Some(periph), Some(state)) = (
+            self.primary_flash_periph.as_mut(),
+            states.get("primary_flash"),
+        ) {
+            periph.periph.restore_state(state)?;
+        }
+        if let (Some(periph), Some(state)) = (
+            self.secondary_flash_periph.as_mut(),
+            states.get("secondary_flash"),
+        ) {
+            periph.periph.restore_state(state)?;
+        }
+        if let (Some(periph), Some(state)) = (self.mci_periph.as_mut(), states.get("mci")) {
+            periph.periph.restore_state(state)?;
+        }
+        if let (Some(periph), Some(state)) = (self.doe_mbox_periph.as_mut(), states.get("doe_mbox"))
+        {
+            periph.periph.restore_state(state)?;
+        }
+        if let (Some(periph), Some(state)) = (self.el2_pic_periph.as_mut(), states.get("el2_pic")) {
+            periph.periph.restore_state(state)?;
+        }
+        if let (Some(periph), Some(state)) = (self.otp_periph.as_mut(), states.get("otp")) {
+            periph.periph.restore_state(state)?;
+        }
+        if let (Some(periph), Some(state)) = (self.lc_periph.as_mut(), states.get("lc")) {
+            periph.periph.restore_state(state)?;
+        }
+        if let (Some(periph), Some(state)) = (self.mbox_periph.as_mut(), states.get("mbox")) {
+            periph.periph.restore_state(state)?;
+        }
+        if let (Some(periph), Some(state)) =
+            (self.sha512_acc_periph.as_mut(), states.get("sha512_acc"))
+        {
+            periph.periph.restore_state(state)?;
+        }
+        if let (Some(periph), Some(state)) = (self.soc_periph.as_mut(), states.get("soc")) {
+            periph.periph.restore_state(state)?;
+        }
+        if let (Some(periph), Some(state)) = (self.axicdma_periph.as_mut(), states.get("axicdma")) {
+            periph.periph.restore_state(state)?;
+        }
+        Ok(())
+    }
 }
 impl caliptra_emu_bus::Bus for AutoRootBus {
     fn read(
@@ -124,6 +124,96 @@ impl From<McuRomBootStatus> for u16 {
     }
 }
 
+impl McuRomBootStatus {
+    /// All status codes, in declaration order.
+    pub const ALL: &'static [McuRomBootStatus] = &[
+        Self::RomStarted,
+        Self::McuMemoryMapInitialized,
+        Self::StrapsLoaded,
+        Self::McuRegistersInitialized,
+        Self::SocManagerInitialized,
+        Self::MciInitialized,
+        Self::ResetReasonDetected,
+        Self::LifecycleControllerInitialized,
+        Self::LifecycleTransitionStarted,
+        Self::LifecycleTransitionComplete,
+        Self::LifecycleTokenBurningStarted,
+        Self::LifecycleTokenBurningComplete,
+        Self::OtpControllerInitialized,
+        Self::WatchdogConfigured,
+        Self::CaliptraBootGoAsserted,
+        Self::I3cInitialized,
+        Self::CaliptraReadyForFuses,
+        Self::AxiUsersConfigured,
+        Self::FusesPopulatedToCaliptra,
+        Self::McuMboxAxiUsersConfigured,
+        Self::SsConfigDoneStickySet,
+        Self::SsConfigDoneSet,
+        Self::PkHashVerified,
+        Self::McuMboxAxiUsersVerified,
+        Self::FuseWriteComplete,
+        Self::CaliptraReadyForMailbox,
+        Self::DeviceOwnershipTransferFlashRead,
+        Self::DeviceOwnershipTransferStarted,
+        Self::DeviceOwnershipDeriveStableKey,
+        Self::DeviceOwnershipBurnFuses,
+        Self::DeviceOwnershipDetermineOwner,
+        Self::DeviceOwnershipTransferComplete,
+        Self::I3cServicesStarted,
+        Self::I3cServicesReady,
+        Self::I3cServicesComplete,
+        Self::RiDownloadFirmwareCommandSent,
+        Self::RiDownloadFirmwareComplete,
+        Self::FlashRecoveryFlowStarted,
+        Self::FlashRecoveryFlowComplete,
+        Self::FirmwareReadyDetected,
+        Self::FirmwareValidationComplete,
+        Self::CaliptraRuntimeReady,
+        Self::FwManifestDotProcessingStarted,
+        Self::FwManifestDotProcessingComplete,
+        Self::FieldEntropyProgrammingStarted,
+        Self::FieldEntropyPartition0Complete,
+        Self::FieldEntropyPartition1Complete,
+        Self::FieldEntropyPartition2Complete,
+        Self::FieldEntropyPartition3Complete,
+        Self::FieldEntropyProgrammingComplete,
+        Self::DotRecoveryStarted,
+        Self::DotRecoveryBlobAuthenticated,
+        Self::DotRecoveryBlobWritten,
+        Self::DotRecoveryComplete,
+        Self::DotRecoveryFailed,
+        Self::DotOverrideStarted,
+        Self::DotOverrideChallengeSent,
+        Self::DotOverrideSigVerified,
+        Self::DotOverrideFuseBurned,
+        Self::DotOverrideBlobWritten,
+        Self::DotOverrideComplete,
+        Self::DotOverrideFailed,
+        Self::ColdBootFlowStarted,
+        Self::ColdBootFlowComplete,
+        Self::WarmResetFlowStarted,
+        Self::WarmResetFlowComplete,
+        Self::FirmwareBootFlowStarted,
+        Self::FirmwareBootFlowComplete,
+        Self::HitlessUpdateFlowStarted,
+        Self::HitlessUpdateFlowComplete,
+    ];
+}
+
+impl TryFrom<u16> for McuRomBootStatus {
+    type Error = u16;
+
+    /// Converts a checkpoint read back from the flow status register. Unknown
+    /// values are returned as the error.
+    fn try_from(value: u16) -> Result<Self, u16> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|status| *status as u16 == value)
+            .ok_or(value)
+    }
+}
+
 pub struct McuBootMilestones(u16);
 
 bitflags! {
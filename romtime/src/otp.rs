@@ -26,6 +26,10 @@ pub const LC_TOKEN_RMA_INDEX: usize = 10;
 
 pub const PROD_DEBUG_UNLOCK_PK_SIZE: usize = 48;
 pub const LC_TOKEN_SIZE: usize = 16;
+pub const DEVICE_UUID_LEN: usize = 16;
+
+// The UEID (manufacturer serial number) is DWORD 07 - 10 of the IDevID certificate attributes.
+const IDEVID_CERT_ATTR_UEID_OFFSET: usize = 7 * 4;
 
 pub const PROD_DEBUG_UNLOCK_PK_ENTRIES: [&FuseEntryInfo; 8] = [
     fuses::OTP_CPTRA_SS_PROD_DEBUG_UNLOCK_PKS_0,
@@ -44,6 +48,29 @@ pub enum PqcKeyType {
     LMS = 2,
 }
 
+/// Returns the UUID identifying the device, derived from its IDevID UEID.
+///
+/// The UEID is turned into an RFC 9562 version 8 (vendor-specific) UUID.
+pub const fn device_uuid(ueid: &[u8; DEVICE_UUID_LEN]) -> [u8; DEVICE_UUID_LEN] {
+    let mut uuid = *ueid;
+    uuid[6] = (uuid[6] & 0x0f) | 0x80;
+    uuid[8] = (uuid[8] & 0x3f) | 0x80;
+    uuid
+}
+
+/// Returns the network ID the device reports over MCTP, derived from its IDevID UEID.
+///
+/// It is built from the complemented UEID so that it differs from the device UUID.
+pub const fn device_network_id(ueid: &[u8; DEVICE_UUID_LEN]) -> [u8; DEVICE_UUID_LEN] {
+    let mut complement = [0u8; DEVICE_UUID_LEN];
+    let mut i = 0;
+    while i < DEVICE_UUID_LEN {
+        complement[i] = !ueid[i];
+        i += 1;
+    }
+    device_uuid(&complement)
+}
+
 pub struct Otp {
     registers: StaticRef<otp_ctrl::regs::OtpCtrl>,
 }
@@ -603,6 +630,16 @@ impl Otp {
         Ok(data)
     }
 
+    /// Read the device UEID (manufacturer serial number) from cptra_core_idevid_cert_idevid_attr.
+    pub fn read_idevid_ueid(&self) -> McuResult<[u8; DEVICE_UUID_LEN]> {
+        let attr = self.read_cptra_core_idevid_cert_idevid_attr()?;
+        let mut ueid = [0u8; DEVICE_UUID_LEN];
+        ueid.copy_from_slice(
+            &attr[IDEVID_CERT_ATTR_UEID_OFFSET..IDEVID_CERT_ATTR_UEID_OFFSET + DEVICE_UUID_LEN],
+        );
+        Ok(ueid)
+    }
+
     /// Read cptra_ss_prod_debug_unlock_pks (index 0-7, each 48 bytes).
     pub fn read_cptra_ss_prod_debug_unlock_pks(
         &self,
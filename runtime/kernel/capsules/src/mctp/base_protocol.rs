@@ -22,6 +22,15 @@ pub const MCTP_BASELINE_TRANSMISSION_UNIT: usize = 64;
 
 pub const MCTP_NUM_MSG_TYPES_SUPPORTED: usize = 5;
 
+/// DSP0236 MT1: maximum request-to-response time at the responder, in milliseconds.
+pub const MCTP_MT1_MAX_MS: u32 = 120;
+/// DSP0236 MT3: maximum transmission delay, in milliseconds.
+pub const MCTP_MT3_MAX_MS: u32 = 100;
+/// DSP0236 MT2: minimum time a requester waits for a response (MT1 max + 2 * MT3 max).
+pub const MCTP_MT2_MIN_MS: u32 = MCTP_MT1_MAX_MS + 2 * MCTP_MT3_MAX_MS;
+/// DSP0236 MT4: minimum instance ID expiration interval, which also bounds MT2, in milliseconds.
+pub const MCTP_MT4_MIN_MS: u32 = 5000;
+
 bitfield! {
     #[derive(Clone, Copy, Default)]
     pub struct MCTPHeader(u32);
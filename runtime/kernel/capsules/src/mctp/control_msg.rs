@@ -7,6 +7,18 @@ use zerocopy::{FromBytes, Immutable, IntoBytes};
 
 pub const MCTP_CTRL_MSG_HEADER_LEN: usize = 3;
 
+/// Length of the endpoint UUID and network ID fields.
+pub const MCTP_UUID_LEN: usize = 16;
+
+/// PCI Vendor ID for Caliptra (Microsoft) vendor defined messages.
+pub const MCTP_VDM_PCI_VENDOR_ID: u16 = 0x1414;
+
+/// Command set version reported for the Caliptra vendor defined messages.
+pub const MCTP_VDM_CMD_SET_VERSION: u16 = 0x0001;
+
+/// Vendor ID set selector value indicating that no more vendor ID sets follow.
+const VENDOR_ID_SET_SELECTOR_END: u8 = 0xFF;
+
 bitfield! {
     #[derive(Default)]
     pub struct MCTPCtrlMsgHdr(u32);
@@ -36,8 +48,13 @@ impl MCTPCtrlMsgHdr {
 pub enum MCTPCtrlCmd {
     SetEID = 1,
     GetEID = 2,
+    GetEndpointUUID = 3,
     GetMsgTypeSupport = 5,
     GetVersionSupport = 4,
+    GetVendorDefinedMsgSupport = 6,
+    PrepareForEndpointDiscovery = 0x0B,
+    EndpointDiscovery = 0x0C,
+    GetNetworkID = 0x0E,
     Unsupported = 0xFF,
 }
 
@@ -46,8 +63,13 @@ impl From<u8> for MCTPCtrlCmd {
         match val {
             1 => MCTPCtrlCmd::SetEID,
             2 => MCTPCtrlCmd::GetEID,
+            3 => MCTPCtrlCmd::GetEndpointUUID,
             4 => MCTPCtrlCmd::GetVersionSupport,
             5 => MCTPCtrlCmd::GetMsgTypeSupport,
+            6 => MCTPCtrlCmd::GetVendorDefinedMsgSupport,
+            0x0B => MCTPCtrlCmd::PrepareForEndpointDiscovery,
+            0x0C => MCTPCtrlCmd::EndpointDiscovery,
+            0x0E => MCTPCtrlCmd::GetNetworkID,
             _ => MCTPCtrlCmd::Unsupported,
         }
     }
@@ -60,6 +82,11 @@ impl MCTPCtrlCmd {
             MCTPCtrlCmd::GetEID => 0,
             MCTPCtrlCmd::GetVersionSupport => 1,
             MCTPCtrlCmd::GetMsgTypeSupport => 0,
+            MCTPCtrlCmd::GetEndpointUUID => 0,
+            MCTPCtrlCmd::GetVendorDefinedMsgSupport => 1,
+            MCTPCtrlCmd::PrepareForEndpointDiscovery => 0,
+            MCTPCtrlCmd::EndpointDiscovery => 0,
+            MCTPCtrlCmd::GetNetworkID => 0,
             MCTPCtrlCmd::Unsupported => 0,
         }
     }
@@ -70,12 +97,22 @@ impl MCTPCtrlCmd {
             MCTPCtrlCmd::GetEID => 4,
             MCTPCtrlCmd::GetVersionSupport => 18, // 2 bytes header + 4 entries * 4 bytes each
             MCTPCtrlCmd::GetMsgTypeSupport => 2 + MCTP_NUM_MSG_TYPES_SUPPORTED, // 1 byte for completion code + 1 byte for count + supported message types
+            MCTPCtrlCmd::GetEndpointUUID => 1 + MCTP_UUID_LEN,
+            MCTPCtrlCmd::GetVendorDefinedMsgSupport => 7, // completion code, next selector, format, 2 bytes vendor ID, 2 bytes command set version
+            MCTPCtrlCmd::PrepareForEndpointDiscovery => 1,
+            MCTPCtrlCmd::EndpointDiscovery => 1,
+            MCTPCtrlCmd::GetNetworkID => 1 + MCTP_UUID_LEN,
             MCTPCtrlCmd::Unsupported => 0,
         }
     }
 
+    /// Processes a Set Endpoint ID request.
+    ///
+    /// Returns the endpoint ID to use if the request was accepted. A Set Discovered Flag
+    /// operation is accepted without changing `local_eid`.
     pub fn process_set_endpoint_id(
         &self,
+        local_eid: u8,
         req: &[u8],
         rsp_buf: &mut [u8],
     ) -> Result<Option<u8>, ErrorCode> {
@@ -103,7 +140,13 @@ impl MCTPCtrlCmd {
                     resp.set_eid_pool_size(0);
                 }
             }
-            SetEIDOp::ResetEID | SetEIDOp::SetDiscoveredFlag => {
+            SetEIDOp::SetDiscoveredFlag => {
+                set_status = SetEIDStatus::Accepted;
+                resp.set_eid_alloc_status(SetEIDAllocStatus::NoEIDPool as u8);
+                resp.set_assigned_eid(local_eid);
+                resp.set_eid_pool_size(0);
+            }
+            SetEIDOp::ResetEID => {
                 set_status = SetEIDStatus::Rejected;
                 completion_code = CmdCompletionCode::ErrorInvalidData;
             }
@@ -115,7 +158,7 @@ impl MCTPCtrlCmd {
             .map_err(|_| ErrorCode::FAIL)?;
 
         if resp.eid_assign_status() == SetEIDStatus::Accepted as u8 {
-            Ok(Some(eid))
+            Ok(Some(resp.assigned_eid()))
         } else {
             Ok(None)
         }
@@ -234,6 +277,66 @@ impl MCTPCtrlCmd {
 
         Ok(())
     }
+
+    pub fn process_get_endpoint_uuid(
+        &self,
+        uuid: &[u8; MCTP_UUID_LEN],
+        rsp_buf: &mut [u8],
+    ) -> Result<(), ErrorCode> {
+        if rsp_buf.len() < self.resp_data_len() {
+            return Err(ErrorCode::NOMEM);
+        }
+        rsp_buf[0] = CmdCompletionCode::Success as u8;
+        rsp_buf[1..1 + MCTP_UUID_LEN].copy_from_slice(uuid);
+        Ok(())
+    }
+
+    pub fn process_get_vendor_defined_msg_support(
+        &self,
+        req: &[u8],
+        rsp_buf: &mut [u8],
+    ) -> Result<(), ErrorCode> {
+        if req.len() < self.req_data_len() || rsp_buf.len() < self.resp_data_len() {
+            return Err(ErrorCode::NOMEM);
+        }
+        rsp_buf[..self.resp_data_len()].fill(0);
+
+        // Only one vendor ID set is supported: the Caliptra PCI vendor defined messages.
+        if req[0] != 0 {
+            rsp_buf[0] = CmdCompletionCode::ErrorInvalidData as u8;
+            return Ok(());
+        }
+
+        rsp_buf[0] = CmdCompletionCode::Success as u8;
+        rsp_buf[1] = VENDOR_ID_SET_SELECTOR_END;
+        rsp_buf[2] = VendorIDFormat::PciVendorID as u8;
+        rsp_buf[3..5].copy_from_slice(&MCTP_VDM_PCI_VENDOR_ID.to_be_bytes());
+        rsp_buf[5..7].copy_from_slice(&MCTP_VDM_CMD_SET_VERSION.to_be_bytes());
+        Ok(())
+    }
+
+    /// Processes the Prepare for Endpoint Discovery and Endpoint Discovery requests.
+    /// Both carry no request data and respond with a completion code only.
+    pub fn process_endpoint_discovery(&self, rsp_buf: &mut [u8]) -> Result<(), ErrorCode> {
+        if rsp_buf.len() < self.resp_data_len() {
+            return Err(ErrorCode::NOMEM);
+        }
+        rsp_buf[0] = CmdCompletionCode::Success as u8;
+        Ok(())
+    }
+
+    pub fn process_get_network_id(
+        &self,
+        network_id: &[u8; MCTP_UUID_LEN],
+        rsp_buf: &mut [u8],
+    ) -> Result<(), ErrorCode> {
+        if rsp_buf.len() < self.resp_data_len() {
+            return Err(ErrorCode::NOMEM);
+        }
+        rsp_buf[0] = CmdCompletionCode::Success as u8;
+        rsp_buf[1..1 + MCTP_UUID_LEN].copy_from_slice(network_id);
+        Ok(())
+    }
 }
 
 pub enum CmdCompletionCode {
@@ -468,6 +571,12 @@ impl GetVersionSupportEntryResp {
     }
 }
 
+// Get Vendor Defined Message Support Response
+pub enum VendorIDFormat {
+    PciVendorID = 0,
+    IanaEnterpriseID = 1,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -491,7 +600,7 @@ mod tests {
 
         let rsp_buf = &mut [0; 4];
         let eid = MCTPCtrlCmd::SetEID
-            .process_set_endpoint_id(&msg_req, rsp_buf)
+            .process_set_endpoint_id(0, &msg_req, rsp_buf)
             .unwrap();
         assert!(eid.is_some());
         assert_eq!(eid.unwrap(), 0x0A);
@@ -510,7 +619,7 @@ mod tests {
 
         let rsp_buf = &mut [0; 4];
         let eid = MCTPCtrlCmd::SetEID
-            .process_set_endpoint_id(&msg_req, rsp_buf)
+            .process_set_endpoint_id(0, &msg_req, rsp_buf)
             .unwrap();
         assert!(eid.is_none());
 
@@ -527,7 +636,7 @@ mod tests {
 
         let rsp_buf = &mut [0; 4];
         let eid = MCTPCtrlCmd::SetEID
-            .process_set_endpoint_id(&msg_req, rsp_buf)
+            .process_set_endpoint_id(0, &msg_req, rsp_buf)
             .unwrap();
         assert!(eid.is_none());
 
@@ -629,4 +738,78 @@ mod tests {
             assert_eq!(rsp_buf[2 + i], MessageType::supported()[i] as u8);
         }
     }
+
+    #[test]
+    fn test_set_discovered_flag() {
+        let msg_req = [0x03, 0x00];
+
+        let rsp_buf = &mut [0; 4];
+        let eid = MCTPCtrlCmd::SetEID
+            .process_set_endpoint_id(0x0A, &msg_req, rsp_buf)
+            .unwrap();
+        assert_eq!(eid, Some(0x0A));
+
+        let rsp: SetEIDResp<[u8; 4]> = SetEIDResp::read_from_bytes(rsp_buf).unwrap();
+        assert_eq!(rsp.completion_code(), CmdCompletionCode::Success as u8);
+        assert_eq!(rsp.eid_assign_status(), SetEIDStatus::Accepted as u8);
+        assert_eq!(rsp.assigned_eid(), 0x0A);
+    }
+
+    #[test]
+    fn test_get_endpoint_uuid() {
+        let uuid = [0xA5; MCTP_UUID_LEN];
+        let rsp_buf = &mut [0xFF; 1 + MCTP_UUID_LEN];
+        MCTPCtrlCmd::GetEndpointUUID
+            .process_get_endpoint_uuid(&uuid, rsp_buf)
+            .unwrap();
+
+        assert_eq!(rsp_buf[0], CmdCompletionCode::Success as u8);
+        assert_eq!(rsp_buf[1..], uuid);
+    }
+
+    #[test]
+    fn test_get_vendor_defined_msg_support() {
+        let rsp_buf = &mut [0xFF; 7];
+        MCTPCtrlCmd::GetVendorDefinedMsgSupport
+            .process_get_vendor_defined_msg_support(&[0x00], rsp_buf)
+            .unwrap();
+
+        assert_eq!(rsp_buf[0], CmdCompletionCode::Success as u8);
+        assert_eq!(rsp_buf[1], 0xFF); // No more vendor ID sets
+        assert_eq!(rsp_buf[2], VendorIDFormat::PciVendorID as u8);
+        assert_eq!(rsp_buf[3..5], MCTP_VDM_PCI_VENDOR_ID.to_be_bytes());
+        assert_eq!(rsp_buf[5..7], MCTP_VDM_CMD_SET_VERSION.to_be_bytes());
+
+        MCTPCtrlCmd::GetVendorDefinedMsgSupport
+            .process_get_vendor_defined_msg_support(&[0x01], rsp_buf)
+            .unwrap();
+        assert_eq!(rsp_buf[0], CmdCompletionCode::ErrorInvalidData as u8);
+    }
+
+    #[test]
+    fn test_endpoint_discovery() {
+        let rsp_buf = &mut [0xFF; 1];
+        MCTPCtrlCmd::PrepareForEndpointDiscovery
+            .process_endpoint_discovery(rsp_buf)
+            .unwrap();
+        assert_eq!(rsp_buf[0], CmdCompletionCode::Success as u8);
+
+        let rsp_buf = &mut [0xFF; 1];
+        MCTPCtrlCmd::EndpointDiscovery
+            .process_endpoint_discovery(rsp_buf)
+            .unwrap();
+        assert_eq!(rsp_buf[0], CmdCompletionCode::Success as u8);
+    }
+
+    #[test]
+    fn test_get_network_id() {
+        let network_id = [0x5A; MCTP_UUID_LEN];
+        let rsp_buf = &mut [0xFF; 1 + MCTP_UUID_LEN];
+        MCTPCtrlCmd::GetNetworkID
+            .process_get_network_id(&network_id, rsp_buf)
+            .unwrap();
+
+        assert_eq!(rsp_buf[0], CmdCompletionCode::Success as u8);
+        assert_eq!(rsp_buf[1..], network_id);
+    }
 }
@@ -12,7 +12,7 @@ use kernel::deferred_call::{DeferredCall, DeferredCallClient};
 use kernel::grant::{AllowRoCount, AllowRwCount, Grant, GrantKernelData, UpcallCount};
 use kernel::processbuffer::{ReadableProcessBuffer, WriteableProcessBuffer};
 use kernel::syscall::{CommandReturn, SyscallDriver};
-use kernel::utilities::cells::{MapCell, OptionalCell, TakeCell};
+use kernel::utilities::cells::{MapCell, TakeCell};
 use kernel::utilities::leasable_buffer::SubSliceMut;
 use kernel::{ErrorCode, ProcessId};
 
@@ -22,6 +22,13 @@ pub const MCTP_SECURE_SPDM_DRIVER_NUM: usize = 0xA0001;
 pub const MCTP_PLDM_DRIVER_NUM: usize = 0xA0002;
 pub const MCTP_CALIPTRA_DRIVER_NUM: usize = 0xA0003;
 
+/// Maximum number of received messages held while no application is waiting for them.
+/// Lets a requester keep several requests outstanding without losing early responses.
+const MAX_BUFFERED_MESSAGES: usize = 4;
+
+/// Maximum number of responses remembered as expired, one per message tag.
+const MAX_EXPIRED_RESPONSES: usize = MCTP_TAG_MASK as usize + 1;
+
 /// IDs for subscribe calls
 mod upcall {
     /// Callback for when the message is received
@@ -31,8 +38,11 @@ mod upcall {
     /// Callback for when the message is transmitted.
     pub const MESSAGE_TRANSMITTED: usize = 2;
 
+    /// Callback for when no response arrived for a pending Receive Response in time.
+    pub const RESPONSE_TIMEOUT: usize = 3;
+
     /// Number of upcalls
-    pub const COUNT: u8 = 3;
+    pub const COUNT: u8 = 4;
 }
 
 /// IDs for read-only allow buffers
@@ -54,7 +64,8 @@ mod rw_allow {
     pub const COUNT: u8 = 2;
 }
 
-/// Structure to hold a buffered message when no application is waiting
+/// Metadata of a buffered message when no application is waiting.
+/// The payload is stored in the kernel rx buffer, packed after earlier buffered messages.
 #[derive(Debug, Clone, Copy)]
 struct BufferedMessage {
     msg_type: u8,
@@ -139,7 +150,10 @@ pub struct MCTPDriver<'a> {
     max_msg_size: usize,
     kernel_msg_buf: MapCell<SubSliceMut<'static, u8>>,
     kernel_rx_buf: TakeCell<'static, [u8]>,
-    buffered_message: OptionalCell<BufferedMessage>,
+    buffered_messages: Cell<[Option<BufferedMessage>; MAX_BUFFERED_MESSAGES]>,
+    // (msg_tag, peer_eid) of responses that timed out or were dropped from the buffer
+    // before any app waited for them
+    expired_responses: Cell<[Option<(u8, u8)>; MAX_EXPIRED_RESPONSES]>,
     deferred_call: DeferredCall,
 }
 
@@ -165,7 +179,8 @@ impl<'a> MCTPDriver<'a> {
             max_msg_size,
             kernel_msg_buf: MapCell::new(msg_buf),
             kernel_rx_buf: TakeCell::new(rx_buf),
-            buffered_message: OptionalCell::empty(),
+            buffered_messages: Cell::new([None; MAX_BUFFERED_MESSAGES]),
+            expired_responses: Cell::new([None; MAX_EXPIRED_RESPONSES]),
             deferred_call: DeferredCall::new(),
         }
     }
@@ -314,8 +329,40 @@ impl<'a> MCTPDriver<'a> {
         true
     }
 
-    /// Store a message in the buffer when no application is waiting
-    /// If there's already a buffered message, replace it with the new one (newer messages take priority)
+    /// Remember a response that will never be delivered, so that a later Receive Response
+    /// for it fails instead of waiting forever. The oldest entry is replaced when full.
+    fn expire_response(&self, msg_tag: u8, peer_eid: u8) {
+        let mut expired = self.expired_responses.get();
+        let index = expired
+            .iter()
+            .position(|entry| entry.is_none() || *entry == Some((msg_tag, peer_eid)))
+            .unwrap_or_else(|| {
+                expired.copy_within(1.., 0);
+                MAX_EXPIRED_RESPONSES - 1
+            });
+        expired[index] = Some((msg_tag, peer_eid));
+        self.expired_responses.set(expired);
+    }
+
+    /// Returns true and forgets the entry if the response was remembered as expired.
+    fn take_expired_response(&self, msg_tag: u8, peer_eid: u8) -> bool {
+        let mut expired = self.expired_responses.get();
+        let Some(index) = expired
+            .iter()
+            .position(|entry| *entry == Some((msg_tag, peer_eid)))
+        else {
+            return false;
+        };
+        expired.copy_within(index + 1.., index);
+        expired[MAX_EXPIRED_RESPONSES - 1] = None;
+        self.expired_responses.set(expired);
+        true
+    }
+
+    /// Store a message in the buffer when no application is waiting.
+    /// Messages are packed back to back in the kernel rx buffer. If the buffer is full,
+    /// the oldest buffered messages are dropped to make room (newer messages take priority).
+    /// A dropped response is remembered as expired.
     fn buffer_message(
         &self,
         src_eid: u8,
@@ -325,14 +372,6 @@ impl<'a> MCTPDriver<'a> {
         msg_len: usize,
         recv_time: u32,
     ) -> bool {
-        // Check if we already have a buffered message and warn about replacement
-        if self.buffered_message.is_some() {
-            println!(
-                "[MCTP-CAPSULE]::buffer_message replacing existing buffered message with new one (msg_tag={}, {} bytes)",
-                msg_tag, msg_len
-            );
-        }
-
         // Take the kernel rx buffer to store the message
         if let Some(rx_buf) = self.kernel_rx_buf.take() {
             if msg_len > rx_buf.len() {
@@ -342,11 +381,30 @@ impl<'a> MCTPDriver<'a> {
                 return false;
             }
 
-            // Copy message to buffer (this overwrites any existing message)
-            rx_buf[..msg_len].copy_from_slice(&msg_payload[..msg_len]);
+            let mut messages = self.buffered_messages.get();
+            let mut count = messages.iter().take_while(|m| m.is_some()).count();
+            let mut used: usize = messages[..count].iter().flatten().map(|m| m.msg_len).sum();
+
+            // Make room by dropping the oldest buffered messages
+            while count == MAX_BUFFERED_MESSAGES || used + msg_len > rx_buf.len() {
+                println!(
+                    "[MCTP-CAPSULE]::buffer_message replacing oldest buffered message with new one (msg_tag={}, {} bytes)",
+                    msg_tag, msg_len
+                );
+                if let Some(dropped) = messages[0] {
+                    if dropped.op_context.pending_response() {
+                        self.expire_response(
+                            dropped.op_context.msg_tag,
+                            dropped.op_context.peer_eid,
+                        );
+                    }
+                }
+                used -= Self::remove_buffered_message(&mut messages, rx_buf, used, 0);
+                count -= 1;
+            }
 
-            // Store message metadata (this replaces any existing buffered message)
-            let buffered_msg = BufferedMessage {
+            rx_buf[used..used + msg_len].copy_from_slice(&msg_payload[..msg_len]);
+            messages[count] = Some(BufferedMessage {
                 msg_type,
                 msg_len,
                 recv_time,
@@ -355,9 +413,9 @@ impl<'a> MCTPDriver<'a> {
                     peer_eid: src_eid,
                     op_type: OpType::Rx,
                 },
-            };
+            });
 
-            self.buffered_message.set(buffered_msg);
+            self.buffered_messages.set(messages);
             self.kernel_rx_buf.replace(rx_buf);
 
             println!(
@@ -371,43 +429,69 @@ impl<'a> MCTPDriver<'a> {
         }
     }
 
+    /// Remove the buffered message at `index`, compacting the remaining payloads in `rx_buf`.
+    /// `used` is the number of bytes currently occupied in `rx_buf`.
+    /// Returns the length of the removed message.
+    fn remove_buffered_message(
+        messages: &mut [Option<BufferedMessage>; MAX_BUFFERED_MESSAGES],
+        rx_buf: &mut [u8],
+        used: usize,
+        index: usize,
+    ) -> usize {
+        let start: usize = messages[..index].iter().flatten().map(|m| m.msg_len).sum();
+        let len = messages[index].map_or(0, |m| m.msg_len);
+        rx_buf.copy_within(start + len..used, start);
+        messages.copy_within(index + 1.., index);
+        messages[MAX_BUFFERED_MESSAGES - 1] = None;
+        len
+    }
+
     /// Check if there's a buffered message that matches a pending request or response
     fn check_buffered_message(&self, op_ctx: &OpContext) -> bool {
-        if let Some(buffered_msg) = self.buffered_message.take() {
-            let matched = buffered_msg.matches_pending_operation(op_ctx);
-            self.buffered_message.set(buffered_msg);
-            matched
-        } else {
-            false
-        }
+        self.buffered_messages
+            .get()
+            .iter()
+            .flatten()
+            .any(|buffered_msg| buffered_msg.matches_pending_operation(op_ctx))
     }
 
-    /// Deliver buffered rx message to waiting application
+    /// Deliver buffered rx messages to waiting applications
     fn deliver_buffered_message(&self) {
-        if let Some(buffered_msg) = self.buffered_message.take() {
-            if let Some(rx_buf) = self.kernel_rx_buf.take() {
-                // Find the app that matches this buffered message
-                self.apps.each(|_, app, kernel_data| {
-                    // Check if this buffered message matches a pending request or response
-                    let rw_buffer: usize;
-                    let rx_request: bool;
-
-                    let request_match = app.pending_rx_request.as_ref().is_some_and(|req_ctx| {
-                        buffered_msg.matches_pending_operation(req_ctx)
-                    });
-                    let response_match = app.pending_rx_response.as_ref().is_some_and(|rsp_ctx| {
-                        buffered_msg.matches_pending_operation(rsp_ctx)
-                    });
-
-                    if request_match {
-                        rw_buffer = rw_allow::READ_REQUEST;
-                        rx_request = true;
-                    } else if response_match {
-                        rw_buffer = rw_allow::READ_RESPONSE;
-                        rx_request = false;
+        let mut messages = self.buffered_messages.get();
+        if messages[0].is_none() {
+            return;
+        }
+
+        if let Some(rx_buf) = self.kernel_rx_buf.take() {
+            self.apps.each(|_, app, kernel_data| {
+                // A request and a response may both be waiting for this app
+                for rx_request in [true, false] {
+                    let pending_op = if rx_request {
+                        app.pending_rx_request.as_ref()
                     } else {
-                        return; // No match, continue to next app
-                    }
+                        app.pending_rx_response.as_ref()
+                    };
+                    let Some(pending_op) = pending_op else {
+                        continue;
+                    };
+
+                    // Deliver the oldest buffered message that matches the pending operation
+                    let Some(index) = messages.iter().position(|m| {
+                        m.is_some_and(|buffered_msg| buffered_msg.matches_pending_operation(pending_op))
+                    }) else {
+                        continue;
+                    };
+                    let Some(buffered_msg) = messages[index] else {
+                        continue;
+                    };
+                    let start: usize = messages[..index].iter().flatten().map(|m| m.msg_len).sum();
+                    let payload = &rx_buf[start..start + buffered_msg.msg_len];
+
+                    let rw_buffer = if rx_request {
+                        rw_allow::READ_REQUEST
+                    } else {
+                        rw_allow::READ_RESPONSE
+                    };
 
                     // Copy the buffered message to the process buffer
                     let res = kernel_data
@@ -417,7 +501,7 @@ impl<'a> MCTPDriver<'a> {
                                 if rmsg_payload.len() < buffered_msg.msg_len {
                                     Err(ErrorCode::SIZE)
                                 } else {
-                                    rmsg_payload[..buffered_msg.msg_len].copy_from_slice(&rx_buf[..buffered_msg.msg_len]);
+                                    rmsg_payload[..buffered_msg.msg_len].copy_from_slice(payload);
                                     Ok(())
                                 }
                             })
@@ -443,12 +527,16 @@ impl<'a> MCTPDriver<'a> {
                         ) {
                             println!("[MCTP-CAPSULE]::deliver_buffered_message upcall schedule failed: {:?}", e);
                         }
+
+                        let used: usize = messages.iter().flatten().map(|m| m.msg_len).sum();
+                        Self::remove_buffered_message(&mut messages, rx_buf, used, index);
                     }
-                });
+                }
+            });
 
-                // Restore the rx buffer
-                self.kernel_rx_buf.replace(rx_buf);
-            }
+            // Restore the rx buffer
+            self.kernel_rx_buf.replace(rx_buf);
+            self.buffered_messages.set(messages);
         }
     }
 }
@@ -466,6 +554,10 @@ impl SyscallDriver for MCTPDriver<'_> {
     ///   Otherwise, replaces the pending rx operation context with the new one.
     ///   When a new message is received from peer EID, the metadata is compared with the pending rx operation context.
     ///   If the metadata matches, the message is copied to the process buffer and the upcall is scheduled.
+    ///   If the response to a request does not arrive within the MCTP response timeout, the pending
+    ///   Receive Response is cleared and the RESPONSE_TIMEOUT upcall is scheduled with
+    ///   (NOACK status code, 0, msg_info). Receive Response fails with NOACK if the response
+    ///   already timed out, or was dropped from the full message buffer, before the command was issued.
     ///
     ///
     /// - `3`: Send Request Message.
@@ -514,6 +606,9 @@ impl SyscallDriver for MCTPDriver<'_> {
                         })
                         .unwrap_or_else(|err| CommandReturn::failure(err.into()))
                 } else if command_num == 2 {
+                    if self.take_expired_response(msg_tag, peer_eid) {
+                        return CommandReturn::failure(ErrorCode::NOACK);
+                    }
                     self.apps
                         .enter(process_id, |app, _| {
                             let rsp_rx_ctx = OpContext {
@@ -704,6 +799,41 @@ impl MCTPRxClient for MCTPDriver<'_> {
             }
         }
     }
+
+    fn receive_timeout(&self, peer_eid: u8, msg_type: u8, msg_tag: u8) {
+        if self.msg_type as u8 != msg_type {
+            return;
+        }
+
+        let mut notified = false;
+        self.apps.each(|_, app, kernel_data| {
+            if notified || !self.pending_rx_response(app, msg_tag, peer_eid) {
+                return;
+            }
+            app.pending_rx_response = None;
+            notified = true;
+
+            let msg_info =
+                ((peer_eid as usize) << 16) | ((msg_type as usize) << 8) | (msg_tag as usize);
+            if let Err(e) = kernel_data.schedule_upcall(
+                upcall::RESPONSE_TIMEOUT,
+                (
+                    kernel::errorcode::into_statuscode(Err(ErrorCode::NOACK)),
+                    0,
+                    msg_info,
+                ),
+            ) {
+                println!(
+                    "[MCTP-CAPSULE]::receive_timeout upcall schedule failed: {:?}",
+                    e
+                );
+            }
+        });
+
+        if !notified {
+            self.expire_response(msg_tag, peer_eid);
+        }
+    }
 }
 
 impl DeferredCallClient for MCTPDriver<'_> {
@@ -4,6 +4,7 @@ pub mod base_protocol;
 pub mod control_msg;
 pub mod driver;
 pub mod mux;
+pub mod pcie_vdm;
 pub mod recv;
 pub mod send;
 pub mod transport_binding;
@@ -1,9 +1,12 @@
 // Licensed under the Apache-2.0 license
 
 use crate::mctp::base_protocol::{
-    MCTPHeader, MessageType, MCTP_BASELINE_TRANSMISSION_UNIT, MCTP_HDR_SIZE,
+    MCTPHeader, MessageType, MCTP_BASELINE_TRANSMISSION_UNIT, MCTP_BROADCAST_EID, MCTP_HDR_SIZE,
+    MCTP_MT2_MIN_MS, MCTP_MT4_MIN_MS, MCTP_TAG_MASK,
+};
+use crate::mctp::control_msg::{
+    MCTPCtrlCmd, MCTPCtrlMsgHdr, MCTP_CTRL_MSG_HEADER_LEN, MCTP_UUID_LEN,
 };
-use crate::mctp::control_msg::{MCTPCtrlCmd, MCTPCtrlMsgHdr, MCTP_CTRL_MSG_HEADER_LEN};
 use crate::mctp::recv::MCTPRxState;
 use crate::mctp::send::MCTPTxState;
 use crate::mctp::transport_binding::{MCTPTransportBinding, TransportRxClient, TransportTxClient};
@@ -12,11 +15,22 @@ use core::cell::Cell;
 use core::fmt::Write;
 use kernel::collections::list::List;
 use kernel::deferred_call::{DeferredCall, DeferredCallClient};
-use kernel::hil::time::{Alarm, Ticks};
+use kernel::hil::time::{Alarm, AlarmClient, ConvertTicks, Ticks};
 use kernel::utilities::cells::TakeCell;
 use kernel::utilities::leasable_buffer::SubSliceMut;
 use kernel::ErrorCode;
 
+/// Number of message tags that can be outstanding at once.
+const MCTP_NUM_MSG_TAGS: usize = 8;
+
+/// A request sent by this endpoint as tag owner that is waiting for its response.
+#[derive(Clone, Copy)]
+struct OutstandingRequest {
+    dest_eid: u8,
+    msg_type: u8,
+    sent_time: u32,
+}
+
 /// MUX struct that manages multiple MCTP driver users (clients).
 ///
 /// This struct implements a FIFO queue for the
@@ -30,6 +44,14 @@ pub struct MuxMCTPDriver<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> {
     next_msg_tag: Cell<u8>, //global msg tag. increment by 1 for next tag upto 7 and wrap around.
     local_eid: Cell<u8>,
     mtu: Cell<usize>,
+    endpoint_uuid: Cell<[u8; MCTP_UUID_LEN]>,
+    network_id: Cell<[u8; MCTP_UUID_LEN]>,
+    // Set by Set Endpoint ID and cleared by Prepare for Endpoint Discovery.
+    discovered: Cell<bool>,
+    // Requests awaiting a response, indexed by message tag
+    outstanding_requests: Cell<[Option<OutstandingRequest>; MCTP_NUM_MSG_TAGS]>,
+    response_timeout_ms: Cell<u32>,
+    reassembly_timeout_ms: Cell<u32>,
     // List of outstanding send requests
     sender_list: List<'a, MCTPTxState<'a, A, M>>,
     receiver_list: List<'a, MCTPRxState<'a>>,
@@ -44,6 +66,8 @@ impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> MuxMCTPDriver<'a, A, M> {
         mctp_device: &'a dyn MCTPTransportBinding<'a>,
         local_eid: u8,
         mtu: usize,
+        endpoint_uuid: [u8; MCTP_UUID_LEN],
+        network_id: [u8; MCTP_UUID_LEN],
         tx_pkt_buf: &'static mut [u8],
         rx_pkt_buf: &'static mut [u8],
         clock: &'a A,
@@ -53,6 +77,12 @@ impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> MuxMCTPDriver<'a, A, M> {
             next_msg_tag: Cell::new(0),
             local_eid: Cell::new(local_eid),
             mtu: Cell::new(mtu),
+            endpoint_uuid: Cell::new(endpoint_uuid),
+            network_id: Cell::new(network_id),
+            discovered: Cell::new(false),
+            outstanding_requests: Cell::new([None; MCTP_NUM_MSG_TAGS]),
+            response_timeout_ms: Cell::new(MCTP_MT4_MIN_MS),
+            reassembly_timeout_ms: Cell::new(MCTP_MT4_MIN_MS),
             sender_list: List::new(),
             receiver_list: List::new(),
             tx_pkt_buffer: TakeCell::new(tx_pkt_buf),
@@ -94,6 +124,26 @@ impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> MuxMCTPDriver<'a, A, M> {
         self.mtu.set(mtu);
     }
 
+    /// Sets how long to wait for the response to a request before notifying the client.
+    /// The value is clamped to the MT2 range of DSP0236.
+    pub fn set_response_timeout_ms(&self, timeout_ms: u32) {
+        self.response_timeout_ms
+            .set(timeout_ms.clamp(MCTP_MT2_MIN_MS, MCTP_MT4_MIN_MS));
+    }
+
+    /// Sets how long a partially received message is kept without new packets.
+    pub fn set_reassembly_timeout_ms(&self, timeout_ms: u32) {
+        self.reassembly_timeout_ms.set(timeout_ms);
+    }
+
+    pub fn set_endpoint_uuid(&self, uuid: [u8; MCTP_UUID_LEN]) {
+        self.endpoint_uuid.set(uuid);
+    }
+
+    pub fn set_network_id(&self, network_id: [u8; MCTP_UUID_LEN]) {
+        self.network_id.set(network_id);
+    }
+
     pub fn get_local_eid(&self) -> u8 {
         self.local_eid.get()
     }
@@ -102,12 +152,147 @@ impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> MuxMCTPDriver<'a, A, M> {
         self.mtu.get()
     }
 
+    pub fn get_endpoint_uuid(&self) -> [u8; MCTP_UUID_LEN] {
+        self.endpoint_uuid.get()
+    }
+
+    pub fn get_network_id(&self) -> [u8; MCTP_UUID_LEN] {
+        self.network_id.get()
+    }
+
+    pub fn is_discovered(&self) -> bool {
+        self.discovered.get()
+    }
+
+    pub fn get_response_timeout_ms(&self) -> u32 {
+        self.response_timeout_ms.get()
+    }
+
+    pub fn get_reassembly_timeout_ms(&self) -> u32 {
+        self.reassembly_timeout_ms.get()
+    }
+
+    /// Returns the next message tag that is not held by an outstanding request.
+    /// If all tags are outstanding, the next tag in sequence is reused.
     pub fn get_next_msg_tag(&self) -> u8 {
-        let msg_tag = self.next_msg_tag.get();
-        self.next_msg_tag.set((msg_tag + 1) % 8);
+        let outstanding = self.outstanding_requests.get();
+        let start = self.next_msg_tag.get();
+        let msg_tag = (0..MCTP_NUM_MSG_TAGS as u8)
+            .map(|i| (start + i) % MCTP_NUM_MSG_TAGS as u8)
+            .find(|tag| outstanding[*tag as usize].is_none())
+            .unwrap_or_else(|| {
+                println!(
+                    "MuxMCTPDriver: All message tags are outstanding. Reusing tag {}.",
+                    start
+                );
+                start
+            });
+        self.next_msg_tag
+            .set((msg_tag + 1) % MCTP_NUM_MSG_TAGS as u8);
         msg_tag
     }
 
+    /// Records a request that was sent as tag owner and starts its response timer.
+    fn track_request(&self, dest_eid: u8, msg_type: u8, msg_tag: u8) {
+        let mut outstanding = self.outstanding_requests.get();
+        outstanding[(msg_tag & MCTP_TAG_MASK) as usize] = Some(OutstandingRequest {
+            dest_eid,
+            msg_type,
+            sent_time: self.clock.now().into_u32(),
+        });
+        self.outstanding_requests.set(outstanding);
+        self.update_timeout_alarm();
+    }
+
+    /// Releases the tag of an outstanding request once its response starts to arrive.
+    fn release_request(&self, src_eid: u8, msg_tag: u8) {
+        let mut outstanding = self.outstanding_requests.get();
+        let tag = (msg_tag & MCTP_TAG_MASK) as usize;
+        if outstanding[tag].is_some_and(|req| req.dest_eid == src_eid) {
+            outstanding[tag] = None;
+            self.outstanding_requests.set(outstanding);
+        }
+    }
+
+    /// Arms the alarm for the earliest response or reassembly deadline, or disarms it if
+    /// there is nothing to wait for.
+    fn update_timeout_alarm(&self) {
+        let now = self.clock.now().into_u32();
+        let response_timeout = self
+            .clock
+            .ticks_from_ms(self.response_timeout_ms.get())
+            .into_u32();
+        let reassembly_timeout = self
+            .clock
+            .ticks_from_ms(self.reassembly_timeout_ms.get())
+            .into_u32();
+
+        let response_deadlines = self
+            .outstanding_requests
+            .get()
+            .into_iter()
+            .flatten()
+            .map(|req| response_timeout.saturating_sub(now.wrapping_sub(req.sent_time)));
+        let reassembly_deadlines = self
+            .receiver_list
+            .iter()
+            .filter_map(|rx_state| rx_state.assembly_time())
+            .map(|last_pkt_time| {
+                reassembly_timeout.saturating_sub(now.wrapping_sub(last_pkt_time))
+            });
+
+        match response_deadlines.chain(reassembly_deadlines).min() {
+            Some(remaining) => self
+                .clock
+                .set_alarm(self.clock.now(), A::Ticks::from(remaining.max(1))),
+            None => {
+                let _ = self.clock.disarm();
+            }
+        }
+    }
+
+    /// Notifies the clients of expired requests and drops stale partial messages.
+    fn check_timeouts(&self) {
+        let now = self.clock.now().into_u32();
+        let response_timeout = self
+            .clock
+            .ticks_from_ms(self.response_timeout_ms.get())
+            .into_u32();
+        let reassembly_timeout = self
+            .clock
+            .ticks_from_ms(self.reassembly_timeout_ms.get())
+            .into_u32();
+
+        let mut outstanding = self.outstanding_requests.get();
+        for (msg_tag, entry) in outstanding.iter_mut().enumerate() {
+            let Some(req) = *entry else {
+                continue;
+            };
+            if now.wrapping_sub(req.sent_time) < response_timeout {
+                continue;
+            }
+            *entry = None;
+            println!(
+                "MuxMCTPDriver: No response from EID {} for msg tag {}",
+                req.dest_eid, msg_tag
+            );
+            if let Some(rx_state) = self
+                .receiver_list
+                .iter()
+                .find(|rx_state| rx_state.is_receive_expected(req.msg_type.into()))
+            {
+                rx_state.response_timeout(req.dest_eid, msg_tag as u8);
+            }
+        }
+        self.outstanding_requests.set(outstanding);
+
+        self.receiver_list
+            .iter()
+            .for_each(|rx_state| rx_state.expire_assembly(now, reassembly_timeout));
+
+        self.update_timeout_alarm();
+    }
+
     fn interpret_packet(&self, packet: &[u8]) -> (MCTPHeader, Option<MessageType>, usize) {
         let mut msg_type = None;
 
@@ -175,9 +360,15 @@ impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> MuxMCTPDriver<'a, A, M> {
             return Err(ErrorCode::INVAL);
         }
 
+        // Respond to broadcast requests from the local EID
+        let resp_src_eid = if mctp_hdr.dest_eid() == MCTP_BROADCAST_EID {
+            self.get_local_eid()
+        } else {
+            mctp_hdr.dest_eid()
+        };
         let mctp_hdr_resp = MCTPHeader::new(
             mctp_hdr.src_eid(),
-            mctp_hdr.dest_eid(),
+            resp_src_eid,
             1,
             1,
             0,
@@ -210,15 +401,25 @@ impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> MuxMCTPDriver<'a, A, M> {
             Err(ErrorCode::INVAL)?;
         }
 
+        // Only undiscovered endpoints respond to Endpoint Discovery
+        if matches!(mctp_ctrl_cmd, MCTPCtrlCmd::EndpointDiscovery) && self.is_discovered() {
+            return Ok(());
+        }
+
         self.tx_pkt_buffer
             .take()
             .map_or(Err(ErrorCode::NOMEM), |resp_buf| {
                 let result = match mctp_ctrl_cmd {
                     MCTPCtrlCmd::SetEID => mctp_ctrl_cmd
-                        .process_set_endpoint_id(req_buf, &mut resp_buf[msg_payload_start..])
+                        .process_set_endpoint_id(
+                            self.get_local_eid(),
+                            req_buf,
+                            &mut resp_buf[msg_payload_start..],
+                        )
                         .map(|eid| {
                             if let Some(eid) = eid {
                                 self.set_local_eid(eid);
+                                self.discovered.set(true);
                             }
                         }),
 
@@ -232,6 +433,30 @@ impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> MuxMCTPDriver<'a, A, M> {
 
                     MCTPCtrlCmd::GetMsgTypeSupport => mctp_ctrl_cmd
                         .process_get_msg_type_support(req_buf, &mut resp_buf[msg_payload_start..]),
+
+                    MCTPCtrlCmd::GetEndpointUUID => mctp_ctrl_cmd.process_get_endpoint_uuid(
+                        &self.get_endpoint_uuid(),
+                        &mut resp_buf[msg_payload_start..],
+                    ),
+
+                    MCTPCtrlCmd::GetVendorDefinedMsgSupport => mctp_ctrl_cmd
+                        .process_get_vendor_defined_msg_support(
+                            req_buf,
+                            &mut resp_buf[msg_payload_start..],
+                        ),
+
+                    MCTPCtrlCmd::PrepareForEndpointDiscovery => mctp_ctrl_cmd
+                        .process_endpoint_discovery(&mut resp_buf[msg_payload_start..])
+                        .map(|_| self.discovered.set(false)),
+
+                    MCTPCtrlCmd::EndpointDiscovery => {
+                        mctp_ctrl_cmd.process_endpoint_discovery(&mut resp_buf[msg_payload_start..])
+                    }
+
+                    MCTPCtrlCmd::GetNetworkID => mctp_ctrl_cmd.process_get_network_id(
+                        &self.get_network_id(),
+                        &mut resp_buf[msg_payload_start..],
+                    ),
                     _ => return Err(ErrorCode::NOSUPPORT),
                 };
 
@@ -251,7 +476,10 @@ impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> MuxMCTPDriver<'a, A, M> {
                             });
 
                         match res {
-                            Ok(_) => match self.mctp_device.transmit(resp_buf, resp_len) {
+                            Ok(_) => match self
+                                .mctp_device
+                                .transmit(resp_buf, mctp_hdr_start + resp_len)
+                            {
                                 Ok(_) => Ok(()),
                                 Err((err, tx_buf)) => {
                                     self.tx_pkt_buffer.replace(tx_buf);
@@ -275,7 +503,13 @@ impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> MuxMCTPDriver<'a, A, M> {
     fn send_next_packet(&self, cur_sender: &'a MCTPTxState<'a, A, M>) {
         let mut tx_pkt = SubSliceMut::new(self.tx_pkt_buffer.take().unwrap());
         let mctp_hdr_offset = self.mctp_hdr_offset();
-        let pkt_end_offset = self.get_mtu();
+
+        // All packets of a message but the last must have the same size, so
+        // only pick up a renegotiated MTU at the start of a message.
+        if cur_sender.is_som() {
+            self.set_mtu(self.mctp_device.get_mtu_size());
+        }
+        let pkt_end_offset = mctp_hdr_offset + self.get_mtu();
 
         // set the window of the subslice for MCTP header and the payload
         tx_pkt.slice(mctp_hdr_offset..pkt_end_offset);
@@ -321,9 +555,16 @@ impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> MuxMCTPDriver<'a, A, M> {
             .iter()
             .find(|rx_state| rx_state.is_receive_expected(msg_type));
 
+        if mctp_hdr.tag_owner() == 0 {
+            self.release_request(mctp_hdr.src_eid(), mctp_hdr.msg_tag());
+        }
+
         if let Some(rx_state) = rx_state {
             let recv_time = self.clock.now().into_u32();
             rx_state.start_receive(mctp_hdr, msg_type, pkt_payload, recv_time);
+            if mctp_hdr.eom() == 0 {
+                self.update_timeout_alarm();
+            }
         } else {
             println!("MuxMCTPDriver: No matching receive request found. Dropping packet.");
         }
@@ -369,6 +610,9 @@ impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> TransportTxClient for MuxMCT
         let mut cur_sender = self.sender_list.head();
         if let Some(sender) = cur_sender {
             if sender.is_eom() || result.is_err() {
+                if sender.is_tag_owner() && result.is_ok() {
+                    self.track_request(sender.dest_eid(), sender.msg_type(), sender.msg_tag());
+                }
                 sender.send_done(result);
                 self.sender_list.pop_head();
                 cur_sender = self.sender_list.head();
@@ -431,6 +675,12 @@ impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> TransportRxClient for MuxMCT
     }
 }
 
+impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> AlarmClient for MuxMCTPDriver<'a, A, M> {
+    fn alarm(&self) {
+        self.check_timeouts();
+    }
+}
+
 impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> DeferredCallClient for MuxMCTPDriver<'a, A, M> {
     fn handle_deferred_call(&self) {
         self.deferred_send();
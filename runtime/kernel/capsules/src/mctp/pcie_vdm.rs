@@ -0,0 +1,448 @@
+// Licensed under the Apache-2.0 license
+
+//! MCTP over PCIe VDM transport binding (DSP0238).
+//!
+//! Each MCTP packet is carried in a PCIe Vendor Defined Message (Type 1) TLP with the DMTF
+//! vendor ID. The TLP header is followed by the MCTP transport header and the packet payload,
+//! padded to a dword boundary. The TLPs are exchanged with the PCIe host through the DOE
+//! mailbox hardware.
+
+use crate::mctp::base_protocol::{MCTPHeader, MCTP_BASELINE_TRANSMISSION_UNIT, MCTP_HDR_SIZE};
+use crate::mctp::transport_binding::{MCTPTransportBinding, TransportRxClient, TransportTxClient};
+use bitfield::bitfield;
+use caliptra_mcu_doe_transport::hil::{DoeTransport, DoeTransportRxClient, DoeTransportTxClient};
+use caliptra_mcu_romtime::println;
+use core::cell::Cell;
+use kernel::utilities::cells::{OptionalCell, TakeCell};
+use kernel::ErrorCode;
+
+/// Size of the PCIe VDM TLP header preceding the MCTP transport header.
+pub const PCIE_VDM_HDR_SIZE: usize = 12;
+
+/// TLP format: 4 DW header with data.
+const PCIE_VDM_FMT: u8 = 0b011;
+/// TLP type: message, lower three bits carry the routing.
+const PCIE_VDM_TYPE_MSG: u8 = 0b10;
+/// Vendor Defined Message Type 1.
+const PCIE_VDM_MSG_CODE: u8 = 0x7F;
+/// MCTP VDM code for MCTP messages.
+const PCIE_VDM_MCTP_CODE: u8 = 0x0;
+/// DMTF PCI vendor ID.
+pub const PCIE_VDM_DMTF_VENDOR_ID: u16 = 0x1AB4;
+
+/// Largest MCTP packet (transport header and payload) sent over PCIe VDM.
+pub const MCTP_PCIE_VDM_MTU: usize = MCTP_HDR_SIZE + MCTP_BASELINE_TRANSMISSION_UNIT;
+
+/// Number of peer endpoints whose PCI ID is remembered for route-by-ID.
+const MCTP_PCIE_VDM_MAX_PEERS: usize = 4;
+
+/// PCIe message routing types allowed for MCTP VDMs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PcieVdmRouting {
+    RouteToRootComplex = 0b000,
+    RouteById = 0b010,
+    BroadcastFromRootComplex = 0b011,
+}
+
+impl TryFrom<u8> for PcieVdmRouting {
+    type Error = ErrorCode;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0b000 => Ok(PcieVdmRouting::RouteToRootComplex),
+            0b010 => Ok(PcieVdmRouting::RouteById),
+            0b011 => Ok(PcieVdmRouting::BroadcastFromRootComplex),
+            _ => Err(ErrorCode::INVAL),
+        }
+    }
+}
+
+bitfield! {
+    /// First header dword, in PCIe (big endian) bit order.
+    #[derive(Clone, Copy, Default)]
+    pub struct PcieVdmHeaderDw0(u32);
+    u8;
+    pub fmt, set_fmt: 31, 29;
+    pub tlp_type, set_tlp_type: 28, 27;
+    pub routing, set_routing: 26, 24;
+    pub traffic_class, set_traffic_class: 22, 20;
+    u16;
+    pub length, set_length: 9, 0;
+}
+
+bitfield! {
+    /// Second header dword, in PCIe (big endian) bit order.
+    #[derive(Clone, Copy, Default)]
+    pub struct PcieVdmHeaderDw1(u32);
+    u16;
+    pub requester_id, set_requester_id: 31, 16;
+    u8;
+    pub pad_len, set_pad_len: 13, 12;
+    pub vdm_code, set_vdm_code: 11, 8;
+    pub msg_code, set_msg_code: 7, 0;
+}
+
+bitfield! {
+    /// Third header dword, in PCIe (big endian) bit order.
+    #[derive(Clone, Copy, Default)]
+    pub struct PcieVdmHeaderDw2(u32);
+    u16;
+    pub target_id, set_target_id: 31, 16;
+    pub vendor_id, set_vendor_id: 15, 0;
+}
+
+/// PCIe VDM TLP header for MCTP, excluding the MCTP transport header.
+#[derive(Clone, Copy, Default)]
+pub struct PcieVdmHeader {
+    pub dw0: PcieVdmHeaderDw0,
+    pub dw1: PcieVdmHeaderDw1,
+    pub dw2: PcieVdmHeaderDw2,
+}
+
+impl PcieVdmHeader {
+    /// Builds the header for an MCTP packet of `pkt_len` bytes, including the MCTP transport header.
+    pub fn new(routing: PcieVdmRouting, requester_id: u16, target_id: u16, pkt_len: usize) -> Self {
+        let payload_len = pkt_len.saturating_sub(MCTP_HDR_SIZE);
+        let pad_len = payload_len.wrapping_neg() % 4;
+
+        let mut hdr = PcieVdmHeader::default();
+        hdr.dw0.set_fmt(PCIE_VDM_FMT);
+        hdr.dw0.set_tlp_type(PCIE_VDM_TYPE_MSG);
+        hdr.dw0.set_routing(routing as u8);
+        hdr.dw0.set_length(((payload_len + pad_len) / 4) as u16);
+        hdr.dw1.set_requester_id(requester_id);
+        hdr.dw1.set_pad_len(pad_len as u8);
+        hdr.dw1.set_vdm_code(PCIE_VDM_MCTP_CODE);
+        hdr.dw1.set_msg_code(PCIE_VDM_MSG_CODE);
+        hdr.dw2.set_target_id(target_id);
+        hdr.dw2.set_vendor_id(PCIE_VDM_DMTF_VENDOR_ID);
+        hdr
+    }
+
+    /// Decodes and validates the header at the start of `buf`.
+    pub fn decode(buf: &[u8]) -> Result<Self, ErrorCode> {
+        if buf.len() < PCIE_VDM_HDR_SIZE {
+            return Err(ErrorCode::SIZE);
+        }
+        let dw = |i: usize| u32::from_be_bytes([buf[i], buf[i + 1], buf[i + 2], buf[i + 3]]);
+        let hdr = PcieVdmHeader {
+            dw0: PcieVdmHeaderDw0(dw(0)),
+            dw1: PcieVdmHeaderDw1(dw(4)),
+            dw2: PcieVdmHeaderDw2(dw(8)),
+        };
+
+        if hdr.dw0.fmt() != PCIE_VDM_FMT
+            || hdr.dw0.tlp_type() != PCIE_VDM_TYPE_MSG
+            || hdr.dw1.msg_code() != PCIE_VDM_MSG_CODE
+            || hdr.dw1.vdm_code() != PCIE_VDM_MCTP_CODE
+            || hdr.dw2.vendor_id() != PCIE_VDM_DMTF_VENDOR_ID
+        {
+            return Err(ErrorCode::INVAL);
+        }
+        hdr.routing()?;
+        Ok(hdr)
+    }
+
+    pub fn encode(&self, buf: &mut [u8]) -> Result<(), ErrorCode> {
+        if buf.len() < PCIE_VDM_HDR_SIZE {
+            return Err(ErrorCode::SIZE);
+        }
+        buf[0..4].copy_from_slice(&self.dw0.0.to_be_bytes());
+        buf[4..8].copy_from_slice(&self.dw1.0.to_be_bytes());
+        buf[8..12].copy_from_slice(&self.dw2.0.to_be_bytes());
+        Ok(())
+    }
+
+    pub fn routing(&self) -> Result<PcieVdmRouting, ErrorCode> {
+        self.dw0.routing().try_into()
+    }
+
+    /// Length of the MCTP packet carried in the TLP, including the MCTP transport header
+    /// and excluding the padding.
+    pub fn mctp_pkt_len(&self) -> Option<usize> {
+        (MCTP_HDR_SIZE + self.dw0.length() as usize * 4).checked_sub(self.dw1.pad_len() as usize)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct PeerRoute {
+    eid: u8,
+    pci_id: u16,
+}
+
+pub struct MCTPPcieVdmBinding<'a, T: DoeTransport<'a>> {
+    /// Reference to the DOE mailbox transport carrying the TLPs.
+    doe_transport: &'a T,
+    rx_client: OptionalCell<&'a dyn TransportRxClient>,
+    tx_client: OptionalCell<&'a dyn TransportTxClient>,
+    /// PCI ID (bus/device/function) of this endpoint. Learned from the target ID of
+    /// route-by-ID TLPs addressed to this endpoint.
+    pci_id: Cell<u16>,
+    /// PCI IDs of the peers that sent us packets, so responses can be routed by ID.
+    peers: Cell<[Option<PeerRoute>; MCTP_PCIE_VDM_MAX_PEERS]>,
+    /// Buffer provided by the client for the next received packet.
+    rx_buffer: TakeCell<'static, [u8]>,
+    /// Buffer to store the transmitted packet.
+    tx_buffer: TakeCell<'static, [u8]>,
+}
+
+impl<'a, T: DoeTransport<'a>> MCTPPcieVdmBinding<'a, T> {
+    pub fn new(doe_transport: &'a T) -> MCTPPcieVdmBinding<'a, T> {
+        MCTPPcieVdmBinding {
+            doe_transport,
+            rx_client: OptionalCell::empty(),
+            tx_client: OptionalCell::empty(),
+            pci_id: Cell::new(0),
+            peers: Cell::new([None; MCTP_PCIE_VDM_MAX_PEERS]),
+            rx_buffer: TakeCell::empty(),
+            tx_buffer: TakeCell::empty(),
+        }
+    }
+
+    pub fn pci_id(&self) -> u16 {
+        self.pci_id.get()
+    }
+
+    fn remember_peer(&self, eid: u8, pci_id: u16) {
+        let mut peers = self.peers.get();
+        let slot = peers
+            .iter()
+            .position(|p| p.is_some_and(|p| p.eid == eid))
+            .or_else(|| peers.iter().position(|p| p.is_none()))
+            .unwrap_or_else(|| {
+                // Table full, evict the oldest entry.
+                peers.rotate_left(1);
+                MCTP_PCIE_VDM_MAX_PEERS - 1
+            });
+        peers[slot] = Some(PeerRoute { eid, pci_id });
+        self.peers.set(peers);
+    }
+
+    /// Route by ID to a known peer, otherwise hand the packet to the root complex,
+    /// which hosts the bus owner.
+    fn route_to(&self, dest_eid: u8) -> (PcieVdmRouting, u16) {
+        self.peers
+            .get()
+            .iter()
+            .flatten()
+            .find(|p| p.eid == dest_eid)
+            .map_or((PcieVdmRouting::RouteToRootComplex, 0), |p| {
+                (PcieVdmRouting::RouteById, p.pci_id)
+            })
+    }
+}
+
+impl<'a, T: DoeTransport<'a>> MCTPTransportBinding<'a> for MCTPPcieVdmBinding<'a, T> {
+    fn set_tx_client(&self, tx_client: &'a dyn TransportTxClient) {
+        self.tx_client.set(tx_client);
+    }
+
+    fn set_rx_client(&self, rx_client: &'a dyn TransportRxClient) {
+        self.rx_client.set(rx_client);
+    }
+
+    fn set_rx_buffer(&self, rx_buf: &'static mut [u8]) {
+        self.rx_buffer.replace(rx_buf);
+    }
+
+    fn transmit(
+        &self,
+        tx_buffer: &'static mut [u8],
+        len: usize,
+    ) -> Result<(), (ErrorCode, &'static mut [u8])> {
+        if len < PCIE_VDM_HDR_SIZE + MCTP_HDR_SIZE || len > tx_buffer.len() {
+            println!("MCTPPcieVdmBinding: Invalid length {}", len);
+            return Err((ErrorCode::SIZE, tx_buffer));
+        }
+
+        let pkt_len = len - PCIE_VDM_HDR_SIZE;
+        let mctp_hdr = MCTPHeader(u32::from_le_bytes([
+            tx_buffer[PCIE_VDM_HDR_SIZE],
+            tx_buffer[PCIE_VDM_HDR_SIZE + 1],
+            tx_buffer[PCIE_VDM_HDR_SIZE + 2],
+            tx_buffer[PCIE_VDM_HDR_SIZE + 3],
+        ]));
+        let (routing, target_id) = self.route_to(mctp_hdr.dest_eid());
+        let hdr = PcieVdmHeader::new(routing, self.pci_id.get(), target_id, pkt_len);
+
+        // Pad the TLP to a dword boundary
+        let tlp_len = len + hdr.dw1.pad_len() as usize;
+        if tlp_len > tx_buffer.len() || tlp_len / 4 > self.doe_transport.max_data_object_size_dw() {
+            println!("MCTPPcieVdmBinding: Packet too large {}", tlp_len);
+            return Err((ErrorCode::SIZE, tx_buffer));
+        }
+        tx_buffer[len..tlp_len].fill(0);
+        if let Err(e) = hdr.encode(tx_buffer) {
+            return Err((e, tx_buffer));
+        }
+
+        let words = tx_buffer[..tlp_len]
+            .chunks_exact(4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+        match self.doe_transport.transmit(words, tlp_len / 4) {
+            Ok(()) => {
+                self.tx_buffer.replace(tx_buffer);
+                Ok(())
+            }
+            Err(e) => Err((e, tx_buffer)),
+        }
+    }
+
+    fn enable(&self) {
+        self.doe_transport.enable();
+    }
+
+    fn disable(&self) {
+        self.doe_transport.disable();
+    }
+
+    fn get_mtu_size(&self) -> usize {
+        MCTP_PCIE_VDM_MTU
+    }
+
+    fn get_hdr_size(&self) -> usize {
+        PCIE_VDM_HDR_SIZE
+    }
+}
+
+impl<'a, T: DoeTransport<'a>> DoeTransportTxClient<'a> for MCTPPcieVdmBinding<'a, T> {
+    fn send_done(&self, result: Result<(), ErrorCode>) {
+        if let Some(tx_buffer) = self.tx_buffer.take() {
+            self.tx_client.map(|client| {
+                client.send_done(tx_buffer, result);
+            });
+        }
+    }
+}
+
+impl<'a, T: DoeTransport<'a>> DoeTransportRxClient for MCTPPcieVdmBinding<'a, T> {
+    fn receive(&self, rx_buf: &'static mut [u32], len_dw: usize) {
+        let mut tlp_hdr = [0u8; PCIE_VDM_HDR_SIZE + MCTP_HDR_SIZE];
+        if len_dw * 4 < tlp_hdr.len() || len_dw > rx_buf.len() {
+            println!(
+                "MCTPPcieVdmBinding: Invalid TLP length {}. Dropping packet.",
+                len_dw
+            );
+            self.doe_transport.set_rx_buffer(rx_buf);
+            return;
+        }
+        for (chunk, word) in tlp_hdr.chunks_exact_mut(4).zip(rx_buf.iter()) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+
+        let hdr = match PcieVdmHeader::decode(&tlp_hdr) {
+            Ok(hdr) => hdr,
+            Err(_) => {
+                println!("MCTPPcieVdmBinding: Not an MCTP VDM. Dropping packet.");
+                self.doe_transport.set_rx_buffer(rx_buf);
+                return;
+            }
+        };
+        let pkt_len = match hdr.mctp_pkt_len() {
+            Some(pkt_len) if PCIE_VDM_HDR_SIZE + pkt_len <= len_dw * 4 => pkt_len,
+            _ => {
+                println!("MCTPPcieVdmBinding: TLP length mismatch. Dropping packet.");
+                self.doe_transport.set_rx_buffer(rx_buf);
+                return;
+            }
+        };
+
+        if hdr.routing() == Ok(PcieVdmRouting::RouteById) {
+            self.pci_id.set(hdr.dw2.target_id());
+        }
+        let mctp_hdr = MCTPHeader(u32::from_le_bytes([
+            tlp_hdr[PCIE_VDM_HDR_SIZE],
+            tlp_hdr[PCIE_VDM_HDR_SIZE + 1],
+            tlp_hdr[PCIE_VDM_HDR_SIZE + 2],
+            tlp_hdr[PCIE_VDM_HDR_SIZE + 3],
+        ]));
+        self.remember_peer(mctp_hdr.src_eid(), hdr.dw1.requester_id());
+
+        if self.rx_buffer.is_none() {
+            self.rx_client.map(|client| client.write_expected());
+        }
+        let rx_buffer = match self.rx_buffer.take() {
+            Some(rx_buffer) if rx_buffer.len() >= pkt_len => rx_buffer,
+            rx_buffer => {
+                println!("MCTPPcieVdmBinding: No receive buffer. Dropping packet.");
+                if let Some(rx_buffer) = rx_buffer {
+                    self.rx_buffer.replace(rx_buffer);
+                }
+                self.doe_transport.set_rx_buffer(rx_buf);
+                return;
+            }
+        };
+
+        // Copy the MCTP packet out of the mailbox and hand the mailbox back right away.
+        let pkt_start = PCIE_VDM_HDR_SIZE / 4;
+        let pkt_words = rx_buf[pkt_start..].iter().flat_map(|w| w.to_le_bytes());
+        for (dst, src) in rx_buffer[..pkt_len].iter_mut().zip(pkt_words) {
+            *dst = src;
+        }
+        self.doe_transport.set_rx_buffer(rx_buf);
+
+        self.rx_client.map(|client| {
+            client.receive(rx_buffer, pkt_len);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vdm_header_encode() {
+        // Route-by-ID response of 7 payload bytes, padded by one byte
+        let hdr = PcieVdmHeader::new(PcieVdmRouting::RouteById, 0x0100, 0x0008, 11);
+        let mut buf = [0u8; PCIE_VDM_HDR_SIZE];
+        hdr.encode(&mut buf).unwrap();
+        assert_eq!(
+            buf,
+            [0x72, 0x00, 0x00, 0x02, 0x01, 0x00, 0x10, 0x7F, 0x00, 0x08, 0x1A, 0xB4]
+        );
+        assert_eq!(hdr.mctp_pkt_len(), Some(11));
+    }
+
+    #[test]
+    fn test_vdm_header_decode() {
+        let buf = [
+            0x70, 0x00, 0x00, 0x10, 0x00, 0x08, 0x00, 0x7F, 0x01, 0x00, 0x1A, 0xB4,
+        ];
+        let hdr = PcieVdmHeader::decode(&buf).unwrap();
+        assert_eq!(hdr.routing(), Ok(PcieVdmRouting::RouteToRootComplex));
+        assert_eq!(hdr.dw1.requester_id(), 0x0008);
+        assert_eq!(hdr.dw2.target_id(), 0x0100);
+        assert_eq!(hdr.mctp_pkt_len(), Some(MCTP_PCIE_VDM_MTU));
+
+        // Wrong vendor ID
+        let mut bad = buf;
+        bad[11] = 0xB5;
+        assert_eq!(PcieVdmHeader::decode(&bad).err(), Some(ErrorCode::INVAL));
+        // Reserved routing type
+        let mut bad = buf;
+        bad[0] = 0x71;
+        assert_eq!(PcieVdmHeader::decode(&bad).err(), Some(ErrorCode::INVAL));
+        assert_eq!(
+            PcieVdmHeader::decode(&buf[..8]).err(),
+            Some(ErrorCode::SIZE)
+        );
+    }
+
+    #[test]
+    fn test_vdm_header_padding() {
+        for payload_len in 0..8usize {
+            let hdr = PcieVdmHeader::new(
+                PcieVdmRouting::RouteToRootComplex,
+                0,
+                0,
+                MCTP_HDR_SIZE + payload_len,
+            );
+            let pad = hdr.dw1.pad_len() as usize;
+            assert_eq!((payload_len + pad) % 4, 0);
+            assert!(pad < 4);
+            assert_eq!(hdr.dw0.length() as usize, (payload_len + pad) / 4);
+            assert_eq!(hdr.mctp_pkt_len(), Some(MCTP_HDR_SIZE + payload_len));
+        }
+    }
+}
@@ -18,6 +18,10 @@ pub trait MCTPRxClient {
         msg_len: usize,
         recv_time: u32,
     );
+
+    /// Called when no response was received within the response timeout for a request
+    /// sent to `peer_eid`. `msg_tag` is the tag of the expected response (without the owner bit).
+    fn receive_timeout(&self, peer_eid: u8, msg_type: u8, msg_tag: u8);
 }
 
 /// Receive state
@@ -49,6 +53,7 @@ struct MsgTerminus {
     start_payload_len: usize,
     pkt_seq: u8,
     msg_size: usize,
+    last_pkt_time: u32,
 }
 
 impl<'a> MCTPRxState<'a> {
@@ -126,6 +131,7 @@ impl<'a> MCTPRxState<'a> {
                     msg_payload[offset..end_offset].copy_from_slice(pkt_payload);
                     msg_terminus.msg_size = end_offset;
                     msg_terminus.pkt_seq = mctp_hdr.next_pkt_seq();
+                    msg_terminus.last_pkt_time = recv_time;
                     self.msg_terminus.replace(msg_terminus);
                 })
                 .unwrap_or_else(|| {
@@ -172,6 +178,42 @@ impl<'a> MCTPRxState<'a> {
         }
     }
 
+    /// Returns the receive time of the last packet if a multi-packet message is being assembled.
+    pub fn assembly_time(&self) -> Option<u32> {
+        self.msg_terminus
+            .map(|msg_terminus| msg_terminus.last_pkt_time)
+    }
+
+    /// Drops the message being assembled if no packet was received for it within `timeout` ticks.
+    ///
+    /// # Arguments
+    /// 'now' - The current time in ticks.
+    /// 'timeout' - The reassembly timeout in ticks.
+    pub fn expire_assembly(&self, now: u32, timeout: u32) {
+        let expired = self.msg_terminus.map_or(false, |msg_terminus| {
+            now.wrapping_sub(msg_terminus.last_pkt_time) >= timeout
+        });
+        if expired {
+            if let Some(msg_terminus) = self.msg_terminus.take() {
+                println!(
+                    "MuxMCTPDriver - Reassembly timed out for msg tag {} from EID {}. Dropping message.",
+                    msg_terminus.msg_tag, msg_terminus.source_eid
+                );
+            }
+        }
+    }
+
+    /// Informs the client that no response arrived for a request sent to `peer_eid`.
+    ///
+    /// # Arguments
+    /// 'peer_eid' - The EID the request was sent to.
+    /// 'msg_tag' - The message tag of the request, without the owner bit.
+    pub fn response_timeout(&self, peer_eid: u8, msg_tag: u8) {
+        self.client.map(|client| {
+            client.receive_timeout(peer_eid, self.msg_type as u8, msg_tag & MCTP_TAG_MASK);
+        });
+    }
+
     /// Called when the first packet of a message is received.
     /// The message terminus state is initialized with the current context.
     /// The previous message assembly state will be lost and a new message assembly
@@ -214,6 +256,7 @@ impl<'a> MCTPRxState<'a> {
                     start_payload_len: pkt_payload_len,
                     pkt_seq: mctp_hdr.next_pkt_seq(),
                     msg_size: pkt_payload_len,
+                    last_pkt_time: recv_time,
                 };
                 self.msg_terminus.replace(msg_terminus);
             })
@@ -118,6 +118,28 @@ impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> MCTPTxState<'a, A, M> {
         }
     }
 
+    pub fn dest_eid(&self) -> u8 {
+        self.dest_eid.get()
+    }
+
+    pub fn msg_type(&self) -> u8 {
+        self.msg_type.get()
+    }
+
+    /// Message tag of the message being sent, without the owner bit.
+    pub fn msg_tag(&self) -> u8 {
+        self.msg_tag.get()
+    }
+
+    /// True if this endpoint owns the tag, i.e. the message is a request awaiting a response.
+    pub fn is_tag_owner(&self) -> bool {
+        self.tag_owner.get()
+    }
+
+    pub fn is_som(&self) -> bool {
+        self.offset.get() == 0
+    }
+
     pub fn is_eom(&self) -> bool {
         self.offset.get() >= self.msg_payload.map_or(0, |msg_payload| msg_payload.len())
     }
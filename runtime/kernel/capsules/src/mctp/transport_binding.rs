@@ -1,7 +1,7 @@
 // Licensed under the Apache-2.0 license
 
 use crate::mctp::base_protocol::{MCTP_BASELINE_TRANSMISSION_UNIT, MCTP_HDR_SIZE};
-use caliptra_mcu_i3c_driver::hil::{I3CTarget, RxClient, TxClient};
+use caliptra_mcu_i3c_driver::hil::{I3CTarget, I3CTargetInfo, RxClient, TxClient};
 use caliptra_mcu_romtime::println;
 use core::cell::Cell;
 use kernel::utilities::cells::OptionalCell;
@@ -14,6 +14,24 @@ pub const MCTP_I3C_MAXBUF: usize = MCTP_HDR_SIZE + MCTP_BASELINE_TRANSMISSION_UN
 pub const MCTP_I3C_MAXMTU: usize = MCTP_I3C_MAXBUF - 1;
 pub const MCTP_I3C_MINMTU: usize = MCTP_HDR_SIZE + MCTP_BASELINE_TRANSMISSION_UNIT;
 
+/// Computes the MTU from the max write/read lengths negotiated by the I3C controller
+/// with SETMWL/SETMRL. Falls back to the baseline MTU until the controller negotiates
+/// a transfer size, and never exceeds what the target device can buffer.
+fn negotiated_mtu(device_info: &I3CTargetInfo) -> usize {
+    let negotiated_len = match (device_info.negotiated_mwl, device_info.negotiated_mrl) {
+        (Some(mwl), Some(mrl)) => mwl.min(mrl),
+        (Some(len), None) | (None, Some(len)) => len,
+        (None, None) => return MCTP_I3C_MAXMTU,
+    };
+    let device_max_len = device_info
+        .max_read_len
+        .min(device_info.max_write_len)
+        .max(MCTP_I3C_MAXBUF);
+
+    // Max MTU excludes the PEC byte appended by the transport binding layer.
+    negotiated_len.clamp(MCTP_I3C_MAXBUF, device_max_len) - 1
+}
+
 /// This trait contains the interface definition
 /// for sending the MCTP packet through MCTP transport binding layer.
 pub trait MCTPTransportBinding<'a> {
@@ -173,7 +191,7 @@ impl<'a> MCTPTransportBinding<'a> for MCTPI3CBinding<'a> {
     }
 
     fn get_mtu_size(&self) -> usize {
-        MCTP_I3C_MAXMTU
+        negotiated_mtu(&self.i3c_target.get_device_info())
     }
 
     fn get_hdr_size(&self) -> usize {
@@ -252,6 +270,33 @@ mod tests {
         assert_eq!(0xf4, calculate_crc8(b"123456789"));
     }
 
+    fn device_info(negotiated_mwl: Option<usize>, negotiated_mrl: Option<usize>) -> I3CTargetInfo {
+        I3CTargetInfo {
+            static_addr: None,
+            dynamic_addr: Some(0x10),
+            max_read_len: 250,
+            max_write_len: 250,
+            negotiated_mwl,
+            negotiated_mrl,
+        }
+    }
+
+    #[test]
+    fn test_negotiated_mtu() {
+        // Nothing negotiated yet: baseline MTU
+        assert_eq!(negotiated_mtu(&device_info(None, None)), MCTP_I3C_MAXMTU);
+        // The smaller of MWL and MRL bounds the packet size
+        assert_eq!(negotiated_mtu(&device_info(Some(200), Some(128))), 127);
+        assert_eq!(negotiated_mtu(&device_info(Some(128), None)), 127);
+        // Never larger than the target device can handle
+        assert_eq!(negotiated_mtu(&device_info(Some(1024), Some(1024))), 249);
+        // Never below the baseline transmission unit
+        assert_eq!(
+            negotiated_mtu(&device_info(Some(8), Some(8))),
+            MCTP_I3C_MINMTU
+        );
+    }
+
     #[test]
     fn test_pec_for_req() {
         // Write to device address 0x10
@@ -11,9 +11,16 @@ use kernel::utilities::leasable_buffer::SubSliceMut;
 use kernel::ErrorCode;
 
 pub const MCTP_TEST_REMOTE_EID: u8 = 0x20;
+/// Requests to this EID are never answered by the test peer, to exercise the response timeout.
+pub const MCTP_TEST_UNRESPONSIVE_EID: u8 = 0x21;
+/// Requests to this EID are answered by the test peer with a partial message that it only
+/// completes after the reassembly timeout, followed by the full response.
+pub const MCTP_TEST_STALE_ASSEMBLY_EID: u8 = 0x22;
 pub const MCTP_TEST_MSG_SIZE: usize = 1000;
 
 static TEST_MSG_LEN_ARR: [usize; 4] = [64, 63, 256, 1000];
+// The loopback tests plus the response timeout and stale reassembly tests
+const TEST_COUNT: usize = TEST_MSG_LEN_ARR.len() + 2;
 
 pub trait TestClient {
     fn test_result(&self, passed: bool, npassed: usize, ntotal: usize);
@@ -26,6 +33,8 @@ pub struct MockMctp<'a> {
     msg_tag: Cell<u8>,
     test_client: OptionalCell<&'a dyn TestClient>,
     cur_idx: Cell<usize>,
+    timeout_test: Cell<bool>,
+    stale_assembly_test: Cell<bool>,
 }
 
 impl<'a> MockMctp<'a> {
@@ -41,6 +50,8 @@ impl<'a> MockMctp<'a> {
             msg_tag: Cell::new(0),
             test_client: OptionalCell::empty(),
             cur_idx: Cell::new(0),
+            timeout_test: Cell::new(false),
+            stale_assembly_test: Cell::new(false),
         }
     }
 
@@ -71,6 +82,47 @@ impl<'a> MockMctp<'a> {
             )
             .unwrap();
     }
+
+    /// Sends a request that the peer never answers and expects the MCTP mux to time it out.
+    fn run_response_timeout_test(&self) {
+        self.timeout_test.set(true);
+        self.prepare_send_data(TEST_MSG_LEN_ARR[0]);
+        self.mctp_sender
+            .send_msg(
+                self.msg_type as u8,
+                MCTP_TEST_UNRESPONSIVE_EID,
+                MCTP_TAG_OWNER,
+                self.mctp_msg_buf.take().unwrap(),
+            )
+            .unwrap();
+    }
+
+    /// Sends a multi-packet request whose response is preceded by a partial message that
+    /// the peer completes only after the reassembly timeout. The MCTP mux must expire the
+    /// stale message so that only the full response is delivered.
+    fn run_stale_assembly_test(&self) {
+        self.timeout_test.set(false);
+        self.stale_assembly_test.set(true);
+        self.prepare_send_data(TEST_MSG_LEN_ARR[self.cur_idx.get()]);
+        self.mctp_sender
+            .send_msg(
+                self.msg_type as u8,
+                MCTP_TEST_STALE_ASSEMBLY_EID,
+                MCTP_TAG_OWNER,
+                self.mctp_msg_buf.take().unwrap(),
+            )
+            .unwrap();
+    }
+
+    fn dest_eid(&self) -> u8 {
+        if self.timeout_test.get() {
+            MCTP_TEST_UNRESPONSIVE_EID
+        } else if self.stale_assembly_test.get() {
+            MCTP_TEST_STALE_ASSEMBLY_EID
+        } else {
+            MCTP_TEST_REMOTE_EID
+        }
+    }
 }
 
 impl MCTPRxClient for MockMctp<'_> {
@@ -83,37 +135,54 @@ impl MCTPRxClient for MockMctp<'_> {
         msg_len: usize,
         _recv_time: u32,
     ) {
+        if self.timeout_test.get() {
+            println!(
+                "FAILED! Received message from EID {} while waiting for the response timeout",
+                src_eid
+            );
+            self.test_client.map(|client| {
+                client.test_result(false, TEST_COUNT, TEST_COUNT);
+            });
+            return;
+        }
+
         if msg_type != self.msg_type as u8
-            || src_eid != MCTP_TEST_REMOTE_EID
+            || src_eid != self.dest_eid()
             || msg_tag != self.msg_tag.get()
             || msg_len != TEST_MSG_LEN_ARR[self.cur_idx.get()]
         {
             println!(
             "FAILED! Received message from EID/expected: {}/{} with message type/expected: {}/{} and message tag/expected: {}/{} msg_len/expected: {}/{}",
-            src_eid, MCTP_TEST_REMOTE_EID, msg_type, self.msg_type as u8, msg_tag, self.msg_tag.get(), msg_len, TEST_MSG_LEN_ARR[self.cur_idx.get()]
+            src_eid, self.dest_eid(), msg_type, self.msg_type as u8, msg_tag, self.msg_tag.get(), msg_len, TEST_MSG_LEN_ARR[self.cur_idx.get()]
         );
             self.test_client.map(|client| {
-                client.test_result(false, self.cur_idx.get() + 1, TEST_MSG_LEN_ARR.len());
+                client.test_result(false, self.cur_idx.get() + 1, TEST_COUNT);
             });
         }
 
         self.mctp_msg_buf.map(|buf| {
             if buf[..msg_len] != msg_payload[..msg_len] {
                 self.test_client.map(|client| {
-                    client.test_result(false, self.cur_idx.get() + 1, TEST_MSG_LEN_ARR.len());
+                    client.test_result(false, self.cur_idx.get() + 1, TEST_COUNT);
                 });
             }
         });
 
+        if self.stale_assembly_test.get() {
+            println!("Completed stale reassembly test");
+            self.test_client.map(|client| {
+                client.test_result(true, TEST_COUNT, TEST_COUNT);
+            });
+            return;
+        }
+
         println!(
             "Completed loopback test for message length: {}",
             TEST_MSG_LEN_ARR[self.cur_idx.get()]
         );
 
         if self.cur_idx.get() == TEST_MSG_LEN_ARR.len() - 1 {
-            self.test_client.map(|client| {
-                client.test_result(true, self.cur_idx.get() + 1, TEST_MSG_LEN_ARR.len());
-            });
+            self.run_response_timeout_test();
         } else {
             self.cur_idx.set(self.cur_idx.get() + 1);
             self.prepare_send_data(TEST_MSG_LEN_ARR[self.cur_idx.get()]);
@@ -127,6 +196,49 @@ impl MCTPRxClient for MockMctp<'_> {
                 .unwrap();
         }
     }
+
+    fn receive_timeout(&self, peer_eid: u8, msg_type: u8, msg_tag: u8) {
+        if self.stale_assembly_test.get() {
+            // The first packet of the stale message releases the request, so it never times out.
+            println!(
+                "FAILED! Response timeout from EID {} for message tag {} during stale reassembly test",
+                peer_eid, msg_tag
+            );
+            self.test_client.map(|client| {
+                client.test_result(false, TEST_COUNT - 1, TEST_COUNT);
+            });
+            return;
+        }
+
+        if !self.timeout_test.get() {
+            // A slow loopback response is still delivered, so this is not a failure.
+            println!(
+                "Response timeout from EID {} for message tag {} during loopback test",
+                peer_eid, msg_tag
+            );
+            return;
+        }
+
+        let passed = peer_eid == MCTP_TEST_UNRESPONSIVE_EID
+            && msg_type == self.msg_type as u8
+            && msg_tag == self.msg_tag.get();
+        if !passed {
+            println!(
+                "FAILED! Response timeout from EID/expected: {}/{} with message tag/expected: {}/{}",
+                peer_eid,
+                MCTP_TEST_UNRESPONSIVE_EID,
+                msg_tag,
+                self.msg_tag.get()
+            );
+            self.test_client.map(|client| {
+                client.test_result(false, TEST_COUNT - 1, TEST_COUNT);
+            });
+            return;
+        }
+
+        println!("Completed response timeout test");
+        self.run_stale_assembly_test();
+    }
 }
 
 impl MCTPTxClient for MockMctp<'_> {
@@ -139,7 +251,7 @@ impl MCTPTxClient for MockMctp<'_> {
         mut msg_payload: SubSliceMut<'static, u8>,
     ) {
         assert!(result == Ok(()));
-        assert!(dest_eid == MCTP_TEST_REMOTE_EID);
+        assert!(dest_eid == self.dest_eid());
         assert!(msg_type == self.msg_type as u8);
         self.msg_tag.set(msg_tag & MCTP_TAG_MASK);
         msg_payload.reset();
@@ -1,6 +1,6 @@
 // Licensed under the Apache-2.0 license
 
-use caliptra_mcu_capsules_runtime::mctp::base_protocol::MessageType;
+use caliptra_mcu_capsules_runtime::mctp::base_protocol::{MessageType, MCTP_MT2_MIN_MS};
 use caliptra_mcu_capsules_runtime::mctp::driver::MCTP_MAX_MESSAGE_SIZE;
 use caliptra_mcu_capsules_runtime::mctp::mux::MuxMCTPDriver;
 use caliptra_mcu_capsules_runtime::mctp::recv::MCTPRxState;
@@ -81,6 +81,9 @@ impl<A: Alarm<'static>> Component for MockMctpComponent<A> {
         tx_state.set_client(mock_mctp);
         rx_state.set_client(mock_mctp);
         self.mux_mctp.add_receiver(rx_state);
+        // Keep the response and reassembly timeout tests short
+        self.mux_mctp.set_response_timeout_ms(MCTP_MT2_MIN_MS);
+        self.mux_mctp.set_reassembly_timeout_ms(MCTP_MT2_MIN_MS);
 
         mock_mctp
     }
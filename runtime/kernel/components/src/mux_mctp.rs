@@ -12,15 +12,32 @@
 //! use caliptra_mcu_tock_veer::timers::InternalTimers;
 //! let mux_mctp = caliptra_mcu_components::mux_mctp::MCTPMuxComponent::new(
 //!    i3c,
-//!    mux_alarm)
+//!    mux_alarm,
+//!    device_uuid,
+//!    network_id)
 //! .finalize(mctp_mux_component_static!(InternalTimers, MCTPI3CBinding));
 //! ```
 //!
+//! MCTPPcieVdmMuxComponent does the same for MCTP over PCIe VDM, carried through
+//! a DOE mailbox transport.
+//!
+//! ```ignore
+//! let mux_mctp = caliptra_mcu_components::mux_mctp::MCTPPcieVdmMuxComponent::new(
+//!    doe_transport,
+//!    mux_alarm,
+//!    device_uuid,
+//!    network_id)
+//! .finalize(mctp_pcie_vdm_mux_component_static!(InternalTimers, DoeTransportType));
+//! ```
+//!
 
+use caliptra_mcu_capsules_runtime::mctp::control_msg::MCTP_UUID_LEN;
 use caliptra_mcu_capsules_runtime::mctp::mux::MuxMCTPDriver;
+use caliptra_mcu_capsules_runtime::mctp::pcie_vdm::MCTPPcieVdmBinding;
 use caliptra_mcu_capsules_runtime::mctp::transport_binding::{
     MCTPI3CBinding, MCTPTransportBinding,
 };
+use caliptra_mcu_doe_transport::hil::DoeTransport;
 use caliptra_mcu_i3c_driver::core::MAX_READ_WRITE_SIZE;
 use capsules_core::virtualizers::virtual_alarm::{MuxAlarm, VirtualMuxAlarm};
 use core::mem::MaybeUninit;
@@ -53,19 +70,50 @@ macro_rules! mctp_mux_component_static {
     }};
 }
 
+#[macro_export]
+macro_rules! mctp_pcie_vdm_mux_component_static {
+    ($A:ty, $T:ty $(,)?) => {{
+        use caliptra_mcu_capsules_runtime::mctp::mux::MuxMCTPDriver;
+        use caliptra_mcu_capsules_runtime::mctp::pcie_vdm::MCTPPcieVdmBinding;
+        use caliptra_mcu_i3c_driver::core::MAX_READ_WRITE_SIZE;
+        use capsules_core::virtualizers::virtual_alarm::VirtualMuxAlarm;
+
+        let alarm = kernel::static_buf!(VirtualMuxAlarm<'static, $A>);
+        let tx_buffer = kernel::static_buf!([u8; MAX_READ_WRITE_SIZE]);
+        let rx_buffer = kernel::static_buf!([u8; MAX_READ_WRITE_SIZE]);
+        let mctp_pcie_vdm_binding = kernel::static_buf!(MCTPPcieVdmBinding<'static, $T>);
+        let mux_mctp_driver = kernel::static_buf!(
+            MuxMCTPDriver<'static, VirtualMuxAlarm<'static, $A>, MCTPPcieVdmBinding<'static, $T>>
+        );
+        (
+            alarm,
+            tx_buffer,
+            rx_buffer,
+            mctp_pcie_vdm_binding,
+            mux_mctp_driver,
+        )
+    }};
+}
+
 pub struct MCTPMuxComponent<A: Alarm<'static> + 'static> {
     i3c_target: &'static dyn caliptra_mcu_i3c_driver::hil::I3CTarget<'static>,
     mux_alarm: &'static MuxAlarm<'static, A>,
+    endpoint_uuid: [u8; MCTP_UUID_LEN],
+    network_id: [u8; MCTP_UUID_LEN],
 }
 
 impl<A: Alarm<'static>> MCTPMuxComponent<A> {
     pub fn new(
         i3c_target: &'static dyn caliptra_mcu_i3c_driver::hil::I3CTarget,
         mux_alarm: &'static MuxAlarm<'static, A>,
+        endpoint_uuid: [u8; MCTP_UUID_LEN],
+        network_id: [u8; MCTP_UUID_LEN],
     ) -> Self {
         Self {
             i3c_target,
             mux_alarm,
+            endpoint_uuid,
+            network_id,
         }
     }
 }
@@ -101,6 +149,83 @@ impl<A: Alarm<'static>> Component for MCTPMuxComponent<A> {
             mctp_device,
             local_eid,
             mtu,
+            self.endpoint_uuid,
+            self.network_id,
+            tx_pkt_buffer,
+            rx_pkt_buffer,
+            mux_mctp_alarm,
+        ));
+
+        mctp_device.set_tx_client(mux_mctp_driver);
+        mctp_device.set_rx_client(mux_mctp_driver);
+        mux_mctp_alarm.set_alarm_client(mux_mctp_driver);
+
+        mux_mctp_driver.register();
+        mux_mctp_driver
+    }
+}
+
+pub struct MCTPPcieVdmMuxComponent<A: Alarm<'static> + 'static, T: DoeTransport<'static> + 'static>
+{
+    doe_transport: &'static T,
+    mux_alarm: &'static MuxAlarm<'static, A>,
+    endpoint_uuid: [u8; MCTP_UUID_LEN],
+    network_id: [u8; MCTP_UUID_LEN],
+}
+
+impl<A: Alarm<'static>, T: DoeTransport<'static>> MCTPPcieVdmMuxComponent<A, T> {
+    pub fn new(
+        doe_transport: &'static T,
+        mux_alarm: &'static MuxAlarm<'static, A>,
+        endpoint_uuid: [u8; MCTP_UUID_LEN],
+        network_id: [u8; MCTP_UUID_LEN],
+    ) -> Self {
+        Self {
+            doe_transport,
+            mux_alarm,
+            endpoint_uuid,
+            network_id,
+        }
+    }
+}
+
+impl<A: Alarm<'static>, T: DoeTransport<'static>> Component for MCTPPcieVdmMuxComponent<A, T> {
+    type StaticInput = (
+        &'static mut MaybeUninit<VirtualMuxAlarm<'static, A>>,
+        &'static mut MaybeUninit<[u8; MAX_READ_WRITE_SIZE]>,
+        &'static mut MaybeUninit<[u8; MAX_READ_WRITE_SIZE]>,
+        &'static mut MaybeUninit<MCTPPcieVdmBinding<'static, T>>,
+        &'static mut MaybeUninit<
+            MuxMCTPDriver<'static, VirtualMuxAlarm<'static, A>, MCTPPcieVdmBinding<'static, T>>,
+        >,
+    );
+    type Output = &'static MuxMCTPDriver<
+        'static,
+        VirtualMuxAlarm<'static, A>,
+        MCTPPcieVdmBinding<'static, T>,
+    >;
+
+    fn finalize(self, static_buffer: Self::StaticInput) -> Self::Output {
+        let mctp_device = static_buffer
+            .3
+            .write(MCTPPcieVdmBinding::new(self.doe_transport));
+        self.doe_transport.set_tx_client(mctp_device);
+        self.doe_transport.set_rx_client(mctp_device);
+
+        let mtu = mctp_device.get_mtu_size();
+        let tx_pkt_buffer = static_buffer.1.write([0; MAX_READ_WRITE_SIZE]);
+        let rx_pkt_buffer = static_buffer.2.write([0; MAX_READ_WRITE_SIZE]);
+        let local_eid = 0; // could be a default value or 0 until dynamically assigned
+
+        let mux_mctp_alarm = static_buffer.0.write(VirtualMuxAlarm::new(self.mux_alarm));
+        mux_mctp_alarm.setup();
+
+        let mux_mctp_driver = static_buffer.4.write(MuxMCTPDriver::new(
+            mctp_device,
+            local_eid,
+            mtu,
+            self.endpoint_uuid,
+            self.network_id,
             tx_pkt_buffer,
             rx_pkt_buffer,
             mux_mctp_alarm,
@@ -108,6 +233,7 @@ impl<A: Alarm<'static>> Component for MCTPMuxComponent<A> {
 
         mctp_device.set_tx_client(mux_mctp_driver);
         mctp_device.set_rx_client(mux_mctp_driver);
+        mux_mctp_alarm.set_alarm_client(mux_mctp_driver);
 
         mux_mctp_driver.register();
         mux_mctp_driver
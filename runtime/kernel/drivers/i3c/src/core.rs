@@ -5,7 +5,8 @@
 use crate::hil::I3CTargetInfo;
 use crate::hil::{RxClient, TxClient};
 use caliptra_mcu_registers_generated::i3c::bits::{
-    InterruptEnable, InterruptStatus, Status, StbyCrDeviceAddr,
+    InterruptEnable, InterruptStatus, Status, StbyCrDeviceAddr, StbyCrIntrStatus, StbyCrMrl,
+    StbyCrMwl,
 };
 use caliptra_mcu_registers_generated::i3c::regs::I3c;
 use capsules_core::virtualizers::virtual_alarm::{MuxAlarm, VirtualMuxAlarm};
@@ -55,6 +56,9 @@ pub struct I3CCore<'a, A: Alarm<'a>> {
     retry_incoming_write: Cell<bool>,
     pending_ibi: OptionalCell<(u8, u16)>,
     deferred_call: DeferredCall,
+
+    // latched once the controller has issued SETMWL or SETMRL
+    ccc_params_modified: Cell<bool>,
 }
 
 impl<'a, A: Alarm<'a>> I3CCore<'a, A> {
@@ -79,6 +83,7 @@ impl<'a, A: Alarm<'a>> I3CCore<'a, A> {
             retry_incoming_write: Cell::new(false),
             pending_ibi: OptionalCell::empty(),
             deferred_call: DeferredCall::new(),
+            ccc_params_modified: Cell::new(false),
         }
     }
 
@@ -89,6 +94,22 @@ impl<'a, A: Alarm<'a>> I3CCore<'a, A> {
         self.register();
     }
 
+    /// Returns true once the controller has changed the MWL or MRL with a CCC.
+    /// The status bit is write-1-to-clear, so it is latched here.
+    fn ccc_params_modified(&self) -> bool {
+        if self
+            .registers
+            .stdby_ctrl_mode_stby_cr_intr_status
+            .is_set(StbyCrIntrStatus::CccParamModifiedStat)
+        {
+            self.registers
+                .stdby_ctrl_mode_stby_cr_intr_status
+                .write(StbyCrIntrStatus::CccParamModifiedStat::SET);
+            self.ccc_params_modified.set(true);
+        }
+        self.ccc_params_modified.get()
+    }
+
     pub fn enable_interrupts(&self) {
         caliptra_mcu_romtime::println!("[mcu-runtime-i3c] Enabling I3C interrupts");
         self.registers
@@ -342,11 +363,29 @@ impl<'a, A: Alarm<'a>> crate::hil::I3CTarget<'a> for I3CCore<'a, A> {
         } else {
             None
         };
+        let (negotiated_mwl, negotiated_mrl) = if self.ccc_params_modified() {
+            (
+                Some(
+                    self.registers
+                        .stdby_ctrl_mode_stby_cr_mwl
+                        .read(StbyCrMwl::Mwl) as usize,
+                ),
+                Some(
+                    self.registers
+                        .stdby_ctrl_mode_stby_cr_mrl
+                        .read(StbyCrMrl::Mrl) as usize,
+                ),
+            )
+        } else {
+            (None, None)
+        };
         I3CTargetInfo {
             static_addr,
             dynamic_addr,
             max_read_len: MAX_READ_WRITE_SIZE,
             max_write_len: MAX_READ_WRITE_SIZE,
+            negotiated_mwl,
+            negotiated_mrl,
         }
     }
 }
@@ -14,6 +14,12 @@ pub struct I3CTargetInfo {
     pub max_read_len: usize,
     /// Maximum length of data that can be sent in response to a Read command.
     pub max_write_len: usize,
+    /// Maximum write length set by the controller with SETMWL,
+    /// or absent if the controller has not negotiated it.
+    pub negotiated_mwl: Option<usize>,
+    /// Maximum read length set by the controller with SETMRL,
+    /// or absent if the controller has not negotiated it.
+    pub negotiated_mrl: Option<usize>,
 }
 
 pub trait TxClient {
@@ -1,9 +1,11 @@
 // Licensed under the Apache-2.0 license
 
 pub use crate::crypto::asym::AsymAlgo;
+use crate::crypto::asym::{MLDSA87_PUBLIC_KEY_SIZE, MLDSA87_SIGNATURE_SIZE};
 use crate::error::{CaliptraApiError, CaliptraApiResult};
 use crate::mailbox_api::{
-    execute_mailbox_cmd, CertificateChainResp, CertifyKeyRespHdr, DPE_PROFILE,
+    execute_mailbox_cmd, CertificateChainResp, CertifyKeyMldsa87RespHdr, CertifyKeyRespHdr,
+    DPE_MLDSA87_PROFILE, DPE_PROFILE,
 };
 use caliptra_api::mailbox::{
     AttestedCsrResp, CommandId, GetAttestedEccCsrReq, GetAttestedMldsaCsrReq,
@@ -13,11 +15,14 @@ use caliptra_api::mailbox::{
 };
 use caliptra_mcu_libsyscall_caliptra::mailbox::Mailbox;
 use dpe::commands::{
-    CertifyKeyCommand, CertifyKeyFlags, CertifyKeyP384Cmd, Command, CommandHdr,
-    GetCertificateChainCmd, SignCommand, SignFlags, SignP384Cmd,
+    CertifyKeyCommand, CertifyKeyFlags, CertifyKeyMldsa87Cmd, CertifyKeyP384Cmd, Command,
+    CommandHdr, GetCertificateChainCmd, SignCommand, SignFlags, SignMldsa87Cmd, SignP384Cmd,
 };
 use dpe::context::ContextHandle;
-use dpe::response::SignP384Resp;
+use dpe::response::{SignMldsa87Resp, SignP384Resp};
+use dpe::DpeProfile;
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::Shake256;
 use zerocopy::{FromBytes, FromZeros, IntoBytes, TryFromBytes};
 
 pub const IDEV_ECC_CSR_MAX_SIZE: usize = GetIdevCsrResp::DATA_MAX_SIZE;
@@ -25,6 +30,7 @@ pub const MAX_ECC_CERT_SIZE: usize = GetLdevCertResp::DATA_MAX_SIZE;
 pub const MAX_ATTESTED_CSR_SIZE: usize = AttestedCsrResp::DATA_MAX_SIZE;
 pub const MAX_CERT_CHUNK_SIZE: usize = 1024;
 pub const KEY_LABEL_SIZE: usize = DPE_PROFILE.hash_size();
+pub const MLDSA87_MU_SIZE: usize = 64;
 
 pub enum CertType {
     Ecc,
@@ -261,6 +267,123 @@ impl CertContext {
         Ok(sig_r_size + sig_s_size)
     }
 
+    /// Certify the ML-DSA-87 key derived by DPE for the label.
+    ///
+    /// # Arguments
+    /// * `cert` - The buffer to store the certificate in.
+    /// * `label` - The label of the key.
+    /// * `derived_pubkey` - The buffer to store the derived public key in, if requested.
+    ///
+    /// # Returns
+    /// The size of the certificate.
+    pub async fn certify_key_mldsa87(
+        &mut self,
+        cert: &mut [u8],
+        label: Option<&[u8; KEY_LABEL_SIZE]>,
+        derived_pubkey: Option<&mut [u8; MLDSA87_PUBLIC_KEY_SIZE]>,
+    ) -> CaliptraApiResult<usize> {
+        let mut dpe_cmd = CertifyKeyMldsa87Cmd {
+            handle: ContextHandle::default(),
+            flags: CertifyKeyFlags::empty(),
+            format: CertifyKeyCommand::FORMAT_X509,
+            label: [0; KEY_LABEL_SIZE],
+        };
+
+        if let Some(label) = label {
+            dpe_cmd.label[..label.len()].copy_from_slice(label);
+        }
+
+        let mut mbox_resp = InvokeDpeResp::default();
+        self.send_profile_dpe_cmd(
+            DPE_MLDSA87_PROFILE,
+            &mut Command::CertifyKey(CertifyKeyCommand::Mldsa87(&dpe_cmd)),
+            &mut mbox_resp,
+        )
+        .await?;
+
+        let data_size = InvokeDpeResp::DATA_MAX_SIZE.min(mbox_resp.data_size as usize);
+        let data = &mbox_resp.data[..data_size];
+
+        let hdr = CertifyKeyMldsa87RespHdr::try_read_from_bytes(
+            data.get(..size_of::<CertifyKeyMldsa87RespHdr>())
+                .ok_or(CaliptraApiError::InvalidResponse)?,
+        )
+        .map_err(|_| CaliptraApiError::InvalidResponse)?;
+
+        let cert_len = hdr.cert_size as usize;
+        let cert_offset = size_of::<CertifyKeyMldsa87RespHdr>();
+
+        if cert_len > cert.len() {
+            return Err(CaliptraApiError::InvalidResponse);
+        }
+
+        let cert_data = data
+            .get(cert_offset..cert_offset + cert_len)
+            .ok_or(CaliptraApiError::InvalidResponse)?;
+        cert[..cert_len].copy_from_slice(cert_data);
+
+        if let Some(derived_pubkey) = derived_pubkey {
+            derived_pubkey.copy_from_slice(&hdr.derived_pubkey);
+        }
+        Ok(cert_len)
+    }
+
+    /// Sign a message with the ML-DSA-87 key derived by DPE for the label.
+    ///
+    /// The message itself is signed (pure ML-DSA with an empty context). DPE signs the
+    /// external mu of the message, which is computed here from the public key of the key.
+    ///
+    /// # Arguments
+    /// * `key_label` - The label of the key.
+    /// * `public_key` - The public key of the key, as returned by `certify_key_mldsa87`.
+    /// * `message` - The message to sign.
+    /// * `signature` - The buffer to store the signature in.
+    ///
+    /// # Returns
+    /// The size of the signature.
+    pub async fn sign_mldsa87(
+        &mut self,
+        key_label: Option<&[u8; KEY_LABEL_SIZE]>,
+        public_key: &[u8; MLDSA87_PUBLIC_KEY_SIZE],
+        message: &[u8],
+        signature: &mut [u8],
+    ) -> CaliptraApiResult<usize> {
+        if signature.len() < MLDSA87_SIGNATURE_SIZE {
+            return Err(CaliptraApiError::InvalidArgument("Invalid signature size"));
+        }
+
+        let mut dpe_cmd = SignMldsa87Cmd {
+            handle: ContextHandle::default(),
+            label: [0; KEY_LABEL_SIZE],
+            flags: SignFlags::empty(),
+            digest: mldsa87_external_mu(public_key, message),
+        };
+        if let Some(label) = key_label {
+            dpe_cmd.label[..label.len()].copy_from_slice(label);
+        }
+
+        let mut mbox_resp = InvokeDpeResp::default();
+        self.send_profile_dpe_cmd(
+            DPE_MLDSA87_PROFILE,
+            &mut Command::Sign(SignCommand::Mldsa87(&dpe_cmd)),
+            &mut mbox_resp,
+        )
+        .await?;
+
+        let data_size = InvokeDpeResp::DATA_MAX_SIZE.min(mbox_resp.data_size as usize);
+        let data = &mbox_resp.data[..data_size];
+
+        let sign_resp = SignMldsa87Resp::try_read_from_bytes(
+            data.get(..size_of::<SignMldsa87Resp>())
+                .ok_or(CaliptraApiError::InvalidResponse)?,
+        )
+        .map_err(|_| CaliptraApiError::InvalidResponse)?;
+
+        signature[..MLDSA87_SIGNATURE_SIZE]
+            .copy_from_slice(&sign_resp.sig[..MLDSA87_SIGNATURE_SIZE]);
+        Ok(MLDSA87_SIGNATURE_SIZE)
+    }
+
     pub fn max_cert_chain_chunk_size(&mut self) -> usize {
         MAX_CERT_CHUNK_SIZE
     }
@@ -269,6 +392,26 @@ impl CertContext {
         &mut self,
         offset: usize,
         cert_chunk: &mut [u8],
+    ) -> CaliptraApiResult<usize> {
+        self.profile_cert_chain_chunk(DPE_PROFILE, offset, cert_chunk)
+            .await
+    }
+
+    /// Read a chunk of the ML-DSA-87 DPE certificate chain.
+    pub async fn mldsa87_cert_chain_chunk(
+        &mut self,
+        offset: usize,
+        cert_chunk: &mut [u8],
+    ) -> CaliptraApiResult<usize> {
+        self.profile_cert_chain_chunk(DPE_MLDSA87_PROFILE, offset, cert_chunk)
+            .await
+    }
+
+    async fn profile_cert_chain_chunk(
+        &mut self,
+        profile: DpeProfile,
+        offset: usize,
+        cert_chunk: &mut [u8],
     ) -> CaliptraApiResult<usize> {
         let size = cert_chunk.len();
         if size > MAX_CERT_CHUNK_SIZE {
@@ -281,8 +424,12 @@ impl CertContext {
         };
 
         let mut mbox_resp = InvokeDpeResp::default();
-        self.send_dpe_cmd(&mut Command::GetCertificateChain(&dpe_cmd), &mut mbox_resp)
-            .await?;
+        self.send_profile_dpe_cmd(
+            profile,
+            &mut Command::GetCertificateChain(&dpe_cmd),
+            &mut mbox_resp,
+        )
+        .await?;
 
         let data_size = InvokeDpeResp::DATA_MAX_SIZE.min(mbox_resp.data_size as usize);
         let data = &mbox_resp.data[..data_size];
@@ -351,11 +498,21 @@ impl CertContext {
         &mut self,
         dpe_cmd: &mut Command<'_>,
         mbox_resp: &mut InvokeDpeResp,
+    ) -> CaliptraApiResult<()> {
+        self.send_profile_dpe_cmd(DPE_PROFILE, dpe_cmd, mbox_resp)
+            .await
+    }
+
+    async fn send_profile_dpe_cmd(
+        &mut self,
+        profile: DpeProfile,
+        dpe_cmd: &mut Command<'_>,
+        mbox_resp: &mut InvokeDpeResp,
     ) -> CaliptraApiResult<()> {
         let mut mbox_req = InvokeDpeReq::new_zeroed();
 
         let (dpe_cmd_id, dpe_cmd_bytes) = Self::dpe_cmd_info(dpe_cmd);
-        let cmd_hdr = CommandHdr::new(DPE_PROFILE, dpe_cmd_id);
+        let cmd_hdr = CommandHdr::new(profile, dpe_cmd_id);
 
         let cmd_hdr_bytes = cmd_hdr.as_bytes();
         mbox_req.data[..cmd_hdr_bytes.len()].copy_from_slice(cmd_hdr_bytes);
@@ -364,9 +521,15 @@ impl CertContext {
             .copy_from_slice(dpe_cmd_bytes);
         mbox_req.data_size = (cmd_hdr_bytes.len() + dpe_cmd_bytes.len()) as u32;
 
+        // Each DPE profile is served by its own DPE instance
+        let mbox_cmd = if profile == DPE_MLDSA87_PROFILE {
+            CommandId::INVOKE_DPE_MLDSA87.0
+        } else {
+            InvokeDpeReq::ID.0
+        };
         execute_mailbox_cmd(
             &self.mbox,
-            InvokeDpeReq::ID.0,
+            mbox_cmd,
             mbox_req.as_mut_bytes(),
             mbox_resp.as_mut_bytes(),
         )
@@ -388,3 +551,23 @@ impl CertContext {
         }
     }
 }
+
+/// Compute the ML-DSA-87 external mu of a message signed with an empty context (FIPS 204):
+/// mu = SHAKE256(tr || 0 || 0 || message) with tr = SHAKE256(public_key).
+fn mldsa87_external_mu(
+    public_key: &[u8; MLDSA87_PUBLIC_KEY_SIZE],
+    message: &[u8],
+) -> [u8; MLDSA87_MU_SIZE] {
+    let mut tr = [0u8; 64];
+    let mut hasher = Shake256::default();
+    hasher.update(public_key);
+    hasher.finalize_xof().read(&mut tr);
+
+    let mut mu = [0u8; MLDSA87_MU_SIZE];
+    let mut hasher = Shake256::default();
+    hasher.update(&tr);
+    hasher.update(&[0, 0]);
+    hasher.update(message);
+    hasher.finalize_xof().read(&mut mu);
+    mu
+}
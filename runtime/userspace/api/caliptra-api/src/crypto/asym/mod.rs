@@ -6,6 +6,7 @@ pub const ECC_P384_SIGNATURE_SIZE: usize = 96;
 pub const ECC_P384_PARAM_X_SIZE: usize = 48;
 pub const ECC_P384_PARAM_Y_SIZE: usize = 48;
 pub const MLDSA87_SIGNATURE_SIZE: usize = 4627;
+pub const MLDSA87_PUBLIC_KEY_SIZE: usize = 2592;
 
 pub enum KeyExchScheme {
     Ecdh,
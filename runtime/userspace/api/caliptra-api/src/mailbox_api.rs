@@ -21,6 +21,7 @@
 //! - `ShaFinalReq`: Represents a request to finalize a SHA operation. Equivalent to `CmShaFinalReq`.
 //! - `CertifyKeyRespHdr`: Header portion of a CertifyKey DPE response (without the variable-length
 //!   cert data). Used for in-place parsing from `DpeResp.data` to avoid copying the cert buffer.
+//! - `CertifyKeyMldsa87RespHdr`: Header portion of an ML-DSA-87 CertifyKey DPE response.
 //! - `CertificateChainResp`: Represents a response containing a chunk of a certificate chain. Equivalent to `GetCertificateChainResp`.
 //! - `RandomStirReq`: Represents a request to stir the random number generator. Equivalent to `CmRandomStirReq`.
 //! - `RandomGenerateResp`: Represents a response for generating random numbers. Equivalent to `CmRandomGenerateResp`.
@@ -29,6 +30,7 @@
 //! These structures and constants are intended for use in the Caliptra subsystem's mailbox
 //! API, particularly for cryptographic and DPE-related operations.
 
+use crate::crypto::asym::MLDSA87_PUBLIC_KEY_SIZE;
 use crate::error::CaliptraApiError;
 use crate::error::CaliptraApiResult;
 use caliptra_api::mailbox::CmRandomGenerateResp;
@@ -48,6 +50,10 @@ use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
 /// selects the P-384 profile.
 pub(crate) const DPE_PROFILE: DpeProfile = DpeProfile::P384Sha384;
 
+/// DPE profile of the ML-DSA-87 DPE instance, selected by the `ml-dsa` feature on the
+/// `dpe` crate. Its keys sign the external mu of the message.
+pub(crate) const DPE_MLDSA87_PROFILE: DpeProfile = DpeProfile::Mldsa87;
+
 pub const MAX_CRYPTO_MBOX_DATA_SIZE: usize = 1024;
 pub const MAX_CERT_CHUNK_SIZE: usize = 1024;
 pub const MAX_RANDOM_STIR_SIZE: usize = 48;
@@ -136,6 +142,24 @@ pub(crate) struct CertifyKeyRespHdr {
     pub cert_size: u32,
 }
 
+/// Header portion of an ML-DSA-87 CertifyKey DPE response, see `CertifyKeyRespHdr`.
+#[repr(C)]
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    zerocopy::IntoBytes,
+    zerocopy::TryFromBytes,
+    zerocopy::Immutable,
+    zerocopy::KnownLayout,
+)]
+pub(crate) struct CertifyKeyMldsa87RespHdr {
+    pub resp_hdr: ResponseHdr,
+    pub new_context_handle: ContextHandle,
+    pub derived_pubkey: [u8; MLDSA87_PUBLIC_KEY_SIZE],
+    pub cert_size: u32,
+}
+
 #[repr(C)]
 #[derive(
     Debug,
@@ -12,6 +12,13 @@ use zerocopy::{Immutable, IntoBytes};
 pub use caliptra_api::mailbox::MAX_ATTESTED_CSR_RESP_DATA_SIZE as MAX_ATTESTED_CSR_DATA_LEN;
 pub const MAX_FW_VERSION_LEN: usize = 32;
 pub const MAX_UID_LEN: usize = 32;
+pub const MAX_LOG_DATA_LEN: usize = 1024;
+pub const MAX_ATTESTATION_DATA_LEN: usize = 2048;
+pub const MAX_IDEVID_CSR_DATA_LEN: usize = 7680;
+pub const MAX_DOT_RESP_DATA_LEN: usize = 256;
+pub const FIELD_ENTROPY_LEN: usize = 32;
+pub const DEBUG_UNLOCK_UDI_LEN: usize = 32;
+pub const DEBUG_UNLOCK_CHALLENGE_LEN: usize = 48;
 
 /// Common error type for unified commands.
 #[derive(Debug)]
@@ -38,6 +45,98 @@ impl Default for AttestedCsrData {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogData {
+    pub len: usize,
+    pub data: [u8; MAX_LOG_DATA_LEN],
+}
+
+impl Default for LogData {
+    fn default() -> Self {
+        Self {
+            len: 0,
+            data: [0u8; MAX_LOG_DATA_LEN],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogType {
+    Debug,
+    Attestation,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttestationData {
+    pub len: usize,
+    pub data: [u8; MAX_ATTESTATION_DATA_LEN],
+}
+
+impl Default for AttestationData {
+    fn default() -> Self {
+        Self {
+            len: 0,
+            data: [0u8; MAX_ATTESTATION_DATA_LEN],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdevidCsrData {
+    pub len: usize,
+    pub data: [u8; MAX_IDEVID_CSR_DATA_LEN],
+}
+
+impl Default for IdevidCsrData {
+    fn default() -> Self {
+        Self {
+            len: 0,
+            data: [0u8; MAX_IDEVID_CSR_DATA_LEN],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotRespData {
+    pub len: usize,
+    pub data: [u8; MAX_DOT_RESP_DATA_LEN],
+}
+
+impl Default for DotRespData {
+    fn default() -> Self {
+        Self {
+            len: 0,
+            data: [0u8; MAX_DOT_RESP_DATA_LEN],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugUnlockChallenge {
+    pub unique_device_identifier: [u8; DEBUG_UNLOCK_UDI_LEN],
+    pub challenge: [u8; DEBUG_UNLOCK_CHALLENGE_LEN],
+}
+
+impl Default for DebugUnlockChallenge {
+    fn default() -> Self {
+        Self {
+            unique_device_identifier: [0u8; DEBUG_UNLOCK_UDI_LEN],
+            challenge: [0u8; DEBUG_UNLOCK_CHALLENGE_LEN],
+        }
+    }
+}
+
+/// Provisioning state of a certificate slot, per the SPDM slot provisioning model.
+#[repr(u32)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CertSlotState {
+    #[default]
+    NotExist = 0,
+    Empty = 1,
+    KeyOnly = 2,
+    KeyAndCert = 3,
+}
+
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct FirmwareVersion {
     pub len: usize,
@@ -142,6 +241,126 @@ pub trait UnifiedCommandHandler {
         algorithm: u32,
         csr_data: &mut AttestedCsrData,
     ) -> Result<(), CommandError>;
+
+    /// Retrieves the contents of the given log.
+    ///
+    /// # Arguments
+    /// * `log_type` - The log to retrieve.
+    /// * `log` - Mutable reference to store the log data.
+    ///
+    /// # Returns
+    /// * `Result<(), CommandError>` - Ok on success, or an error.
+    async fn get_log(&self, log_type: LogType, log: &mut LogData) -> Result<(), CommandError>;
+
+    /// Clears the given log. Clearing the attestation log requires authorization.
+    ///
+    /// # Arguments
+    /// * `log_type` - The log to clear.
+    ///
+    /// # Returns
+    /// * `Result<(), CommandError>` - Ok on success, or an error.
+    async fn clear_log(&self, log_type: LogType) -> Result<(), CommandError>;
+
+    /// Retrieves attestation evidence in the requested format.
+    ///
+    /// # Arguments
+    /// * `format` - The evidence format (0x0000=OCP EAT, 0x0001=signed PCR quote).
+    /// * `nonce` - Freshness nonce to include in the evidence.
+    /// * `evidence` - Mutable reference to store the attestation evidence.
+    ///
+    /// # Returns
+    /// * `Result<(), CommandError>` - Ok on success, or an error.
+    async fn get_attestation(
+        &self,
+        format: u32,
+        nonce: &[u8],
+        evidence: &mut AttestationData,
+    ) -> Result<(), CommandError>;
+
+    /// Requests a production debug unlock challenge.
+    ///
+    /// # Arguments
+    /// * `unlock_level` - The debug unlock level (1-8).
+    /// * `challenge` - Mutable reference to store the device identifier and challenge.
+    ///
+    /// # Returns
+    /// * `Result<(), CommandError>` - Ok on success, or an error.
+    async fn request_debug_unlock(
+        &self,
+        unlock_level: u8,
+        challenge: &mut DebugUnlockChallenge,
+    ) -> Result<(), CommandError>;
+
+    /// Authorizes a production debug unlock token.
+    ///
+    /// # Arguments
+    /// * `token` - The token, laid out as the `MC_PRODUCTION_DEBUG_UNLOCK_TOKEN` input
+    ///   arguments starting at `length`.
+    ///
+    /// # Returns
+    /// * `Result<(), CommandError>` - Ok on success, or an error.
+    async fn authorize_debug_unlock_token(&self, token: &[u8]) -> Result<(), CommandError>;
+
+    /// Exports the IDevID CSR. Only available in the Manufacturing lifecycle state.
+    ///
+    /// # Arguments
+    /// * `index` - The CSR index (0x00=ECC P-384, 0x01=ML-DSA).
+    /// * `csr_data` - Mutable reference to store the DER-encoded CSR.
+    ///
+    /// # Returns
+    /// * `Result<(), CommandError>` - Ok on success, or an error.
+    async fn export_idevid_csr(
+        &self,
+        index: u32,
+        csr_data: &mut IdevidCsrData,
+    ) -> Result<(), CommandError>;
+
+    /// Sets the CA-signed IDevID certificate in certificate slot 0.
+    ///
+    /// # Arguments
+    /// * `cert` - The DER-encoded certificate.
+    ///
+    /// # Returns
+    /// * `Result<(), CommandError>` - Ok on success, or an error.
+    async fn set_slot0_cert(&self, cert: &[u8]) -> Result<(), CommandError>;
+
+    /// Retrieves the provisioning state of certificate slot 0.
+    ///
+    /// # Arguments
+    /// * `state` - Mutable reference to store the slot state.
+    ///
+    /// # Returns
+    /// * `Result<(), CommandError>` - Ok on success, or an error.
+    async fn get_slot0_state(&self, state: &mut CertSlotState) -> Result<(), CommandError>;
+
+    /// Programs field entropy into the device. Implementors must authorize the request.
+    ///
+    /// # Arguments
+    /// * `entropy` - The field entropy to program.
+    ///
+    /// # Returns
+    /// * `Result<(), CommandError>` - Ok on success, or an error.
+    async fn program_field_entropy(
+        &self,
+        entropy: &[u8; FIELD_ENTROPY_LEN],
+    ) -> Result<(), CommandError>;
+
+    /// Executes a Device Ownership Transfer subcommand. Implementors must authenticate
+    /// the subcommand data.
+    ///
+    /// # Arguments
+    /// * `subcommand` - The DOT subcommand.
+    /// * `data` - The subcommand data.
+    /// * `resp` - Mutable reference to store the subcommand response data.
+    ///
+    /// # Returns
+    /// * `Result<(), CommandError>` - Ok on success, or an error.
+    async fn device_ownership_transfer(
+        &self,
+        subcommand: u32,
+        data: &[u8],
+        resp: &mut DotRespData,
+    ) -> Result<(), CommandError>;
 }
 
 pub struct AuthorizationError;
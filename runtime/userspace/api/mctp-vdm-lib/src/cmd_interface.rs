@@ -3,14 +3,23 @@
 use crate::error::VdmLibError;
 use crate::transport::MctpVdmTransport;
 use caliptra_mcu_external_cmds_common::{
-    AttestedCsrData, CommandError, DeviceCapabilities, DeviceId, DeviceInfo, FirmwareVersion, Uid,
-    UnifiedCommandHandler, MAX_ATTESTED_CSR_DATA_LEN, MAX_UID_LEN,
+    AttestationData, AttestedCsrData, CertSlotState, CommandError, DebugUnlockChallenge,
+    DeviceCapabilities, DeviceId, DeviceInfo, DotRespData, FirmwareVersion, IdevidCsrData, LogData,
+    LogType, Uid, UnifiedCommandHandler, MAX_ATTESTATION_DATA_LEN, MAX_ATTESTED_CSR_DATA_LEN,
+    MAX_DOT_RESP_DATA_LEN, MAX_IDEVID_CSR_DATA_LEN, MAX_LOG_DATA_LEN, MAX_UID_LEN,
 };
 use caliptra_mcu_mctp_vdm_common::codec::VdmCodec;
 use caliptra_mcu_mctp_vdm_common::message::{
-    AsymAlgorithm, DeviceCapabilitiesResponse, DeviceIdResponse, DeviceInfoRequest,
-    DeviceInfoResponse, ExportAttestedCsrRequest, ExportAttestedCsrResponse,
-    FirmwareVersionRequest, FirmwareVersionResponse, DEVICE_CAPS_SIZE,
+    AsymAlgorithm, AuthorizeDebugUnlockTokenRequest, AuthorizeDebugUnlockTokenResponse,
+    ClearAttestationLogResponse, ClearDebugLogResponse, DeviceCapabilitiesResponse,
+    DeviceIdResponse, DeviceInfoRequest, DeviceInfoResponse, DeviceOwnershipTransferRequestHeader,
+    DeviceOwnershipTransferResponse, ExportAttestedCsrRequest, ExportAttestedCsrResponse,
+    ExportIdevidCsrRequest, ExportIdevidCsrResponse, FirmwareVersionRequest,
+    FirmwareVersionResponse, GetAttestationLogResponse, GetAttestationRequest,
+    GetAttestationResponse, GetDebugLogResponse, GetSlot0StateResponse, ProgramFieldEntropyRequest,
+    ProgramFieldEntropyResponse, RequestDebugUnlockRequest, RequestDebugUnlockResponse,
+    SetSlot0CertRequestHeader, SetSlot0CertResponse, DEVICE_CAPS_SIZE, MAX_DOT_REQ_DATA_SIZE,
+    MAX_SLOT0_CERT_SIZE,
 };
 use caliptra_mcu_mctp_vdm_common::protocol::{
     VdmCommand, VdmCompletionCode, VdmFailureResponse, VdmMsgHeader, VDM_MSG_HEADER_LEN,
@@ -120,14 +129,39 @@ impl<'a> CmdInterface<'a> {
             }
             VdmCommand::DeviceId => self.handle_device_id(msg_buf, vdm_req_len).await,
             VdmCommand::DeviceInfo => self.handle_device_info(msg_buf, vdm_req_len).await,
+            VdmCommand::GetDebugLog => self.handle_get_debug_log(msg_buf, vdm_req_len).await,
+            VdmCommand::ClearDebugLog => self.handle_clear_debug_log(msg_buf, vdm_req_len).await,
+            VdmCommand::GetAttestationLog => {
+                self.handle_get_attestation_log(msg_buf, vdm_req_len).await
+            }
+            VdmCommand::ClearAttestationLog => {
+                self.handle_clear_attestation_log(msg_buf, vdm_req_len)
+                    .await
+            }
+            VdmCommand::GetAttestation => self.handle_get_attestation(msg_buf, vdm_req_len).await,
+            VdmCommand::RequestDebugUnlock => {
+                self.handle_request_debug_unlock(msg_buf, vdm_req_len).await
+            }
+            VdmCommand::AuthorizeDebugUnlockToken => {
+                self.handle_authorize_debug_unlock_token(msg_buf, vdm_req_len)
+                    .await
+            }
+            VdmCommand::ExportIdevidCsr => {
+                self.handle_export_idevid_csr(msg_buf, vdm_req_len).await
+            }
+            VdmCommand::SetSlot0Cert => self.handle_set_slot0_cert(msg_buf, vdm_req_len).await,
+            VdmCommand::GetSlot0State => self.handle_get_slot0_state(msg_buf, vdm_req_len).await,
             VdmCommand::ExportAttestedCsr => {
                 self.handle_export_attested_csr(msg_buf, vdm_req_len).await
             }
-            _ => self.send_error_response(
-                msg_buf,
-                hdr.command_code,
-                VdmCompletionCode::UnsupportedCommand,
-            ),
+            VdmCommand::ProgramFieldEntropy => {
+                self.handle_program_field_entropy(msg_buf, vdm_req_len)
+                    .await
+            }
+            VdmCommand::DeviceOwnershipTransfer => {
+                self.handle_device_ownership_transfer(msg_buf, vdm_req_len)
+                    .await
+            }
         }
     }
 
@@ -348,17 +382,12 @@ impl<'a> CmdInterface<'a> {
         let (completion_code, data) = match result {
             Ok(()) => {
                 let len = csr_data.len.min(MAX_ATTESTED_CSR_DATA_LEN);
-                (VdmCompletionCode::Success, csr_data.data[..len].to_vec())
+                (VdmCompletionCode::Success, &csr_data.data[..len])
             }
-            Err(CommandError::InvalidParams) => (VdmCompletionCode::InvalidData, alloc::vec![]),
-            Err(CommandError::NotSupported) => {
-                (VdmCompletionCode::UnsupportedCommand, alloc::vec![])
-            }
-            Err(CommandError::Busy) => (VdmCompletionCode::NotReady, alloc::vec![]),
-            Err(_) => (VdmCompletionCode::GeneralError, alloc::vec![]),
+            Err(e) => (completion_code_for(&e), &[][..]),
         };
 
-        let resp = ExportAttestedCsrResponse::new(completion_code as u32, &data);
+        let resp = ExportAttestedCsrResponse::new(completion_code as u32, data);
 
         // Encode the response into the MCTP payload.
         self.encode_export_attested_csr_response(msg_buf, &resp)
@@ -381,4 +410,335 @@ impl<'a> CmdInterface<'a> {
         // Return total MCTP payload length (1 byte MCTP header + VDM response).
         Ok(VDM_MSG_OFFSET + resp_len)
     }
+
+    /// Handle Get Debug Log command.
+    async fn handle_get_debug_log(
+        &self,
+        msg_buf: &mut [u8],
+        _req_len: usize,
+    ) -> Result<usize, VdmLibError> {
+        let mut log = LogData::default();
+        let result = self.unified_handler.get_log(LogType::Debug, &mut log).await;
+
+        let (completion_code, data) = match result {
+            Ok(()) => (
+                VdmCompletionCode::Success,
+                &log.data[..log.len.min(MAX_LOG_DATA_LEN)],
+            ),
+            Err(e) => (completion_code_for(&e), &[][..]),
+        };
+
+        let resp = GetDebugLogResponse::new(completion_code as u32, data);
+        self.encode_response(msg_buf, &resp)
+    }
+
+    /// Handle Clear Debug Log command.
+    async fn handle_clear_debug_log(
+        &self,
+        msg_buf: &mut [u8],
+        _req_len: usize,
+    ) -> Result<usize, VdmLibError> {
+        let completion_code = match self.unified_handler.clear_log(LogType::Debug).await {
+            Ok(()) => VdmCompletionCode::Success,
+            Err(e) => completion_code_for(&e),
+        };
+
+        let resp = ClearDebugLogResponse::new(completion_code as u32);
+        self.encode_response(msg_buf, &resp)
+    }
+
+    /// Handle Get Attestation Log command.
+    async fn handle_get_attestation_log(
+        &self,
+        msg_buf: &mut [u8],
+        _req_len: usize,
+    ) -> Result<usize, VdmLibError> {
+        let mut log = LogData::default();
+        let result = self
+            .unified_handler
+            .get_log(LogType::Attestation, &mut log)
+            .await;
+
+        let (completion_code, data) = match result {
+            Ok(()) => (
+                VdmCompletionCode::Success,
+                &log.data[..log.len.min(MAX_LOG_DATA_LEN)],
+            ),
+            Err(e) => (completion_code_for(&e), &[][..]),
+        };
+
+        let resp = GetAttestationLogResponse::new(completion_code as u32, data);
+        self.encode_response(msg_buf, &resp)
+    }
+
+    /// Handle Clear Attestation Log command.
+    async fn handle_clear_attestation_log(
+        &self,
+        msg_buf: &mut [u8],
+        _req_len: usize,
+    ) -> Result<usize, VdmLibError> {
+        let completion_code = match self.unified_handler.clear_log(LogType::Attestation).await {
+            Ok(()) => VdmCompletionCode::Success,
+            Err(e) => completion_code_for(&e),
+        };
+
+        let resp = ClearAttestationLogResponse::new(completion_code as u32);
+        self.encode_response(msg_buf, &resp)
+    }
+
+    /// Handle Get Attestation command.
+    async fn handle_get_attestation(
+        &self,
+        msg_buf: &mut [u8],
+        req_len: usize,
+    ) -> Result<usize, VdmLibError> {
+        // Extract VDM message portion.
+        let vdm_msg = extract_vdm_msg(msg_buf).map_err(|_| VdmLibError::DecodingError)?;
+
+        // Decode the request.
+        let req = GetAttestationRequest::decode(&vdm_msg[..req_len])
+            .map_err(|_| VdmLibError::DecodingError)?;
+
+        let format = req.format;
+        let nonce = req.nonce;
+        let mut evidence = AttestationData::default();
+        let result = self
+            .unified_handler
+            .get_attestation(format, &nonce, &mut evidence)
+            .await;
+
+        let (completion_code, data) = match result {
+            Ok(()) => (
+                VdmCompletionCode::Success,
+                &evidence.data[..evidence.len.min(MAX_ATTESTATION_DATA_LEN)],
+            ),
+            Err(e) => (completion_code_for(&e), &[][..]),
+        };
+
+        let resp = GetAttestationResponse::new(completion_code as u32, data);
+        self.encode_response(msg_buf, &resp)
+    }
+
+    /// Handle Request Debug Unlock command.
+    async fn handle_request_debug_unlock(
+        &self,
+        msg_buf: &mut [u8],
+        req_len: usize,
+    ) -> Result<usize, VdmLibError> {
+        // Extract VDM message portion.
+        let vdm_msg = extract_vdm_msg(msg_buf).map_err(|_| VdmLibError::DecodingError)?;
+
+        // Decode the request.
+        let req = RequestDebugUnlockRequest::decode(&vdm_msg[..req_len])
+            .map_err(|_| VdmLibError::DecodingError)?;
+
+        let mut challenge = DebugUnlockChallenge::default();
+        let result = self
+            .unified_handler
+            .request_debug_unlock(req.unlock_level, &mut challenge)
+            .await;
+
+        let resp = match result {
+            Ok(()) => RequestDebugUnlockResponse::new(
+                VdmCompletionCode::Success as u32,
+                &challenge.unique_device_identifier,
+                &challenge.challenge,
+            ),
+            Err(e) => RequestDebugUnlockResponse {
+                completion_code: completion_code_for(&e) as u32,
+                ..Default::default()
+            },
+        };
+
+        self.encode_response(msg_buf, &resp)
+    }
+
+    /// Handle Authorize Debug Unlock Token command.
+    async fn handle_authorize_debug_unlock_token(
+        &self,
+        msg_buf: &mut [u8],
+        req_len: usize,
+    ) -> Result<usize, VdmLibError> {
+        // Extract VDM message portion.
+        let vdm_msg = extract_vdm_msg(msg_buf).map_err(|_| VdmLibError::DecodingError)?;
+
+        // The token is forwarded as-is, so only check that it is complete.
+        if req_len < core::mem::size_of::<AuthorizeDebugUnlockTokenRequest>() {
+            let resp =
+                AuthorizeDebugUnlockTokenResponse::new(VdmCompletionCode::InvalidLength as u32);
+            return self.encode_response(msg_buf, &resp);
+        }
+        let token =
+            &vdm_msg[VDM_MSG_HEADER_LEN..core::mem::size_of::<AuthorizeDebugUnlockTokenRequest>()];
+
+        let completion_code = match self
+            .unified_handler
+            .authorize_debug_unlock_token(token)
+            .await
+        {
+            Ok(()) => VdmCompletionCode::Success,
+            Err(e) => completion_code_for(&e),
+        };
+
+        let resp = AuthorizeDebugUnlockTokenResponse::new(completion_code as u32);
+        self.encode_response(msg_buf, &resp)
+    }
+
+    /// Handle Export IDevID CSR command.
+    async fn handle_export_idevid_csr(
+        &self,
+        msg_buf: &mut [u8],
+        req_len: usize,
+    ) -> Result<usize, VdmLibError> {
+        // Extract VDM message portion.
+        let vdm_msg = extract_vdm_msg(msg_buf).map_err(|_| VdmLibError::DecodingError)?;
+
+        // Decode the request.
+        let req = ExportIdevidCsrRequest::decode(&vdm_msg[..req_len])
+            .map_err(|_| VdmLibError::DecodingError)?;
+
+        let mut csr_data = IdevidCsrData::default();
+        let result = self
+            .unified_handler
+            .export_idevid_csr(req.index, &mut csr_data)
+            .await;
+
+        let (completion_code, data) = match result {
+            Ok(()) => (
+                VdmCompletionCode::Success,
+                &csr_data.data[..csr_data.len.min(MAX_IDEVID_CSR_DATA_LEN)],
+            ),
+            Err(e) => (completion_code_for(&e), &[][..]),
+        };
+
+        let resp = ExportIdevidCsrResponse::new(completion_code as u32, data);
+        self.encode_response(msg_buf, &resp)
+    }
+
+    /// Handle Set Slot 0 Cert command.
+    async fn handle_set_slot0_cert(
+        &self,
+        msg_buf: &mut [u8],
+        req_len: usize,
+    ) -> Result<usize, VdmLibError> {
+        // Extract VDM message portion.
+        let vdm_msg = extract_vdm_msg(msg_buf).map_err(|_| VdmLibError::DecodingError)?;
+
+        // Decode only the fixed header; the certificate is passed to the handler in place.
+        let header = SetSlot0CertRequestHeader::decode(&vdm_msg[..req_len])
+            .map_err(|_| VdmLibError::DecodingError)?;
+        let header_size = core::mem::size_of::<SetSlot0CertRequestHeader>();
+        let cert_size = header.cert_size as usize;
+
+        let completion_code = if cert_size == 0
+            || cert_size > MAX_SLOT0_CERT_SIZE
+            || header_size + cert_size > req_len
+        {
+            VdmCompletionCode::InvalidLength
+        } else {
+            let cert = &vdm_msg[header_size..header_size + cert_size];
+            match self.unified_handler.set_slot0_cert(cert).await {
+                Ok(()) => VdmCompletionCode::Success,
+                Err(e) => completion_code_for(&e),
+            }
+        };
+
+        let resp = SetSlot0CertResponse::new(completion_code as u32);
+        self.encode_response(msg_buf, &resp)
+    }
+
+    /// Handle Get Slot 0 State command.
+    async fn handle_get_slot0_state(
+        &self,
+        msg_buf: &mut [u8],
+        _req_len: usize,
+    ) -> Result<usize, VdmLibError> {
+        let mut state = CertSlotState::default();
+        let result = self.unified_handler.get_slot0_state(&mut state).await;
+
+        let resp = match result {
+            Ok(()) => GetSlot0StateResponse::new(VdmCompletionCode::Success as u32, state as u32),
+            Err(e) => GetSlot0StateResponse::new(
+                completion_code_for(&e) as u32,
+                CertSlotState::NotExist as u32,
+            ),
+        };
+
+        self.encode_response(msg_buf, &resp)
+    }
+
+    /// Handle Program Field Entropy command.
+    async fn handle_program_field_entropy(
+        &self,
+        msg_buf: &mut [u8],
+        req_len: usize,
+    ) -> Result<usize, VdmLibError> {
+        // Extract VDM message portion.
+        let vdm_msg = extract_vdm_msg(msg_buf).map_err(|_| VdmLibError::DecodingError)?;
+
+        // Decode the request.
+        let req = ProgramFieldEntropyRequest::decode(&vdm_msg[..req_len])
+            .map_err(|_| VdmLibError::DecodingError)?;
+
+        let completion_code = match self
+            .unified_handler
+            .program_field_entropy(&req.entropy)
+            .await
+        {
+            Ok(()) => VdmCompletionCode::Success,
+            Err(e) => completion_code_for(&e),
+        };
+
+        let resp = ProgramFieldEntropyResponse::new(completion_code as u32);
+        self.encode_response(msg_buf, &resp)
+    }
+
+    /// Handle Device Ownership Transfer command.
+    async fn handle_device_ownership_transfer(
+        &self,
+        msg_buf: &mut [u8],
+        req_len: usize,
+    ) -> Result<usize, VdmLibError> {
+        // Extract VDM message portion.
+        let vdm_msg = extract_vdm_msg(msg_buf).map_err(|_| VdmLibError::DecodingError)?;
+
+        // Decode only the fixed header; the subcommand data is passed to the handler in place.
+        let header = DeviceOwnershipTransferRequestHeader::decode(&vdm_msg[..req_len])
+            .map_err(|_| VdmLibError::DecodingError)?;
+        let header_size = core::mem::size_of::<DeviceOwnershipTransferRequestHeader>();
+        let data_size = header.data_size as usize;
+
+        let mut dot_resp = DotRespData::default();
+        let completion_code =
+            if data_size > MAX_DOT_REQ_DATA_SIZE || header_size + data_size > req_len {
+                VdmCompletionCode::InvalidLength
+            } else {
+                let data = &vdm_msg[header_size..header_size + data_size];
+                match self
+                    .unified_handler
+                    .device_ownership_transfer(header.subcommand, data, &mut dot_resp)
+                    .await
+                {
+                    Ok(()) => VdmCompletionCode::Success,
+                    Err(e) => completion_code_for(&e),
+                }
+            };
+
+        let data = match completion_code {
+            VdmCompletionCode::Success => &dot_resp.data[..dot_resp.len.min(MAX_DOT_RESP_DATA_LEN)],
+            _ => &[][..],
+        };
+        let resp = DeviceOwnershipTransferResponse::new(completion_code as u32, data);
+        self.encode_response(msg_buf, &resp)
+    }
+}
+
+/// Map a unified command handler error to a VDM completion code.
+fn completion_code_for(err: &CommandError) -> VdmCompletionCode {
+    match err {
+        CommandError::InvalidParams => VdmCompletionCode::InvalidData,
+        CommandError::NotSupported => VdmCompletionCode::UnsupportedCommand,
+        CommandError::Busy => VdmCompletionCode::NotReady,
+        _ => VdmCompletionCode::GeneralError,
+    }
 }
@@ -7,8 +7,9 @@ use core::fmt::Write;
 use core::sync::atomic::{AtomicBool, Ordering};
 use embassy_executor::Spawner;
 
-/// Maximum size of VDM message buffer (implementation-defined limit).
-pub const MAX_VDM_MSG_SIZE: usize = 1024;
+/// Maximum size of VDM message buffer (implementation-defined limit). Sized for the
+/// largest message, the Authorize Debug Unlock Token request.
+pub const MAX_VDM_MSG_SIZE: usize = 8192;
 
 /// VDM Service error types.
 #[derive(Debug)]
@@ -3,12 +3,14 @@
 use crate::control_context::{ControlContext, CtrlCmdResponder, ProtocolCapability};
 use crate::error::MsgHandlerError;
 use crate::firmware_device::fd_context::FirmwareDeviceContext;
+use crate::platform::platform_context::PlatformContext;
 use crate::transport::MctpTransport;
 use caliptra_mcu_pldm_common::codec::PldmCodec;
 use caliptra_mcu_pldm_common::protocol::base::{
     PldmBaseCompletionCode, PldmControlCmd, PldmFailureResponse, PldmMsgHeader, PldmSupportedType,
 };
 use caliptra_mcu_pldm_common::protocol::firmware_update::FwUpdateCmd;
+use caliptra_mcu_pldm_common::protocol::platform::PlatformCmd;
 use caliptra_mcu_pldm_common::util::mctp_transport::{
     construct_mctp_pldm_msg, extract_pldm_msg, MCTP_PLDM_MSG_HDR_LEN,
 };
@@ -32,6 +34,7 @@ pub(crate) fn generate_failure_response(
 pub struct CmdInterface<'a> {
     ctrl_ctx: ControlContext<'a>,
     fd_ctx: FirmwareDeviceContext<'a>,
+    platform_ctx: Option<PlatformContext<'a>>,
     busy: AtomicBool,
 }
 
@@ -39,11 +42,13 @@ impl<'a> CmdInterface<'a> {
     pub fn new(
         protocol_capabilities: &'a [ProtocolCapability],
         fd_ctx: FirmwareDeviceContext<'a>,
+        platform_ctx: Option<PlatformContext<'a>>,
     ) -> Self {
         let ctrl_ctx = ControlContext::new(protocol_capabilities);
         Self {
             ctrl_ctx,
             fd_ctx,
+            platform_ctx,
             busy: AtomicBool::new(false),
         }
     }
@@ -54,13 +59,13 @@ impl<'a> CmdInterface<'a> {
         msg_buf: &mut [u8],
     ) -> Result<(), MsgHandlerError> {
         // Receive msg from mctp transport
-        transport
+        let req_len = transport
             .receive_request(msg_buf)
             .await
             .map_err(MsgHandlerError::Transport)?;
 
         // Process the request
-        let resp_len = self.process_request(msg_buf).await?;
+        let resp_len = self.process_request(msg_buf, req_len).await?;
 
         // Send the response
         transport
@@ -94,12 +99,12 @@ impl<'a> CmdInterface<'a> {
             .map_err(MsgHandlerError::Transport)?;
 
         // Wait for and process the response
-        transport
+        let rsp_len = transport
             .receive_response(msg_buf)
             .await
             .map_err(MsgHandlerError::Transport)?;
 
-        let payload = extract_pldm_msg(msg_buf).map_err(MsgHandlerError::Util)?;
+        let payload = extract_pldm_msg(&mut msg_buf[..rsp_len]).map_err(MsgHandlerError::Util)?;
 
         // Handle the response
         self.fd_ctx.handle_response(payload).await?;
@@ -145,7 +150,11 @@ impl<'a> CmdInterface<'a> {
         self.fd_ctx.ops()
     }
 
-    async fn process_request(&self, msg_buf: &mut [u8]) -> Result<usize, MsgHandlerError> {
+    async fn process_request(
+        &self,
+        msg_buf: &mut [u8],
+        req_len: usize,
+    ) -> Result<usize, MsgHandlerError> {
         // Check if the handler is busy processing a request
         if self.busy.load(Ordering::SeqCst) {
             return Err(MsgHandlerError::NotReady);
@@ -168,6 +177,11 @@ impl<'a> CmdInterface<'a> {
         let resp_len = match pldm_type {
             PldmSupportedType::Base => self.process_control_cmd(cmd_opcode, payload),
             PldmSupportedType::FwUpdate => self.process_fw_update_cmd(cmd_opcode, payload).await,
+            PldmSupportedType::Platform => {
+                let payload_len = req_len.saturating_sub(reserved_len);
+                self.process_platform_cmd(cmd_opcode, payload, payload_len)
+                    .await
+            }
             _ => {
                 unreachable!()
             }
@@ -211,9 +225,26 @@ impl<'a> CmdInterface<'a> {
                 FwUpdateCmd::GetFirmwareParameters => {
                     self.fd_ctx.get_firmware_parameters_rsp(payload).await
                 }
+                FwUpdateCmd::QueryDownstreamDevices => {
+                    self.fd_ctx.query_downstream_devices_rsp(payload).await
+                }
+                FwUpdateCmd::QueryDownstreamIdentifiers => {
+                    self.fd_ctx.query_downstream_identifiers_rsp(payload).await
+                }
+                FwUpdateCmd::GetDownstreamFirmwareParameters => {
+                    self.fd_ctx
+                        .get_downstream_firmware_parameters_rsp(payload)
+                        .await
+                }
                 FwUpdateCmd::RequestUpdate => self.fd_ctx.request_update_rsp(payload).await,
+                FwUpdateCmd::RequestDownstreamDeviceUpdate => {
+                    self.fd_ctx.request_downstream_update_rsp(payload).await
+                }
                 FwUpdateCmd::PassComponentTable => self.fd_ctx.pass_component_rsp(payload).await,
                 FwUpdateCmd::UpdateComponent => self.fd_ctx.update_component_rsp(payload).await,
+                FwUpdateCmd::GetDeviceMetaData => {
+                    self.fd_ctx.get_device_metadata_rsp(payload).await
+                }
 
                 FwUpdateCmd::ActivateFirmware => self.fd_ctx.activate_firmware_rsp(payload).await,
                 FwUpdateCmd::CancelUpdateComponent => {
@@ -232,6 +263,49 @@ impl<'a> CmdInterface<'a> {
         }
     }
 
+    async fn process_platform_cmd(
+        &self,
+        cmd_opcode: u8,
+        payload: &mut [u8],
+        payload_len: usize,
+    ) -> Result<usize, MsgHandlerError> {
+        let platform_ctx = match &self.platform_ctx {
+            Some(ctx) => ctx,
+            None => {
+                return generate_failure_response(
+                    payload,
+                    PldmBaseCompletionCode::InvalidPldmType as u8,
+                )
+            }
+        };
+
+        match PlatformCmd::try_from(cmd_opcode) {
+            Ok(cmd) => match cmd {
+                PlatformCmd::GetPdrRepositoryInfo => {
+                    platform_ctx.get_pdr_repository_info_rsp(payload).await
+                }
+                PlatformCmd::GetPdr => platform_ctx.get_pdr_rsp(payload).await,
+                PlatformCmd::GetSensorReading => platform_ctx.get_sensor_reading_rsp(payload).await,
+                PlatformCmd::GetStateSensorReadings => {
+                    platform_ctx.get_state_sensor_readings_rsp(payload).await
+                }
+                PlatformCmd::PlatformEventMessage => {
+                    platform_ctx
+                        .platform_event_message_rsp(payload, payload_len)
+                        .await
+                }
+                PlatformCmd::PollForPlatformEventMessage => {
+                    platform_ctx
+                        .poll_for_platform_event_message_rsp(payload, self.ctrl_ctx.get_tid())
+                        .await
+                }
+            },
+            Err(_) => {
+                generate_failure_response(payload, PldmBaseCompletionCode::UnsupportedPldmCmd as u8)
+            }
+        }
+    }
+
     fn preprocess_request(
         &self,
         payload: &[u8],
@@ -1,17 +1,33 @@
 // Licensed under the Apache-2.0 license
 
 use crate::control_context::ProtocolCapability;
+use caliptra_mcu_pldm_common::message::firmware_update::request_fw_data::RequestFirmwareDataResponseFixed;
 use caliptra_mcu_pldm_common::protocol::base::{PldmControlCmd, PldmSupportedType};
 use caliptra_mcu_pldm_common::protocol::firmware_update::{FwUpdateCmd, PldmFdTime};
+use caliptra_mcu_pldm_common::protocol::platform::PlatformCmd;
+use caliptra_mcu_pldm_common::util::mctp_transport::MCTP_PLDM_MSG_HDR_LEN;
 use embassy_sync::lazy_lock::LazyLock;
 
-pub const PLDM_PROTOCOL_CAP_COUNT: usize = 2;
+pub const PLDM_PROTOCOL_CAP_COUNT: usize = 3;
 pub const FD_MAX_XFER_SIZE: usize = 512; // Arbitrary limit and change as needed.
 pub const DEFAULT_FD_T1_TIMEOUT: PldmFdTime = 120000; // FD_T1 update mode idle timeout, range is [60s, 120s].
 pub const DEFAULT_FD_T2_RETRY_TIME: PldmFdTime = 5000; // FD_T2 retry request for firmware data, range is [1s, 5s].
 pub const INSTANCE_ID_COUNT: u8 = 32;
 pub const UA_EID: u8 = 8; // Update Agent Endpoint ID for testing.
+pub const PLATFORM_MAX_PDR_SIZE: usize = 256; // Arbitrary limit and change as needed.
+pub const PLATFORM_MAX_EVENT_DATA_SIZE: usize = 256; // Arbitrary limit and change as needed.
 
+// Size of the MCTP driver buffer holding responses not collected yet (MCTP_MAX_MESSAGE_SIZE).
+pub const MCTP_RX_BUFFER_SIZE: usize = 2048;
+// RequestFirmwareData window. Responses to the requests behind the oldest one wait in the
+// MCTP driver buffer, so the window is limited to the full-size responses it can hold.
+pub const FD_MAX_OUTSTANDING_XFER_REQ: usize = MCTP_RX_BUFFER_SIZE
+    / (MCTP_PLDM_MSG_HDR_LEN
+        + core::mem::size_of::<RequestFirmwareDataResponseFixed>()
+        + FD_MAX_XFER_SIZE);
+
+// The Platform (Type 2) capability must stay last. It is only advertised when a
+// `PlatformOps` provider is registered with the PLDM service.
 pub static PLDM_PROTOCOL_CAPABILITIES: LazyLock<
     [ProtocolCapability<'static>; PLDM_PROTOCOL_CAP_COUNT],
 > = LazyLock::new(|| {
@@ -33,18 +49,37 @@ pub static PLDM_PROTOCOL_CAPABILITIES: LazyLock<
             supported_commands: &[
                 FwUpdateCmd::QueryDeviceIdentifiers as u8,
                 FwUpdateCmd::GetFirmwareParameters as u8,
+                FwUpdateCmd::QueryDownstreamDevices as u8,
+                FwUpdateCmd::QueryDownstreamIdentifiers as u8,
+                FwUpdateCmd::GetDownstreamFirmwareParameters as u8,
                 FwUpdateCmd::RequestUpdate as u8,
+                FwUpdateCmd::RequestDownstreamDeviceUpdate as u8,
+                FwUpdateCmd::GetPackageData as u8,
+                FwUpdateCmd::GetDeviceMetaData as u8,
                 FwUpdateCmd::PassComponentTable as u8,
                 FwUpdateCmd::UpdateComponent as u8,
                 FwUpdateCmd::RequestFirmwareData as u8,
                 FwUpdateCmd::TransferComplete as u8,
                 FwUpdateCmd::VerifyComplete as u8,
                 FwUpdateCmd::ApplyComplete as u8,
+                FwUpdateCmd::GetMetaData as u8,
                 FwUpdateCmd::ActivateFirmware as u8,
                 FwUpdateCmd::GetStatus as u8,
                 FwUpdateCmd::CancelUpdateComponent as u8,
                 FwUpdateCmd::CancelUpdate as u8,
             ],
         },
+        ProtocolCapability {
+            pldm_type: PldmSupportedType::Platform,
+            protocol_version: 0xF1F2F000, // "1.2.0"
+            supported_commands: &[
+                PlatformCmd::PlatformEventMessage as u8,
+                PlatformCmd::PollForPlatformEventMessage as u8,
+                PlatformCmd::GetSensorReading as u8,
+                PlatformCmd::GetStateSensorReadings as u8,
+                PlatformCmd::GetPdrRepositoryInfo as u8,
+                PlatformCmd::GetPdr as u8,
+            ],
+        },
     ]
 });
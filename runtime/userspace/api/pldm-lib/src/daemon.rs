@@ -4,9 +4,11 @@ use crate::cmd_interface::CmdInterface;
 use crate::config;
 use crate::firmware_device::fd_context::FirmwareDeviceContext;
 use crate::firmware_device::fd_ops::FdOps;
-use crate::firmware_device::transfer_session::TransferSession;
+use crate::firmware_device::transfer_session::{OutstandingRequest, TransferSession};
+use crate::platform::platform_context::PlatformContext;
+use crate::platform::platform_ops::PlatformOps;
 use crate::timer::AsyncAlarm;
-use crate::transport::MctpTransport;
+use crate::transport::{MctpTransport, TransportError};
 use caliptra_mcu_libsyscall_caliptra::mctp::driver_num;
 use caliptra_mcu_libsyscall_caliptra::DefaultSyscalls;
 use caliptra_mcu_libtock_console::Console;
@@ -16,7 +18,7 @@ use caliptra_mcu_pldm_common::message::firmware_update::request_fw_data::{
 };
 use caliptra_mcu_pldm_common::message::firmware_update::transfer_complete::TransferResult;
 use caliptra_mcu_pldm_common::protocol::base::{PldmBaseCompletionCode, PldmMsgType};
-use caliptra_mcu_pldm_common::protocol::firmware_update::{FwUpdateCmd, FwUpdateCompletionCode};
+use caliptra_mcu_pldm_common::protocol::firmware_update::FwUpdateCompletionCode;
 use caliptra_mcu_pldm_common::util::mctp_transport::{
     construct_mctp_pldm_msg, extract_pldm_msg, MAX_MCTP_PLDM_MSG_SIZE, MCTP_PLDM_MSG_HDR_LEN,
 };
@@ -58,9 +60,34 @@ pub struct PldmService<'a> {
 // It will be extended and refactored to support additional PLDM commands in both responder and requester modes.
 impl<'a> PldmService<'a> {
     pub fn init(fdops: &'a dyn FdOps, spawner: Spawner) -> Self {
+        Self::new(fdops, None, spawner)
+    }
+
+    /// Initializes the PLDM service with a Platform Monitoring and Control (Type 2)
+    /// responder backed by `platform_ops`, in addition to the firmware device.
+    pub fn init_with_platform(
+        fdops: &'a dyn FdOps,
+        platform_ops: &'a dyn PlatformOps,
+        spawner: Spawner,
+    ) -> Self {
+        Self::new(fdops, Some(platform_ops), spawner)
+    }
+
+    fn new(
+        fdops: &'a dyn FdOps,
+        platform_ops: Option<&'a dyn PlatformOps>,
+        spawner: Spawner,
+    ) -> Self {
+        // Type 2 is only advertised when a platform provider is registered.
+        let capabilities = config::PLDM_PROTOCOL_CAPABILITIES.get();
+        let capabilities = match platform_ops {
+            Some(_) => &capabilities[..],
+            None => &capabilities[..config::PLDM_PROTOCOL_CAP_COUNT - 1],
+        };
         let cmd_interface = CmdInterface::new(
-            config::PLDM_PROTOCOL_CAPABILITIES.get(),
+            capabilities,
             FirmwareDeviceContext::new(fdops),
+            platform_ops.map(PlatformContext::new),
         );
         Self {
             spawner,
@@ -160,6 +187,7 @@ pub async fn pldm_initiator(
                 {
                     Ok(download_complete) => {
                         if download_complete {
+                            drain_outstanding_requests(&mut transport, &mut msg_buffer, sess).await;
                             // Sync session state back to internal state
                             cmd_interface.sync_transfer_session(sess).await;
                             session = None;
@@ -174,6 +202,7 @@ pub async fn pldm_initiator(
                         )
                         .unwrap();
                         // Sync and fall back to regular path
+                        drain_outstanding_requests(&mut transport, &mut msg_buffer, sess).await;
                         cmd_interface.sync_transfer_session(sess).await;
                         session = None;
                     }
@@ -244,8 +273,9 @@ pub async fn pldm_responder(
             }
         }
 
-        // When FD state is download state, signal the initiator task
-        if cmd_interface.should_start_initiator_mode().await && !initiator_signal.signaled() {
+        // When FD has requests to send (download, or package data and metadata in
+        // LearnComponents state), signal the initiator task
+        if !cmd_interface.should_stop_initiator_mode().await && !initiator_signal.signaled() {
             initiator_signal.signal(());
         }
     }
@@ -255,6 +285,10 @@ pub async fn pldm_responder(
 ///
 /// This function runs the download phase with the session state kept outside the async mutex,
 /// only syncing back periodically or when the transfer completes/is cancelled.
+///
+/// Each call hands buffered data to the firmware device in offset order, refills the window
+/// of outstanding RequestFirmwareData requests and collects the response to the oldest one.
+/// Responses to later requests are held by the MCTP driver until they are collected.
 async fn run_optimized_download(
     cmd_interface: &'static CmdInterface<'static>,
     transport: &mut MctpTransport,
@@ -278,11 +312,6 @@ async fn run_optimized_download(
         return Ok(true);
     }
 
-    // Check if we should send a request
-    if !session.should_send_request(now) {
-        return Ok(false);
-    }
-
     // If transfer is complete, signal done (TransferComplete will be handled by fallback path)
     if session.complete {
         return Ok(true);
@@ -294,48 +323,81 @@ async fn run_optimized_download(
         .await
         .map_err(crate::error::MsgHandlerError::FdOps)?;
 
-    // Calculate chunk parameters using local session state
-    let (chunk_offset, chunk_length) =
-        match session.get_download_chunk(requested_offset as u32, requested_length as u32) {
-            Some(chunk) => chunk,
-            None => {
-                session.mark_failed(TransferResult::FdAbortedTransfer);
-                return Ok(true);
-            }
-        };
+    if !session.set_download_range(requested_offset as u32, requested_length as u32) {
+        session.mark_failed(TransferResult::FdAbortedTransfer);
+        return Ok(true);
+    }
 
-    // Update session state
-    session.offset = chunk_offset;
-    session.length = chunk_length;
-
-    // Build request message
-    let instance_id = session.alloc_next_instance_id();
-    let payload =
-        construct_mctp_pldm_msg(msg_buffer).map_err(crate::error::MsgHandlerError::Util)?;
-
-    let msg_len = RequestFirmwareDataRequest::new(
-        instance_id,
-        PldmMsgType::Request,
-        chunk_offset,
-        chunk_length,
-    )
-    .encode(payload)
-    .map_err(crate::error::MsgHandlerError::Codec)?;
-
-    // Mark as sent
-    session.mark_sent(cmd_interface.now(), FwUpdateCmd::RequestFirmwareData as u8);
-
-    // Send request
-    transport
-        .send_request(ua_eid, &msg_buffer[..msg_len + MCTP_PLDM_MSG_HDR_LEN])
-        .await
-        .map_err(crate::error::MsgHandlerError::Transport)?;
+    // Hand data that has already been received to ops in offset order
+    if let Some(fw_data) = session.deliverable_chunk() {
+        let delivered_len = fw_data.len() as u32;
+        let result = ops
+            .download_fw_data(session.offset as usize, fw_data, &session.component)
+            .await
+            .map_err(crate::error::MsgHandlerError::FdOps)?;
 
-    // Receive response
-    transport
-        .receive_response(msg_buffer)
-        .await
-        .map_err(crate::error::MsgHandlerError::Transport)?;
+        if result != TransferResult::TransferSuccess {
+            session.mark_complete(result);
+            return Ok(true);
+        }
+
+        session.consume_delivered(delivered_len);
+        if ops.is_download_complete(&session.component) {
+            session.mark_complete(TransferResult::TransferSuccess);
+            return Ok(true);
+        }
+        return Ok(false);
+    }
+
+    // Keep the window of outstanding requests full
+    while let Some((chunk_offset, chunk_length)) = session.next_request() {
+        let instance_id = session.alloc_next_instance_id();
+        let payload =
+            construct_mctp_pldm_msg(msg_buffer).map_err(crate::error::MsgHandlerError::Util)?;
+
+        let msg_len = RequestFirmwareDataRequest::new(
+            instance_id,
+            PldmMsgType::Request,
+            chunk_offset,
+            chunk_length,
+        )
+        .encode(payload)
+        .map_err(crate::error::MsgHandlerError::Codec)?;
+
+        let tag = transport
+            .send_tagged_request(ua_eid, &msg_buffer[..msg_len + MCTP_PLDM_MSG_HDR_LEN])
+            .await
+            .map_err(crate::error::MsgHandlerError::Transport)?;
+
+        session.track_request(
+            OutstandingRequest {
+                instance_id,
+                tag,
+                offset: chunk_offset,
+                length: chunk_length,
+            },
+            cmd_interface.now(),
+        );
+    }
+
+    let Some(request) = session.oldest_request() else {
+        return Ok(false);
+    };
+
+    // Receive the response to the oldest request. The wait is bounded by the MCTP response
+    // timeout; a range whose response timed out is requested again with a smaller chunk.
+    let result = transport
+        .receive_tagged_response(ua_eid, request.tag, msg_buffer)
+        .await;
+    session.complete_request(request.instance_id);
+    match result {
+        Ok(_) => {}
+        Err(TransportError::ResponseTimeout) => {
+            session.record_retry(request.length);
+            return Ok(false);
+        }
+        Err(e) => return Err(crate::error::MsgHandlerError::Transport(e)),
+    }
 
     // Process response
     let resp_payload = extract_pldm_msg(msg_buffer).map_err(crate::error::MsgHandlerError::Util)?;
@@ -346,35 +408,26 @@ async fn run_optimized_download(
     // Update T1 timestamp on response
     session.update_t1_timestamp(cmd_interface.now());
 
+    if rsp_fixed.hdr.instance_id() != request.instance_id {
+        // Not the response to this request; its range is requested again
+        session.record_retry(request.length);
+        return Ok(false);
+    }
+
     match rsp_fixed.completion_code {
         code if code == PldmBaseCompletionCode::Success as u8 => {
-            // Extract firmware data and pass to ops
+            // Hold the data until every chunk before it has been delivered
             let fw_data = &resp_payload[core::mem::size_of::<RequestFirmwareDataResponseFixed>()..]
-                .get(..chunk_length as usize)
+                .get(..request.length as usize)
                 .ok_or(crate::error::MsgHandlerError::Codec(
                     caliptra_mcu_pldm_common::codec::PldmCodecError::BufferTooShort,
                 ))?;
-
-            let result = ops
-                .download_fw_data(chunk_offset as usize, fw_data, &session.component)
-                .await
-                .map_err(crate::error::MsgHandlerError::FdOps)?;
-
-            if result == TransferResult::TransferSuccess {
-                if ops.is_download_complete(&session.component) {
-                    session.mark_complete(TransferResult::TransferSuccess);
-                    return Ok(true);
-                } else {
-                    session.mark_ready_for_next();
-                }
-            } else {
-                session.mark_complete(result);
-                return Ok(true);
-            }
+            session.store_chunk(request.offset, fw_data);
+            session.record_success();
         }
         code if code == FwUpdateCompletionCode::RetryRequestFwData as u8 => {
-            // Retry - keep state as ready
-            session.mark_ready_for_next();
+            // The range is requested again with a smaller chunk size
+            session.record_retry(request.length);
         }
         _ => {
             session.mark_complete(TransferResult::FdAbortedTransfer);
@@ -384,3 +437,18 @@ async fn run_optimized_download(
 
     Ok(false)
 }
+
+/// Collects the responses to requests that are still outstanding when the download phase
+/// ends, so they are not left for the requests that follow.
+async fn drain_outstanding_requests(
+    transport: &mut MctpTransport,
+    msg_buffer: &mut [u8],
+    session: &mut TransferSession,
+) {
+    while let Some(request) = session.oldest_request() {
+        let _ = transport
+            .receive_tagged_response(crate::config::UA_EID, request.tag, msg_buffer)
+            .await;
+        session.complete_request(request.instance_id);
+    }
+}
@@ -3,14 +3,24 @@
 use crate::cmd_interface::generate_failure_response;
 use crate::error::MsgHandlerError;
 use crate::firmware_device::fd_internal::{FdInternal, FdReqState};
-use crate::firmware_device::fd_ops::{ComponentOperation, FdOps};
+use crate::firmware_device::fd_ops::{ComponentOperation, FdOps, FdOpsError};
 use caliptra_mcu_pldm_common::codec::PldmCodec;
 use caliptra_mcu_pldm_common::message::firmware_update::activate_fw::{
     ActivateFirmwareRequest, ActivateFirmwareResponse,
 };
+use caliptra_mcu_pldm_common::message::firmware_update::get_downstream_fw_params::{
+    DownstreamFirmwareParameters, GetDownstreamFirmwareParametersRequest,
+    GetDownstreamFirmwareParametersResponse,
+};
 use caliptra_mcu_pldm_common::message::firmware_update::get_fw_params::{
     FirmwareParameters, GetFirmwareParametersRequest, GetFirmwareParametersResponse,
 };
+use caliptra_mcu_pldm_common::message::firmware_update::get_metadata::{
+    GetDeviceMetaDataRequest, GetDeviceMetaDataResponse, GetMetaDataRequest, GetMetaDataResponse,
+};
+use caliptra_mcu_pldm_common::message::firmware_update::get_package_data::{
+    GetPackageDataRequest, GetPackageDataResponse,
+};
 use caliptra_mcu_pldm_common::message::firmware_update::get_status::ProgressPercent;
 use caliptra_mcu_pldm_common::message::firmware_update::pass_component::{
     PassComponentTableRequest, PassComponentTableResponse,
@@ -18,12 +28,22 @@ use caliptra_mcu_pldm_common::message::firmware_update::pass_component::{
 use caliptra_mcu_pldm_common::message::firmware_update::query_devid::{
     QueryDeviceIdentifiersRequest, QueryDeviceIdentifiersResponse,
 };
+use caliptra_mcu_pldm_common::message::firmware_update::query_downstream_devices::{
+    DownstreamDeviceUpdateSupported, QueryDownstreamDevicesRequest, QueryDownstreamDevicesResponse,
+};
+use caliptra_mcu_pldm_common::message::firmware_update::query_downstream_identifiers::{
+    DownstreamDevice, DownstreamDeviceIdentifiers, QueryDownstreamIdentifiersRequest,
+    QueryDownstreamIdentifiersResponse,
+};
 use caliptra_mcu_pldm_common::message::firmware_update::request_cancel::{
     CancelUpdateComponentRequest, CancelUpdateComponentResponse, CancelUpdateRequest,
     CancelUpdateResponse,
 };
+use caliptra_mcu_pldm_common::message::firmware_update::request_downstream_update::{
+    RequestDownstreamDeviceUpdateRequest, RequestDownstreamDeviceUpdateResponse,
+};
 use caliptra_mcu_pldm_common::message::firmware_update::request_update::{
-    RequestUpdateRequest, RequestUpdateResponse,
+    FdWillSendPkgDataCmd, RequestUpdateRequest, RequestUpdateResponse,
 };
 use caliptra_mcu_pldm_common::message::firmware_update::transfer_complete::{
     TransferCompleteRequest, TransferResult,
@@ -47,18 +67,19 @@ use caliptra_mcu_pldm_common::message::firmware_update::verify_complete::{
     VerifyCompleteRequest, VerifyResult,
 };
 use caliptra_mcu_pldm_common::protocol::base::{
-    PldmBaseCompletionCode, PldmMsgHeader, PldmMsgType, TransferRespFlag,
+    PldmBaseCompletionCode, PldmFailureResponse, PldmMsgHeader, PldmMsgType, TransferOperationFlag,
+    TransferRespFlag,
 };
 use caliptra_mcu_pldm_common::protocol::firmware_update::{
     ComponentActivationMethods, ComponentCompatibilityResponse, ComponentCompatibilityResponseCode,
     ComponentResponse, ComponentResponseCode, Descriptor, FirmwareDeviceState, FwUpdateCmd,
     FwUpdateCompletionCode, PldmFirmwareString, UpdateOptionFlags, MAX_DESCRIPTORS_COUNT,
-    PLDM_FWUP_BASELINE_TRANSFER_SIZE,
+    MAX_DOWNSTREAM_DEVICE_COUNT, PLDM_FWUP_BASELINE_TRANSFER_SIZE, PLDM_FWUP_MAX_DATA_PORTION_SIZE,
 };
 use caliptra_mcu_pldm_common::util::fw_component::FirmwareComponent;
 
 use crate::firmware_device::fd_internal::{
-    ApplyState, DownloadState, InitiatorModeState, VerifyState,
+    ApplyState, DownloadState, InitiatorModeState, LearnComponentsState, VerifyState,
 };
 use crate::firmware_device::transfer_session::CancellationFlag;
 
@@ -158,31 +179,268 @@ impl<'a> FirmwareDeviceContext<'a> {
             );
         }
 
-        // Get the transfer size for the firmware update operation
-        let fd_transfer_size = self
+        let (fd_meta_data_len, learn_components) = self
+            .prepare_update(
+                ua_transfer_size,
+                req.fixed.max_outstanding_transfer_req,
+                req.fixed.pkg_data_len as usize,
+                false,
+            )
+            .await?;
+
+        // Construct response
+        let resp = if learn_components.pkg_data_pending {
+            RequestUpdateResponse::new(
+                req.fixed.hdr.instance_id(),
+                PldmBaseCompletionCode::Success as u8,
+                fd_meta_data_len,
+                FdWillSendPkgDataCmd::WillSendWithMaxTransferSize as u8,
+                Some(PLDM_FWUP_MAX_DATA_PORTION_SIZE as u32),
+            )
+        } else {
+            RequestUpdateResponse::new(
+                req.fixed.hdr.instance_id(),
+                PldmBaseCompletionCode::Success as u8,
+                fd_meta_data_len,
+                FdWillSendPkgDataCmd::NoPackageData as u8,
+                None,
+            )
+        };
+
+        match resp.encode(payload) {
+            Ok(bytes) => {
+                self.enter_learn_components(learn_components).await;
+                Ok(bytes)
+            }
+            Err(_) => {
+                generate_failure_response(payload, PldmBaseCompletionCode::InvalidLength as u8)
+            }
+        }
+    }
+
+    pub async fn query_downstream_devices_rsp(
+        &self,
+        payload: &mut [u8],
+    ) -> Result<usize, MsgHandlerError> {
+        // Decode the request message
+        let req = QueryDownstreamDevicesRequest::decode(payload).map_err(MsgHandlerError::Codec)?;
+
+        let mut downstream_devices = [DownstreamDevice::default(); MAX_DOWNSTREAM_DEVICE_COUNT];
+        let device_cnt = self
             .ops
-            .get_xfer_size(ua_transfer_size)
-            .await
+            .get_downstream_devices(&mut downstream_devices)
             .map_err(MsgHandlerError::FdOps)?;
 
-        // Set transfer size to the internal state
-        self.internal.set_xfer_size(fd_transfer_size).await;
-
-        // Construct response, no metadata or package data.
-        let resp = RequestUpdateResponse::new(
-            req.fixed.hdr.instance_id(),
+        // Construct response
+        let resp = QueryDownstreamDevicesResponse::new(
+            req.hdr.instance_id(),
             PldmBaseCompletionCode::Success as u8,
+            if device_cnt > 0 {
+                DownstreamDeviceUpdateSupported::Supported
+            } else {
+                DownstreamDeviceUpdateSupported::NotSupported
+            },
+            device_cnt as u16,
+            MAX_DOWNSTREAM_DEVICE_COUNT as u16,
             0,
-            0,
-            None,
         );
 
+        match resp.encode(payload) {
+            Ok(bytes) => Ok(bytes),
+            Err(_) => {
+                generate_failure_response(payload, PldmBaseCompletionCode::InvalidLength as u8)
+            }
+        }
+    }
+
+    pub async fn query_downstream_identifiers_rsp(
+        &self,
+        payload: &mut [u8],
+    ) -> Result<usize, MsgHandlerError> {
+        // Decode the request message
+        let req =
+            QueryDownstreamIdentifiersRequest::decode(payload).map_err(MsgHandlerError::Codec)?;
+
+        let mut downstream_devices = [DownstreamDevice::default(); MAX_DOWNSTREAM_DEVICE_COUNT];
+        let device_cnt = self
+            .ops
+            .get_downstream_devices(&mut downstream_devices)
+            .map_err(MsgHandlerError::FdOps)?;
+        if device_cnt == 0 {
+            return generate_failure_response(
+                payload,
+                PldmBaseCompletionCode::UnsupportedPldmCmd as u8,
+            );
+        }
+
+        let identifiers = DownstreamDeviceIdentifiers::new(
+            downstream_devices
+                .get(..device_cnt)
+                .ok_or(MsgHandlerError::FdOps(FdOpsError::DownstreamDeviceError))?,
+        )
+        .map_err(MsgHandlerError::PldmCommon)?;
+
+        // The encoded identifiers are served in portions, the in-memory size bounds their length
+        let mut data = [0u8; core::mem::size_of::<DownstreamDeviceIdentifiers>()];
+        let data_len = identifiers
+            .encode(&mut data)
+            .map_err(MsgHandlerError::Codec)?;
+        let offset = match portion_offset(req.transfer_op_flag, req.data_transfer_handle, data_len)
+        {
+            Ok(offset) => offset,
+            Err(completion_code) => {
+                return generate_failure_response(payload, completion_code as u8)
+            }
+        };
+        let portion_len = (data_len - offset).min(PLDM_FWUP_MAX_DATA_PORTION_SIZE);
+        let (next_handle, transfer_flag) = portion_transfer_flag(offset, portion_len, data_len);
+
+        // Construct response
+        let resp = QueryDownstreamIdentifiersResponse::new(
+            req.hdr.instance_id(),
+            PldmBaseCompletionCode::Success as u8,
+            next_handle,
+            transfer_flag,
+            &data[offset..offset + portion_len],
+        )
+        .map_err(MsgHandlerError::PldmCommon)?;
+
+        match resp.encode(payload) {
+            Ok(bytes) => Ok(bytes),
+            Err(_) => {
+                generate_failure_response(payload, PldmBaseCompletionCode::InvalidLength as u8)
+            }
+        }
+    }
+
+    pub async fn get_downstream_firmware_parameters_rsp(
+        &self,
+        payload: &mut [u8],
+    ) -> Result<usize, MsgHandlerError> {
+        // Decode the request message
+        let req = GetDownstreamFirmwareParametersRequest::decode(payload)
+            .map_err(MsgHandlerError::Codec)?;
+
+        let mut downstream_devices = [DownstreamDevice::default(); MAX_DOWNSTREAM_DEVICE_COUNT];
+        let device_cnt = self
+            .ops
+            .get_downstream_devices(&mut downstream_devices)
+            .map_err(MsgHandlerError::FdOps)?;
+        if device_cnt == 0 {
+            return generate_failure_response(
+                payload,
+                PldmBaseCompletionCode::UnsupportedPldmCmd as u8,
+            );
+        }
+
+        let mut downstream_params = DownstreamFirmwareParameters::default();
+        self.ops
+            .get_downstream_firmware_parms(&mut downstream_params)
+            .map_err(MsgHandlerError::FdOps)?;
+
+        // The encoded parameters are served in portions, the in-memory size bounds their length
+        let mut data = [0u8; core::mem::size_of::<DownstreamFirmwareParameters>()];
+        let data_len = downstream_params
+            .encode(&mut data)
+            .map_err(MsgHandlerError::Codec)?;
+        let offset = match portion_offset(req.transfer_op_flag, req.data_transfer_handle, data_len)
+        {
+            Ok(offset) => offset,
+            Err(completion_code) => {
+                return generate_failure_response(payload, completion_code as u8)
+            }
+        };
+        let portion_len = (data_len - offset).min(PLDM_FWUP_MAX_DATA_PORTION_SIZE);
+        let (next_handle, transfer_flag) = portion_transfer_flag(offset, portion_len, data_len);
+
+        // Construct response
+        let resp = GetDownstreamFirmwareParametersResponse::new(
+            req.hdr.instance_id(),
+            PldmBaseCompletionCode::Success as u8,
+            next_handle,
+            transfer_flag,
+            &data[offset..offset + portion_len],
+        )
+        .map_err(MsgHandlerError::PldmCommon)?;
+
+        match resp.encode(payload) {
+            Ok(bytes) => Ok(bytes),
+            Err(_) => {
+                generate_failure_response(payload, PldmBaseCompletionCode::InvalidLength as u8)
+            }
+        }
+    }
+
+    pub async fn request_downstream_update_rsp(
+        &self,
+        payload: &mut [u8],
+    ) -> Result<usize, MsgHandlerError> {
+        // Check if FD is in idle state. Otherwise returns 'ALREADY_IN_UPDATE_MODE' completion code
+        if self.internal.is_update_mode().await {
+            return generate_failure_response(
+                payload,
+                FwUpdateCompletionCode::AlreadyInUpdateMode as u8,
+            );
+        }
+
+        // Set timestamp for FD T1 timeout
+        self.set_fd_t1_ts().await;
+
+        // Decode the request message
+        let req = RequestDownstreamDeviceUpdateRequest::decode(payload)
+            .map_err(MsgHandlerError::Codec)?;
+        let ua_transfer_size = req.max_downstream_device_transfer_size as usize;
+        if ua_transfer_size < PLDM_FWUP_BASELINE_TRANSFER_SIZE {
+            return generate_failure_response(
+                payload,
+                FwUpdateCompletionCode::InvalidTransferLength as u8,
+            );
+        }
+
+        // The update can only be initiated if the device has downstream devices
+        let mut downstream_devices = [DownstreamDevice::default(); MAX_DOWNSTREAM_DEVICE_COUNT];
+        let device_cnt = self
+            .ops
+            .get_downstream_devices(&mut downstream_devices)
+            .map_err(MsgHandlerError::FdOps)?;
+        if device_cnt == 0 || self.ops.start_downstream_update().await.is_err() {
+            return generate_failure_response(
+                payload,
+                FwUpdateCompletionCode::UnableToInitiateUpdate as u8,
+            );
+        }
+
+        let (meta_data_len, learn_components) = self
+            .prepare_update(
+                ua_transfer_size,
+                req.max_outstanding_transfer_req,
+                req.downstream_device_pkg_data_len as usize,
+                true,
+            )
+            .await?;
+
+        // Construct response
+        let resp = if learn_components.pkg_data_pending {
+            RequestDownstreamDeviceUpdateResponse::new(
+                req.hdr.instance_id(),
+                PldmBaseCompletionCode::Success as u8,
+                meta_data_len,
+                FdWillSendPkgDataCmd::WillSendWithMaxTransferSize as u8,
+                Some(PLDM_FWUP_MAX_DATA_PORTION_SIZE as u32),
+            )
+        } else {
+            RequestDownstreamDeviceUpdateResponse::new(
+                req.hdr.instance_id(),
+                PldmBaseCompletionCode::Success as u8,
+                meta_data_len,
+                FdWillSendPkgDataCmd::NoPackageData as u8,
+                None,
+            )
+        };
+
         match resp.encode(payload) {
             Ok(bytes) => {
-                // Move FD state to 'LearnComponents'
-                self.internal
-                    .set_fd_state(FirmwareDeviceState::LearnComponents)
-                    .await;
+                self.enter_learn_components(learn_components).await;
                 Ok(bytes)
             }
             Err(_) => {
@@ -229,6 +487,10 @@ impl<'a> FirmwareDeviceContext<'a> {
             None,
             None,
         );
+        let pass_comp = FirmwareComponent {
+            downstream_device_update: self.internal.is_downstream_update().await,
+            ..pass_comp
+        };
 
         let mut firmware_params = FirmwareParameters::default();
         self.ops
@@ -303,6 +565,10 @@ impl<'a> FirmwareDeviceContext<'a> {
             Some(req.fixed.comp_image_size),
             Some(UpdateOptionFlags(req.fixed.update_option_flags)),
         );
+        let update_comp = FirmwareComponent {
+            downstream_device_update: self.internal.is_downstream_update().await,
+            ..update_comp
+        };
 
         // Store the component info into the internal state.
         self.internal.set_component(&update_comp).await;
@@ -365,6 +631,67 @@ impl<'a> FirmwareDeviceContext<'a> {
         }
     }
 
+    pub async fn get_device_metadata_rsp(
+        &self,
+        payload: &mut [u8],
+    ) -> Result<usize, MsgHandlerError> {
+        // Check if FD is in 'ReadyTransfer' state. Otherwise returns 'INVALID_STATE' completion code
+        if self.internal.get_fd_state().await != FirmwareDeviceState::ReadyXfer {
+            return generate_failure_response(
+                payload,
+                FwUpdateCompletionCode::InvalidStateForCommand as u8,
+            );
+        }
+
+        // Set timestamp for FD T1 timeout
+        self.set_fd_t1_ts().await;
+
+        // Decode the request message
+        let req = GetDeviceMetaDataRequest::decode(payload).map_err(MsgHandlerError::Codec)?;
+
+        let metadata_len = self.ops.get_device_metadata_len();
+        if metadata_len == 0 {
+            return generate_failure_response(
+                payload,
+                FwUpdateCompletionCode::NoDeviceMetadata as u8,
+            );
+        }
+
+        let offset =
+            match portion_offset(req.transfer_op_flag, req.data_transfer_handle, metadata_len) {
+                Ok(offset) => offset,
+                Err(completion_code) => {
+                    return generate_failure_response(payload, completion_code as u8)
+                }
+            };
+
+        let mut portion = [0u8; PLDM_FWUP_MAX_DATA_PORTION_SIZE];
+        let portion_len = (metadata_len - offset).min(PLDM_FWUP_MAX_DATA_PORTION_SIZE);
+        let portion_len = self
+            .ops
+            .get_device_metadata(offset, &mut portion[..portion_len])
+            .await
+            .map_err(MsgHandlerError::FdOps)?;
+        let (next_handle, transfer_flag) = portion_transfer_flag(offset, portion_len, metadata_len);
+
+        // Construct response
+        let resp = GetDeviceMetaDataResponse::new(
+            req.hdr.instance_id(),
+            PldmBaseCompletionCode::Success as u8,
+            next_handle,
+            transfer_flag,
+            &portion[..portion_len],
+        )
+        .map_err(MsgHandlerError::PldmCommon)?;
+
+        match resp.encode(payload) {
+            Ok(bytes) => Ok(bytes),
+            Err(_) => {
+                generate_failure_response(payload, PldmBaseCompletionCode::InvalidLength as u8)
+            }
+        }
+    }
+
     pub async fn activate_firmware_rsp(
         &self,
         payload: &mut [u8],
@@ -653,6 +980,57 @@ impl<'a> FirmwareDeviceContext<'a> {
         }
     }
 
+    // Negotiates the transfer size and the number of outstanding RequestFirmwareData requests, and
+    // determines whether the device retrieves the package data or its metadata from the UA.
+    // Returns the device metadata length and the LearnComponents state.
+    async fn prepare_update(
+        &self,
+        ua_transfer_size: usize,
+        ua_max_outstanding_xfer_req: u8,
+        pkg_data_len: usize,
+        downstream_update: bool,
+    ) -> Result<(u16, LearnComponentsState), MsgHandlerError> {
+        // Get the transfer size for the firmware update operation
+        let fd_transfer_size = self
+            .ops
+            .get_xfer_size(ua_transfer_size)
+            .await
+            .map_err(MsgHandlerError::FdOps)?;
+
+        // Set transfer size to the internal state
+        self.internal.set_xfer_size(fd_transfer_size).await;
+        self.internal
+            .set_max_outstanding_xfer_req(ua_max_outstanding_xfer_req)
+            .await;
+        self.internal.set_downstream_update(downstream_update).await;
+
+        let learn_components = LearnComponentsState {
+            pkg_data_pending: pkg_data_len > 0 && self.ops.wants_package_data(pkg_data_len),
+            metadata_pending: self.ops.is_metadata_needed(),
+            ..Default::default()
+        };
+        let fd_meta_data_len = u16::try_from(self.ops.get_device_metadata_len())
+            .map_err(|_| MsgHandlerError::FdOps(FdOpsError::MetaDataError))?;
+        Ok((fd_meta_data_len, learn_components))
+    }
+
+    async fn enter_learn_components(&self, learn_components: LearnComponentsState) {
+        if learn_components.is_pending() {
+            // Set up the req for GetPackageData and GetMetaData.
+            self.internal
+                .set_initiator_mode(InitiatorModeState::LearnComponents(learn_components))
+                .await;
+            self.internal
+                .set_fd_req(FdReqState::Ready, false, None, None, None, None)
+                .await;
+        }
+
+        // Move FD state to 'LearnComponents'
+        self.internal
+            .set_fd_state(FirmwareDeviceState::LearnComponents)
+            .await;
+    }
+
     pub async fn set_fd_t1_ts(&self) {
         self.internal.set_fd_t1_update_ts(self.ops.now()).await;
     }
@@ -662,18 +1040,26 @@ impl<'a> FirmwareDeviceContext<'a> {
     }
 
     pub async fn should_stop_initiator_mode(&self) -> bool {
-        !matches!(
-            self.internal.get_fd_state().await,
+        match self.internal.get_fd_state().await {
+            FirmwareDeviceState::LearnComponents => !self
+                .internal
+                .get_fd_learn_components_state()
+                .await
+                .is_some_and(|learn| learn.is_pending()),
             FirmwareDeviceState::Download
-                | FirmwareDeviceState::Verify
-                | FirmwareDeviceState::Apply
-        )
+            | FirmwareDeviceState::Verify
+            | FirmwareDeviceState::Apply => false,
+            _ => true,
+        }
     }
 
     pub async fn fd_progress(&self, payload: &mut [u8]) -> Result<usize, MsgHandlerError> {
         let fd_state = self.internal.get_fd_state().await;
 
         let result = match fd_state {
+            FirmwareDeviceState::LearnComponents => {
+                self.fd_progress_learn_components(payload).await
+            }
             FirmwareDeviceState::Download => self.fd_progress_download(payload).await,
             FirmwareDeviceState::Verify => self.pldm_fd_progress_verify(payload).await,
             FirmwareDeviceState::Apply => self.pldm_fd_progress_apply(payload).await,
@@ -717,6 +1103,8 @@ impl<'a> FirmwareDeviceContext<'a> {
         self.set_fd_t1_ts().await;
 
         match FwUpdateCmd::try_from(cmd_code) {
+            Ok(FwUpdateCmd::GetPackageData) => self.process_get_package_data_rsp(payload).await,
+            Ok(FwUpdateCmd::GetMetaData) => self.process_get_metadata_rsp(payload).await,
             Ok(FwUpdateCmd::RequestFirmwareData) => self.process_request_fw_data_rsp(payload).await,
             Ok(FwUpdateCmd::TransferComplete) => self.process_transfer_complete_rsp(payload).await,
             Ok(FwUpdateCmd::VerifyComplete) => self.process_verify_complete_rsp(payload).await,
@@ -725,6 +1113,103 @@ impl<'a> FirmwareDeviceContext<'a> {
         }
     }
 
+    async fn process_get_package_data_rsp(
+        &self,
+        payload: &mut [u8],
+    ) -> Result<(), MsgHandlerError> {
+        let mut learn = self.get_learn_components_state().await?;
+        if !learn.pkg_data_pending {
+            return Err(MsgHandlerError::FdInitiatorModeError);
+        }
+
+        let completion_code = PldmFailureResponse::decode(payload)
+            .map_err(MsgHandlerError::Codec)?
+            .completion_code;
+        if completion_code != PldmBaseCompletionCode::Success as u8 {
+            // Continue without the package data
+            learn.pkg_data_pending = false;
+            return self.finish_learn_components_request(learn).await;
+        }
+
+        let rsp = GetPackageDataResponse::decode(payload).map_err(MsgHandlerError::Codec)?;
+        let complete = learn
+            .advance(
+                rsp.fixed.next_data_transfer_handle,
+                rsp.fixed.transfer_flag,
+                rsp.portion_len,
+            )
+            .ok_or(MsgHandlerError::FdInitiatorModeError)?;
+        self.ops
+            .handle_package_data(learn.offset - rsp.portion_len, rsp.portion(), complete)
+            .await
+            .map_err(MsgHandlerError::FdOps)?;
+        if complete {
+            learn.pkg_data_pending = false;
+            learn.offset = 0;
+            learn.data_transfer_handle = None;
+        }
+        self.finish_learn_components_request(learn).await
+    }
+
+    async fn process_get_metadata_rsp(&self, payload: &mut [u8]) -> Result<(), MsgHandlerError> {
+        let mut learn = self.get_learn_components_state().await?;
+        if learn.pkg_data_pending || !learn.metadata_pending {
+            return Err(MsgHandlerError::FdInitiatorModeError);
+        }
+
+        let completion_code = PldmFailureResponse::decode(payload)
+            .map_err(MsgHandlerError::Codec)?
+            .completion_code;
+        if completion_code != PldmBaseCompletionCode::Success as u8 {
+            // The UA holds no metadata for this device
+            learn.metadata_pending = false;
+            return self.finish_learn_components_request(learn).await;
+        }
+
+        let rsp = GetMetaDataResponse::decode(payload).map_err(MsgHandlerError::Codec)?;
+        let complete = learn
+            .advance(
+                rsp.fixed.next_data_transfer_handle,
+                rsp.fixed.transfer_flag,
+                rsp.portion_len,
+            )
+            .ok_or(MsgHandlerError::FdInitiatorModeError)?;
+        self.ops
+            .restore_metadata(learn.offset - rsp.portion_len, rsp.portion(), complete)
+            .await
+            .map_err(MsgHandlerError::FdOps)?;
+        if complete {
+            learn.metadata_pending = false;
+        }
+        self.finish_learn_components_request(learn).await
+    }
+
+    async fn get_learn_components_state(&self) -> Result<LearnComponentsState, MsgHandlerError> {
+        if self.internal.get_fd_state().await != FirmwareDeviceState::LearnComponents {
+            return Err(MsgHandlerError::FdInitiatorModeError);
+        }
+        self.internal
+            .get_fd_learn_components_state()
+            .await
+            .ok_or(MsgHandlerError::FdInitiatorModeError)
+    }
+
+    async fn finish_learn_components_request(
+        &self,
+        learn: LearnComponentsState,
+    ) -> Result<(), MsgHandlerError> {
+        self.internal.set_fd_learn_components_state(learn).await;
+        let req_state = if learn.is_pending() {
+            FdReqState::Ready
+        } else {
+            FdReqState::Unused
+        };
+        self.internal
+            .set_fd_req(req_state, false, None, None, None, None)
+            .await;
+        Ok(())
+    }
+
     async fn process_request_fw_data_rsp(&self, payload: &mut [u8]) -> Result<(), MsgHandlerError> {
         let fd_state = self.internal.get_fd_state().await;
         if fd_state != FirmwareDeviceState::Download {
@@ -899,6 +1384,60 @@ impl<'a> FirmwareDeviceContext<'a> {
         Ok(())
     }
 
+    async fn fd_progress_learn_components(
+        &self,
+        payload: &mut [u8],
+    ) -> Result<usize, MsgHandlerError> {
+        if !self.should_send_fd_request().await {
+            return Err(MsgHandlerError::FdInitiatorModeError);
+        }
+
+        let learn = self.get_learn_components_state().await?;
+        let (data_transfer_handle, transfer_op_flag) = match learn.data_transfer_handle {
+            Some(handle) => (handle, TransferOperationFlag::GetNextPart),
+            None => (0, TransferOperationFlag::GetFirstPart),
+        };
+
+        // Package data is retrieved first, then the metadata
+        let instance_id = self.internal.alloc_next_instance_id().await.unwrap();
+        let (msg_len, command) = if learn.pkg_data_pending {
+            let msg_len = GetPackageDataRequest::new(
+                instance_id,
+                PldmMsgType::Request,
+                data_transfer_handle,
+                transfer_op_flag,
+            )
+            .encode(payload)
+            .map_err(MsgHandlerError::Codec)?;
+            (msg_len, FwUpdateCmd::GetPackageData)
+        } else if learn.metadata_pending {
+            let msg_len = GetMetaDataRequest::new(
+                instance_id,
+                PldmMsgType::Request,
+                data_transfer_handle,
+                transfer_op_flag,
+            )
+            .encode(payload)
+            .map_err(MsgHandlerError::Codec)?;
+            (msg_len, FwUpdateCmd::GetMetaData)
+        } else {
+            return Ok(0);
+        };
+
+        self.internal
+            .set_fd_req(
+                FdReqState::Sent,
+                false,
+                None,
+                Some(instance_id),
+                Some(command as u8),
+                Some(self.ops.now()),
+            )
+            .await;
+
+        Ok(msg_len)
+    }
+
     async fn fd_progress_download(&self, payload: &mut [u8]) -> Result<usize, MsgHandlerError> {
         // Get offset and length from ops first (this is async but outside the batch)
         // We need to do this before the batch because query_download_offset_and_length
@@ -1130,3 +1669,42 @@ impl<'a> FirmwareDeviceContext<'a> {
         self.ops
     }
 }
+
+// Returns the next data transfer handle and the transfer flag for a portion starting at `offset`.
+// The next data transfer handle is the offset of the following portion, or 0 after the last one.
+// The data transfer handle of a multipart response is the offset of the next portion
+// within the data. Validates the request and returns the offset of the requested portion.
+fn portion_offset(
+    transfer_op_flag: u8,
+    data_transfer_handle: u32,
+    total_len: usize,
+) -> Result<usize, FwUpdateCompletionCode> {
+    let offset = match TransferOperationFlag::try_from(transfer_op_flag) {
+        Ok(TransferOperationFlag::GetFirstPart) => 0,
+        Ok(TransferOperationFlag::GetNextPart) if data_transfer_handle != 0 => {
+            data_transfer_handle as usize
+        }
+        Ok(TransferOperationFlag::GetNextPart) => {
+            return Err(FwUpdateCompletionCode::InvalidTransferHandle)
+        }
+        Err(_) => return Err(FwUpdateCompletionCode::InvalidTransferOperationFlag),
+    };
+    if offset >= total_len {
+        return Err(FwUpdateCompletionCode::InvalidTransferHandle);
+    }
+    Ok(offset)
+}
+
+fn portion_transfer_flag(
+    offset: usize,
+    portion_len: usize,
+    total_len: usize,
+) -> (u32, TransferRespFlag) {
+    let end = offset + portion_len;
+    match (offset == 0, end >= total_len) {
+        (true, true) => (0, TransferRespFlag::StartAndEnd),
+        (true, false) => (end as u32, TransferRespFlag::Start),
+        (false, true) => (0, TransferRespFlag::End),
+        (false, false) => (end as u32, TransferRespFlag::Middle),
+    }
+}
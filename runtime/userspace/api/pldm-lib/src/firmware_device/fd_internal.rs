@@ -2,6 +2,7 @@
 
 use crate::control_context::Tid;
 use caliptra_mcu_pldm_common::message::firmware_update::get_status::GetStatusReasonCode;
+use caliptra_mcu_pldm_common::protocol::base::TransferRespFlag;
 use caliptra_mcu_pldm_common::protocol::firmware_update::{
     FirmwareDeviceState, PldmFdTime, UpdateOptionFlags, PLDM_FWUP_MAX_PADDING_SIZE,
 };
@@ -40,9 +41,16 @@ pub struct FdInternalInner {
     // Flags indicating update options.
     update_flags: UpdateOptionFlags,
 
+    // Set when the update mode was entered with RequestDownstreamDeviceUpdate.
+    downstream_update: bool,
+
     // Maximum transfer size allowed by the UA or platform implementation.
     max_xfer_size: u32,
 
+    // Number of RequestFirmwareData requests the FD may keep outstanding, negotiated
+    // from the UA's MaxOutstandingTransferRequests and FD_MAX_OUTSTANDING_XFER_REQ.
+    max_outstanding_xfer_req: u8,
+
     // Request details used for download/verify/apply operations.
     req: FdReq,
 
@@ -146,6 +154,12 @@ impl FdInternal {
         inner.max_xfer_size as usize
     }
 
+    pub async fn set_max_outstanding_xfer_req(&self, ua_max_outstanding_xfer_req: u8) {
+        let mut inner = self.inner.lock().await;
+        inner.max_outstanding_xfer_req =
+            ua_max_outstanding_xfer_req.clamp(1, crate::config::FD_MAX_OUTSTANDING_XFER_REQ as u8);
+    }
+
     pub async fn set_component(&self, comp: &FirmwareComponent) {
         let mut inner = self.inner.lock().await;
         inner.update_comp = comp.clone();
@@ -166,6 +180,16 @@ impl FdInternal {
         inner.update_flags
     }
 
+    pub async fn set_downstream_update(&self, downstream_update: bool) {
+        let mut inner = self.inner.lock().await;
+        inner.downstream_update = downstream_update;
+    }
+
+    pub async fn is_downstream_update(&self) -> bool {
+        let inner = self.inner.lock().await;
+        inner.downstream_update
+    }
+
     pub async fn set_fd_req(
         &self,
         req_state: FdReqState,
@@ -275,6 +299,22 @@ impl FdInternal {
         inner.initiator_mode_state = mode;
     }
 
+    pub async fn get_fd_learn_components_state(&self) -> Option<LearnComponentsState> {
+        let inner = self.inner.lock().await;
+        if let InitiatorModeState::LearnComponents(learn) = &inner.initiator_mode_state {
+            Some(*learn)
+        } else {
+            None
+        }
+    }
+
+    pub async fn set_fd_learn_components_state(&self, state: LearnComponentsState) {
+        let mut inner = self.inner.lock().await;
+        if let InitiatorModeState::LearnComponents(learn) = &mut inner.initiator_mode_state {
+            *learn = state;
+        }
+    }
+
     pub async fn set_fd_verify_progress(&self, progress: u8) {
         let mut inner = self.inner.lock().await;
         if let InitiatorModeState::Verify(verify) = &mut inner.initiator_mode_state {
@@ -432,6 +472,7 @@ impl FdInternal {
         let inner = self.inner.lock().await;
         super::transfer_session::TransferSession::new(
             inner.max_xfer_size,
+            inner.max_outstanding_xfer_req,
             inner.update_comp.clone(),
             inner.fd_t1_timeout,
             inner.fd_t2_retry_time,
@@ -478,7 +519,9 @@ impl FdInternalInner {
             reason: None,
             update_comp: FirmwareComponent::default(),
             update_flags: UpdateOptionFlags(0),
+            downstream_update: false,
             max_xfer_size,
+            max_outstanding_xfer_req: 1,
             req: FdReq::new(),
             initiator_mode_state: InitiatorModeState::Download(DownloadState::default()),
             _ua_address: None,
@@ -544,11 +587,54 @@ impl FdReq {
 
 #[derive(Debug)]
 pub enum InitiatorModeState {
+    LearnComponents(LearnComponentsState),
     Download(DownloadState),
     Verify(VerifyState),
     Apply(ApplyState),
 }
 
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LearnComponentsState {
+    // Package data is still to be retrieved with GetPackageData.
+    pub pkg_data_pending: bool,
+
+    // Metadata is still to be retrieved with GetMetaData.
+    pub metadata_pending: bool,
+
+    // Handle of the next portion, only valid after the first portion.
+    pub data_transfer_handle: Option<u32>,
+
+    // Offset of the next portion within the package data or metadata.
+    pub offset: usize,
+}
+
+impl LearnComponentsState {
+    pub fn is_pending(&self) -> bool {
+        self.pkg_data_pending || self.metadata_pending
+    }
+
+    // Advances the transfer past a received portion. Returns whether it was the last portion,
+    // or `None` if the transfer flag is out of sequence.
+    pub fn advance(
+        &mut self,
+        next_data_transfer_handle: u32,
+        transfer_flag: u8,
+        portion_len: usize,
+    ) -> Option<bool> {
+        let first = self.data_transfer_handle.is_none();
+        let complete = match TransferRespFlag::try_from(transfer_flag) {
+            Ok(TransferRespFlag::StartAndEnd) if first => true,
+            Ok(TransferRespFlag::Start) if first => false,
+            Ok(TransferRespFlag::Middle) if !first => false,
+            Ok(TransferRespFlag::End) if !first => true,
+            _ => return None,
+        };
+        self.offset += portion_len;
+        self.data_transfer_handle = Some(next_data_transfer_handle);
+        Some(complete)
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct DownloadState {
     pub offset: u32,
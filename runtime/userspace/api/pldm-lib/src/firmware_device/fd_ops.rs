@@ -5,7 +5,9 @@ use alloc::boxed::Box;
 use async_trait::async_trait;
 use caliptra_mcu_libsyscall_caliptra::DefaultSyscalls;
 use caliptra_mcu_pldm_common::message::firmware_update::apply_complete::ApplyResult;
+use caliptra_mcu_pldm_common::message::firmware_update::get_downstream_fw_params::DownstreamFirmwareParameters;
 use caliptra_mcu_pldm_common::message::firmware_update::get_status::ProgressPercent;
+use caliptra_mcu_pldm_common::message::firmware_update::query_downstream_identifiers::DownstreamDevice;
 use caliptra_mcu_pldm_common::message::firmware_update::request_cancel::{
     NonFunctioningComponentBitmap, NonFunctioningComponentIndication,
 };
@@ -30,6 +32,9 @@ pub enum FdOpsError {
     ApplyError,
     ActivateError,
     CancelUpdateError,
+    PackageDataError,
+    MetaDataError,
+    DownstreamDeviceError,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -258,6 +263,145 @@ pub trait FdOps {
         ))
     }
 
+    /// Indicates whether the device wants to retrieve the FirmwareDevicePackageData advertised
+    /// by the UA in RequestUpdate. When `true`, the device fetches it with GetPackageData
+    /// before the UA passes the component table.
+    ///
+    /// # Arguments
+    ///
+    /// * `pkg_data_len` - The length in bytes of the package data held by the UA.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Returns `true` if the device will send GetPackageData, otherwise `false`.
+    fn wants_package_data(&self, _pkg_data_len: usize) -> bool {
+        false
+    }
+
+    /// Handles a portion of the FirmwareDevicePackageData received with GetPackageData.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - The offset in bytes of this portion within the package data.
+    /// * `data` - A slice of bytes representing the package data portion.
+    /// * `complete` - Indicates if this is the last portion of the package data.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), FdOpsError>` - On success, returns `Ok(())`. On failure, returns an `FdOpsError`.
+    async fn handle_package_data(
+        &self,
+        _offset: usize,
+        _data: &[u8],
+        _complete: bool,
+    ) -> Result<(), FdOpsError> {
+        Err(FdOpsError::PackageDataError)
+    }
+
+    /// Retrieves the length of the device metadata the UA shall collect with GetDeviceMetaData
+    /// before activation, so that the device can restore its state afterwards.
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The length of the device metadata in bytes, or 0 if the device has none.
+    fn get_device_metadata_len(&self) -> usize {
+        0
+    }
+
+    /// Retrieves a portion of the device metadata.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - The offset in bytes within the device metadata.
+    /// * `data` - A mutable slice of bytes to store the device metadata portion.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<usize, FdOpsError>` - On success, returns the number of bytes copied into `data`.
+    ///   On failure, returns an `FdOpsError`.
+    async fn get_device_metadata(
+        &self,
+        _offset: usize,
+        _data: &mut [u8],
+    ) -> Result<usize, FdOpsError> {
+        Err(FdOpsError::MetaDataError)
+    }
+
+    /// Indicates whether the device needs the metadata previously collected by the UA, e.g.
+    /// after an activation reset. When `true`, the device fetches it with GetMetaData after
+    /// RequestUpdate.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Returns `true` if the device will send GetMetaData, otherwise `false`.
+    fn is_metadata_needed(&self) -> bool {
+        false
+    }
+
+    /// Handles a portion of the metadata received with GetMetaData.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - The offset in bytes of this portion within the metadata.
+    /// * `data` - A slice of bytes representing the metadata portion.
+    /// * `complete` - Indicates if this is the last portion of the metadata.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), FdOpsError>` - On success, returns `Ok(())`. On failure, returns an `FdOpsError`.
+    async fn restore_metadata(
+        &self,
+        _offset: usize,
+        _data: &[u8],
+        _complete: bool,
+    ) -> Result<(), FdOpsError> {
+        Err(FdOpsError::MetaDataError)
+    }
+
+    /// Retrieves the downstream devices that are updated through this device, e.g. the
+    /// components of a SoC image behind the MCU.
+    ///
+    /// # Arguments
+    ///
+    /// * `downstream_devices` - A mutable slice of `DownstreamDevice` to store the downstream device records.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<usize, FdOpsError>` - On success, returns the number of downstream devices, or 0 if
+    ///   the device has none. On failure, returns an `FdOpsError`.
+    fn get_downstream_devices(
+        &self,
+        _downstream_devices: &mut [DownstreamDevice],
+    ) -> Result<usize, FdOpsError> {
+        Ok(0)
+    }
+
+    /// Retrieves the firmware parameters of the downstream devices.
+    ///
+    /// # Arguments
+    ///
+    /// * `downstream_params` - A mutable reference to `DownstreamFirmwareParameters` to store the parameters.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), FdOpsError>` - On success, returns `Ok(())`. On failure, returns an `FdOpsError`.
+    fn get_downstream_firmware_parms(
+        &self,
+        _downstream_params: &mut DownstreamFirmwareParameters,
+    ) -> Result<(), FdOpsError> {
+        Err(FdOpsError::DownstreamDeviceError)
+    }
+
+    /// Prepares the downstream devices for an update requested with RequestDownstreamDeviceUpdate.
+    /// The components passed and updated during this session have `downstream_device_update` set.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), FdOpsError>` - On success, returns `Ok(())`. On failure, returns an `FdOpsError`.
+    async fn start_downstream_update(&self) -> Result<(), FdOpsError> {
+        Err(FdOpsError::DownstreamDeviceError)
+    }
+
     /// Retrieves the current timestamp in milliseconds.
     ///
     /// # Returns
@@ -7,21 +7,70 @@
 //! The session captures state at the start of a transfer and only requires
 //! mutex access at boundaries (start, end, cancellation check).
 
+use crate::config::{FD_MAX_OUTSTANDING_XFER_REQ, FD_MAX_XFER_SIZE};
 use caliptra_mcu_pldm_common::message::firmware_update::transfer_complete::TransferResult;
-use caliptra_mcu_pldm_common::protocol::firmware_update::{PldmFdTime, PLDM_FWUP_MAX_PADDING_SIZE};
+use caliptra_mcu_pldm_common::protocol::firmware_update::{
+    PldmFdTime, PLDM_FWUP_BASELINE_TRANSFER_SIZE, PLDM_FWUP_MAX_PADDING_SIZE,
+};
 use caliptra_mcu_pldm_common::util::fw_component::FirmwareComponent;
 use core::sync::atomic::{AtomicBool, Ordering};
 
 use super::fd_internal::FdReqState;
 
+/// Number of consecutive successful chunks after which the chunk size is doubled again.
+const CHUNK_GROW_THRESHOLD: u32 = 8;
+
+/// A RequestFirmwareData request that has been sent and not answered yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutstandingRequest {
+    /// PLDM instance ID of the request
+    pub instance_id: u8,
+    /// MCTP message tag the response will carry
+    pub tag: u8,
+    /// Requested image offset
+    pub offset: u32,
+    /// Requested length
+    pub length: u32,
+}
+
+/// Firmware data received ahead of the delivery offset.
+struct ReassemblySlot {
+    offset: u32,
+    len: u32,
+    data: [u8; FD_MAX_XFER_SIZE],
+}
+
+impl ReassemblySlot {
+    const fn new() -> Self {
+        Self {
+            offset: 0,
+            len: 0,
+            data: [0; FD_MAX_XFER_SIZE],
+        }
+    }
+
+    fn is_free(&self) -> bool {
+        self.len == 0
+    }
+
+    fn end(&self) -> u32 {
+        self.offset + self.len
+    }
+}
+
 /// Local state for an active download transfer.
 ///
 /// This struct holds all the state needed during a firmware download,
 /// allowing the hot path to avoid mutex acquisitions on every chunk.
+///
+/// Up to `window_size` RequestFirmwareData requests are kept outstanding. Responses may
+/// complete in any order; their data is held in reassembly slots and handed to the
+/// firmware device in offset order. The chunk size adapts to the link: it is halved when
+/// the UA asks for a retry and grows back towards `max_xfer_size` after a run of successes.
 pub struct TransferSession {
-    /// Current download offset
+    /// Current download offset, i.e. the next offset delivered to the firmware device
     pub offset: u32,
-    /// Current chunk length being requested
+    /// Length of the last chunk requested
     pub length: u32,
     /// Maximum transfer size allowed
     pub max_xfer_size: u32,
@@ -45,18 +94,33 @@ pub struct TransferSession {
     pub fd_t1_update_ts: PldmFdTime,
     /// Cached component info
     pub component: FirmwareComponent,
+    /// Number of requests that may be outstanding at once
+    pub window_size: u8,
+    /// Current chunk size, adapted between the baseline transfer size and `max_xfer_size`
+    pub chunk_size: u32,
+    /// End of the range the firmware device last asked for
+    request_end: u32,
+    /// Consecutive successful chunks at the current chunk size
+    success_streak: u32,
+    /// Outstanding requests, oldest first
+    outstanding: [Option<OutstandingRequest>; FD_MAX_OUTSTANDING_XFER_REQ],
+    /// Data received ahead of `offset`
+    slots: [ReassemblySlot; FD_MAX_OUTSTANDING_XFER_REQ],
 }
 
 impl TransferSession {
     /// Create a new transfer session from the given parameters.
     pub fn new(
         max_xfer_size: u32,
+        max_outstanding_xfer_req: u8,
         component: FirmwareComponent,
         fd_t1_timeout: PldmFdTime,
         fd_t2_retry_time: PldmFdTime,
         initial_instance_id: u8,
         now: PldmFdTime,
     ) -> Self {
+        // Each response must fit in a reassembly slot
+        let max_xfer_size = max_xfer_size.min(FD_MAX_XFER_SIZE as u32);
         Self {
             offset: 0,
             length: 0,
@@ -71,6 +135,12 @@ impl TransferSession {
             fd_t2_retry_time,
             fd_t1_update_ts: now,
             component,
+            window_size: max_outstanding_xfer_req.clamp(1, FD_MAX_OUTSTANDING_XFER_REQ as u8),
+            chunk_size: max_xfer_size,
+            request_end: 0,
+            success_streak: 0,
+            outstanding: [None; FD_MAX_OUTSTANDING_XFER_REQ],
+            slots: [const { ReassemblySlot::new() }; FD_MAX_OUTSTANDING_XFER_REQ],
         }
     }
 
@@ -80,25 +150,6 @@ impl TransferSession {
         self.instance_id
     }
 
-    /// Check if we should send a request based on current state and timing.
-    pub fn should_send_request(&self, now: PldmFdTime) -> bool {
-        match self.req_state {
-            FdReqState::Unused => false,
-            FdReqState::Ready => true,
-            FdReqState::Failed => false,
-            FdReqState::Sent => {
-                if let Some(sent_time) = self.sent_time {
-                    if now < sent_time {
-                        return false;
-                    }
-                    (now - sent_time) >= self.fd_t2_retry_time
-                } else {
-                    false
-                }
-            }
-        }
-    }
-
     /// Calculate the chunk parameters for a download request.
     ///
     /// Returns `Some((offset, length))` if valid, `None` if the request is invalid.
@@ -118,24 +169,172 @@ impl TransferSession {
         {
             return None;
         }
-        let chunk_size = requested_length.min(self.max_xfer_size);
+        let chunk_size = requested_length.min(self.chunk_size);
         Some((requested_offset, chunk_size))
     }
 
+    /// Set the range the firmware device wants next, as reported by
+    /// `FdOps::query_download_offset_and_length`.
+    ///
+    /// Buffered data outside the new range is dropped. Returns `false` if the range is invalid.
+    pub fn set_download_range(&mut self, requested_offset: u32, requested_length: u32) -> bool {
+        if self
+            .get_download_chunk(requested_offset, requested_length)
+            .is_none()
+        {
+            return false;
+        }
+        self.offset = requested_offset;
+        self.request_end = requested_offset + requested_length;
+        for slot in self.slots.iter_mut() {
+            if slot.end() <= self.offset || slot.offset >= self.request_end {
+                slot.len = 0;
+            }
+        }
+        true
+    }
+
+    /// Ranges that are either requested or already received but not delivered.
+    fn covered_ranges(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.outstanding
+            .iter()
+            .flatten()
+            .map(|req| (req.offset, req.offset + req.length))
+            .chain(
+                self.slots
+                    .iter()
+                    .filter(|slot| !slot.is_free())
+                    .map(|slot| (slot.offset, slot.end())),
+            )
+    }
+
+    /// Returns the next chunk to request, if the window has room.
+    ///
+    /// The chunk starts at the lowest offset of the requested range that is neither
+    /// outstanding nor buffered, so data dropped by a retry is requested again first.
+    pub fn next_request(&self) -> Option<(u32, u32)> {
+        // Outstanding requests and buffered chunks share the reassembly slots
+        if self.covered_ranges().count() >= self.window_size as usize {
+            return None;
+        }
+
+        let mut start = self.offset;
+        while let Some(end) = self
+            .covered_ranges()
+            .filter(|&(s, e)| s <= start && start < e)
+            .map(|(_, e)| e)
+            .max()
+        {
+            start = end;
+        }
+        if start >= self.request_end {
+            return None;
+        }
+
+        let next_covered = self
+            .covered_ranges()
+            .map(|(s, _)| s)
+            .filter(|&s| s > start)
+            .min()
+            .unwrap_or(u32::MAX);
+        let length = self
+            .chunk_size
+            .min(self.request_end - start)
+            .min(next_covered - start);
+        Some((start, length))
+    }
+
+    /// Record a request that has been sent.
+    pub fn track_request(&mut self, request: OutstandingRequest, now: PldmFdTime) {
+        if let Some(entry) = self.outstanding.iter_mut().find(|entry| entry.is_none()) {
+            *entry = Some(request);
+        }
+        self.length = request.length;
+        self.mark_sent(now);
+    }
+
+    /// Returns the oldest outstanding request.
+    pub fn oldest_request(&self) -> Option<OutstandingRequest> {
+        self.outstanding[0]
+    }
+
+    /// Remove the outstanding request with the given instance ID.
+    pub fn complete_request(&mut self, instance_id: u8) -> Option<OutstandingRequest> {
+        let index = self
+            .outstanding
+            .iter()
+            .position(|entry| entry.is_some_and(|req| req.instance_id == instance_id))?;
+        let request = self.outstanding[index];
+        self.outstanding.copy_within(index + 1.., index);
+        self.outstanding[FD_MAX_OUTSTANDING_XFER_REQ - 1] = None;
+        if self.outstanding[0].is_none() {
+            self.req_state = FdReqState::Ready;
+            self.sent_time = None;
+        }
+        request
+    }
+
+    /// Buffer firmware data received for `offset` until it can be delivered in order.
+    pub fn store_chunk(&mut self, offset: u32, data: &[u8]) {
+        let len = data.len().min(FD_MAX_XFER_SIZE);
+        if len == 0 || offset + len as u32 <= self.offset || offset >= self.request_end {
+            return;
+        }
+        if let Some(slot) = self.slots.iter_mut().find(|slot| slot.is_free()) {
+            slot.offset = offset;
+            slot.len = len as u32;
+            slot.data[..len].copy_from_slice(&data[..len]);
+        }
+    }
+
+    /// Returns the buffered data at the current offset, limited to the requested range.
+    pub fn deliverable_chunk(&self) -> Option<&[u8]> {
+        if self.offset >= self.request_end {
+            return None;
+        }
+        let slot = self.slots.iter().find(|slot| {
+            !slot.is_free() && slot.offset <= self.offset && self.offset < slot.end()
+        })?;
+        let start = (self.offset - slot.offset) as usize;
+        let end = (slot.end().min(self.request_end) - slot.offset) as usize;
+        Some(&slot.data[start..end])
+    }
+
+    /// Advance the offset past `len` delivered bytes and release fully delivered slots.
+    pub fn consume_delivered(&mut self, len: u32) {
+        self.offset += len;
+        for slot in self.slots.iter_mut() {
+            if slot.end() <= self.offset {
+                slot.len = 0;
+            }
+        }
+    }
+
+    /// Record a successful response, growing the chunk size after a run of successes.
+    pub fn record_success(&mut self) {
+        self.success_streak += 1;
+        if self.success_streak >= CHUNK_GROW_THRESHOLD && self.chunk_size < self.max_xfer_size {
+            self.chunk_size = (self.chunk_size * 2).min(self.max_xfer_size);
+            self.success_streak = 0;
+        }
+    }
+
+    /// Record a retry of a request of `length` bytes, halving the chunk size.
+    ///
+    /// The chunk size is derived from the retried request, so a burst of retries for the
+    /// requests of one window halves it once instead of once per request.
+    pub fn record_retry(&mut self, length: u32) {
+        self.success_streak = 0;
+        self.chunk_size = (length / 2)
+            .max(PLDM_FWUP_BASELINE_TRANSFER_SIZE as u32)
+            .min(self.chunk_size);
+    }
+
     /// Mark the request as sent.
-    pub fn mark_sent(&mut self, now: PldmFdTime, command: u8) {
+    pub fn mark_sent(&mut self, now: PldmFdTime) {
         self.req_state = FdReqState::Sent;
         self.sent_time = Some(now);
         self.fd_t1_update_ts = now;
-        let _ = command; // Command tracking could be added if needed
-    }
-
-    /// Mark the transfer as ready for next chunk.
-    pub fn mark_ready_for_next(&mut self) {
-        self.req_state = FdReqState::Ready;
-        self.complete = false;
-        self.result = None;
-        self.sent_time = None;
     }
 
     /// Mark the transfer as complete with the given result.
@@ -203,3 +402,159 @@ impl Default for CancellationFlag {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::MCTP_RX_BUFFER_SIZE;
+    use caliptra_mcu_pldm_common::message::firmware_update::request_fw_data::RequestFirmwareDataResponseFixed;
+    use caliptra_mcu_pldm_common::util::mctp_transport::MCTP_PLDM_MSG_HDR_LEN;
+    use core::mem::size_of;
+
+    fn session(max_xfer_size: u32, window: u8, image_size: u32) -> TransferSession {
+        let component = FirmwareComponent {
+            comp_image_size: Some(image_size),
+            ..Default::default()
+        };
+        TransferSession::new(max_xfer_size, window, component, 1000, 100, 0, 0)
+    }
+
+    fn issue(session: &mut TransferSession) -> OutstandingRequest {
+        let (offset, length) = session.next_request().unwrap();
+        let request = OutstandingRequest {
+            instance_id: session.alloc_next_instance_id(),
+            tag: 0,
+            offset,
+            length,
+        };
+        session.track_request(request, 0);
+        request
+    }
+
+    #[test]
+    fn test_window_fills_sequential_chunks() {
+        let mut session = session(64, 3, 1024);
+        assert!(session.set_download_range(0, 1024));
+        assert_eq!(issue(&mut session).offset, 0);
+        assert_eq!(issue(&mut session).offset, 64);
+        assert_eq!(issue(&mut session).offset, 128);
+        assert_eq!(session.next_request(), None);
+        assert_eq!(session.req_state, FdReqState::Sent);
+    }
+
+    #[test]
+    fn test_out_of_order_reassembly() {
+        let mut session = session(64, 2, 1024);
+        assert!(session.set_download_range(0, 1024));
+        let first = issue(&mut session);
+        let second = issue(&mut session);
+
+        // The second chunk completes first and is held back
+        session.complete_request(second.instance_id).unwrap();
+        session.store_chunk(second.offset, &[2u8; 64]);
+        assert!(session.deliverable_chunk().is_none());
+
+        session.complete_request(first.instance_id).unwrap();
+        session.store_chunk(first.offset, &[1u8; 64]);
+        assert_eq!(session.deliverable_chunk().unwrap(), &[1u8; 64]);
+        session.consume_delivered(64);
+        assert_eq!(session.deliverable_chunk().unwrap(), &[2u8; 64]);
+        session.consume_delivered(64);
+        assert!(session.deliverable_chunk().is_none());
+        assert_eq!(session.offset, 128);
+        assert_eq!(session.req_state, FdReqState::Ready);
+    }
+
+    #[test]
+    fn test_retry_rerequests_gap_with_smaller_chunk() {
+        let mut session = session(128, 2, 1024);
+        assert!(session.set_download_range(0, 1024));
+        let first = issue(&mut session);
+        let second = issue(&mut session);
+
+        session.complete_request(first.instance_id).unwrap();
+        session.record_retry(first.length);
+        assert_eq!(session.chunk_size, 64);
+
+        // The dropped range is requested again before anything past the window
+        assert_eq!(session.next_request(), Some((0, 64)));
+        session.complete_request(second.instance_id).unwrap();
+        session.store_chunk(second.offset, &[0u8; 128]);
+        assert_eq!(session.next_request(), Some((0, 64)));
+    }
+
+    #[test]
+    fn test_chunk_size_grows_after_successes() {
+        let mut session = session(256, 1, 4096);
+        session.record_retry(256);
+        session.record_retry(128);
+        assert_eq!(session.chunk_size, 64);
+        for _ in 0..CHUNK_GROW_THRESHOLD {
+            session.record_success();
+        }
+        assert_eq!(session.chunk_size, 128);
+        for _ in 0..CHUNK_GROW_THRESHOLD {
+            session.record_success();
+        }
+        assert_eq!(session.chunk_size, 256);
+        session.record_retry(256);
+        session.record_retry(128);
+        session.record_retry(64);
+        session.record_retry(32);
+        assert_eq!(session.chunk_size, PLDM_FWUP_BASELINE_TRANSFER_SIZE as u32);
+    }
+
+    #[test]
+    fn test_window_retry_burst_halves_once() {
+        let mut session = session(256, 3, 4096);
+        assert!(session.set_download_range(0, 4096));
+        let requests = [
+            issue(&mut session),
+            issue(&mut session),
+            issue(&mut session),
+        ];
+
+        // Every request of the window is answered with a retry
+        for request in requests {
+            session.complete_request(request.instance_id).unwrap();
+            session.record_retry(request.length);
+        }
+        assert_eq!(session.chunk_size, 128);
+        assert_eq!(session.next_request(), Some((0, 128)));
+
+        // Full-size requests resume after a run of successes
+        for _ in 0..CHUNK_GROW_THRESHOLD {
+            let request = issue(&mut session);
+            session.complete_request(request.instance_id).unwrap();
+            session.store_chunk(request.offset, &[0u8; 256][..request.length as usize]);
+            session.record_success();
+            while let Some(data) = session.deliverable_chunk() {
+                let len = data.len() as u32;
+                session.consume_delivered(len);
+            }
+        }
+        assert_eq!(session.next_request().map(|(_, length)| length), Some(256));
+    }
+
+    #[test]
+    fn test_window_clamped_to_rx_buffer() {
+        let session = session(64, 8, 1024);
+        assert_eq!(session.window_size as usize, FD_MAX_OUTSTANDING_XFER_REQ);
+
+        // Responses to the whole window fit in the MCTP driver buffer
+        let response_size = MCTP_PLDM_MSG_HDR_LEN
+            + size_of::<RequestFirmwareDataResponseFixed>()
+            + FD_MAX_XFER_SIZE;
+        assert!(FD_MAX_OUTSTANDING_XFER_REQ * response_size <= MCTP_RX_BUFFER_SIZE);
+    }
+
+    #[test]
+    fn test_request_bounded_by_range() {
+        let mut session = session(64, 4, 100);
+        assert!(session.set_download_range(0, 100));
+        assert_eq!(issue(&mut session).length, 64);
+        assert_eq!(issue(&mut session).length, 36);
+        assert_eq!(session.next_request(), None);
+        assert!(!session.set_download_range(200, 64));
+    }
+}
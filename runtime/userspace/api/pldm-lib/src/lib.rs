@@ -8,5 +8,6 @@ pub mod control_context;
 pub mod daemon;
 pub mod error;
 pub mod firmware_device;
+pub mod platform;
 pub mod timer;
 pub mod transport;
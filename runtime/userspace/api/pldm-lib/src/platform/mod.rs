@@ -0,0 +1,4 @@
+// Licensed under the Apache-2.0 license
+
+pub mod platform_context;
+pub mod platform_ops;
@@ -0,0 +1,947 @@
+// Licensed under the Apache-2.0 license
+
+use crate::cmd_interface::generate_failure_response;
+use crate::config::{PLATFORM_MAX_EVENT_DATA_SIZE, PLATFORM_MAX_PDR_SIZE};
+use crate::error::MsgHandlerError;
+use crate::platform::platform_ops::{PlatformOps, PlatformOpsError};
+use caliptra_mcu_pldm_common::codec::PldmCodec;
+use caliptra_mcu_pldm_common::message::platform::get_pdr::{
+    GetPdrRequest, GetPdrResponse, MAX_PDR_TRANSFER_SIZE,
+};
+use caliptra_mcu_pldm_common::message::platform::get_pdr_repo_info::{
+    GetPdrRepositoryInfoRequest, GetPdrRepositoryInfoResponse, PdrRepositoryInfo,
+};
+use caliptra_mcu_pldm_common::message::platform::get_sensor_reading::{
+    GetSensorReadingRequest, GetSensorReadingResponse,
+};
+use caliptra_mcu_pldm_common::message::platform::get_state_sensor_readings::{
+    GetStateSensorReadingsRequest, GetStateSensorReadingsResponse,
+};
+use caliptra_mcu_pldm_common::message::platform::platform_event_msg::{
+    PlatformEventMessageRequest, PlatformEventMessageResponse, MAX_EVENT_DATA_SIZE,
+};
+use caliptra_mcu_pldm_common::message::platform::poll_platform_event_msg::{
+    PollForPlatformEventMessageRequest, PollForPlatformEventMessageResponse,
+};
+use caliptra_mcu_pldm_common::protocol::base::{
+    PldmBaseCompletionCode, TransferOperationFlag, TransferRespFlag,
+};
+use caliptra_mcu_pldm_common::protocol::platform::{
+    event_data_checksum, pdr_transfer_crc, GetPdrCompletionCode, PdrHeader,
+    PlatformEventCompletionCode, PollEventCompletionCode, PollTransferOperationFlag,
+    SensorCompletionCode, StateField, MAX_COMPOSITE_SENSOR_COUNT, PLATFORM_EVENT_FORMAT_VERSION,
+    POLL_EVENT_ID_ACK, POLL_EVENT_ID_NONE,
+};
+
+/// Portion of a record or event data returned by one part of a multipart transfer.
+struct TransferPart {
+    offset: usize,
+    len: usize,
+    next_handle: u32,
+    flag: TransferRespFlag,
+}
+
+impl TransferPart {
+    // Data transfer handles are the byte offset of the next part within the record or event data.
+    fn new(offset: usize, max_len: usize, total_len: usize) -> Self {
+        let len = max_len.min(total_len - offset);
+        let end = offset + len >= total_len;
+        let flag = match (offset == 0, end) {
+            (true, true) => TransferRespFlag::StartAndEnd,
+            (true, false) => TransferRespFlag::Start,
+            (false, true) => TransferRespFlag::End,
+            (false, false) => TransferRespFlag::Middle,
+        };
+        TransferPart {
+            offset,
+            len,
+            next_handle: if end { 0 } else { (offset + len) as u32 },
+            flag,
+        }
+    }
+
+    fn data<'a>(&self, data: &'a [u8]) -> &'a [u8] {
+        &data[self.offset..self.offset + self.len]
+    }
+}
+
+pub struct PlatformContext<'a> {
+    ops: &'a dyn PlatformOps,
+}
+
+impl<'a> PlatformContext<'a> {
+    pub fn new(ops: &'a dyn PlatformOps) -> Self {
+        Self { ops }
+    }
+
+    pub async fn get_pdr_repository_info_rsp(
+        &self,
+        payload: &mut [u8],
+    ) -> Result<usize, MsgHandlerError> {
+        let req = GetPdrRepositoryInfoRequest::decode(payload).map_err(MsgHandlerError::Codec)?;
+
+        let mut info = PdrRepositoryInfo::default();
+        if self.ops.get_pdr_repository_info(&mut info).await.is_err() {
+            return generate_failure_response(payload, PldmBaseCompletionCode::Error as u8);
+        }
+
+        let resp = GetPdrRepositoryInfoResponse::new(
+            req.hdr.instance_id(),
+            PldmBaseCompletionCode::Success as u8,
+            &info,
+        );
+        resp.encode(payload).map_err(MsgHandlerError::Codec)
+    }
+
+    pub async fn get_pdr_rsp(&self, payload: &mut [u8]) -> Result<usize, MsgHandlerError> {
+        let req = GetPdrRequest::decode(payload).map_err(MsgHandlerError::Codec)?;
+
+        let transfer_op_flag = match TransferOperationFlag::try_from(req.transfer_op_flag) {
+            Ok(flag) => flag,
+            Err(_) => {
+                return generate_failure_response(
+                    payload,
+                    GetPdrCompletionCode::InvalidTransferOperationFlag as u8,
+                )
+            }
+        };
+
+        let mut record = [0u8; PLATFORM_MAX_PDR_SIZE];
+        let record_info = match self.ops.get_pdr(req.record_handle, &mut record).await {
+            Ok(info) if info.size <= record.len() => info,
+            Err(PlatformOpsError::InvalidRecordHandle) => {
+                return generate_failure_response(
+                    payload,
+                    GetPdrCompletionCode::InvalidRecordHandle as u8,
+                )
+            }
+            Err(PlatformOpsError::RepositoryUpdateInProgress) => {
+                return generate_failure_response(
+                    payload,
+                    GetPdrCompletionCode::RepositoryUpdateInProgress as u8,
+                )
+            }
+            _ => return generate_failure_response(payload, PldmBaseCompletionCode::Error as u8),
+        };
+        let record = &record[..record_info.size];
+
+        let offset = match transfer_op_flag {
+            TransferOperationFlag::GetFirstPart => 0,
+            TransferOperationFlag::GetNextPart => {
+                // Later parts must refer to the same revision of the record.
+                let record_change_number = PdrHeader::decode(record)
+                    .map(|hdr| hdr.record_change_number)
+                    .unwrap_or(0);
+                if record_change_number != req.record_change_number {
+                    return generate_failure_response(
+                        payload,
+                        GetPdrCompletionCode::InvalidRecordChangeNumber as u8,
+                    );
+                }
+                let offset = req.data_transfer_handle as usize;
+                if offset == 0 || offset >= record.len() {
+                    return generate_failure_response(
+                        payload,
+                        GetPdrCompletionCode::InvalidDataTransferHandle as u8,
+                    );
+                }
+                offset
+            }
+        };
+
+        let max_len = (req.request_count as usize).min(MAX_PDR_TRANSFER_SIZE);
+        let part = TransferPart::new(offset, max_len, record.len());
+        let transfer_crc = if part.flag == TransferRespFlag::End {
+            pdr_transfer_crc(record)
+        } else {
+            0
+        };
+
+        let resp = GetPdrResponse::new(
+            req.hdr.instance_id(),
+            PldmBaseCompletionCode::Success as u8,
+            record_info.next_record_handle,
+            part.next_handle,
+            part.flag,
+            part.data(record),
+            transfer_crc,
+        )
+        .map_err(MsgHandlerError::PldmCommon)?;
+
+        match resp.encode(payload) {
+            Ok(bytes) => Ok(bytes),
+            Err(_) => {
+                generate_failure_response(payload, PldmBaseCompletionCode::InvalidLength as u8)
+            }
+        }
+    }
+
+    pub async fn get_sensor_reading_rsp(
+        &self,
+        payload: &mut [u8],
+    ) -> Result<usize, MsgHandlerError> {
+        let req = GetSensorReadingRequest::decode(payload).map_err(MsgHandlerError::Codec)?;
+
+        let reading = match self
+            .ops
+            .get_sensor_reading(req.sensor_id, req.rearm_event_state != 0)
+            .await
+        {
+            Ok(reading) => reading,
+            Err(e) => return generate_failure_response(payload, sensor_completion_code(e)),
+        };
+
+        let resp = GetSensorReadingResponse::new(
+            req.hdr.instance_id(),
+            PldmBaseCompletionCode::Success as u8,
+            &reading,
+        );
+        resp.encode(payload).map_err(MsgHandlerError::Codec)
+    }
+
+    pub async fn get_state_sensor_readings_rsp(
+        &self,
+        payload: &mut [u8],
+    ) -> Result<usize, MsgHandlerError> {
+        let req = GetStateSensorReadingsRequest::decode(payload).map_err(MsgHandlerError::Codec)?;
+
+        let mut state_fields = [StateField::default(); MAX_COMPOSITE_SENSOR_COUNT];
+        let count = match self
+            .ops
+            .get_state_sensor_readings(req.sensor_id, req.sensor_rearm, &mut state_fields)
+            .await
+        {
+            Ok(count) if count > 0 && count <= MAX_COMPOSITE_SENSOR_COUNT => count,
+            Ok(_) => {
+                return generate_failure_response(payload, PldmBaseCompletionCode::Error as u8)
+            }
+            Err(e) => return generate_failure_response(payload, sensor_completion_code(e)),
+        };
+
+        let resp = GetStateSensorReadingsResponse::new(
+            req.hdr.instance_id(),
+            PldmBaseCompletionCode::Success as u8,
+            &state_fields[..count],
+        )
+        .map_err(MsgHandlerError::PldmCommon)?;
+        resp.encode(payload).map_err(MsgHandlerError::Codec)
+    }
+
+    /// `req_len` is the length of the PLDM message in `payload`, since the event data
+    /// runs to the end of the request.
+    pub async fn platform_event_message_rsp(
+        &self,
+        payload: &mut [u8],
+        req_len: usize,
+    ) -> Result<usize, MsgHandlerError> {
+        let req = match payload
+            .get(..req_len)
+            .map(PlatformEventMessageRequest::decode)
+        {
+            Some(Ok(req)) => req,
+            _ => {
+                return generate_failure_response(
+                    payload,
+                    PldmBaseCompletionCode::InvalidLength as u8,
+                )
+            }
+        };
+
+        if req.fixed.format_version != PLATFORM_EVENT_FORMAT_VERSION {
+            return generate_failure_response(
+                payload,
+                PlatformEventCompletionCode::UnsupportedEventFormatVersion as u8,
+            );
+        }
+
+        let status = match self
+            .ops
+            .handle_platform_event(req.fixed.tid, req.fixed.event_class, req.event_data())
+            .await
+        {
+            Ok(status) => status,
+            Err(_) => {
+                return generate_failure_response(payload, PldmBaseCompletionCode::Error as u8)
+            }
+        };
+
+        let resp = PlatformEventMessageResponse::new(
+            req.fixed.hdr.instance_id(),
+            PldmBaseCompletionCode::Success as u8,
+            status,
+        );
+        resp.encode(payload).map_err(MsgHandlerError::Codec)
+    }
+
+    pub async fn poll_for_platform_event_message_rsp(
+        &self,
+        payload: &mut [u8],
+        tid: u8,
+    ) -> Result<usize, MsgHandlerError> {
+        let req =
+            PollForPlatformEventMessageRequest::decode(payload).map_err(MsgHandlerError::Codec)?;
+        let instance_id = req.hdr.instance_id();
+
+        if req.format_version != PLATFORM_EVENT_FORMAT_VERSION {
+            return generate_failure_response(payload, PldmBaseCompletionCode::InvalidData as u8);
+        }
+
+        let transfer_op_flag = match PollTransferOperationFlag::try_from(req.transfer_op_flag) {
+            Ok(flag) => flag,
+            Err(_) => {
+                return generate_failure_response(
+                    payload,
+                    PollEventCompletionCode::InvalidTransferOperationFlag as u8,
+                )
+            }
+        };
+
+        if transfer_op_flag == PollTransferOperationFlag::AcknowledgementOnly {
+            return match self.ops.acknowledge_event(req.event_id_to_ack).await {
+                Ok(()) => PollForPlatformEventMessageResponse::new(
+                    instance_id,
+                    PldmBaseCompletionCode::Success as u8,
+                    tid,
+                    POLL_EVENT_ID_ACK,
+                )
+                .encode(payload)
+                .map_err(MsgHandlerError::Codec),
+                Err(PlatformOpsError::InvalidEventId) => generate_failure_response(
+                    payload,
+                    PollEventCompletionCode::EventIdNotValid as u8,
+                ),
+                Err(_) => generate_failure_response(payload, PldmBaseCompletionCode::Error as u8),
+            };
+        }
+
+        let mut event_data = [0u8; PLATFORM_MAX_EVENT_DATA_SIZE];
+        let event = match self.ops.peek_event(&mut event_data).await {
+            Ok(Some(event)) if event.size <= event_data.len() => Some(event),
+            Ok(Some(_)) | Err(_) => {
+                return generate_failure_response(payload, PldmBaseCompletionCode::Error as u8)
+            }
+            Ok(None) => None,
+        };
+
+        let offset = match (transfer_op_flag, event) {
+            (PollTransferOperationFlag::GetFirstPart, _) => 0,
+            (PollTransferOperationFlag::GetNextPart, Some(event)) => {
+                // Later parts must refer to the event whose transfer is in progress.
+                if event.event_id != req.event_id_to_ack {
+                    return generate_failure_response(
+                        payload,
+                        PollEventCompletionCode::EventIdNotValid as u8,
+                    );
+                }
+                let offset = req.data_transfer_handle as usize;
+                if offset == 0 || offset >= event.size {
+                    return generate_failure_response(
+                        payload,
+                        PollEventCompletionCode::InvalidDataTransferHandle as u8,
+                    );
+                }
+                offset
+            }
+            _ => {
+                return generate_failure_response(
+                    payload,
+                    PollEventCompletionCode::InvalidDataTransferHandle as u8,
+                )
+            }
+        };
+
+        let resp = match event {
+            None => PollForPlatformEventMessageResponse::new(
+                instance_id,
+                PldmBaseCompletionCode::Success as u8,
+                tid,
+                POLL_EVENT_ID_NONE,
+            ),
+            Some(event) => {
+                let event_data = &event_data[..event.size];
+                let part = TransferPart::new(offset, MAX_EVENT_DATA_SIZE, event_data.len());
+                let checksum = if part.flag == TransferRespFlag::End {
+                    event_data_checksum(event_data)
+                } else {
+                    0
+                };
+                PollForPlatformEventMessageResponse::new(
+                    instance_id,
+                    PldmBaseCompletionCode::Success as u8,
+                    tid,
+                    event.event_id,
+                )
+                .with_event_data(
+                    part.next_handle,
+                    part.flag,
+                    event.event_class,
+                    part.data(event_data),
+                    checksum,
+                )
+                .map_err(MsgHandlerError::PldmCommon)?
+            }
+        };
+
+        match resp.encode(payload) {
+            Ok(bytes) => Ok(bytes),
+            Err(_) => {
+                generate_failure_response(payload, PldmBaseCompletionCode::InvalidLength as u8)
+            }
+        }
+    }
+}
+
+fn sensor_completion_code(err: PlatformOpsError) -> u8 {
+    match err {
+        PlatformOpsError::InvalidSensorId => SensorCompletionCode::InvalidSensorId as u8,
+        PlatformOpsError::RearmUnavailable => {
+            SensorCompletionCode::RearmUnavailableInPresentState as u8
+        }
+        _ => PldmBaseCompletionCode::Error as u8,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::platform::platform_ops::{PdrRecordInfo, PendingEvent};
+    use async_trait::async_trait;
+    use caliptra_mcu_pldm_common::message::platform::get_sensor_reading::NumericSensorReading;
+    use caliptra_mcu_pldm_common::protocol::base::{
+        PldmFailureResponse, PldmMsgType, PldmSupportedType,
+    };
+    use caliptra_mcu_pldm_common::protocol::platform::{
+        PdrRepositoryState, PdrType, PlatformCmd, PlatformEventClass, PlatformEventStatus,
+        SensorEventMessageEnable, SensorOperationalState, SensorReading, SensorState,
+    };
+    use core::cell::Cell;
+    use futures::executor::block_on;
+
+    const PAY_LOAD_BUFFER_LEN: usize = 256;
+    const TEST_TID: u8 = 2;
+    const NUMERIC_SENSOR_ID: u16 = 1;
+    const STATE_SENSOR_ID: u16 = 2;
+    const RECORD_HANDLE: u32 = 1;
+    const RECORD_CHANGE_NUMBER: u16 = 3;
+    // Larger than MAX_PDR_TRANSFER_SIZE so that GetPDR needs two parts
+    const RECORD_LEN: usize = 200;
+    const EVENT_ID: u16 = 0x10;
+    const EVENT_DATA: [u8; 4] = [0xDE, 0xAD, 0xBE, 0xEF];
+
+    fn test_record() -> [u8; RECORD_LEN] {
+        let mut record = [0u8; RECORD_LEN];
+        record[..4].copy_from_slice(&RECORD_HANDLE.to_le_bytes());
+        record[4] = 1;
+        record[5] = PdrType::NumericSensor as u8;
+        record[6..8].copy_from_slice(&RECORD_CHANGE_NUMBER.to_le_bytes());
+        record[8..10].copy_from_slice(&((RECORD_LEN - 10) as u16).to_le_bytes());
+        for (i, byte) in record[10..].iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        record
+    }
+
+    fn numeric_reading() -> NumericSensorReading {
+        NumericSensorReading {
+            sensor_op_state: SensorOperationalState::Enabled,
+            event_message_enable: SensorEventMessageEnable::EventsDisabled,
+            present_state: SensorState::Normal,
+            previous_state: SensorState::Normal,
+            event_state: SensorState::Normal,
+            present_reading: SensorReading::Sint16(45),
+        }
+    }
+
+    fn state_fields() -> [StateField; 2] {
+        [
+            StateField::new(SensorOperationalState::Enabled, 1, 1, 1),
+            StateField::new(SensorOperationalState::Enabled, 2, 1, 2),
+        ]
+    }
+
+    struct TestPlatformOps {
+        event_pending: Cell<bool>,
+    }
+
+    impl TestPlatformOps {
+        fn new() -> Self {
+            Self {
+                event_pending: Cell::new(true),
+            }
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl PlatformOps for TestPlatformOps {
+        async fn get_pdr_repository_info(
+            &self,
+            info: &mut PdrRepositoryInfo,
+        ) -> Result<(), PlatformOpsError> {
+            *info = PdrRepositoryInfo::new(
+                PdrRepositoryState::Available,
+                1,
+                RECORD_LEN as u32,
+                RECORD_LEN as u32,
+            );
+            Ok(())
+        }
+
+        async fn get_pdr(
+            &self,
+            record_handle: u32,
+            record: &mut [u8],
+        ) -> Result<PdrRecordInfo, PlatformOpsError> {
+            if record_handle != 0 && record_handle != RECORD_HANDLE {
+                return Err(PlatformOpsError::InvalidRecordHandle);
+            }
+            record[..RECORD_LEN].copy_from_slice(&test_record());
+            Ok(PdrRecordInfo {
+                size: RECORD_LEN,
+                next_record_handle: 0,
+            })
+        }
+
+        async fn get_sensor_reading(
+            &self,
+            sensor_id: u16,
+            _rearm_event_state: bool,
+        ) -> Result<NumericSensorReading, PlatformOpsError> {
+            match sensor_id {
+                NUMERIC_SENSOR_ID => Ok(numeric_reading()),
+                _ => Err(PlatformOpsError::InvalidSensorId),
+            }
+        }
+
+        async fn get_state_sensor_readings(
+            &self,
+            sensor_id: u16,
+            _sensor_rearm: u8,
+            fields: &mut [StateField],
+        ) -> Result<usize, PlatformOpsError> {
+            if sensor_id != STATE_SENSOR_ID {
+                return Err(PlatformOpsError::InvalidSensorId);
+            }
+            let readings = state_fields();
+            fields[..readings.len()].copy_from_slice(&readings);
+            Ok(readings.len())
+        }
+
+        async fn peek_event(
+            &self,
+            event_data: &mut [u8],
+        ) -> Result<Option<PendingEvent>, PlatformOpsError> {
+            if !self.event_pending.get() {
+                return Ok(None);
+            }
+            event_data[..EVENT_DATA.len()].copy_from_slice(&EVENT_DATA);
+            Ok(Some(PendingEvent {
+                event_id: EVENT_ID,
+                event_class: PlatformEventClass::SensorEvent,
+                size: EVENT_DATA.len(),
+            }))
+        }
+
+        async fn acknowledge_event(&self, event_id: u16) -> Result<(), PlatformOpsError> {
+            if !self.event_pending.get() || event_id != EVENT_ID {
+                return Err(PlatformOpsError::InvalidEventId);
+            }
+            self.event_pending.set(false);
+            Ok(())
+        }
+    }
+
+    fn construct_request<T: PldmCodec>(buf: &mut [u8], request_msg: T) -> usize {
+        request_msg.encode(buf).unwrap()
+    }
+
+    fn validate_response<T: PldmCodec + PartialEq + core::fmt::Debug>(
+        buf: &[u8],
+        expected_rsp_msg: T,
+    ) {
+        let rsp = T::decode(buf).unwrap();
+        assert_eq!(rsp, expected_rsp_msg);
+    }
+
+    fn failure_response(
+        instance_id: u8,
+        cmd: PlatformCmd,
+        completion_code: u8,
+    ) -> PldmFailureResponse {
+        PldmFailureResponse::new(
+            instance_id,
+            PldmSupportedType::Platform,
+            cmd as u8,
+            completion_code,
+        )
+    }
+
+    #[test]
+    fn test_get_pdr_repository_info() {
+        let ops = TestPlatformOps::new();
+        let platform_ctx = PlatformContext::new(&ops);
+        let mut msg_buf = [0u8; PAY_LOAD_BUFFER_LEN];
+
+        construct_request(
+            &mut msg_buf,
+            GetPdrRepositoryInfoRequest::new(0x01, PldmMsgType::Request),
+        );
+        let resp_len = block_on(platform_ctx.get_pdr_repository_info_rsp(&mut msg_buf)).unwrap();
+        validate_response(
+            &msg_buf[..resp_len],
+            GetPdrRepositoryInfoResponse::new(
+                0x01,
+                PldmBaseCompletionCode::Success as u8,
+                &PdrRepositoryInfo::new(
+                    PdrRepositoryState::Available,
+                    1,
+                    RECORD_LEN as u32,
+                    RECORD_LEN as u32,
+                ),
+            ),
+        );
+    }
+
+    #[test]
+    fn test_get_pdr_multipart() {
+        let ops = TestPlatformOps::new();
+        let platform_ctx = PlatformContext::new(&ops);
+        let mut msg_buf = [0u8; PAY_LOAD_BUFFER_LEN];
+        let record = test_record();
+
+        construct_request(
+            &mut msg_buf,
+            GetPdrRequest::new(
+                0x01,
+                PldmMsgType::Request,
+                0,
+                0,
+                TransferOperationFlag::GetFirstPart,
+                RECORD_LEN as u16,
+                0,
+            ),
+        );
+        let resp_len = block_on(platform_ctx.get_pdr_rsp(&mut msg_buf)).unwrap();
+        validate_response(
+            &msg_buf[..resp_len],
+            GetPdrResponse::new(
+                0x01,
+                PldmBaseCompletionCode::Success as u8,
+                0,
+                MAX_PDR_TRANSFER_SIZE as u32,
+                TransferRespFlag::Start,
+                &record[..MAX_PDR_TRANSFER_SIZE],
+                0,
+            )
+            .unwrap(),
+        );
+
+        // The last part carries the CRC over the whole record
+        construct_request(
+            &mut msg_buf,
+            GetPdrRequest::new(
+                0x02,
+                PldmMsgType::Request,
+                RECORD_HANDLE,
+                MAX_PDR_TRANSFER_SIZE as u32,
+                TransferOperationFlag::GetNextPart,
+                RECORD_LEN as u16,
+                RECORD_CHANGE_NUMBER,
+            ),
+        );
+        let resp_len = block_on(platform_ctx.get_pdr_rsp(&mut msg_buf)).unwrap();
+        validate_response(
+            &msg_buf[..resp_len],
+            GetPdrResponse::new(
+                0x02,
+                PldmBaseCompletionCode::Success as u8,
+                0,
+                0,
+                TransferRespFlag::End,
+                &record[MAX_PDR_TRANSFER_SIZE..],
+                pdr_transfer_crc(&record),
+            )
+            .unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_get_pdr_errors() {
+        let ops = TestPlatformOps::new();
+        let platform_ctx = PlatformContext::new(&ops);
+        let mut msg_buf = [0u8; PAY_LOAD_BUFFER_LEN];
+
+        construct_request(
+            &mut msg_buf,
+            GetPdrRequest::new(
+                0x01,
+                PldmMsgType::Request,
+                5,
+                0,
+                TransferOperationFlag::GetFirstPart,
+                RECORD_LEN as u16,
+                0,
+            ),
+        );
+        let resp_len = block_on(platform_ctx.get_pdr_rsp(&mut msg_buf)).unwrap();
+        validate_response(
+            &msg_buf[..resp_len],
+            failure_response(
+                0x01,
+                PlatformCmd::GetPdr,
+                GetPdrCompletionCode::InvalidRecordHandle as u8,
+            ),
+        );
+
+        // A later part must refer to the same revision of the record
+        construct_request(
+            &mut msg_buf,
+            GetPdrRequest::new(
+                0x02,
+                PldmMsgType::Request,
+                RECORD_HANDLE,
+                MAX_PDR_TRANSFER_SIZE as u32,
+                TransferOperationFlag::GetNextPart,
+                RECORD_LEN as u16,
+                RECORD_CHANGE_NUMBER + 1,
+            ),
+        );
+        let resp_len = block_on(platform_ctx.get_pdr_rsp(&mut msg_buf)).unwrap();
+        validate_response(
+            &msg_buf[..resp_len],
+            failure_response(
+                0x02,
+                PlatformCmd::GetPdr,
+                GetPdrCompletionCode::InvalidRecordChangeNumber as u8,
+            ),
+        );
+
+        construct_request(
+            &mut msg_buf,
+            GetPdrRequest::new(
+                0x03,
+                PldmMsgType::Request,
+                RECORD_HANDLE,
+                RECORD_LEN as u32,
+                TransferOperationFlag::GetNextPart,
+                RECORD_LEN as u16,
+                RECORD_CHANGE_NUMBER,
+            ),
+        );
+        let resp_len = block_on(platform_ctx.get_pdr_rsp(&mut msg_buf)).unwrap();
+        validate_response(
+            &msg_buf[..resp_len],
+            failure_response(
+                0x03,
+                PlatformCmd::GetPdr,
+                GetPdrCompletionCode::InvalidDataTransferHandle as u8,
+            ),
+        );
+    }
+
+    #[test]
+    fn test_sensor_readings() {
+        let ops = TestPlatformOps::new();
+        let platform_ctx = PlatformContext::new(&ops);
+        let mut msg_buf = [0u8; PAY_LOAD_BUFFER_LEN];
+
+        construct_request(
+            &mut msg_buf,
+            GetSensorReadingRequest::new(0x01, PldmMsgType::Request, NUMERIC_SENSOR_ID, false),
+        );
+        let resp_len = block_on(platform_ctx.get_sensor_reading_rsp(&mut msg_buf)).unwrap();
+        validate_response(
+            &msg_buf[..resp_len],
+            GetSensorReadingResponse::new(
+                0x01,
+                PldmBaseCompletionCode::Success as u8,
+                &numeric_reading(),
+            ),
+        );
+
+        construct_request(
+            &mut msg_buf,
+            GetSensorReadingRequest::new(0x02, PldmMsgType::Request, STATE_SENSOR_ID, false),
+        );
+        let resp_len = block_on(platform_ctx.get_sensor_reading_rsp(&mut msg_buf)).unwrap();
+        validate_response(
+            &msg_buf[..resp_len],
+            failure_response(
+                0x02,
+                PlatformCmd::GetSensorReading,
+                SensorCompletionCode::InvalidSensorId as u8,
+            ),
+        );
+
+        construct_request(
+            &mut msg_buf,
+            GetStateSensorReadingsRequest::new(0x03, PldmMsgType::Request, STATE_SENSOR_ID, 0),
+        );
+        let resp_len = block_on(platform_ctx.get_state_sensor_readings_rsp(&mut msg_buf)).unwrap();
+        validate_response(
+            &msg_buf[..resp_len],
+            GetStateSensorReadingsResponse::new(
+                0x03,
+                PldmBaseCompletionCode::Success as u8,
+                &state_fields(),
+            )
+            .unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_platform_event_message() {
+        let ops = TestPlatformOps::new();
+        let platform_ctx = PlatformContext::new(&ops);
+        let mut msg_buf = [0u8; PAY_LOAD_BUFFER_LEN];
+
+        let req_len = construct_request(
+            &mut msg_buf,
+            PlatformEventMessageRequest::new(
+                0x01,
+                PldmMsgType::Request,
+                TEST_TID,
+                PlatformEventClass::SensorEvent,
+                &EVENT_DATA,
+            )
+            .unwrap(),
+        );
+        let resp_len =
+            block_on(platform_ctx.platform_event_message_rsp(&mut msg_buf, req_len)).unwrap();
+        validate_response(
+            &msg_buf[..resp_len],
+            PlatformEventMessageResponse::new(
+                0x01,
+                PldmBaseCompletionCode::Success as u8,
+                PlatformEventStatus::NoLogging,
+            ),
+        );
+
+        let mut req = PlatformEventMessageRequest::new(
+            0x02,
+            PldmMsgType::Request,
+            TEST_TID,
+            PlatformEventClass::SensorEvent,
+            &EVENT_DATA,
+        )
+        .unwrap();
+        req.fixed.format_version = PLATFORM_EVENT_FORMAT_VERSION + 1;
+        let req_len = construct_request(&mut msg_buf, req);
+        let resp_len =
+            block_on(platform_ctx.platform_event_message_rsp(&mut msg_buf, req_len)).unwrap();
+        validate_response(
+            &msg_buf[..resp_len],
+            failure_response(
+                0x02,
+                PlatformCmd::PlatformEventMessage,
+                PlatformEventCompletionCode::UnsupportedEventFormatVersion as u8,
+            ),
+        );
+    }
+
+    #[test]
+    fn test_poll_and_acknowledge_event() {
+        let ops = TestPlatformOps::new();
+        let platform_ctx = PlatformContext::new(&ops);
+        let mut msg_buf = [0u8; PAY_LOAD_BUFFER_LEN];
+
+        construct_request(
+            &mut msg_buf,
+            PollForPlatformEventMessageRequest::new(
+                0x01,
+                PldmMsgType::Request,
+                PollTransferOperationFlag::GetFirstPart,
+                0,
+                0,
+            ),
+        );
+        let resp_len =
+            block_on(platform_ctx.poll_for_platform_event_message_rsp(&mut msg_buf, TEST_TID))
+                .unwrap();
+        validate_response(
+            &msg_buf[..resp_len],
+            PollForPlatformEventMessageResponse::new(
+                0x01,
+                PldmBaseCompletionCode::Success as u8,
+                TEST_TID,
+                EVENT_ID,
+            )
+            .with_event_data(
+                0,
+                TransferRespFlag::StartAndEnd,
+                PlatformEventClass::SensorEvent,
+                &EVENT_DATA,
+                0,
+            )
+            .unwrap(),
+        );
+
+        construct_request(
+            &mut msg_buf,
+            PollForPlatformEventMessageRequest::new(
+                0x02,
+                PldmMsgType::Request,
+                PollTransferOperationFlag::AcknowledgementOnly,
+                0,
+                EVENT_ID,
+            ),
+        );
+        let resp_len =
+            block_on(platform_ctx.poll_for_platform_event_message_rsp(&mut msg_buf, TEST_TID))
+                .unwrap();
+        validate_response(
+            &msg_buf[..resp_len],
+            PollForPlatformEventMessageResponse::new(
+                0x02,
+                PldmBaseCompletionCode::Success as u8,
+                TEST_TID,
+                POLL_EVENT_ID_ACK,
+            ),
+        );
+
+        // The queue is now empty and the event cannot be acknowledged again
+        construct_request(
+            &mut msg_buf,
+            PollForPlatformEventMessageRequest::new(
+                0x03,
+                PldmMsgType::Request,
+                PollTransferOperationFlag::GetFirstPart,
+                0,
+                0,
+            ),
+        );
+        let resp_len =
+            block_on(platform_ctx.poll_for_platform_event_message_rsp(&mut msg_buf, TEST_TID))
+                .unwrap();
+        validate_response(
+            &msg_buf[..resp_len],
+            PollForPlatformEventMessageResponse::new(
+                0x03,
+                PldmBaseCompletionCode::Success as u8,
+                TEST_TID,
+                POLL_EVENT_ID_NONE,
+            ),
+        );
+
+        construct_request(
+            &mut msg_buf,
+            PollForPlatformEventMessageRequest::new(
+                0x04,
+                PldmMsgType::Request,
+                PollTransferOperationFlag::AcknowledgementOnly,
+                0,
+                EVENT_ID,
+            ),
+        );
+        let resp_len =
+            block_on(platform_ctx.poll_for_platform_event_message_rsp(&mut msg_buf, TEST_TID))
+                .unwrap();
+        validate_response(
+            &msg_buf[..resp_len],
+            failure_response(
+                0x04,
+                PlatformCmd::PollForPlatformEventMessage,
+                PollEventCompletionCode::EventIdNotValid as u8,
+            ),
+        );
+    }
+}
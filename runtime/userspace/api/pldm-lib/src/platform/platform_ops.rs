@@ -0,0 +1,166 @@
+// Licensed under the Apache-2.0 license
+
+extern crate alloc;
+use alloc::boxed::Box;
+use async_trait::async_trait;
+use caliptra_mcu_pldm_common::message::platform::get_pdr_repo_info::PdrRepositoryInfo;
+use caliptra_mcu_pldm_common::message::platform::get_sensor_reading::NumericSensorReading;
+use caliptra_mcu_pldm_common::protocol::platform::{
+    PlatformEventClass, PlatformEventStatus, StateField,
+};
+
+#[derive(Debug)]
+pub enum PlatformOpsError {
+    InvalidRecordHandle,
+    RepositoryUpdateInProgress,
+    InvalidSensorId,
+    RearmUnavailable,
+    InvalidEventId,
+    BufferTooSmall,
+    ProviderError,
+}
+
+/// Location of a PDR record within the repository.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PdrRecordInfo {
+    /// Size of the record in bytes, including the common PDR header.
+    pub size: usize,
+    /// Handle of the next record, or 0 if this is the last record.
+    pub next_record_handle: u32,
+}
+
+/// Event queued by the terminus for retrieval through PollForPlatformEventMessage.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PendingEvent {
+    pub event_id: u16,
+    pub event_class: PlatformEventClass,
+    /// Size of the event data in bytes.
+    pub size: usize,
+}
+
+/// Trait for the PDR repository and sensors exposed through PLDM Platform Monitoring
+/// and Control (Type 2).
+///
+/// The platform context takes care of message decoding, multipart transfers and transfer
+/// integrity checks. Implementors only provide the repository content, sensor readings
+/// and queued events.
+#[async_trait(?Send)]
+pub trait PlatformOps {
+    /// Retrieves a summary of the PDR repository.
+    ///
+    /// # Arguments
+    ///
+    /// * `info` - A mutable reference to `PdrRepositoryInfo` to store the repository summary.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), PlatformOpsError>` - On success, returns `Ok(())`. On failure, returns a `PlatformOpsError`.
+    async fn get_pdr_repository_info(
+        &self,
+        info: &mut PdrRepositoryInfo,
+    ) -> Result<(), PlatformOpsError>;
+
+    /// Retrieves a complete PDR record.
+    ///
+    /// # Arguments
+    ///
+    /// * `record_handle` - Handle of the requested record. A handle of 0 refers to the first record.
+    /// * `record` - A mutable slice to store the record, starting with the common PDR header.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<PdrRecordInfo, PlatformOpsError>` - On success, returns the record size and the handle
+    ///   of the next record. Returns `PlatformOpsError::InvalidRecordHandle` if no such record exists.
+    async fn get_pdr(
+        &self,
+        record_handle: u32,
+        record: &mut [u8],
+    ) -> Result<PdrRecordInfo, PlatformOpsError>;
+
+    /// Retrieves the present reading and state of a numeric sensor.
+    ///
+    /// # Arguments
+    ///
+    /// * `sensor_id` - The sensor ID as published in the numeric sensor PDR.
+    /// * `rearm_event_state` - Indicates if the sensor's event state should be re-armed.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<NumericSensorReading, PlatformOpsError>` - On success, returns the sensor reading.
+    ///   Returns `PlatformOpsError::InvalidSensorId` if the sensor does not exist.
+    async fn get_sensor_reading(
+        &self,
+        sensor_id: u16,
+        rearm_event_state: bool,
+    ) -> Result<NumericSensorReading, PlatformOpsError>;
+
+    /// Retrieves the state of each sensor within a composite state sensor.
+    ///
+    /// # Arguments
+    ///
+    /// * `sensor_id` - The sensor ID as published in the state sensor PDR.
+    /// * `sensor_rearm` - Bitmap of the composite sensor offsets to re-arm.
+    /// * `state_fields` - A mutable slice of `StateField` to store one entry per composite sensor.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<usize, PlatformOpsError>` - On success, returns the number of state fields written.
+    ///   Returns `PlatformOpsError::InvalidSensorId` if the sensor does not exist.
+    async fn get_state_sensor_readings(
+        &self,
+        sensor_id: u16,
+        sensor_rearm: u8,
+        state_fields: &mut [StateField],
+    ) -> Result<usize, PlatformOpsError>;
+
+    /// Handles a PlatformEventMessage sent to this terminus.
+    ///
+    /// # Arguments
+    ///
+    /// * `tid` - The TID of the terminus that originated the event.
+    /// * `event_class` - The raw event class.
+    /// * `event_data` - The event data.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<PlatformEventStatus, PlatformOpsError>` - On success, returns how the event was handled.
+    async fn handle_platform_event(
+        &self,
+        _tid: u8,
+        _event_class: u8,
+        _event_data: &[u8],
+    ) -> Result<PlatformEventStatus, PlatformOpsError> {
+        Ok(PlatformEventStatus::NoLogging)
+    }
+
+    /// Retrieves the oldest event that has not been acknowledged yet, without removing it.
+    ///
+    /// # Arguments
+    ///
+    /// * `event_data` - A mutable slice to store the complete event data.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<PendingEvent>, PlatformOpsError>` - On success, returns the pending event or
+    ///   `None` if no event is queued.
+    async fn peek_event(
+        &self,
+        _event_data: &mut [u8],
+    ) -> Result<Option<PendingEvent>, PlatformOpsError> {
+        Ok(None)
+    }
+
+    /// Removes an event from the queue once the event receiver has acknowledged it.
+    ///
+    /// # Arguments
+    ///
+    /// * `event_id` - The ID of the acknowledged event.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), PlatformOpsError>` - On success, returns `Ok(())`. Returns
+    ///   `PlatformOpsError::InvalidEventId` if the event is not the one at the head of the queue.
+    async fn acknowledge_event(&self, _event_id: u16) -> Result<(), PlatformOpsError> {
+        Err(PlatformOpsError::InvalidEventId)
+    }
+}
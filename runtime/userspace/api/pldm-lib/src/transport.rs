@@ -1,6 +1,7 @@
 // Licensed under the Apache-2.0 license
 
 use caliptra_mcu_libsyscall_caliptra::mctp::{Mctp, MessageInfo};
+use caliptra_mcu_libtock_platform::ErrorCode;
 use caliptra_mcu_pldm_common::util::mctp_transport::{
     MctpCommonHeader, MCTP_COMMON_HEADER_OFFSET, MCTP_PLDM_MSG_TYPE,
 };
@@ -15,6 +16,7 @@ pub enum TransportError {
     BufferTooSmall,
     UnexpectedMessageType,
     ReceiveError,
+    ResponseTimeout,
     SendError,
     ResponseNotExpected,
     NoRequestInFlight,
@@ -52,7 +54,7 @@ impl MctpTransport {
         Ok(())
     }
 
-    pub async fn receive_response(&mut self, rsp: &mut [u8]) -> Result<(), TransportError> {
+    pub async fn receive_response(&mut self, rsp: &mut [u8]) -> Result<usize, TransportError> {
         // Reset msg buffer
         rsp.fill(0);
         let (rsp_len, _msg_info) = if let Some(msg_info) = &self.cur_req_ctx {
@@ -75,10 +77,55 @@ impl MctpTransport {
         }
 
         self.cur_req_ctx = None;
-        Ok(())
+        Ok(rsp_len as usize)
+    }
+
+    /// Sends a request without tracking it as the single in-flight request and returns the
+    /// MCTP message tag assigned to it. Several such requests can be outstanding at once;
+    /// each response is collected with `receive_tagged_response`.
+    pub async fn send_tagged_request(
+        &mut self,
+        dest_eid: u8,
+        req: &[u8],
+    ) -> Result<u8, TransportError> {
+        check_pldm_header(req)?;
+
+        self.mctp
+            .send_request(dest_eid, req)
+            .await
+            .map_err(|_| TransportError::SendError)
+    }
+
+    /// Receives the response to a request sent with `send_tagged_request`.
+    ///
+    /// Fails with `ResponseTimeout` if the response does not arrive within the MCTP
+    /// response timeout, or was dropped by the MCTP driver before it was collected.
+    pub async fn receive_tagged_response(
+        &mut self,
+        src_eid: u8,
+        tag: u8,
+        rsp: &mut [u8],
+    ) -> Result<usize, TransportError> {
+        // Reset msg buffer
+        rsp.fill(0);
+        let (rsp_len, _msg_info) = self
+            .mctp
+            .receive_response(rsp, tag, src_eid)
+            .await
+            .map_err(|e| match e {
+                ErrorCode::NoAck => TransportError::ResponseTimeout,
+                _ => TransportError::ReceiveError,
+            })?;
+
+        if rsp_len == 0 {
+            Err(TransportError::BufferTooSmall)?;
+        }
+
+        check_pldm_header(rsp)?;
+        Ok(rsp_len as usize)
     }
 
-    pub async fn receive_request(&mut self, req: &mut [u8]) -> Result<(), TransportError> {
+    pub async fn receive_request(&mut self, req: &mut [u8]) -> Result<usize, TransportError> {
         // Reset msg buffer
         req.fill(0);
         let (req_len, msg_info) = self
@@ -99,7 +146,7 @@ impl MctpTransport {
 
         self.cur_resp_ctx = Some(msg_info);
 
-        Ok(())
+        Ok(req_len as usize)
     }
 
     pub async fn send_response(&mut self, resp: &[u8]) -> Result<(), TransportError> {
@@ -122,3 +169,11 @@ impl MctpTransport {
         Ok(())
     }
 }
+
+fn check_pldm_header(msg: &[u8]) -> Result<(), TransportError> {
+    let mctp_hdr = MctpCommonHeader(msg[MCTP_COMMON_HEADER_OFFSET]);
+    if mctp_hdr.ic() != 0 || mctp_hdr.msg_type() != MCTP_PLDM_MSG_TYPE {
+        Err(TransportError::UnexpectedMessageType)?;
+    }
+    Ok(())
+}
@@ -6,7 +6,7 @@ use crate::error::{SpdmError, SpdmResult};
 use crate::protocol::*;
 use alloc::boxed::Box;
 use async_trait::async_trait;
-use caliptra_mcu_libapi_caliptra::crypto::asym::{AsymAlgo, ECC_P384_SIGNATURE_SIZE};
+use caliptra_mcu_libapi_caliptra::crypto::asym::AsymAlgo;
 use caliptra_mcu_libapi_caliptra::crypto::hash::{HashAlgoType, HashContext, SHA384_HASH_SIZE};
 use caliptra_mcu_libapi_caliptra::error::CaliptraApiError;
 use zerocopy::IntoBytes;
@@ -24,6 +24,9 @@ pub enum CertStoreError {
     BufferTooSmall,
     InvalidOffset,
     CertReadError,
+    InvalidCertChain,
+    UnsupportedOperation,
+    ResetRequired,
     CaliptraApi(CaliptraApiError),
 }
 pub type CertStoreResult<T> = Result<T, CertStoreError>;
@@ -37,14 +40,16 @@ pub trait SpdmCertStore {
     /// * `u8` - The number of supported certificate slots.
     fn slot_count(&self) -> u8;
 
-    /// Check if the slot is provisioned.
+    /// Check if the slot is provisioned with a certificate chain of the given type.
+    /// A slot may hold an ECC P-384 and an ML-DSA-87 certificate chain.
     ///
     /// # Arguments
     /// * `slot_id` - The slot ID of the certificate chain.
+    /// * `asym_algo` - The asymmetric algorithm to indicate the type of certificate chain.
     ///
     /// # Returns
     /// * `bool` - True if the slot is provisioned, false otherwise.
-    async fn is_provisioned(&self, slot_id: u8) -> bool;
+    async fn is_provisioned(&self, slot_id: u8, asym_algo: AsymAlgo) -> bool;
 
     /// Get the length of the certificate chain in bytes.
     /// The certificate chain is in ASN.1 DER-encoded X.509 v3 format.
@@ -96,13 +101,13 @@ pub trait SpdmCertStore {
         cert_hash: &'a mut [u8; SHA384_HASH_SIZE],
     ) -> CertStoreResult<()>;
 
-    /// Sign hash with leaf certificate key
+    /// Sign hash with leaf certificate key. Used for ECC P-384.
     ///
     /// # Arguments
     /// * `slot_id` - The slot ID of the certificate chain.
     /// * `asym_algo` - Asymmetric algorithm to sign with.
     /// * `hash` - The hash to sign.
-    /// * `signature` - The output buffer to store the ECC384 signature.
+    /// * `signature` - The output buffer to store the signature.
     ///
     /// # Returns
     /// * `()` - Ok if successful, error otherwise.
@@ -111,7 +116,26 @@ pub trait SpdmCertStore {
         slot_id: u8,
         asym_algo: AsymAlgo,
         hash: &'a [u8; SHA384_HASH_SIZE],
-        signature: &'a mut [u8; ECC_P384_SIGNATURE_SIZE],
+        signature: &'a mut [u8],
+    ) -> CertStoreResult<()>;
+
+    /// Sign message with leaf certificate key. Used for ML-DSA-87, which signs the
+    /// message itself rather than its hash.
+    ///
+    /// # Arguments
+    /// * `slot_id` - The slot ID of the certificate chain.
+    /// * `asym_algo` - Asymmetric algorithm to sign with.
+    /// * `message` - The message to sign.
+    /// * `signature` - The output buffer to store the signature.
+    ///
+    /// # Returns
+    /// * `()` - Ok if successful, error otherwise.
+    async fn sign_message<'a>(
+        &self,
+        slot_id: u8,
+        asym_algo: AsymAlgo,
+        message: &'a [u8],
+        signature: &'a mut [u8],
     ) -> CertStoreResult<()>;
 
     /// Get the KeyPairID associated with the certificate chain if SPDM responder supports
@@ -144,6 +168,90 @@ pub trait SpdmCertStore {
     /// # Returns
     /// * `KeyUsageMask` - The KeyUsageMask associated with the certificate chain or None if not supported or not found.
     async fn key_usage_mask(&self, slot_id: u8) -> Option<KeyUsageMask>;
+
+    /// Get the total number of key pairs of the SPDM responder (SPDM 1.3+).
+    /// The KeyPairIDs are consecutive from 1 to total_key_pairs.
+    ///
+    /// # Returns
+    /// * `u8` - The total number of key pairs or 0 if GET_KEY_PAIR_INFO is not supported.
+    async fn total_key_pairs(&self) -> u8;
+
+    /// Get the information about the key pair identified by `key_pair_id` (SPDM 1.3+).
+    ///
+    /// # Arguments
+    /// * `key_pair_id` - The KeyPairID of the key pair.
+    /// * `public_key_info` - The buffer to store the DER-encoded SubjectPublicKeyInfo of the key pair.
+    ///
+    /// # Returns
+    /// * `(KeyPairInfo, usize)` - The key pair information and the length of the SubjectPublicKeyInfo
+    ///   in bytes or error.
+    async fn key_pair_info<'a>(
+        &self,
+        key_pair_id: u8,
+        public_key_info: &'a mut [u8],
+    ) -> CertStoreResult<(KeyPairInfo, usize)>;
+
+    /// Generate a Certificate Signing Request (CSR) for the key pair identified by `key_pair_id`.
+    /// The CSR is an ASN.1 DER-encoded PKCS #10 request. The generated CSR is retained by the
+    /// cert store until the next call to `generate_csr` and is read back with `get_csr`.
+    ///
+    /// # Arguments
+    /// * `key_pair_id` - The KeyPairID of the key to certify. 0 if multiple asymmetric keys are not supported.
+    /// * `cert_model` - The certificate model the Requester intends to install for the key (SPDM 1.3+).
+    ///   `CertModel::None` if not specified.
+    /// * `requester_info` - The DER-encoded CertificationRequestInfo provided by the Requester. May be empty.
+    ///
+    /// # Returns
+    /// * `usize` - The length of the CSR in bytes or error.
+    ///   `CertStoreError::ResetRequired` if the CSR can only be generated after a device reset.
+    async fn generate_csr<'a>(
+        &self,
+        key_pair_id: u8,
+        cert_model: CertModel,
+        requester_info: &'a [u8],
+    ) -> CertStoreResult<usize>;
+
+    /// Get the last generated CSR in portion.
+    ///
+    /// # Arguments
+    /// * `offset` - The offset in bytes to start reading from.
+    /// * `csr_portion` - The buffer to read the CSR into.
+    ///
+    /// # Returns
+    /// * `usize` - The number of bytes read or error.
+    async fn get_csr<'a>(&self, offset: usize, csr_portion: &'a mut [u8])
+        -> CertStoreResult<usize>;
+
+    /// Install a certificate chain into the slot. The certificate chain is in ASN.1 DER-encoded
+    /// X.509 v3 format, starting with the root certificate. The leaf certificate must certify the
+    /// public key of the key pair identified by `key_pair_id`.
+    ///
+    /// # Arguments
+    /// * `slot_id` - The slot ID to install the certificate chain into.
+    /// * `key_pair_id` - The KeyPairID associated with the certificate chain. 0 if multiple asymmetric keys are not supported.
+    /// * `cert_model` - The certificate model of the certificate chain (SPDM 1.3+). `CertModel::None` if not specified.
+    /// * `cert_chain` - The DER-encoded certificate chain.
+    ///
+    /// # Returns
+    /// * `()` - Ok if successful, error otherwise.
+    ///   `CertStoreError::ResetRequired` if the certificate chain is installed on the next device reset.
+    async fn set_cert_chain<'a>(
+        &self,
+        slot_id: u8,
+        key_pair_id: u8,
+        cert_model: CertModel,
+        cert_chain: &'a [u8],
+    ) -> CertStoreResult<()>;
+
+    /// Erase the certificate chain installed in the slot (SPDM 1.3+).
+    ///
+    /// # Arguments
+    /// * `slot_id` - The slot ID of the certificate chain to erase.
+    ///
+    /// # Returns
+    /// * `()` - Ok if successful, error otherwise.
+    ///   `CertStoreError::ResetRequired` if the slot is erased on the next device reset.
+    async fn erase_cert_chain(&self, slot_id: u8) -> CertStoreResult<()>;
 }
 
 pub(crate) fn validate_cert_store(cert_store: &dyn SpdmCertStore) -> SpdmResult<()> {
@@ -154,13 +262,16 @@ pub(crate) fn validate_cert_store(cert_store: &dyn SpdmCertStore) -> SpdmResult<
     Ok(())
 }
 
-pub(crate) async fn cert_slot_mask(cert_store: &dyn SpdmCertStore) -> (u8, u8) {
+pub(crate) async fn cert_slot_mask(
+    cert_store: &dyn SpdmCertStore,
+    asym_algo: AsymAlgo,
+) -> (u8, u8) {
     let slot_count = cert_store.slot_count().min(MAX_CERT_SLOTS_SUPPORTED);
     let supported_slot_mask = (1 << slot_count) - 1;
 
     let mut provisioned_slot_mask = 0;
     for i in 0..slot_count {
-        if cert_store.is_provisioned(i).await {
+        if cert_store.is_provisioned(i, asym_algo).await {
             provisioned_slot_mask |= 1 << i;
         }
     }
@@ -168,6 +279,40 @@ pub(crate) async fn cert_slot_mask(cert_store: &dyn SpdmCertStore) -> (u8, u8) {
     (supported_slot_mask, provisioned_slot_mask)
 }
 
+/// Sign the message of a response with the leaf certificate key of the slot.
+/// ECC P-384 signs the SHA-384 digest of the message, ML-DSA-87 signs the message itself.
+///
+/// # Arguments
+/// * `cert_store` - The certificate store holding the signing key.
+/// * `slot_id` - The slot ID of the certificate chain.
+/// * `asym_algo` - The asymmetric algorithm to sign with.
+/// * `message` - The message to sign.
+/// * `signature` - The output buffer to store the signature.
+pub(crate) async fn spdm_sign(
+    cert_store: &dyn SpdmCertStore,
+    slot_id: u8,
+    asym_algo: AsymAlgo,
+    message: &SigningMessage,
+    signature: &mut [u8],
+) -> CertStoreResult<()> {
+    match asym_algo {
+        AsymAlgo::EccP384 => {
+            let tbs = message
+                .digest()
+                .await
+                .map_err(CertStoreError::CaliptraApi)?;
+            cert_store
+                .sign_hash(slot_id, asym_algo, &tbs, signature)
+                .await
+        }
+        AsymAlgo::MlDsa87 => {
+            cert_store
+                .sign_message(slot_id, asym_algo, message.message(), signature)
+                .await
+        }
+    }
+}
+
 /// Get the hash of the certificate chain.
 /// The certificate chain is in ASN.1 DER-encoded X.509 v3 format.
 /// The type of the certificate chain is indicated by the asym_algo parameter.
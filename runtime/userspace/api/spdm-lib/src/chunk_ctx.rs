@@ -1,6 +1,8 @@
 // Licensed under the Apache-2.0 license
 
 use crate::commands::certificate_rsp::CertificateResponse;
+use crate::commands::csr_rsp::CsrResponse;
+use crate::commands::measurement_extension_log_rsp::MelResponse;
 use crate::commands::measurements_rsp::MeasurementsResponse;
 use crate::commands::vendor_defined_rsp::VendorLargeResponse;
 
@@ -16,6 +18,12 @@ pub enum ChunkError {
     InvalidChunkSeqNum,
     /// Invalid message offset provided
     InvalidMessageOffset,
+    /// No large request is currently in progress
+    NoLargeRequestInProgress,
+    /// The large message does not fit into the reassembly buffer
+    LargeMessageTooLarge,
+    /// The chunk size is inconsistent with the large message size
+    InvalidChunkSize,
 }
 
 /// Stores state and metadata for managing ongoing large message requests and responses.
@@ -50,29 +58,41 @@ pub type ChunkResult<T> = Result<T, ChunkError>;
 /// Represents a large message response type that can be split into chunks
 pub(crate) enum LargeResponse {
     Certificate(CertificateResponse),
+    Csr(CsrResponse),
     Measurements(MeasurementsResponse),
+    Mel(MelResponse),
     Vdm(VendorLargeResponse),
+    /// Response generated in full into the large response buffer
+    Buffered,
 }
 
 /// Manages the context for ongoing large message responses
-pub(crate) struct LargeResponseCtx {
+pub(crate) struct LargeResponseCtx<'a> {
     chunk_state: ChunkState,
     response: Option<LargeResponse>,
     /// Global handle counter for large responses (incremented for each new response)
     global_handle: u8,
+    /// Buffer holding a large response that cannot be regenerated chunk by chunk
+    buffer: Option<&'a mut [u8]>,
 }
 
-impl Default for LargeResponseCtx {
+impl Default for LargeResponseCtx<'_> {
     fn default() -> Self {
         Self {
             chunk_state: ChunkState::default(),
             response: None,
             global_handle: 1,
+            buffer: None,
         }
     }
 }
 
-impl LargeResponseCtx {
+impl<'a> LargeResponseCtx<'a> {
+    /// Set the buffer used to hold large responses generated in full
+    pub(crate) fn set_buffer(&mut self, buffer: &'a mut [u8]) {
+        self.buffer = Some(buffer);
+    }
+
     /// Reset the context to its initial state
     /// This action increments the global handle for the next large response
     pub(crate) fn reset(&mut self) {
@@ -165,7 +185,351 @@ impl LargeResponseCtx {
         self.response.as_ref()
     }
 
+    /// Initialize the context for a large response generated in full.
+    /// The response is copied into the large response buffer.
+    ///
+    /// # Arguments
+    /// * `large_rsp` - The complete large response message
+    ///
+    /// # Returns
+    /// The handle(u8) for this large response, or `LargeMessageTooLarge` if it
+    /// does not fit into the large response buffer
+    pub fn init_buffered(&mut self, large_rsp: &[u8]) -> ChunkResult<u8> {
+        let buffer = self
+            .buffer
+            .as_mut()
+            .filter(|buffer| buffer.len() >= large_rsp.len())
+            .ok_or(ChunkError::LargeMessageTooLarge)?;
+        buffer[..large_rsp.len()].copy_from_slice(large_rsp);
+        Ok(self.init(LargeResponse::Buffered, large_rsp.len()))
+    }
+
+    /// Copy the next chunk of a buffered large response
+    ///
+    /// # Arguments
+    /// * `offset` - The offset of the chunk in the large response
+    /// * `chunk` - The buffer to copy the chunk into
+    ///
+    /// # Returns
+    /// The number of bytes copied
+    pub fn buffered_chunk(&self, offset: usize, chunk: &mut [u8]) -> ChunkResult<usize> {
+        let buffer = match (&self.response, &self.buffer) {
+            (Some(LargeResponse::Buffered), Some(buffer)) => buffer,
+            _ => return Err(ChunkError::NoLargeResponseInProgress),
+        };
+        let large_rsp_size = self.chunk_state.large_msg_size;
+        if offset >= large_rsp_size {
+            return Err(ChunkError::InvalidMessageOffset);
+        }
+
+        let len = (large_rsp_size - offset).min(chunk.len());
+        chunk[..len].copy_from_slice(&buffer[offset..offset + len]);
+        Ok(len)
+    }
+
     pub fn bytes_transferred(&self) -> usize {
         self.chunk_state.bytes_transferred
     }
 }
+
+/// Manages the reassembly of a large request received in chunks with CHUNK_SEND
+pub(crate) struct LargeRequestCtx<'a> {
+    chunk_state: ChunkState,
+    buffer: Option<&'a mut [u8]>,
+}
+
+impl Default for LargeRequestCtx<'_> {
+    fn default() -> Self {
+        Self {
+            chunk_state: ChunkState::default(),
+            buffer: None,
+        }
+    }
+}
+
+impl<'a> LargeRequestCtx<'a> {
+    /// Create a context reassembling large requests into the given buffer
+    pub(crate) fn new(buffer: &'a mut [u8]) -> Self {
+        Self {
+            chunk_state: ChunkState::default(),
+            buffer: Some(buffer),
+        }
+    }
+
+    /// Reset the context, dropping any partially received request
+    pub(crate) fn reset(&mut self) {
+        self.chunk_state.reset();
+        self.chunk_state.large_msg_size = 0;
+    }
+
+    /// Is large message request in progress
+    pub(crate) fn in_progress(&self) -> bool {
+        self.chunk_state.in_use
+    }
+
+    /// Returns the maximum size of a large request that can be reassembled
+    pub(crate) fn capacity(&self) -> usize {
+        self.buffer.as_ref().map_or(0, |buffer| buffer.len())
+    }
+
+    /// Start the reassembly of a new large request. Any request in progress is dropped.
+    ///
+    /// # Arguments
+    /// * `handle` - The handle chosen by the Requester for this large request
+    /// * `large_msg_size` - The size of the large request
+    pub(crate) fn start(&mut self, handle: u8, large_msg_size: usize) -> ChunkResult<()> {
+        self.reset();
+        if large_msg_size > self.capacity() {
+            return Err(ChunkError::LargeMessageTooLarge);
+        }
+        self.chunk_state.init(large_msg_size, handle);
+        Ok(())
+    }
+
+    /// Append the next chunk of the large request
+    ///
+    /// # Arguments
+    /// * `handle` - The handle of the chunk
+    /// * `chunk_seq_num` - The sequence number of the chunk
+    /// * `chunk` - The chunk data
+    /// * `last_chunk` - Whether the Requester marked this chunk as the last one
+    ///
+    /// # Returns
+    /// `Ok(true)` if the large request is complete, `Ok(false)` if more chunks are expected,
+    /// or a specific `ChunkError` if the chunk is out of order or inconsistent
+    pub(crate) fn append_chunk(
+        &mut self,
+        handle: u8,
+        chunk_seq_num: u16,
+        chunk: &[u8],
+        last_chunk: bool,
+    ) -> ChunkResult<bool> {
+        if !self.chunk_state.in_use {
+            return Err(ChunkError::NoLargeRequestInProgress);
+        }
+        if self.chunk_state.handle != handle {
+            return Err(ChunkError::InvalidChunkHandle);
+        }
+        if self.chunk_state.seq_num != chunk_seq_num {
+            return Err(ChunkError::InvalidChunkSeqNum);
+        }
+
+        let offset = self.chunk_state.bytes_transferred;
+        let total_len = offset + chunk.len();
+        let complete = total_len == self.chunk_state.large_msg_size;
+        if chunk.is_empty() || total_len > self.chunk_state.large_msg_size || complete != last_chunk
+        {
+            return Err(ChunkError::InvalidChunkSize);
+        }
+
+        // The sequence number must not wrap within a large request
+        if !last_chunk && chunk_seq_num == u16::MAX {
+            return Err(ChunkError::InvalidChunkSeqNum);
+        }
+
+        let buffer = self
+            .buffer
+            .as_mut()
+            .ok_or(ChunkError::LargeMessageTooLarge)?;
+        buffer[offset..total_len].copy_from_slice(chunk);
+        self.chunk_state.bytes_transferred = total_len;
+        self.chunk_state.seq_num = chunk_seq_num.wrapping_add(1);
+
+        Ok(complete)
+    }
+
+    /// Take the reassembled large request out of the context.
+    /// The buffer must be handed back with `restore_buffer` once the request is processed.
+    ///
+    /// # Returns
+    /// The reassembly buffer and the size of the large request
+    pub(crate) fn take_request(&mut self) -> ChunkResult<(&'a mut [u8], usize)> {
+        let large_msg_size = self.chunk_state.large_msg_size;
+        if !self.chunk_state.in_use || self.chunk_state.bytes_transferred != large_msg_size {
+            return Err(ChunkError::NoLargeRequestInProgress);
+        }
+        let buffer = self
+            .buffer
+            .take()
+            .ok_or(ChunkError::NoLargeRequestInProgress)?;
+        self.reset();
+        Ok((buffer, large_msg_size))
+    }
+
+    /// Hand back the reassembly buffer taken with `take_request`
+    pub(crate) fn restore_buffer(&mut self, buffer: &'a mut [u8]) {
+        self.buffer = Some(buffer);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const HANDLE: u8 = 5;
+
+    #[test]
+    fn test_large_request_reassembly() {
+        let mut buffer = [0u8; 8];
+        let mut ctx = LargeRequestCtx::new(&mut buffer);
+
+        ctx.start(HANDLE, 8).unwrap();
+        assert!(ctx.in_progress());
+        assert_eq!(ctx.append_chunk(HANDLE, 0, &[1, 2, 3], false), Ok(false));
+        assert_eq!(ctx.append_chunk(HANDLE, 1, &[4, 5, 6], false), Ok(false));
+        assert_eq!(ctx.append_chunk(HANDLE, 2, &[7, 8], true), Ok(true));
+
+        let (buffer, len) = ctx.take_request().unwrap();
+        assert_eq!(&buffer[..len], &[1, 2, 3, 4, 5, 6, 7, 8]);
+        assert!(!ctx.in_progress());
+        assert_eq!(ctx.capacity(), 0);
+
+        ctx.restore_buffer(buffer);
+        assert_eq!(ctx.capacity(), 8);
+    }
+
+    #[test]
+    fn test_large_request_size_boundaries() {
+        let mut buffer = [0u8; 8];
+        let mut ctx = LargeRequestCtx::new(&mut buffer);
+
+        // A request of exactly the buffer size fits, one more byte does not
+        assert_eq!(ctx.start(HANDLE, 9), Err(ChunkError::LargeMessageTooLarge));
+        assert!(!ctx.in_progress());
+        ctx.start(HANDLE, 8).unwrap();
+        assert_eq!(ctx.append_chunk(HANDLE, 0, &[0; 8], true), Ok(true));
+
+        // Without a buffer no large request is accepted
+        let mut ctx = LargeRequestCtx::default();
+        assert_eq!(ctx.start(HANDLE, 1), Err(ChunkError::LargeMessageTooLarge));
+    }
+
+    #[test]
+    fn test_large_request_out_of_order_chunk() {
+        let mut buffer = [0u8; 8];
+        let mut ctx = LargeRequestCtx::new(&mut buffer);
+
+        assert_eq!(
+            ctx.append_chunk(HANDLE, 0, &[0; 4], false),
+            Err(ChunkError::NoLargeRequestInProgress)
+        );
+
+        ctx.start(HANDLE, 8).unwrap();
+        assert_eq!(ctx.append_chunk(HANDLE, 0, &[0; 4], false), Ok(false));
+
+        // Repeated, skipped and foreign chunks are rejected
+        assert_eq!(
+            ctx.append_chunk(HANDLE, 0, &[0; 4], true),
+            Err(ChunkError::InvalidChunkSeqNum)
+        );
+        assert_eq!(
+            ctx.append_chunk(HANDLE, 2, &[0; 4], true),
+            Err(ChunkError::InvalidChunkSeqNum)
+        );
+        assert_eq!(
+            ctx.append_chunk(HANDLE + 1, 1, &[0; 4], true),
+            Err(ChunkError::InvalidChunkHandle)
+        );
+
+        // The expected chunk is still accepted
+        assert_eq!(ctx.append_chunk(HANDLE, 1, &[0; 4], true), Ok(true));
+    }
+
+    #[test]
+    fn test_large_request_invalid_chunk_size() {
+        let mut buffer = [0u8; 8];
+        let mut ctx = LargeRequestCtx::new(&mut buffer);
+        ctx.start(HANDLE, 6).unwrap();
+
+        // Empty chunk
+        assert_eq!(
+            ctx.append_chunk(HANDLE, 0, &[], false),
+            Err(ChunkError::InvalidChunkSize)
+        );
+        // Chunk beyond the large message size, even if it fits into the buffer
+        assert_eq!(
+            ctx.append_chunk(HANDLE, 0, &[0; 7], true),
+            Err(ChunkError::InvalidChunkSize)
+        );
+        // Last chunk flag on an incomplete message
+        assert_eq!(
+            ctx.append_chunk(HANDLE, 0, &[0; 4], true),
+            Err(ChunkError::InvalidChunkSize)
+        );
+        // Complete message without the last chunk flag
+        assert_eq!(
+            ctx.append_chunk(HANDLE, 0, &[0; 6], false),
+            Err(ChunkError::InvalidChunkSize)
+        );
+        // The complete message cannot be taken before the last chunk
+        assert!(ctx.take_request().is_err());
+
+        assert_eq!(ctx.append_chunk(HANDLE, 0, &[0; 6], true), Ok(true));
+        assert!(ctx.take_request().is_ok());
+    }
+
+    #[test]
+    fn test_buffered_large_response() {
+        let mut buffer = [0u8; 8];
+        let mut ctx = LargeResponseCtx::default();
+
+        // Without a buffer no response can be buffered
+        assert_eq!(
+            ctx.init_buffered(&[1; 4]),
+            Err(ChunkError::LargeMessageTooLarge)
+        );
+
+        ctx.set_buffer(&mut buffer);
+        assert_eq!(
+            ctx.init_buffered(&[1; 9]),
+            Err(ChunkError::LargeMessageTooLarge)
+        );
+        assert!(!ctx.in_progress());
+
+        let handle = ctx.init_buffered(&[1, 2, 3, 4, 5, 6]).unwrap();
+        assert!(ctx.validate_chunk(handle, 0).is_ok());
+
+        let mut chunk = [0u8; 4];
+        assert_eq!(ctx.buffered_chunk(0, &mut chunk), Ok(4));
+        assert_eq!(chunk, [1, 2, 3, 4]);
+        ctx.next_chunk_sent(4);
+
+        assert_eq!(
+            ctx.buffered_chunk(ctx.bytes_transferred(), &mut chunk),
+            Ok(2)
+        );
+        assert_eq!(&chunk[..2], &[5, 6]);
+        assert_eq!(
+            ctx.buffered_chunk(6, &mut chunk),
+            Err(ChunkError::InvalidMessageOffset)
+        );
+        ctx.next_chunk_sent(2);
+
+        // The transfer is complete
+        assert!(!ctx.in_progress());
+        assert_eq!(
+            ctx.buffered_chunk(0, &mut chunk),
+            Err(ChunkError::NoLargeResponseInProgress)
+        );
+    }
+
+    #[test]
+    fn test_large_request_restart() {
+        let mut buffer = [0u8; 8];
+        let mut ctx = LargeRequestCtx::new(&mut buffer);
+
+        ctx.start(HANDLE, 8).unwrap();
+        assert_eq!(ctx.append_chunk(HANDLE, 0, &[1; 4], false), Ok(false));
+
+        // A new large request drops the partially received one
+        ctx.start(HANDLE + 1, 4).unwrap();
+        assert_eq!(
+            ctx.append_chunk(HANDLE, 1, &[1; 4], true),
+            Err(ChunkError::InvalidChunkHandle)
+        );
+        assert_eq!(ctx.append_chunk(HANDLE + 1, 0, &[2; 4], true), Ok(true));
+
+        let (buffer, len) = ctx.take_request().unwrap();
+        assert_eq!(&buffer[..len], &[2; 4]);
+    }
+}
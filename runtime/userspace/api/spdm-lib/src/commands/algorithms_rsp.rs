@@ -26,7 +26,8 @@ struct NegotiateAlgorithmsReq {
     other_param_support: OtherParamSupport,
     base_asym_algo: BaseAsymAlgo,
     base_hash_algo: BaseHashAlgo,
-    reserved_1: [u8; 12],
+    pqc_asym_algo: PqcAsymAlgo,
+    reserved_1: [u8; 8],
     ext_asyn_count: u8,
     ext_hash_count: u8,
     reserved_2: u8,
@@ -81,7 +82,8 @@ struct AlgorithmsResp {
     measurement_hash_algo: MeasurementHashAlgo,
     base_asym_sel: BaseAsymAlgo,
     base_hash_sel: BaseHashAlgo,
-    reserved_2: [u8; 11],
+    pqc_asym_sel: PqcAsymAlgo,
+    reserved_2: [u8; 7],
     mel_specification_sel: MelSpecification,
     ext_asym_sel_count: u8,
     ext_hash_sel_count: u8,
@@ -156,6 +158,45 @@ pub(crate) fn selected_measurement_specification(ctx: &SpdmContext) -> Measureme
     measurement_specification_sel
 }
 
+pub(crate) fn selected_mel_specification(ctx: &SpdmContext) -> MelSpecification {
+    let local_algorithms = &ctx.local_algorithms.device_algorithms;
+    let peer_algorithms = ctx.state.connection_info.peer_algorithms();
+    let algorithm_priority_table = &ctx.local_algorithms.algorithm_priority_table;
+
+    let mut mel_specification_sel = MelSpecification::default();
+    if ctx.local_capabilities.flags.mel_cap() == 1 {
+        mel_specification_sel = MelSpecification(local_algorithms.mel_specification.0.prioritize(
+            &peer_algorithms.mel_specification.0,
+            algorithm_priority_table.mel_specification,
+        ));
+    }
+    mel_specification_sel
+}
+
+/// Returns the selected BaseAsymSel and PqcAsymSel. At most one of them is non-zero.
+/// A common PQC asymmetric algorithm is preferred over the traditional ones from v1.4.
+pub(crate) fn selected_asym_algo(ctx: &SpdmContext) -> (BaseAsymAlgo, PqcAsymAlgo) {
+    let local_algorithms = &ctx.local_algorithms.device_algorithms;
+    let peer_algorithms = ctx.state.connection_info.peer_algorithms();
+    let algorithm_priority_table = &ctx.local_algorithms.algorithm_priority_table;
+
+    if ctx.state.connection_info.version_number() >= SpdmVersion::V14 {
+        let pqc_asym_sel = PqcAsymAlgo(local_algorithms.pqc_asym_algo.0.prioritize(
+            &peer_algorithms.pqc_asym_algo.0,
+            algorithm_priority_table.pqc_asym_algo,
+        ));
+        if pqc_asym_sel.0 != 0 {
+            return (BaseAsymAlgo::default(), pqc_asym_sel);
+        }
+    }
+
+    let base_asym_sel = BaseAsymAlgo(local_algorithms.base_asym_algo.0.prioritize(
+        &peer_algorithms.base_asym_algo.0,
+        algorithm_priority_table.base_asym_algo,
+    ));
+    (base_asym_sel, PqcAsymAlgo::default())
+}
+
 async fn process_negotiate_algorithms_request<'a>(
     ctx: &mut SpdmContext<'a>,
     spdm_hdr: SpdmMsgHdr,
@@ -171,7 +212,13 @@ async fn process_negotiate_algorithms_request<'a>(
     })?;
 
     // Reserved fields check
-    if req.param2 != 0 || req.reserved_1 != [0; 12] || req.reserved_2 != 0 {
+    if req.param2 != 0 || req.reserved_1 != [0; 8] || req.reserved_2 != 0 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+
+    // PqcAsymAlgo is reserved before v1.4
+    let pqc_asym_algo = req.pqc_asym_algo;
+    if connection_version < SpdmVersion::V14 && pqc_asym_algo.0 != 0 {
         Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
     }
 
@@ -265,6 +312,7 @@ async fn process_negotiate_algorithms_request<'a>(
         measurement_hash_algo,
         base_asym_algo: req.base_asym_algo,
         base_hash_algo: req.base_hash_algo,
+        pqc_asym_algo,
         mel_specification: req.mel_specification,
         dhe_group,
         aead_cipher_suite,
@@ -325,11 +373,8 @@ async fn generate_algorithms_response<'a>(
         measurement_hash_algo = local_algorithms.measurement_hash_algo;
     }
 
-    // BaseAsymSel
-    let base_asym_sel = BaseAsymAlgo(local_algorithms.base_asym_algo.0.prioritize(
-        &peer_algorithms.base_asym_algo.0,
-        algorithm_priority_table.base_asym_algo,
-    ));
+    // BaseAsymSel and PqcAsymSel
+    let (base_asym_sel, pqc_asym_sel) = selected_asym_algo(ctx);
 
     // BaseHashSel
     let base_hash_sel = local_algorithms.base_hash_algo.prioritize(
@@ -338,10 +383,7 @@ async fn generate_algorithms_response<'a>(
     );
 
     // MelSpecificationSel
-    let mel_specification_sel = MelSpecification(local_algorithms.mel_specification.0.prioritize(
-        &peer_algorithms.mel_specification.0,
-        algorithm_priority_table.mel_specification,
-    ));
+    let mel_specification_sel = selected_mel_specification(ctx);
 
     let algorithms_rsp = AlgorithmsResp {
         num_alg_struct_tables: num_alg_struct_tables as u8,
@@ -352,7 +394,8 @@ async fn generate_algorithms_response<'a>(
         measurement_hash_algo,
         base_asym_sel,
         base_hash_sel,
-        reserved_2: [0; 11],
+        pqc_asym_sel,
+        reserved_2: [0; 7],
         mel_specification_sel,
         ext_asym_sel_count: 0,
         ext_hash_sel_count: 0,
@@ -247,8 +247,9 @@ async fn process_get_certificate<'a>(
     }
 
     // Check if the slot is provisioned. Otherwise, return an InvalidRequest error.
+    let asym_algo = ctx.validate_negotiated_base_asym_algo(req_payload)?;
     let slot_mask = 1 << slot_id;
-    let (_, provisioned_slot_mask) = cert_slot_mask(ctx.device_certs_store).await;
+    let (_, provisioned_slot_mask) = cert_slot_mask(ctx.device_certs_store, asym_algo).await;
 
     if provisioned_slot_mask & slot_mask == 0 {
         Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
@@ -272,7 +273,6 @@ async fn process_get_certificate<'a>(
         .await?;
 
     // Prepare the response context
-    let asym_algo = ctx.validate_negotiated_base_asym_algo(req_payload)?;
     let certchain_len = spdm_cert_chain_len(ctx.device_certs_store, slot_id, asym_algo)
         .await
         .map_err(|_| {
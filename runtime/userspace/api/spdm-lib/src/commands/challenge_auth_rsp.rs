@@ -1,5 +1,5 @@
 // Licensed under the Apache-2.0 license
-use crate::cert_store::{spdm_cert_chain_hash, MAX_CERT_SLOTS_SUPPORTED};
+use crate::cert_store::{spdm_cert_chain_hash, spdm_sign, MAX_CERT_SLOTS_SUPPORTED};
 use crate::codec::{Codec, CommonCodec, MessageBuf};
 use crate::commands::algorithms_rsp::selected_measurement_specification;
 use crate::commands::error_rsp::ErrorCode;
@@ -10,7 +10,7 @@ use crate::state::ConnectionState;
 use crate::transcript::TranscriptContext;
 use bitfield::bitfield;
 use caliptra_mcu_libapi_caliptra::crypto::asym::*;
-use caliptra_mcu_libapi_caliptra::crypto::hash::{HashAlgoType, HashContext, SHA384_HASH_SIZE};
+use caliptra_mcu_libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
 use caliptra_mcu_libapi_caliptra::crypto::rng::Rng;
 use zerocopy::{FromBytes, Immutable, IntoBytes};
 
@@ -84,10 +84,11 @@ async fn process_challenge<'a>(
     }
 
     // Note: Pubkey of the responder will not be pre-provisioned to Requester. So slot ID 0xFF is invalid.
+    let asym_algo = ctx.validate_negotiated_base_asym_algo(req_payload)?;
     if challenge_req.slot_id >= MAX_CERT_SLOTS_SUPPORTED
         || !ctx
             .device_certs_store
-            .is_provisioned(challenge_req.slot_id)
+            .is_provisioned(challenge_req.slot_id, asym_algo)
             .await
     {
         Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
@@ -131,53 +132,25 @@ async fn encode_m1_signature<'a>(
         .await
         .map_err(|e| (false, CommandError::Transcript(e)))?;
 
-    let signing_context = if spdm_version >= SpdmVersion::V12 {
-        Some(
-            create_responder_signing_context(spdm_version, ReqRespCode::ChallengeAuth)
-                .map_err(|e| (false, CommandError::SignCtx(e)))?,
-        )
-    } else {
-        None
-    };
-
-    let context = signing_context.as_ref().map(|x| &x[..]);
-
-    let mut hash_ctx = HashContext::new();
-    let tbs = if let Some(context) = context {
-        // If the signing context is present, use it to compute the TBS hash
-        let mut tbs = [0u8; SHA384_HASH_SIZE];
-        let mut message = [0u8; SPDM_SIGNING_CONTEXT_LEN + SHA384_HASH_SIZE];
-        message[..SPDM_SIGNING_CONTEXT_LEN].copy_from_slice(context);
-        message[SPDM_SIGNING_CONTEXT_LEN..]
-            .copy_from_slice(&m1_transcript_hash[..SHA384_HASH_SIZE]);
-        hash_ctx
-            .init(HashAlgoType::SHA384, Some(&message[..]))
-            .await
-            .map_err(|e| (false, CommandError::CaliptraApi(e)))?;
-        hash_ctx
-            .finalize(&mut tbs)
-            .await
-            .map_err(|e| (false, CommandError::CaliptraApi(e)))?;
-        tbs
-    } else {
-        m1_transcript_hash
-    };
-
-    let mut signature = [0u8; ECC_P384_SIGNATURE_SIZE];
+    let message = SigningMessage::new(spdm_version, ReqRespCode::ChallengeAuth, m1_transcript_hash)
+        .map_err(|e| (false, CommandError::SignCtx(e)))?;
 
-    ctx.device_certs_store
-        .sign_hash(slot_id, asym_algo, &tbs, &mut signature)
-        .await
-        .map_err(|e| (false, CommandError::CertStore(e)))?;
-
-    // Encode the signature
+    // Sign directly into the signature field of the response
     let sig_len = asym_algo.signature_size();
     rsp.put_data(sig_len)
         .map_err(|e| (false, CommandError::Codec(e)))?;
     let sig_buf = rsp
         .data_mut(sig_len)
         .map_err(|e| (false, CommandError::Codec(e)))?;
-    sig_buf.copy_from_slice(&signature[..sig_len]);
+    spdm_sign(
+        ctx.device_certs_store,
+        slot_id,
+        asym_algo,
+        &message,
+        sig_buf,
+    )
+    .await
+    .map_err(|e| (false, CommandError::CertStore(e)))?;
     rsp.pull_data(sig_len)
         .map_err(|e| (false, CommandError::Codec(e)))?;
 
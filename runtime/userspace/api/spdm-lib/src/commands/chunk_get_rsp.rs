@@ -176,6 +176,12 @@ async fn encode_chunk_data(
                     )
                     .await?
             }
+            LargeResponse::Csr(csr_rsp) => {
+                // Get the chunk data from the CSR response
+                csr_rsp
+                    .get_chunk(ctx.device_certs_store, offset, chunk_buf)
+                    .await?
+            }
             LargeResponse::Measurements(meas_rsp) => {
                 // Get the session info for measurements chunked within a session
                 let session_info = match ctx.session_mgr.active_session_id() {
@@ -197,9 +203,21 @@ async fn encode_chunk_data(
                     )
                     .await?
             }
+            LargeResponse::Mel(mel_rsp) => {
+                // Get the chunk data from the MEL response
+                mel_rsp
+                    .get_chunk(&mut ctx.measurements, offset, chunk_buf)
+                    .await?
+            }
             LargeResponse::Vdm(_vdm_rsp) => {
                 todo!("implement chunking logic for VDM response")
             }
+            LargeResponse::Buffered => {
+                // Get the chunk data from the large response buffer
+                ctx.large_resp_context
+                    .buffered_chunk(offset, chunk_buf)
+                    .map_err(|e| (false, CommandError::Chunk(e)))?
+            }
         }
     } else {
         Err((
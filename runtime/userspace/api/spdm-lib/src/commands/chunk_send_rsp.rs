@@ -0,0 +1,263 @@
+// Licensed under the Apache-2.0 license
+use crate::codec::{encode_u8_slice, Codec, CommonCodec, MessageBuf};
+use crate::commands::error_rsp::ErrorCode;
+use crate::context::SpdmContext;
+use crate::error::{CommandError, CommandResult};
+use crate::protocol::*;
+use crate::state::ConnectionState;
+use bitfield::bitfield;
+use core::mem::size_of;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+struct ChunkSendReq {
+    chunk_sender_attr: ChunkSenderAttr,
+    handle: u8,
+    chunk_seq_num: u16,
+    _reserved: u16,
+    chunk_size: u32,
+}
+impl CommonCodec for ChunkSendReq {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct LargeMessageSize(u32);
+impl CommonCodec for LargeMessageSize {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+struct ChunkSendAckRsp {
+    chunk_receiver_attr: ChunkReceiverAttr,
+    handle: u8,
+    chunk_seq_num: u16,
+}
+impl CommonCodec for ChunkSendAckRsp {}
+
+bitfield! {
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct ChunkSenderAttr(u8);
+impl Debug;
+u8;
+pub last_chunk, set_last_chunk: 0, 0;
+reserved, _: 7, 1;
+}
+
+bitfield! {
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct ChunkReceiverAttr(u8);
+impl Debug;
+u8;
+pub early_error_detected, set_early_error_detected: 0, 0;
+reserved, _: 7, 1;
+}
+
+fn process_chunk_send<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<(u8, u16, bool)> {
+    // Check that the spdm version valid and is >= SPDM_VERSION_1_2
+    let connection_version = ctx.validate_spdm_version(&spdm_hdr, req_payload)?;
+
+    if connection_version < SpdmVersion::V12 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    // Decode the request payload
+    let chunk_send_req = ChunkSendReq::decode(req_payload).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+    let handle = chunk_send_req.handle;
+    let chunk_seq_num = chunk_send_req.chunk_seq_num;
+    let last_chunk = chunk_send_req.chunk_sender_attr.last_chunk() == 1;
+
+    // The first chunk carries the size of the large request and starts a new transfer
+    if chunk_seq_num == 0 {
+        let large_msg_size = LargeMessageSize::decode(req_payload).map_err(|_| {
+            ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+        })?;
+        let large_msg_size = large_msg_size.0 as usize;
+
+        // A request that fits into the DataTransferSize must not be chunked
+        if large_msg_size <= ctx.local_capabilities.data_transfer_size as usize {
+            Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+        }
+
+        if large_msg_size > ctx.local_capabilities.max_spdm_msg_size as usize
+            || ctx.large_req_context.start(handle, large_msg_size).is_err()
+        {
+            Err(ctx.generate_error_response(req_payload, ErrorCode::RequestTooLarge, 0, None))?;
+        }
+    } else if !ctx.large_req_context.in_progress() {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    let chunk_size = chunk_send_req.chunk_size as usize;
+    let chunk = match req_payload.data(chunk_size) {
+        Ok(chunk) => chunk,
+        Err(_) => {
+            ctx.large_req_context.reset();
+            return Err(ctx.generate_error_response(
+                req_payload,
+                ErrorCode::InvalidRequest,
+                0,
+                None,
+            ));
+        }
+    };
+
+    // Out-of-order, foreign or inconsistent chunks abort the transfer
+    let complete =
+        match ctx
+            .large_req_context
+            .append_chunk(handle, chunk_seq_num, chunk, last_chunk)
+        {
+            Ok(complete) => complete,
+            Err(_) => {
+                ctx.large_req_context.reset();
+                return Err(ctx.generate_error_response(
+                    req_payload,
+                    ErrorCode::InvalidRequest,
+                    0,
+                    None,
+                ));
+            }
+        };
+
+    Ok((handle, chunk_seq_num, complete))
+}
+
+/// Processes the reassembled large request like a non-chunked request.
+/// The response is encoded into the large request buffer.
+async fn handle_large_request<'a>(
+    ctx: &mut SpdmContext<'a>,
+    large_req: &mut MessageBuf<'a>,
+    large_msg_size: usize,
+) -> CommandResult<()> {
+    large_req
+        .put_data(large_msg_size)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    let req_msg_header = SpdmMsgHdr::decode(large_req)
+        .map_err(|_| ctx.generate_error_response(large_req, ErrorCode::InvalidRequest, 0, None))?;
+
+    let req_code = req_msg_header.req_resp_code().map_err(|_| {
+        ctx.generate_error_response(large_req, ErrorCode::UnsupportedRequest, 0, None)
+    })?;
+
+    // Chunking requests cannot be chunked themselves
+    if req_code == ReqRespCode::ChunkSend || req_code == ReqRespCode::ChunkGet {
+        Err(ctx.generate_error_response(large_req, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    ctx.dispatch_request(req_msg_header, req_code, large_req)
+        .await
+}
+
+fn generate_chunk_send_ack<'a>(
+    ctx: &SpdmContext<'a>,
+    handle: u8,
+    chunk_seq_num: u16,
+    response: Option<&[u8]>,
+    rsp: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    // Spdm Header first
+    let connection_version = ctx.state.connection_info.version_number();
+    let spdm_hdr = SpdmMsgHdr::new(connection_version, ReqRespCode::ChunkSendAck);
+    let mut payload_len = spdm_hdr
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    let chunk_send_ack = ChunkSendAckRsp {
+        chunk_receiver_attr: ChunkReceiverAttr(0),
+        handle,
+        chunk_seq_num,
+    };
+    payload_len += chunk_send_ack
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    // The response to the large request follows the acknowledgement of the last chunk
+    if let Some(response) = response {
+        payload_len +=
+            encode_u8_slice(response, rsp).map_err(|e| (false, CommandError::Codec(e)))?;
+    }
+
+    rsp.push_data(payload_len)
+        .map_err(|e| (false, CommandError::Codec(e)))
+}
+
+async fn generate_large_request_response<'a>(
+    ctx: &mut SpdmContext<'a>,
+    handle: u8,
+    chunk_seq_num: u16,
+    rsp: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    let (buffer, large_msg_size) = ctx
+        .large_req_context
+        .take_request()
+        .map_err(|e| (false, CommandError::Chunk(e)))?;
+    let mut large_req = MessageBuf::new(buffer);
+
+    let result = match handle_large_request(ctx, &mut large_req, large_msg_size).await {
+        // The response or the error response is embedded into CHUNK_SEND_ACK
+        Ok(()) | Err((true, _)) => {
+            let max_response_size = ctx
+                .min_data_transfer_size()
+                .saturating_sub(size_of::<SpdmMsgHdr>() + size_of::<ChunkSendAckRsp>());
+            if large_req.data_len() > max_response_size {
+                ctx.large_resp_context.reset();
+                let _ = ctx.generate_error_response(
+                    &mut large_req,
+                    ErrorCode::ResponseTooLarge,
+                    0,
+                    None,
+                );
+            }
+
+            ctx.prepare_response_buffer(rsp)?;
+            match large_req.data(large_req.data_len()) {
+                Ok(response) => {
+                    generate_chunk_send_ack(ctx, handle, chunk_seq_num, Some(response), rsp)
+                }
+                Err(e) => Err((false, CommandError::Codec(e))),
+            }
+        }
+        Err(e) => Err(e),
+    };
+
+    // Hand the reassembly buffer back for the next large request
+    ctx.large_req_context.restore_buffer(large_req.into_inner());
+
+    result
+}
+
+pub(crate) async fn handle_chunk_send<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    // Perform all checks and send a error response if any fail
+    // 1. Check CHUNK_SEND is sent after CAPABILITIES
+    // 2. Check if chunk capabilities are enabled
+    if ctx.state.connection_info.state() < ConnectionState::AfterCapabilities
+        || ctx.local_capabilities.flags.chunk_cap() == 0
+    {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    // process CHUNK_SEND request
+    let (handle, chunk_seq_num, complete) = process_chunk_send(ctx, spdm_hdr, req_payload)?;
+
+    if !complete {
+        // Acknowledge the chunk and wait for the next one
+        ctx.prepare_response_buffer(req_payload)?;
+        return generate_chunk_send_ack(ctx, handle, chunk_seq_num, None, req_payload);
+    }
+
+    // Process the reassembled large request and generate CHUNK_SEND_ACK with its response
+    generate_large_request_response(ctx, handle, chunk_seq_num, req_payload).await
+}
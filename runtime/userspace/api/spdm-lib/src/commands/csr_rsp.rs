@@ -0,0 +1,249 @@
+// Licensed under the Apache-2.0 license
+
+use crate::cert_store::SpdmCertStore;
+use crate::chunk_ctx::LargeResponse;
+use crate::codec::{encode_u8_slice, Codec, CommonCodec, MessageBuf};
+use crate::commands::error_rsp::ErrorCode;
+use crate::commands::set_certificate_rsp::cert_store_error_code;
+use crate::context::SpdmContext;
+use crate::error::{CommandError, CommandResult};
+use crate::protocol::*;
+use crate::state::ConnectionState;
+use bitfield::bitfield;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+const CSR_RESP_HEADER_SIZE: usize = size_of::<CsrRespHdr>();
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+struct GetCsrReqBase {
+    key_pair_id: u8,
+    param2: CsrReqAttributes,
+    requester_info_len: u16,
+    opaque_data_len: u16,
+}
+impl CommonCodec for GetCsrReqBase {}
+
+bitfield! {
+    #[derive(FromBytes, IntoBytes, Immutable)]
+    #[repr(C)]
+    struct CsrReqAttributes(u8);
+    impl Debug;
+    u8;
+    pub csr_cert_model, set_csr_cert_model: 2, 0;
+    reserved1, _: 3, 3;
+    pub csr_tracking_tag, set_csr_tracking_tag: 6, 4;
+    pub overwrite, set_overwrite: 7, 7;
+}
+
+#[derive(IntoBytes, FromBytes, Immutable)]
+#[repr(C, packed)]
+struct CsrRespHdr {
+    spdm_version: SpdmMsgHdr,
+    param1: u8,
+    param2: u8,
+    csr_len: u16,
+    reserved: u16,
+}
+impl CommonCodec for CsrRespHdr {}
+
+#[derive(Debug, Clone)]
+pub(crate) struct CsrResponse {
+    spdm_version: SpdmVersion,
+    csr_len: u16,
+}
+
+impl CsrResponse {
+    fn resp_hdr(&self) -> CommandResult<[u8; CSR_RESP_HEADER_SIZE]> {
+        let mut buf = [0u8; CSR_RESP_HEADER_SIZE];
+        let mut msg_buf = MessageBuf::new(&mut buf);
+
+        let csr_rsp_hdr = CsrRespHdr {
+            spdm_version: SpdmMsgHdr::new(self.spdm_version, ReqRespCode::Csr),
+            param1: 0,
+            param2: 0,
+            csr_len: self.csr_len,
+            reserved: 0,
+        };
+        csr_rsp_hdr
+            .encode(&mut msg_buf)
+            .map_err(|e| (false, CommandError::Codec(e)))?;
+
+        Ok(buf)
+    }
+
+    fn rsp_len(&self) -> usize {
+        CSR_RESP_HEADER_SIZE + self.csr_len as usize
+    }
+
+    pub async fn get_chunk(
+        &self,
+        cert_store: &dyn SpdmCertStore,
+        csr_rsp_offset: usize,
+        chunk: &mut [u8],
+    ) -> CommandResult<usize> {
+        let csr_offset: usize;
+        let mut chunk_data_len = 0;
+        if csr_rsp_offset < CSR_RESP_HEADER_SIZE {
+            // Read from the response header
+            let header_bytes = self.resp_hdr()?;
+            let copy_len = (CSR_RESP_HEADER_SIZE - csr_rsp_offset).min(chunk.len());
+            chunk[..copy_len]
+                .copy_from_slice(&header_bytes[csr_rsp_offset..csr_rsp_offset + copy_len]);
+            chunk_data_len = copy_len;
+            csr_offset = 0;
+        } else {
+            csr_offset = csr_rsp_offset - CSR_RESP_HEADER_SIZE;
+        }
+
+        let rem_len =
+            (chunk.len() - chunk_data_len).min((self.csr_len as usize).saturating_sub(csr_offset));
+        if rem_len > 0 {
+            let rem_chunk = &mut chunk[chunk_data_len..chunk_data_len + rem_len];
+            chunk_data_len += cert_store
+                .get_csr(csr_offset, rem_chunk)
+                .await
+                .map_err(|e| (false, CommandError::CertStore(e)))?;
+        }
+
+        Ok(chunk_data_len)
+    }
+}
+
+async fn process_get_csr<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<CsrResponse> {
+    // Validate the version
+    let connection_version = ctx.validate_spdm_version(&spdm_hdr, req_payload)?;
+
+    // GET_CSR is not supported before v1.2
+    if connection_version < SpdmVersion::V12 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    let req = GetCsrReqBase::decode(req_payload).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    // KeyPairID and RequestAttributes are reserved before v1.3
+    let (key_pair_id, cert_model) = if connection_version >= SpdmVersion::V13 {
+        let cert_model = CertModel::try_from(req.param2.csr_cert_model()).map_err(|_| {
+            ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+        })?;
+
+        // The certificate model of the CSR is only selectable with multiple asymmetric keys
+        let valid = if ctx.state.connection_info.multi_key_conn_rsp() {
+            cert_model != CertModel::None
+        } else {
+            req.key_pair_id == 0 && cert_model == CertModel::None
+        };
+
+        // CSR tracking is not supported, so the Requester cannot refer to a pending CSR
+        if !valid || req.param2.csr_tracking_tag() != 0 {
+            Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+        }
+        (req.key_pair_id, cert_model)
+    } else {
+        (0, CertModel::None)
+    };
+
+    let requester_info_len = req.requester_info_len as usize;
+    let opaque_data_len = req.opaque_data_len as usize;
+    if req_payload.data_len() < requester_info_len + opaque_data_len {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+
+    ctx.reset_transcript_via_req_code(ReqRespCode::GetCsr);
+
+    // Generate the CSR for the RequesterInfo. The OpaqueData is not interpreted.
+    let requester_info = req_payload
+        .data(requester_info_len)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+    let csr_result = ctx
+        .device_certs_store
+        .generate_csr(key_pair_id, cert_model, requester_info)
+        .await;
+
+    let csr_len = match csr_result {
+        Ok(len) if len <= u16::MAX as usize => len,
+        Ok(_) => {
+            Err(ctx.generate_error_response(req_payload, ErrorCode::ResponseTooLarge, 0, None))?
+        }
+        Err(e) => {
+            let error_code = cert_store_error_code(ctx, &e);
+            Err(ctx.generate_error_response(req_payload, error_code, 0, None))?
+        }
+    };
+
+    Ok(CsrResponse {
+        spdm_version: connection_version,
+        csr_len: csr_len as u16,
+    })
+}
+
+async fn generate_csr_response<'a>(
+    ctx: &mut SpdmContext<'a>,
+    rsp_ctx: CsrResponse,
+    rsp: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    let rsp_len = rsp_ctx.rsp_len();
+    if rsp_len > ctx.min_data_transfer_size() {
+        if !ctx.support_large_msg_chunking() {
+            Err(ctx.generate_error_response(rsp, ErrorCode::ResponseTooLarge, 0, None))?;
+        }
+
+        // The CSR does not fit into a single message, send it with CHUNK_GET
+        let large_rsp = LargeResponse::Csr(rsp_ctx.clone());
+        let handle = ctx.large_resp_context.init(large_rsp, rsp_len);
+        Err(ctx.generate_error_response(rsp, ErrorCode::LargeResponse, 0, Some(&[handle])))?;
+    }
+
+    let mut payload_len =
+        encode_u8_slice(&rsp_ctx.resp_hdr()?, rsp).map_err(|e| (false, CommandError::Codec(e)))?;
+
+    let csr_len = rsp_ctx.csr_len as usize;
+    rsp.put_data(csr_len)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+    let csr_buf = rsp
+        .data_mut(csr_len)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+    let read_len = ctx
+        .device_certs_store
+        .get_csr(0, csr_buf)
+        .await
+        .map_err(|e| (false, CommandError::CertStore(e)))?;
+    if read_len != csr_len {
+        Err(ctx.generate_error_response(rsp, ErrorCode::Unspecified, 0, None))?;
+    }
+    rsp.pull_data(read_len)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+    payload_len += read_len;
+
+    rsp.push_data(payload_len)
+        .map_err(|e| (false, CommandError::Codec(e)))
+}
+
+pub(crate) async fn handle_get_csr<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    // Check if the connection state is valid
+    if ctx.state.connection_info.state() < ConnectionState::AlgorithmsNegotiated {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    // Check if CSR_CAP is supported
+    if ctx.local_capabilities.flags.csr_cap() == 0 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    // Process GET_CSR request
+    let rsp_ctx = process_get_csr(ctx, spdm_hdr, req_payload).await?;
+
+    // Generate CSR response
+    ctx.prepare_response_buffer(req_payload)?;
+    generate_csr_response(ctx, rsp_ctx, req_payload).await
+}
@@ -63,7 +63,8 @@ async fn generate_digests_response<'a>(
     let asym_algo = ctx.validate_negotiated_base_asym_algo(rsp)?;
 
     // Get the supported and provisioned slot masks.
-    let (supported_slot_mask, provisioned_slot_mask) = cert_slot_mask(ctx.device_certs_store).await;
+    let (supported_slot_mask, provisioned_slot_mask) =
+        cert_slot_mask(ctx.device_certs_store, asym_algo).await;
 
     // No slots provisioned with certificates
     let slot_cnt = provisioned_slot_mask.count_ones() as usize;
@@ -0,0 +1,174 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{Codec, CommonCodec, MessageBuf};
+use crate::commands::certificate_rsp::{CertificateReqAttributes, GetCertificateReq, SlotId};
+use crate::commands::digests_rsp::GetDigestsReq;
+use crate::commands::error_rsp::ErrorCode;
+use crate::context::SpdmContext;
+use crate::encap_ctx::{EncapError, EncapState};
+use crate::error::{CommandError, CommandResult};
+use crate::protocol::*;
+use crate::state::ConnectionState;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct GetEncapsulatedRequestReq {
+    _param1: u8,
+    _param2: u8,
+}
+impl CommonCodec for GetEncapsulatedRequestReq {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct EncapsulatedRequestRspBase {
+    request_id: u8,
+    _reserved: u8,
+}
+impl CommonCodec for EncapsulatedRequestRspBase {}
+
+/// Returns the ID of the session in the Session Handshake phase that the encapsulated
+/// request flow belongs to.
+pub(crate) fn encap_session_id(
+    ctx: &SpdmContext<'_>,
+    req_payload: &mut MessageBuf<'_>,
+) -> CommandResult<u32> {
+    if ctx.state.connection_info.handshake_in_the_clear() {
+        // For handshake in the clear: the session of the single pending handshake,
+        // no active session
+        if ctx.session_mgr.active_session_id().is_some() {
+            Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+        }
+        ctx.session_mgr.pending_key_exchange_session_id()
+    } else {
+        // If handshake is not in the clear, the request is sent within the session
+        ctx.session_mgr.active_session_id()
+    }
+    .ok_or_else(|| ctx.generate_error_response(req_payload, ErrorCode::SessionRequired, 0, None))
+}
+
+/// Encode the encapsulated request of the given state of the flow.
+/// GET_DIGESTS selects the Requester certificate chain, GET_CERTIFICATE retrieves
+/// the next portion of it.
+pub(crate) fn encode_encapsulated_request(
+    ctx: &SpdmContext<'_>,
+    state: EncapState,
+    rsp: &mut MessageBuf<'_>,
+) -> CommandResult<usize> {
+    let connection_version = ctx.state.connection_info.version_number();
+
+    match state {
+        EncapState::WaitDigests => {
+            let mut len = SpdmMsgHdr::new(connection_version, ReqRespCode::GetDigests)
+                .encode(rsp)
+                .map_err(|e| (false, CommandError::Codec(e)))?;
+            len += GetDigestsReq::default()
+                .encode(rsp)
+                .map_err(|e| (false, CommandError::Codec(e)))?;
+            Ok(len)
+        }
+        EncapState::WaitCertificate => {
+            let mut slot_id = SlotId(0);
+            slot_id.set_slot_id(ctx.encap_ctx.slot_id());
+            let get_cert_req = GetCertificateReq {
+                slot_id,
+                param2: CertificateReqAttributes(0),
+                offset: ctx.encap_ctx.received_len() as u16,
+                length: ctx
+                    .encap_ctx
+                    .next_portion_len(SPDM_MAX_CERT_CHAIN_PORTION_LEN as usize)
+                    as u16,
+            };
+
+            let mut len = SpdmMsgHdr::new(connection_version, ReqRespCode::GetCertificate)
+                .encode(rsp)
+                .map_err(|e| (false, CommandError::Codec(e)))?;
+            len += get_cert_req
+                .encode(rsp)
+                .map_err(|e| (false, CommandError::Codec(e)))?;
+            Ok(len)
+        }
+        _ => Err((
+            false,
+            CommandError::Encap(EncapError::NoEncapRequestInProgress),
+        )),
+    }
+}
+
+fn process_get_encapsulated_request<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    // Validate the version
+    let _ = ctx.validate_spdm_version(&spdm_hdr, req_payload)?;
+
+    let _ = GetEncapsulatedRequestReq::decode(req_payload).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    // The flow is started by KEY_EXCHANGE_RSP with MutAuthRequested set
+    let session_id = encap_session_id(ctx, req_payload)?;
+    if ctx.encap_ctx.state(session_id) != Ok(EncapState::Pending) {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    ctx.reset_transcript_via_req_code(ReqRespCode::GetEncapsulatedRequest);
+
+    Ok(())
+}
+
+fn generate_encapsulated_request_response(
+    ctx: &mut SpdmContext<'_>,
+    rsp: &mut MessageBuf<'_>,
+) -> CommandResult<()> {
+    // Spdm Header first
+    let connection_version = ctx.state.connection_info.version_number();
+    let spdm_hdr = SpdmMsgHdr::new(connection_version, ReqRespCode::EncapsulatedRequest);
+    let mut payload_len = spdm_hdr
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    let encap_req_rsp = EncapsulatedRequestRspBase {
+        request_id: ctx.encap_ctx.next_request_id(),
+        _reserved: 0,
+    };
+    payload_len += encap_req_rsp
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    // The first encapsulated request retrieves the Requester certificate chain digests
+    payload_len += encode_encapsulated_request(ctx, EncapState::WaitDigests, rsp)?;
+    ctx.encap_ctx.set_state(EncapState::WaitDigests);
+
+    rsp.push_data(payload_len)
+        .map_err(|e| (false, CommandError::Codec(e)))
+}
+
+pub(crate) async fn handle_get_encapsulated_request<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    // Check if the connection state is valid
+    if ctx.state.connection_info.state() < ConnectionState::AlgorithmsNegotiated {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    // GET_ENCAPSULATED_REQUEST is not supported in v1.0
+    if ctx.state.connection_info.version_number() < SpdmVersion::V11 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    // Check if ENCAP_CAP is supported
+    if ctx.local_capabilities.flags.encap_cap() == 0 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    // Process GET_ENCAPSULATED_REQUEST request
+    process_get_encapsulated_request(ctx, spdm_hdr, req_payload)?;
+
+    // Generate ENCAPSULATED_REQUEST response
+    ctx.prepare_response_buffer(req_payload)?;
+    generate_encapsulated_request_response(ctx, req_payload)
+}
@@ -0,0 +1,320 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{decode_u8_slice, Codec, CommonCodec, MessageBuf};
+use crate::commands::digests_rsp::GetDigestsRespCommon;
+use crate::commands::encapsulated_request_rsp::{encap_session_id, encode_encapsulated_request};
+use crate::commands::error_rsp::ErrorCode;
+use crate::context::SpdmContext;
+use crate::encap_ctx::EncapState;
+use crate::error::{CommandError, CommandResult};
+use crate::protocol::*;
+use crate::session::RequesterIdentity;
+use crate::state::ConnectionState;
+use crate::trust_anchor_store::{sha384, verify_requester_cert_chain};
+use caliptra_mcu_libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct DeliverEncapsulatedResponseReqBase {
+    request_id: u8,
+    _reserved: u8,
+}
+impl CommonCodec for DeliverEncapsulatedResponseReqBase {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct EncapsulatedResponseAckBase {
+    request_id: u8,
+    payload_type: u8,
+}
+impl CommonCodec for EncapsulatedResponseAckBase {}
+
+// Fields of ENCAPSULATED_RESPONSE_ACK introduced in v1.2
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct EncapsulatedResponseAckV12 {
+    ack_request_id: u8,
+    _reserved: [u8; 3],
+}
+impl CommonCodec for EncapsulatedResponseAckV12 {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct EncapCertificateRspBase {
+    slot_id: u8,
+    _param2: u8,
+    portion_length: u16,
+    remainder_length: u16,
+}
+impl CommonCodec for EncapCertificateRspBase {}
+
+#[repr(u8)]
+enum EncapPayloadType {
+    Present = 1,
+    ReqSlotNumber = 2,
+}
+
+/// Next step of the encapsulated request flow after a delivered response
+enum EncapAck {
+    /// Send the encapsulated request of the state
+    NextRequest(EncapState),
+    /// The Requester certificate chain of the slot is verified
+    Done(u8),
+}
+
+fn process_encap_digests(
+    ctx: &mut SpdmContext<'_>,
+    req_payload: &mut MessageBuf<'_>,
+) -> CommandResult<EncapAck> {
+    let digests_rsp = GetDigestsRespCommon::decode(req_payload).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidResponseCode, 0, None)
+    })?;
+
+    // Authenticate with the certificate chain of the lowest provisioned slot,
+    // its digest is the first one in the list
+    let slot_mask = digests_rsp.provisioned_slot_mask;
+    if slot_mask == 0 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidResponseCode, 0, None))?;
+    }
+    let slot_id = slot_mask.trailing_zeros() as u8;
+
+    let mut digest = [0u8; SHA384_HASH_SIZE];
+    decode_u8_slice(req_payload, &mut digest).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidResponseCode, 0, None)
+    })?;
+
+    ctx.encap_ctx.select_cert_chain(slot_id, &digest);
+    Ok(EncapAck::NextRequest(EncapState::WaitCertificate))
+}
+
+async fn process_encap_certificate(
+    ctx: &mut SpdmContext<'_>,
+    session_id: u32,
+    req_payload: &mut MessageBuf<'_>,
+) -> CommandResult<EncapAck> {
+    let cert_rsp = EncapCertificateRspBase::decode(req_payload).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidResponseCode, 0, None)
+    })?;
+
+    let slot_id = ctx.encap_ctx.slot_id();
+    let portion_len = cert_rsp.portion_length as usize;
+    if cert_rsp.slot_id & 0x0F != slot_id
+        || portion_len > SPDM_MAX_CERT_CHAIN_PORTION_LEN as usize
+        || portion_len > req_payload.data_len()
+    {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidResponseCode, 0, None))?;
+    }
+
+    let portion = req_payload
+        .data(portion_len)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+    let complete = ctx
+        .encap_ctx
+        .append_cert_chain_portion(portion, cert_rsp.remainder_length as usize);
+
+    match complete {
+        Ok(false) => Ok(EncapAck::NextRequest(EncapState::WaitCertificate)),
+        Ok(true) => {
+            verify_encap_cert_chain(ctx, session_id, req_payload).await?;
+            Ok(EncapAck::Done(slot_id))
+        }
+        Err(_) => {
+            Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidResponseCode, 0, None))
+        }
+    }
+}
+
+/// Verify the retrieved Requester certificate chain and record the Requester identity
+/// in the session.
+async fn verify_encap_cert_chain(
+    ctx: &mut SpdmContext<'_>,
+    session_id: u32,
+    req_payload: &mut MessageBuf<'_>,
+) -> CommandResult<()> {
+    let Some(trust_anchor_store) = ctx.requester_trust_anchor_store else {
+        return Err(ctx.generate_error_response(req_payload, ErrorCode::Unspecified, 0, None));
+    };
+
+    let result = async {
+        let cert_chain = ctx.encap_ctx.cert_chain().ok()?;
+
+        // The digest reported in DIGESTS covers the complete certificate chain in SPDM format
+        let cert_chain_hash = sha384(cert_chain).await.ok()?;
+        if cert_chain_hash != *ctx.encap_ctx.cert_chain_digest() {
+            return None;
+        }
+
+        let (cert_chain_hdr, certs) = SpdmCertChainHeader::read_from_prefix(cert_chain).ok()?;
+        if cert_chain_hdr.length as usize != cert_chain.len() {
+            return None;
+        }
+
+        let public_key =
+            verify_requester_cert_chain(trust_anchor_store, &cert_chain_hdr.root_hash, certs)
+                .await
+                .ok()?;
+
+        Some(RequesterIdentity {
+            slot_id: ctx.encap_ctx.slot_id(),
+            cert_chain_hash,
+            public_key,
+        })
+    }
+    .await;
+
+    let Some(requester_identity) = result else {
+        return Err(ctx.generate_error_response(
+            req_payload,
+            ErrorCode::InvalidResponseCode,
+            0,
+            None,
+        ));
+    };
+
+    let session_info = ctx
+        .session_mgr
+        .session_info_mut(session_id)
+        .map_err(|e| (false, CommandError::Session(e)))?;
+    session_info.requester_identity = Some(requester_identity);
+
+    Ok(())
+}
+
+async fn process_deliver_encapsulated_response<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<(u8, EncapAck)> {
+    // Validate the version
+    let connection_version = ctx.validate_spdm_version(&spdm_hdr, req_payload)?;
+
+    let req = DeliverEncapsulatedResponseReqBase::decode(req_payload).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    // The response must match the outstanding encapsulated request
+    let session_id = encap_session_id(ctx, req_payload)?;
+    let state = match ctx.encap_ctx.state(session_id) {
+        Ok(state @ (EncapState::WaitDigests | EncapState::WaitCertificate)) => state,
+        _ => Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?,
+    };
+    if ctx.encap_ctx.validate_request_id(req.request_id).is_err() {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+
+    ctx.reset_transcript_via_req_code(ReqRespCode::DeliverEncapsulatedResponse);
+
+    // The encapsulated response is a complete SPDM response message
+    let encap_rsp_hdr = SpdmMsgHdr::decode(req_payload).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidResponseCode, 0, None)
+    })?;
+    let expected_rsp_code = match state {
+        EncapState::WaitDigests => ReqRespCode::Digests,
+        _ => ReqRespCode::Certificate,
+    };
+    if encap_rsp_hdr.version().ok() != Some(connection_version)
+        || encap_rsp_hdr.req_resp_code().ok() != Some(expected_rsp_code)
+    {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidResponseCode, 0, None))?;
+    }
+
+    let ack = match state {
+        EncapState::WaitDigests => process_encap_digests(ctx, req_payload)?,
+        _ => process_encap_certificate(ctx, session_id, req_payload).await?,
+    };
+
+    Ok((req.request_id, ack))
+}
+
+fn generate_encapsulated_response_ack(
+    ctx: &mut SpdmContext<'_>,
+    ack_request_id: u8,
+    ack: EncapAck,
+    rsp: &mut MessageBuf<'_>,
+) -> CommandResult<()> {
+    // Spdm Header first
+    let connection_version = ctx.state.connection_info.version_number();
+    let spdm_hdr = SpdmMsgHdr::new(connection_version, ReqRespCode::EncapsulatedResponseAck);
+    let mut payload_len = spdm_hdr
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    let ack_base = match ack {
+        EncapAck::NextRequest(_) => EncapsulatedResponseAckBase {
+            request_id: ctx.encap_ctx.next_request_id(),
+            payload_type: EncapPayloadType::Present as u8,
+        },
+        EncapAck::Done(_) => EncapsulatedResponseAckBase {
+            request_id: 0,
+            payload_type: EncapPayloadType::ReqSlotNumber as u8,
+        },
+    };
+    payload_len += ack_base
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    if connection_version >= SpdmVersion::V12 {
+        let ack_v12 = EncapsulatedResponseAckV12 {
+            ack_request_id,
+            _reserved: [0; 3],
+        };
+        payload_len += ack_v12
+            .encode(rsp)
+            .map_err(|e| (false, CommandError::Codec(e)))?;
+    }
+
+    match ack {
+        EncapAck::NextRequest(state) => {
+            payload_len += encode_encapsulated_request(ctx, state, rsp)?;
+            ctx.encap_ctx.set_state(state);
+        }
+        EncapAck::Done(slot_id) => {
+            payload_len += slot_id
+                .encode(rsp)
+                .map_err(|e| (false, CommandError::Codec(e)))?;
+            ctx.encap_ctx.reset();
+        }
+    }
+
+    rsp.push_data(payload_len)
+        .map_err(|e| (false, CommandError::Codec(e)))
+}
+
+pub(crate) async fn handle_deliver_encapsulated_response<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    // Check if the connection state is valid
+    if ctx.state.connection_info.state() < ConnectionState::AlgorithmsNegotiated {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    // DELIVER_ENCAPSULATED_RESPONSE is not supported in v1.0
+    if ctx.state.connection_info.version_number() < SpdmVersion::V11 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    // Check if ENCAP_CAP is supported
+    if ctx.local_capabilities.flags.encap_cap() == 0 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    // Process DELIVER_ENCAPSULATED_RESPONSE request. An invalid encapsulated
+    // response aborts the flow, so FINISH fails the mutual authentication.
+    let (ack_request_id, ack) =
+        match process_deliver_encapsulated_response(ctx, spdm_hdr, req_payload).await {
+            Ok(result) => result,
+            Err(e) => {
+                if e.1 == CommandError::ErrorCode(ErrorCode::InvalidResponseCode) {
+                    ctx.encap_ctx.reset();
+                }
+                return Err(e);
+            }
+        };
+
+    // Generate ENCAPSULATED_RESPONSE_ACK response
+    ctx.prepare_response_buffer(req_payload)?;
+    generate_encapsulated_response_ack(ctx, ack_request_id, ack, req_payload)
+}
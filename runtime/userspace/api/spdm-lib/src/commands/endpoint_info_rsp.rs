@@ -0,0 +1,269 @@
+// Licensed under the Apache-2.0 license
+
+use crate::cert_store::{spdm_sign, MAX_CERT_SLOTS_SUPPORTED};
+use crate::codec::{encode_u8_slice, Codec, CommonCodec, MessageBuf};
+use crate::commands::error_rsp::ErrorCode;
+use crate::context::SpdmContext;
+use crate::error::{CommandError, CommandResult};
+use crate::protocol::*;
+use crate::state::ConnectionState;
+use crate::transcript::TranscriptContext;
+use bitfield::bitfield;
+use caliptra_mcu_libapi_caliptra::crypto::asym::*;
+use caliptra_mcu_libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
+use caliptra_mcu_libapi_caliptra::crypto::rng::Rng;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+// Sub code for the device class identifier endpoint information
+const EP_INFO_SUB_CODE_DEVICE_CLASS_IDENTIFIER: u8 = 0x01;
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+struct GetEndpointInfoReq {
+    sub_code: u8,
+    slot_id: EndpointInfoSlotId,
+    request_attributes: EndpointInfoReqAttr,
+    _reserved: [u8; 3],
+}
+impl CommonCodec for GetEndpointInfoReq {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct EndpointInfoNonce([u8; SPDM_NONCE_LEN]);
+impl CommonCodec for EndpointInfoNonce {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+struct EndpointInfoRspBase {
+    _param1: u8,
+    slot_id: EndpointInfoSlotId,
+}
+impl CommonCodec for EndpointInfoRspBase {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct EndpointInfoLen(u32);
+impl CommonCodec for EndpointInfoLen {}
+
+bitfield! {
+    #[derive(FromBytes, IntoBytes, Immutable, Clone, Copy)]
+    #[repr(C)]
+    struct EndpointInfoSlotId(u8);
+    impl Debug;
+    u8;
+    pub slot_id, set_slot_id: 3, 0;
+    reserved, _: 7, 4;
+}
+
+bitfield! {
+    #[derive(FromBytes, IntoBytes, Immutable)]
+    #[repr(C)]
+    struct EndpointInfoReqAttr(u8);
+    impl Debug;
+    u8;
+    pub signature_requested, _: 0, 0;
+    reserved, _: 7, 1;
+}
+
+async fn process_get_endpoint_info<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<Option<u8>> {
+    // Validate the version
+    let connection_version = ctx.validate_spdm_version(&spdm_hdr, req_payload)?;
+
+    // GET_ENDPOINT_INFO is not supported before v1.3
+    if connection_version < SpdmVersion::V13 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    let req = GetEndpointInfoReq::decode(req_payload).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    if req.sub_code != EP_INFO_SUB_CODE_DEVICE_CLASS_IDENTIFIER {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+
+    let signature_requested = req.request_attributes.signature_requested() == 1;
+    let slot_id = req.slot_id.slot_id();
+
+    if signature_requested {
+        // Signing requires EP_INFO_CAP with signature
+        if ctx.local_capabilities.flags.ep_info_cap() != EpInfoCapability::EpInfoWithSignature as u8
+        {
+            Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+        }
+
+        // Make sure the selected hash algorithm is SHA384
+        ctx.validate_negotiated_hash_algo(req_payload)?;
+
+        // Note: Pubkey of the responder will not be pre-provisioned to Requester. So slot ID 0xF is invalid.
+        let asym_algo = ctx.validate_negotiated_base_asym_algo(req_payload)?;
+        if slot_id >= MAX_CERT_SLOTS_SUPPORTED
+            || !ctx
+                .device_certs_store
+                .is_provisioned(slot_id, asym_algo)
+                .await
+        {
+            Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+        }
+
+        // If multi-key connection response is supported, validate the key supports endpoint info usage
+        if ctx.state.connection_info.multi_key_conn_rsp() {
+            match ctx.device_certs_store.key_usage_mask(slot_id).await {
+                Some(key_usage_mask) if key_usage_mask.endpoint_info_usage() != 0 => {}
+                _ => Err(ctx.generate_error_response(
+                    req_payload,
+                    ErrorCode::InvalidRequest,
+                    0,
+                    None,
+                ))?,
+            }
+        }
+
+        // The nonce is only present if a signature is requested
+        EndpointInfoNonce::decode(req_payload).map_err(|_| {
+            ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+        })?;
+    }
+
+    ctx.reset_transcript_via_req_code(ReqRespCode::GetEndpointInfo);
+
+    if !signature_requested {
+        return Ok(None);
+    }
+
+    // Append the GET_ENDPOINT_INFO request to the IL1 transcript
+    ctx.shared_transcript.reset_context(TranscriptContext::Il1);
+    ctx.append_message_to_transcript(req_payload, TranscriptContext::Il1, None)
+        .await?;
+
+    Ok(Some(slot_id))
+}
+
+async fn encode_il1_signature<'a>(
+    ctx: &mut SpdmContext<'a>,
+    slot_id: u8,
+    asym_algo: AsymAlgo,
+    rsp: &mut MessageBuf<'a>,
+) -> CommandResult<usize> {
+    let spdm_version = ctx.state.connection_info.version_number();
+
+    // Get the IL1 transcript hash
+    let mut il1_transcript_hash = [0u8; SHA384_HASH_SIZE];
+    ctx.shared_transcript
+        .hash(TranscriptContext::Il1, None, &mut il1_transcript_hash, true)
+        .await
+        .map_err(|e| (false, CommandError::Transcript(e)))?;
+
+    let message = SigningMessage::new(spdm_version, ReqRespCode::EndpointInfo, il1_transcript_hash)
+        .map_err(|e| (false, CommandError::SignCtx(e)))?;
+
+    // Sign directly into the signature field of the response
+    let sig_len = asym_algo.signature_size();
+    rsp.put_data(sig_len)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+    let sig_buf = rsp
+        .data_mut(sig_len)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+    spdm_sign(
+        ctx.device_certs_store,
+        slot_id,
+        asym_algo,
+        &message,
+        sig_buf,
+    )
+    .await
+    .map_err(|e| (false, CommandError::CertStore(e)))?;
+    rsp.pull_data(sig_len)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    Ok(sig_len)
+}
+
+async fn generate_endpoint_info_response<'a>(
+    ctx: &mut SpdmContext<'a>,
+    slot_id: Option<u8>,
+    rsp: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    let ep_info = ctx.endpoint_info.unwrap_or(&[]);
+
+    let asym_algo = match slot_id {
+        Some(_) => Some(ctx.validate_negotiated_base_asym_algo(rsp)?),
+        None => None,
+    };
+
+    // Spdm Header first
+    let connection_version = ctx.state.connection_info.version_number();
+    let spdm_hdr = SpdmMsgHdr::new(connection_version, ReqRespCode::EndpointInfo);
+    let mut payload_len = spdm_hdr
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    let mut rsp_slot_id = EndpointInfoSlotId(0);
+    if let Some(slot_id) = slot_id {
+        rsp_slot_id.set_slot_id(slot_id);
+    }
+    let rsp_base = EndpointInfoRspBase {
+        _param1: 0,
+        slot_id: rsp_slot_id,
+    };
+    payload_len += rsp_base
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    // The nonce is only present if a signature is requested
+    if slot_id.is_some() {
+        let mut nonce = EndpointInfoNonce([0u8; SPDM_NONCE_LEN]);
+        Rng::generate_random_number(&mut nonce.0)
+            .await
+            .map_err(|e| (false, CommandError::CaliptraApi(e)))?;
+        payload_len += nonce
+            .encode(rsp)
+            .map_err(|e| (false, CommandError::Codec(e)))?;
+    }
+
+    payload_len += EndpointInfoLen(ep_info.len() as u32)
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+    payload_len += encode_u8_slice(ep_info, rsp).map_err(|e| (false, CommandError::Codec(e)))?;
+
+    if let (Some(slot_id), Some(asym_algo)) = (slot_id, asym_algo) {
+        // Append ENDPOINT_INFO to the IL1 transcript
+        ctx.append_message_to_transcript(rsp, TranscriptContext::Il1, None)
+            .await?;
+
+        // Generate the signature and encode it in the response
+        payload_len += encode_il1_signature(ctx, slot_id, asym_algo, rsp).await?;
+    }
+
+    rsp.push_data(payload_len)
+        .map_err(|e| (false, CommandError::Codec(e)))
+}
+
+pub(crate) async fn handle_get_endpoint_info<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    // Check if the connection state is valid
+    if ctx.state.connection_info.state() < ConnectionState::AlgorithmsNegotiated {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    // Check if EP_INFO_CAP is supported and the endpoint information is available
+    if ctx.local_capabilities.flags.ep_info_cap() == EpInfoCapability::NoEpInfo as u8
+        || ctx.endpoint_info.is_none()
+    {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    // Process GET_ENDPOINT_INFO request
+    let slot_id = process_get_endpoint_info(ctx, spdm_hdr, req_payload).await?;
+
+    // Generate ENDPOINT_INFO response
+    ctx.prepare_response_buffer(req_payload)?;
+    generate_endpoint_info_response(ctx, slot_id, req_payload).await
+}
@@ -7,10 +7,11 @@ use crate::commands::error_rsp::ErrorCode;
 use crate::context::SpdmContext;
 use crate::error::{CommandError, CommandResult};
 use crate::protocol::*;
-use crate::session::{SessionKeyType, SessionState};
+use crate::session::{RequesterIdentity, SessionKeyType, SessionState};
 use crate::state::ConnectionState;
 use crate::transcript::TranscriptContext;
 use bitfield::bitfield;
+use caliptra_mcu_libapi_caliptra::crypto::asym::ECC_P384_SIGNATURE_SIZE;
 use caliptra_mcu_libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
 use constant_time_eq::constant_time_eq;
 use zerocopy::{FromBytes, Immutable, IntoBytes};
@@ -33,11 +34,21 @@ bitfield! {
 #[derive(FromBytes, IntoBytes, Immutable)]
 #[repr(C)]
 struct FinishReqBase {
-    req_signature_present: u8,
+    req_signature_present: FinishReqAttributes,
     req_slot_id: u8,
 }
 impl CommonCodec for FinishReqBase {}
 
+bitfield! {
+    #[derive(FromBytes, IntoBytes, Immutable)]
+    #[repr(C)]
+    struct FinishReqAttributes(u8);
+    impl Debug;
+    u8;
+    pub signature_included, _: 0, 0;
+    reserved, _: 7, 1;
+}
+
 #[derive(FromBytes, IntoBytes, Immutable)]
 #[repr(C)]
 struct FinishRspBase {
@@ -83,6 +94,37 @@ async fn verify_requester_verify_data(
     Ok(())
 }
 
+async fn verify_requester_signature(
+    ctx: &mut SpdmContext<'_>,
+    session_id: u32,
+    requester_identity: &RequesterIdentity,
+    signature: &[u8; ECC_P384_SIGNATURE_SIZE],
+    req_payload: &mut MessageBuf<'_>,
+) -> CommandResult<()> {
+    let spdm_version = ctx.state.connection_info.version_number();
+    let th_transcript_hash = ctx
+        .transcript_hash(TranscriptContext::Th, Some(session_id), false)
+        .await?;
+
+    // The Requester identity key is ECC P-384, which signs the digest of the message
+    let tbs = SigningMessage::new(spdm_version, ReqRespCode::Finish, th_transcript_hash)
+        .map_err(|e| (false, CommandError::SignCtx(e)))?
+        .digest()
+        .await
+        .map_err(|e| (false, CommandError::CaliptraApi(e)))?;
+
+    if requester_identity
+        .public_key
+        .verify(tbs, signature)
+        .await
+        .is_err()
+    {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::DecryptError, 0, None))?
+    }
+
+    Ok(())
+}
+
 async fn process_finish<'a>(
     ctx: &mut SpdmContext<'a>,
     session_id: u32,
@@ -92,16 +134,70 @@ async fn process_finish<'a>(
     // Validate the version
     let _ = ctx.validate_spdm_version(&spdm_hdr, req_payload)?;
 
-    // Decode the FINISH request payload (currently no Session-based mutual authentication is supported)
-    let _finish_req_base =
+    // Decode the FINISH request payload
+    let finish_req_base =
         FinishReqBase::decode(req_payload).map_err(|e| (false, CommandError::Codec(e)))?;
 
+    let (mut_auth_requested, requester_identity) = ctx
+        .session_mgr
+        .session_info(session_id)
+        .map(|session_info| {
+            (
+                session_info.mut_auth_requested,
+                session_info.requester_identity,
+            )
+        })
+        .map_err(|e| (false, CommandError::Session(e)))?;
+
+    // With session-based mutual authentication the Requester signs FINISH with the
+    // certificate chain retrieved by the encapsulated request flow
+    let signature_included = finish_req_base.req_signature_present.signature_included() != 0;
+    if mut_auth_requested {
+        let Some(requester_identity) = requester_identity else {
+            return Err(ctx.generate_error_response(
+                req_payload,
+                ErrorCode::UnexpectedRequest,
+                0,
+                None,
+            ));
+        };
+        if !signature_included || finish_req_base.req_slot_id != requester_identity.slot_id {
+            Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+        }
+    } else if signature_included {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+
     ctx.reset_transcript_via_req_code(ReqRespCode::Finish);
 
-    // Append FINISH req (excluding RequesterVerifyData) to TH transcript.
+    // Append the hash of the Requester cert chain to TH transcript
+    if let Some(requester_identity) = &requester_identity {
+        ctx.append_slice_to_transcript(
+            &requester_identity.cert_chain_hash,
+            TranscriptContext::Th,
+            Some(session_id),
+        )
+        .await?;
+    }
+
+    // Append FINISH req (excluding Signature and RequesterVerifyData) to TH transcript.
     ctx.append_message_to_transcript(req_payload, TranscriptContext::Th, Some(session_id))
         .await?;
 
+    // Verify the Requester signature and add it to the transcript
+    if let Some(requester_identity) = &requester_identity {
+        let mut signature = [0u8; ECC_P384_SIGNATURE_SIZE];
+        decode_u8_slice(req_payload, &mut signature).map_err(|_| {
+            ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+        })?;
+
+        verify_requester_signature(ctx, session_id, requester_identity, &signature, req_payload)
+            .await?;
+
+        ctx.append_slice_to_transcript(&signature, TranscriptContext::Th, Some(session_id))
+            .await?;
+    }
+
     // Verify HMAC of the RequesterVerifyData
     let mut requester_verify_data = [0u8; SHA384_HASH_SIZE];
     decode_u8_slice(req_payload, &mut requester_verify_data)
@@ -226,17 +322,31 @@ pub(crate) async fn handle_finish<'a>(
 
     // Validate session state based on handshake in the clear mode
     let session_id = if ctx.state.connection_info.handshake_in_the_clear() {
-        // For handshake in the clear: must have handshake phase session, no active session
+        // For handshake in the clear: the session of the single pending handshake,
+        // no active session
         if ctx.session_mgr.active_session_id().is_some() {
             Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
         }
-        ctx.session_mgr.handshake_phase_session_id()
+        ctx.session_mgr.pending_key_exchange_session_id()
     } else {
-        // If handshake is not in the clear, we must use the active session ID
+        // If handshake is not in the clear, the session ID is carried in the secured message
         ctx.session_mgr.active_session_id()
     }
     .ok_or_else(|| ctx.generate_error_response(req_payload, ErrorCode::SessionRequired, 0, None))?;
 
+    if ctx.state.connection_info.handshake_in_the_clear() {
+        ctx.session_mgr.set_clear_request_session_id(session_id);
+    }
+
+    // Sessions created by PSK_EXCHANGE are completed with PSK_FINISH
+    if ctx
+        .session_mgr
+        .session_info(session_id)
+        .is_ok_and(|session_info| session_info.psk_session)
+    {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
     // Verify the negotiated Hash algorithm is SHA384
     ctx.validate_negotiated_hash_algo(req_payload)?;
 
@@ -252,8 +362,5 @@ pub(crate) async fn handle_finish<'a>(
         .set_session_state(session_id, SessionState::Establishing)
         .map_err(|e| (false, CommandError::Session(e)))?;
 
-    // Reset handshake phase session ID
-    ctx.session_mgr.reset_handshake_phase_session_id();
-
     Ok(())
 }
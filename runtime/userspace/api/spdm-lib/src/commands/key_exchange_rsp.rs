@@ -2,7 +2,7 @@
 
 #![allow(dead_code)]
 
-use crate::cert_store::{spdm_cert_chain_hash, MAX_CERT_SLOTS_SUPPORTED};
+use crate::cert_store::{spdm_cert_chain_hash, spdm_sign, MAX_CERT_SLOTS_SUPPORTED};
 use crate::codec::{encode_u8_slice, Codec, CommonCodec, MessageBuf};
 use crate::commands::algorithms_rsp::selected_measurement_specification;
 use crate::commands::challenge_auth_rsp::encode_measurement_summary_hash;
@@ -19,7 +19,7 @@ use crate::state::ConnectionState;
 use crate::transcript::TranscriptContext;
 use bitfield::bitfield;
 use caliptra_mcu_libapi_caliptra::crypto::asym::ecdh::CMB_ECDH_EXCHANGE_DATA_MAX_SIZE;
-use caliptra_mcu_libapi_caliptra::crypto::asym::{AsymAlgo, ECC_P384_SIGNATURE_SIZE};
+use caliptra_mcu_libapi_caliptra::crypto::asym::AsymAlgo;
 use caliptra_mcu_libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
 use caliptra_mcu_libapi_caliptra::crypto::rng::Rng;
 use zerocopy::{FromBytes, Immutable, IntoBytes};
@@ -87,14 +87,31 @@ struct KeyExchRspContext {
     selected_sm_version: SmVersion,
     resp_session_id: u16,
     session_id: u32,
+    heartbeat_period: u8,
+    mut_auth_requested: bool,
+}
+
+/// Session-based mutual authentication is requested with the encapsulated request flow
+/// if both sides support it and the Requester certificate chain can be verified.
+fn request_mut_auth(ctx: &SpdmContext) -> bool {
+    let local_flags = ctx.local_capabilities.flags;
+    let peer_flags = ctx.state.connection_info.peer_capabilities().flags;
+
+    local_flags.mut_auth_cap() != 0
+        && local_flags.encap_cap() != 0
+        && peer_flags.mut_auth_cap() != 0
+        && peer_flags.encap_cap() != 0
+        && ctx.requester_trust_anchor_store.is_some()
+        && ctx.negotiated_req_base_asym_algo_p384()
 }
 
-fn init_session(
+pub(crate) fn init_session(
     session_info: &mut SessionInfo,
     local_capabilities_flags: CapabilityFlags,
     connection_info: &ConnectionInfo,
     session_policy: SessionPolicy,
     asym_algo: AsymAlgo,
+    heartbeat_period: u8,
 ) {
     // let local_capabilities_flags = ctx.local_capabilities.flags;
     let peer_capabilities = connection_info.peer_capabilities().flags;
@@ -114,6 +131,7 @@ fn init_session(
         session_type,
         connection_info.version_number(),
         asym_algo,
+        heartbeat_period,
     );
 }
 
@@ -143,11 +161,12 @@ async fn process_key_exchange<'a>(
     }
 
     // Note: Pubkey of the responder will not be pre-provisioned to Requester. So slot ID 0xFF is invalid.
+    let asym_algo = ctx.validate_negotiated_base_asym_algo(req_payload)?;
     if exch_req.slot_id >= MAX_CERT_SLOTS_SUPPORTED
         || ctx.local_capabilities.flags.cert_cap() == 0
         || !ctx
             .device_certs_store
-            .is_provisioned(exch_req.slot_id)
+            .is_provisioned(exch_req.slot_id, asym_algo)
             .await
     {
         Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
@@ -181,10 +200,35 @@ async fn process_key_exchange<'a>(
             |_| ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None),
         )?;
 
+    // FINISH sent in the clear carries no session ID, so it can only be matched with
+    // its KEY_EXCHANGE if a single handshake in the clear is in progress at a time
+    if ctx.state.connection_info.handshake_in_the_clear()
+        && ctx.session_mgr.pending_key_exchange_session_id().is_some()
+    {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::Busy, 0, None))?;
+    }
+
     ctx.state
         .connection_info
         .set_sec_msg_version(selected_sm_version);
 
+    // Heartbeat is only enabled if both the Responder and the Requester support it
+    let heartbeat_period = if ctx.local_capabilities.flags.hbeat_cap() != 0
+        && ctx
+            .state
+            .connection_info
+            .peer_capabilities()
+            .flags
+            .hbeat_cap()
+            != 0
+    {
+        ctx.heartbeat_period
+    } else {
+        0
+    };
+
+    let mut_auth_requested = request_mut_auth(ctx);
+
     // Create session
     let (session_id, resp_session_id) =
         ctx.session_mgr.generate_session_id(exch_req.req_session_id);
@@ -194,8 +238,40 @@ async fn process_key_exchange<'a>(
         ctx.generate_error_response(req_payload, ErrorCode::SessionLimitExceeded, 0, None)
     })?;
 
-    ctx.session_mgr.set_handshake_phase_session_id(session_id);
+    ctx.session_mgr.set_clear_request_session_id(session_id);
+
+    let key_exch_rsp_ctx = KeyExchRspContext {
+        meas_summary_hash_type: exch_req.meas_summary_hash_type,
+        slot_id: exch_req.slot_id,
+        resp_exch_data: [0; CMB_ECDH_EXCHANGE_DATA_MAX_SIZE],
+        selected_sm_version,
+        resp_session_id,
+        session_id,
+        heartbeat_period,
+        mut_auth_requested,
+    };
+
+    // Only the session allocated by this request is torn down if it fails
+    match init_key_exchange_session(ctx, asym_algo, &exch_req, key_exch_rsp_ctx, req_payload).await
+    {
+        Ok(key_exch_rsp_ctx) => Ok(key_exch_rsp_ctx),
+        Err(e) => {
+            let _ = ctx.session_mgr.delete_session(session_id);
+            Err(e)
+        }
+    }
+}
 
+/// Initializes the session created for KEY_EXCHANGE, computes the DHE secret and
+/// starts the session transcript.
+async fn init_key_exchange_session<'a>(
+    ctx: &mut SpdmContext<'a>,
+    asym_algo: AsymAlgo,
+    exch_req: &KeyExchangeEcdhReqBase,
+    mut key_exch_rsp_ctx: KeyExchRspContext,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<KeyExchRspContext> {
+    let session_id = key_exch_rsp_ctx.session_id;
     let session_info = ctx
         .session_mgr
         .session_info_mut(session_id)
@@ -207,9 +283,11 @@ async fn process_key_exchange<'a>(
         &ctx.state.connection_info,
         exch_req.session_policy,
         asym_algo,
+        key_exch_rsp_ctx.heartbeat_period,
     );
+    session_info.mut_auth_requested = key_exch_rsp_ctx.mut_auth_requested;
 
-    let resp_exch_data = session_info
+    key_exch_rsp_ctx.resp_exch_data = session_info
         .compute_dhe_secret(&exch_req.exchange_data)
         .await
         .map_err(|e| (false, CommandError::Session(e)))?;
@@ -236,23 +314,23 @@ async fn process_key_exchange<'a>(
     ctx.append_message_to_transcript(req_payload, TranscriptContext::Th, Some(session_id))
         .await?;
 
-    Ok(KeyExchRspContext {
-        meas_summary_hash_type: exch_req.meas_summary_hash_type,
-        slot_id: exch_req.slot_id,
-        resp_exch_data,
-        selected_sm_version,
-        resp_session_id,
-        session_id,
-    })
+    Ok(key_exch_rsp_ctx)
 }
 
 async fn encode_key_exchange_rsp_base(
+    heartbeat_period: u8,
     resp_session_id: u16,
+    mut_auth_requested: bool,
     resp_exchange_data: [u8; CMB_ECDH_EXCHANGE_DATA_MAX_SIZE],
     rsp: &mut MessageBuf<'_>,
 ) -> CommandResult<usize> {
     let mut key_exch_rsp = KeyExchangeRspBase::new();
+    key_exch_rsp.heartbeat_period = heartbeat_period;
     key_exch_rsp.rsp_session_id = resp_session_id;
+    if mut_auth_requested {
+        // The Requester certificate chain is retrieved with encapsulated requests
+        key_exch_rsp.mut_auth_requested.set_encaps_request_flow(1);
+    }
     key_exch_rsp
         .exchange_data
         .copy_from_slice(&resp_exchange_data);
@@ -273,7 +351,8 @@ async fn th1_signature(
     session_id: u32,
     slot_id: u8,
     asym_algo: AsymAlgo,
-) -> CommandResult<[u8; ECC_P384_SIGNATURE_SIZE]> {
+    signature: &mut [u8],
+) -> CommandResult<()> {
     let spdm_version = ctx.state.connection_info.version_number();
     let th1_transcript_hash = ctx
         .transcript_hash(
@@ -283,20 +362,22 @@ async fn th1_signature(
         )
         .await?;
 
-    let tbs = get_tbs_via_response_code(
+    let message = SigningMessage::new(
         spdm_version,
         ReqRespCode::KeyExchangeRsp,
         th1_transcript_hash,
     )
-    .await
     .map_err(|e| (false, CommandError::SignCtx(e)))?;
 
-    let mut signature = [0u8; ECC_P384_SIGNATURE_SIZE];
-    ctx.device_certs_store
-        .sign_hash(slot_id, asym_algo, &tbs, &mut signature)
-        .await
-        .map_err(|e| (false, CommandError::CertStore(e)))?;
-    Ok(signature)
+    spdm_sign(
+        ctx.device_certs_store,
+        slot_id,
+        asym_algo,
+        &message,
+        signature,
+    )
+    .await
+    .map_err(|e| (false, CommandError::CertStore(e)))
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -316,7 +397,9 @@ async fn generate_key_exchange_response<'a>(
 
     // Encode the KEY_EXCHANGE response fixed fields
     payload_len += encode_key_exchange_rsp_base(
+        key_exch_rsp_ctx.heartbeat_period,
         key_exch_rsp_ctx.resp_session_id,
+        key_exch_rsp_ctx.mut_auth_requested,
         key_exch_rsp_ctx.resp_exch_data,
         rsp,
     )
@@ -345,25 +428,34 @@ async fn generate_key_exchange_response<'a>(
     )
     .await?;
 
-    // Encode TH1 signature.
-    let th1_sig = th1_signature(
+    // Encode TH1 signature. Sign directly into the signature field of the response.
+    let sig_len = asym_algo.signature_size();
+    rsp.put_data(sig_len)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+    let th1_sig = rsp
+        .data_mut(sig_len)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+    th1_signature(
         ctx,
         key_exch_rsp_ctx.session_id,
         key_exch_rsp_ctx.slot_id,
         asym_algo,
+        th1_sig,
     )
     .await?;
 
-    payload_len += encode_u8_slice(&th1_sig, rsp).map_err(|e| (false, CommandError::Codec(e)))?;
-
     // Update the session transcript with the KEY_EXCHANGE_RSP signature
     ctx.append_slice_to_transcript(
-        &th1_sig,
+        th1_sig,
         TranscriptContext::Th,
         Some(key_exch_rsp_ctx.session_id),
     )
     .await?;
 
+    rsp.pull_data(sig_len)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+    payload_len += sig_len;
+
     // Compute TH1 transcript hash for generating the session handshake key
     let th1_transcript_hash = ctx
         .transcript_hash(
@@ -446,40 +538,31 @@ pub(crate) async fn handle_key_exchange<'a>(
     let asym_algo = ctx.validate_negotiated_base_asym_algo(req_payload)?;
 
     // Process KEY_EXCHANGE request
-    let key_exch_rsp_ctx = match process_key_exchange(ctx, asym_algo, spdm_hdr, req_payload).await {
-        Ok(result) => result,
-        Err(e) => {
-            if ctx.session_mgr.handshake_phase_session_id().is_some() {
-                let session_id = ctx.session_mgr.handshake_phase_session_id().unwrap();
-                let _ = ctx.session_mgr.delete_session(session_id);
-            }
-            return Err(e);
-        }
-    };
+    let key_exch_rsp_ctx = process_key_exchange(ctx, asym_algo, spdm_hdr, req_payload).await?;
 
     // Generate KEY_EXCHANGE response
     ctx.prepare_response_buffer(req_payload)?;
 
     let session_id = key_exch_rsp_ctx.session_id;
+    let mut_auth_requested = key_exch_rsp_ctx.mut_auth_requested;
 
     // Generate response with automatic cleanup on error
     if let Err(e) =
         generate_key_exchange_response(ctx, asym_algo, key_exch_rsp_ctx, req_payload).await
     {
-        // Clean up session on error
-        if ctx.session_mgr.handshake_phase_session_id().is_some() {
-            let _ = ctx.session_mgr.delete_session(session_id); // Ignore cleanup errors
-        }
+        // Clean up the session allocated by this request on error
+        let _ = ctx.session_mgr.delete_session(session_id); // Ignore cleanup errors
         return Err(e);
     }
 
-    if !ctx.state.connection_info.handshake_in_the_clear() {
-        ctx.session_mgr.set_handshake_phase_session_id(session_id);
-    }
-
     ctx.session_mgr
         .set_session_state(session_id, SessionState::HandshakeInProgress)
         .map_err(|e| (false, CommandError::Session(e)))?;
 
+    // Wait for GET_ENCAPSULATED_REQUEST to authenticate the Requester
+    if mut_auth_requested {
+        ctx.encap_ctx.start(session_id);
+    }
+
     Ok(())
 }
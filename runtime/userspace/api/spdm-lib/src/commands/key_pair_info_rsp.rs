@@ -0,0 +1,166 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{encode_u8_slice, Codec, CommonCodec, MessageBuf};
+use crate::commands::error_rsp::ErrorCode;
+use crate::context::SpdmContext;
+use crate::error::{CommandError, CommandResult};
+use crate::protocol::*;
+use crate::state::ConnectionState;
+use core::mem::size_of;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+// Maximum size of the DER-encoded SubjectPublicKeyInfo reported in KEY_PAIR_INFO
+const MAX_PUBLIC_KEY_INFO_LEN: usize = 256;
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+struct GetKeyPairInfoReq {
+    _param1: u8,
+    _param2: u8,
+    key_pair_id: u8,
+}
+impl CommonCodec for GetKeyPairInfoReq {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+struct KeyPairInfoRspBase {
+    _param1: u8,
+    _param2: u8,
+    total_key_pairs: u8,
+    key_pair_id: u8,
+    capabilities: KeyPairCapabilities,
+    key_usage_capabilities: KeyUsageMask,
+    current_key_usage: KeyUsageMask,
+    asym_algo_capabilities: KeyPairAsymAlgo,
+    current_asym_algo: KeyPairAsymAlgo,
+    public_key_info_len: u16,
+    assoc_cert_slot_mask: u8,
+}
+impl CommonCodec for KeyPairInfoRspBase {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+struct KeyPairInfoRspPqc {
+    pqc_asym_algo_capabilities: u32,
+    current_pqc_asym_algo: u32,
+}
+impl CommonCodec for KeyPairInfoRspPqc {}
+
+async fn process_get_key_pair_info<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<(u8, u8)> {
+    // Validate the version
+    let connection_version = ctx.validate_spdm_version(&spdm_hdr, req_payload)?;
+
+    // GET_KEY_PAIR_INFO is not supported before v1.3
+    if connection_version < SpdmVersion::V13 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    let req = GetKeyPairInfoReq::decode(req_payload).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    // KeyPairIDs are consecutive from 1 to TotalKeyPairs
+    let total_key_pairs = ctx.device_certs_store.total_key_pairs().await;
+    if req.key_pair_id == 0 || req.key_pair_id > total_key_pairs {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+
+    ctx.reset_transcript_via_req_code(ReqRespCode::GetKeyPairInfo);
+
+    Ok((total_key_pairs, req.key_pair_id))
+}
+
+async fn generate_key_pair_info_response<'a>(
+    ctx: &mut SpdmContext<'a>,
+    total_key_pairs: u8,
+    key_pair_id: u8,
+    rsp: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    let mut public_key_info = [0u8; MAX_PUBLIC_KEY_INFO_LEN];
+    let (key_pair_info, public_key_info_len) = match ctx
+        .device_certs_store
+        .key_pair_info(key_pair_id, &mut public_key_info)
+        .await
+    {
+        Ok((info, len)) if len <= MAX_PUBLIC_KEY_INFO_LEN => (info, len),
+        _ => Err(ctx.generate_error_response(rsp, ErrorCode::Unspecified, 0, None))?,
+    };
+
+    // The PQC algorithm fields are present from v1.4
+    let connection_version = ctx.state.connection_info.version_number();
+    let mut rsp_len =
+        size_of::<SpdmMsgHdr>() + size_of::<KeyPairInfoRspBase>() + public_key_info_len;
+    if connection_version >= SpdmVersion::V14 {
+        rsp_len += size_of::<KeyPairInfoRspPqc>();
+    }
+    if rsp_len > ctx.min_data_transfer_size() {
+        Err(ctx.generate_error_response(rsp, ErrorCode::ResponseTooLarge, 0, None))?;
+    }
+
+    // Spdm Header first
+    let spdm_hdr = SpdmMsgHdr::new(connection_version, ReqRespCode::KeyPairInfo);
+    let mut payload_len = spdm_hdr
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    let rsp_base = KeyPairInfoRspBase {
+        _param1: 0,
+        _param2: 0,
+        total_key_pairs,
+        key_pair_id,
+        capabilities: key_pair_info.capabilities,
+        key_usage_capabilities: key_pair_info.key_usage_capabilities,
+        current_key_usage: key_pair_info.current_key_usage,
+        asym_algo_capabilities: key_pair_info.asym_algo_capabilities,
+        current_asym_algo: key_pair_info.current_asym_algo,
+        public_key_info_len: public_key_info_len as u16,
+        assoc_cert_slot_mask: key_pair_info.assoc_cert_slot_mask,
+    };
+    payload_len += rsp_base
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    payload_len += encode_u8_slice(&public_key_info[..public_key_info_len], rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    if connection_version >= SpdmVersion::V14 {
+        let rsp_pqc = KeyPairInfoRspPqc {
+            pqc_asym_algo_capabilities: key_pair_info.pqc_asym_algo_capabilities,
+            current_pqc_asym_algo: key_pair_info.current_pqc_asym_algo,
+        };
+        payload_len += rsp_pqc
+            .encode(rsp)
+            .map_err(|e| (false, CommandError::Codec(e)))?;
+    }
+
+    rsp.push_data(payload_len)
+        .map_err(|e| (false, CommandError::Codec(e)))
+}
+
+pub(crate) async fn handle_get_key_pair_info<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    // Check if the connection state is valid
+    if ctx.state.connection_info.state() < ConnectionState::AlgorithmsNegotiated {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    // Check if GET_KEY_PAIR_INFO_CAP is supported
+    if ctx.local_capabilities.flags.get_key_pair_info_cap() == 0 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    // Process GET_KEY_PAIR_INFO request
+    let (total_key_pairs, key_pair_id) =
+        process_get_key_pair_info(ctx, spdm_hdr, req_payload).await?;
+
+    // Generate KEY_PAIR_INFO response
+    ctx.prepare_response_buffer(req_payload)?;
+    generate_key_pair_info_response(ctx, total_key_pairs, key_pair_id, req_payload).await
+}
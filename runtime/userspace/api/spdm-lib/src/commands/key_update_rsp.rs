@@ -0,0 +1,283 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{Codec, CommonCodec, MessageBuf};
+use crate::commands::error_rsp::ErrorCode;
+use crate::context::SpdmContext;
+use crate::error::{CommandError, CommandResult};
+use crate::protocol::*;
+use crate::session::KeyUpdateState;
+use crate::state::ConnectionState;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum KeyUpdateOperation {
+    UpdateKey = 1,
+    UpdateAllKeys = 2,
+    VerifyNewKey = 3,
+}
+
+impl TryFrom<u8> for KeyUpdateOperation {
+    type Error = ();
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(KeyUpdateOperation::UpdateKey),
+            2 => Ok(KeyUpdateOperation::UpdateAllKeys),
+            3 => Ok(KeyUpdateOperation::VerifyNewKey),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct KeyUpdateReq {
+    key_operation: u8,
+    tag: u8,
+}
+
+impl CommonCodec for KeyUpdateReq {}
+
+#[derive(Debug, FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct KeyUpdateAck {
+    key_operation: u8,
+    tag: u8,
+}
+
+impl CommonCodec for KeyUpdateAck {}
+
+/// Key changes requested by a KEY_UPDATE request.
+#[derive(Debug, PartialEq)]
+enum KeyUpdateAction {
+    /// Update the request direction key right away and, if `update_response_key`
+    /// is set, the response direction key once KEY_UPDATE_ACK is sent.
+    Update { update_response_key: bool },
+    /// Retry of a KEY_UPDATE whose KEY_UPDATE_ACK was lost. The keys are already updated.
+    Retry,
+    /// Discard the keys replaced by the last KEY_UPDATE.
+    Verify,
+}
+
+fn key_update_action(
+    key_operation: KeyUpdateOperation,
+    key_update_req: &KeyUpdateReq,
+    key_update: &KeyUpdateState,
+) -> Option<KeyUpdateAction> {
+    // Only the last KEY_UPDATE may be retried with the previous request direction key
+    if key_update.previous_key_in_use {
+        let retry = Some((key_update_req.key_operation, key_update_req.tag));
+        return (key_update.last_update == retry).then_some(KeyUpdateAction::Retry);
+    }
+
+    match key_operation {
+        KeyUpdateOperation::UpdateKey => Some(KeyUpdateAction::Update {
+            update_response_key: false,
+        }),
+        KeyUpdateOperation::UpdateAllKeys => Some(KeyUpdateAction::Update {
+            update_response_key: true,
+        }),
+        // The request has already been decrypted with the new request direction key
+        KeyUpdateOperation::VerifyNewKey => Some(KeyUpdateAction::Verify),
+    }
+}
+
+async fn process_key_update<'a>(
+    ctx: &mut SpdmContext<'a>,
+    session_id: u32,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<(KeyUpdateReq, bool)> {
+    // Validate the version
+    let _ = ctx.validate_spdm_version(&spdm_hdr, req_payload)?;
+
+    let key_update_req = KeyUpdateReq::decode(req_payload).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    let key_operation =
+        KeyUpdateOperation::try_from(key_update_req.key_operation).map_err(|_| {
+            ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+        })?;
+
+    let key_update = ctx
+        .session_mgr
+        .session_info(session_id)
+        .map_err(|e| (false, CommandError::Session(e)))?
+        .key_update;
+
+    let action =
+        key_update_action(key_operation, &key_update_req, &key_update).ok_or_else(|| {
+            ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+        })?;
+
+    ctx.reset_transcript_via_req_code(ReqRespCode::KeyUpdate);
+
+    let session_info = ctx
+        .session_mgr
+        .session_info_mut(session_id)
+        .map_err(|e| (false, CommandError::Session(e)))?;
+
+    let mut update_response_key = false;
+    match action {
+        KeyUpdateAction::Update {
+            update_response_key: update_all_keys,
+        } => {
+            // The request direction key takes effect with the next request
+            session_info
+                .update_request_data_key()
+                .await
+                .map_err(|e| (false, CommandError::Session(e)))?;
+            session_info.key_update.last_update =
+                Some((key_update_req.key_operation, key_update_req.tag));
+            update_response_key = update_all_keys;
+        }
+        KeyUpdateAction::Retry => {}
+        KeyUpdateAction::Verify => session_info.verify_new_data_keys(),
+    }
+
+    Ok((key_update_req, update_response_key))
+}
+
+fn generate_key_update_response(
+    ctx: &mut SpdmContext<'_>,
+    key_update_req: KeyUpdateReq,
+    rsp: &mut MessageBuf<'_>,
+) -> CommandResult<()> {
+    // Spdm Header first
+    let connection_version = ctx.state.connection_info.version_number();
+    let spdm_hdr = SpdmMsgHdr::new(connection_version, ReqRespCode::KeyUpdateAck);
+    let mut payload_len = spdm_hdr
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    // KEY_UPDATE_ACK echoes the operation and tag of the request
+    let key_update_ack = KeyUpdateAck {
+        key_operation: key_update_req.key_operation,
+        tag: key_update_req.tag,
+    };
+    payload_len += key_update_ack
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    rsp.push_data(payload_len)
+        .map_err(|_| (false, CommandError::BufferTooSmall))
+}
+
+pub(crate) async fn handle_key_update<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    // Check if the connection state is valid
+    if ctx.state.connection_info.state() < ConnectionState::AlgorithmsNegotiated {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    // KEY_UPDATE is not supported in v1.0
+    if ctx.state.connection_info.version_number() < SpdmVersion::V11 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    // Both the Responder and the Requester must support KEY_UPD_CAP
+    if ctx.local_capabilities.flags.key_upd_cap() == 0
+        || ctx
+            .state
+            .connection_info
+            .peer_capabilities()
+            .flags
+            .key_upd_cap()
+            == 0
+    {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    // KEY_UPDATE is only allowed within a session
+    let session_id = ctx.session_mgr.active_session_id().ok_or_else(|| {
+        ctx.generate_error_response(req_payload, ErrorCode::SessionRequired, 0, None)
+    })?;
+
+    // Process KEY_UPDATE request
+    let (key_update_req, update_response_key) =
+        process_key_update(ctx, session_id, spdm_hdr, req_payload).await?;
+
+    // Generate KEY_UPDATE_ACK response
+    ctx.prepare_response_buffer(req_payload)?;
+    generate_key_update_response(ctx, key_update_req, req_payload)?;
+
+    // The response direction key is updated after KEY_UPDATE_ACK is protected with the current key
+    if update_response_key {
+        ctx.session_mgr
+            .session_info_mut(session_id)
+            .map_err(|e| (false, CommandError::Session(e)))?
+            .stage_response_data_key_update();
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn key_update_req(key_operation: KeyUpdateOperation, tag: u8) -> KeyUpdateReq {
+        KeyUpdateReq {
+            key_operation: key_operation as u8,
+            tag,
+        }
+    }
+
+    #[test]
+    fn test_key_update_actions() {
+        let key_update = KeyUpdateState::default();
+
+        let req = key_update_req(KeyUpdateOperation::UpdateKey, 1);
+        assert_eq!(
+            key_update_action(KeyUpdateOperation::UpdateKey, &req, &key_update),
+            Some(KeyUpdateAction::Update {
+                update_response_key: false
+            })
+        );
+
+        let req = key_update_req(KeyUpdateOperation::UpdateAllKeys, 2);
+        assert_eq!(
+            key_update_action(KeyUpdateOperation::UpdateAllKeys, &req, &key_update),
+            Some(KeyUpdateAction::Update {
+                update_response_key: true
+            })
+        );
+
+        let req = key_update_req(KeyUpdateOperation::VerifyNewKey, 3);
+        assert_eq!(
+            key_update_action(KeyUpdateOperation::VerifyNewKey, &req, &key_update),
+            Some(KeyUpdateAction::Verify)
+        );
+    }
+
+    #[test]
+    fn test_key_update_retry() {
+        let key_update = KeyUpdateState {
+            last_update: Some((KeyUpdateOperation::UpdateAllKeys as u8, 2)),
+            response_key_update_pending: false,
+            previous_key_in_use: true,
+        };
+
+        // The KEY_UPDATE whose KEY_UPDATE_ACK was lost is acknowledged again
+        let req = key_update_req(KeyUpdateOperation::UpdateAllKeys, 2);
+        assert_eq!(
+            key_update_action(KeyUpdateOperation::UpdateAllKeys, &req, &key_update),
+            Some(KeyUpdateAction::Retry)
+        );
+
+        // Any other request protected with the previous key is rejected
+        let req = key_update_req(KeyUpdateOperation::UpdateAllKeys, 3);
+        assert_eq!(
+            key_update_action(KeyUpdateOperation::UpdateAllKeys, &req, &key_update),
+            None
+        );
+        let req = key_update_req(KeyUpdateOperation::VerifyNewKey, 2);
+        assert_eq!(
+            key_update_action(KeyUpdateOperation::VerifyNewKey, &req, &key_update),
+            None
+        );
+    }
+}
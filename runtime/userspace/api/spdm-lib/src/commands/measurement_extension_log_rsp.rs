@@ -0,0 +1,234 @@
+// Licensed under the Apache-2.0 license
+
+use crate::chunk_ctx::LargeResponse;
+use crate::codec::{encode_u8_slice, Codec, CommonCodec, MessageBuf};
+use crate::commands::algorithms_rsp::selected_mel_specification;
+use crate::commands::chunk_get_rsp::max_chunked_resp_size;
+use crate::commands::error_rsp::ErrorCode;
+use crate::context::SpdmContext;
+use crate::error::{CommandError, CommandResult};
+use crate::measurements::{MeasurementsError, SpdmMeasurements};
+use crate::protocol::*;
+use crate::state::ConnectionState;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+const MEL_RESP_HEADER_SIZE: usize = size_of::<MelRespHdr>();
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+struct GetMelReq {
+    _param1: u8,
+    _param2: u8,
+    offset: u32,
+    length: u32,
+}
+impl CommonCodec for GetMelReq {}
+
+#[derive(IntoBytes, FromBytes, Immutable)]
+#[repr(C, packed)]
+struct MelRespHdr {
+    spdm_version: SpdmMsgHdr,
+    param1: u8,
+    param2: u8,
+    portion_length: u32,
+    remainder_length: u32,
+}
+impl CommonCodec for MelRespHdr {}
+
+#[derive(Debug, Clone)]
+pub(crate) struct MelResponse {
+    spdm_version: SpdmVersion,
+    offset: u32,
+    portion_len: u32,
+    remainder_len: u32,
+}
+
+impl MelResponse {
+    fn resp_hdr(&self) -> CommandResult<[u8; MEL_RESP_HEADER_SIZE]> {
+        let mut buf = [0u8; MEL_RESP_HEADER_SIZE];
+        let mut msg_buf = MessageBuf::new(&mut buf);
+
+        let mel_rsp_hdr = MelRespHdr {
+            spdm_version: SpdmMsgHdr::new(self.spdm_version, ReqRespCode::MeasurementExtensionLog),
+            param1: 0,
+            param2: 0,
+            portion_length: self.portion_len,
+            remainder_length: self.remainder_len,
+        };
+        mel_rsp_hdr
+            .encode(&mut msg_buf)
+            .map_err(|e| (false, CommandError::Codec(e)))?;
+
+        Ok(buf)
+    }
+
+    fn rsp_len(&self) -> usize {
+        MEL_RESP_HEADER_SIZE + self.portion_len as usize
+    }
+
+    pub async fn get_chunk(
+        &self,
+        measurements: &mut SpdmMeasurements<'_>,
+        mel_rsp_offset: usize,
+        chunk: &mut [u8],
+    ) -> CommandResult<usize> {
+        let portion_offset: usize;
+        let mut chunk_data_len = 0;
+        if mel_rsp_offset < MEL_RESP_HEADER_SIZE {
+            // Read from the response header
+            let header_bytes = self.resp_hdr()?;
+            let copy_len = (MEL_RESP_HEADER_SIZE - mel_rsp_offset).min(chunk.len());
+            chunk[..copy_len]
+                .copy_from_slice(&header_bytes[mel_rsp_offset..mel_rsp_offset + copy_len]);
+            chunk_data_len = copy_len;
+            portion_offset = 0;
+        } else {
+            portion_offset = mel_rsp_offset - MEL_RESP_HEADER_SIZE;
+        }
+
+        let rem_len = (chunk.len() - chunk_data_len)
+            .min((self.portion_len as usize).saturating_sub(portion_offset));
+        if rem_len > 0 {
+            let rem_chunk = &mut chunk[chunk_data_len..chunk_data_len + rem_len];
+            let read_len = measurements
+                .mel_portion(self.offset as usize + portion_offset, rem_chunk)
+                .await
+                .map_err(|e| (false, CommandError::Measurement(e)))?;
+            if read_len != rem_len {
+                Err((
+                    false,
+                    CommandError::Measurement(MeasurementsError::MeasurementSizeMismatch),
+                ))?;
+            }
+            chunk_data_len += read_len;
+        }
+
+        Ok(chunk_data_len)
+    }
+}
+
+async fn process_get_mel<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<MelResponse> {
+    // Validate the version
+    let connection_version = ctx.validate_spdm_version(&spdm_hdr, req_payload)?;
+
+    // GET_MEASUREMENT_EXTENSION_LOG is not supported before v1.3
+    if connection_version < SpdmVersion::V13 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    let req = GetMelReq::decode(req_payload).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    // A MEL specification must have been negotiated
+    if selected_mel_specification(ctx).0 == 0 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    ctx.reset_transcript_via_req_code(ReqRespCode::GetMeasurementExtensionLog);
+
+    // A transfer of the log starts at offset 0, the following portions are read from
+    // the same snapshot
+    if req.offset == 0 && ctx.measurements.refresh_mel().await.is_err() {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::Unspecified, 0, None))?;
+    }
+
+    let mel_size = match ctx.measurements.mel_size().await {
+        Ok(size) if size <= u32::MAX as usize => size as u32,
+        _ => Err(ctx.generate_error_response(req_payload, ErrorCode::Unspecified, 0, None))?,
+    };
+
+    let offset = req.offset;
+    if offset >= mel_size {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+    let rem_len = mel_size - offset;
+
+    // The portion is limited by what can be transferred in a single response,
+    // or by the maximum message size of the Requester when chunking is supported.
+    let max_rsp_len = if ctx.support_large_msg_chunking() {
+        (ctx.state
+            .connection_info
+            .peer_capabilities()
+            .max_spdm_msg_size as usize)
+            .min(max_chunked_resp_size(ctx))
+    } else {
+        ctx.min_data_transfer_size()
+    };
+    let max_portion_len = max_rsp_len.saturating_sub(MEL_RESP_HEADER_SIZE) as u32;
+    let portion_len = req.length.min(rem_len).min(max_portion_len);
+
+    Ok(MelResponse {
+        spdm_version: connection_version,
+        offset,
+        portion_len,
+        remainder_len: rem_len - portion_len,
+    })
+}
+
+async fn generate_mel_response<'a>(
+    ctx: &mut SpdmContext<'a>,
+    rsp_ctx: MelResponse,
+    rsp: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    let rsp_len = rsp_ctx.rsp_len();
+    if rsp_len > ctx.min_data_transfer_size() {
+        // The MEL portion does not fit into a single message, send it with CHUNK_GET
+        let large_rsp = LargeResponse::Mel(rsp_ctx.clone());
+        let handle = ctx.large_resp_context.init(large_rsp, rsp_len);
+        Err(ctx.generate_error_response(rsp, ErrorCode::LargeResponse, 0, Some(&[handle])))?;
+    }
+
+    let mut payload_len =
+        encode_u8_slice(&rsp_ctx.resp_hdr()?, rsp).map_err(|e| (false, CommandError::Codec(e)))?;
+
+    let portion_len = rsp_ctx.portion_len as usize;
+    if portion_len > 0 {
+        rsp.put_data(portion_len)
+            .map_err(|e| (false, CommandError::Codec(e)))?;
+        let mel_buf = rsp
+            .data_mut(portion_len)
+            .map_err(|e| (false, CommandError::Codec(e)))?;
+        let read_len = ctx
+            .measurements
+            .mel_portion(rsp_ctx.offset as usize, mel_buf)
+            .await
+            .map_err(|e| (false, CommandError::Measurement(e)))?;
+        if read_len != portion_len {
+            Err(ctx.generate_error_response(rsp, ErrorCode::Unspecified, 0, None))?;
+        }
+        rsp.pull_data(read_len)
+            .map_err(|e| (false, CommandError::Codec(e)))?;
+        payload_len += read_len;
+    }
+
+    rsp.push_data(payload_len)
+        .map_err(|e| (false, CommandError::Codec(e)))
+}
+
+pub(crate) async fn handle_get_measurement_extension_log<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    // Check if the connection state is valid
+    if ctx.state.connection_info.state() < ConnectionState::AlgorithmsNegotiated {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    // Check if MEL_CAP is supported and a MEL source is available
+    if ctx.local_capabilities.flags.mel_cap() == 0 || !ctx.measurements.mel_supported() {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    // Process GET_MEASUREMENT_EXTENSION_LOG request
+    let rsp_ctx = process_get_mel(ctx, spdm_hdr, req_payload).await?;
+
+    // Generate MEASUREMENT_EXTENSION_LOG response
+    ctx.prepare_response_buffer(req_payload)?;
+    generate_mel_response(ctx, rsp_ctx, req_payload).await
+}
@@ -1,6 +1,6 @@
 // Licensed under the Apache-2.0 license
 
-use crate::cert_store::SpdmCertStore;
+use crate::cert_store::{spdm_sign, SpdmCertStore};
 use crate::chunk_ctx::{ChunkError, LargeResponse};
 use crate::codec::{encode_u8_slice, Codec, CommonCodec, MessageBuf};
 use crate::commands::algorithms_rsp::selected_measurement_specification;
@@ -126,6 +126,7 @@ impl MeasurementsResponse {
         let trailer_start = record_end;
         let (variable_fields, trailer_len) = self.response_variable_fields().await?;
         let signature_start = trailer_start + trailer_len;
+        let signature_len = self.asym_algo.signature_size();
 
         // If signature is requested, avoid splitting it across chunks.
         // If this chunk would partially overlap the signature, truncate to
@@ -133,7 +134,7 @@ impl MeasurementsResponse {
         if self.req_attr.signature_requested() == 1
             && offset < signature_start
             && offset + rem_len > signature_start
-            && offset + rem_len < signature_start + ECC_P384_SIGNATURE_SIZE
+            && offset + rem_len < signature_start + signature_len
         {
             rem_len = signature_start - offset;
         }
@@ -192,22 +193,25 @@ impl MeasurementsResponse {
             .await
             .map_err(|e| (false, CommandError::Transcript(e)))?;
 
-        // 4. Copy from the signature if requested.
+        // 4. Sign into the chunk if requested.
         // Due to the truncation above, the signature is always fully contained
         // within a single chunk (offset + copied == signature_start).
         if rem_len > 0
             && self.req_attr.signature_requested() == 1
             && offset + copied >= signature_start
         {
-            let signature = self
-                .l1_signature(self.asym_algo, shared_transcript, session_info, cert_store)
-                .await?;
-
-            let sig_offset = (offset + copied) - signature_start;
-            let copy_len = (ECC_P384_SIGNATURE_SIZE - sig_offset).min(rem_len);
-            chunk_buf[copied..copied + copy_len]
-                .copy_from_slice(&signature[sig_offset..sig_offset + copy_len]);
-            copied += copy_len;
+            if offset + copied != signature_start || rem_len < signature_len {
+                Err((false, CommandError::Chunk(ChunkError::InvalidMessageOffset)))?;
+            }
+            self.l1_signature(
+                self.asym_algo,
+                shared_transcript,
+                session_info,
+                cert_store,
+                &mut chunk_buf[copied..copied + signature_len],
+            )
+            .await?;
+            copied += signature_len;
         }
 
         Ok(copied)
@@ -317,30 +321,8 @@ impl MeasurementsResponse {
         transcript: &mut Transcript,
         session_info: Option<&mut SessionInfo>,
         cert_store: &dyn SpdmCertStore,
-    ) -> CommandResult<[u8; ECC_P384_SIGNATURE_SIZE]> {
-        let mut signature = [0u8; ECC_P384_SIGNATURE_SIZE];
-        let mut signature_buf = MessageBuf::new(&mut signature);
-        let _ = self
-            .encode_l1_signature(
-                asym_algo,
-                transcript,
-                session_info,
-                cert_store,
-                &mut signature_buf,
-            )
-            .await?;
-
-        Ok(signature)
-    }
-
-    async fn encode_l1_signature(
-        &self,
-        asym_algo: AsymAlgo,
-        transcript: &mut Transcript,
-        session_info: Option<&mut SessionInfo>,
-        cert_store: &dyn SpdmCertStore,
-        buf: &mut MessageBuf<'_>,
-    ) -> CommandResult<usize> {
+        signature: &mut [u8],
+    ) -> CommandResult<()> {
         // Get the L1 transcript hash
         let mut l1_transcript_hash = [0u8; SHA384_HASH_SIZE];
 
@@ -354,13 +336,11 @@ impl MeasurementsResponse {
             .await
             .map_err(|e| (false, CommandError::Transcript(e)))?;
 
-        // Get TBS via response code
-        let tbs = get_tbs_via_response_code(
+        let message = SigningMessage::new(
             self.spdm_version,
             ReqRespCode::Measurements,
             l1_transcript_hash,
         )
-        .await
         .map_err(|e| (false, CommandError::SignCtx(e)))?;
 
         let slot_id = self.slot_id.ok_or((
@@ -368,22 +348,9 @@ impl MeasurementsResponse {
             CommandError::Measurement(MeasurementsError::InvalidSlotId),
         ))?;
 
-        let mut signature = [0u8; ECC_P384_SIGNATURE_SIZE];
-        cert_store
-            .sign_hash(slot_id, asym_algo, &tbs, &mut signature)
+        spdm_sign(cert_store, slot_id, asym_algo, &message, signature)
             .await
-            .map_err(|e| (false, CommandError::CertStore(e)))?;
-
-        buf.put_data(signature.len())
-            .map_err(|e| (false, CommandError::Codec(e)))?;
-        let signature_buf = buf
-            .data_mut(signature.len())
-            .map_err(|e| (false, CommandError::Codec(e)))?;
-        signature_buf.copy_from_slice(&signature);
-        buf.pull_data(signature.len())
-            .map_err(|e| (false, CommandError::Codec(e)))?;
-
-        Ok(signature.len())
+            .map_err(|e| (false, CommandError::CertStore(e)))
     }
 
     async fn response_size(&self, measurements: &mut SpdmMeasurements<'_>) -> CommandResult<usize> {
@@ -499,12 +466,22 @@ pub(crate) async fn generate_measurements_response<'a>(
         Err(e) => Err(e)?,
     };
 
-    if rsp_len > ctx.min_data_transfer_size() {
+    // An ML-DSA signature does not fit into a single chunk. Such a signed response is
+    // generated in full and sent in chunks from the large response buffer instead.
+    let signature_fits_chunk =
+        rsp_ctx.req_attr.signature_requested() == 0 || rsp_ctx.asym_algo == AsymAlgo::EccP384;
+
+    if rsp_len > ctx.min_data_transfer_size() && signature_fits_chunk {
         // If the response is larger than the minimum data transfer size, use chunked response
         let large_rsp = LargeResponse::Measurements(rsp_ctx);
         let handle = ctx.large_resp_context.init(large_rsp, rsp_len);
         Err(ctx.generate_error_response(rsp, ErrorCode::LargeResponse, 0, Some(&[handle])))?
     } else {
+        // If the response fits in a single message, prepare it directly
+        // Encode the response fixed fields
+        rsp.put_data(rsp_len)
+            .map_err(|_| ctx.generate_error_response(rsp, ErrorCode::ResponseTooLarge, 0, None))?;
+
         let session_info = match ctx.session_mgr.active_session_id() {
             Some(session_id) => match ctx.session_mgr.session_info_mut(session_id) {
                 Ok(info) => Some(info),
@@ -513,10 +490,6 @@ pub(crate) async fn generate_measurements_response<'a>(
             None => None,
         };
 
-        // If the response fits in a single message, prepare it directly
-        // Encode the response fixed fields
-        rsp.put_data(rsp_len)
-            .map_err(|e| (false, CommandError::Codec(e)))?;
         let rsp_buf = rsp
             .data_mut(rsp_len)
             .map_err(|e| (false, CommandError::Codec(e)))?;
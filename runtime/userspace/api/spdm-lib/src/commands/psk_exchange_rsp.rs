@@ -0,0 +1,385 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{decode_u8_slice, encode_u8_slice, Codec, CommonCodec, MessageBuf};
+use crate::commands::algorithms_rsp::selected_measurement_specification;
+use crate::commands::challenge_auth_rsp::encode_measurement_summary_hash;
+use crate::commands::error_rsp::ErrorCode;
+use crate::commands::key_exchange_rsp::init_session;
+use crate::context::SpdmContext;
+use crate::error::{CommandError, CommandResult};
+use crate::opaque_element::secure_message::{
+    sm_select_version_from_list, sm_selected_version_opaque_data, SmVersion,
+};
+use crate::protocol::*;
+use crate::psk_store::MAX_PSK_HINT_LEN;
+use crate::session::{SessionKeyType, SessionPolicy, SessionState};
+use crate::state::ConnectionState;
+use crate::transcript::TranscriptContext;
+use caliptra_mcu_libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
+use caliptra_mcu_libapi_caliptra::crypto::rng::Rng;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+// Length of the ResponderContext generated in PskWithContext mode
+const PSK_RESPONDER_CONTEXT_LEN: usize = SHA384_HASH_SIZE;
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct PskExchangeReqBase {
+    meas_summary_hash_type: u8,
+    session_policy: SessionPolicy,
+    req_session_id: u16,
+    psk_hint_len: u16,
+    requester_context_len: u16,
+    opaque_data_len: u16,
+}
+
+impl CommonCodec for PskExchangeReqBase {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct PskExchangeRspBase {
+    heartbeat_period: u8,
+    _reserved0: u8,
+    rsp_session_id: u16,
+    _reserved1: u16,
+    responder_context_len: u16,
+    opaque_data_len: u16,
+}
+
+impl CommonCodec for PskExchangeRspBase {}
+
+struct PskExchRspContext {
+    meas_summary_hash_type: u8,
+    selected_sm_version: SmVersion,
+    resp_session_id: u16,
+    session_id: u32,
+    heartbeat_period: u8,
+    with_context: bool,
+}
+
+async fn process_psk_exchange<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<PskExchRspContext> {
+    // Validate the version
+    let _ = ctx.validate_spdm_version(&spdm_hdr, req_payload)?;
+
+    // Decode the PSK_EXCHANGE request fixed fields
+    let psk_exch_req = PskExchangeReqBase::decode(req_payload).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    // Validate measurement summary hash type and DMTF spec
+    match psk_exch_req.meas_summary_hash_type {
+        0 => {} // No measurement summary hash requested
+        1 | 0xFF => {
+            if selected_measurement_specification(ctx).dmtf_measurement_spec() != 1 {
+                Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+            }
+        }
+        _ => Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?,
+    }
+
+    // If session policy with event_all_policy is set, verify that the responder supports event capability
+    if psk_exch_req.session_policy.event_all_policy() != 0
+        && ctx.local_capabilities.flags.event_cap() == 0
+    {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+
+    // Decode the PSKHint
+    let psk_hint_len = psk_exch_req.psk_hint_len as usize;
+    if psk_hint_len > MAX_PSK_HINT_LEN {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+    let mut psk_hint = [0u8; MAX_PSK_HINT_LEN];
+    decode_u8_slice(req_payload, &mut psk_hint[..psk_hint_len]).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    // The RequesterContext is only consumed through the transcript
+    let requester_context_len = psk_exch_req.requester_context_len as usize;
+    if req_payload.data_len() < requester_context_len {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+    req_payload
+        .pull_data(requester_context_len)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    // Decode the OpaqueData and select the secure version from list
+    let opaque_data_len = psk_exch_req.opaque_data_len as usize;
+    if opaque_data_len > OPAQUE_DATA_LEN_MAX_SIZE {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+    let mut req_opaque_data = OpaqueData {
+        len: opaque_data_len as u16,
+        ..Default::default()
+    };
+    decode_u8_slice(req_payload, &mut req_opaque_data.data[..opaque_data_len]).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    let selected_sm_version =
+        sm_select_version_from_list(req_opaque_data, ctx.supported_secure_versions).map_err(
+            |_| ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None),
+        )?;
+
+    // Look up the PSK for the hint
+    let psk_store = ctx.psk_store.ok_or_else(|| {
+        ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None)
+    })?;
+    let psk = psk_store
+        .psk(&psk_hint[..psk_hint_len])
+        .await
+        .map_err(|_| {
+            ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+        })?;
+
+    ctx.state
+        .connection_info
+        .set_sec_msg_version(selected_sm_version);
+
+    // Create session
+    let (session_id, resp_session_id) = ctx
+        .session_mgr
+        .generate_session_id(psk_exch_req.req_session_id);
+
+    // Create session and initialize it
+    ctx.session_mgr.create_session(session_id).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::SessionLimitExceeded, 0, None)
+    })?;
+
+    ctx.session_mgr.set_clear_request_session_id(session_id);
+
+    // Heartbeat is only enabled if both the Responder and the Requester support it
+    let heartbeat_period = if ctx.local_capabilities.flags.hbeat_cap() != 0
+        && ctx
+            .state
+            .connection_info
+            .peer_capabilities()
+            .flags
+            .hbeat_cap()
+            != 0
+    {
+        ctx.heartbeat_period
+    } else {
+        0
+    };
+
+    let session_info = ctx
+        .session_mgr
+        .session_info_mut(session_id)
+        .map_err(|e| (false, CommandError::Session(e)))?;
+
+    // PSK sessions are not authenticated with the asymmetric key, keep the default
+    let asym_algo = session_info.asym_algo;
+    init_session(
+        session_info,
+        ctx.local_capabilities.flags,
+        &ctx.state.connection_info,
+        psk_exch_req.session_policy,
+        asym_algo,
+        heartbeat_period,
+    );
+    session_info.set_psk(psk);
+
+    ctx.reset_transcript_via_req_code(ReqRespCode::PskExchange);
+
+    // Update transcript with the PSK_EXCHANGE request
+    if let Err(e) = ctx
+        .append_message_to_transcript(req_payload, TranscriptContext::Th, Some(session_id))
+        .await
+    {
+        let _ = ctx.session_mgr.delete_session(session_id);
+        return Err(e);
+    }
+
+    // The ResponderContext, and with it PSK_FINISH, is only used in PskWithContext mode
+    let with_context =
+        ctx.local_capabilities.flags.psk_cap() == PskCapability::PskWithContext as u8;
+
+    Ok(PskExchRspContext {
+        meas_summary_hash_type: psk_exch_req.meas_summary_hash_type,
+        selected_sm_version,
+        resp_session_id,
+        session_id,
+        heartbeat_period,
+        with_context,
+    })
+}
+
+async fn generate_psk_exchange_response<'a>(
+    ctx: &mut SpdmContext<'a>,
+    psk_exch_rsp_ctx: &PskExchRspContext,
+    rsp: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    let session_id = psk_exch_rsp_ctx.session_id;
+
+    // Spdm Header first
+    let connection_version = ctx.state.connection_info.version_number();
+    let spdm_hdr = SpdmMsgHdr::new(connection_version, ReqRespCode::PskExchangeRsp);
+    let mut payload_len = spdm_hdr
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    let opaque_data = sm_selected_version_opaque_data(psk_exch_rsp_ctx.selected_sm_version)
+        .map_err(|e| (false, CommandError::OpaqueData(e)))?;
+
+    let responder_context_len = if psk_exch_rsp_ctx.with_context {
+        PSK_RESPONDER_CONTEXT_LEN
+    } else {
+        0
+    };
+
+    // Encode the PSK_EXCHANGE_RSP fixed fields
+    let psk_exch_rsp = PskExchangeRspBase {
+        heartbeat_period: psk_exch_rsp_ctx.heartbeat_period,
+        _reserved0: 0,
+        rsp_session_id: psk_exch_rsp_ctx.resp_session_id,
+        _reserved1: 0,
+        responder_context_len: responder_context_len as u16,
+        opaque_data_len: opaque_data.len,
+    };
+    payload_len += psk_exch_rsp
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    // Get the measurement summary hash
+    if psk_exch_rsp_ctx.meas_summary_hash_type != 0 {
+        payload_len +=
+            encode_measurement_summary_hash(ctx, psk_exch_rsp_ctx.meas_summary_hash_type, rsp)
+                .await?;
+    }
+
+    // Encode the ResponderContext
+    if responder_context_len > 0 {
+        let mut responder_context = [0u8; PSK_RESPONDER_CONTEXT_LEN];
+        Rng::generate_random_number(&mut responder_context)
+            .await
+            .map_err(|e| (false, CommandError::CaliptraApi(e)))?;
+        payload_len += encode_u8_slice(&responder_context, rsp)
+            .map_err(|e| (false, CommandError::Codec(e)))?;
+    }
+
+    // Encode the Opaque data with version selection
+    payload_len += encode_u8_slice(&opaque_data.data[..opaque_data.len as usize], rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    // Append the response excluding ResponderVerifyData to the TH transcript
+    ctx.append_message_to_transcript(rsp, TranscriptContext::Th, Some(session_id))
+        .await?;
+
+    // Compute TH1 transcript hash for generating the session handshake key
+    let th1_transcript_hash = ctx
+        .transcript_hash(TranscriptContext::Th, Some(session_id), false)
+        .await?;
+
+    let session_info = ctx
+        .session_mgr
+        .session_info_mut(session_id)
+        .map_err(|e| (false, CommandError::Session(e)))?;
+
+    session_info
+        .generate_session_handshake_key(&th1_transcript_hash)
+        .await
+        .map_err(|e| (false, CommandError::Session(e)))?;
+
+    // ResponderVerifyData is always present in PSK_EXCHANGE_RSP
+    let responder_verify_data = session_info
+        .compute_hmac(SessionKeyType::ResponseFinishedKey, &th1_transcript_hash)
+        .await
+        .map_err(|e| (false, CommandError::Session(e)))?;
+
+    payload_len += encode_u8_slice(&responder_verify_data, rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    ctx.append_slice_to_transcript(
+        &responder_verify_data,
+        TranscriptContext::Th,
+        Some(session_id),
+    )
+    .await?;
+
+    // Without PSK_FINISH, the session data key is derived right away from TH2,
+    // which then covers PSK_EXCHANGE and PSK_EXCHANGE_RSP only.
+    if !psk_exch_rsp_ctx.with_context {
+        let th2_transcript_hash = ctx
+            .transcript_hash(TranscriptContext::Th, Some(session_id), true)
+            .await?;
+
+        let session_info = ctx
+            .session_mgr
+            .session_info_mut(session_id)
+            .map_err(|e| (false, CommandError::Session(e)))?;
+
+        session_info
+            .generate_session_data_key(&th2_transcript_hash)
+            .await
+            .map_err(|e| (false, CommandError::Session(e)))?;
+    }
+
+    rsp.push_data(payload_len)
+        .map_err(|e| (false, CommandError::Codec(e)))
+}
+
+pub(crate) async fn handle_psk_exchange<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    // Check if the connection state is valid
+    if ctx.state.connection_info.state() < ConnectionState::AlgorithmsNegotiated {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    // PSK_EXCHANGE is not supported in v1.0
+    if ctx.state.connection_info.version_number() < SpdmVersion::V11 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    // Check if PSK_CAP is supported and a PSK store is provisioned
+    let psk_cap = ctx.local_capabilities.flags.psk_cap();
+    if (psk_cap != PskCapability::PskWithNoContext as u8
+        && psk_cap != PskCapability::PskWithContext as u8)
+        || ctx.psk_store.is_none()
+    {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    // DSP0277 specifies that secure messaging requires at least MAC_CAP to be set.
+    if ctx.local_capabilities.flags.mac_cap() == 0 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+
+    // Check negotiated algorithms are valid
+    ctx.validate_negotiated_hash_algo(req_payload)?;
+
+    // Process PSK_EXCHANGE request
+    let psk_exch_rsp_ctx = process_psk_exchange(ctx, spdm_hdr, req_payload).await?;
+
+    // Generate PSK_EXCHANGE_RSP response
+    ctx.prepare_response_buffer(req_payload)?;
+
+    let session_id = psk_exch_rsp_ctx.session_id;
+
+    // Generate response with automatic cleanup on error
+    if let Err(e) = generate_psk_exchange_response(ctx, &psk_exch_rsp_ctx, req_payload).await {
+        let _ = ctx.session_mgr.delete_session(session_id); // Ignore cleanup errors
+        return Err(e);
+    }
+
+    if psk_exch_rsp_ctx.with_context {
+        // Wait for PSK_FINISH within the session
+        ctx.session_mgr
+            .set_session_state(session_id, SessionState::HandshakeInProgress)
+            .map_err(|e| (false, CommandError::Session(e)))?;
+    } else {
+        // Without PSK_FINISH, the session enters the application phase immediately
+        ctx.session_mgr
+            .set_session_state(session_id, SessionState::Established)
+            .map_err(|e| (false, CommandError::Session(e)))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,184 @@
+// Licensed under the Apache-2.0 license
+
+use crate::cert_store::{CertStoreError, MAX_CERT_SLOTS_SUPPORTED};
+use crate::codec::{Codec, CommonCodec, MessageBuf};
+use crate::commands::error_rsp::ErrorCode;
+use crate::context::SpdmContext;
+use crate::error::{CommandError, CommandResult};
+use crate::protocol::*;
+use crate::state::ConnectionState;
+use bitfield::bitfield;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct SetCertificateReqBase {
+    param1: SetCertificateReqAttributes,
+    key_pair_id: u8,
+}
+impl CommonCodec for SetCertificateReqBase {}
+
+bitfield! {
+    #[derive(FromBytes, IntoBytes, Immutable)]
+    #[repr(C)]
+    struct SetCertificateReqAttributes(u8);
+    impl Debug;
+    u8;
+    pub slot_id, set_slot_id: 3, 0;
+    pub cert_model, set_cert_model: 6, 4;
+    pub erase, set_erase: 7, 7;
+}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct SetCertificateRspBase {
+    slot_id: u8,
+    _reserved: u8,
+}
+impl CommonCodec for SetCertificateRspBase {}
+
+/// Map a certificate store error of a GET_CSR or SET_CERTIFICATE request to the SPDM error code.
+pub(crate) fn cert_store_error_code(ctx: &SpdmContext, e: &CertStoreError) -> ErrorCode {
+    match e {
+        // The Responder can only ask for a reset if it advertised CERT_INSTALL_RESET_CAP
+        CertStoreError::ResetRequired
+            if ctx.local_capabilities.flags.cert_install_reset_cap() != 0 =>
+        {
+            ErrorCode::ResetRequired
+        }
+        CertStoreError::InvalidSlotId
+        | CertStoreError::InvalidCertChain
+        | CertStoreError::UnsupportedOperation
+        | CertStoreError::UnsupportedAsymAlgo => ErrorCode::InvalidRequest,
+        _ => ErrorCode::Unspecified,
+    }
+}
+
+async fn process_set_certificate<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<u8> {
+    // Validate the version
+    let connection_version = ctx.validate_spdm_version(&spdm_hdr, req_payload)?;
+
+    // SET_CERTIFICATE is not supported before v1.2
+    if connection_version < SpdmVersion::V12 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    let req = SetCertificateReqBase::decode(req_payload).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    let slot_id = req.param1.slot_id();
+    let slot_count = ctx
+        .device_certs_store
+        .slot_count()
+        .min(MAX_CERT_SLOTS_SUPPORTED);
+    if slot_id >= slot_count {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+
+    // CertModel, Erase and KeyPairID are reserved before v1.3
+    let (key_pair_id, cert_model, erase) = if connection_version >= SpdmVersion::V13 {
+        let cert_model = CertModel::try_from(req.param1.cert_model()).map_err(|_| {
+            ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+        })?;
+        let erase = req.param1.erase() != 0;
+
+        let valid = if erase {
+            // Erasing a slot does not carry a certificate model
+            cert_model == CertModel::None
+        } else if ctx.state.connection_info.multi_key_conn_rsp() {
+            cert_model != CertModel::None
+        } else {
+            req.key_pair_id == 0 && cert_model == CertModel::None
+        };
+        if !valid {
+            Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+        }
+        (req.key_pair_id, cert_model, erase)
+    } else {
+        (0, CertModel::None, false)
+    };
+
+    ctx.reset_transcript_via_req_code(ReqRespCode::SetCertificate);
+
+    let result = if erase {
+        ctx.device_certs_store.erase_cert_chain(slot_id).await
+    } else {
+        // The certificate chain is in SPDM format: Length, Reserved, RootHash, Certificates
+        let cert_chain_hdr = SpdmCertChainHeader::decode(req_payload).map_err(|_| {
+            ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+        })?;
+
+        let cert_chain_len =
+            (cert_chain_hdr.length as usize).saturating_sub(SPDM_CERT_CHAIN_METADATA_LEN);
+        if cert_chain_len == 0 || cert_chain_len > req_payload.data_len() {
+            Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+        }
+
+        let cert_chain = req_payload
+            .data(cert_chain_len)
+            .map_err(|e| (false, CommandError::Codec(e)))?;
+
+        ctx.device_certs_store
+            .set_cert_chain(slot_id, key_pair_id, cert_model, cert_chain)
+            .await
+    };
+
+    if let Err(e) = result {
+        let error_code = cert_store_error_code(ctx, &e);
+        Err(ctx.generate_error_response(req_payload, error_code, 0, None))?;
+    }
+
+    Ok(slot_id)
+}
+
+fn generate_set_certificate_response(
+    ctx: &mut SpdmContext<'_>,
+    slot_id: u8,
+    rsp: &mut MessageBuf<'_>,
+) -> CommandResult<()> {
+    // Spdm Header first
+    let connection_version = ctx.state.connection_info.version_number();
+    let spdm_hdr = SpdmMsgHdr::new(connection_version, ReqRespCode::SetCertificateRsp);
+    let mut payload_len = spdm_hdr
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    let set_cert_rsp = SetCertificateRspBase {
+        slot_id,
+        _reserved: 0,
+    };
+    payload_len += set_cert_rsp
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    rsp.push_data(payload_len)
+        .map_err(|e| (false, CommandError::Codec(e)))
+}
+
+pub(crate) async fn handle_set_certificate<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    // Check if the connection state is valid
+    if ctx.state.connection_info.state() < ConnectionState::AlgorithmsNegotiated {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    // Check if SET_CERT_CAP is supported
+    if ctx.local_capabilities.flags.set_certificate_cap() == 0 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    // Process SET_CERTIFICATE request
+    let slot_id = process_set_certificate(ctx, spdm_hdr, req_payload).await?;
+
+    // Generate SET_CERTIFICATE_RSP response
+    ctx.prepare_response_buffer(req_payload)?;
+    generate_set_certificate_response(ctx, slot_id, req_payload)
+}
@@ -1,28 +1,35 @@
 // Licensed under the Apache-2.0 license
 
 use crate::cert_store::*;
-use crate::chunk_ctx::LargeResponseCtx;
+use crate::chunk_ctx::{LargeRequestCtx, LargeResponseCtx};
 use crate::codec::{encode_u8_slice, Codec, MessageBuf};
 use crate::commands::error_rsp::{encode_error_response, ErrorCode};
 use crate::commands::{
     algorithms_rsp, capabilities_rsp, certificate_rsp, challenge_auth_rsp, chunk_get_rsp,
-    digests_rsp, end_session_ack_rsp, finish_rsp, key_exchange_rsp, measurements_rsp,
-    vendor_defined_rsp, version_rsp,
+    chunk_send_rsp, csr_rsp, digests_rsp, encapsulated_request_rsp, encapsulated_response_ack_rsp,
+    end_session_ack_rsp, endpoint_info_rsp, finish_rsp, heartbeat_rsp, key_exchange_rsp,
+    key_pair_info_rsp, key_update_rsp, measurement_extension_log_rsp, measurements_rsp,
+    psk_exchange_rsp, psk_finish_rsp, set_certificate_rsp, vendor_defined_rsp, version_rsp,
 };
+use crate::encap_ctx::EncapRequestCtx;
 use crate::error::*;
 use crate::measurements::SpdmMeasurements;
 use crate::protocol::algorithms::*;
 use crate::protocol::common::{ReqRespCode, SpdmMsgHdr};
 use crate::protocol::version::*;
 use crate::protocol::DeviceCapabilities;
-use crate::session::{SessionManager, SessionState};
+use crate::psk_store::PskStore;
+use crate::session::{SessionEvictionPolicy, SessionManager, SessionSlot, SessionState};
 use crate::state::{ConnectionState, State};
 use crate::transcript::{Transcript, TranscriptContext};
 use crate::transport::common::SpdmTransport;
+use crate::trust_anchor_store::RequesterTrustAnchorStore;
 use crate::vdm_handler::VdmHandler;
 use caliptra_mcu_libapi_caliptra::crypto::aes_gcm::Aes256GcmTag;
 use caliptra_mcu_libapi_caliptra::crypto::asym::*;
 use caliptra_mcu_libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
+use caliptra_mcu_libsyscall_caliptra::DefaultSyscalls;
+use caliptra_mcu_libtock_alarm::Alarm;
 use core::mem::size_of;
 
 // Maximum SPDM responder buffer size
@@ -38,9 +45,15 @@ pub struct SpdmContext<'a> {
     pub(crate) local_algorithms: LocalDeviceAlgorithms<'a>,
     pub(crate) device_certs_store: &'a dyn SpdmCertStore,
     pub(crate) measurements: SpdmMeasurements<'a>,
-    pub(crate) large_resp_context: LargeResponseCtx,
-    pub(crate) session_mgr: SessionManager,
+    pub(crate) large_resp_context: LargeResponseCtx<'a>,
+    pub(crate) large_req_context: LargeRequestCtx<'a>,
+    pub(crate) session_mgr: SessionManager<'a>,
     pub(crate) vdm_handlers: Option<&'a mut [&'a mut dyn VdmHandler]>,
+    pub(crate) heartbeat_period: u8,
+    pub(crate) psk_store: Option<&'a dyn PskStore>,
+    pub(crate) requester_trust_anchor_store: Option<&'a dyn RequesterTrustAnchorStore>,
+    pub(crate) encap_ctx: EncapRequestCtx<'a>,
+    pub(crate) endpoint_info: Option<&'a [u8]>,
 }
 
 impl<'a> SpdmContext<'a> {
@@ -53,9 +66,11 @@ impl<'a> SpdmContext<'a> {
         local_algorithms: LocalDeviceAlgorithms<'a>,
         device_certs_store: &'a dyn SpdmCertStore,
         measurements: SpdmMeasurements<'a>,
+        session_slots: &'a mut [SessionSlot],
         vdm_handlers: Option<&'a mut [&'a mut dyn VdmHandler]>,
     ) -> SpdmResult<Self> {
         validate_supported_versions(supported_versions)?;
+        validate_supported_versions(supported_secure_versions)?;
 
         validate_cert_store(device_certs_store)?;
 
@@ -70,11 +85,103 @@ impl<'a> SpdmContext<'a> {
             device_certs_store,
             measurements,
             large_resp_context: LargeResponseCtx::default(),
-            session_mgr: SessionManager::new(),
+            large_req_context: LargeRequestCtx::default(),
+            session_mgr: SessionManager::new(session_slots),
             vdm_handlers,
+            heartbeat_period: 0,
+            psk_store: None,
+            requester_trust_anchor_store: None,
+            encap_ctx: EncapRequestCtx::default(),
+            endpoint_info: None,
         })
     }
 
+    /// Sets the heartbeat period (in seconds) reported in KEY_EXCHANGE_RSP.
+    /// A session is torn down if no message is received within twice this period.
+    /// The period only takes effect if both sides support HBEAT_CAP. A value of 0
+    /// disables the heartbeat.
+    ///
+    /// # Arguments
+    /// * `heartbeat_period` - The heartbeat period in seconds.
+    pub fn set_heartbeat_period(&mut self, heartbeat_period: u8) {
+        self.heartbeat_period = heartbeat_period;
+    }
+
+    /// Sets the PSK store used to look up the pre-shared key of PSK_EXCHANGE requests.
+    /// PSK sessions are only accepted if PSK_CAP is set in the local capabilities.
+    ///
+    /// # Arguments
+    /// * `psk_store` - The PSK store.
+    pub fn set_psk_store(&mut self, psk_store: &'a dyn PskStore) {
+        self.psk_store = Some(psk_store);
+    }
+
+    /// Sets the trust anchor store used to verify the Requester certificate chain for
+    /// session-based mutual authentication. Mutual authentication is only requested in
+    /// KEY_EXCHANGE_RSP if MUT_AUTH_CAP and ENCAP_CAP are set in the local capabilities
+    /// and in the Requester capabilities.
+    ///
+    /// # Arguments
+    /// * `trust_anchor_store` - The trust anchor store.
+    /// * `cert_chain_buf` - The buffer to retrieve the Requester certificate chain into
+    ///   with encapsulated GET_CERTIFICATE requests. Limits the supported chain size.
+    pub fn set_requester_trust_anchor_store(
+        &mut self,
+        trust_anchor_store: &'a dyn RequesterTrustAnchorStore,
+        cert_chain_buf: &'a mut [u8],
+    ) {
+        self.requester_trust_anchor_store = Some(trust_anchor_store);
+        self.encap_ctx = EncapRequestCtx::new(cert_chain_buf);
+    }
+
+    /// Sets the buffer used to reassemble large requests received in chunks with CHUNK_SEND.
+    /// Without this buffer, requests larger than the DataTransferSize are rejected.
+    /// The size of a large request is further limited by the MaxSPDMmsgSize in the
+    /// local capabilities.
+    ///
+    /// # Arguments
+    /// * `buffer` - The reassembly buffer. Limits the supported large request size.
+    pub fn set_large_request_buffer(&mut self, buffer: &'a mut [u8]) {
+        self.large_req_context = LargeRequestCtx::new(buffer);
+    }
+
+    /// Sets the buffer used to send large responses that are generated in full, such as
+    /// responses carrying an ML-DSA-87 signature, in chunks with CHUNK_GET.
+    /// Without this buffer, such responses larger than the DataTransferSize are rejected
+    /// with ResponseTooLarge. The message buffer passed to `process_message` must also
+    /// fit the largest of these responses.
+    ///
+    /// # Arguments
+    /// * `buffer` - The large response buffer. Limits the supported large response size.
+    pub fn set_large_response_buffer(&mut self, buffer: &'a mut [u8]) {
+        self.large_resp_context.set_buffer(buffer);
+    }
+
+    /// Sets the endpoint information returned in ENDPOINT_INFO (SPDM 1.3+).
+    /// GET_ENDPOINT_INFO is only supported if EP_INFO_CAP is set in the local capabilities.
+    /// The response is signed on request if EP_INFO_CAP indicates signature support.
+    ///
+    /// # Arguments
+    /// * `endpoint_info` - The EPInfo for the device class identifier sub code.
+    pub fn set_endpoint_info(&mut self, endpoint_info: &'a [u8]) {
+        self.endpoint_info = Some(endpoint_info);
+    }
+
+    /// Sets the policy applied when KEY_EXCHANGE is received while all session slots are in use.
+    ///
+    /// # Arguments
+    /// * `eviction_policy` - The session eviction policy.
+    pub fn set_session_eviction_policy(&mut self, eviction_policy: SessionEvictionPolicy) {
+        self.session_mgr.set_eviction_policy(eviction_policy);
+    }
+
+    /// Terminates the sessions whose session policy requested termination upon a
+    /// runtime code or configuration update. To be called by the integrator when
+    /// such an update takes effect.
+    pub fn terminate_sessions_on_runtime_update(&mut self) {
+        self.session_mgr.terminate_sessions_on_runtime_update();
+    }
+
     pub async fn process_message(&mut self, msg_buf: &mut MessageBuf<'a>) -> SpdmResult<()> {
         let secure = self
             .transport
@@ -82,8 +189,14 @@ impl<'a> SpdmContext<'a> {
             .await
             .map_err(SpdmError::Transport)?;
 
-        // Reset active session_id
-        self.session_mgr.reset_active_session_id();
+        // Reset the session of the previous request
+        self.session_mgr.reset_request_session_id();
+
+        // Tear down sessions whose heartbeat timeout has expired
+        let now_ms = Alarm::<DefaultSyscalls>::get_milliseconds().ok();
+        if let Some(now_ms) = now_ms {
+            self.session_mgr.terminate_expired_sessions(now_ms);
+        }
 
         if secure {
             // Create a temporary buffer for decrypted application data
@@ -103,7 +216,14 @@ impl<'a> SpdmContext<'a> {
         }
 
         // Process message
-        match self.handle_request(msg_buf).await {
+        let result = self.handle_request(msg_buf).await;
+
+        // Record the session activity for the heartbeat timeout
+        if let Some(now_ms) = now_ms {
+            self.session_mgr.record_activity(now_ms);
+        }
+
+        match result {
             Ok(()) => {
                 self.send_response(msg_buf, secure).await?;
             }
@@ -135,6 +255,28 @@ impl<'a> SpdmContext<'a> {
             self.large_resp_context.reset();
         }
 
+        if req_code != ReqRespCode::ChunkSend && self.large_req_context.in_progress() {
+            // Drop the partially received large request if the request is not a CHUNK_SEND
+            self.large_req_context.reset();
+        }
+
+        if req_code == ReqRespCode::ChunkSend {
+            // Check for requests prohibited within session
+            self.validate_request_in_session_context(req_code, req)?;
+            return chunk_send_rsp::handle_chunk_send(self, req_msg_header, req).await;
+        }
+
+        self.dispatch_request(req_msg_header, req_code, req).await
+    }
+
+    /// Dispatches a request to its handler. Also used for a large request once it is
+    /// reassembled from CHUNK_SEND requests.
+    pub(crate) async fn dispatch_request(
+        &mut self,
+        req_msg_header: SpdmMsgHdr,
+        req_code: ReqRespCode,
+        req: &mut MessageBuf<'a>,
+    ) -> CommandResult<()> {
         // Check for requests prohibited within session
         self.validate_request_in_session_context(req_code, req)?;
 
@@ -160,6 +302,20 @@ impl<'a> SpdmContext<'a> {
             ReqRespCode::GetMeasurements => {
                 measurements_rsp::handle_get_measurements(self, req_msg_header, req).await?
             }
+            ReqRespCode::GetMeasurementExtensionLog => {
+                measurement_extension_log_rsp::handle_get_measurement_extension_log(
+                    self,
+                    req_msg_header,
+                    req,
+                )
+                .await?
+            }
+            ReqRespCode::GetEndpointInfo => {
+                endpoint_info_rsp::handle_get_endpoint_info(self, req_msg_header, req).await?
+            }
+            ReqRespCode::GetKeyPairInfo => {
+                key_pair_info_rsp::handle_get_key_pair_info(self, req_msg_header, req).await?
+            }
             ReqRespCode::ChunkGet => {
                 chunk_get_rsp::handle_chunk_get(self, req_msg_header, req).await?
             }
@@ -167,15 +323,74 @@ impl<'a> SpdmContext<'a> {
                 key_exchange_rsp::handle_key_exchange(self, req_msg_header, req).await?
             }
             ReqRespCode::Finish => finish_rsp::handle_finish(self, req_msg_header, req).await?,
+            ReqRespCode::PskExchange => {
+                psk_exchange_rsp::handle_psk_exchange(self, req_msg_header, req).await?
+            }
+            ReqRespCode::PskFinish => {
+                psk_finish_rsp::handle_psk_finish(self, req_msg_header, req).await?
+            }
+            ReqRespCode::GetEncapsulatedRequest => {
+                encapsulated_request_rsp::handle_get_encapsulated_request(self, req_msg_header, req)
+                    .await?
+            }
+            ReqRespCode::DeliverEncapsulatedResponse => {
+                encapsulated_response_ack_rsp::handle_deliver_encapsulated_response(
+                    self,
+                    req_msg_header,
+                    req,
+                )
+                .await?
+            }
             ReqRespCode::EndSession => {
                 end_session_ack_rsp::handle_end_session(self, req_msg_header, req).await?
             }
+            ReqRespCode::KeyUpdate => {
+                key_update_rsp::handle_key_update(self, req_msg_header, req).await?
+            }
+            ReqRespCode::Heartbeat => {
+                heartbeat_rsp::handle_heartbeat(self, req_msg_header, req).await?
+            }
+            ReqRespCode::GetCsr => csr_rsp::handle_get_csr(self, req_msg_header, req).await?,
+            ReqRespCode::SetCertificate => {
+                set_certificate_rsp::handle_set_certificate(self, req_msg_header, req).await?
+            }
             ReqRespCode::VendorDefinedRequest => {
                 vendor_defined_rsp::handle_vendor_defined_request(self, req_msg_header, req).await?
             }
             _ => Err((false, CommandError::UnsupportedRequest))?,
         }
-        Ok(())
+
+        // Responses generated in full beyond the DataTransferSize are sent with CHUNK_GET
+        self.buffer_large_response(req)
+    }
+
+    fn buffer_large_response(&mut self, rsp: &mut MessageBuf<'a>) -> CommandResult<()> {
+        let rsp_len = rsp.data_len();
+        if self.state.connection_info.state() < ConnectionState::AlgorithmsNegotiated
+            || rsp_len <= self.min_data_transfer_size()
+        {
+            return Ok(());
+        }
+
+        let max_rsp_len = (self
+            .state
+            .connection_info
+            .peer_capabilities()
+            .max_spdm_msg_size as usize)
+            .min(chunk_get_rsp::max_chunked_resp_size(self));
+        if !self.support_large_msg_chunking() || rsp_len > max_rsp_len {
+            Err(self.generate_error_response(rsp, ErrorCode::ResponseTooLarge, 0, None))?;
+        }
+
+        let large_rsp = rsp
+            .data(rsp_len)
+            .map_err(|e| (false, CommandError::Codec(e)))?;
+        let handle = match self.large_resp_context.init_buffered(large_rsp) {
+            Ok(handle) => handle,
+            Err(_) => Err(self.generate_error_response(rsp, ErrorCode::ResponseTooLarge, 0, None))?,
+        };
+
+        Err(self.generate_error_response(rsp, ErrorCode::LargeResponse, 0, Some(&[handle])))
     }
 
     async fn send_response(&mut self, resp: &mut MessageBuf<'a>, secure: bool) -> SpdmResult<()> {
@@ -207,6 +422,7 @@ impl<'a> SpdmContext<'a> {
     pub(crate) fn reset(&mut self) {
         self.state.reset();
         self.session_mgr.reset();
+        self.encap_ctx.reset();
     }
 
     pub(crate) fn prepare_response_buffer(&self, rsp_buf: &mut MessageBuf) -> CommandResult<()> {
@@ -287,6 +503,8 @@ impl<'a> SpdmContext<'a> {
             ReqRespCode::GetMeasurements
             | ReqRespCode::KeyExchange
             | ReqRespCode::Finish
+            | ReqRespCode::PskExchange
+            | ReqRespCode::PskFinish
             | ReqRespCode::EndSession => {
                 if self.state.connection_info.state() < ConnectionState::Authenticated {
                     self.shared_transcript.reset_context(TranscriptContext::M1);
@@ -408,26 +626,36 @@ impl<'a> SpdmContext<'a> {
         &self,
         req_payload: &mut MessageBuf,
     ) -> CommandResult<AsymAlgo> {
+        let (base_asym_sel, pqc_asym_sel) = algorithms_rsp::selected_asym_algo(self);
+
+        // Ensure PqcAsymSel has exactly one bit set and it is ML-DSA-87
+        if pqc_asym_sel.0 != 0 {
+            if pqc_asym_sel.0.count_ones() == 1 && pqc_asym_sel.ml_dsa_87() == 1 {
+                return Ok(AsymAlgo::MlDsa87);
+            }
+        } else if base_asym_sel.0.count_ones() == 1
+            && base_asym_sel.tpm_alg_ecdsa_ecc_nist_p384() == 1
+        {
+            // Otherwise BaseAsymSel must have exactly one bit set and it is ECC P-384
+            return Ok(AsymAlgo::EccP384);
+        }
+
+        Err(self.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))
+    }
+
+    /// Returns true if ECC P-384 is the negotiated Requester asymmetric algorithm
+    pub(crate) fn negotiated_req_base_asym_algo_p384(&self) -> bool {
         let peer_algorithms = self.state.connection_info.peer_algorithms();
         let local_algorithms = &self.local_algorithms.device_algorithms;
         let algorithm_priority_table = &self.local_algorithms.algorithm_priority_table;
 
-        let base_asym_sel = BaseAsymAlgo(local_algorithms.base_asym_algo.0.prioritize(
-            &peer_algorithms.base_asym_algo.0,
-            algorithm_priority_table.base_asym_algo,
+        let req_base_asym_sel = ReqBaseAsymAlg(local_algorithms.req_base_asym_algo.0.prioritize(
+            &peer_algorithms.req_base_asym_algo.0,
+            algorithm_priority_table.req_base_asym_algo,
         ));
 
-        // Ensure AsymAlgoSel has exactly one bit set and it is ECC P-384
-        if base_asym_sel.0.count_ones() != 1 || base_asym_sel.tpm_alg_ecdsa_ecc_nist_p384() != 1 {
-            return Err(self.generate_error_response(
-                req_payload,
-                ErrorCode::InvalidRequest,
-                0,
-                None,
-            ));
-        }
-
-        Ok(AsymAlgo::EccP384)
+        req_base_asym_sel.0.count_ones() == 1
+            && req_base_asym_sel.tpm_alg_ecdsa_ecc_nist_p384() == 1
     }
 
     pub(crate) fn validate_negotiated_dhe_group(&self, rsp: &mut MessageBuf) -> CommandResult<()> {
@@ -464,13 +692,19 @@ impl<'a> SpdmContext<'a> {
             .session_info(session_id)
             .map_err(|_| self.generate_error_response(req, ErrorCode::SessionRequired, 0, None))?;
 
+        // Only a retried KEY_UPDATE may be protected with the keys replaced by KEY_UPDATE
+        if session_info.key_update.previous_key_in_use && req_code != ReqRespCode::KeyUpdate {
+            return Err(self.generate_error_response(req, ErrorCode::UnexpectedRequest, 0, None));
+        }
+
         match req_code {
             // These requests are completely prohibited within any session
             ReqRespCode::GetVersion
             | ReqRespCode::GetCapabilities
             | ReqRespCode::NegotiateAlgorithms
             | ReqRespCode::Challenge
-            | ReqRespCode::KeyExchange => {
+            | ReqRespCode::KeyExchange
+            | ReqRespCode::PskExchange => {
                 Err(self.generate_error_response(req, ErrorCode::UnexpectedRequest, 0, None))
             }
 
@@ -478,6 +712,12 @@ impl<'a> SpdmContext<'a> {
             ReqRespCode::GetDigests
             | ReqRespCode::GetCertificate
             | ReqRespCode::GetMeasurements
+            | ReqRespCode::KeyUpdate
+            | ReqRespCode::Heartbeat
+            | ReqRespCode::GetCsr
+            | ReqRespCode::SetCertificate
+            | ReqRespCode::GetEndpointInfo
+            | ReqRespCode::GetKeyPairInfo
             | ReqRespCode::EndSession => {
                 if session_info.session_state == SessionState::Established {
                     Ok(())
@@ -486,9 +726,24 @@ impl<'a> SpdmContext<'a> {
                 }
             }
 
-            // FINISH requires handshake in progress state (Session Handshake phase)
-            ReqRespCode::Finish => {
-                if session_info.session_state == SessionState::HandshakeInProgress {
+            // FINISH and PSK_FINISH require handshake in progress state (Session Handshake phase)
+            // of a session created by KEY_EXCHANGE and PSK_EXCHANGE respectively
+            ReqRespCode::Finish | ReqRespCode::PskFinish => {
+                if session_info.session_state == SessionState::HandshakeInProgress
+                    && session_info.psk_session == (req_code == ReqRespCode::PskFinish)
+                {
+                    Ok(())
+                } else {
+                    Err(self.generate_error_response(req, ErrorCode::UnexpectedRequest, 0, None))
+                }
+            }
+
+            // Encapsulated requests are only used for mutual authentication during the
+            // Session Handshake phase of a session created by KEY_EXCHANGE
+            ReqRespCode::GetEncapsulatedRequest | ReqRespCode::DeliverEncapsulatedResponse => {
+                if session_info.session_state == SessionState::HandshakeInProgress
+                    && !session_info.psk_session
+                {
                     Ok(())
                 } else {
                     Err(self.generate_error_response(req, ErrorCode::UnexpectedRequest, 0, None))
@@ -0,0 +1,186 @@
+// Licensed under the Apache-2.0 license
+
+use crate::protocol::SPDM_CERT_CHAIN_METADATA_LEN;
+use caliptra_mcu_libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
+
+#[derive(Debug, PartialEq)]
+pub enum EncapError {
+    /// No encapsulated request flow is in progress for the session
+    NoEncapRequestInProgress,
+    /// The RequestID does not match the outstanding encapsulated request
+    InvalidRequestId,
+    /// The Requester certificate chain does not fit into the buffer
+    CertChainTooLarge,
+    /// The certificate chain portion does not match the requested one
+    InvalidCertChainPortion,
+}
+
+pub type EncapResult<T> = Result<T, EncapError>;
+
+/// Progress of the encapsulated request flow
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum EncapState {
+    /// No encapsulated request flow in progress
+    Idle,
+    /// Mutual authentication requested, waiting for GET_ENCAPSULATED_REQUEST
+    Pending,
+    /// Encapsulated GET_DIGESTS sent, waiting for DIGESTS
+    WaitDigests,
+    /// Encapsulated GET_CERTIFICATE sent, waiting for CERTIFICATE
+    WaitCertificate,
+}
+
+/// Manages the encapsulated request flow used to retrieve the Requester
+/// certificate chain for session-based mutual authentication.
+pub(crate) struct EncapRequestCtx<'a> {
+    state: EncapState,
+    session_id: u32,
+    request_id: u8,
+    slot_id: u8,
+    cert_chain_digest: [u8; SHA384_HASH_SIZE],
+    cert_chain_buf: &'a mut [u8],
+    cert_chain_len: usize,
+    received_len: usize,
+}
+
+impl Default for EncapRequestCtx<'_> {
+    fn default() -> Self {
+        Self::new(&mut [])
+    }
+}
+
+impl<'a> EncapRequestCtx<'a> {
+    pub(crate) fn new(cert_chain_buf: &'a mut [u8]) -> Self {
+        Self {
+            state: EncapState::Idle,
+            session_id: 0,
+            request_id: 0,
+            slot_id: 0,
+            cert_chain_digest: [0; SHA384_HASH_SIZE],
+            cert_chain_buf,
+            cert_chain_len: 0,
+            received_len: 0,
+        }
+    }
+
+    /// Reset the context to its initial state
+    pub(crate) fn reset(&mut self) {
+        self.state = EncapState::Idle;
+        self.session_id = 0;
+        self.slot_id = 0;
+        self.cert_chain_digest = [0; SHA384_HASH_SIZE];
+        self.cert_chain_len = 0;
+        self.received_len = 0;
+    }
+
+    /// Start the encapsulated request flow of a session.
+    /// Any flow in progress for another session is abandoned.
+    pub(crate) fn start(&mut self, session_id: u32) {
+        self.reset();
+        self.state = EncapState::Pending;
+        self.session_id = session_id;
+    }
+
+    /// Returns the state of the flow if it belongs to the session
+    pub(crate) fn state(&self, session_id: u32) -> EncapResult<EncapState> {
+        if self.state == EncapState::Idle || self.session_id != session_id {
+            Err(EncapError::NoEncapRequestInProgress)?;
+        }
+        Ok(self.state)
+    }
+
+    pub(crate) fn set_state(&mut self, state: EncapState) {
+        self.state = state;
+    }
+
+    /// Allocate the RequestID of the next encapsulated request. RequestID 0 is reserved
+    /// for the ENCAPSULATED_RESPONSE_ACK without a further request.
+    pub(crate) fn next_request_id(&mut self) -> u8 {
+        self.request_id = self.request_id.wrapping_add(1).max(1);
+        self.request_id
+    }
+
+    /// Check the RequestID of a DELIVER_ENCAPSULATED_RESPONSE
+    pub(crate) fn validate_request_id(&self, request_id: u8) -> EncapResult<()> {
+        if request_id != self.request_id {
+            Err(EncapError::InvalidRequestId)?;
+        }
+        Ok(())
+    }
+
+    /// Record the slot and the digest of the Requester certificate chain to retrieve
+    pub(crate) fn select_cert_chain(
+        &mut self,
+        slot_id: u8,
+        cert_chain_digest: &[u8; SHA384_HASH_SIZE],
+    ) {
+        self.slot_id = slot_id;
+        self.cert_chain_digest = *cert_chain_digest;
+        self.cert_chain_len = 0;
+        self.received_len = 0;
+    }
+
+    pub(crate) fn slot_id(&self) -> u8 {
+        self.slot_id
+    }
+
+    pub(crate) fn cert_chain_digest(&self) -> &[u8; SHA384_HASH_SIZE] {
+        &self.cert_chain_digest
+    }
+
+    /// Returns the offset of the next portion to request
+    pub(crate) fn received_len(&self) -> usize {
+        self.received_len
+    }
+
+    /// Returns the length of the portion to request from the remaining certificate chain
+    pub(crate) fn next_portion_len(&self, max_portion_len: usize) -> usize {
+        let rem_len = if self.cert_chain_len == 0 {
+            self.cert_chain_buf.len()
+        } else {
+            self.cert_chain_len - self.received_len
+        };
+        rem_len.min(max_portion_len)
+    }
+
+    /// Append a portion of the Requester certificate chain.
+    ///
+    /// # Arguments
+    /// * `portion` - The certificate chain portion of the CERTIFICATE response.
+    /// * `remainder_len` - The RemainderLength of the CERTIFICATE response.
+    ///
+    /// # Returns
+    /// * `bool` - True if the complete certificate chain was received.
+    pub(crate) fn append_cert_chain_portion(
+        &mut self,
+        portion: &[u8],
+        remainder_len: usize,
+    ) -> EncapResult<bool> {
+        let total_len = self.received_len + portion.len() + remainder_len;
+        if total_len > self.cert_chain_buf.len() {
+            Err(EncapError::CertChainTooLarge)?;
+        }
+
+        // The total length must not change between portions
+        if portion.is_empty() || (self.cert_chain_len != 0 && total_len != self.cert_chain_len) {
+            Err(EncapError::InvalidCertChainPortion)?;
+        }
+
+        self.cert_chain_buf[self.received_len..self.received_len + portion.len()]
+            .copy_from_slice(portion);
+        self.received_len += portion.len();
+        self.cert_chain_len = total_len;
+
+        Ok(remainder_len == 0)
+    }
+
+    /// Returns the received certificate chain in SPDM format
+    pub(crate) fn cert_chain(&self) -> EncapResult<&[u8]> {
+        if self.cert_chain_len < SPDM_CERT_CHAIN_METADATA_LEN
+            || self.received_len != self.cert_chain_len
+        {
+            Err(EncapError::InvalidCertChainPortion)?;
+        }
+        Ok(&self.cert_chain_buf[..self.cert_chain_len])
+    }
+}
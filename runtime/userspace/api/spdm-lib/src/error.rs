@@ -4,9 +4,11 @@ use crate::cert_store::CertStoreError;
 use crate::chunk_ctx::ChunkError;
 use crate::codec::CodecError;
 use crate::commands::error_rsp::ErrorCode;
+use crate::encap_ctx::EncapError;
 use crate::measurements::MeasurementsError;
 use crate::protocol::opaque_data::OpaqueDataError;
 use crate::protocol::SignCtxError;
+use crate::psk_store::PskStoreError;
 use crate::session::SessionError;
 use crate::transcript::TranscriptError;
 use crate::transport::common::TransportError;
@@ -46,7 +48,9 @@ pub enum CommandError {
     InvalidChunkContext,
     MissingVdmHandler,
     Chunk(ChunkError),
+    Encap(EncapError),
     CertStore(CertStoreError),
+    PskStore(PskStoreError),
     CaliptraApi(CaliptraApiError),
     Transcript(TranscriptError),
     Measurement(MeasurementsError),
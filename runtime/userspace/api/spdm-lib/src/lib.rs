@@ -24,6 +24,12 @@ pub mod transport;
 // Device certificate management
 pub mod cert_store;
 
+// Pre-shared key management
+pub mod psk_store;
+
+// Requester trust anchors for mutual authentication
+pub mod trust_anchor_store;
+
 // Transcript management
 pub mod transcript;
 
@@ -33,6 +39,9 @@ pub mod measurements;
 // Chunking context for large messages
 pub mod chunk_ctx;
 
+// Encapsulated request context for mutual authentication
+pub mod encap_ctx;
+
 // Secure session management
 pub mod session;
 
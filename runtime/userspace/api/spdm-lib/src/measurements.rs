@@ -63,6 +63,35 @@ pub trait SpdmMeasurementValue {
     ) -> MeasurementsResult<usize>;
 }
 
+#[async_trait]
+pub trait SpdmMelSource {
+    /// Takes a snapshot of the Measurement Extension Log (MEL). Called when a Requester
+    /// starts reading the log at offset 0, so that all the portions read in one
+    /// transfer of the log are consistent.
+    async fn refresh(&mut self) -> MeasurementsResult<()>;
+
+    /// Returns the size of the Measurement Extension Log snapshot in the format of the
+    /// negotiated MEL specification.
+    ///
+    /// # Returns
+    /// The size of the MEL in bytes.
+    async fn mel_size(&mut self) -> MeasurementsResult<usize>;
+
+    /// Reads a portion of the Measurement Extension Log.
+    ///
+    /// # Arguments
+    /// * `offset` - The offset in the MEL to start reading from.
+    /// * `mel_portion` - The buffer to store the MEL portion.
+    ///
+    /// # Returns
+    /// The number of bytes read.
+    async fn read_mel(
+        &mut self,
+        offset: usize,
+        mel_portion: &mut [u8],
+    ) -> MeasurementsResult<usize>;
+}
+
 /// Information about each measurement value
 #[derive(Clone)]
 pub struct MeasurementValueInfo {
@@ -142,6 +171,7 @@ impl MeasurementValueInfo {
 pub struct SpdmMeasurements<'a> {
     meas_value_info: &'a [MeasurementValueInfo],
     meas_value: &'a mut dyn SpdmMeasurementValue,
+    mel_source: Option<&'a mut dyn SpdmMelSource>,
     nonce: Option<[u8; SPDM_NONCE_LEN]>,
     asym_algo: Option<AsymAlgo>,
     spdm_version: Option<SpdmVersion>,
@@ -223,6 +253,7 @@ impl<'a> SpdmMeasurements<'a> {
         SpdmMeasurements {
             meas_value_info,
             meas_value,
+            mel_source: None,
             nonce: None,
             asym_algo: None,
             spdm_version: None,
@@ -230,6 +261,59 @@ impl<'a> SpdmMeasurements<'a> {
         }
     }
 
+    /// Sets the source of the Measurement Extension Log returned by
+    /// GET_MEASUREMENT_EXTENSION_LOG. The log is only served if MEL_CAP is set
+    /// in the local capabilities.
+    ///
+    /// # Arguments
+    /// * `mel_source` - A mutable reference to a type that implements the `SpdmMelSource` trait.
+    pub fn set_mel_source(&mut self, mel_source: &'a mut dyn SpdmMelSource) {
+        self.mel_source = Some(mel_source);
+    }
+
+    /// Returns true if a Measurement Extension Log source is available.
+    pub(crate) fn mel_supported(&self) -> bool {
+        self.mel_source.is_some()
+    }
+
+    /// Takes a new snapshot of the Measurement Extension Log.
+    pub(crate) async fn refresh_mel(&mut self) -> MeasurementsResult<()> {
+        let mel_source = self
+            .mel_source
+            .as_mut()
+            .ok_or(MeasurementsError::InvalidOperation)?;
+        mel_source.refresh().await
+    }
+
+    /// Returns the size of the Measurement Extension Log.
+    pub(crate) async fn mel_size(&mut self) -> MeasurementsResult<usize> {
+        let mel_source = self
+            .mel_source
+            .as_mut()
+            .ok_or(MeasurementsError::InvalidOperation)?;
+        mel_source.mel_size().await
+    }
+
+    /// Reads a portion of the Measurement Extension Log.
+    ///
+    /// # Arguments
+    /// * `offset` - The offset in the MEL to start reading from.
+    /// * `mel_portion` - The buffer to store the MEL portion.
+    ///
+    /// # Returns
+    /// The number of bytes read.
+    pub(crate) async fn mel_portion(
+        &mut self,
+        offset: usize,
+        mel_portion: &mut [u8],
+    ) -> MeasurementsResult<usize> {
+        let mel_source = self
+            .mel_source
+            .as_mut()
+            .ok_or(MeasurementsError::InvalidOperation)?;
+        mel_source.read_mel(offset, mel_portion).await
+    }
+
     /// Sets the nonce to be included in the measurement value.
     pub(crate) fn set_nonce(&mut self, nonce: [u8; SPDM_NONCE_LEN]) {
         self.nonce = Some(nonce);
@@ -203,8 +203,10 @@ fn select_secure_version(
 ) -> OpaqueDataResult<SmVersion> {
     let mut max_version: Option<SmVersion> = None;
     for version in &sec_msg_versions.versions[..sec_msg_versions.version_count as usize] {
-        let ver: SpdmVersion = SpdmVersion::new(version.major_version(), version.minor_version())
-            .map_err(|_| OpaqueDataError::InvalidFormat)?;
+        // Versions unknown to the Responder are skipped
+        let Ok(ver) = SpdmVersion::new(version.major_version(), version.minor_version()) else {
+            continue;
+        };
         if local_sec_msg_version_list.contains(&ver) {
             if let Some(current_max) = max_version {
                 if version.minor_version() > current_max.minor_version() {
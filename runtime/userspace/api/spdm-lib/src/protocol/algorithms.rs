@@ -226,6 +226,36 @@ pub enum BaseAsymAlgoType {
     EddsaEd448,
 }
 
+// PQC Asymmetric Algorithm field (SPDM 1.4)
+bitfield! {
+#[derive(FromBytes, IntoBytes, Immutable, Default, Clone, Copy)]
+#[repr(C)]
+pub struct PqcAsymAlgo(u32);
+impl Debug;
+u8;
+pub ml_dsa_44, set_ml_dsa_44: 0,0;
+pub ml_dsa_65, set_ml_dsa_65: 1,1;
+pub ml_dsa_87, set_ml_dsa_87: 2,2;
+reserved, _: 31,3;
+}
+
+impl From<PqcAsymAlgoType> for u32 {
+    fn from(pqc_asym_algo_type: PqcAsymAlgoType) -> u32 {
+        match pqc_asym_algo_type {
+            PqcAsymAlgoType::MlDsa44 => PqcAsymAlgo(1 << 0).0,
+            PqcAsymAlgoType::MlDsa65 => PqcAsymAlgo(1 << 1).0,
+            PqcAsymAlgoType::MlDsa87 => PqcAsymAlgo(1 << 2).0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum PqcAsymAlgoType {
+    MlDsa44,
+    MlDsa65,
+    MlDsa87,
+}
+
 // Base Hash Algorithm field
 bitfield! {
 #[derive(FromBytes, IntoBytes, Immutable, Default, Clone, Copy)]
@@ -531,6 +561,7 @@ pub struct DeviceAlgorithms {
     pub measurement_hash_algo: MeasurementHashAlgo,
     pub base_asym_algo: BaseAsymAlgo,
     pub base_hash_algo: BaseHashAlgo,
+    pub pqc_asym_algo: PqcAsymAlgo,
     pub mel_specification: MelSpecification,
     pub dhe_group: DheNamedGroup,
     pub aead_cipher_suite: AeadCipherSuite,
@@ -554,6 +585,8 @@ impl Default for DeviceAlgorithms {
         let mut base_hash_algo = BaseHashAlgo::default();
         base_hash_algo.set_tpm_alg_sha_384(1);
 
+        let pqc_asym_algo = PqcAsymAlgo::default();
+
         let mut mel_specification = MelSpecification::default();
         mel_specification.set_dmtf_mel_spec(1);
 
@@ -568,6 +601,7 @@ impl Default for DeviceAlgorithms {
             measurement_hash_algo,
             base_asym_algo,
             base_hash_algo,
+            pqc_asym_algo,
             mel_specification,
             dhe_group,
             aead_cipher_suite,
@@ -613,6 +647,18 @@ impl DeviceAlgorithms {
         self.key_schedule = key_schedule;
     }
 
+    pub fn set_req_base_asym_algo(&mut self) {
+        let mut req_base_asym_algo = ReqBaseAsymAlg::default();
+        req_base_asym_algo.set_tpm_alg_ecdsa_ecc_nist_p384(1);
+        self.req_base_asym_algo = req_base_asym_algo;
+    }
+
+    pub fn set_pqc_asym_algo(&mut self) {
+        let mut pqc_asym_algo = PqcAsymAlgo::default();
+        pqc_asym_algo.set_ml_dsa_87(1);
+        self.pqc_asym_algo = pqc_asym_algo;
+    }
+
     pub fn set_other_param_support(&mut self) {
         let mut other_param = OtherParamSupport::default();
         other_param.set_opaque_data_fmt1(1);
@@ -627,6 +673,7 @@ pub struct AlgorithmPriorityTable<'a> {
     pub opaque_data_format: Option<&'a [OpaqueDataFormatType]>,
     pub base_asym_algo: Option<&'a [BaseAsymAlgoType]>,
     pub base_hash_algo: Option<&'a [BaseHashAlgoType]>,
+    pub pqc_asym_algo: Option<&'a [PqcAsymAlgoType]>,
     pub mel_specification: Option<&'a [MelSpecificationType]>,
     pub dhe_group: Option<&'a [DheGroupType]>,
     pub aead_cipher_suite: Option<&'a [AeadCipherSuiteType]>,
@@ -648,6 +695,7 @@ impl Default for LocalDeviceAlgorithms<'_> {
                 opaque_data_format: None,
                 base_asym_algo: None,
                 base_hash_algo: Some(HASH_PRIORITY_TABLE),
+                pqc_asym_algo: None,
                 mel_specification: None,
                 dhe_group: None,
                 aead_cipher_suite: None,
@@ -667,6 +715,7 @@ impl LocalDeviceAlgorithms<'_> {
                 opaque_data_format: None,
                 base_asym_algo: None,
                 base_hash_algo: Some(HASH_PRIORITY_TABLE),
+                pqc_asym_algo: None,
                 mel_specification: None,
                 dhe_group: None,
                 aead_cipher_suite: None,
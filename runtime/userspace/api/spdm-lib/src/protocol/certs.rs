@@ -1,4 +1,6 @@
 // Licensed under the Apache-2.0 license
+use crate::codec::CommonCodec;
+use crate::error::SpdmError;
 use bitfield::bitfield;
 use caliptra_mcu_libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
 use zerocopy::{FromBytes, Immutable, IntoBytes};
@@ -14,6 +16,8 @@ pub(crate) struct SpdmCertChainHeader {
     pub root_hash: [u8; SHA384_HASH_SIZE],
 }
 
+impl CommonCodec for SpdmCertChainHeader {}
+
 impl Default for SpdmCertChainHeader {
     fn default() -> Self {
         Self {
@@ -31,8 +35,35 @@ bitfield! {
 pub struct CertificateInfo(u8);
 impl Debug;
 u8;
-pub cert_model, set_cert_model: 0,2;
-reserved, _: 3,7;
+pub cert_model, set_cert_model: 2,0;
+reserved, _: 7,3;
+}
+
+/// SPDM certificate model of a certificate slot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CertModel {
+    /// The slot is empty or the certificate model is not reported
+    None = 0,
+    /// The leaf certificate is the DeviceCert
+    DeviceCert = 1,
+    /// The leaf certificate is the AliasCert
+    AliasCert = 2,
+    /// The leaf certificate is a GenericCert
+    GenericCert = 3,
+}
+
+impl TryFrom<u8> for CertModel {
+    type Error = SpdmError;
+
+    fn try_from(value: u8) -> Result<Self, SpdmError> {
+        match value {
+            0 => Ok(CertModel::None),
+            1 => Ok(CertModel::DeviceCert),
+            2 => Ok(CertModel::AliasCert),
+            3 => Ok(CertModel::GenericCert),
+            _ => Err(SpdmError::InvalidParam),
+        }
+    }
 }
 
 // SPDM KeyUsageMask fields
@@ -50,3 +81,59 @@ reserved, _: 13,4;
 pub standards_key_usage, set_standards_key_usage: 14,14;
 pub vendor_key_usage, set_vendor_key_usage: 15,15;
 }
+
+// SPDM KeyPairInfo Capabilities fields
+bitfield! {
+#[derive(FromBytes, IntoBytes, Immutable, Default, Clone, Copy)]
+#[repr(C)]
+pub struct KeyPairCapabilities(u16);
+impl Debug;
+u16;
+pub gen_key_cap, set_gen_key_cap: 0,0;
+pub erasable_cap, set_erasable_cap: 1,1;
+pub cert_assoc_cap, set_cert_assoc_cap: 2,2;
+pub key_usage_cap, set_key_usage_cap: 3,3;
+pub asym_algo_cap, set_asym_algo_cap: 4,4;
+pub shareable_cap, set_shareable_cap: 5,5;
+reserved, _: 15,6;
+}
+
+// SPDM KeyPairInfo asymmetric algorithm fields
+bitfield! {
+#[derive(FromBytes, IntoBytes, Immutable, Default, Clone, Copy)]
+#[repr(C)]
+pub struct KeyPairAsymAlgo(u32);
+impl Debug;
+u8;
+pub rsa2048, set_rsa2048: 0,0;
+pub rsa3072, set_rsa3072: 1,1;
+pub rsa4096, set_rsa4096: 2,2;
+pub ecc256, set_ecc256: 3,3;
+pub ecc384, set_ecc384: 4,4;
+pub ecc521, set_ecc521: 5,5;
+pub sm2, set_sm2: 6,6;
+pub ed25519, set_ed25519: 7,7;
+pub ed448, set_ed448: 8,8;
+reserved, _: 31,9;
+}
+
+/// Information about a key pair of the Responder as reported in KEY_PAIR_INFO
+#[derive(Debug, Default, Clone, Copy)]
+pub struct KeyPairInfo {
+    /// Capabilities of the key pair
+    pub capabilities: KeyPairCapabilities,
+    /// Key usages the key pair can be configured for
+    pub key_usage_capabilities: KeyUsageMask,
+    /// Key usages the key pair is currently configured for
+    pub current_key_usage: KeyUsageMask,
+    /// Asymmetric algorithms the key pair can be configured for
+    pub asym_algo_capabilities: KeyPairAsymAlgo,
+    /// Asymmetric algorithm of the key pair
+    pub current_asym_algo: KeyPairAsymAlgo,
+    /// PQC asymmetric algorithms the key pair can be configured for (SPDM 1.4+)
+    pub pqc_asym_algo_capabilities: u32,
+    /// PQC asymmetric algorithm of the key pair (SPDM 1.4+)
+    pub current_pqc_asym_algo: u32,
+    /// Bitmask of the certificate slots associated with the key pair
+    pub assoc_cert_slot_mask: u8,
+}
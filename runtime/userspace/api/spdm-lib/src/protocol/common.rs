@@ -8,7 +8,7 @@ use zerocopy::{FromBytes, Immutable, IntoBytes};
 pub const SPDM_NONCE_LEN: usize = 32;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
-pub(crate) enum ReqRespCode {
+pub enum ReqRespCode {
     GetVersion = 0x84,
     Version = 0x04,
     GetCapabilities = 0xE1,
@@ -23,14 +23,38 @@ pub(crate) enum ReqRespCode {
     ChallengeAuth = 0x03,
     GetMeasurements = 0xE0,
     Measurements = 0x60,
+    ChunkSend = 0x85,
+    ChunkSendAck = 0x05,
     ChunkGet = 0x86,
     ChunkResponse = 0x06,
+    GetEndpointInfo = 0x87,
+    EndpointInfo = 0x07,
     KeyExchange = 0xE4,
     KeyExchangeRsp = 0x64,
     Finish = 0xE5,
     FinishRsp = 0x65,
+    PskExchange = 0xE6,
+    PskExchangeRsp = 0x66,
+    PskFinish = 0xE7,
+    PskFinishRsp = 0x67,
+    Heartbeat = 0xE8,
+    HeartbeatAck = 0x68,
+    KeyUpdate = 0xE9,
+    KeyUpdateAck = 0x69,
+    GetEncapsulatedRequest = 0xEA,
+    EncapsulatedRequest = 0x6A,
+    DeliverEncapsulatedResponse = 0xEB,
+    EncapsulatedResponseAck = 0x6B,
     EndSession = 0xEC,
     EndSessionAck = 0x6C,
+    GetCsr = 0xED,
+    Csr = 0x6D,
+    SetCertificate = 0xEE,
+    SetCertificateRsp = 0x6E,
+    GetMeasurementExtensionLog = 0xEF,
+    MeasurementExtensionLog = 0x6F,
+    GetKeyPairInfo = 0xFC,
+    KeyPairInfo = 0x7C,
     VendorDefinedRequest = 0xFE,
     VendorDefinedResponse = 0x7E,
     Error = 0x7F,
@@ -54,14 +78,39 @@ impl TryFrom<u8> for ReqRespCode {
             0x03 => Ok(ReqRespCode::ChallengeAuth),
             0xE0 => Ok(ReqRespCode::GetMeasurements),
             0x60 => Ok(ReqRespCode::Measurements),
+            0x85 => Ok(ReqRespCode::ChunkSend),
+            0x05 => Ok(ReqRespCode::ChunkSendAck),
             0x86 => Ok(ReqRespCode::ChunkGet),
             0x06 => Ok(ReqRespCode::ChunkResponse),
+            0x87 => Ok(ReqRespCode::GetEndpointInfo),
+            0x07 => Ok(ReqRespCode::EndpointInfo),
             0x7F => Ok(ReqRespCode::Error),
             0xE4 => Ok(ReqRespCode::KeyExchange),
+            0x64 => Ok(ReqRespCode::KeyExchangeRsp),
             0xE5 => Ok(ReqRespCode::Finish),
             0x65 => Ok(ReqRespCode::FinishRsp),
+            0xE6 => Ok(ReqRespCode::PskExchange),
+            0x66 => Ok(ReqRespCode::PskExchangeRsp),
+            0xE7 => Ok(ReqRespCode::PskFinish),
+            0x67 => Ok(ReqRespCode::PskFinishRsp),
+            0xE8 => Ok(ReqRespCode::Heartbeat),
+            0x68 => Ok(ReqRespCode::HeartbeatAck),
+            0xE9 => Ok(ReqRespCode::KeyUpdate),
+            0x69 => Ok(ReqRespCode::KeyUpdateAck),
+            0xEA => Ok(ReqRespCode::GetEncapsulatedRequest),
+            0x6A => Ok(ReqRespCode::EncapsulatedRequest),
+            0xEB => Ok(ReqRespCode::DeliverEncapsulatedResponse),
+            0x6B => Ok(ReqRespCode::EncapsulatedResponseAck),
             0xEC => Ok(ReqRespCode::EndSession),
             0x6C => Ok(ReqRespCode::EndSessionAck),
+            0xED => Ok(ReqRespCode::GetCsr),
+            0x6D => Ok(ReqRespCode::Csr),
+            0xEE => Ok(ReqRespCode::SetCertificate),
+            0x6E => Ok(ReqRespCode::SetCertificateRsp),
+            0xEF => Ok(ReqRespCode::GetMeasurementExtensionLog),
+            0x6F => Ok(ReqRespCode::MeasurementExtensionLog),
+            0xFC => Ok(ReqRespCode::GetKeyPairInfo),
+            0x7C => Ok(ReqRespCode::KeyPairInfo),
             0xFE => Ok(ReqRespCode::VendorDefinedRequest),
             0x7E => Ok(ReqRespCode::VendorDefinedResponse),
             _ => Err(SpdmError::UnsupportedRequest),
@@ -76,12 +125,14 @@ impl From<ReqRespCode> for u8 {
 }
 
 impl ReqRespCode {
-    pub(crate) fn spdm_context_string(&self) -> SpdmResult<[u8; SPDM_CONTEXT_LEN]> {
+    pub fn spdm_context_string(&self) -> SpdmResult<[u8; SPDM_CONTEXT_LEN]> {
         let mut context = [0u8; SPDM_CONTEXT_LEN];
         let ctx_str = match self {
             ReqRespCode::ChallengeAuth => "responder-challenge_auth signing",
             ReqRespCode::Measurements => "responder-measurements signing",
             ReqRespCode::KeyExchangeRsp => "responder-key_exchange_rsp signing",
+            ReqRespCode::Finish => "requester-finish signing",
+            ReqRespCode::EndpointInfo => "responder-endpoint_info signing",
             _ => return Err(SpdmError::UnsupportedRequest),
         };
 
@@ -97,24 +148,24 @@ impl ReqRespCode {
 
 #[derive(FromBytes, IntoBytes, Immutable)]
 #[repr(C)]
-pub(crate) struct SpdmMsgHdr {
+pub struct SpdmMsgHdr {
     version: u8,
     req_resp_code: u8,
 }
 
 impl SpdmMsgHdr {
-    pub(crate) fn new(version: SpdmVersion, req_resp_code: ReqRespCode) -> Self {
+    pub fn new(version: SpdmVersion, req_resp_code: ReqRespCode) -> Self {
         Self {
             version: version.into(),
             req_resp_code: req_resp_code.into(),
         }
     }
 
-    pub(crate) fn version(&self) -> SpdmResult<SpdmVersion> {
+    pub fn version(&self) -> SpdmResult<SpdmVersion> {
         self.version.try_into()
     }
 
-    pub(crate) fn req_resp_code(&self) -> SpdmResult<ReqRespCode> {
+    pub fn req_resp_code(&self) -> SpdmResult<ReqRespCode> {
         self.req_resp_code.try_into()
     }
 }
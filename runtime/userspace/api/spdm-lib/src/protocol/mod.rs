@@ -3,7 +3,7 @@
 pub mod algorithms;
 pub mod capabilities;
 pub mod certs;
-pub(crate) mod common;
+pub mod common;
 pub mod measurements;
 pub(crate) mod opaque_data;
 pub mod signature;
@@ -13,7 +13,7 @@ pub mod version;
 pub use algorithms::*;
 pub use capabilities::*;
 pub use certs::*;
-pub(crate) use common::*;
+pub use common::*;
 pub use measurements::*;
 pub(crate) use opaque_data::*;
 pub use signature::*;
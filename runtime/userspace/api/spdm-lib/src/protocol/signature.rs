@@ -2,7 +2,7 @@
 
 use crate::protocol::*;
 use caliptra_mcu_libapi_caliptra::crypto::hash::{HashAlgoType, HashContext, SHA384_HASH_SIZE};
-use caliptra_mcu_libapi_caliptra::error::CaliptraApiError;
+use caliptra_mcu_libapi_caliptra::error::{CaliptraApiError, CaliptraApiResult};
 
 pub const REQUESTER_CONTEXT_LEN: usize = 8;
 
@@ -22,7 +22,9 @@ pub enum SignCtxError {
 
 pub type SignatureCtxResult<T> = Result<T, SignCtxError>;
 
-pub(crate) fn create_responder_signing_context(
+/// Builds the combined SPDM prefix and context string that is hashed together with
+/// the transcript hash to form the data to be signed (SPDM 1.2 and later).
+pub fn create_responder_signing_context(
     spdm_version: SpdmVersion,
     opcode: ReqRespCode,
 ) -> SignatureCtxResult<[u8; SPDM_SIGNING_CONTEXT_LEN]> {
@@ -56,33 +58,57 @@ pub(crate) fn create_responder_signing_context(
     Ok(combined_spdm_prefix)
 }
 
-pub(crate) async fn get_tbs_via_response_code(
-    spdm_version: SpdmVersion,
-    resp_code: ReqRespCode,
-    transcript_hash: [u8; SHA384_HASH_SIZE],
-) -> SignatureCtxResult<[u8; SHA384_HASH_SIZE]> {
-    if spdm_version < SpdmVersion::V12 {
-        return Ok(transcript_hash);
-    }
-    let signing_context = create_responder_signing_context(spdm_version, resp_code)?;
+/// Message signed by the Responder for a response: the combined SPDM prefix followed
+/// by the transcript hash (SPDM 1.2 and later), or only the transcript hash before
+/// SPDM 1.2.
+pub struct SigningMessage {
+    message: [u8; SPDM_SIGNING_CONTEXT_LEN + SHA384_HASH_SIZE],
+    len: usize,
+}
 
-    // Create the TBS (To-Be-Signed) message
-    let mut tbs = [0u8; SHA384_HASH_SIZE];
+impl SigningMessage {
+    pub fn new(
+        spdm_version: SpdmVersion,
+        resp_code: ReqRespCode,
+        transcript_hash: [u8; SHA384_HASH_SIZE],
+    ) -> SignatureCtxResult<Self> {
+        let mut message = [0u8; SPDM_SIGNING_CONTEXT_LEN + SHA384_HASH_SIZE];
+        if spdm_version < SpdmVersion::V12 {
+            message[..SHA384_HASH_SIZE].copy_from_slice(&transcript_hash);
+            return Ok(Self {
+                message,
+                len: SHA384_HASH_SIZE,
+            });
+        }
 
-    let mut message = [0u8; SPDM_SIGNING_CONTEXT_LEN + SHA384_HASH_SIZE];
-    message[..SPDM_SIGNING_CONTEXT_LEN].copy_from_slice(&signing_context);
-    message[SPDM_SIGNING_CONTEXT_LEN..].copy_from_slice(&transcript_hash);
+        let signing_context = create_responder_signing_context(spdm_version, resp_code)?;
+        message[..SPDM_SIGNING_CONTEXT_LEN].copy_from_slice(&signing_context);
+        message[SPDM_SIGNING_CONTEXT_LEN..].copy_from_slice(&transcript_hash);
+        Ok(Self {
+            message,
+            len: message.len(),
+        })
+    }
 
-    let mut hash_ctx = HashContext::new();
+    /// The message as signed by algorithms that sign the message itself (ML-DSA-87).
+    pub fn message(&self) -> &[u8] {
+        &self.message[..self.len]
+    }
 
-    hash_ctx
-        .init(HashAlgoType::SHA384, Some(&message))
-        .await
-        .map_err(SignCtxError::CaliptraApi)?;
+    /// The digest of the message as signed by ECDSA. Before SPDM 1.2 this is the
+    /// transcript hash itself.
+    pub async fn digest(&self) -> CaliptraApiResult<[u8; SHA384_HASH_SIZE]> {
+        let mut tbs = [0u8; SHA384_HASH_SIZE];
+        if self.len == SHA384_HASH_SIZE {
+            tbs.copy_from_slice(self.message());
+            return Ok(tbs);
+        }
 
-    hash_ctx
-        .finalize(&mut tbs)
-        .await
-        .map_err(SignCtxError::CaliptraApi)?;
-    Ok(tbs)
+        let mut hash_ctx = HashContext::new();
+        hash_ctx
+            .init(HashAlgoType::SHA384, Some(self.message()))
+            .await?;
+        hash_ctx.finalize(&mut tbs).await?;
+        Ok(tbs)
+    }
 }
@@ -2,8 +2,8 @@
 
 use crate::error::{SpdmError, SpdmResult};
 
-const MAX_NUM_SUPPORTED_SPDM_VERSIONS: usize = 4;
-const MAX_SUPPORTED_VERSION: SpdmVersion = SpdmVersion::V13;
+const MAX_NUM_SUPPORTED_SPDM_VERSIONS: usize = 5;
+const MAX_SUPPORTED_VERSION: SpdmVersion = SpdmVersion::V14;
 
 #[derive(Debug, Default, PartialEq, Clone, Copy, PartialOrd)]
 pub enum SpdmVersion {
@@ -12,6 +12,7 @@ pub enum SpdmVersion {
     V11,
     V12,
     V13,
+    V14,
 }
 
 impl SpdmVersion {
@@ -21,6 +22,7 @@ impl SpdmVersion {
             SpdmVersion::V11 => "1.1.*",
             SpdmVersion::V12 => "1.2.*",
             SpdmVersion::V13 => "1.3.*",
+            SpdmVersion::V14 => "1.4.*",
         }
     }
 }
@@ -33,6 +35,7 @@ impl TryFrom<u8> for SpdmVersion {
             0x11 => Ok(SpdmVersion::V11),
             0x12 => Ok(SpdmVersion::V12),
             0x13 => Ok(SpdmVersion::V13),
+            0x14 => Ok(SpdmVersion::V14),
             _ => Err(SpdmError::UnsupportedVersion),
         }
     }
@@ -56,6 +59,7 @@ impl SpdmVersion {
             SpdmVersion::V11 => 0x11,
             SpdmVersion::V12 => 0x12,
             SpdmVersion::V13 => 0x13,
+            SpdmVersion::V14 => 0x14,
         }
     }
 
@@ -77,5 +81,14 @@ pub(crate) fn validate_supported_versions(supported_versions: &[SpdmVersion]) ->
     {
         Err(SpdmError::InvalidParam)?;
     }
+
+    // Each version must be listed only once
+    if supported_versions
+        .iter()
+        .enumerate()
+        .any(|(i, v)| supported_versions[..i].contains(v))
+    {
+        Err(SpdmError::InvalidParam)?;
+    }
     Ok(())
 }
@@ -0,0 +1,100 @@
+// Licensed under the Apache-2.0 license
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use arrayvec::ArrayVec;
+use async_trait::async_trait;
+use caliptra_api::mailbox::{CmKeyUsage, Cmk};
+use caliptra_mcu_libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
+use caliptra_mcu_libapi_caliptra::crypto::hmac::Hmac;
+use caliptra_mcu_libapi_caliptra::error::CaliptraApiError;
+
+/// Maximum length of the PSKHint field supported by the responder.
+pub const MAX_PSK_HINT_LEN: usize = 16;
+
+// Label prepended to the PSK hint to form the HKDF-Expand info of a derived PSK
+const PSK_DERIVE_LABEL: &[u8] = b"spdm psk";
+
+#[derive(Debug, PartialEq)]
+pub enum PskStoreError {
+    InvalidPskHint,
+    UnknownPskHint,
+    CaliptraApi(CaliptraApiError),
+}
+pub type PskStoreResult<T> = Result<T, PskStoreError>;
+
+#[async_trait]
+pub trait PskStore {
+    /// Get the pre-shared key identified by the PSK hint.
+    /// The returned key is used as the input keying material of the
+    /// SPDM key schedule (Handshake-Secret = HKDF-Extract(Salt_0, PSK)),
+    /// so it must be a Caliptra HMAC key.
+    ///
+    /// # Arguments
+    /// * `psk_hint` - The PSKHint sent by the Requester. May be empty if the
+    ///   Requester and the Responder agreed on the PSK out of band.
+    ///
+    /// # Returns
+    /// * `Cmk` - The pre-shared key or error if the hint is unknown.
+    async fn psk(&self, psk_hint: &[u8]) -> PskStoreResult<Cmk>;
+}
+
+/// A PSK store that derives a distinct PSK for each known hint from a single base key.
+///
+/// PSK = HKDF-Expand(base_key, "spdm psk" || PSKHint, 48)
+pub struct HkdfPskStore<'a> {
+    base_key: Cmk,
+    psk_hints: &'a [&'a [u8]],
+}
+
+impl<'a> HkdfPskStore<'a> {
+    /// Create a new PSK store.
+    ///
+    /// # Arguments
+    /// * `base_key` - The HKDF pseudorandom key to derive the PSKs from.
+    /// * `psk_hints` - The PSK hints accepted by the Responder.
+    pub fn new(base_key: Cmk, psk_hints: &'a [&'a [u8]]) -> Self {
+        Self {
+            base_key,
+            psk_hints,
+        }
+    }
+}
+
+#[async_trait]
+impl PskStore for HkdfPskStore<'_> {
+    async fn psk(&self, psk_hint: &[u8]) -> PskStoreResult<Cmk> {
+        if !self.psk_hints.iter().any(|hint| *hint == psk_hint) {
+            Err(PskStoreError::UnknownPskHint)?;
+        }
+        derive_psk(&self.base_key, psk_hint).await
+    }
+}
+
+/// Derive a PSK from a base key and a PSK hint using Caliptra HKDF-Expand.
+///
+/// # Arguments
+/// * `base_key` - The HKDF pseudorandom key to derive the PSK from.
+/// * `psk_hint` - The PSK hint to bind the derived key to.
+///
+/// # Returns
+/// * `Cmk` - The derived PSK.
+pub async fn derive_psk(base_key: &Cmk, psk_hint: &[u8]) -> PskStoreResult<Cmk> {
+    let mut info = ArrayVec::<u8, { PSK_DERIVE_LABEL.len() + MAX_PSK_HINT_LEN }>::new();
+    info.try_extend_from_slice(PSK_DERIVE_LABEL)
+        .map_err(|_| PskStoreError::InvalidPskHint)?;
+    info.try_extend_from_slice(psk_hint)
+        .map_err(|_| PskStoreError::InvalidPskHint)?;
+
+    let expand_rsp = Hmac::hkdf_expand(
+        base_key,
+        CmKeyUsage::Hmac,
+        SHA384_HASH_SIZE as u32,
+        info.as_slice(),
+    )
+    .await
+    .map_err(PskStoreError::CaliptraApi)?;
+
+    Ok(expand_rsp.okm)
+}
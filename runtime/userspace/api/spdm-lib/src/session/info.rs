@@ -3,7 +3,9 @@
 use super::{KeySchedule, SessionError, SessionKeyType, SessionResult};
 use crate::protocol::SpdmVersion;
 use crate::transcript::SessionTranscript;
+use crate::trust_anchor_store::EccP384PublicKey;
 use bitfield::bitfield;
+use caliptra_api::mailbox::Cmk;
 use caliptra_mcu_libapi_caliptra::crypto::aes_gcm::Aes256GcmTag;
 use caliptra_mcu_libapi_caliptra::crypto::asym::ecdh::CMB_ECDH_EXCHANGE_DATA_MAX_SIZE;
 use caliptra_mcu_libapi_caliptra::crypto::asym::AsymAlgo;
@@ -37,6 +39,26 @@ pub enum SessionType {
     MacAndEncrypt,
 }
 
+/// Identity of the Requester retrieved with encapsulated requests
+/// for session-based mutual authentication.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RequesterIdentity {
+    pub(crate) slot_id: u8,
+    pub(crate) cert_chain_hash: [u8; SHA384_HASH_SIZE],
+    pub(crate) public_key: EccP384PublicKey,
+}
+
+/// KEY_UPDATE progress of a session.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) struct KeyUpdateState {
+    /// Operation and tag of the last KEY_UPDATE that updated the keys
+    pub(crate) last_update: Option<(u8, u8)>,
+    /// The response direction key is updated once KEY_UPDATE_ACK is encrypted
+    pub(crate) response_key_update_pending: bool,
+    /// The request being processed was decrypted with the previous request direction key
+    pub(crate) previous_key_in_use: bool,
+}
+
 #[allow(dead_code)]
 pub(crate) struct SessionInfo {
     pub(crate) session_id: u32,
@@ -47,6 +69,13 @@ pub(crate) struct SessionInfo {
     pub(crate) asym_algo: AsymAlgo, // Asymmetric algorithm negotiated for this session
     key_schedule_ctx: KeySchedule,  // Key schedule context for this session
     pub(crate) session_transcript: SessionTranscript,
+    pub(crate) heartbeat_period: u8, // Heartbeat period in seconds, 0 if disabled
+    last_activity_ms: Option<u64>,   // Timestamp of the last message received in this session
+    pub(crate) last_used: u64,       // Least recently used stamp for session eviction
+    pub(crate) psk_session: bool,    // Session created by PSK_EXCHANGE instead of KEY_EXCHANGE
+    pub(crate) mut_auth_requested: bool, // Mutual authentication requested in KEY_EXCHANGE_RSP
+    pub(crate) requester_identity: Option<RequesterIdentity>, // Verified Requester identity
+    pub(crate) key_update: KeyUpdateState, // KEY_UPDATE progress
 }
 
 impl SessionInfo {
@@ -59,6 +88,13 @@ impl SessionInfo {
             asym_algo: AsymAlgo::EccP384, // Default to ECC P384
             key_schedule_ctx: KeySchedule::default(),
             session_transcript: SessionTranscript::new(),
+            heartbeat_period: 0,
+            last_activity_ms: None,
+            last_used: 0,
+            psk_session: false,
+            mut_auth_requested: false,
+            requester_identity: None,
+            key_update: KeyUpdateState::default(),
         }
     }
 
@@ -68,12 +104,105 @@ impl SessionInfo {
         session_type: SessionType,
         spdm_version: SpdmVersion,
         asym_algo: AsymAlgo,
+        heartbeat_period: u8,
     ) {
         self.session_policy = session_policy;
         self.session_state = SessionState::HandshakeNotStarted;
         self.session_type = session_type;
         self.key_schedule_ctx.set_spdm_version(spdm_version);
         self.asym_algo = asym_algo;
+        self.heartbeat_period = heartbeat_period;
+    }
+
+    /// Records that a message was received in this session.
+    ///
+    /// # Arguments
+    /// `now_ms` is the current time in milliseconds.
+    pub fn record_activity(&mut self, now_ms: u64) {
+        self.last_activity_ms = Some(now_ms);
+    }
+
+    /// Checks whether the heartbeat timeout has elapsed for this session.
+    /// Per DSP0274, the session is terminated if no message is received
+    /// within twice the heartbeat period.
+    ///
+    /// # Arguments
+    /// `now_ms` is the current time in milliseconds.
+    pub fn is_expired(&self, now_ms: u64) -> bool {
+        if self.heartbeat_period == 0 {
+            return false;
+        }
+
+        match self.last_activity_ms {
+            Some(last_ms) => {
+                let timeout_ms = 2 * 1000 * u64::from(self.heartbeat_period);
+                now_ms.saturating_sub(last_ms) > timeout_ms
+            }
+            None => false,
+        }
+    }
+
+    /// Updates the request direction data key as part of KEY_UPDATE.
+    /// The updated key takes effect with the next request. The replaced key
+    /// is kept until the Requester verifies the new key.
+    pub async fn update_request_data_key(&mut self) -> SessionResult<()> {
+        if self.session_state != SessionState::Established {
+            return Err(SessionError::InvalidState);
+        }
+
+        let data_secret = self
+            .key_schedule_ctx
+            .derive_updated_data_secret(SessionKeyType::RequestDataEncDecKey)
+            .await
+            .map_err(SessionError::KeySchedule)?;
+        self.key_schedule_ctx
+            .install_data_secret(SessionKeyType::RequestDataEncDecKey, data_secret)
+            .map_err(SessionError::KeySchedule)
+    }
+
+    /// Stages the response direction data key update of KEY_UPDATE.
+    /// KEY_UPDATE_ACK is still protected with the current key; the update
+    /// is applied by `apply_response_data_key_update` once it is encrypted.
+    pub fn stage_response_data_key_update(&mut self) {
+        self.key_update.response_key_update_pending = true;
+    }
+
+    /// Applies the staged response direction data key update, if any.
+    pub async fn apply_response_data_key_update(&mut self) -> SessionResult<()> {
+        if !self.key_update.response_key_update_pending {
+            return Ok(());
+        }
+        self.key_update.response_key_update_pending = false;
+
+        let data_secret = self
+            .key_schedule_ctx
+            .derive_updated_data_secret(SessionKeyType::ResponseDataEncDecKey)
+            .await
+            .map_err(SessionError::KeySchedule)?;
+        self.key_schedule_ctx
+            .install_data_secret(SessionKeyType::ResponseDataEncDecKey, data_secret)
+            .map_err(SessionError::KeySchedule)
+    }
+
+    /// Discards the keys replaced by KEY_UPDATE once the Requester has verified the new keys.
+    pub fn verify_new_data_keys(&mut self) {
+        self.key_schedule_ctx.discard_previous_data_secrets();
+        self.key_update.last_update = None;
+    }
+
+    /// Returns the key protecting the response to the request being processed.
+    /// A retried KEY_UPDATE, protected with the previous request direction key,
+    /// is answered with the previous response direction key if it was updated as well.
+    fn response_data_key_type(&self) -> SessionKeyType {
+        if self.key_update.previous_key_in_use
+            && self
+                .key_schedule_ctx
+                .has_previous_data_secret(SessionKeyType::PreviousResponseDataEncDecKey)
+        {
+            SessionKeyType::PreviousResponseDataEncDecKey
+        } else {
+            SessionKeyType::ResponseDataEncDecKey
+        }
     }
 
     /// Sets the session state
@@ -101,6 +230,15 @@ impl SessionInfo {
             .map_err(SessionError::KeySchedule)
     }
 
+    /// Sets the pre-shared key of the session and marks it as a PSK session.
+    ///
+    /// # Arguments
+    /// `psk` is the pre-shared key retrieved from the PSK store.
+    pub fn set_psk(&mut self, psk: Cmk) {
+        self.key_schedule_ctx.set_psk(psk);
+        self.psk_session = true;
+    }
+
     pub async fn generate_session_handshake_key(
         &mut self,
         th1_transcript_hash: &[u8; SHA384_HASH_SIZE],
@@ -143,9 +281,7 @@ impl SessionInfo {
             SessionState::HandshakeInProgress | SessionState::Establishing => {
                 SessionKeyType::ResponseHandshakeEncDecKey
             }
-            SessionState::Established | SessionState::Terminating => {
-                SessionKeyType::ResponseDataEncDecKey
-            }
+            SessionState::Established | SessionState::Terminating => self.response_data_key_type(),
         };
 
         self.key_schedule_ctx
@@ -166,6 +302,8 @@ impl SessionInfo {
         plaintext_message: &mut [u8],
         tag: Aes256GcmTag,
     ) -> SessionResult<usize> {
+        self.key_update.previous_key_in_use = false;
+
         let session_key_type = match self.session_state {
             SessionState::HandshakeNotStarted => return Err(SessionError::InvalidState),
             SessionState::HandshakeInProgress | SessionState::Establishing => {
@@ -176,7 +314,8 @@ impl SessionInfo {
             }
         };
 
-        self.key_schedule_ctx
+        let result = self
+            .key_schedule_ctx
             .decrypt_message(
                 session_key_type,
                 aad_data,
@@ -184,7 +323,123 @@ impl SessionInfo {
                 plaintext_message,
                 tag,
             )
-            .await
-            .map_err(SessionError::KeySchedule)
+            .await;
+
+        // A Requester that did not receive KEY_UPDATE_ACK retries KEY_UPDATE with the
+        // previous request direction key until it has verified the new one.
+        if result.is_err()
+            && session_key_type == SessionKeyType::RequestDataEncDecKey
+            && self
+                .key_schedule_ctx
+                .has_previous_data_secret(SessionKeyType::PreviousRequestDataEncDecKey)
+        {
+            let decrypted_size = self
+                .key_schedule_ctx
+                .decrypt_message(
+                    SessionKeyType::PreviousRequestDataEncDecKey,
+                    aad_data,
+                    encrypted_message,
+                    plaintext_message,
+                    tag,
+                )
+                .await
+                .map_err(SessionError::KeySchedule)?;
+            self.key_update.previous_key_in_use = true;
+            return Ok(decrypted_size);
+        }
+
+        result.map_err(SessionError::KeySchedule)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn established_session() -> SessionInfo {
+        let mut session_info = SessionInfo::new(1);
+        session_info.set_session_state(SessionState::Established);
+        session_info
+            .key_schedule_ctx
+            .set_data_secrets(Cmk::default(), Cmk::default());
+        session_info
+    }
+
+    #[test]
+    fn test_update_all_keys_stages_response_key() {
+        let mut session_info = established_session();
+
+        // The request direction key is updated right away
+        session_info
+            .key_schedule_ctx
+            .install_data_secret(SessionKeyType::RequestDataEncDecKey, Cmk::default())
+            .unwrap();
+        session_info.stage_response_data_key_update();
+
+        // KEY_UPDATE_ACK is still protected with the current response direction key
+        assert!(session_info.key_update.response_key_update_pending);
+        assert_eq!(
+            session_info.response_data_key_type(),
+            SessionKeyType::ResponseDataEncDecKey
+        );
+    }
+
+    #[test]
+    fn test_retried_key_update_uses_previous_keys() {
+        let mut session_info = established_session();
+        session_info
+            .key_schedule_ctx
+            .install_data_secret(SessionKeyType::RequestDataEncDecKey, Cmk::default())
+            .unwrap();
+
+        // UpdateKey: only the request direction key was replaced
+        session_info.key_update.previous_key_in_use = true;
+        assert_eq!(
+            session_info.response_data_key_type(),
+            SessionKeyType::ResponseDataEncDecKey
+        );
+
+        // UpdateAllKeys: the retried KEY_UPDATE_ACK uses the previous response direction key
+        session_info
+            .key_schedule_ctx
+            .install_data_secret(SessionKeyType::ResponseDataEncDecKey, Cmk::default())
+            .unwrap();
+        assert_eq!(
+            session_info.response_data_key_type(),
+            SessionKeyType::PreviousResponseDataEncDecKey
+        );
+    }
+
+    #[test]
+    fn test_verify_new_key_discards_previous_keys() {
+        let mut session_info = established_session();
+        for key_type in [
+            SessionKeyType::RequestDataEncDecKey,
+            SessionKeyType::ResponseDataEncDecKey,
+        ] {
+            session_info
+                .key_schedule_ctx
+                .install_data_secret(key_type, Cmk::default())
+                .unwrap();
+        }
+        session_info.key_update.last_update = Some((2, 1));
+
+        session_info.verify_new_data_keys();
+        assert_eq!(session_info.key_update.last_update, None);
+        for key_type in [
+            SessionKeyType::PreviousRequestDataEncDecKey,
+            SessionKeyType::PreviousResponseDataEncDecKey,
+        ] {
+            assert!(!session_info
+                .key_schedule_ctx
+                .has_previous_data_secret(key_type));
+        }
+
+        // Responses are protected with the new response direction key
+        session_info.key_update.previous_key_in_use = true;
+        assert_eq!(
+            session_info.response_data_key_type(),
+            SessionKeyType::ResponseDataEncDecKey
+        );
     }
 }
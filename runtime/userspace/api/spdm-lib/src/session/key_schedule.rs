@@ -37,6 +37,8 @@ pub enum SessionKeyType {
     ResponseHandshakeEncDecKey,
     RequestDataEncDecKey,
     ResponseDataEncDecKey,
+    PreviousRequestDataEncDecKey,
+    PreviousResponseDataEncDecKey,
 }
 
 #[derive(Default)]
@@ -79,6 +81,15 @@ impl KeySchedule {
         Ok(self_exch_data)
     }
 
+    /// Sets the pre-shared key of a PSK session. The PSK replaces the
+    /// DHE secret as the input keying material of the handshake secret.
+    ///
+    /// # Arguments
+    /// `psk` is the pre-shared key retrieved from the PSK store.
+    pub fn set_psk(&mut self, psk: Cmk) {
+        self.master_secret_ctx.psk = Some(psk);
+    }
+
     pub async fn generate_session_handshake_key(
         &mut self,
         th1_transcript_hash: &[u8],
@@ -100,6 +111,103 @@ impl KeySchedule {
             .await
     }
 
+    /// Derives the updated data secret for one direction as part of KEY_UPDATE.
+    /// The current data secret stays in use until the updated one is installed.
+    ///
+    /// # Arguments
+    /// `session_key_type` is either `RequestDataEncDecKey` or `ResponseDataEncDecKey`.
+    pub async fn derive_updated_data_secret(
+        &self,
+        session_key_type: SessionKeyType,
+    ) -> KeyScheduleResult<Cmk> {
+        let bin_str9 = self.bin_concat(SpdmBinStr::BinStr9, SHA384_HASH_SIZE as u16, None)?;
+
+        let cur_secret = match session_key_type {
+            SessionKeyType::RequestDataEncDecKey => &self.data_secret_ctx.request_data_secret,
+            SessionKeyType::ResponseDataEncDecKey => &self.data_secret_ctx.response_data_secret,
+            _ => Err(KeyScheduleError::InvalidSessionKeyType)?,
+        };
+
+        // Updated-Data-Secret = HKDF-Expand(Current-Data-Secret, bin_str9, Hash.Length)
+        let expand_rsp = Hmac::hkdf_expand(
+            cur_secret
+                .as_ref()
+                .ok_or(KeyScheduleError::DataSecretNotFound)?,
+            CmKeyUsage::Hmac,
+            SHA384_HASH_SIZE as u32,
+            bin_str9.as_slice(),
+        )
+        .await
+        .map_err(KeyScheduleError::CaliptraApi)?;
+
+        Ok(expand_rsp.okm)
+    }
+
+    /// Installs an updated data secret for one direction and resets the sequence
+    /// number of that direction. The replaced data secret and its sequence number
+    /// are kept as the previous key until `discard_previous_data_secrets` is called.
+    ///
+    /// # Arguments
+    /// `session_key_type` is either `RequestDataEncDecKey` or `ResponseDataEncDecKey`.
+    /// `data_secret` is the updated data secret.
+    pub fn install_data_secret(
+        &mut self,
+        session_key_type: SessionKeyType,
+        data_secret: Cmk,
+    ) -> KeyScheduleResult<()> {
+        let ctx = &mut self.data_secret_ctx;
+        let (secret, sequence_num, previous) = match session_key_type {
+            SessionKeyType::RequestDataEncDecKey => (
+                &mut ctx.request_data_secret,
+                &mut ctx.request_sequence_num,
+                &mut ctx.previous_request,
+            ),
+            SessionKeyType::ResponseDataEncDecKey => (
+                &mut ctx.response_data_secret,
+                &mut ctx.response_sequence_num,
+                &mut ctx.previous_response,
+            ),
+            _ => Err(KeyScheduleError::InvalidSessionKeyType)?,
+        };
+
+        let cur_secret = secret.take().ok_or(KeyScheduleError::DataSecretNotFound)?;
+        *previous = Some((cur_secret, *sequence_num));
+        *secret = Some(data_secret);
+
+        // The sequence number of the updated direction restarts from zero
+        *sequence_num = 0;
+
+        Ok(())
+    }
+
+    /// Checks whether the data secret replaced by the last KEY_UPDATE is still available.
+    ///
+    /// # Arguments
+    /// `session_key_type` is either `PreviousRequestDataEncDecKey` or `PreviousResponseDataEncDecKey`.
+    pub fn has_previous_data_secret(&self, session_key_type: SessionKeyType) -> bool {
+        match session_key_type {
+            SessionKeyType::PreviousRequestDataEncDecKey => {
+                self.data_secret_ctx.previous_request.is_some()
+            }
+            SessionKeyType::PreviousResponseDataEncDecKey => {
+                self.data_secret_ctx.previous_response.is_some()
+            }
+            _ => false,
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn set_data_secrets(&mut self, request_data_secret: Cmk, response_data_secret: Cmk) {
+        self.data_secret_ctx.request_data_secret = Some(request_data_secret);
+        self.data_secret_ctx.response_data_secret = Some(response_data_secret);
+    }
+
+    /// Discards the data secrets replaced by KEY_UPDATE once the new keys are verified.
+    pub fn discard_previous_data_secrets(&mut self) {
+        self.data_secret_ctx.previous_request = None;
+        self.data_secret_ctx.previous_response = None;
+    }
+
     pub async fn hmac(
         &self,
         key_type: SessionKeyType,
@@ -204,6 +312,18 @@ impl KeySchedule {
             }
             SessionKeyType::RequestDataEncDecKey => Ok(self.data_secret_ctx.request_sequence_num),
             SessionKeyType::ResponseDataEncDecKey => Ok(self.data_secret_ctx.response_sequence_num),
+            SessionKeyType::PreviousRequestDataEncDecKey => self
+                .data_secret_ctx
+                .previous_request
+                .as_ref()
+                .map(|(_, sequence_num)| *sequence_num)
+                .ok_or(KeyScheduleError::DataSecretNotFound),
+            SessionKeyType::PreviousResponseDataEncDecKey => self
+                .data_secret_ctx
+                .previous_response
+                .as_ref()
+                .map(|(_, sequence_num)| *sequence_num)
+                .ok_or(KeyScheduleError::DataSecretNotFound),
             _ => Err(KeyScheduleError::InvalidSessionKeyType),
         }
     }
@@ -230,6 +350,18 @@ impl KeySchedule {
                 .response_data_secret
                 .clone()
                 .ok_or(KeyScheduleError::DataSecretNotFound),
+            SessionKeyType::PreviousRequestDataEncDecKey => self
+                .data_secret_ctx
+                .previous_request
+                .as_ref()
+                .map(|(secret, _)| secret.clone())
+                .ok_or(KeyScheduleError::DataSecretNotFound),
+            SessionKeyType::PreviousResponseDataEncDecKey => self
+                .data_secret_ctx
+                .previous_response
+                .as_ref()
+                .map(|(secret, _)| secret.clone())
+                .ok_or(KeyScheduleError::DataSecretNotFound),
             _ => Err(KeyScheduleError::InvalidSessionKeyType),
         }
     }
@@ -251,19 +383,41 @@ impl KeySchedule {
             SessionKeyType::ResponseDataEncDecKey => {
                 self.data_secret_ctx.response_sequence_num += 1;
             }
+            SessionKeyType::PreviousRequestDataEncDecKey => {
+                let (_, sequence_num) = self
+                    .data_secret_ctx
+                    .previous_request
+                    .as_mut()
+                    .ok_or(KeyScheduleError::DataSecretNotFound)?;
+                *sequence_num += 1;
+            }
+            SessionKeyType::PreviousResponseDataEncDecKey => {
+                let (_, sequence_num) = self
+                    .data_secret_ctx
+                    .previous_response
+                    .as_mut()
+                    .ok_or(KeyScheduleError::DataSecretNotFound)?;
+                *sequence_num += 1;
+            }
             _ => return Err(KeyScheduleError::InvalidSessionKeyType),
         }
 
         Ok(())
     }
 
-    // Generates the handshake secret using the DHE Secret and Salt_0
+    // Generates the handshake secret using the DHE Secret (or the PSK) and Salt_0
     async fn generate_handshake_secret(&mut self) -> KeyScheduleResult<()> {
         let salt_0 = [0u8; SHA384_HASH_SIZE];
 
-        // Handshake-Secret = HKDF-Extract(Salt_0, DHE-Secret)
-        if let Some(dhe_secret) = &self.master_secret_ctx.dhe_secret {
-            let extract = Hmac::hkdf_extract(HkdfSalt::Data(&salt_0), dhe_secret)
+        let ikm = self
+            .master_secret_ctx
+            .dhe_secret
+            .as_ref()
+            .or(self.master_secret_ctx.psk.as_ref());
+
+        // Handshake-Secret = HKDF-Extract(Salt_0, DHE-Secret or PSK)
+        if let Some(ikm) = ikm {
+            let extract = Hmac::hkdf_extract(HkdfSalt::Data(&salt_0), ikm)
                 .await
                 .map_err(KeyScheduleError::CaliptraApi)?;
 
@@ -504,6 +658,7 @@ impl KeySchedule {
             SpdmVersion::V11 => "spdm1.1 ",
             SpdmVersion::V12 => "spdm1.2 ",
             SpdmVersion::V13 => "spdm1.3 ",
+            SpdmVersion::V14 => "spdm1.4 ",
         }
     }
 }
@@ -512,6 +667,8 @@ impl KeySchedule {
 struct MasterSecretCtx {
     // DHE secret
     dhe_secret: Option<Cmk>,
+    // Pre-shared key
+    psk: Option<Cmk>,
     // Handshake secret
     handshake_secret: Option<Cmk>,
     // Master secret
@@ -544,6 +701,10 @@ struct DataSecretCtx {
     request_sequence_num: u64,
     // Response direction sequence number
     response_sequence_num: u64,
+    // Request direction data secret and sequence number replaced by KEY_UPDATE
+    previous_request: Option<(Cmk, u64)>,
+    // Response direction data secret and sequence number replaced by KEY_UPDATE
+    previous_response: Option<(Cmk, u64)>,
 }
 
 #[allow(dead_code)]
@@ -576,3 +737,101 @@ impl SpdmBinStr {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn cmk(byte: u8) -> Cmk {
+        let mut cmk = Cmk::default();
+        cmk.0.fill(byte);
+        cmk
+    }
+
+    fn established_key_schedule() -> KeySchedule {
+        let mut key_schedule = KeySchedule::default();
+        key_schedule.set_data_secrets(cmk(1), cmk(2));
+        key_schedule.data_secret_ctx.request_sequence_num = 5;
+        key_schedule.data_secret_ctx.response_sequence_num = 7;
+        key_schedule
+    }
+
+    #[test]
+    fn test_install_request_data_secret() {
+        let mut key_schedule = established_key_schedule();
+        key_schedule
+            .install_data_secret(SessionKeyType::RequestDataEncDecKey, cmk(3))
+            .unwrap();
+
+        // The updated key starts from sequence number zero
+        let key_type = SessionKeyType::RequestDataEncDecKey;
+        assert_eq!(key_schedule.get_major_secret(key_type), Ok(cmk(3)));
+        assert_eq!(key_schedule.get_sequence_number(key_type), Ok(0));
+        key_schedule.increment_sequence_number(key_type).unwrap();
+        assert_eq!(key_schedule.get_sequence_number(key_type), Ok(1));
+
+        // The replaced key keeps its sequence number for a retried KEY_UPDATE
+        let key_type = SessionKeyType::PreviousRequestDataEncDecKey;
+        assert!(key_schedule.has_previous_data_secret(key_type));
+        assert_eq!(key_schedule.get_major_secret(key_type), Ok(cmk(1)));
+        assert_eq!(key_schedule.get_sequence_number(key_type), Ok(5));
+        key_schedule.increment_sequence_number(key_type).unwrap();
+        assert_eq!(key_schedule.get_sequence_number(key_type), Ok(6));
+
+        // The response direction is untouched
+        let key_type = SessionKeyType::ResponseDataEncDecKey;
+        assert_eq!(key_schedule.get_major_secret(key_type), Ok(cmk(2)));
+        assert_eq!(key_schedule.get_sequence_number(key_type), Ok(7));
+        assert!(
+            !key_schedule.has_previous_data_secret(SessionKeyType::PreviousResponseDataEncDecKey)
+        );
+    }
+
+    #[test]
+    fn test_install_all_data_secrets() {
+        let mut key_schedule = established_key_schedule();
+        key_schedule
+            .install_data_secret(SessionKeyType::RequestDataEncDecKey, cmk(3))
+            .unwrap();
+        key_schedule
+            .install_data_secret(SessionKeyType::ResponseDataEncDecKey, cmk(4))
+            .unwrap();
+
+        let key_type = SessionKeyType::ResponseDataEncDecKey;
+        assert_eq!(key_schedule.get_major_secret(key_type), Ok(cmk(4)));
+        assert_eq!(key_schedule.get_sequence_number(key_type), Ok(0));
+        let key_type = SessionKeyType::PreviousResponseDataEncDecKey;
+        assert_eq!(key_schedule.get_major_secret(key_type), Ok(cmk(2)));
+        assert_eq!(key_schedule.get_sequence_number(key_type), Ok(7));
+
+        // The previous keys are discarded once the new keys are verified
+        key_schedule.discard_previous_data_secrets();
+        for key_type in [
+            SessionKeyType::PreviousRequestDataEncDecKey,
+            SessionKeyType::PreviousResponseDataEncDecKey,
+        ] {
+            assert!(!key_schedule.has_previous_data_secret(key_type));
+            assert_eq!(
+                key_schedule.get_major_secret(key_type),
+                Err(KeyScheduleError::DataSecretNotFound)
+            );
+        }
+        assert_eq!(
+            key_schedule.get_major_secret(SessionKeyType::RequestDataEncDecKey),
+            Ok(cmk(3))
+        );
+    }
+
+    #[test]
+    fn test_install_data_secret_without_session_keys() {
+        let mut key_schedule = KeySchedule::default();
+        assert_eq!(
+            key_schedule.install_data_secret(SessionKeyType::RequestDataEncDecKey, cmk(3)),
+            Err(KeyScheduleError::DataSecretNotFound)
+        );
+        assert_eq!(
+            key_schedule.install_data_secret(SessionKeyType::RequestFinishedKey, cmk(3)),
+            Err(KeyScheduleError::InvalidSessionKeyType)
+        );
+    }
+}
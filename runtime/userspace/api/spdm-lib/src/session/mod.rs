@@ -13,10 +13,11 @@ pub mod info;
 pub mod key_schedule;
 
 // Re-export main types
-pub(crate) use info::{SessionInfo, SessionPolicy, SessionState, SessionType};
+pub(crate) use info::{
+    KeyUpdateState, RequesterIdentity, SessionInfo, SessionPolicy, SessionState, SessionType,
+};
 pub(crate) use key_schedule::{KeySchedule, KeyScheduleError, SessionKeyType};
 
-pub const MAX_NUM_SESSIONS: usize = 1;
 const MAX_SPDM_AEAD_ASSOCIATED_DATA_SIZE: usize = 16; // Size of the associated data for AEAD
 
 #[derive(Debug, PartialEq)]
@@ -36,40 +37,89 @@ pub enum SessionError {
 
 pub type SessionResult<T> = Result<T, SessionError>;
 
+/// Storage for a single secure session.
+///
+/// The session table of an `SpdmContext` is a caller-provided slice of session slots.
+/// The number of slots is the maximum number of concurrent sessions.
 #[derive(Default)]
-pub(crate) struct SessionManager {
+pub struct SessionSlot(Option<SessionInfo>);
+
+/// Policy applied when a new session is requested while all session slots are in use.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum SessionEvictionPolicy {
+    /// Reject the new session with `SessionLimitExceeded`.
+    #[default]
+    RejectNew,
+    /// Evict the least recently used session that has not completed its handshake.
+    /// Established sessions are never evicted.
+    EvictStaleHandshake,
+    /// Evict the least recently used session, regardless of its state.
+    EvictLeastRecentlyUsed,
+}
+
+pub(crate) struct SessionManager<'a> {
+    // Session of the secured message being processed
     active_session_id: Option<u32>,
-    handshake_phase_session_id: Option<u32>,
-    sessions: [Option<SessionInfo>; MAX_NUM_SESSIONS],
+    // Session of the handshake request being processed when it is sent in the clear
+    clear_request_session_id: Option<u32>,
+    sessions: &'a mut [SessionSlot],
     cur_responder_session_id: u16,
+    eviction_policy: SessionEvictionPolicy,
+    // Monotonic counter used to track the least recently used session
+    use_counter: u64,
 }
 
-impl SessionManager {
-    pub fn new() -> Self {
+impl<'a> SessionManager<'a> {
+    pub fn new(sessions: &'a mut [SessionSlot]) -> Self {
+        sessions.iter_mut().for_each(|slot| slot.0 = None);
         Self {
             active_session_id: None,
-            handshake_phase_session_id: None,
-            sessions: [None; MAX_NUM_SESSIONS],
+            clear_request_session_id: None,
+            sessions,
             cur_responder_session_id: 0,
+            eviction_policy: SessionEvictionPolicy::default(),
+            use_counter: 0,
         }
     }
 
     pub fn reset(&mut self) {
         self.active_session_id = None;
-        self.handshake_phase_session_id = None;
-        self.sessions = [None; MAX_NUM_SESSIONS];
+        self.clear_request_session_id = None;
+        self.sessions.iter_mut().for_each(|slot| slot.0 = None);
         self.cur_responder_session_id = 0;
+        self.use_counter = 0;
+    }
+
+    /// Returns the maximum number of concurrent sessions.
+    pub fn max_sessions(&self) -> usize {
+        self.sessions.len()
+    }
+
+    /// Returns the number of sessions currently in use.
+    pub fn num_sessions(&self) -> usize {
+        self.sessions.iter().filter(|slot| slot.0.is_some()).count()
+    }
+
+    pub fn set_eviction_policy(&mut self, eviction_policy: SessionEvictionPolicy) {
+        self.eviction_policy = eviction_policy;
     }
 
     pub fn generate_session_id(&mut self, requester_session_id: u16) -> (u32, u16) {
-        let rsp_session_id = self.cur_responder_session_id;
-        let session_id = (u32::from(rsp_session_id) << 16) | u32::from(requester_session_id);
-        self.cur_responder_session_id = self.cur_responder_session_id.wrapping_add(1);
-        (session_id, rsp_session_id)
+        // Skip responder session IDs that would collide with a session still in use.
+        // The table can never be full with all 2^16 responder session IDs, so this terminates.
+        loop {
+            let rsp_session_id = self.cur_responder_session_id;
+            let session_id = (u32::from(rsp_session_id) << 16) | u32::from(requester_session_id);
+            self.cur_responder_session_id = self.cur_responder_session_id.wrapping_add(1);
+            if self.session_info(session_id).is_err() {
+                return (session_id, rsp_session_id);
+            }
+        }
     }
 
     pub fn set_active_session_id(&mut self, session_id: u32) {
         self.active_session_id = Some(session_id);
+        self.touch(session_id);
     }
 
     pub fn reset_active_session_id(&mut self) {
@@ -80,35 +130,88 @@ impl SessionManager {
         self.active_session_id
     }
 
-    pub fn handshake_phase_session_id(&self) -> Option<u32> {
-        self.handshake_phase_session_id
+    /// Forgets the session of the previous request before a new request is processed.
+    pub fn reset_request_session_id(&mut self) {
+        self.active_session_id = None;
+        self.clear_request_session_id = None;
     }
 
-    pub fn set_handshake_phase_session_id(&mut self, session_id: u32) {
-        self.handshake_phase_session_id = Some(session_id);
+    /// Associates the handshake request being processed, which is sent in the clear and
+    /// therefore carries no session ID, with the session it creates or completes.
+    pub fn set_clear_request_session_id(&mut self, session_id: u32) {
+        self.clear_request_session_id = Some(session_id);
+        self.touch(session_id);
     }
 
-    pub fn reset_handshake_phase_session_id(&mut self) {
-        self.handshake_phase_session_id = None;
+    /// Returns the session created by KEY_EXCHANGE that is waiting for FINISH.
+    ///
+    /// When the handshake is performed in the clear, FINISH and the encapsulated requests
+    /// carry no session ID. Only one such handshake is allowed at a time, so the session
+    /// is identified by its state.
+    pub fn pending_key_exchange_session_id(&self) -> Option<u32> {
+        self.sessions
+            .iter()
+            .filter_map(|slot| slot.0.as_ref())
+            .find(|info| {
+                info.session_state == SessionState::HandshakeInProgress && !info.psk_session
+            })
+            .map(|info| info.session_id)
     }
 
     pub fn create_session(&mut self, session_id: u32) -> SessionResult<()> {
-        for i in 0..MAX_NUM_SESSIONS {
-            if self.sessions[i].is_none() {
-                let session_info = SessionInfo::new(session_id);
-                self.sessions[i] = Some(session_info);
-                return Ok(());
-            }
+        if self.session_info(session_id).is_ok() {
+            return Err(SessionError::InvalidSessionId);
+        }
+
+        let slot_index = match self.sessions.iter().position(|slot| slot.0.is_none()) {
+            Some(index) => index,
+            None => self.evict_session()?,
+        };
+
+        self.sessions[slot_index].0 = Some(SessionInfo::new(session_id));
+        self.touch(session_id);
+        Ok(())
+    }
+
+    // Frees a session slot according to the eviction policy and returns its index.
+    fn evict_session(&mut self) -> SessionResult<usize> {
+        let active_session_id = self.active_session_id;
+        let clear_request_session_id = self.clear_request_session_id;
+        let eviction_policy = self.eviction_policy;
+        let victim = self
+            .sessions
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| slot.0.as_ref().map(|info| (index, info)))
+            // Never evict the session the current message belongs to
+            .filter(|(_, info)| Some(info.session_id) != active_session_id)
+            .filter(|(_, info)| Some(info.session_id) != clear_request_session_id)
+            .filter(|(_, info)| match eviction_policy {
+                SessionEvictionPolicy::RejectNew => false,
+                SessionEvictionPolicy::EvictStaleHandshake => {
+                    info.session_state != SessionState::Established
+                }
+                SessionEvictionPolicy::EvictLeastRecentlyUsed => true,
+            })
+            .min_by_key(|(_, info)| info.last_used)
+            .map(|(index, info)| (index, info.session_id));
+
+        let (index, session_id) = victim.ok_or(SessionError::SessionsLimitReached)?;
+        self.delete_session(session_id)?;
+        Ok(index)
+    }
+
+    // Marks the session as the most recently used one
+    fn touch(&mut self, session_id: u32) {
+        self.use_counter = self.use_counter.wrapping_add(1);
+        let use_counter = self.use_counter;
+        if let Ok(session_info) = self.session_info_mut(session_id) {
+            session_info.last_used = use_counter;
         }
-        Err(SessionError::SessionsLimitReached)
     }
 
     pub fn set_session_state(&mut self, session_id: u32, state: SessionState) -> SessionResult<()> {
-        let session_info = self
-            .sessions
-            .iter_mut()
-            .find_map(|s| s.as_mut().filter(|info| info.session_id == session_id))
-            .ok_or(SessionError::InvalidSessionId)?;
+        let session_info = self.session_info_mut(session_id)?;
 
         session_info.set_session_state(state);
         Ok(())
@@ -118,35 +221,77 @@ impl SessionManager {
         let session_index = self
             .sessions
             .iter()
-            .position(|s| {
-                s.as_ref()
+            .position(|slot| {
+                slot.0
+                    .as_ref()
                     .map(|info| info.session_id == session_id)
                     .unwrap_or(false)
             })
             .ok_or(SessionError::InvalidSessionId)?;
 
-        self.sessions[session_index] = None;
+        self.sessions[session_index].0 = None;
         if self.active_session_id == Some(session_id) {
             self.reset_active_session_id();
         }
-        if self.handshake_phase_session_id == Some(session_id) {
-            self.reset_handshake_phase_session_id();
+        if self.clear_request_session_id == Some(session_id) {
+            self.clear_request_session_id = None;
         }
         Ok(())
     }
 
-    #[allow(dead_code)]
+    /// Tears down all sessions whose heartbeat timeout has elapsed.
+    ///
+    /// # Arguments
+    /// `now_ms` is the current time in milliseconds.
+    pub fn terminate_expired_sessions(&mut self, now_ms: u64) {
+        self.terminate_sessions_if(|info| info.is_expired(now_ms));
+    }
+
+    /// Tears down all sessions whose session policy requests termination
+    /// upon a runtime code or configuration update.
+    pub fn terminate_sessions_on_runtime_update(&mut self) {
+        self.terminate_sessions_if(|info| info.session_policy.termination_policy() != 0);
+    }
+
+    fn terminate_sessions_if(&mut self, predicate: impl Fn(&SessionInfo) -> bool) {
+        for i in 0..self.sessions.len() {
+            let session_id = self.sessions[i]
+                .0
+                .as_ref()
+                .filter(|info| predicate(*info))
+                .map(|info| info.session_id);
+
+            if let Some(session_id) = session_id {
+                let _ = self.delete_session(session_id);
+            }
+        }
+    }
+
+    /// Records the activity on the session the current message belongs to.
+    /// This is the active session, or the session of a handshake request sent
+    /// in the clear.
+    ///
+    /// # Arguments
+    /// `now_ms` is the current time in milliseconds.
+    pub fn record_activity(&mut self, now_ms: u64) {
+        if let Some(session_id) = self.active_session_id.or(self.clear_request_session_id) {
+            if let Ok(session_info) = self.session_info_mut(session_id) {
+                session_info.record_activity(now_ms);
+            }
+        }
+    }
+
     pub fn session_info(&self, session_id: u32) -> SessionResult<&SessionInfo> {
         self.sessions
             .iter()
-            .find_map(|s| s.as_ref().filter(|info| info.session_id == session_id))
+            .find_map(|slot| slot.0.as_ref().filter(|info| info.session_id == session_id))
             .ok_or(SessionError::InvalidSessionId)
     }
 
     pub fn session_info_mut(&mut self, session_id: u32) -> SessionResult<&mut SessionInfo> {
         self.sessions
             .iter_mut()
-            .find_map(|s| s.as_mut().filter(|info| info.session_id == session_id))
+            .find_map(|slot| slot.0.as_mut().filter(|info| info.session_id == session_id))
             .ok_or(SessionError::InvalidSessionId)
     }
 
@@ -212,6 +357,10 @@ impl SessionManager {
 
         secure_message_len += encode_u8_slice(&tag, secure_message).map_err(SessionError::Codec)?;
 
+        // If this is the KEY_UPDATE_ACK response for UpdateAllKeys, the updated response
+        // direction key takes effect now that the response is protected with the current key.
+        session_info.apply_response_data_key_update().await?;
+
         if session_info.session_state == SessionState::Establishing {
             // If this is the response message for the FINISH request, set the session state to Established.
             session_info.set_session_state(SessionState::Established);
@@ -295,3 +444,234 @@ impl SessionManager {
         Ok(app_data_len)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::protocol::SpdmVersion;
+    use caliptra_mcu_libapi_caliptra::crypto::asym::AsymAlgo;
+    use zerocopy::FromBytes;
+
+    const REQ_SESSION_ID_A: u16 = 0xAAAA;
+    const REQ_SESSION_ID_B: u16 = 0xBBBB;
+    const REQ_SESSION_ID_C: u16 = 0xCCCC;
+
+    fn new_session(session_mgr: &mut SessionManager, req_session_id: u16) -> u32 {
+        let (session_id, _) = session_mgr.generate_session_id(req_session_id);
+        session_mgr.create_session(session_id).unwrap();
+        session_id
+    }
+
+    fn establish_session(session_mgr: &mut SessionManager, session_id: u32) {
+        session_mgr
+            .set_session_state(session_id, SessionState::Established)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_session_table_size_is_configurable() {
+        let mut slots: [SessionSlot; 2] = Default::default();
+        let mut session_mgr = SessionManager::new(&mut slots);
+        assert_eq!(session_mgr.max_sessions(), 2);
+
+        new_session(&mut session_mgr, REQ_SESSION_ID_A);
+        new_session(&mut session_mgr, REQ_SESSION_ID_B);
+        assert_eq!(session_mgr.num_sessions(), 2);
+
+        let (session_id, _) = session_mgr.generate_session_id(REQ_SESSION_ID_C);
+        assert_eq!(
+            session_mgr.create_session(session_id),
+            Err(SessionError::SessionsLimitReached)
+        );
+
+        session_mgr.reset();
+        assert_eq!(session_mgr.num_sessions(), 0);
+    }
+
+    // Secured messages exchanged on concurrent sessions are covered end to end by the
+    // DOE SPDM requester integration test (test_doe_spdm_requester).
+    #[test]
+    fn test_interleaved_session_bookkeeping() {
+        let mut slots: [SessionSlot; 2] = Default::default();
+        let mut session_mgr = SessionManager::new(&mut slots);
+        let session_a = new_session(&mut session_mgr, REQ_SESSION_ID_A);
+        let session_b = new_session(&mut session_mgr, REQ_SESSION_ID_B);
+        assert_ne!(session_a, session_b);
+
+        // Messages for session A and session B arrive interleaved
+        session_mgr.set_active_session_id(session_a);
+        establish_session(&mut session_mgr, session_a);
+        session_mgr.record_activity(100);
+
+        session_mgr.set_active_session_id(session_b);
+        session_mgr.record_activity(200);
+
+        session_mgr.set_active_session_id(session_a);
+        session_mgr
+            .session_info_mut(session_a)
+            .unwrap()
+            .heartbeat_period = 1;
+
+        let info_a = session_mgr.session_info(session_a).unwrap();
+        let info_b = session_mgr.session_info(session_b).unwrap();
+        assert_eq!(info_a.session_state, SessionState::Established);
+        assert_eq!(info_b.session_state, SessionState::HandshakeNotStarted);
+        assert!(info_a.is_expired(2101));
+        assert!(!info_b.is_expired(2101));
+
+        // Tearing down session B leaves the active session A untouched
+        session_mgr.delete_session(session_b).unwrap();
+        assert_eq!(session_mgr.active_session_id(), Some(session_a));
+        assert!(session_mgr.session_info(session_a).is_ok());
+        assert_eq!(
+            session_mgr.session_info(session_b).err(),
+            Some(SessionError::InvalidSessionId)
+        );
+
+        // Heartbeat timeout only tears down the expired session
+        let session_c = new_session(&mut session_mgr, REQ_SESSION_ID_C);
+        session_mgr.set_active_session_id(session_c);
+        session_mgr.record_activity(2000);
+        session_mgr.terminate_expired_sessions(2101);
+        assert!(session_mgr.session_info(session_a).is_err());
+        assert!(session_mgr.session_info(session_c).is_ok());
+    }
+
+    #[test]
+    fn test_concurrent_handshakes() {
+        let mut slots: [SessionSlot; 2] = Default::default();
+        let mut session_mgr = SessionManager::new(&mut slots);
+        assert_eq!(session_mgr.pending_key_exchange_session_id(), None);
+
+        // KEY_EXCHANGE A, KEY_EXCHANGE B: each request is bound to the session it created
+        let session_a = new_session(&mut session_mgr, REQ_SESSION_ID_A);
+        session_mgr.set_clear_request_session_id(session_a);
+        session_mgr
+            .set_session_state(session_a, SessionState::HandshakeInProgress)
+            .unwrap();
+        session_mgr.record_activity(100);
+
+        session_mgr.reset_request_session_id();
+        let session_b = new_session(&mut session_mgr, REQ_SESSION_ID_B);
+        session_mgr.set_clear_request_session_id(session_b);
+        session_mgr.record_activity(200);
+
+        // The failed handshake B is torn down without affecting the handshake A
+        session_mgr.delete_session(session_b).unwrap();
+        assert_eq!(
+            session_mgr.pending_key_exchange_session_id(),
+            Some(session_a)
+        );
+        assert_eq!(
+            session_mgr.session_info(session_a).unwrap().session_state,
+            SessionState::HandshakeInProgress
+        );
+        session_mgr
+            .session_info_mut(session_a)
+            .unwrap()
+            .heartbeat_period = 1;
+        session_mgr.record_activity(300);
+        assert!(session_mgr
+            .session_info(session_a)
+            .unwrap()
+            .is_expired(2101));
+
+        // FINISH of session A arrives in a secured message for session A
+        session_mgr.reset_request_session_id();
+        session_mgr.set_active_session_id(session_a);
+        session_mgr
+            .set_session_state(session_a, SessionState::Establishing)
+            .unwrap();
+        assert_eq!(session_mgr.pending_key_exchange_session_id(), None);
+
+        // PSK handshakes are completed with PSK_FINISH within the session
+        let session_c = new_session(&mut session_mgr, REQ_SESSION_ID_C);
+        let info_c = session_mgr.session_info_mut(session_c).unwrap();
+        info_c.psk_session = true;
+        info_c.set_session_state(SessionState::HandshakeInProgress);
+        assert_eq!(session_mgr.pending_key_exchange_session_id(), None);
+    }
+
+    #[test]
+    fn test_evict_stale_handshake() {
+        let mut slots: [SessionSlot; 2] = Default::default();
+        let mut session_mgr = SessionManager::new(&mut slots);
+        session_mgr.set_eviction_policy(SessionEvictionPolicy::EvictStaleHandshake);
+
+        let session_a = new_session(&mut session_mgr, REQ_SESSION_ID_A);
+        establish_session(&mut session_mgr, session_a);
+        let session_b = new_session(&mut session_mgr, REQ_SESSION_ID_B);
+        session_mgr
+            .set_session_state(session_b, SessionState::HandshakeInProgress)
+            .unwrap();
+
+        // The pending handshake is evicted, the established session is kept
+        let session_c = new_session(&mut session_mgr, REQ_SESSION_ID_C);
+        assert!(session_mgr.session_info(session_a).is_ok());
+        assert!(session_mgr.session_info(session_b).is_err());
+        assert!(session_mgr.session_info(session_c).is_ok());
+
+        // Established sessions are never evicted
+        establish_session(&mut session_mgr, session_c);
+        let (session_id, _) = session_mgr.generate_session_id(REQ_SESSION_ID_B);
+        assert_eq!(
+            session_mgr.create_session(session_id),
+            Err(SessionError::SessionsLimitReached)
+        );
+    }
+
+    #[test]
+    fn test_evict_least_recently_used() {
+        let mut slots: [SessionSlot; 2] = Default::default();
+        let mut session_mgr = SessionManager::new(&mut slots);
+        session_mgr.set_eviction_policy(SessionEvictionPolicy::EvictLeastRecentlyUsed);
+
+        let session_a = new_session(&mut session_mgr, REQ_SESSION_ID_A);
+        let session_b = new_session(&mut session_mgr, REQ_SESSION_ID_B);
+        establish_session(&mut session_mgr, session_a);
+        establish_session(&mut session_mgr, session_b);
+
+        // Session A is used after session B, so session B is the least recently used
+        session_mgr.set_active_session_id(session_b);
+        session_mgr.set_active_session_id(session_a);
+        session_mgr.reset_active_session_id();
+
+        let session_c = new_session(&mut session_mgr, REQ_SESSION_ID_C);
+        assert!(session_mgr.session_info(session_a).is_ok());
+        assert!(session_mgr.session_info(session_b).is_err());
+        assert!(session_mgr.session_info(session_c).is_ok());
+    }
+
+    #[test]
+    fn test_generate_session_id_skips_ids_in_use() {
+        let mut slots: [SessionSlot; 2] = Default::default();
+        let mut session_mgr = SessionManager::new(&mut slots);
+
+        let session_a = new_session(&mut session_mgr, REQ_SESSION_ID_A);
+        // Force the responder session ID to wrap around onto the one in use
+        session_mgr.cur_responder_session_id = (session_a >> 16) as u16;
+        let session_b = new_session(&mut session_mgr, REQ_SESSION_ID_A);
+        assert_ne!(session_a, session_b);
+        assert_eq!(session_b & 0xFFFF, u32::from(REQ_SESSION_ID_A));
+    }
+
+    #[test]
+    fn test_terminate_sessions_on_runtime_update() {
+        let mut slots: [SessionSlot; 2] = Default::default();
+        let mut session_mgr = SessionManager::new(&mut slots);
+
+        let session_a = new_session(&mut session_mgr, REQ_SESSION_ID_A);
+        let session_b = new_session(&mut session_mgr, REQ_SESSION_ID_B);
+        session_mgr.session_info_mut(session_a).unwrap().init(
+            SessionPolicy::read_from_bytes(&[1]).unwrap(),
+            SessionType::MacAndEncrypt,
+            SpdmVersion::V12,
+            AsymAlgo::EccP384,
+            0,
+        );
+
+        session_mgr.terminate_sessions_on_runtime_update();
+        assert!(session_mgr.session_info(session_a).is_err());
+        assert!(session_mgr.session_info(session_b).is_ok());
+    }
+}
@@ -32,6 +32,7 @@ pub enum TranscriptContext {
     Digests,
     M1,
     L1,
+    Il1,
     Th,
 }
 
@@ -55,6 +56,11 @@ pub(crate) struct Transcript {
     // where
     // M = Concatenate (GET_MEASUREMENTS, MEASUREMENTS\signature)
     hash_ctx_l1: Option<HashContext>,
+    // Hash context for `IL1`
+    // IL1 = Concatenate(A, E)
+    // where
+    // E = Concatenate (GET_ENDPOINT_INFO, ENDPOINT_INFO excluding signature)
+    hash_ctx_il1: Option<HashContext>,
 }
 
 impl Transcript {
@@ -65,6 +71,7 @@ impl Transcript {
             digests_buf: None,
             hash_ctx_m1: None,
             hash_ctx_l1: None,
+            hash_ctx_il1: None,
         }
     }
 
@@ -86,6 +93,7 @@ impl Transcript {
         self.digests_buf = None;
         self.hash_ctx_m1 = None;
         self.hash_ctx_l1 = None;
+        self.hash_ctx_il1 = None;
     }
 
     /// Reset a transcript context.
@@ -98,6 +106,7 @@ impl Transcript {
             TranscriptContext::Digests => self.digests_buf = None,
             TranscriptContext::M1 => self.hash_ctx_m1 = None,
             TranscriptContext::L1 => self.hash_ctx_l1 = None,
+            TranscriptContext::Il1 => self.hash_ctx_il1 = None,
             _ => {}
         }
     }
@@ -122,6 +131,7 @@ impl Transcript {
             TranscriptContext::Digests => self.append_digests(data),
             TranscriptContext::M1 => self.append_m1(data).await,
             TranscriptContext::L1 => self.append_l1(self.spdm_version, session_info, data).await,
+            TranscriptContext::Il1 => self.append_il1(data).await,
             TranscriptContext::Th => {
                 if let Some(session) = session_info {
                     self.append_th(session, data).await
@@ -196,6 +206,20 @@ impl Transcript {
                     }
                 }
             }
+            TranscriptContext::Il1 => {
+                // IL1 always uses global hash context
+                if let Some(ctx) = &mut self.hash_ctx_il1 {
+                    ctx.finalize(hash)
+                        .await
+                        .map_err(TranscriptError::CaliptraApi)?;
+                    if finish_hash {
+                        self.hash_ctx_il1 = None;
+                    }
+                    Ok(())
+                } else {
+                    Err(TranscriptError::InvalidState)
+                }
+            }
             TranscriptContext::Th => {
                 // TH requires session_info - error if None
                 match session_info {
@@ -254,6 +278,23 @@ impl Transcript {
         }
     }
 
+    async fn append_il1(&mut self, data: &[u8]) -> TranscriptResult<()> {
+        if let Some(ctx) = &mut self.hash_ctx_il1 {
+            ctx.update(data).await.map_err(TranscriptError::CaliptraApi)
+        } else {
+            let vca_data = self.vca_buf.as_slice();
+            let mut ctx = HashContext::new();
+            ctx.init(HashAlgoType::SHA384, Some(vca_data))
+                .await
+                .map_err(TranscriptError::CaliptraApi)?;
+            ctx.update(data)
+                .await
+                .map_err(TranscriptError::CaliptraApi)?;
+            self.hash_ctx_il1 = Some(ctx);
+            Ok(())
+        }
+    }
+
     async fn append_l1(
         &mut self,
         spdm_version: SpdmVersion,
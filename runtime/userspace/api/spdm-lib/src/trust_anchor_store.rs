@@ -0,0 +1,630 @@
+// Licensed under the Apache-2.0 license
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use async_trait::async_trait;
+use caliptra_mcu_libapi_caliptra::crypto::asym::ecdsa::Ecdsa;
+use caliptra_mcu_libapi_caliptra::crypto::asym::{
+    ECC_P384_PARAM_X_SIZE, ECC_P384_PARAM_Y_SIZE, ECC_P384_SIGNATURE_SIZE,
+};
+use caliptra_mcu_libapi_caliptra::crypto::hash::{HashAlgoType, HashContext, SHA384_HASH_SIZE};
+use caliptra_mcu_libapi_caliptra::error::CaliptraApiError;
+
+// DER tags used by the X.509 certificate parser
+const DER_TAG_BOOLEAN: u8 = 0x01;
+const DER_TAG_INTEGER: u8 = 0x02;
+const DER_TAG_BIT_STRING: u8 = 0x03;
+const DER_TAG_OCTET_STRING: u8 = 0x04;
+const DER_TAG_OID: u8 = 0x06;
+const DER_TAG_SEQUENCE: u8 = 0x30;
+const DER_TAG_EXPLICIT_VERSION: u8 = 0xA0;
+const DER_TAG_EXPLICIT_EXTENSIONS: u8 = 0xA3;
+
+// OID 1.2.840.10045.4.3.3 (ecdsa-with-SHA384)
+const OID_ECDSA_WITH_SHA384: &[u8] = &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x04, 0x03, 0x03];
+// OID 1.2.840.10045.2.1 (id-ecPublicKey)
+const OID_EC_PUBLIC_KEY: &[u8] = &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x02, 0x01];
+// OID 1.3.132.0.34 (secp384r1)
+const OID_SECP384R1: &[u8] = &[0x2B, 0x81, 0x04, 0x00, 0x22];
+// OID 2.5.29.15 (id-ce-keyUsage)
+const OID_KEY_USAGE: &[u8] = &[0x55, 0x1D, 0x0F];
+// OID 2.5.29.19 (id-ce-basicConstraints)
+const OID_BASIC_CONSTRAINTS: &[u8] = &[0x55, 0x1D, 0x13];
+
+// KeyUsage bits in the first byte of the BIT STRING
+const KEY_USAGE_DIGITAL_SIGNATURE: u8 = 0x80;
+const KEY_USAGE_KEY_CERT_SIGN: u8 = 0x04;
+
+// Uncompressed EC point marker of the SubjectPublicKey
+const EC_POINT_UNCOMPRESSED: u8 = 0x04;
+
+#[derive(Debug, PartialEq)]
+pub enum TrustAnchorError {
+    UntrustedRootCert,
+    InvalidCertChain,
+    UnsupportedCertAlgo,
+    InvalidSignature,
+    CaliptraApi(CaliptraApiError),
+}
+pub type TrustAnchorResult<T> = Result<T, TrustAnchorError>;
+
+#[async_trait]
+pub trait RequesterTrustAnchorStore {
+    /// Check whether a root certificate is trusted to issue Requester certificate chains.
+    /// Used for session-based mutual authentication.
+    ///
+    /// # Arguments
+    /// * `root_cert` - The DER-encoded root certificate of the Requester certificate chain.
+    ///
+    /// # Returns
+    /// * `bool` - True if the root certificate is a trust anchor.
+    async fn is_trust_anchor(&self, root_cert: &[u8]) -> TrustAnchorResult<bool>;
+}
+
+/// A trust anchor store that identifies the trusted root certificates by their SHA-384 digest.
+pub struct RootCertHashTrustAnchors<'a> {
+    root_cert_hashes: &'a [[u8; SHA384_HASH_SIZE]],
+}
+
+impl<'a> RootCertHashTrustAnchors<'a> {
+    /// Create a new trust anchor store.
+    ///
+    /// # Arguments
+    /// * `root_cert_hashes` - The SHA-384 digests of the DER-encoded trusted root certificates.
+    pub fn new(root_cert_hashes: &'a [[u8; SHA384_HASH_SIZE]]) -> Self {
+        Self { root_cert_hashes }
+    }
+}
+
+#[async_trait]
+impl RequesterTrustAnchorStore for RootCertHashTrustAnchors<'_> {
+    async fn is_trust_anchor(&self, root_cert: &[u8]) -> TrustAnchorResult<bool> {
+        let root_cert_hash = sha384(root_cert).await?;
+        Ok(self
+            .root_cert_hashes
+            .iter()
+            .any(|hash| *hash == root_cert_hash))
+    }
+}
+
+/// ECC P-384 public key of a certificate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct EccP384PublicKey {
+    pub(crate) x: [u8; ECC_P384_PARAM_X_SIZE],
+    pub(crate) y: [u8; ECC_P384_PARAM_Y_SIZE],
+}
+
+impl EccP384PublicKey {
+    /// Verify an ECDSA P-384 signature over a SHA-384 digest.
+    pub(crate) async fn verify(
+        &self,
+        hash: [u8; SHA384_HASH_SIZE],
+        signature: &[u8; ECC_P384_SIGNATURE_SIZE],
+    ) -> TrustAnchorResult<()> {
+        Ecdsa::ecdsa_verify(self.x, self.y, signature, hash)
+            .await
+            .map_err(|_| TrustAnchorError::InvalidSignature)
+    }
+}
+
+/// Verify a Requester certificate chain and return the public key of the leaf certificate.
+///
+/// The root certificate must match the RootHash of the chain and be a trust anchor of
+/// the store. Every other certificate must be issued by its predecessor: its issuer name
+/// is the subject name of the predecessor, the predecessor is a CA allowed to sign
+/// certificates, and the signature is ECDSA P-384 / SHA-384. The leaf certificate must
+/// allow digital signatures. Certificate validity periods are not checked, as the
+/// Responder has no trusted time source.
+///
+/// # Arguments
+/// * `trust_anchor_store` - The store of trusted root certificates.
+/// * `root_hash` - The RootHash of the certificate chain.
+/// * `certs` - The DER-encoded certificates of the chain, starting with the root certificate.
+///
+/// # Returns
+/// * `EccP384PublicKey` - The public key of the leaf certificate.
+pub(crate) async fn verify_requester_cert_chain(
+    trust_anchor_store: &dyn RequesterTrustAnchorStore,
+    root_hash: &[u8; SHA384_HASH_SIZE],
+    certs: &[u8],
+) -> TrustAnchorResult<EccP384PublicKey> {
+    let mut issuer: Option<X509Cert> = None;
+    let mut rem = certs;
+
+    while !rem.is_empty() {
+        let (_, _, next) = der_expect(rem, DER_TAG_SEQUENCE)?;
+        let cert_der = &rem[..rem.len() - next.len()];
+        let cert = X509Cert::parse(cert_der)?;
+
+        match issuer {
+            None => {
+                if sha384(cert_der).await? != *root_hash {
+                    Err(TrustAnchorError::InvalidCertChain)?;
+                }
+                if !trust_anchor_store.is_trust_anchor(cert_der).await? {
+                    Err(TrustAnchorError::UntrustedRootCert)?;
+                }
+            }
+            Some(issuer) => {
+                cert.check_issued_by(&issuer)?;
+                let tbs_hash = sha384(cert.tbs).await?;
+                issuer.public_key.verify(tbs_hash, &cert.signature).await?;
+            }
+        }
+
+        issuer = Some(cert);
+        rem = next;
+    }
+
+    let leaf = issuer.ok_or(TrustAnchorError::InvalidCertChain)?;
+    leaf.check_leaf()?;
+    Ok(leaf.public_key)
+}
+
+pub(crate) async fn sha384(data: &[u8]) -> TrustAnchorResult<[u8; SHA384_HASH_SIZE]> {
+    let mut hash = [0u8; SHA384_HASH_SIZE];
+    let mut hash_ctx = HashContext::new();
+    hash_ctx
+        .init(HashAlgoType::SHA384, Some(data))
+        .await
+        .map_err(TrustAnchorError::CaliptraApi)?;
+    hash_ctx
+        .finalize(&mut hash)
+        .await
+        .map_err(TrustAnchorError::CaliptraApi)?;
+    Ok(hash)
+}
+
+/// The fields of an X.509 certificate needed to verify a certificate chain.
+struct X509Cert<'c> {
+    tbs: &'c [u8],
+    signature: [u8; ECC_P384_SIGNATURE_SIZE],
+    public_key: EccP384PublicKey,
+    // DER-encoded issuer and subject names
+    issuer: &'c [u8],
+    subject: &'c [u8],
+    // cA flag of the basicConstraints extension
+    ca: bool,
+    // First byte of the keyUsage extension, if present
+    key_usage: Option<u8>,
+}
+
+impl<'c> X509Cert<'c> {
+    fn parse(cert_der: &'c [u8]) -> TrustAnchorResult<Self> {
+        // Certificate ::= SEQUENCE { tbsCertificate, signatureAlgorithm, signatureValue }
+        let (_, cert, _) = der_expect(cert_der, DER_TAG_SEQUENCE)?;
+        let (_, tbs_value, rem) = der_expect(cert, DER_TAG_SEQUENCE)?;
+        let tbs = &cert[..cert.len() - rem.len()];
+
+        let (_, sig_algo, rem) = der_expect(rem, DER_TAG_SEQUENCE)?;
+        let (_, sig_algo_oid, _) = der_expect(sig_algo, DER_TAG_OID)?;
+        if sig_algo_oid != OID_ECDSA_WITH_SHA384 {
+            Err(TrustAnchorError::UnsupportedCertAlgo)?;
+        }
+
+        let (_, sig_value, _) = der_expect(rem, DER_TAG_BIT_STRING)?;
+        let signature = ecdsa_sig_value(sig_value)?;
+
+        // Skip the optional version
+        let mut rem = tbs_value;
+        if rem.first() == Some(&DER_TAG_EXPLICIT_VERSION) {
+            (_, _, rem) = der_tlv(rem)?;
+        }
+
+        // Skip serialNumber and signature
+        (_, _, rem) = der_expect(rem, DER_TAG_INTEGER)?;
+        (_, _, rem) = der_expect(rem, DER_TAG_SEQUENCE)?;
+
+        let (_, _, after_issuer) = der_expect(rem, DER_TAG_SEQUENCE)?;
+        let issuer = &rem[..rem.len() - after_issuer.len()];
+        (_, _, rem) = der_expect(after_issuer, DER_TAG_SEQUENCE)?;
+        let (_, _, after_subject) = der_expect(rem, DER_TAG_SEQUENCE)?;
+        let subject = &rem[..rem.len() - after_subject.len()];
+
+        let (_, spki, rem) = der_expect(after_subject, DER_TAG_SEQUENCE)?;
+        let public_key = spki_public_key(spki)?;
+
+        let mut cert = Self {
+            tbs,
+            signature,
+            public_key,
+            issuer,
+            subject,
+            ca: false,
+            key_usage: None,
+        };
+        cert.parse_extensions(rem)?;
+        Ok(cert)
+    }
+
+    /// Parse the basicConstraints and keyUsage extensions following the SubjectPublicKeyInfo.
+    fn parse_extensions(&mut self, tbs_rem: &[u8]) -> TrustAnchorResult<()> {
+        // Skip the optional issuerUniqueID and subjectUniqueID
+        let mut rem = tbs_rem;
+        while let Some(tag) = rem.first() {
+            let (_, value, next) = der_tlv(rem)?;
+            rem = next;
+            if *tag != DER_TAG_EXPLICIT_EXTENSIONS {
+                continue;
+            }
+
+            // Extensions ::= SEQUENCE OF Extension
+            let (_, mut extensions, _) = der_expect(value, DER_TAG_SEQUENCE)?;
+            while !extensions.is_empty() {
+                // Extension ::= SEQUENCE { extnID, critical BOOLEAN DEFAULT FALSE, extnValue }
+                let (_, extension, next) = der_expect(extensions, DER_TAG_SEQUENCE)?;
+                extensions = next;
+                let (_, oid, mut ext_rem) = der_expect(extension, DER_TAG_OID)?;
+                if ext_rem.first() == Some(&DER_TAG_BOOLEAN) {
+                    (_, _, ext_rem) = der_tlv(ext_rem)?;
+                }
+                let (_, ext_value, _) = der_expect(ext_rem, DER_TAG_OCTET_STRING)?;
+
+                if oid == OID_BASIC_CONSTRAINTS {
+                    // BasicConstraints ::= SEQUENCE { cA BOOLEAN DEFAULT FALSE, pathLen }
+                    let (_, constraints, _) = der_expect(ext_value, DER_TAG_SEQUENCE)?;
+                    if constraints.first() == Some(&DER_TAG_BOOLEAN) {
+                        let (_, ca, _) = der_tlv(constraints)?;
+                        self.ca = ca.iter().any(|b| *b != 0);
+                    }
+                } else if oid == OID_KEY_USAGE {
+                    // KeyUsage ::= BIT STRING, the first byte holds the number of unused bits
+                    let (_, key_usage, _) = der_expect(ext_value, DER_TAG_BIT_STRING)?;
+                    self.key_usage = Some(key_usage.get(1).copied().unwrap_or(0));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that this certificate names `issuer` as its issuer and that `issuer`
+    /// is a CA allowed to sign certificates.
+    fn check_issued_by(&self, issuer: &X509Cert) -> TrustAnchorResult<()> {
+        if self.issuer != issuer.subject
+            || !issuer.ca
+            || issuer.key_usage.unwrap_or(0) & KEY_USAGE_KEY_CERT_SIGN == 0
+        {
+            Err(TrustAnchorError::InvalidCertChain)?;
+        }
+        Ok(())
+    }
+
+    /// Check that the leaf certificate key may be used for digital signatures.
+    fn check_leaf(&self) -> TrustAnchorResult<()> {
+        if self.key_usage.unwrap_or(0) & KEY_USAGE_DIGITAL_SIGNATURE == 0 {
+            Err(TrustAnchorError::InvalidCertChain)?;
+        }
+        Ok(())
+    }
+}
+
+/// Extract the ECC P-384 SubjectPublicKey from the SubjectPublicKeyInfo contents.
+fn spki_public_key(spki: &[u8]) -> TrustAnchorResult<EccP384PublicKey> {
+    // SubjectPublicKeyInfo ::= SEQUENCE { algorithm, subjectPublicKey }
+    let (_, algo, rem) = der_expect(spki, DER_TAG_SEQUENCE)?;
+    let (_, algo_oid, curve) = der_expect(algo, DER_TAG_OID)?;
+    let (_, curve_oid, _) = der_expect(curve, DER_TAG_OID)?;
+    if algo_oid != OID_EC_PUBLIC_KEY || curve_oid != OID_SECP384R1 {
+        Err(TrustAnchorError::UnsupportedCertAlgo)?;
+    }
+
+    // The BIT STRING holds the number of unused bits and the uncompressed point 04 || X || Y
+    let (_, subject_public_key, _) = der_expect(rem, DER_TAG_BIT_STRING)?;
+    match subject_public_key {
+        [0, EC_POINT_UNCOMPRESSED, point @ ..]
+            if point.len() == ECC_P384_PARAM_X_SIZE + ECC_P384_PARAM_Y_SIZE =>
+        {
+            let mut public_key = EccP384PublicKey {
+                x: [0; ECC_P384_PARAM_X_SIZE],
+                y: [0; ECC_P384_PARAM_Y_SIZE],
+            };
+            public_key
+                .x
+                .copy_from_slice(&point[..ECC_P384_PARAM_X_SIZE]);
+            public_key
+                .y
+                .copy_from_slice(&point[ECC_P384_PARAM_X_SIZE..]);
+            Ok(public_key)
+        }
+        _ => Err(TrustAnchorError::UnsupportedCertAlgo),
+    }
+}
+
+/// Convert the signatureValue BIT STRING contents (Ecdsa-Sig-Value) to the fixed size r || s form.
+fn ecdsa_sig_value(sig_value: &[u8]) -> TrustAnchorResult<[u8; ECC_P384_SIGNATURE_SIZE]> {
+    let Some((0, sig_value)) = sig_value.split_first() else {
+        return Err(TrustAnchorError::InvalidCertChain);
+    };
+
+    // Ecdsa-Sig-Value ::= SEQUENCE { r INTEGER, s INTEGER }
+    let (_, sig_seq, _) = der_expect(sig_value, DER_TAG_SEQUENCE)?;
+    let (_, r, rem) = der_expect(sig_seq, DER_TAG_INTEGER)?;
+    let (_, s, _) = der_expect(rem, DER_TAG_INTEGER)?;
+
+    let mut signature = [0u8; ECC_P384_SIGNATURE_SIZE];
+    let (sig_r, sig_s) = signature.split_at_mut(ECC_P384_SIGNATURE_SIZE / 2);
+    der_uint_to_fixed(r, sig_r)?;
+    der_uint_to_fixed(s, sig_s)?;
+    Ok(signature)
+}
+
+/// Copy a DER INTEGER to a big-endian fixed size buffer, stripping the sign padding.
+fn der_uint_to_fixed(value: &[u8], out: &mut [u8]) -> TrustAnchorResult<()> {
+    let start = value.iter().position(|b| *b != 0).unwrap_or(value.len());
+    let value = &value[start..];
+    if value.len() > out.len() {
+        Err(TrustAnchorError::InvalidCertChain)?;
+    }
+    let pad = out.len() - value.len();
+    out[..pad].fill(0);
+    out[pad..].copy_from_slice(value);
+    Ok(())
+}
+
+/// Split the DER element at the start of `data` and check its tag.
+///
+/// # Returns
+/// * The tag, the contents and the data following the element.
+fn der_expect(data: &[u8], tag: u8) -> TrustAnchorResult<(u8, &[u8], &[u8])> {
+    let tlv = der_tlv(data)?;
+    if tlv.0 != tag {
+        Err(TrustAnchorError::InvalidCertChain)?;
+    }
+    Ok(tlv)
+}
+
+/// Split the DER element at the start of `data`.
+///
+/// # Returns
+/// * The tag, the contents and the data following the element.
+fn der_tlv(data: &[u8]) -> TrustAnchorResult<(u8, &[u8], &[u8])> {
+    let [tag, len_byte, rem @ ..] = data else {
+        return Err(TrustAnchorError::InvalidCertChain);
+    };
+
+    let (len, rem) = if len_byte & 0x80 == 0 {
+        (*len_byte as usize, rem)
+    } else {
+        // Long form, certificates never need more than 3 length bytes
+        let num_len_bytes = (len_byte & 0x7F) as usize;
+        if num_len_bytes == 0 || num_len_bytes > 3 || rem.len() < num_len_bytes {
+            Err(TrustAnchorError::InvalidCertChain)?;
+        }
+        let len = rem[..num_len_bytes]
+            .iter()
+            .fold(0usize, |len, b| (len << 8) | *b as usize);
+        (len, &rem[num_len_bytes..])
+    };
+
+    if rem.len() < len {
+        Err(TrustAnchorError::InvalidCertChain)?;
+    }
+    Ok((*tag, &rem[..len], &rem[len..]))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    fn der(tag: u8, content: &[u8]) -> Vec<u8> {
+        let mut tlv = vec![tag];
+        match content.len() {
+            len @ 0..=0x7F => tlv.push(len as u8),
+            len @ 0x80..=0xFF => tlv.extend_from_slice(&[0x81, len as u8]),
+            len => tlv.extend_from_slice(&[0x82, (len >> 8) as u8, len as u8]),
+        }
+        tlv.extend_from_slice(content);
+        tlv
+    }
+
+    fn name(common_name: &[u8]) -> Vec<u8> {
+        let attribute = [
+            der(DER_TAG_OID, &[0x55, 0x04, 0x03]),
+            der(0x0C, common_name),
+        ]
+        .concat();
+        der(
+            DER_TAG_SEQUENCE,
+            &der(0x31, &der(DER_TAG_SEQUENCE, &attribute)),
+        )
+    }
+
+    /// A certificate with a dummy key and signature, enough for the chain checks
+    fn cert(issuer: &[u8], subject: &[u8], ca: bool, key_usage: u8) -> Vec<u8> {
+        let algo = [
+            der(DER_TAG_OID, OID_EC_PUBLIC_KEY),
+            der(DER_TAG_OID, OID_SECP384R1),
+        ]
+        .concat();
+        let mut point = vec![0, EC_POINT_UNCOMPRESSED];
+        point.extend_from_slice(&[0x11; ECC_P384_PARAM_X_SIZE + ECC_P384_PARAM_Y_SIZE]);
+        let spki = [
+            der(DER_TAG_SEQUENCE, &algo),
+            der(DER_TAG_BIT_STRING, &point),
+        ]
+        .concat();
+
+        let constraints = if ca {
+            der(DER_TAG_BOOLEAN, &[0xFF])
+        } else {
+            vec![]
+        };
+        let basic_constraints = [
+            der(DER_TAG_OID, OID_BASIC_CONSTRAINTS),
+            der(DER_TAG_BOOLEAN, &[0xFF]),
+            der(DER_TAG_OCTET_STRING, &der(DER_TAG_SEQUENCE, &constraints)),
+        ]
+        .concat();
+        let key_usage = [
+            der(DER_TAG_OID, OID_KEY_USAGE),
+            der(
+                DER_TAG_OCTET_STRING,
+                &der(DER_TAG_BIT_STRING, &[0x01, key_usage]),
+            ),
+        ]
+        .concat();
+        let extensions = [
+            der(DER_TAG_SEQUENCE, &basic_constraints),
+            der(DER_TAG_SEQUENCE, &key_usage),
+        ]
+        .concat();
+
+        let sig_algo = der(DER_TAG_SEQUENCE, &der(DER_TAG_OID, OID_ECDSA_WITH_SHA384));
+        let tbs = [
+            der(DER_TAG_EXPLICIT_VERSION, &der(DER_TAG_INTEGER, &[0x02])),
+            der(DER_TAG_INTEGER, &[0x01]),
+            sig_algo.clone(),
+            issuer.to_vec(),
+            der(DER_TAG_SEQUENCE, &[]),
+            subject.to_vec(),
+            der(DER_TAG_SEQUENCE, &spki),
+            der(
+                DER_TAG_EXPLICIT_EXTENSIONS,
+                &der(DER_TAG_SEQUENCE, &extensions),
+            ),
+        ]
+        .concat();
+
+        let ecdsa_sig = [der(DER_TAG_INTEGER, &[0x01]), der(DER_TAG_INTEGER, &[0x02])].concat();
+        let sig_value = [&[0u8][..], &der(DER_TAG_SEQUENCE, &ecdsa_sig)].concat();
+        let cert = [
+            der(DER_TAG_SEQUENCE, &tbs),
+            sig_algo,
+            der(DER_TAG_BIT_STRING, &sig_value),
+        ]
+        .concat();
+        der(DER_TAG_SEQUENCE, &cert)
+    }
+
+    #[test]
+    fn test_cert_chain_links() {
+        let root_name = name(b"Root CA");
+        let leaf_name = name(b"Requester");
+        let root_der = cert(&root_name, &root_name, true, KEY_USAGE_KEY_CERT_SIGN);
+        let leaf_der = cert(&root_name, &leaf_name, false, KEY_USAGE_DIGITAL_SIGNATURE);
+
+        let root = X509Cert::parse(&root_der).unwrap();
+        let leaf = X509Cert::parse(&leaf_der).unwrap();
+        assert!(root.ca);
+        assert!(!leaf.ca);
+        assert_eq!(root.signature[ECC_P384_SIGNATURE_SIZE / 2 - 1], 0x01);
+        assert_eq!(leaf.public_key.x, [0x11; ECC_P384_PARAM_X_SIZE]);
+        assert_eq!(leaf.check_issued_by(&root), Ok(()));
+        assert_eq!(leaf.check_leaf(), Ok(()));
+
+        // A root certificate that does not allow certificate signing
+        let root_der = cert(&root_name, &root_name, true, KEY_USAGE_DIGITAL_SIGNATURE);
+        let root = X509Cert::parse(&root_der).unwrap();
+        assert_eq!(
+            leaf.check_issued_by(&root),
+            Err(TrustAnchorError::InvalidCertChain)
+        );
+
+        // A leaf certificate that does not allow digital signatures
+        let leaf_der = cert(&root_name, &leaf_name, false, KEY_USAGE_KEY_CERT_SIGN);
+        let leaf = X509Cert::parse(&leaf_der).unwrap();
+        assert_eq!(leaf.check_leaf(), Err(TrustAnchorError::InvalidCertChain));
+    }
+
+    #[test]
+    fn test_cert_signed_by_leaf() {
+        let root_name = name(b"Root CA");
+        let leaf_name = name(b"Requester");
+        let leaf_der = cert(&root_name, &leaf_name, false, 0xFF);
+        let forged_der = cert(
+            &leaf_name,
+            &name(b"Forged"),
+            false,
+            KEY_USAGE_DIGITAL_SIGNATURE,
+        );
+
+        let leaf = X509Cert::parse(&leaf_der).unwrap();
+        let forged = X509Cert::parse(&forged_der).unwrap();
+        assert_eq!(
+            forged.check_issued_by(&leaf),
+            Err(TrustAnchorError::InvalidCertChain)
+        );
+    }
+
+    #[test]
+    fn test_cert_issuer_subject_mismatch() {
+        let root_name = name(b"Root CA");
+        let root_der = cert(&root_name, &root_name, true, KEY_USAGE_KEY_CERT_SIGN);
+        let leaf_der = cert(
+            &name(b"Other CA"),
+            &name(b"Requester"),
+            false,
+            KEY_USAGE_DIGITAL_SIGNATURE,
+        );
+
+        let root = X509Cert::parse(&root_der).unwrap();
+        let leaf = X509Cert::parse(&leaf_der).unwrap();
+        assert_eq!(
+            leaf.check_issued_by(&root),
+            Err(TrustAnchorError::InvalidCertChain)
+        );
+    }
+
+    #[test]
+    fn test_der_tlv_short_and_long_form() {
+        let data = [0x30, 0x02, 0xAA, 0xBB, 0xCC];
+        let (tag, value, rem) = der_tlv(&data).unwrap();
+        assert_eq!(tag, DER_TAG_SEQUENCE);
+        assert_eq!(value, &[0xAA, 0xBB]);
+        assert_eq!(rem, &[0xCC]);
+
+        let mut data = [0u8; 4 + 0x100];
+        data[..4].copy_from_slice(&[0x04, 0x82, 0x01, 0x00]);
+        let (tag, value, rem) = der_tlv(&data).unwrap();
+        assert_eq!(tag, 0x04);
+        assert_eq!(value.len(), 0x100);
+        assert!(rem.is_empty());
+    }
+
+    #[test]
+    fn test_der_tlv_truncated() {
+        assert_eq!(
+            der_tlv(&[0x30, 0x03, 0xAA]),
+            Err(TrustAnchorError::InvalidCertChain)
+        );
+        assert_eq!(
+            der_tlv(&[0x30, 0x80]),
+            Err(TrustAnchorError::InvalidCertChain)
+        );
+        assert_eq!(der_tlv(&[0x30]), Err(TrustAnchorError::InvalidCertChain));
+        assert_eq!(
+            der_expect(&[0x02, 0x01, 0x01], DER_TAG_SEQUENCE),
+            Err(TrustAnchorError::InvalidCertChain)
+        );
+    }
+
+    #[test]
+    fn test_ecdsa_sig_value() {
+        // r has a sign padding byte, s is shorter than the coordinate size
+        let mut sig_value = [0u8; 2 + 2 + 49 + 2 + 47 + 1];
+        sig_value[..5].copy_from_slice(&[0x00, 0x30, 0x64, 0x02, 0x31]);
+        sig_value[5] = 0x00;
+        sig_value[6..54].fill(0x80);
+        sig_value[54..56].copy_from_slice(&[0x02, 0x2F]);
+        sig_value[56..103].fill(0x11);
+
+        let signature = ecdsa_sig_value(&sig_value).unwrap();
+        assert_eq!(&signature[..48], &[0x80; 48]);
+        assert_eq!(signature[48], 0);
+        assert_eq!(&signature[49..], &[0x11; 47]);
+    }
+
+    #[test]
+    fn test_der_uint_to_fixed_too_large() {
+        let mut out = [0u8; 2];
+        assert_eq!(
+            der_uint_to_fixed(&[0x01, 0x02, 0x03], &mut out),
+            Err(TrustAnchorError::InvalidCertChain)
+        );
+        der_uint_to_fixed(&[0x00, 0x00, 0xFF], &mut out).unwrap();
+        assert_eq!(out, [0x00, 0xFF]);
+    }
+}
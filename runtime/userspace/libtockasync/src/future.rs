@@ -18,16 +18,32 @@ use core::task::{Context, Poll, Waker};
 ///
 pub struct TockSubscribe {
     result: Cell<Option<(u32, u32, u32)>>,
+    // Error status reported by one of the upcalls added with subscribe_also
+    upcall_error: Cell<Option<ErrorCode>>,
     waker: Cell<Option<Waker>>,
     error: Option<ErrorCode>,
+    // (driver_num, subscribe_num) of the upcall the future was created with
+    subscription: Option<(u32, u32)>,
+    // (driver_num, subscribe_num) of the upcalls added with subscribe_also. They are
+    // unsubscribed when the future completes or is dropped, so no upcall is left pointing
+    // to this instance.
+    extra_subscriptions: [Option<(u32, u32)>; MAX_EXTRA_SUBSCRIPTIONS],
+    unsubscribe: Option<fn(u32, u32)>,
 }
 
+/// Maximum number of upcalls that can be added to a TockSubscribe with subscribe_also.
+const MAX_EXTRA_SUBSCRIPTIONS: usize = 2;
+
 impl TockSubscribe {
     fn new() -> TockSubscribe {
         TockSubscribe {
             result: Cell::new(None),
+            upcall_error: Cell::new(None),
             waker: Cell::new(None),
             error: None,
+            subscription: None,
+            extra_subscriptions: [None; MAX_EXTRA_SUBSCRIPTIONS],
+            unsubscribe: None,
         }
     }
 
@@ -35,6 +51,28 @@ impl TockSubscribe {
         self.error = Some(err);
     }
 
+    fn unsubscribe_extra(&mut self) {
+        let Some(unsubscribe) = self.unsubscribe else {
+            return;
+        };
+        for (driver_num, subscribe_num) in
+            self.extra_subscriptions.iter_mut().filter_map(Option::take)
+        {
+            unsubscribe(driver_num, subscribe_num);
+        }
+    }
+
+    // Removes every upcall pointing to this instance, once the primary upcall can no
+    // longer complete the future.
+    fn unsubscribe_all(&mut self) {
+        self.unsubscribe_extra();
+        if let (Some(unsubscribe), Some((driver_num, subscribe_num))) =
+            (self.unsubscribe, self.subscription.take())
+        {
+            unsubscribe(driver_num, subscribe_num);
+        }
+    }
+
     pub fn subscribe_allow_rw<S: Syscalls, C: allow_rw::Config>(
         driver_num: u32,
         subscribe_num: u32,
@@ -85,7 +123,10 @@ impl TockSubscribe {
         };
         let return_variant: ReturnVariant = r0.as_u32().into();
         match return_variant {
-            return_variant::SUCCESS_2_U32 => {}
+            return_variant::SUCCESS_2_U32 => {
+                f.subscription = Some((driver_num, subscribe_num));
+                f.unsubscribe = Some(S::unsubscribe);
+            }
             return_variant::FAILURE_2_U32 => {
                 f.set_err(r1.as_u32().try_into().unwrap_or(ErrorCode::Fail));
             }
@@ -146,7 +187,10 @@ impl TockSubscribe {
         };
         let return_variant: ReturnVariant = r0.as_u32().into();
         match return_variant {
-            return_variant::SUCCESS_2_U32 => {}
+            return_variant::SUCCESS_2_U32 => {
+                f.subscription = Some((driver_num, subscribe_num));
+                f.unsubscribe = Some(S::unsubscribe);
+            }
             return_variant::FAILURE_2_U32 => {
                 f.set_err(r1.as_u32().try_into().unwrap_or(ErrorCode::Fail));
             }
@@ -238,7 +282,10 @@ impl TockSubscribe {
         };
         let return_variant: ReturnVariant = r0.as_u32().into();
         match return_variant {
-            return_variant::SUCCESS_2_U32 => {}
+            return_variant::SUCCESS_2_U32 => {
+                f.subscription = Some((driver_num, subscribe_num));
+                f.unsubscribe = Some(S::unsubscribe);
+            }
             return_variant::FAILURE_2_U32 => {
                 f.set_err(r1.as_u32().try_into().unwrap_or(ErrorCode::Fail));
             }
@@ -267,7 +314,10 @@ impl TockSubscribe {
         };
         let return_variant: ReturnVariant = r0.as_u32().into();
         match return_variant {
-            return_variant::SUCCESS_2_U32 => {}
+            return_variant::SUCCESS_2_U32 => {
+                f.subscription = Some((driver_num, subscribe_num));
+                f.unsubscribe = Some(S::unsubscribe);
+            }
             return_variant::FAILURE_2_U32 => {
                 f.set_err(r1.as_u32().try_into().unwrap_or(ErrorCode::Fail));
             }
@@ -278,6 +328,51 @@ impl TockSubscribe {
         f
     }
 
+    /// Subscribes an existing TockSubscribe to an upcall that reports a failure. If it
+    /// happens first, the future completes with the error code passed as the first
+    /// upcall argument. The extra upcall is unsubscribed when the future completes or
+    /// is dropped. If the extra upcall cannot be subscribed, the future fails and its
+    /// upcalls are unsubscribed.
+    pub fn subscribe_also<S: Syscalls>(
+        f: &mut Pin<Box<TockSubscribe>>,
+        driver_num: u32,
+        subscribe_num: u32,
+    ) {
+        let Some(slot) = f.extra_subscriptions.iter().position(Option::is_none) else {
+            f.set_err(ErrorCode::NoMem);
+            f.unsubscribe_all();
+            return;
+        };
+
+        let upcall_fcn = (kernel_error_upcall::<S> as *const ()) as usize;
+        let upcall_data = (&**f as *const TockSubscribe) as usize;
+
+        // Safety: same as in subscribe(), the pointer is to a pinned instance.
+        let [r0, r1, _, _] = unsafe {
+            S::syscall4::<{ syscall_class::SUBSCRIBE }>([
+                driver_num.into(),
+                subscribe_num.into(),
+                upcall_fcn.into(),
+                upcall_data.into(),
+            ])
+        };
+        let return_variant: ReturnVariant = r0.as_u32().into();
+        match return_variant {
+            return_variant::SUCCESS_2_U32 => {
+                f.extra_subscriptions[slot] = Some((driver_num, subscribe_num));
+                f.unsubscribe = Some(S::unsubscribe);
+            }
+            return_variant::FAILURE_2_U32 => {
+                f.set_err(r1.as_u32().try_into().unwrap_or(ErrorCode::Fail));
+                f.unsubscribe_all();
+            }
+            _ => {
+                f.set_err(ErrorCode::Fail);
+                f.unsubscribe_all();
+            }
+        }
+    }
+
     /// This function should be called to turn the TockSubscribe into impl Future/async fn.
     pub fn subscribe_finish(
         f: Pin<Box<TockSubscribe>>,
@@ -307,17 +402,40 @@ extern "C" fn kernel_upcall<S: Syscalls>(arg0: u32, arg1: u32, arg2: u32, data:
     core::mem::forget(exit);
 }
 
+extern "C" fn kernel_error_upcall<S: Syscalls>(status: u32, _: u32, _: u32, data: Register) {
+    let exit: ExitOnDrop<S> = Default::default();
+    let upcall: *mut TockSubscribe = data.into();
+    // Safety: same as in kernel_upcall(), subscribe_also set the pointer to a pinned
+    // TockSubscribe instance.
+    unsafe {
+        (*upcall)
+            .upcall_error
+            .set(Some(status.try_into().unwrap_or(ErrorCode::Fail)))
+    };
+    if let Some(waker) = unsafe { (*upcall).waker.take() } {
+        waker.wake();
+    }
+    core::mem::forget(exit);
+}
+
 impl Future for TockSubscribe {
     type Output = Result<(u32, u32, u32), ErrorCode>;
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        if let Some(err) = self.error {
+        let this = self.get_mut();
+        if let Some(err) = this.error {
+            this.unsubscribe_extra();
+            return Poll::Ready(Err(err));
+        }
+        if let Some(err) = this.upcall_error.get() {
+            this.unsubscribe_all();
             return Poll::Ready(Err(err));
         }
-        if let Some(ret) = self.result.get() {
+        if let Some(ret) = this.result.get() {
+            this.unsubscribe_extra();
             Poll::Ready(Ok(ret))
         } else {
             // set ourselves to wake when the upcall happens
-            self.waker.replace(Some(cx.waker().clone()));
+            this.waker.replace(Some(cx.waker().clone()));
             // we don't call yield ourself, but let the executor call yield
             Poll::Pending
         }
@@ -326,7 +444,9 @@ impl Future for TockSubscribe {
 
 impl Drop for TockSubscribe {
     fn drop(&mut self) {
-        if self.result.get().is_none() && self.error.is_none() {
+        self.unsubscribe_extra();
+        if self.result.get().is_none() && self.upcall_error.get().is_none() && self.error.is_none()
+        {
             panic!("The TockSubscribe future was dropped before the upcall happened.");
         }
     }
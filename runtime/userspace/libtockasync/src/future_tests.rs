@@ -0,0 +1,123 @@
+// Licensed under the Apache-2.0 license
+
+use crate::TockSubscribe;
+use caliptra_mcu_libtock_platform::{CommandReturn, ErrorCode};
+use caliptra_mcu_libtock_unittest::fake::wait_for_future_ready;
+use caliptra_mcu_libtock_unittest::{
+    command_return, fake, DriverInfo, DriverShareRef, ExpectedSyscall, SyscallLogEntry,
+};
+use std::rc::Rc;
+
+const DRIVER_NUM: u32 = 0x9_0000;
+const RESPONSE: u32 = 0;
+const TIMEOUT: u32 = 1;
+const OTHER_TIMEOUT: u32 = 2;
+const EXTRA_TIMEOUT: u32 = 3;
+
+#[derive(Default)]
+struct UpcallDriver {
+    share_ref: DriverShareRef,
+}
+
+impl fake::SyscallDriver for UpcallDriver {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(DRIVER_NUM).upcall_count(4)
+    }
+
+    fn register(&self, share_ref: DriverShareRef) {
+        self.share_ref.replace(share_ref);
+    }
+
+    fn command(&self, _: u32, _: u32, _: u32) -> CommandReturn {
+        command_return::failure(ErrorCode::NoSupport)
+    }
+}
+
+fn setup() -> (fake::Kernel, Rc<UpcallDriver>) {
+    let kernel = fake::Kernel::new();
+    let driver = Rc::new(UpcallDriver::default());
+    kernel.add_driver(&driver);
+    (kernel, driver)
+}
+
+fn subscribe(subscribe_num: u32) -> SyscallLogEntry {
+    SyscallLogEntry::Subscribe {
+        driver_num: DRIVER_NUM,
+        subscribe_num,
+    }
+}
+
+#[test]
+fn subscribe_also_error_upcall() {
+    let (kernel, driver) = setup();
+
+    let mut sub = TockSubscribe::subscribe::<fake::Syscalls>(DRIVER_NUM, RESPONSE);
+    TockSubscribe::subscribe_also::<fake::Syscalls>(&mut sub, DRIVER_NUM, TIMEOUT);
+    driver
+        .share_ref
+        .schedule_upcall(TIMEOUT, (ErrorCode::NoAck as u32, 0, 0))
+        .unwrap();
+
+    let result = wait_for_future_ready(TockSubscribe::subscribe_finish(sub));
+    assert_eq!(result, Err(ErrorCode::NoAck));
+
+    // Both upcalls are removed, a late response cannot reach the completed future
+    let log = kernel.take_syscall_log();
+    assert_eq!(
+        log[log.len() - 2..],
+        [subscribe(TIMEOUT), subscribe(RESPONSE)]
+    );
+    driver
+        .share_ref
+        .schedule_upcall(RESPONSE, (1, 0, 0))
+        .unwrap();
+    assert!(!fake::Kernel::is_upcall_pending());
+}
+
+#[test]
+fn subscribe_also_failure() {
+    let (kernel, _driver) = setup();
+
+    let mut sub = TockSubscribe::subscribe::<fake::Syscalls>(DRIVER_NUM, RESPONSE);
+    kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
+        driver_num: DRIVER_NUM,
+        subscribe_num: TIMEOUT,
+        skip_with_error: Some(ErrorCode::Fail),
+    });
+    TockSubscribe::subscribe_also::<fake::Syscalls>(&mut sub, DRIVER_NUM, TIMEOUT);
+
+    let result = wait_for_future_ready(TockSubscribe::subscribe_finish(sub));
+    assert_eq!(result, Err(ErrorCode::Fail));
+
+    // The primary subscription is removed along with the failed one
+    assert_eq!(
+        kernel.take_syscall_log(),
+        [subscribe(RESPONSE), subscribe(TIMEOUT), subscribe(RESPONSE)]
+    );
+}
+
+#[test]
+fn subscribe_also_no_mem() {
+    let (kernel, _driver) = setup();
+
+    let mut sub = TockSubscribe::subscribe::<fake::Syscalls>(DRIVER_NUM, RESPONSE);
+    TockSubscribe::subscribe_also::<fake::Syscalls>(&mut sub, DRIVER_NUM, TIMEOUT);
+    TockSubscribe::subscribe_also::<fake::Syscalls>(&mut sub, DRIVER_NUM, OTHER_TIMEOUT);
+    TockSubscribe::subscribe_also::<fake::Syscalls>(&mut sub, DRIVER_NUM, EXTRA_TIMEOUT);
+
+    let result = wait_for_future_ready(TockSubscribe::subscribe_finish(sub));
+    assert_eq!(result, Err(ErrorCode::NoMem));
+
+    // No upcall is left subscribed
+    assert_eq!(
+        kernel.take_syscall_log(),
+        [
+            subscribe(RESPONSE),
+            subscribe(TIMEOUT),
+            subscribe(OTHER_TIMEOUT),
+            subscribe(TIMEOUT),
+            subscribe(OTHER_TIMEOUT),
+            subscribe(RESPONSE),
+        ]
+    );
+}
@@ -5,6 +5,8 @@
 extern crate alloc;
 
 mod future;
+#[cfg(test)]
+mod future_tests;
 pub use future::TockSubscribe;
 mod tock_executor;
 pub use tock_executor::TockExecutor;
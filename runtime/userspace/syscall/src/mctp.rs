@@ -190,7 +190,7 @@ impl<S: Syscalls> Mctp<S> {
     ///
     /// # Returns
     /// * `(u32, MessageInfo)` - On success, returns tuple containing length of the response received and the message information containing the source EID, message tag
-    /// * `ErrorCode` - The error code on failure
+    /// * `ErrorCode` - The error code on failure. `ErrorCode::NoAck` if no response arrived within the MCTP response timeout
     pub async fn receive_response(
         &self,
         resp: &mut [u8],
@@ -208,6 +208,12 @@ impl<S: Syscalls> Mctp<S> {
                 allow_rw::READ_RESPONSE,
                 resp,
             );
+            // A response timeout fails the future with the NOACK status of the timeout upcall.
+            TockSubscribe::subscribe_also::<S>(
+                &mut sub,
+                self.driver_num,
+                subscribe::RESPONSE_TIMEOUT,
+            );
             if let Err(e) = S::command(
                 self.driver_num,
                 command::RECEIVE_RESPONSE,
@@ -277,6 +283,8 @@ mod subscribe {
     pub const RECEIVED_RESPONSE: u32 = 1;
     /// Message transmitted
     pub const MESSAGE_TRANSMITTED: u32 = 2;
+    /// No response received within the response timeout
+    pub const RESPONSE_TIMEOUT: u32 = 3;
 }
 
 mod allow_ro {
@@ -18,6 +18,7 @@ mod test_i3c_constant_writes;
 mod test_i3c_simple;
 mod test_mctp_capsule_loopback;
 mod test_mctp_spdm_attestation;
+mod test_mctp_spdm_requester;
 mod test_mctp_spdm_responder_conformance;
 mod test_mctp_vdm_cmds;
 mod test_mctp_vdm_validator;
@@ -679,6 +680,12 @@ mod test {
             emulator_args.extend(["--hw-revision".to_string(), hw_revision]);
         }
 
+        // Runtimes built with the MCTP over PCIe VDM binding are driven through its socket.
+        if feature.contains("test-mctp-pcie-vdm") {
+            let pcie_vdm_port = PortPicker::new().random(true).pick().unwrap();
+            emulator_args.extend(["--pcie-vdm-port".to_string(), pcie_vdm_port.to_string()]);
+        }
+
         if active_mode {
             emulator_args.extend([
                 "--device-security-state".to_string(),
@@ -963,11 +970,13 @@ mod test {
     run_test!(test_log_flash_circular);
     run_test!(test_log_flash_usermode, example_app);
     run_test!(test_mctp_ctrl_cmds);
+    run_test!(test_mctp_pcie_vdm_ctrl_cmds);
     run_test!(test_mctp_user_loopback, example_app);
     run_test!(test_pldm_discovery);
     run_test!(test_pldm_fw_update);
     run_test!(test_doe_spdm_responder_conformance, nightly);
     run_test!(test_doe_spdm_tdisp_ide_validator, nightly);
+    run_test!(test_doe_spdm_requester);
     run_test!(test_mci, example_app);
     run_test!(test_mcu_mbox_driver);
     run_test!(test_mcu_mbox_soc_requester_loopback, example_app);
@@ -2,3 +2,4 @@
 
 mod test_increase_caliptra_svn;
 mod test_mcu_mailbox;
+mod test_snapshot;
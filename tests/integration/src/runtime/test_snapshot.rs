@@ -0,0 +1,60 @@
+// Licensed under the Apache-2.0 license
+
+use crate::test::{start_runtime_hw_model, TestParams};
+use caliptra_mcu_hw_model::{DefaultHwModel, McuHwModel};
+use std::path::Path;
+
+/// How long to wait for the Caliptra mailbox to go idle before a snapshot.
+const IDLE_WAIT_STEPS: usize = 1_000_000;
+/// How long both models run after the fork before they are compared.
+const RESUME_STEPS: usize = 2_000_000;
+
+fn save_when_idle(hw: &mut DefaultHwModel, path: &Path) {
+    for _ in 0..IDLE_WAIT_STEPS {
+        if hw.save_snapshot(path).is_ok() {
+            return;
+        }
+        hw.step();
+    }
+    hw.save_snapshot(path).unwrap();
+}
+
+#[test]
+#[cfg_attr(feature = "fpga_realtime", ignore)]
+fn test_snapshot_resume_runtime() {
+    let dir = tempfile::tempdir().unwrap();
+    let fork = dir.path().join("fork.json");
+
+    let mut hw = start_runtime_hw_model(TestParams::default());
+    save_when_idle(&mut hw, &fork);
+    hw.output().take(usize::MAX);
+
+    // Same firmware and fuses, but not stepped yet.
+    let mut restored = start_runtime_hw_model(TestParams {
+        rom_only: true,
+        ..Default::default()
+    });
+    restored.load_snapshot(&fork).unwrap();
+    assert_eq!(restored.cycle_count(), hw.cycle_count());
+
+    for _ in 0..RESUME_STEPS {
+        hw.step();
+        restored.step();
+    }
+    assert_eq!(restored.cycle_count(), hw.cycle_count());
+    assert_eq!(
+        restored.output().take(usize::MAX),
+        hw.output().take(usize::MAX)
+    );
+    assert_eq!(restored.mci_flow_status(), hw.mci_flow_status());
+
+    // Both runs must end in exactly the same CPU, memory and peripheral state.
+    let original = dir.path().join("original.json");
+    let resumed = dir.path().join("resumed.json");
+    save_when_idle(&mut hw, &original);
+    save_when_idle(&mut restored, &resumed);
+    assert!(
+        std::fs::read(&original).unwrap() == std::fs::read(&resumed).unwrap(),
+        "resumed run diverged from the original"
+    );
+}
@@ -14,6 +14,16 @@ mod test {
     use random_port::PortPicker;
     use std::sync::atomic::Ordering;
 
+    // Requests to this EID are not answered so the device times them out.
+    // Matches MCTP_TEST_UNRESPONSIVE_EID in the kernel capsule test.
+    const MCTP_TEST_UNRESPONSIVE_EID: u8 = 0x21;
+    // Requests to this EID are first answered with a message that is only completed
+    // after the device's reassembly timeout, then with the full response.
+    // Matches MCTP_TEST_STALE_ASSEMBLY_EID in the kernel capsule test.
+    const MCTP_TEST_STALE_ASSEMBLY_EID: u8 = 0x22;
+    // Longer than the reassembly timeout the kernel capsule test configures
+    const MCTP_TEST_STALE_ASSEMBLY_DELAY_TICKS: u32 = 1_000_000;
+
     #[test]
     fn test_mctp_capsule_loopback() {
         let feature = "test-mctp-capsule-loopback";
@@ -87,9 +97,27 @@ mod test {
                         self.loopback_msg =
                             self.mctp_util
                                 .receive_request(stream, target_addr, Some(30));
-                        self.test_state = MctpTestState::SendResp;
+                        if self.mctp_util.get_dest_eid() != MCTP_TEST_UNRESPONSIVE_EID {
+                            self.test_state = MctpTestState::SendResp;
+                        }
                     }
                     MctpTestState::SendResp => {
+                        if self.mctp_util.get_dest_eid() == MCTP_TEST_STALE_ASSEMBLY_EID {
+                            // Corrupt the first packet so a stale message that is not
+                            // dropped does not match the loopback message.
+                            let mut stale_msg = self.loopback_msg.clone();
+                            let first_pkt_len =
+                                stale_msg.len().min(self.mctp_util.get_pkt_payload_size());
+                            for byte in stale_msg[1..first_pkt_len].iter_mut() {
+                                *byte = !*byte;
+                            }
+                            self.mctp_util.clone().send_split_response(
+                                stale_msg.as_slice(),
+                                stream,
+                                target_addr,
+                                MCTP_TEST_STALE_ASSEMBLY_DELAY_TICKS,
+                            );
+                        }
                         self.mctp_util.send_response(
                             self.loopback_msg.as_slice(),
                             stream,
@@ -0,0 +1,131 @@
+// Licensed under the Apache-2.0 license
+
+//! This module runs end-to-end SPDM attestation against the emulator with the
+//! host-side SPDM requester over MCTP transport
+
+#[cfg(test)]
+mod test {
+    use crate::test::{finish_runtime_hw_model, start_runtime_hw_model, TestParams, TEST_LOCK};
+    use caliptra_mcu_hw_model::McuHwModel;
+    use caliptra_mcu_spdm_requester::requester::MEASUREMENT_OPERATION_ALL;
+    use caliptra_mcu_spdm_requester::{SpdmRequester, SpdmRequesterResult, SpdmTransport};
+    use caliptra_mcu_testing_common::i3c::DynamicI3cAddress;
+    use caliptra_mcu_testing_common::i3c_socket::BufferedStream;
+    use caliptra_mcu_testing_common::spdm_requester::{
+        emulator_requester_config, MctpSpdmTransport,
+    };
+    use caliptra_mcu_testing_common::{wait_for_runtime_start, MCU_RUNNING};
+    use random_port::PortPicker;
+    use std::net::{SocketAddr, TcpStream};
+    use std::process::exit;
+    use std::sync::atomic::Ordering;
+    use std::thread;
+    use std::time::Duration;
+
+    const TEST_NAME: &str = "MCTP-SPDM-REQUESTER";
+
+    #[ignore]
+    #[test]
+    fn test_mctp_spdm_requester() {
+        let lock = TEST_LOCK.lock().unwrap();
+        lock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+
+        let mut hw = start_runtime_hw_model(TestParams {
+            i3c_port: Some(PortPicker::new().pick().unwrap()),
+            use_strap_secrets: true,
+            ..Default::default()
+        });
+
+        hw.start_i3c_controller();
+
+        run_mctp_spdm_requester_test(
+            hw.i3c_port().unwrap(),
+            hw.i3c_address().unwrap().into(),
+            Duration::from_secs(3000),
+        );
+
+        let test = finish_runtime_hw_model(&mut hw);
+
+        assert_eq!(0, test);
+
+        // force the compiler to keep the lock
+        lock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    fn attest<T: SpdmTransport>(requester: &mut SpdmRequester<T>) -> SpdmRequesterResult<()> {
+        requester.init_connection()?;
+        println!(
+            "[{}]: Negotiated SPDM {:?} with {:?}",
+            TEST_NAME,
+            requester.version(),
+            requester.asym_algo()
+        );
+
+        let slot_mask = requester.get_digests()?;
+        println!("[{}]: Provisioned slot mask {:#x}", TEST_NAME, slot_mask);
+        let cert_chain = requester.get_certificate(0)?;
+        println!(
+            "[{}]: Slot 0 certificate chain with {} certificates",
+            TEST_NAME,
+            cert_chain.certs().len()
+        );
+
+        requester.challenge(0, 0xFF)?;
+        println!("[{}]: CHALLENGE_AUTH signature verified", TEST_NAME);
+
+        let measurements = requester.get_measurements(MEASUREMENT_OPERATION_ALL, true, 0)?;
+        for block in measurements.blocks.iter() {
+            println!(
+                "[{}]: Measurement block {} type {:#x}: {:02x?}",
+                TEST_NAME, block.index, block.value_type, block.value
+            );
+        }
+        assert!(measurements.verified);
+        assert!(!measurements.blocks.is_empty());
+        Ok(())
+    }
+
+    pub fn run_mctp_spdm_requester_test(
+        port: u16,
+        target_addr: DynamicI3cAddress,
+        test_timeout_seconds: Duration,
+    ) {
+        let addr = SocketAddr::from(([127, 0, 0, 1], port));
+        let stream = TcpStream::connect(addr).unwrap();
+        let transport = MctpSpdmTransport::new(BufferedStream::new(stream), target_addr.into(), 1);
+
+        thread::spawn(move || {
+            thread::sleep(test_timeout_seconds);
+            println!(
+                "[{}] TIMED OUT AFTER {:?} SECONDS",
+                TEST_NAME,
+                test_timeout_seconds.as_secs()
+            );
+            exit(-1);
+        });
+
+        thread::spawn(move || {
+            wait_for_runtime_start();
+
+            if !MCU_RUNNING.load(Ordering::Relaxed) {
+                exit(-1);
+            }
+            thread::sleep(Duration::from_secs(5)); // give time for the app to be loaded and ready
+            if !MCU_RUNNING.load(Ordering::Relaxed) {
+                exit(-1);
+            }
+
+            let mut requester = SpdmRequester::new(transport, emulator_requester_config());
+            match attest(&mut requester) {
+                Ok(()) => {
+                    println!("[{}]: Spdm Requester Test Passed", TEST_NAME);
+                    exit(0);
+                }
+                Err(e) => {
+                    println!("[{}]: Spdm Requester Test Failed: {:?}", TEST_NAME, e);
+                    exit(-1);
+                }
+            }
+        });
+    }
+}
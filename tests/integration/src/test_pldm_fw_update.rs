@@ -200,10 +200,11 @@ pub mod test {
                 PldmDaemon::run(
                     self.socket.clone(),
                     Options {
-                        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg),
+                        caliptra_mcu_pldm_fw_pkg: Some(caliptra_mcu_pldm_fw_pkg.into()),
                         discovery_sm_actions: discovery_sm::DefaultActions {},
                         update_sm_actions: update_sm::DefaultActions {},
                         fd_tid: 0x01,
+                        trust_anchor: None,
                     },
                 )
                 .map_err(|_| ())?,
@@ -2,9 +2,13 @@
 
 use anyhow::{bail, Result};
 use caliptra_mcu_builder::{rom_build, PROJECT_ROOT};
+use caliptra_mcu_coverage::{CoverageMap, SourceCoverage, SourceMap};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
 use std::process::Command;
 
-pub(crate) fn coverage(analyze_only: bool) -> Result<()> {
+pub(crate) fn coverage(analyze_only: bool, report_dir: Option<&Path>) -> Result<()> {
     let cov_dir = std::env::var("MCU_COVERAGE_PATH")
         .map(std::path::PathBuf::from)
         .unwrap_or_else(|_| std::env::temp_dir().join("mcu-coverage"));
@@ -156,5 +160,183 @@ pub(crate) fn coverage(analyze_only: bool) -> Result<()> {
         );
     }
 
+    if let Some(report_dir) = report_dir {
+        match (&rom_elf, &rom_path) {
+            (Some(rom_elf_path), Some(rom_bin_path)) if rom_elf_path.exists() => {
+                let rom_tag =
+                    caliptra_mcu_coverage::get_tag_from_image(&std::fs::read(rom_bin_path)?);
+                source_reports(&cv, rom_elf_path, rom_tag, report_dir)?;
+            }
+            _ => bail!("Source coverage reports need the MCU ROM ELF"),
+        }
+    }
+
     Ok(())
 }
+
+/// Write lcov, Cobertura and HTML reports of the source lines executed by
+/// the ROM, and by the runtime and apps whose ELF files are next to the ROM
+/// ELF.
+fn source_reports(cv: &CoverageMap, rom_elf: &Path, rom_tag: u64, report_dir: &Path) -> Result<()> {
+    let memory_map = &caliptra_mcu_config_emulator::EMULATOR_MEMORY_MAP;
+    let mut coverage = SourceCoverage::default();
+
+    if let Some(bitmap) = cv.map.get(&rom_tag) {
+        let map = SourceMap::from_elf(&std::fs::read(rom_elf)?)?;
+        coverage.add(&map, memory_map.rom_offset, bitmap);
+    }
+
+    // Each test may load a different runtime image, so each SRAM bitmap is only
+    // applied to the ELF files of the image whose hash is its tag.
+    let sram = memory_map.sram_offset..memory_map.sram_offset + memory_map.sram_size;
+    let mut sram_maps = vec![];
+    let mut sram_images = vec![];
+    let mut bundles = vec![];
+    let release_dir = rom_elf.parent().unwrap_or(Path::new("."));
+    for entry in std::fs::read_dir(release_dir)? {
+        let path = entry?.path();
+        if !path.is_file() || path == rom_elf {
+            continue;
+        }
+        if path.extension().is_some_and(|ext| ext == "bin") {
+            bundles.push(std::fs::read(&path)?);
+            continue;
+        }
+        let elf_bytes = std::fs::read(&path)?;
+        if !elf_bytes.starts_with(&[0x7f, 0x45, 0x4c, 0x46]) {
+            continue;
+        }
+        match SourceMap::from_elf(&elf_bytes) {
+            Ok(map) => {
+                if !map
+                    .address_range()
+                    .is_some_and(|r| sram.contains(&r.start) && r.end <= sram.end)
+                {
+                    continue;
+                }
+                // The flat image objcopy produced from the ELF file is next to it
+                match std::fs::read(path.with_extension("bin")) {
+                    Ok(image) => {
+                        sram_maps.push((path, map));
+                        sram_images.push(image);
+                    }
+                    Err(e) => println!("Skipping {}: no flat image: {}", path.display(), e),
+                }
+            }
+            Err(e) => println!("Skipping {}: {}", path.display(), e),
+        }
+    }
+    for (tag, bitmap) in cv.map.iter() {
+        if *tag == rom_tag {
+            continue;
+        }
+        let elfs = elfs_with_tag(*tag, &sram_images, &bundles);
+        if elfs.is_empty() {
+            println!(
+                "Warning: no ELF file in {} matches coverage tag {}",
+                release_dir.display(),
+                tag
+            );
+        }
+        for i in elfs {
+            let (path, map) = &sram_maps[i];
+            println!("Mapping SRAM coverage (tag={}) to {}", tag, path.display());
+            coverage.add(map, memory_map.sram_offset, bitmap);
+        }
+    }
+
+    coverage.retain_under(PROJECT_ROOT.as_path());
+    std::fs::create_dir_all(report_dir)?;
+
+    let mut lcov = BufWriter::new(File::create(report_dir.join("lcov.info"))?);
+    coverage.write_lcov(&mut lcov)?;
+    lcov.flush()?;
+
+    let mut cobertura = BufWriter::new(File::create(report_dir.join("cobertura.xml"))?);
+    coverage.write_cobertura(&mut cobertura, PROJECT_ROOT.as_path())?;
+    cobertura.flush()?;
+
+    let html_dir = report_dir.join("html");
+    coverage.write_html(&html_dir, PROJECT_ROOT.as_path())?;
+
+    let (lines_hit, lines_total) = coverage.line_totals();
+    let (functions_hit, functions_total) = coverage.function_totals();
+    println!("////////////////////////////////////");
+    println!("Source Coverage");
+    println!("////////////////////////////////////");
+    println!("Lines = {} / {}", lines_hit, lines_total);
+    println!("Functions = {} / {}", functions_hit, functions_total);
+    println!(
+        "Reports written to {} (HTML: {})",
+        report_dir.display(),
+        html_dir.join("index.html").display()
+    );
+    Ok(())
+}
+
+/// Indices of the flat images in `elf_images` executed from the image with the
+/// coverage tag `tag`. The tag is the hash of the loaded image, which is either
+/// the flat image of a single ELF file or a firmware bundle containing the flat
+/// images of the kernel and its apps.
+fn elfs_with_tag(tag: u64, elf_images: &[Vec<u8>], bundles: &[Vec<u8>]) -> Vec<usize> {
+    if let Some(i) = elf_images
+        .iter()
+        .position(|image| caliptra_mcu_coverage::get_tag_from_image(image) == tag)
+    {
+        return vec![i];
+    }
+
+    let Some(bundle) = bundles
+        .iter()
+        .find(|bundle| caliptra_mcu_coverage::get_tag_from_image(bundle) == tag)
+    else {
+        return vec![];
+    };
+    elf_images
+        .iter()
+        .enumerate()
+        .filter(|(_, image)| {
+            !image.is_empty() && bundle.windows(image.len()).any(|w| w == image.as_slice())
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use caliptra_mcu_coverage::get_tag_from_image;
+
+    #[test]
+    fn test_elfs_with_tag() {
+        let runtime = vec![0x13, 0x05, 0x10, 0x00, 0x67, 0x80, 0x00, 0x00];
+        let app = vec![0x93, 0x05, 0x20, 0x00, 0x73, 0x00, 0x00, 0x00];
+        let elf_images = vec![runtime.clone(), app.clone()];
+
+        // Each bitmap is applied only to the ELF file it was collected from
+        assert_eq!(
+            elfs_with_tag(get_tag_from_image(&runtime), &elf_images, &[]),
+            vec![0]
+        );
+        assert_eq!(
+            elfs_with_tag(get_tag_from_image(&app), &elf_images, &[]),
+            vec![1]
+        );
+        assert!(elfs_with_tag(0, &elf_images, &[]).is_empty());
+
+        // A bundle covers the ELF files it contains
+        let mut bundle = runtime.clone();
+        bundle.resize(0x100, 0);
+        let bundles = vec![bundle.clone()];
+        assert_eq!(
+            elfs_with_tag(get_tag_from_image(&bundle), &elf_images, &bundles),
+            vec![0]
+        );
+        bundle.extend_from_slice(&app);
+        let bundles = vec![bundle.clone()];
+        assert_eq!(
+            elfs_with_tag(get_tag_from_image(&bundle), &elf_images, &bundles),
+            vec![0, 1]
+        );
+    }
+}
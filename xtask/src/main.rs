@@ -205,6 +205,10 @@ enum Commands {
         /// Only analyze existing coverage data (skip test run)
         #[arg(long, default_value_t = false)]
         analyze_only: bool,
+
+        /// Write lcov, Cobertura and HTML source line coverage reports to this directory
+        #[arg(long)]
+        report_dir: Option<PathBuf>,
     },
     /// Check files for Apache license header
     HeaderCheck,
@@ -564,7 +568,10 @@ fn main() {
         Commands::Precheckin => precheckin::precheckin(),
         Commands::Format => format::format(),
         Commands::CargoLock => cargo_lock::cargo_lock(),
-        Commands::Coverage { analyze_only } => coverage::coverage(*analyze_only),
+        Commands::Coverage {
+            analyze_only,
+            report_dir,
+        } => coverage::coverage(*analyze_only, report_dir.as_deref()),
         Commands::HeaderFix => header::fix(),
         Commands::HeaderCheck => header::check(),
         Commands::Test {
@@ -699,6 +699,12 @@ fn emu_make_peripheral_trait(
             fn poll(&mut self) {}
             fn warm_reset(&mut self) {}
             fn update_reset(&mut self) {}
+            fn save_state(&self) -> Option<Vec<u8>> {
+                None
+            }
+            fn restore_state(&mut self, _state: &[u8]) -> std::io::Result<()> {
+                Ok(())
+            }
             fn generated(&mut self) -> Option<&mut #generated_struct> {
                 None
             }
@@ -1047,6 +1053,8 @@ fn emu_make_root_bus<'a>(
     let mut update_reset_tokens = TokenStream::new();
     let mut incoming_event_tokens = TokenStream::new();
     let mut register_outgoing_events_tokens = TokenStream::new();
+    let mut save_state_tokens = TokenStream::new();
+    let mut restore_state_tokens = TokenStream::new();
     let mut field_tokens = TokenStream::new();
     let mut constructor_tokens = TokenStream::new();
     let mut constructor_params_tokens = TokenStream::new();
@@ -1130,6 +1138,17 @@ fn emu_make_root_bus<'a>(
                 periph.register_outgoing_events(sender.clone());
             }
         });
+        let state_key = rblock.name.as_str();
+        save_state_tokens.extend(quote! {
+            if let Some(state) = self.#periph_field.as_ref().and_then(|p| p.periph.save_state()) {
+                states.insert(#state_key.to_string(), state);
+            }
+        });
+        restore_state_tokens.extend(quote! {
+            if let (Some(periph), Some(state)) = (self.#periph_field.as_mut(), states.get(#state_key)) {
+                periph.periph.restore_state(state)?;
+            }
+        });
     }
     let mut tokens = TokenStream::new();
     tokens.extend(quote! {
@@ -1165,6 +1184,21 @@ fn emu_make_root_bus<'a>(
                     #constructor_tokens
                 }
             }
+
+            /// Save the state of every mounted peripheral that supports snapshots,
+            /// keyed by peripheral name.
+            pub fn save_peripheral_states(&self) -> std::collections::BTreeMap<String, Vec<u8>> {
+                let mut states = std::collections::BTreeMap::new();
+                #save_state_tokens
+                states
+            }
+
+            /// Restore peripheral state previously returned by `save_peripheral_states()`.
+            /// Peripherals without a saved state are left untouched.
+            pub fn restore_peripheral_states(&mut self, states: &std::collections::BTreeMap<String, Vec<u8>>) -> std::io::Result<()> {
+                #restore_state_tokens
+                Ok(())
+            }
         }
         impl caliptra_emu_bus::Bus for AutoRootBus {
             fn read(&mut self, size: caliptra_emu_types::RvSize, addr: caliptra_emu_types::RvAddr) -> Result<caliptra_emu_types::RvData, caliptra_emu_bus::BusError> {